@@ -244,6 +244,10 @@ async function main() {
       new anchor.BN(SESSION_DURATION_SECS),
       new anchor.BN(MAX_LAMPORTS),
       STRATEGY_LP,
+      new anchor.BN(0), // max_action_lamports=0: adopt the protocol per-action ceiling
+      null, // referrer: none
+      0, // fee_tier: tier 0
+      Array(32).fill(0), // attestation_hash: unattested
     )
     .accounts({ owner: ownerKeypair.publicKey })
     .instruction();
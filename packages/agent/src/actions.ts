@@ -18,11 +18,18 @@ import * as path from "path";
 import BN from "bn.js";
 import { checkLpPosition } from "@hyperbiscus/shared";
 import { AgentConfig } from "./config";
-import { DELEGATION_PROGRAM_ID } from "./constants";
+import {
+  DELEGATION_PROGRAM_ID,
+  MAGIC_CONTEXT_ID,
+  MAGIC_OVERRIDDEN,
+  MAGIC_PROGRAM_ID,
+} from "./constants";
 import { SolanaContext, submitUpdateLpStatus } from "./solana";
 
 // execute_action type for LP rebalance (matches on-chain enum)
 const ACTION_LP_REBALANCE = 0;
+// execute_action reason: requested by the user (REASON_MANUAL on-chain)
+const REASON_MANUAL = 4;
 
 // Timing constants for MagicBlock ER lifecycle
 const ER_PICKUP_DELAY_MS = 3000;          // wait after delegation for ER to pick up the account
@@ -209,10 +216,11 @@ export async function handleAddLiquidity(
     emit(3, "Executing LP action on Ephemeral Rollup", "pending");
 
     const actionTx = await erProgram.methods
-      .executeAction(ACTION_LP_REBALANCE, EXECUTE_ACTION_AMOUNT)
+      .executeAction(ACTION_LP_REBALANCE, EXECUTE_ACTION_AMOUNT, new anchor.BN(0), REASON_MANUAL)
       .accounts({
         sessionKey: config.sessionKeypair.publicKey,
         session: config.sessionPda,
+        cosigner: null, // below the co-sign threshold
       })
       .transaction();
 
@@ -228,12 +236,24 @@ export async function handleAddLiquidity(
     // ── Step 4: Commit state + undelegate back to base layer ─────────────────
     emit(4, "Committing state & undelegating from ER", "pending");
 
+    const magicAccounts = {
+      magicProgram: MAGIC_PROGRAM_ID,
+      magicContext: MAGIC_CONTEXT_ID,
+      config: MAGIC_OVERRIDDEN
+        ? PublicKey.findProgramAddressSync(
+            [Buffer.from("config")],
+            erProgram.programId,
+          )[0]
+        : null,
+    };
+
     // commitSession — sync ER state to Solana base layer without undelegating yet
     const commitTx = await erProgram.methods
       .commitSession()
       .accounts({
         payer: config.sessionKeypair.publicKey,
         session: config.sessionPda,
+        ...magicAccounts,
       })
       .transaction();
     const commitSig = await sendErTx(erConnection, sessionWallet, commitTx);
@@ -245,6 +265,7 @@ export async function handleAddLiquidity(
       .accounts({
         payer: config.sessionKeypair.publicKey,
         session: config.sessionPda,
+        ...magicAccounts,
       })
       .transaction();
     const undelegateSig = await sendErTx(
@@ -274,9 +295,10 @@ export async function handleAddLiquidity(
         try {
           const sig = await submitUpdateLpStatus(
             ctx,
-            status.activeBin,
             status.feeX,
             status.feeY,
+            status.amountX,
+            status.amountY,
           );
           emit(5, "LP status checkpointed on-chain", "success", {
             txSignature: sig,
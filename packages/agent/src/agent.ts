@@ -38,7 +38,7 @@ export async function runTick(
     "devnet",
   );
 
-  const { activeBin, positionMinBin, positionMaxBin, isInRange, feeX, feeY } =
+  const { activeBin, positionMinBin, positionMaxBin, isInRange, feeX, feeY, amountX, amountY } =
     status;
 
   console.log(
@@ -55,7 +55,7 @@ export async function runTick(
   if (isDelegated) {
     console.log(`  [skip] session still delegated to MagicBlock ER — skipping update_lp_status`);
   } else {
-    sig = await submitUpdateLpStatus(ctx, activeBin, feeX, feeY);
+    sig = await submitUpdateLpStatus(ctx, feeX, feeY, amountX, amountY);
     console.log(`  tx: ${sig}`);
   }
 
@@ -4,3 +4,21 @@ import { PublicKey } from "@solana/web3.js";
 export const DELEGATION_PROGRAM_ID = new PublicKey(
   "DELeGGvXpWV2fqJUhqcF5ZSYMS4JTLjteaAMARRSaeSh",
 );
+
+/**
+ * MagicBlock magic program and context targeted by commitSession /
+ * undelegateSession. Local and devnet ER validators may use their own — set
+ * MAGIC_PROGRAM_ID / MAGIC_CONTEXT_ID to match the Config's
+ * set_magic_programs override.
+ */
+export const MAGIC_PROGRAM_ID = new PublicKey(
+  process.env.MAGIC_PROGRAM_ID ?? "Magic11111111111111111111111111111111111111",
+);
+export const MAGIC_CONTEXT_ID = new PublicKey(
+  process.env.MAGIC_CONTEXT_ID ?? "MagicContext1111111111111111111111111111111",
+);
+
+/** Overridden magic accounts are only accepted alongside the Config PDA */
+export const MAGIC_OVERRIDDEN =
+  process.env.MAGIC_PROGRAM_ID !== undefined ||
+  process.env.MAGIC_CONTEXT_ID !== undefined;
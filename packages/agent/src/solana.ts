@@ -54,22 +54,26 @@ export async function buildSolanaContext(config: AgentConfig): Promise<SolanaCon
 
 /**
  * Submit an update_lp_status instruction to base-layer devnet.
- * Signed by the session keypair (the "ESP32 key").
+ * Signed by the session keypair (the "ESP32 key"). The program reads the
+ * pool's active bin from the lb_pair account itself.
  */
 export async function submitUpdateLpStatus(
   ctx: SolanaContext,
-  activeBin: number,
   feeX: BN,
   feeY: BN,
+  amountX: BN,
+  amountY: BN,
 ): Promise<string> {
   const { program, config, monitorPda } = ctx;
 
   const tx = await program.methods
-    .updateLpStatus(activeBin, feeX, feeY)
+    .updateLpStatus(feeX, feeY, amountX, amountY)
     .accounts({
       sessionKey: config.sessionKeypair.publicKey,
       session: config.sessionPda,
       monitor: monitorPda,
+      position: config.positionPubkey,
+      lbPair: config.lbPair,
     })
     .transaction();
 
@@ -37,14 +37,12 @@ export const TOOL_DEFINITIONS: Tool[] = [
     name: "update_lp_status",
     description:
       "Submit the current LP position status to the on-chain LpPositionMonitor PDA. " +
-      "Signed by the session key. Call this after check_lp_position to checkpoint on-chain.",
+      "Signed by the session key. Call this after check_lp_position to checkpoint on-chain. " +
+      "The position's token amounts are taken from that last check; the program " +
+      "reads the pool's active bin on-chain.",
     input_schema: {
       type: "object" as const,
       properties: {
-        active_bin: {
-          type: "integer",
-          description: "The pool's current active bin (from check_lp_position)",
-        },
         fee_x: {
           type: "integer",
           description: "Unclaimed fee token X, raw units (from check_lp_position)",
@@ -54,7 +52,7 @@ export const TOOL_DEFINITIONS: Tool[] = [
           description: "Unclaimed fee token Y, raw units (from check_lp_position)",
         },
       },
-      required: ["active_bin", "fee_x", "fee_y"],
+      required: ["fee_x", "fee_y"],
     },
   },
 ];
@@ -71,7 +69,6 @@ export interface PositionSnapshot {
 export interface ToolExecutors {
   check_lp_position: () => Promise<object>;
   update_lp_status: (input: {
-    active_bin: number;
     fee_x: number;
     fee_y: number;
   }) => Promise<object>;
@@ -105,12 +102,13 @@ export function buildToolExecutors(
       return snapshot;
     },
 
-    async update_lp_status({ active_bin, fee_x, fee_y }) {
+    async update_lp_status({ fee_x, fee_y }) {
       const sig = await submitUpdateLpStatus(
         ctx,
-        active_bin,
         new BN(fee_x),
         new BN(fee_y),
+        lastStatus?.amountX ?? new BN(0),
+        lastStatus?.amountY ?? new BN(0),
       );
       return {
         success: true,
@@ -136,18 +134,10 @@ export async function executeTool(
       return executors.check_lp_position();
     case "update_lp_status": {
       const i = input as Record<string, unknown>;
-      if (
-        typeof i.active_bin !== "number" ||
-        typeof i.fee_x !== "number" ||
-        typeof i.fee_y !== "number"
-      ) {
-        throw new Error(
-          "Invalid tool input: active_bin, fee_x, fee_y must be numbers",
-        );
+      if (typeof i.fee_x !== "number" || typeof i.fee_y !== "number") {
+        throw new Error("Invalid tool input: fee_x, fee_y must be numbers");
       }
-      return executors.update_lp_status(
-        i as { active_bin: number; fee_x: number; fee_y: number },
-      );
+      return executors.update_lp_status(i as { fee_x: number; fee_y: number });
     }
     default:
       throw new Error(`Unknown tool: ${name}`);
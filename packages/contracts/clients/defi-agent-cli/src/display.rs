@@ -0,0 +1,344 @@
+//! Human-readable rendering of session, monitor and daily stats accounts.
+
+use std::fmt::Write;
+
+use anchor_lang::prelude::Pubkey;
+use defi_agent::state::{
+    ActionReceiptLog, AgentSession, DailyStats, FeeSponsor, LpPositionMonitor, ALERT_EXPOSURE, ALERT_FEES,
+    LIQUIDITY_SHAPE_BID_ASK, LIQUIDITY_SHAPE_CURVE, LIQUIDITY_SHAPE_SPOT, NATIVE_MINT,
+    STRATEGY_DLMM_ADD_LIQUIDITY,
+    STRATEGY_DLMM_OPEN_POSITION, STRATEGY_DLMM_REMOVE_LIQUIDITY, STRATEGY_DLMM_SWAP,
+    STRATEGY_LIQUIDATION, STRATEGY_LP, STRATEGY_YIELD,
+};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+fn sol(lamports: u64) -> String {
+    format!("{:.4} SOL", lamports as f64 / LAMPORTS_PER_SOL)
+}
+
+/// "+0.4000 SOL (valued 5m ago)", in raw units for non-SOL valuation mints
+fn pnl(s: &AgentSession, now: i64) -> String {
+    if s.valuation_mint == Pubkey::default() {
+        return "not valued".into();
+    }
+    let amount = if s.valuation_mint == NATIVE_MINT {
+        format!("{:+.4} SOL", s.realized_pnl as f64 / LAMPORTS_PER_SOL)
+    } else {
+        format!("{:+} of {}", s.realized_pnl, s.valuation_mint)
+    };
+    format!("{amount} (valued {})", relative(s.last_valued_at, now))
+}
+
+/// "in 3h 12m" / "5m ago" / "never"
+fn relative(ts: i64, now: i64) -> String {
+    if ts == 0 {
+        return "never".into();
+    }
+    let delta = ts - now;
+    let secs = delta.unsigned_abs();
+    let span = match secs {
+        s if s >= 86_400 => format!("{}d {}h", s / 86_400, (s % 86_400) / 3_600),
+        s if s >= 3_600 => format!("{}h {}m", s / 3_600, (s % 3_600) / 60),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    };
+    if delta >= 0 {
+        format!("in {span}")
+    } else {
+        format!("{span} ago")
+    }
+}
+
+fn alert_config(s: &AgentSession) -> String {
+    let c = s.alert_config;
+    let mut parts = Vec::new();
+    if c.fees_lamports > 0 {
+        let fired = if s.alerts_raised & ALERT_FEES != 0 { " (fired)" } else { "" };
+        parts.push(format!("fees ≥ {}{fired}", sol(c.fees_lamports)));
+    }
+    if c.exposure_bps > 0 {
+        let fired = if s.alerts_raised & ALERT_EXPOSURE != 0 { " (fired)" } else { "" };
+        parts.push(format!("exposure ≥ {} bps{fired}", c.exposure_bps));
+    }
+    if c.out_of_range_secs > 0 {
+        parts.push(format!("out of range ≥ {}s", c.out_of_range_secs));
+    }
+    if parts.is_empty() {
+        "none".into()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Preset names, plus the raw bits of any single shapes outside a full preset
+fn liquidity_shapes(mask: u16) -> String {
+    let mut names = Vec::new();
+    let mut rest = mask;
+    for (preset, name) in [
+        (LIQUIDITY_SHAPE_SPOT, "spot"),
+        (LIQUIDITY_SHAPE_CURVE, "curve"),
+        (LIQUIDITY_SHAPE_BID_ASK, "bid-ask"),
+    ] {
+        if mask & preset == preset {
+            names.push(name.to_string());
+            rest &= !preset;
+        }
+    }
+    if rest != 0 {
+        names.push(format!("{rest:#05x}"));
+    }
+    names.join(", ")
+}
+
+fn strategies(mask: u8) -> String {
+    let names: Vec<&str> = [
+        (STRATEGY_LP, "lp"),
+        (STRATEGY_YIELD, "yield"),
+        (STRATEGY_LIQUIDATION, "liquidation"),
+        (STRATEGY_DLMM_SWAP, "lp:swap"),
+        (STRATEGY_DLMM_ADD_LIQUIDITY, "lp:add"),
+        (STRATEGY_DLMM_REMOVE_LIQUIDITY, "lp:remove"),
+        (STRATEGY_DLMM_OPEN_POSITION, "lp:open"),
+    ]
+    .iter()
+    .filter(|(bit, _)| mask & bit != 0)
+    .map(|(_, name)| *name)
+    .collect();
+    if names.is_empty() {
+        "none".into()
+    } else {
+        names.join(", ")
+    }
+}
+
+pub fn session(s: &AgentSession, delegated: bool, now: i64) -> String {
+    let mut out = String::new();
+    let status = match (s.is_active, s.is_expired(now)) {
+        (false, _) => "closed",
+        (true, true) => "expired",
+        (true, false) => "active",
+    };
+    let layer = if delegated { "ephemeral rollup" } else { "base layer" };
+
+    if !s.label().is_empty() {
+        let _ = writeln!(out, "  label          {}", s.label());
+    }
+    if !s.metadata_uri().is_empty() {
+        let _ = writeln!(out, "  metadata       {}", s.metadata_uri());
+    }
+    let _ = writeln!(out, "  owner          {}", s.owner);
+    if s.fee_payer != Pubkey::default() {
+        let _ = writeln!(out, "  fee payer      {}", s.fee_payer);
+    }
+    let _ = writeln!(out, "  status         {status} ({layer})");
+    let _ = writeln!(out, "  expires        {}", relative(s.expires_at, now));
+    if s.budget_mint == Pubkey::default() {
+        let _ = writeln!(out, "  spent          {} / {}", sol(s.spent_lamports), sol(s.max_lamports));
+    } else {
+        let _ = writeln!(out, "  spent          {} / {} of {}", s.spent_lamports, s.max_lamports, s.budget_mint);
+    }
+    let [lp, yield_, liquidation] = s.spent_by_strategy;
+    let _ = writeln!(
+        out,
+        "  by strategy    LP {}, yield {}, liquidation {}",
+        sol(lp),
+        sol(yield_),
+        sol(liquidation)
+    );
+    let _ = writeln!(
+        out,
+        "  market value   {}{} (revalued {})",
+        sol(s.current_exposure_value),
+        if s.mark_to_market { ", capped" } else { "" },
+        relative(s.exposure_valued_at, now),
+    );
+    let _ = writeln!(out, "  per-action cap {}", sol(s.max_action_lamports));
+    let fee_budget = match s.fee_budget_lamports {
+        0 => "uncapped".to_string(),
+        budget => format!("{} budget", sol(budget)),
+    };
+    let _ = writeln!(out, "  fees           {} / {fee_budget}", sol(s.fee_spent_lamports));
+    let tx_budget = match s.tx_fee_budget_lamports {
+        0 => "uncapped".to_string(),
+        budget => format!("{} budget", sol(budget)),
+    };
+    let _ = writeln!(out, "  tx fees        {} / {tx_budget}", sol(s.tx_fees_lamports));
+    let slippage_bound = match s.max_slippage_bps {
+        0 => "no alert".to_string(),
+        bps => format!("alert above {bps} bps"),
+    };
+    let _ = writeln!(out, "  slippage paid  {} y ({slippage_bound})", s.slippage_paid);
+    let swap_fee_budget = match s.swap_fee_budget {
+        0 => "uncapped".to_string(),
+        budget => format!("{budget} y budget"),
+    };
+    let _ = writeln!(out, "  pool fees      {} y / {swap_fee_budget}", s.swap_fees_paid);
+    if s.checkpoint_count > 0 {
+        let _ = writeln!(
+            out,
+            "  checkpoints    {} (last {})",
+            s.checkpoint_count,
+            relative(s.last_committed_at, now),
+        );
+    }
+    if s.max_uncommitted_actions > 0 || s.max_uncommitted_secs > 0 {
+        let _ = writeln!(
+            out,
+            "  uncommitted    {} actions since commit {} (limits: {} actions, {}s)",
+            s.uncommitted_actions,
+            relative(s.last_committed_at, now),
+            s.max_uncommitted_actions,
+            s.max_uncommitted_secs,
+        );
+    }
+    if s.max_idle_slots > 0 {
+        let _ = writeln!(
+            out,
+            "  idle limit     {} ER slots (last action at slot {})",
+            s.max_idle_slots,
+            s.er_last_action_slot,
+        );
+    }
+    let _ = writeln!(out, "  alerts         {}", alert_config(s));
+    let _ = writeln!(out, "  strategies     {}", strategies(s.strategy_mask));
+    if s.liquidity_shapes != 0 {
+        let _ = writeln!(out, "  shapes         {}", liquidity_shapes(s.liquidity_shapes));
+    }
+    let _ = writeln!(out, "  actions        {} (last {})", s.total_actions, relative(s.last_action_at, now));
+    let _ = writeln!(out, "  registry only  {}", s.registry_only);
+    let _ = writeln!(out, "  realized pnl   {}", pnl(s, now));
+    let _ = writeln!(out, "  fees earned    {} x / {} y", s.total_fees_earned_x, s.total_fees_earned_y);
+    if s.needs_review {
+        let _ = writeln!(out, "  review         NEEDED — a close diverged from its monitor checkpoint");
+    }
+    if s.exposure_cooldown_until > now {
+        let _ = writeln!(
+            out,
+            "  cooldown       exposure cap hit — execution resumes {}",
+            relative(s.exposure_cooldown_until, now)
+        );
+    }
+    if s.suspended {
+        let _ = writeln!(out, "  SUSPENDED      {} consecutive scope violations", s.consecutive_violations);
+    } else if s.violation_threshold > 0 {
+        let _ = writeln!(
+            out,
+            "  violations     {} / {} before suspension",
+            s.consecutive_violations, s.violation_threshold
+        );
+    }
+
+    let bound: Vec<String> = s
+        .bound_positions
+        .iter()
+        .filter(|p| **p != Pubkey::default())
+        .map(|p| p.to_string())
+        .collect();
+    let _ = writeln!(
+        out,
+        "  positions      {}",
+        if bound.is_empty() { "any".into() } else { bound.join(", ") }
+    );
+
+    let _ = write!(out, "  devices");
+    for d in s.devices.iter().filter(|d| !d.is_empty()) {
+        let mut flags = Vec::new();
+        if d.disabled {
+            flags.push("disabled");
+        }
+        if d.is_expired(now) {
+            flags.push("expired");
+        }
+        if d.is_standby() {
+            flags.push("standby");
+        }
+        let _ = write!(
+            out,
+            "\n    {}  expires {}  spent {} / {}  seen {}{}",
+            d.key,
+            relative(d.expires_at, now),
+            sol(d.spent_lamports),
+            sol(d.max_lamports),
+            relative(d.last_seen_at, now),
+            if flags.is_empty() { String::new() } else { format!("  [{}]", flags.join(", ")) },
+        );
+    }
+    out
+}
+
+pub fn monitor(m: &LpPositionMonitor, now: i64) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "  lb_pair        {}", m.lb_pair);
+    let _ = writeln!(out, "  position       {}", m.position);
+    let _ = writeln!(out, "  range          [{}, {}]", m.min_bin_id, m.max_bin_id);
+    let _ = writeln!(
+        out,
+        "  active bin     {} ({})",
+        m.last_active_bin,
+        if m.in_range() {
+            "in range".to_string()
+        } else if m.out_of_range_since > 0 {
+            format!("OUT OF RANGE since {}", relative(m.out_of_range_since, now))
+        } else {
+            "OUT OF RANGE".to_string()
+        }
+    );
+    let _ = writeln!(out, "  size           x={} y={}", m.amount_x_snapshot, m.amount_y_snapshot);
+    let _ = writeln!(out, "  fees           x={} y={}", m.fee_x_snapshot, m.fee_y_snapshot);
+    let _ = write!(out, "  last checked   {}", relative(m.last_checked_at, now));
+    out
+}
+
+/// Most recent receipts shown by `receipt_log`
+const RECEIPTS_SHOWN: u64 = 5;
+
+pub fn receipt_log(log: &ActionReceiptLog) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "  recorded       {}", log.count);
+    for nonce in (log.count.saturating_sub(RECEIPTS_SHOWN) + 1..=log.count).rev() {
+        if let Some(r) = log.get(nonce) {
+            let hash: String = r.hash.iter().map(|b| format!("{b:02x}")).collect();
+            let _ = write!(out, "\n  #{:<13} slot {}  {hash}", r.nonce, r.slot);
+        }
+    }
+    out
+}
+
+/// Days shown by `daily_stats`, today included
+const STATS_DAYS_SHOWN: i64 = 7;
+
+pub fn fee_sponsor(sponsor: &FeeSponsor, lamports: u64, now: i64) -> String {
+    let today = if sponsor.day == DailyStats::day_of(now) { sponsor.refilled_today_lamports } else { 0 };
+    let mut out = String::new();
+    let _ = writeln!(out, "  balance        {}", sol(lamports));
+    let _ = writeln!(out, "  refills to     {}", sol(sponsor.target_balance_lamports));
+    let _ = writeln!(out, "  today          {} of {}", sol(today), sol(sponsor.daily_cap_lamports));
+    let _ = writeln!(out, "  total refilled {}", sol(sponsor.total_refilled_lamports));
+    out.trim_end().to_string()
+}
+
+pub fn daily_stats(stats: &DailyStats, now: i64) -> String {
+    let today = DailyStats::day_of(now);
+    let mut out = String::new();
+    for day in (today - STATS_DAYS_SHOWN + 1..=today).rev() {
+        let label = if day == today { "today".to_string() } else { format!("{}d ago", today - day) };
+        match stats.get(day) {
+            Some(b) => {
+                let _ = writeln!(
+                    out,
+                    "  {label:<14} {} actions, volume {}, fees paid {}, earned x={} y={}",
+                    b.actions,
+                    b.volume,
+                    sol(b.fees_paid),
+                    b.fees_earned_x,
+                    b.fees_earned_y,
+                );
+            }
+            None => {
+                let _ = writeln!(out, "  {label:<14} -");
+            }
+        }
+    }
+    out.trim_end().to_string()
+}
@@ -0,0 +1,585 @@
+//! defi-agent-cli — session lifecycle management for operators without the
+//! mobile app.
+//!
+//! Usage:
+//!   defi-agent-cli init --session-key <PUBKEY> --duration-secs 86400 --max-lamports 1000000000
+//!   defi-agent-cli status
+//!   defi-agent-cli extend --device <PUBKEY> --secs 604800
+
+mod display;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use defi_agent::state::{
+    AlertConfig, BudgetCaps, LIQUIDITY_SHAPE_BID_ASK, LIQUIDITY_SHAPE_CURVE, LIQUIDITY_SHAPE_SPOT,
+    MAX_DEVICES, STRATEGY_ALL,
+};
+use defi_agent_client::instructions::MagicAccounts;
+use defi_agent_client::{accounts, instructions, pda, DELEGATION_PROGRAM_ID};
+
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(name = "defi-agent-cli", about = "Manage defi-agent sessions")]
+struct Cli {
+    /// Base-layer RPC endpoint
+    #[arg(long, env = "SOLANA_RPC_URL", default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// MagicBlock Ephemeral Rollup RPC endpoint (used by `close`, and by
+    /// `narrow-strategies` on a delegated session)
+    #[arg(long, env = "MAGICBLOCK_RPC_URL", default_value = "https://devnet.magicblock.app/")]
+    er_url: String,
+
+    /// Owner (or admin) keypair — signs and pays
+    #[arg(long, short, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the session PDA and enroll the first device key
+    Init {
+        #[arg(long)]
+        session_key: Pubkey,
+        #[arg(long)]
+        duration_secs: i64,
+        #[arg(long)]
+        max_lamports: u64,
+        #[arg(long, default_value_t = STRATEGY_ALL)]
+        strategy_mask: u8,
+        #[arg(long, default_value_t = 0)]
+        max_action_lamports: u64,
+        #[arg(long)]
+        referrer: Option<Pubkey>,
+        #[arg(long, default_value_t = 0)]
+        fee_tier: u8,
+        /// Device attestation hash, 64 hex characters (zero when omitted)
+        #[arg(long)]
+        attestation_hash: Option<String>,
+    },
+    /// Start a new term on an expired or closed session, keeping its devices,
+    /// stats and monitors (0 = Config default for each limit)
+    Renew {
+        #[arg(long, default_value_t = 0)]
+        duration_secs: i64,
+        #[arg(long, default_value_t = 0)]
+        max_lamports: u64,
+        #[arg(long, default_value_t = 0)]
+        max_action_lamports: u64,
+    },
+    /// Delegate the session to the Ephemeral Rollup, with its receipt log
+    /// when it has one
+    Delegate,
+    /// Push a device key's expiry out by `secs` from now, keeping its spend cap
+    Extend {
+        #[arg(long)]
+        device: Pubkey,
+        #[arg(long)]
+        secs: i64,
+        /// Replace the device's cumulative spend cap as well
+        #[arg(long)]
+        max_lamports: Option<u64>,
+    },
+    /// Disable a device key (its record and spend history are kept)
+    Revoke {
+        #[arg(long)]
+        device: Pubkey,
+    },
+    /// Turn strategies off mid-session, keeping only the bits in `mask`
+    /// (which must be a subset of the current mask)
+    NarrowStrategies { mask: u8 },
+    /// Restrict deposits to these liquidity shapes (none = any shape)
+    Shapes {
+        #[arg(value_parser = ["spot", "curve", "bid-ask"])]
+        shapes: Vec<String>,
+    },
+    /// Commit the delegated session's state to the base layer, keeping it on the ER
+    Commit,
+    /// Commit and undelegate the session, with its other delegated accounts,
+    /// from the ER, deactivating it
+    Close,
+    /// LP monitor management
+    #[command(subcommand)]
+    Monitor(MonitorCommand),
+    /// Pool / position allowlists
+    #[command(subcommand)]
+    Allowlist(AllowlistCommand),
+    /// Fee sponsor that tops device keys up with transaction-fee SOL
+    #[command(subcommand)]
+    Sponsor(SponsorCommand),
+    /// Create the session's DailyStats account so actions are tallied per day
+    EnableStats,
+    /// Create the session's ActionReceiptLog so actions executed on the ER
+    /// leave receipts that land on the base layer at each commit
+    EnableReceipts,
+    /// Enforce the exposure cap against marked-to-market value (`true`) or
+    /// historical cost (`false`)
+    MarkToMarket {
+        #[arg(action = clap::ArgAction::Set)]
+        enabled: bool,
+    },
+    /// Alert when a swap's output falls more than this many basis points short
+    /// of the pool's pre-trade quote (0 = off)
+    SlippageAlert { max_slippage_bps: u16 },
+    /// Cap the estimated DLMM pool fees the session's swaps may pay, in pool
+    /// Y units (0 = uncapped)
+    SwapFeeBudget { budget: u64 },
+    /// Force a commit after this many ER actions or seconds since the last
+    /// one (0 = no limit for either)
+    CommitCadence {
+        #[arg(long, default_value_t = 0)]
+        max_actions: u32,
+        #[arg(long, default_value_t = 0)]
+        max_secs: i64,
+    },
+    /// Deactivate the session when it goes this many ER slots without an
+    /// action while delegated (0 = off)
+    IdleSlots { max_idle_slots: u64 },
+    /// Count caps and exposure in this stable mint's base units, e.g. USDC
+    /// (`11111111111111111111111111111111` = raw amounts), restating every
+    /// cap in them; only before anything is spent
+    BudgetMint {
+        mint: Pubkey,
+        /// Cumulative spend cap
+        #[arg(long)]
+        max_lamports: u64,
+        /// Per-action cap
+        #[arg(long)]
+        max_action_lamports: u64,
+        /// Co-sign threshold (0 = never)
+        #[arg(long, default_value_t = 0)]
+        cosign_above_lamports: u64,
+        /// Per-device caps by device slot, comma separated (0 = session cap only)
+        #[arg(long, value_delimiter = ',')]
+        device_max_lamports: Vec<u64>,
+    },
+    /// Pin the DLMM pool that values `mint` in the budget mint (omit the pool
+    /// to unpin)
+    BudgetPricePool { mint: Pubkey, pool: Option<Pubkey> },
+    /// Admin: point commits and undelegations at a test environment's magic
+    /// program and context (`11111111111111111111111111111111` for both
+    /// restores the SDK's)
+    MagicPrograms { program: Pubkey, context: Pubkey },
+    /// Clear the review flag raised by a settlement discrepancy on close
+    AckReview,
+    /// Suspend the session after this many consecutive scope violations by
+    /// its devices (0 = off); also lifts a suspension
+    ViolationFreeze { threshold: u8 },
+    /// Pause execution for this many seconds after an action hits the
+    /// exposure cap (0 = off); also ends a running cooldown
+    ExposureCooldown { secs: i64 },
+    /// Name the session for wallets and explorers
+    Label {
+        /// Display name, at most 32 bytes
+        label: String,
+        /// Off-chain metadata URI (cleared when omitted)
+        #[arg(long)]
+        uri: Option<String>,
+    },
+    /// Let a relayer pay transaction fees for the devices' transactions; it
+    /// gains no spending authority
+    FeePayer {
+        /// Relayer pubkey (cleared when omitted)
+        fee_payer: Option<Pubkey>,
+    },
+    /// Set the on-chain alert thresholds (0 = off for each)
+    Alerts {
+        /// Alert once priority + signature fees paid reach this many lamports
+        #[arg(long, default_value_t = 0)]
+        fees_lamports: u64,
+        /// Alert when the monitored position stays out of range this long
+        #[arg(long, default_value_t = 0)]
+        out_of_range_secs: i64,
+        /// Alert once exposure reaches this share of the cap, in basis points
+        #[arg(long, default_value_t = 0)]
+        exposure_bps: u16,
+    },
+    /// Show the session, its LP monitor and recent daily totals
+    Status {
+        /// Session owner to inspect (defaults to the keypair's pubkey)
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MonitorCommand {
+    /// Create the session's LpPositionMonitor
+    Register {
+        #[arg(long)]
+        lb_pair: Pubkey,
+        #[arg(long)]
+        position: Pubkey,
+        #[arg(long, allow_negative_numbers = true)]
+        min_bin_id: i32,
+        #[arg(long, allow_negative_numbers = true)]
+        max_bin_id: i32,
+    },
+    /// Move the monitor, with its history, under the session of another owner
+    /// wallet; that owner co-signs and pays rent, and its session must have the
+    /// positions' device enrolled
+    Reassign {
+        /// Keypair of the receiving session's owner
+        #[arg(long)]
+        new_owner_keypair: String,
+        /// Move the PositionRegistry along with the monitor
+        #[arg(long)]
+        with_registry: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AllowlistCommand {
+    /// Admin: allow a pool in the global PoolRegistry
+    AddPool {
+        lb_pair: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        risk_tier: u8,
+    },
+    /// Admin: remove a pool from the global PoolRegistry
+    RemovePool { lb_pair: Pubkey },
+    /// Restrict the session to registry pools (`true`) or lift it (`false`)
+    RegistryOnly {
+        #[arg(action = clap::ArgAction::Set)]
+        enabled: bool,
+    },
+    /// Replace the session's bound-position allowlist (none = any position)
+    BindPositions { positions: Vec<Pubkey> },
+    /// Set the smallest per-action amount of a mint, in whole tokens
+    /// (e.g. `0.05`); smaller actions are rejected. `0` removes the minimum
+    MinTrade { mint: Pubkey, amount: String },
+    /// Rank the acceptable pools for a token pair, most preferred first; once
+    /// any pair is ranked, new positions must be in a ranked pool. No pools
+    /// drops the pair's list
+    RankPools {
+        token_x_mint: Pubkey,
+        token_y_mint: Pubkey,
+        pools: Vec<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SponsorCommand {
+    /// Create or retune the sponsor: refills top a device up to
+    /// `--target-lamports`, at most `--daily-cap-lamports` per UTC day
+    Configure {
+        #[arg(long)]
+        target_lamports: u64,
+        #[arg(long)]
+        daily_cap_lamports: u64,
+    },
+    /// Deposit lamports into the sponsor
+    Fund { lamports: u64 },
+    /// Top a device key up from the sponsor
+    Refill { device: Pubkey },
+    /// Close the sponsor, reclaiming its balance
+    Close,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+    let signer = load_keypair(&cli.keypair)?;
+    let me = signer.pubkey();
+    let rpc = RpcClient::new(cli.url.clone());
+
+    let ix = match cli.command {
+        Command::Init {
+            session_key,
+            duration_secs,
+            max_lamports,
+            strategy_mask,
+            max_action_lamports,
+            referrer,
+            fee_tier,
+            attestation_hash,
+        } => instructions::initialize_session(
+            me,
+            instructions::InitializeSessionArgs {
+                session_key,
+                duration_secs,
+                max_lamports,
+                strategy_mask,
+                max_action_lamports,
+                referrer,
+                fee_tier,
+                attestation_hash: parse_hash(attestation_hash.as_deref())?,
+                valuation: None,
+                // A lookup naming another session is repointed only with it passed
+                previous_session: rpc
+                    .get_account_data(&pda::session_lookup(&session_key).0)
+                    .ok()
+                    .and_then(|data| accounts::decode_session_lookup(&data).ok())
+                    .map(|lookup| lookup.session)
+                    .filter(|session| *session != pda::session(&me).0),
+            },
+        ),
+        Command::Renew { duration_secs, max_lamports, max_action_lamports } => {
+            instructions::renew_session(me, duration_secs, max_lamports, max_action_lamports)
+        }
+        Command::Delegate => {
+            // The log must be delegated while the session is still on the base layer
+            if rpc.get_account(&pda::receipt_log(&pda::session(&me).0).0).is_ok() {
+                println!("delegate_receipt_log: {}", send(&rpc, instructions::delegate_receipt_log(me), &signer)?);
+            }
+            instructions::delegate_session(me, me)
+        }
+        Command::Extend { device, secs, max_lamports } => {
+            let session = accounts::decode_session(&rpc.get_account_data(&pda::session(&me).0)?)?;
+            let slot = session.device_index(&device).ok_or("device is not enrolled in this session")?;
+            let cap = max_lamports.unwrap_or(session.devices[slot].max_lamports);
+            instructions::set_device_limits(me, device, unix_now() + secs, cap)
+        }
+        Command::Revoke { device } => instructions::disable_device_key(me, device),
+        Command::NarrowStrategies { mask } => {
+            let ix = instructions::set_strategy_mask(me, mask);
+            // A delegated session lives on the ER
+            if rpc.get_account(&pda::session(&me).0)?.owner == DELEGATION_PROGRAM_ID {
+                let er = RpcClient::new(cli.er_url.clone());
+                println!("set_strategy_mask (ER): {}", send(&er, ix, &signer)?);
+                return Ok(());
+            }
+            ix
+        }
+        Command::Commit => {
+            let er = RpcClient::new(cli.er_url.clone());
+            let receipts = rpc.get_account(&pda::receipt_log(&pda::session(&me).0).0);
+            let with_receipts = receipts.is_ok_and(|log| log.owner == DELEGATION_PROGRAM_ID);
+            let ix = instructions::commit_session(me, me, with_receipts, magic_accounts(&rpc)?);
+            let sig = send(&er, ix, &signer)?;
+            println!("commit_session (ER): {sig}");
+            return Ok(());
+        }
+        Command::Close => {
+            let er = RpcClient::new(cli.er_url.clone());
+            // Return every delegated account of the session with it
+            let session = pda::session(&me).0;
+            let delegated: Vec<Pubkey> = [pda::receipt_log(&session).0]
+                .into_iter()
+                .filter(|key| rpc.get_account(key).is_ok_and(|a| a.owner == DELEGATION_PROGRAM_ID))
+                .collect();
+            let ix = instructions::undelegate_all(me, me, None, &delegated, magic_accounts(&rpc)?);
+            let sig = send(&er, ix, &signer)?;
+            println!("undelegate_all (ER): {sig}");
+            return Ok(());
+        }
+        Command::Monitor(MonitorCommand::Register { lb_pair, position, min_bin_id, max_bin_id }) => {
+            instructions::register_lp_monitor(me, lb_pair, position, min_bin_id, max_bin_id)
+        }
+        Command::Monitor(MonitorCommand::Reassign { new_owner_keypair, with_registry }) => {
+            let new_owner = load_keypair(&new_owner_keypair)?;
+            // The positions that move along, each checked against the new session's devices
+            let session = pda::session(&me).0;
+            let monitor = accounts::decode_lp_monitor(&rpc.get_account_data(&pda::lp_monitor(&session).0)?)?;
+            let mut positions = vec![monitor.position];
+            if with_registry {
+                let data = rpc.get_account_data(&pda::position_registry(&session).0)?;
+                let registry = accounts::decode_position_registry(&data)?;
+                positions.extend(registry.active().iter().map(|entry| entry.position));
+            }
+            let ix = instructions::reassign_monitor(me, new_owner.pubkey(), with_registry, &positions);
+            let blockhash = rpc.get_latest_blockhash()?;
+            let tx = Transaction::new_signed_with_payer(&[ix], Some(&me), &[&signer, &new_owner], blockhash);
+            println!("{}", rpc.send_and_confirm_transaction(&tx)?);
+            return Ok(());
+        }
+        Command::Allowlist(cmd) => match cmd {
+            AllowlistCommand::AddPool { lb_pair, risk_tier } => instructions::add_registry_pool(me, lb_pair, risk_tier),
+            AllowlistCommand::RemovePool { lb_pair } => instructions::remove_registry_pool(me, lb_pair),
+            AllowlistCommand::RegistryOnly { enabled } => instructions::set_registry_only(me, enabled),
+            AllowlistCommand::BindPositions { positions } => instructions::set_bound_positions(me, positions),
+            AllowlistCommand::MinTrade { mint, amount } => {
+                let decimals = accounts::decode_mint_decimals(&rpc.get_account_data(&mint)?)?;
+                let base_units = parse_token_amount(&amount, decimals)?;
+                instructions::set_min_trade_amount(me, mint, base_units, decimals)
+            }
+            AllowlistCommand::RankPools { token_x_mint, token_y_mint, pools } if pools.is_empty() => {
+                instructions::close_pair_pools(me, token_x_mint, token_y_mint)
+            }
+            AllowlistCommand::RankPools { token_x_mint, token_y_mint, pools } => {
+                instructions::set_pair_pools(me, token_x_mint, token_y_mint, pools)
+            }
+        },
+        Command::Sponsor(cmd) => match cmd {
+            SponsorCommand::Configure { target_lamports, daily_cap_lamports } => {
+                instructions::configure_fee_sponsor(me, target_lamports, daily_cap_lamports)
+            }
+            SponsorCommand::Fund { lamports } => instructions::fund_fee_sponsor(me, lamports),
+            SponsorCommand::Refill { device } => instructions::refill_session_key(me, device),
+            SponsorCommand::Close => instructions::close_fee_sponsor(me),
+        },
+        Command::EnableStats => instructions::initialize_daily_stats(me),
+        Command::EnableReceipts => instructions::initialize_receipt_log(me),
+        Command::MarkToMarket { enabled } => instructions::set_mark_to_market(me, enabled),
+        Command::AckReview => instructions::acknowledge_review(me),
+        Command::Shapes { shapes } => {
+            let mask = shapes.iter().fold(0, |mask, shape| {
+                mask | match shape.as_str() {
+                    "spot" => LIQUIDITY_SHAPE_SPOT,
+                    "curve" => LIQUIDITY_SHAPE_CURVE,
+                    _ => LIQUIDITY_SHAPE_BID_ASK,
+                }
+            });
+            instructions::set_liquidity_shapes(me, mask)
+        }
+        Command::ViolationFreeze { threshold } => instructions::set_violation_freeze(me, threshold),
+        Command::ExposureCooldown { secs } => instructions::set_exposure_cooldown(me, secs),
+        Command::Label { label, uri } => {
+            if label.len() > 32 {
+                return Err("label must be at most 32 bytes".into());
+            }
+            instructions::set_session_metadata(me, &label, uri.as_deref())
+        }
+        Command::FeePayer { fee_payer } => instructions::set_fee_payer(me, fee_payer),
+        Command::Alerts { fees_lamports, out_of_range_secs, exposure_bps } => instructions::set_alert_config(
+            me,
+            AlertConfig {
+                fees_lamports,
+                out_of_range_secs,
+                exposure_bps,
+            },
+        ),
+        Command::SlippageAlert { max_slippage_bps } => instructions::set_slippage_alert(me, max_slippage_bps),
+        Command::SwapFeeBudget { budget } => instructions::set_swap_fee_budget(me, budget),
+        Command::CommitCadence { max_actions, max_secs } => {
+            instructions::set_commit_cadence(me, max_actions, max_secs)
+        }
+        Command::IdleSlots { max_idle_slots } => instructions::set_max_idle_slots(me, max_idle_slots),
+        Command::BudgetMint {
+            mint,
+            max_lamports,
+            max_action_lamports,
+            cosign_above_lamports,
+            device_max_lamports,
+        } => {
+            if device_max_lamports.len() > MAX_DEVICES {
+                return Err(format!("at most {MAX_DEVICES} device caps").into());
+            }
+            let mut caps = BudgetCaps {
+                max_lamports,
+                max_action_lamports,
+                cosign_above_lamports,
+                ..BudgetCaps::default()
+            };
+            caps.device_max_lamports[..device_max_lamports.len()].copy_from_slice(&device_max_lamports);
+            instructions::set_budget_mint(me, mint, caps)
+        }
+        Command::BudgetPricePool { mint, pool } => instructions::set_budget_price_pool(me, mint, pool),
+        Command::MagicPrograms { program, context } => instructions::set_magic_programs(me, program, context),
+        Command::Status { owner } => return status(&rpc, owner.unwrap_or(me)),
+    };
+
+    let sig = send(&rpc, ix, &signer)?;
+    println!("{sig}");
+    Ok(())
+}
+
+fn status(rpc: &RpcClient, owner: Pubkey) -> CliResult<()> {
+    let session_key = pda::session(&owner).0;
+    let account = rpc.get_account(&session_key)?;
+    let session = accounts::decode_session(&account.data)?;
+    let now = unix_now();
+
+    println!("Session  {session_key}");
+    println!("{}", display::session(&session, account.owner == DELEGATION_PROGRAM_ID, now));
+
+    let monitor_key = pda::lp_monitor(&session_key).0;
+    match rpc.get_account_data(&monitor_key) {
+        Ok(data) => {
+            println!("\nMonitor  {monitor_key}");
+            println!("{}", display::monitor(&accounts::decode_lp_monitor(&data)?, now));
+        }
+        Err(_) => println!("\nMonitor  (not registered)"),
+    }
+
+    let sponsor_key = pda::fee_sponsor(&session_key).0;
+    if let Ok(account) = rpc.get_account(&sponsor_key) {
+        println!("\nSponsor  {sponsor_key}");
+        println!("{}", display::fee_sponsor(&accounts::decode_fee_sponsor(&account.data)?, account.lamports, now));
+    }
+
+    let stats_key = pda::daily_stats(&session_key).0;
+    if let Ok(data) = rpc.get_account_data(&stats_key) {
+        println!("\nDaily    {stats_key}");
+        println!("{}", display::daily_stats(&accounts::decode_daily_stats(&data)?, now));
+    }
+
+    let receipts_key = pda::receipt_log(&session_key).0;
+    if let Ok(data) = rpc.get_account_data(&receipts_key) {
+        println!("\nReceipts {receipts_key}");
+        println!("{}", display::receipt_log(&accounts::decode_receipt_log(&data)?));
+    }
+    Ok(())
+}
+
+fn send(rpc: &RpcClient, ix: Instruction, signer: &Keypair) -> CliResult<String> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+    Ok(rpc.send_and_confirm_transaction(&tx)?.to_string())
+}
+
+/// The magic accounts the deployment's Config points commits at — the SDK's
+/// when there is no Config
+fn magic_accounts(rpc: &RpcClient) -> CliResult<MagicAccounts> {
+    match rpc.get_account_data(&pda::config().0) {
+        Ok(data) => Ok(MagicAccounts::from_config(&accounts::decode_config(&data)?)),
+        Err(_) => Ok(MagicAccounts::SDK),
+    }
+}
+
+fn load_keypair(path: &str) -> CliResult<Keypair> {
+    let path = match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    };
+    read_keypair_file(&path).map_err(|e| format!("{path}: {e}").into())
+}
+
+fn parse_hash(hex: Option<&str>) -> CliResult<[u8; 32]> {
+    let mut out = [0u8; 32];
+    let Some(hex) = hex else {
+        return Ok(out);
+    };
+    if hex.len() != 64 {
+        return Err("attestation hash must be 64 hex characters".into());
+    }
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)?;
+    }
+    Ok(out)
+}
+
+/// Convert a whole-token amount such as `1.5` to base units for a mint with
+/// `decimals` decimals, without going through floating point
+fn parse_token_amount(amount: &str, decimals: u8) -> CliResult<u64> {
+    let (whole, frac) = amount.split_once('.').unwrap_or((amount, ""));
+    if frac.len() > decimals as usize {
+        return Err(format!("amount has more than {decimals} decimal places").into());
+    }
+    let digits = format!("{whole}{frac:0<width$}", width = decimals as usize);
+    Ok(digits.parse::<u64>()?)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token_2022::spl_token_2022::extension::StateWithExtensions;
+use anchor_spl::token_2022::spl_token_2022::state::Mint;
+
+use defi_agent::dlmm::accounts::{BinArray, LbPair, PositionV2};
+use defi_agent::state::{
+    ActionReceiptLog, AgentSession, Config, DailyStats, FeeSponsor, Intent, LpPositionMonitor,
+    PairPools, PositionRegistry, SessionLookup,
+};
+
+/// Decode raw `AgentSession` account data (discriminator included)
+pub fn decode_session(data: &[u8]) -> Result<AgentSession> {
+    let mut data = data;
+    AgentSession::try_deserialize(&mut data)
+}
+
+/// Decode raw `Config` account data (discriminator included)
+pub fn decode_config(data: &[u8]) -> Result<Config> {
+    let mut data = data;
+    Config::try_deserialize(&mut data)
+}
+
+/// Decode raw `SessionLookup` account data (discriminator included)
+pub fn decode_session_lookup(data: &[u8]) -> Result<SessionLookup> {
+    let mut data = data;
+    SessionLookup::try_deserialize(&mut data)
+}
+
+/// Decode raw `FeeSponsor` account data (discriminator included)
+pub fn decode_fee_sponsor(data: &[u8]) -> Result<FeeSponsor> {
+    let mut data = data;
+    FeeSponsor::try_deserialize(&mut data)
+}
+
+/// Decode raw `Intent` account data (discriminator included)
+pub fn decode_intent(data: &[u8]) -> Result<Intent> {
+    let mut data = data;
+    Intent::try_deserialize(&mut data)
+}
+
+/// Decode raw `PairPools` account data (discriminator included)
+pub fn decode_pair_pools(data: &[u8]) -> Result<PairPools> {
+    let mut data = data;
+    PairPools::try_deserialize(&mut data)
+}
+
+/// Decode raw `LpPositionMonitor` account data
+pub fn decode_lp_monitor(data: &[u8]) -> Result<LpPositionMonitor> {
+    decode_zero_copy(data)
+}
+
+/// Decode raw `PositionRegistry` account data
+pub fn decode_position_registry(data: &[u8]) -> Result<PositionRegistry> {
+    decode_zero_copy(data)
+}
+
+/// Decode raw `DailyStats` account data
+pub fn decode_daily_stats(data: &[u8]) -> Result<DailyStats> {
+    decode_zero_copy(data)
+}
+
+/// Decode raw `ActionReceiptLog` account data
+pub fn decode_receipt_log(data: &[u8]) -> Result<ActionReceiptLog> {
+    decode_zero_copy(data)
+}
+
+/// Decode a Meteora DLMM `LbPair` (pool) account
+pub fn decode_lb_pair(data: &[u8]) -> Result<LbPair> {
+    decode_zero_copy(data)
+}
+
+/// Decode a Meteora DLMM `PositionV2` account
+pub fn decode_dlmm_position(data: &[u8]) -> Result<PositionV2> {
+    decode_zero_copy(data)
+}
+
+/// Decode a Meteora DLMM `BinArray` account
+pub fn decode_bin_array(data: &[u8]) -> Result<BinArray> {
+    decode_zero_copy(data)
+}
+
+/// Decimals of an SPL Token or Token-2022 mint
+pub fn decode_mint_decimals(data: &[u8]) -> Result<u8> {
+    StateWithExtensions::<Mint>::unpack(data)
+        .map(|mint| mint.base.decimals)
+        .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+/// Decode any zero-copy account — the body is copied out of the buffer, so
+/// `data` need not be aligned
+pub fn decode_zero_copy<T: bytemuck::Pod + Discriminator>(data: &[u8]) -> Result<T> {
+    let disc = T::DISCRIMINATOR;
+    if data.len() < disc.len() || &data[..disc.len()] != disc {
+        return err!(ErrorCode::AccountDiscriminatorMismatch);
+    }
+    let body = &data[disc.len()..];
+    let size = std::mem::size_of::<T>();
+    if body.len() < size {
+        return err!(ErrorCode::AccountDidNotDeserialize);
+    }
+    Ok(bytemuck::pod_read_unaligned(&body[..size]))
+}
@@ -0,0 +1,104 @@
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+
+use defi_agent::dlmm::accounts::{BinArray, PositionV2};
+
+pub use defi_agent::dlmm::ID as DLMM_PROGRAM_ID;
+pub use defi_agent::DLMM_EVENT_AUTHORITY;
+
+/// Number of bins stored in a single DLMM BinArray account
+pub const MAX_BIN_PER_ARRAY: i32 = 70;
+
+/// BinArray index that holds `bin_id` (floor division — negative bins round down)
+pub fn bin_id_to_bin_array_index(bin_id: i32) -> i64 {
+    bin_id.div_euclid(MAX_BIN_PER_ARRAY) as i64
+}
+
+/// Lower / upper bin id covered by the BinArray at `index`
+pub fn bin_array_bounds(index: i64) -> (i32, i32) {
+    let lower = index as i32 * MAX_BIN_PER_ARRAY;
+    (lower, lower + MAX_BIN_PER_ARRAY - 1)
+}
+
+/// BinArray PDA — `[b"bin_array", lb_pair, index_le]` under the DLMM program
+pub fn bin_array(lb_pair: &Pubkey, index: i64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"bin_array", lb_pair.as_ref(), &index.to_le_bytes()],
+        &DLMM_PROGRAM_ID,
+    )
+}
+
+/// Bitmap extension PDA — `[b"bitmap", lb_pair]` under the DLMM program
+pub fn bin_array_bitmap_extension(lb_pair: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"bitmap", lb_pair.as_ref()], &DLMM_PROGRAM_ID)
+}
+
+/// BinArray addresses spanning `[lower_bin_id, upper_bin_id]`, in ascending order
+pub fn bin_arrays_for_range(lb_pair: &Pubkey, lower_bin_id: i32, upper_bin_id: i32) -> Vec<Pubkey> {
+    let lower = bin_id_to_bin_array_index(lower_bin_id);
+    let upper = bin_id_to_bin_array_index(upper_bin_id);
+    (lower..=upper).map(|index| bin_array(lb_pair, index).0).collect()
+}
+
+/// Writable remaining-account metas for the bin arrays a swap will traverse.
+///
+/// Starts at the array holding `active_bin` and walks `count` arrays in the
+/// swap direction (`swap_for_y` moves towards lower bins).
+pub fn swap_bin_array_metas(lb_pair: &Pubkey, active_bin: i32, swap_for_y: bool, count: usize) -> Vec<AccountMeta> {
+    let start = bin_id_to_bin_array_index(active_bin);
+    (0..count as i64)
+        .map(|step| if swap_for_y { start - step } else { start + step })
+        .map(|index| AccountMeta::new(bin_array(lb_pair, index).0, false))
+        .collect()
+}
+
+/// Settled-but-unclaimed fees of a position, summed over its bins.
+///
+/// This is the `fee_*_pending` bookkeeping DLMM updates on every liquidity
+/// change — fees accrued since then are not included.
+pub fn pending_fees(position: &PositionV2) -> (u64, u64) {
+    position.fee_infos.iter().fold((0u64, 0u64), |(x, y), info| {
+        (x.saturating_add(info.fee_x_pending), y.saturating_add(info.fee_y_pending))
+    })
+}
+
+/// Token amounts a position holds, fees excluded: each bin's reserves pro rata
+/// to the position's share of its liquidity.
+///
+/// `bin_arrays` must cover the position's range (see `bin_arrays_for_range`);
+/// bins outside the given arrays count as empty.
+pub fn position_amounts(position: &PositionV2, bin_arrays: &[BinArray]) -> (u64, u64) {
+    let (mut amount_x, mut amount_y) = (0f64, 0f64);
+    for (offset, share) in position.liquidity_shares.iter().enumerate() {
+        let bin_id = position.lower_bin_id + offset as i32;
+        if *share == 0 || bin_id > position.upper_bin_id {
+            continue;
+        }
+        let index = bin_id_to_bin_array_index(bin_id);
+        let Some(array) = bin_arrays.iter().find(|a| a.index == index) else {
+            continue;
+        };
+        let bin = &array.bins[(bin_id - bin_array_bounds(index).0) as usize];
+        if bin.liquidity_supply == 0 {
+            continue;
+        }
+        // Shares are Q64-scaled, so the exact product can overflow u128
+        let fraction = *share as f64 / bin.liquidity_supply as f64;
+        amount_x += bin.amount_x as f64 * fraction;
+        amount_y += bin.amount_y as f64 * fraction;
+    }
+    (amount_x as u64, amount_y as u64)
+}
+
+/// BinArray indices covered by the LbPair's internal bitmap; arrays outside
+/// `[-BIN_ARRAY_BITMAP_SIZE, BIN_ARRAY_BITMAP_SIZE - 1]` need the bitmap extension
+pub const BIN_ARRAY_BITMAP_SIZE: i64 = 512;
+
+/// True when the BinArray at `index` is tracked by the bitmap extension account
+pub fn needs_bitmap_extension(index: i64) -> bool {
+    !(-BIN_ARRAY_BITMAP_SIZE..BIN_ARRAY_BITMAP_SIZE).contains(&index)
+}
+
+/// Price of `bin_id` in raw token units (Y per X): `(1 + bin_step / 10_000) ^ bin_id`
+pub fn bin_price(bin_id: i32, bin_step: u16) -> f64 {
+    (1.0 + bin_step as f64 / 10_000.0).powi(bin_id)
+}
@@ -0,0 +1,1325 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+use defi_agent::dlmm::types::LiquidityParameterByStrategy;
+use defi_agent::introspection::{SignedIntent, ED25519_PROGRAM_ID, MEMO_PROGRAM_ID};
+use defi_agent::state::{ActionSpec, AlertConfig, BudgetCaps, Config, IntentSpec};
+use defi_agent::{accounts, instruction};
+use ephemeral_rollups_sdk::consts::{DELEGATION_PROGRAM_ID, MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use ephemeral_rollups_sdk::pda::{
+    delegate_buffer_pda_from_delegated_account_and_owner_program,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+};
+
+use crate::{dlmm, pda, PROGRAM_ID};
+
+/// Assemble a program instruction from Anchor's generated accounts / args structs.
+/// `remaining` is appended after the named accounts (bin arrays, batch accounts, …).
+pub fn build(accounts: impl ToAccountMetas, args: impl InstructionData, remaining: Vec<AccountMeta>) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining);
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data: args.data(),
+    }
+}
+
+/// A DLMM pool and a device's token accounts for its X and Y mints, valued
+/// into the session's PnL by `initialize_session` and `undelegate_session`.
+/// The pool must be in the PoolRegistry or pinned as a budget price pool.
+#[derive(Clone, Copy)]
+pub struct ValuationAccounts {
+    pub lb_pair: Pubkey,
+    pub token_x: Pubkey,
+    pub token_y: Pubkey,
+    /// Pass the PoolRegistry, vouching for a registered `lb_pair`
+    pub registered: bool,
+}
+
+impl ValuationAccounts {
+    fn pool_registry(valuation: Option<Self>) -> Option<Pubkey> {
+        valuation.filter(|v| v.registered).map(|_| pda::pool_registry().0)
+    }
+}
+
+/// The MagicBlock magic program and context a commit or undelegation targets,
+/// with the Config that authorizes them when the deployment overrides them
+#[derive(Clone, Copy)]
+pub struct MagicAccounts {
+    pub program: Pubkey,
+    pub context: Pubkey,
+    pub config: Option<Pubkey>,
+}
+
+impl MagicAccounts {
+    /// The SDK's magic program and context
+    pub const SDK: Self = Self {
+        program: MAGIC_PROGRAM_ID,
+        context: MAGIC_CONTEXT_ID,
+        config: None,
+    };
+
+    /// The magic accounts `config` resolves to (see `set_magic_programs`),
+    /// passing the Config along so the program accepts them
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            program: config.magic_program_id(),
+            context: config.magic_context_id(),
+            config: Some(pda::config().0),
+        }
+    }
+}
+
+impl Default for MagicAccounts {
+    fn default() -> Self {
+        Self::SDK
+    }
+}
+
+/// Arguments for [`initialize_session`] — mirrors the on-chain handler
+pub struct InitializeSessionArgs {
+    pub session_key: Pubkey,
+    pub duration_secs: i64,
+    pub max_lamports: u64,
+    pub strategy_mask: u8,
+    pub max_action_lamports: u64,
+    pub referrer: Option<Pubkey>,
+    pub fee_tier: u8,
+    pub attestation_hash: [u8; 32],
+    /// Records the entry portfolio value when set
+    pub valuation: Option<ValuationAccounts>,
+    /// Session the key's lookup entry names, when it names another one
+    pub previous_session: Option<Pubkey>,
+}
+
+/// [Base Layer] Create the AgentSession PDA for `owner`
+pub fn initialize_session(owner: Pubkey, args: InitializeSessionArgs) -> Instruction {
+    build(
+        accounts::InitializeSession {
+            owner,
+            session: pda::session(&owner).0,
+            session_lookup: pda::session_lookup(&args.session_key).0,
+            previous_session: args.previous_session,
+            config: pda::config().0,
+            system_program: system_program::ID,
+            valuation_lb_pair: args.valuation.map(|v| v.lb_pair),
+            valuation_token_x: args.valuation.map(|v| v.token_x),
+            valuation_token_y: args.valuation.map(|v| v.token_y),
+            pool_registry: ValuationAccounts::pool_registry(args.valuation),
+        },
+        instruction::InitializeSession {
+            session_key: args.session_key,
+            duration_secs: args.duration_secs,
+            max_lamports: args.max_lamports,
+            strategy_mask: args.strategy_mask,
+            max_action_lamports: args.max_action_lamports,
+            referrer: args.referrer,
+            fee_tier: args.fee_tier,
+            attestation_hash: args.attestation_hash,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Delegate `owner`'s session PDA to the Ephemeral Rollup
+pub fn delegate_session(payer: Pubkey, owner: Pubkey) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::DelegateSession {
+            payer,
+            agent_session: session,
+            buffer_agent_session: delegate_buffer_pda_from_delegated_account_and_owner_program(&session, &PROGRAM_ID),
+            delegation_record_agent_session: delegation_record_pda_from_delegated_account(&session),
+            delegation_metadata_agent_session: delegation_metadata_pda_from_delegated_account(&session),
+            owner_program: PROGRAM_ID,
+            delegation_program: DELEGATION_PROGRAM_ID,
+            system_program: system_program::ID,
+        },
+        instruction::DelegateSession { owner },
+        vec![],
+    )
+}
+
+/// [Base Layer] Delegate `owner`'s ActionReceiptLog to the Ephemeral Rollup —
+/// send before [`delegate_session`]
+pub fn delegate_receipt_log(owner: Pubkey) -> Instruction {
+    let session = pda::session(&owner).0;
+    let receipt_log = pda::receipt_log(&session).0;
+    build(
+        accounts::DelegateReceiptLog {
+            owner,
+            session,
+            receipt_log,
+            buffer_receipt_log: delegate_buffer_pda_from_delegated_account_and_owner_program(&receipt_log, &PROGRAM_ID),
+            delegation_record_receipt_log: delegation_record_pda_from_delegated_account(&receipt_log),
+            delegation_metadata_receipt_log: delegation_metadata_pda_from_delegated_account(&receipt_log),
+            owner_program: PROGRAM_ID,
+            delegation_program: DELEGATION_PROGRAM_ID,
+            system_program: system_program::ID,
+        },
+        instruction::DelegateReceiptLog {},
+        vec![],
+    )
+}
+
+/// [Ephemeral Rollup] Commit the session's state to the base layer without undelegating,
+/// opening a new commit-cadence window. `with_receipts` commits the session's delegated
+/// ActionReceiptLog too.
+pub fn commit_session(payer: Pubkey, owner: Pubkey, with_receipts: bool, magic: MagicAccounts) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::CommitSession {
+            payer,
+            session,
+            receipt_log: with_receipts.then(|| pda::receipt_log(&session).0),
+            config: magic.config,
+            magic_context: magic.context,
+            magic_program: magic.program,
+        },
+        instruction::CommitSession {},
+        vec![],
+    )
+}
+
+/// [Ephemeral Rollup] Commit the session, return it to the base layer and deactivate it,
+/// recording a final portfolio valuation when `valuation` is set. `with_receipts`
+/// commits and returns the session's delegated ActionReceiptLog with it.
+pub fn undelegate_session(
+    payer: Pubkey,
+    owner: Pubkey,
+    valuation: Option<ValuationAccounts>,
+    with_receipts: bool,
+    magic: MagicAccounts,
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::UndelegateSession {
+            payer,
+            session,
+            valuation_lb_pair: valuation.map(|v| v.lb_pair),
+            valuation_token_x: valuation.map(|v| v.token_x),
+            valuation_token_y: valuation.map(|v| v.token_y),
+            pool_registry: ValuationAccounts::pool_registry(valuation),
+            receipt_log: with_receipts.then(|| pda::receipt_log(&session).0),
+            config: magic.config,
+            magic_context: magic.context,
+            magic_program: magic.program,
+        },
+        instruction::UndelegateSession {},
+        vec![],
+    )
+}
+
+/// [Ephemeral Rollup] [`undelegate_session`] that also commits and undelegates
+/// `delegated` — the session's other delegated accounts (receipt log, …)
+pub fn undelegate_all(
+    payer: Pubkey,
+    owner: Pubkey,
+    valuation: Option<ValuationAccounts>,
+    delegated: &[Pubkey],
+    magic: MagicAccounts,
+) -> Instruction {
+    build(
+        accounts::UndelegateAll {
+            payer,
+            session: pda::session(&owner).0,
+            valuation_lb_pair: valuation.map(|v| v.lb_pair),
+            valuation_token_x: valuation.map(|v| v.token_x),
+            valuation_token_y: valuation.map(|v| v.token_y),
+            pool_registry: ValuationAccounts::pool_registry(valuation),
+            config: magic.config,
+            magic_context: magic.context,
+            magic_program: magic.program,
+        },
+        instruction::UndelegateAll {},
+        delegated.iter().map(|key| AccountMeta::new(*key, false)).collect(),
+    )
+}
+
+/// [Base Layer] Set a device key's expiry and cumulative spend cap
+pub fn set_device_limits(owner: Pubkey, device_key: Pubkey, expires_at: i64, max_lamports: u64) -> Instruction {
+    build(
+        accounts::SetDeviceLimits {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetDeviceLimits {
+            device_key,
+            expires_at,
+            max_lamports,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Disable a device key while keeping its record
+pub fn disable_device_key(owner: Pubkey, device_key: Pubkey) -> Instruction {
+    build(
+        accounts::DisableDeviceKey {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::DisableDeviceKey { device_key },
+        vec![],
+    )
+}
+
+/// [Base Layer / Ephemeral Rollup] Owner: clear strategy bits on an active
+/// session — `strategy_mask` must be a subset of the current mask. Send it to
+/// the ER while the session is delegated.
+pub fn set_strategy_mask(owner: Pubkey, strategy_mask: u8) -> Instruction {
+    build(
+        accounts::SetStrategyMask {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetStrategyMask { strategy_mask },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: set the on-chain alert thresholds (zero fields are
+/// off); re-arms the one-shot fee and exposure alerts
+pub fn set_alert_config(owner: Pubkey, alert_config: AlertConfig) -> Instruction {
+    build(
+        accounts::SetAlertConfig {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetAlertConfig { alert_config },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: set the session's display label (at most 32 bytes of
+/// UTF-8, truncated on a character boundary) and optional metadata URI;
+/// `None` clears the URI
+pub fn set_session_metadata(owner: Pubkey, label: &str, metadata_uri: Option<&str>) -> Instruction {
+    let mut padded = [0u8; 32];
+    let mut len = label.len().min(32);
+    while !label.is_char_boundary(len) {
+        len -= 1;
+    }
+    padded[..len].copy_from_slice(&label.as_bytes()[..len]);
+    build(
+        accounts::SetSessionMetadata {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetSessionMetadata {
+            label: padded,
+            metadata_uri: metadata_uri.unwrap_or_default().to_string(),
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: let `fee_payer` pay transaction fees for device-signed
+/// instructions; `None` clears it
+pub fn set_fee_payer(owner: Pubkey, fee_payer: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetFeePayer {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetFeePayer {
+            fee_payer: fee_payer.unwrap_or_default(),
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: create or retune the session's fee sponsor
+pub fn configure_fee_sponsor(owner: Pubkey, target_balance_lamports: u64, daily_cap_lamports: u64) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::ConfigureFeeSponsor {
+            owner,
+            session,
+            fee_sponsor: pda::fee_sponsor(&session).0,
+            system_program: system_program::ID,
+        },
+        instruction::ConfigureFeeSponsor {
+            target_balance_lamports,
+            daily_cap_lamports,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: deposit `lamports` into the session's fee sponsor
+pub fn fund_fee_sponsor(owner: Pubkey, lamports: u64) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::FundFeeSponsor {
+            owner,
+            session,
+            fee_sponsor: pda::fee_sponsor(&session).0,
+            system_program: system_program::ID,
+        },
+        instruction::FundFeeSponsor { lamports },
+        vec![],
+    )
+}
+
+/// [Base Layer] Anyone: top `device` up from `owner`'s session fee sponsor
+pub fn refill_session_key(owner: Pubkey, device: Pubkey) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::RefillSessionKey {
+            session,
+            fee_sponsor: pda::fee_sponsor(&session).0,
+            device,
+        },
+        instruction::RefillSessionKey {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: close the fee sponsor, reclaiming its balance
+pub fn close_fee_sponsor(owner: Pubkey) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::CloseFeeSponsor {
+            owner,
+            session,
+            fee_sponsor: pda::fee_sponsor(&session).0,
+        },
+        instruction::CloseFeeSponsor {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: restrict deposits to `liquidity_shapes`
+/// (`LIQUIDITY_SHAPE_*` bits; 0 = any)
+pub fn set_liquidity_shapes(owner: Pubkey, liquidity_shapes: u16) -> Instruction {
+    build(
+        accounts::SetLiquidityShapes {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetLiquidityShapes { liquidity_shapes },
+        vec![],
+    )
+}
+
+/// [Base Layer] Restrict (or stop restricting) the session to registry pools
+pub fn set_registry_only(owner: Pubkey, registry_only: bool) -> Instruction {
+    build(
+        accounts::SetRegistryOnly {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetRegistryOnly { registry_only },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: enforce the exposure cap against marked-to-market value
+/// (`true`) or historical cost (`false`)
+pub fn set_mark_to_market(owner: Pubkey, enabled: bool) -> Instruction {
+    build(
+        accounts::SetMarkToMarket {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetMarkToMarket { enabled },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: set the per-swap slippage, in basis points, above
+/// which swaps emit `SlippageExceeded` (0 = off)
+pub fn set_slippage_alert(owner: Pubkey, max_slippage_bps: u16) -> Instruction {
+    build(
+        accounts::SetSlippageAlert {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetSlippageAlert { max_slippage_bps },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: cap the estimated DLMM pool swap fees counted into
+/// `swap_fees_paid` (0 = uncapped)
+pub fn set_swap_fee_budget(owner: Pubkey, swap_fee_budget: u64) -> Instruction {
+    build(
+        accounts::SetSwapFeeBudget {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetSwapFeeBudget { swap_fee_budget },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: bound the actions / seconds a delegated session may run
+/// on the ER between commits (0 = no limit)
+pub fn set_commit_cadence(owner: Pubkey, max_uncommitted_actions: u32, max_uncommitted_secs: i64) -> Instruction {
+    build(
+        accounts::SetCommitCadence {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetCommitCadence {
+            max_uncommitted_actions,
+            max_uncommitted_secs,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: deactivate a delegated session that goes `max_idle_slots`
+/// ER slots without an action (0 = off)
+pub fn set_max_idle_slots(owner: Pubkey, max_idle_slots: u64) -> Instruction {
+    build(
+        accounts::SetMaxIdleSlots {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetMaxIdleSlots { max_idle_slots },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: denominate caps and exposure in a stable mint
+/// (`Pubkey::default()` = raw amounts), restating every cap in its units;
+/// only before anything is spent
+pub fn set_budget_mint(owner: Pubkey, budget_mint: Pubkey, caps: BudgetCaps) -> Instruction {
+    build(
+        accounts::SetBudgetMint {
+            owner,
+            session: pda::session(&owner).0,
+            config: pda::config().0,
+        },
+        instruction::SetBudgetMint { budget_mint, caps },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: pin the DLMM pool that values `mint` in the budget
+/// mint; `None` unpins it
+pub fn set_budget_price_pool(owner: Pubkey, mint: Pubkey, price_pool: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetBudgetPricePool {
+            owner,
+            session: pda::session(&owner).0,
+            price_pool,
+        },
+        instruction::SetBudgetPricePool { mint },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: start a new term on an expired or undelegated session,
+/// keeping its devices, stats, registries and monitors. Zero limits take the
+/// Config defaults, as in [`initialize_session`].
+pub fn renew_session(owner: Pubkey, duration_secs: i64, max_lamports: u64, max_action_lamports: u64) -> Instruction {
+    build(
+        accounts::RenewSession {
+            owner,
+            session: pda::session(&owner).0,
+            config: pda::config().0,
+        },
+        instruction::RenewSession {
+            duration_secs,
+            max_lamports,
+            max_action_lamports,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: clear the review flag raised by a `SettlementDiscrepancy`
+pub fn acknowledge_review(owner: Pubkey) -> Instruction {
+    build(
+        accounts::AcknowledgeReview {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::AcknowledgeReview {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: suspend the session after `violation_threshold`
+/// consecutive scope violations (0 = off); also lifts a suspension
+pub fn set_violation_freeze(owner: Pubkey, violation_threshold: u8) -> Instruction {
+    build(
+        accounts::SetViolationFreeze {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetViolationFreeze { violation_threshold },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: fail execute calls for `cooldown_secs` after one hits
+/// the exposure cap (0 = off); also ends a running cooldown
+pub fn set_exposure_cooldown(owner: Pubkey, cooldown_secs: i64) -> Instruction {
+    build(
+        accounts::SetExposureCooldown {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetExposureCooldown { cooldown_secs },
+        vec![],
+    )
+}
+
+/// [Base Layer] Move `owner`'s LpPositionMonitor — and its PositionRegistry
+/// when `with_registry` — under `new_owner`'s session. Both owners sign;
+/// `new_owner` pays rent for the new accounts. `positions` lists the DLMM
+/// positions being moved — the monitor's, then each registry entry in order —
+/// and each must be owned by a device of the new session.
+pub fn reassign_monitor(
+    owner: Pubkey,
+    new_owner: Pubkey,
+    with_registry: bool,
+    positions: &[Pubkey],
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    let new_session = pda::session(&new_owner).0;
+    build(
+        accounts::ReassignMonitor {
+            owner,
+            new_owner,
+            session,
+            new_session,
+            monitor: pda::lp_monitor(&session).0,
+            new_monitor: pda::lp_monitor(&new_session).0,
+            position_registry: with_registry.then(|| pda::position_registry(&session).0),
+            new_position_registry: with_registry.then(|| pda::position_registry(&new_session).0),
+            system_program: system_program::ID,
+        },
+        instruction::ReassignMonitor {},
+        positions.iter().map(|position| AccountMeta::new_readonly(*position, false)).collect(),
+    )
+}
+
+/// [Base Layer] Reprice the session's registered positions, signed by the
+/// session key. `pools` must list every registered position's LbPair once.
+pub fn revalue_exposure(session_key: Pubkey, owner: Pubkey, pools: &[Pubkey]) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::RevalueExposure {
+            session_key,
+            session,
+            position_registry: pda::position_registry(&session).0,
+        },
+        instruction::RevalueExposure {},
+        pools.iter().map(|pool| AccountMeta::new_readonly(*pool, false)).collect(),
+    )
+}
+
+/// [Base Layer] Replace the session's bound-position allowlist
+pub fn set_bound_positions(owner: Pubkey, positions: Vec<Pubkey>) -> Instruction {
+    build(
+        accounts::SetBoundPositions {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetBoundPositions { positions },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: rank the acceptable DLMM pools for a token pair, most
+/// preferred first. Each pool is passed as a read-only remaining account.
+pub fn set_pair_pools(owner: Pubkey, token_x_mint: Pubkey, token_y_mint: Pubkey, pools: Vec<Pubkey>) -> Instruction {
+    let session = pda::session(&owner).0;
+    let remaining = pools.iter().map(|pool| AccountMeta::new_readonly(*pool, false)).collect();
+    build(
+        accounts::SetPairPools {
+            owner,
+            session,
+            pair_pools: pda::pair_pools(&session, &token_x_mint, &token_y_mint).0,
+            system_program: system_program::ID,
+        },
+        instruction::SetPairPools { token_x_mint, token_y_mint, pools },
+        remaining,
+    )
+}
+
+/// [Base Layer] Owner: drop the ranked pool list for a token pair
+pub fn close_pair_pools(owner: Pubkey, token_x_mint: Pubkey, token_y_mint: Pubkey) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::ClosePairPools {
+            owner,
+            session,
+            pair_pools: pda::pair_pools(&session, &token_x_mint, &token_y_mint).0,
+        },
+        instruction::ClosePairPools {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: set the session's minimum per-action amount of `mint`,
+/// in base units; `decimals` must match the mint's. 0 removes it.
+pub fn set_min_trade_amount(owner: Pubkey, mint: Pubkey, min_amount: u64, decimals: u8) -> Instruction {
+    build(
+        accounts::SetMinTradeAmount {
+            owner,
+            session: pda::session(&owner).0,
+            mint,
+        },
+        instruction::SetMinTradeAmount { min_amount, decimals },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: create the session's DailyStats account
+pub fn initialize_daily_stats(owner: Pubkey) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::InitializeDailyStats {
+            owner,
+            session,
+            daily_stats: pda::daily_stats(&session).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeDailyStats {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: create the session's ActionReceiptLog account
+pub fn initialize_receipt_log(owner: Pubkey) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::InitializeReceiptLog {
+            owner,
+            session,
+            receipt_log: pda::receipt_log(&session).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeReceiptLog {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Admin: allow a DLMM pool in the global PoolRegistry
+pub fn add_registry_pool(admin: Pubkey, lb_pair: Pubkey, risk_tier: u8) -> Instruction {
+    build(
+        accounts::AddRegistryPool {
+            admin,
+            config: pda::config().0,
+            pool_registry: pda::pool_registry().0,
+        },
+        instruction::AddRegistryPool { lb_pair, risk_tier },
+        vec![],
+    )
+}
+
+/// [Base Layer] Admin: override the magic program and context the commit paths
+/// target; `Pubkey::default()` for both restores the SDK's
+pub fn set_magic_programs(admin: Pubkey, magic_program: Pubkey, magic_context: Pubkey) -> Instruction {
+    build(
+        accounts::SetMagicPrograms {
+            admin,
+            config: pda::config().0,
+        },
+        instruction::SetMagicPrograms { magic_program, magic_context },
+        vec![],
+    )
+}
+
+/// [Base Layer] Admin: remove a DLMM pool from the global PoolRegistry
+pub fn remove_registry_pool(admin: Pubkey, lb_pair: Pubkey) -> Instruction {
+    build(
+        accounts::RemoveRegistryPool {
+            admin,
+            config: pda::config().0,
+            pool_registry: pda::pool_registry().0,
+        },
+        instruction::RemoveRegistryPool { lb_pair },
+        vec![],
+    )
+}
+
+/// [Base Layer / Ephemeral Rollup] Device liveness ping, signed by the session
+/// key; its signature fees are counted on the session
+pub fn device_heartbeat(session_key: Pubkey, owner: Pubkey) -> Instruction {
+    build(
+        accounts::DeviceHeartbeat {
+            session_key,
+            session: pda::session(&owner).0,
+            instructions_sysvar: Some(sysvar::instructions::ID),
+        },
+        instruction::DeviceHeartbeat {},
+        vec![],
+    )
+}
+
+/// [Base Layer / Ephemeral Rollup] Emit a numbered session snapshot, signed by
+/// the session key. The flags say which optional session accounts exist and
+/// should be included.
+pub fn snapshot_session(
+    session_key: Pubkey,
+    owner: Pubkey,
+    with_registry: bool,
+    with_monitor: bool,
+    with_stats: bool,
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::SnapshotSession {
+            session_key,
+            session,
+            position_registry: with_registry.then(|| pda::position_registry(&session).0),
+            monitor: with_monitor.then(|| pda::lp_monitor(&session).0),
+            daily_stats: with_stats.then(|| pda::daily_stats(&session).0),
+        },
+        instruction::SnapshotSession {},
+        vec![],
+    )
+}
+
+/// [Ephemeral Rollup] Record a strategy action, signed by the session key.
+/// `reason` is one of the program's `REASON_*` codes; `with_receipts` records
+/// a receipt in the session's ActionReceiptLog.
+pub fn execute_action(
+    session_key: Pubkey,
+    owner: Pubkey,
+    action_type: u8,
+    amount_lamports: u64,
+    fee_lamports: u64,
+    reason: u8,
+    with_receipts: bool,
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::ExecuteAction {
+            session_key,
+            session,
+            config: pda::config().0,
+            instructions_sysvar: sysvar::instructions::ID,
+            cosigner: None,
+            daily_stats: None,
+            receipt_log: with_receipts.then(|| pda::receipt_log(&session).0),
+        },
+        instruction::ExecuteAction {
+            action_type,
+            amount_lamports,
+            fee_lamports,
+            reason,
+        },
+        vec![],
+    )
+}
+
+/// [Ephemeral Rollup] Apply several strategy actions in one instruction, signed by
+/// the session key; the batch lands whole or not at all. `with_receipts` records a
+/// receipt of each action in the session's ActionReceiptLog.
+pub fn execute_actions_batch(
+    session_key: Pubkey,
+    owner: Pubkey,
+    actions: Vec<ActionSpec>,
+    fee_lamports: u64,
+    with_receipts: bool,
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::ExecuteActionsBatch {
+            session_key,
+            session,
+            config: pda::config().0,
+            instructions_sysvar: sysvar::instructions::ID,
+            cosigner: None,
+            daily_stats: None,
+            receipt_log: with_receipts.then(|| pda::receipt_log(&session).0),
+        },
+        instruction::ExecuteActionsBatch { actions, fee_lamports },
+        vec![],
+    )
+}
+
+/// SPL Memo instruction annotating the execute instruction it is sent with;
+/// the program ties `memo` to the action in an `ActionMemo` event
+pub fn action_memo(memo: &str) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![],
+        data: memo.as_bytes().to_vec(),
+    }
+}
+
+/// [Base Layer] Owner: require `device_key`'s execute calls to carry an intent
+/// signed by `intent_signer`; the default pubkey lifts the requirement
+pub fn set_intent_signer(owner: Pubkey, device_key: Pubkey, intent_signer: Pubkey) -> Instruction {
+    build(
+        accounts::SetIntentSigner {
+            owner,
+            session: pda::session(&owner).0,
+        },
+        instruction::SetIntentSigner { device_key, intent_signer },
+        vec![],
+    )
+}
+
+/// Canonical signed-intent payload (see `introspection::SignedIntent`):
+/// session | pool | amount | nonce | expires_at. `pool` is the default pubkey
+/// for actions that touch none.
+pub fn signed_intent_message(
+    session: &Pubkey,
+    pool: &Pubkey,
+    amount: u64,
+    nonce: u64,
+    expires_at: i64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(SignedIntent::LEN);
+    message.extend_from_slice(session.as_ref());
+    message.extend_from_slice(pool.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expires_at.to_le_bytes());
+    message
+}
+
+/// Ed25519-verify instruction for one `signature` by `signer` over `message`,
+/// every offset pointing into the instruction itself — the form the program's
+/// signed-intent check accepts. Send it alongside the execute instruction.
+pub fn ed25519_verify(signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    const PUBKEY_OFFSET: u16 = 2 + 14;
+    const SIGNATURE_OFFSET: u16 = PUBKEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+    let offsets = [
+        SIGNATURE_OFFSET,
+        u16::MAX, // signature in this instruction
+        PUBKEY_OFFSET,
+        u16::MAX, // public key in this instruction
+        MESSAGE_OFFSET,
+        message.len() as u16,
+        u16::MAX, // message in this instruction
+    ];
+    let mut data = vec![1, 0]; // one signature, padding
+    data.extend(offsets.iter().flat_map(|field| field.to_le_bytes()));
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ED25519_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// [Base Layer] Owner: pre-approve the swap `spec` describes; `owner` pays
+/// the intent's rent and escrows its keeper bounty
+pub fn create_intent(owner: Pubkey, spec: IntentSpec) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::CreateIntent {
+            owner,
+            session,
+            intent: pda::intent(&session, spec.intent_id).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateIntent {
+            intent_id: spec.intent_id,
+            lb_pair: spec.lb_pair,
+            swap_for_y: spec.swap_for_y,
+            max_amount_in: spec.max_amount_in,
+            min_amount_out: spec.min_amount_out,
+            deadline: spec.deadline,
+            keeper_fillable: spec.keeper_fillable,
+            keeper_bounty_lamports: spec.keeper_bounty_lamports,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: create every intent in `specs` in one instruction
+pub fn create_intents_batch(owner: Pubkey, specs: Vec<IntentSpec>) -> Instruction {
+    let session = pda::session(&owner).0;
+    let intents = specs
+        .iter()
+        .map(|spec| AccountMeta::new(pda::intent(&session, spec.intent_id).0, false))
+        .collect();
+    build(
+        accounts::CreateIntentsBatch {
+            owner,
+            session,
+            system_program: system_program::ID,
+        },
+        instruction::CreateIntentsBatch { specs },
+        intents,
+    )
+}
+
+/// [Base Layer] Discard an intent and refund its rent to `owner`
+pub fn cancel_intent(owner: Pubkey, intent_id: u64) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::CancelIntent {
+            owner,
+            session,
+            intent: pda::intent(&session, intent_id).0,
+        },
+        instruction::CancelIntent {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Owner: rewrite the session's borsh-era LpPositionMonitor and/or
+/// PositionRegistry in the zero-copy layout
+pub fn migrate_lp_accounts(owner: Pubkey, monitor: bool, registry: bool) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::MigrateLpAccounts {
+            owner,
+            session,
+            lp_monitor: monitor.then(|| pda::lp_monitor(&session).0),
+            position_registry: registry.then(|| pda::position_registry(&session).0),
+            system_program: system_program::ID,
+        },
+        instruction::MigrateLpAccounts {},
+        vec![],
+    )
+}
+
+/// [Base Layer] Create the LpPositionMonitor PDA for `owner`'s session
+pub fn register_lp_monitor(
+    owner: Pubkey,
+    lb_pair: Pubkey,
+    position: Pubkey,
+    min_bin_id: i32,
+    max_bin_id: i32,
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::RegisterLpMonitor {
+            owner,
+            session,
+            monitor: pda::lp_monitor(&session).0,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterLpMonitor {
+            lb_pair,
+            position,
+            min_bin_id,
+            max_bin_id,
+        },
+        vec![],
+    )
+}
+
+/// [Ephemeral Rollup] Checkpoint accrued fees and position size from the
+/// device; the program reads the active bin from `lb_pair`
+#[allow(clippy::too_many_arguments)]
+pub fn update_lp_status(
+    session_key: Pubkey,
+    owner: Pubkey,
+    lb_pair: Pubkey,
+    position: Pubkey,
+    fee_x: u64,
+    fee_y: u64,
+    amount_x: u64,
+    amount_y: u64,
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::UpdateLpStatus {
+            session_key,
+            session,
+            monitor: pda::lp_monitor(&session).0,
+            position,
+            lb_pair,
+            instructions_sysvar: Some(sysvar::instructions::ID),
+        },
+        instruction::UpdateLpStatus {
+            fee_x,
+            fee_y,
+            amount_x,
+            amount_y,
+        },
+        vec![],
+    )
+}
+
+/// The routine `execute_dlmm_swap` account set, shared with `fulfill_intent`
+fn swap_accounts(
+    session_key: Pubkey,
+    owner: Pubkey,
+    pool: &DlmmSwapPool,
+    user_token_in: Pubkey,
+    user_token_out: Pubkey,
+) -> accounts::ExecuteDlmmSwap {
+    accounts::ExecuteDlmmSwap {
+        session_key,
+        session: pda::session(&owner).0,
+        config: pda::config().0,
+        pool_registry: None,
+        lb_pair: pool.lb_pair,
+        bin_array_bitmap_extension: pool.bin_array_bitmap_extension,
+        reserve_x: pool.reserve_x,
+        reserve_y: pool.reserve_y,
+        user_token_in,
+        user_token_out,
+        token_x_mint: pool.token_x_mint,
+        token_y_mint: pool.token_y_mint,
+        oracle: pool.oracle,
+        dlmm_program: dlmm::DLMM_PROGRAM_ID,
+        event_authority: dlmm::DLMM_EVENT_AUTHORITY,
+        token_x_program: pool.token_x_program,
+        token_y_program: pool.token_y_program,
+        treasury: None,
+        treasury_ledger: None,
+        referrer_token: None,
+        fee_vault: pda::fee_vault().0,
+        system_program: system_program::ID,
+        instructions_sysvar: sysvar::instructions::ID,
+        cosigner: None,
+        action_request: None,
+        daily_stats: None,
+        budget_price_pool: None,
+    }
+}
+
+/// Pool-side accounts for a DLMM swap — read from the LbPair account
+pub struct DlmmSwapPool {
+    pub lb_pair: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub token_x_program: Pubkey,
+    pub token_y_program: Pubkey,
+    pub oracle: Pubkey,
+    /// Pass when the pool uses the extended bin-array bitmap
+    pub bin_array_bitmap_extension: Option<Pubkey>,
+}
+
+/// [Base Layer] Swap through a Meteora DLMM pool, signed by the session key.
+///
+/// Covers the routine path: no registry, treasury, co-signer or ActionRequest.
+/// Use [`build`] with `accounts::ExecuteDlmmSwap` directly for the rest.
+/// `active_bin_band` is the inclusive `(min, max)` active bin the swap may
+/// execute at; `None` leaves it unbounded.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_dlmm_swap(
+    session_key: Pubkey,
+    owner: Pubkey,
+    pool: &DlmmSwapPool,
+    user_token_in: Pubkey,
+    user_token_out: Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    fee_lamports: u64,
+    active_bin_band: Option<(i32, i32)>,
+    bin_arrays: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        swap_accounts(session_key, owner, pool, user_token_in, user_token_out),
+        instruction::ExecuteDlmmSwap {
+            amount_in,
+            min_amount_out,
+            fee_lamports,
+            min_active_bin: active_bin_band.map(|(min, _)| min),
+            max_active_bin: active_bin_band.map(|(_, max)| max),
+        },
+        bin_arrays,
+    )
+}
+
+/// [Base Layer] Fill `amount_in` of intent `intent_id` through the
+/// `execute_dlmm_swap` path, signed by the session key
+#[allow(clippy::too_many_arguments)]
+pub fn fulfill_intent(
+    session_key: Pubkey,
+    owner: Pubkey,
+    intent_id: u64,
+    pool: &DlmmSwapPool,
+    user_token_in: Pubkey,
+    user_token_out: Pubkey,
+    amount_in: u64,
+    fee_lamports: u64,
+    bin_arrays: Vec<AccountMeta>,
+) -> Instruction {
+    build(
+        accounts::FulfillIntent {
+            swap: swap_accounts(session_key, owner, pool, user_token_in, user_token_out),
+            intent: pda::intent(&pda::session(&owner).0, intent_id).0,
+            owner,
+        },
+        instruction::FulfillIntent { amount_in, fee_lamports },
+        bin_arrays,
+    )
+}
+
+/// [Base Layer] Open a DLMM position over `[lower_bin_id, lower_bin_id + width - 1]`,
+/// signed by the session key and the fresh `position` keypair.
+///
+/// With `register_monitor`, the session's LpPositionMonitor is created for the
+/// new position in the same instruction (fails if one already exists).
+/// Pass the pair's `pair_pools` list once the owner has ranked any pools.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_dlmm_create_position(
+    session_key: Pubkey,
+    owner: Pubkey,
+    position: Pubkey,
+    lb_pair: Pubkey,
+    lower_bin_id: i32,
+    width: i32,
+    fee_lamports: u64,
+    register_monitor: bool,
+    pair_pools: Option<Pubkey>,
+) -> Instruction {
+    let session = pda::session(&owner).0;
+    build(
+        accounts::ExecuteDlmmCreatePosition {
+            session_key,
+            session,
+            config: pda::config().0,
+            pool_registry: None,
+            position_registry: pda::position_registry(&session).0,
+            monitor: register_monitor.then(|| pda::lp_monitor(&session).0),
+            position,
+            lb_pair,
+            dlmm_program: dlmm::DLMM_PROGRAM_ID,
+            event_authority: dlmm::DLMM_EVENT_AUTHORITY,
+            fee_vault: pda::fee_vault().0,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            daily_stats: None,
+            pair_pools,
+        },
+        instruction::ExecuteDlmmCreatePosition {
+            lower_bin_id,
+            width,
+            fee_lamports,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Deposit into a position with a DLMM strategy, signed by the
+/// session key. `pool` supplies the same pool-side accounts as a swap.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_dlmm_add_liquidity(
+    session_key: Pubkey,
+    owner: Pubkey,
+    pool: &DlmmSwapPool,
+    position: Pubkey,
+    user_token_x: Pubkey,
+    user_token_y: Pubkey,
+    liquidity_parameter: LiquidityParameterByStrategy,
+    fee_lamports: u64,
+) -> Instruction {
+    let strategy = &liquidity_parameter.strategy_parameters;
+    let bin_array_lower = dlmm::bin_array(&pool.lb_pair, dlmm::bin_id_to_bin_array_index(strategy.min_bin_id)).0;
+    let bin_array_upper = dlmm::bin_array(&pool.lb_pair, dlmm::bin_id_to_bin_array_index(strategy.max_bin_id)).0;
+    let session = pda::session(&owner).0;
+    build(
+        accounts::ExecuteDlmmAddLiquidity {
+            session_key,
+            session,
+            config: pda::config().0,
+            pool_registry: None,
+            position,
+            lb_pair: pool.lb_pair,
+            bin_array_bitmap_extension: pool.bin_array_bitmap_extension,
+            user_token_x,
+            user_token_y,
+            reserve_x: pool.reserve_x,
+            reserve_y: pool.reserve_y,
+            token_x_mint: pool.token_x_mint,
+            token_y_mint: pool.token_y_mint,
+            bin_array_lower,
+            bin_array_upper,
+            dlmm_program: dlmm::DLMM_PROGRAM_ID,
+            event_authority: dlmm::DLMM_EVENT_AUTHORITY,
+            token_x_program: pool.token_x_program,
+            token_y_program: pool.token_y_program,
+            fee_vault: pda::fee_vault().0,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            cosigner: None,
+            action_request: None,
+            position_registry: Some(pda::position_registry(&session).0),
+            daily_stats: None,
+            budget_price_pool: None,
+        },
+        instruction::ExecuteDlmmAddLiquidity {
+            liquidity_parameter,
+            fee_lamports,
+        },
+        vec![],
+    )
+}
+
+/// [Base Layer] Close `position` (currently over `current_range`) and reopen
+/// it as `new_position`, `extra_bins_each_side` wider on each side and
+/// centered on `active_id`. The program derives the same range from the
+/// accounts; it is computed here only to pick the bin arrays, so pass the
+/// pool's current active bin. Pass `track_monitor` when the session's
+/// LpPositionMonitor tracks `position`.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_dlmm_widen_range(
+    session_key: Pubkey,
+    owner: Pubkey,
+    pool: &DlmmSwapPool,
+    position: Pubkey,
+    new_position: Pubkey,
+    user_token_x: Pubkey,
+    user_token_y: Pubkey,
+    current_range: (i32, i32),
+    active_id: i32,
+    extra_bins_each_side: u16,
+    liquidity_parameter: LiquidityParameterByStrategy,
+    fee_lamports: u64,
+    reason: u8,
+    track_monitor: bool,
+) -> Instruction {
+    let (min_bin_id, max_bin_id) = current_range;
+    let (lower_bin_id, width) =
+        defi_agent::reposition::widened_range(min_bin_id, max_bin_id, active_id, extra_bins_each_side)
+            .expect("bin range overflow");
+    let bin_array = |bin_id| dlmm::bin_array(&pool.lb_pair, dlmm::bin_id_to_bin_array_index(bin_id)).0;
+    let session = pda::session(&owner).0;
+    build(
+        accounts::ExecuteDlmmWidenRange {
+            session_key,
+            session,
+            config: pda::config().0,
+            pool_registry: None,
+            user_token_x,
+            user_token_y,
+            token_x_mint: pool.token_x_mint,
+            token_y_mint: pool.token_y_mint,
+            position,
+            lb_pair: pool.lb_pair,
+            bin_array_bitmap_extension: pool.bin_array_bitmap_extension,
+            reserve_x: pool.reserve_x,
+            reserve_y: pool.reserve_y,
+            bin_array_lower: bin_array(min_bin_id),
+            bin_array_upper: bin_array(max_bin_id),
+            new_position,
+            new_bin_array_lower: bin_array(lower_bin_id),
+            new_bin_array_upper: bin_array(lower_bin_id + width - 1),
+            dlmm_program: dlmm::DLMM_PROGRAM_ID,
+            event_authority: dlmm::DLMM_EVENT_AUTHORITY,
+            token_x_program: pool.token_x_program,
+            token_y_program: pool.token_y_program,
+            fee_vault: pda::fee_vault().0,
+            system_program: system_program::ID,
+            instructions_sysvar: sysvar::instructions::ID,
+            position_registry: Some(pda::position_registry(&session).0),
+            monitor: track_monitor.then(|| pda::lp_monitor(&session).0),
+            daily_stats: None,
+        },
+        instruction::ExecuteDlmmWidenRange {
+            extra_bins_each_side,
+            liquidity_parameter,
+            fee_lamports,
+            reason,
+        },
+        vec![],
+    )
+}
@@ -0,0 +1,20 @@
+//! Native Rust client for the `defi-agent` program.
+//!
+//! Mirrors what the TypeScript agent gets from the Anchor IDL so Rust bots,
+//! keepers and the ESP32 firmware toolchain can talk to the program without JS:
+//!
+//! - [`pda`] — PDA derivation for every program-owned account
+//! - [`dlmm`] — Meteora DLMM bin-array / position helpers
+//! - [`accounts`] — decoders for program accounts and DLMM pools / positions
+//! - [`instructions`] — typed instruction builders
+//! - [`rebalance`] — ordered wrap / swap / create / add / monitor bundle for an LP rebalance
+
+pub mod accounts;
+pub mod dlmm;
+pub mod instructions;
+pub mod pda;
+pub mod rebalance;
+
+pub use defi_agent::ID as PROGRAM_ID;
+pub use ephemeral_rollups_sdk::consts::DELEGATION_PROGRAM_ID;
+pub use defi_agent::state::{AgentSession, LpPositionMonitor};
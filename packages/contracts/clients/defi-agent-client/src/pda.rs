@@ -0,0 +1,97 @@
+use anchor_lang::prelude::Pubkey;
+
+use crate::PROGRAM_ID;
+
+/// AgentSession PDA — `[b"session", owner]`
+pub fn session(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"session", owner.as_ref()], &PROGRAM_ID)
+}
+
+/// Global Config PDA — `[b"config"]`
+pub fn config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID)
+}
+
+/// Global PoolRegistry PDA — `[b"pool_registry"]`
+pub fn pool_registry() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pool_registry"], &PROGRAM_ID)
+}
+
+/// Protocol fee vault (system-owned PDA) — `[b"fee_vault"]`
+pub fn fee_vault() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_vault"], &PROGRAM_ID)
+}
+
+/// Per-mint treasury token account — `[b"treasury", mint]`
+pub fn treasury(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury", mint.as_ref()], &PROGRAM_ID)
+}
+
+/// Per-mint fee ledger — `[b"treasury_ledger", mint]`
+pub fn treasury_ledger(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury_ledger", mint.as_ref()], &PROGRAM_ID)
+}
+
+/// Timelocked treasury withdrawal — `[b"pending_withdrawal", asset]`
+pub fn pending_withdrawal(asset: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pending_withdrawal", asset.as_ref()], &PROGRAM_ID)
+}
+
+/// SessionLookup PDA — `[b"session_lookup", device_key]`
+pub fn session_lookup(device_key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"session_lookup", device_key.as_ref()], &PROGRAM_ID)
+}
+
+/// FeeSponsor PDA — `[b"fee_sponsor", session]`
+pub fn fee_sponsor(session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fee_sponsor", session.as_ref()], &PROGRAM_ID)
+}
+
+/// LpPositionMonitor PDA — `[b"lp_monitor", session]`
+pub fn lp_monitor(session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lp_monitor", session.as_ref()], &PROGRAM_ID)
+}
+
+/// PositionRegistry PDA — `[b"position_registry", session]`
+pub fn position_registry(session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"position_registry", session.as_ref()], &PROGRAM_ID)
+}
+
+/// DailyStats PDA — `[b"daily_stats", session]`
+pub fn daily_stats(session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"daily_stats", session.as_ref()], &PROGRAM_ID)
+}
+
+/// ActionReceiptLog PDA — `[b"receipts", session]`
+pub fn receipt_log(session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipts", session.as_ref()], &PROGRAM_ID)
+}
+
+/// CompressedMonitorTree PDA — `[b"monitor_tree", session]`
+pub fn monitor_tree(session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"monitor_tree", session.as_ref()], &PROGRAM_ID)
+}
+
+/// PairPools PDA — `[b"pair_pools", session, token_x_mint, token_y_mint]`
+pub fn pair_pools(session: &Pubkey, token_x_mint: &Pubkey, token_y_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"pair_pools", session.as_ref(), token_x_mint.as_ref(), token_y_mint.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Intent PDA — `[b"intent", session, intent_id_le]`
+pub fn intent(session: &Pubkey, intent_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"intent", session.as_ref(), &intent_id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+/// ActionRequest PDA — `[b"action_request", session, request_id_le]`
+pub fn action_request(session: &Pubkey, request_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"action_request", session.as_ref(), &request_id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
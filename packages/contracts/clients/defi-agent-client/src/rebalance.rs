@@ -0,0 +1,340 @@
+//! LP rebalance bundle: everything a session key signs to move its liquidity
+//! into a new bin range, in execution order.
+//!
+//! 1. wrap      — native SOL into the device's WSOL account (native-mint pools only)
+//! 2. bin arrays — `initialize_bin_array` for any array the new range needs that doesn't exist
+//! 3. swap      — rebalance token X / Y to the ratio the new range holds at the active bin
+//! 4. create    — `execute_dlmm_create_position` (auto-registers the monitor when none exists,
+//!                passes the pair's ranked pool list when one exists)
+//! 5. add       — `execute_dlmm_add_liquidity` with `SpotBalanced`
+//! 6. monitor   — `update_lp_status` checkpoint at the current active bin (new monitor only)
+//!
+//! Closing the old position is left to the caller: do it first, so its monitor
+//! is closed and step 4 registers the new one. The bundle usually exceeds one
+//! legacy transaction — split it after the swap, or compile it against the
+//! session's address lookup table.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_instruction, system_program};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, spl_associated_token_account};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+
+use defi_agent::dlmm::accounts::LbPair;
+use defi_agent::dlmm::client::{accounts as dlmm_accounts, args as dlmm_args};
+use defi_agent::dlmm::types::{LiquidityParameterByStrategy, StrategyParameters, StrategyType};
+use defi_agent::errors::AgentError;
+
+use crate::accounts::{decode_lb_pair, decode_session};
+use crate::dlmm::{self, DLMM_PROGRAM_ID};
+use crate::instructions::{self, DlmmSwapPool};
+use crate::pda;
+
+/// Bin arrays passed to the swap, walking from the active bin
+const SWAP_BIN_ARRAYS: usize = 3;
+
+/// Bins the active bin may move between planning and execution before the
+/// swap and deposit refuse to run
+const MAX_ACTIVE_BIN_DRIFT: i32 = 3;
+
+/// Inputs for [`build_rebalance`]
+pub struct RebalanceParams {
+    /// Session owner (derives the session PDA)
+    pub owner: Pubkey,
+    /// Device key that signs and pays for the bundle
+    pub session_key: Pubkey,
+    pub lb_pair: Pubkey,
+    /// Fresh keypair for the new position — must co-sign the create step
+    pub position: Pubkey,
+    /// Target range `[lower_bin_id, lower_bin_id + width - 1]`
+    pub lower_bin_id: i32,
+    pub width: i32,
+    /// Token X / Y to deploy, in raw units, already held by the device
+    /// (excluding `wrap_lamports`)
+    pub amount_x: u64,
+    pub amount_y: u64,
+    /// Native SOL to wrap first and deploy on the native-mint side; 0 = none
+    pub wrap_lamports: u64,
+    /// Swap slippage tolerance; the add step deposits the post-slippage minimum
+    pub slippage_bps: u16,
+    /// Declared priority fee per program instruction (see `verify_declared_fee`)
+    pub fee_lamports: u64,
+}
+
+/// The swap [`build_rebalance`] sized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapPlan {
+    pub swap_for_y: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+pub struct RebalanceBundle {
+    /// Ordered instructions, ready to sign by `session_key` (+ `position`)
+    pub instructions: Vec<Instruction>,
+    pub swap: Option<SwapPlan>,
+    /// Amounts deposited by the add step
+    pub deposit_x: u64,
+    pub deposit_y: u64,
+    pub active_id: i32,
+}
+
+/// Fraction of the range's value held as token Y with `SpotBalanced` at
+/// `active_id`: bins below the active bin are all Y, bins above all X, and
+/// the active bin is split evenly.
+fn y_share(lower_bin_id: i32, upper_bin_id: i32, active_id: i32) -> f64 {
+    if active_id < lower_bin_id {
+        return 0.0;
+    }
+    if active_id > upper_bin_id {
+        return 1.0;
+    }
+    let width = (upper_bin_id - lower_bin_id + 1) as f64;
+    ((active_id - lower_bin_id) as f64 + 0.5) / width
+}
+
+fn apply_slippage(amount: f64, slippage_bps: u16) -> u64 {
+    (amount * (10_000 - slippage_bps.min(10_000)) as f64 / 10_000.0) as u64
+}
+
+/// Size the X/Y swap that brings `(amount_x, amount_y)` to the ratio the
+/// target range holds at `active_id`. `None` when already balanced.
+pub fn size_swap(
+    amount_x: u64,
+    amount_y: u64,
+    lower_bin_id: i32,
+    upper_bin_id: i32,
+    active_id: i32,
+    bin_step: u16,
+    slippage_bps: u16,
+) -> Option<SwapPlan> {
+    let price = dlmm::bin_price(active_id, bin_step);
+    let value_y = amount_x as f64 * price + amount_y as f64;
+    let target_y = value_y * y_share(lower_bin_id, upper_bin_id, active_id);
+
+    let plan = if (amount_y as f64) < target_y {
+        let out_y = target_y - amount_y as f64;
+        SwapPlan {
+            swap_for_y: true,
+            amount_in: ((out_y / price) as u64).min(amount_x),
+            min_amount_out: apply_slippage(out_y, slippage_bps),
+        }
+    } else {
+        let in_y = amount_y as f64 - target_y;
+        SwapPlan {
+            swap_for_y: false,
+            amount_in: (in_y as u64).min(amount_y),
+            min_amount_out: apply_slippage(in_y / price, slippage_bps),
+        }
+    };
+    (plan.amount_in > 0 && plan.min_amount_out > 0).then_some(plan)
+}
+
+fn token_program(flag: u8) -> Pubkey {
+    match flag {
+        1 => spl_token_2022::ID,
+        _ => spl_token::ID,
+    }
+}
+
+fn dlmm_ix(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: DLMM_PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// Build the rebalance bundle. `fetch` returns raw account data, or `None`
+/// if the account doesn't exist — pass an RPC `get_account` wrapper.
+pub fn build_rebalance(
+    mut fetch: impl FnMut(&Pubkey) -> Option<Vec<u8>>,
+    params: &RebalanceParams,
+) -> Result<RebalanceBundle> {
+    require!(params.width > 0, AgentError::InvalidBinRange);
+    let lower_bin_id = params.lower_bin_id;
+    let upper_bin_id = lower_bin_id
+        .checked_add(params.width - 1)
+        .ok_or(AgentError::InvalidBinRange)?;
+
+    let lb_pair: LbPair = decode_lb_pair(
+        &fetch(&params.lb_pair).ok_or(error!(ErrorCode::AccountNotInitialized))?,
+    )?;
+    let active_id = lb_pair.active_id;
+
+    // The add step deposits `SpotBalanced`; refuse up front if the owner's
+    // liquidity shapes exclude it rather than failing mid-bundle
+    let session = pda::session(&params.owner).0;
+    if let Some(data) = fetch(&session) {
+        decode_session(&data)?.check_liquidity_shape(&StrategyType::SpotBalanced)?;
+    }
+
+    let lower_index = dlmm::bin_id_to_bin_array_index(lower_bin_id);
+    let upper_index = dlmm::bin_id_to_bin_array_index(upper_bin_id);
+    let active_index = dlmm::bin_id_to_bin_array_index(active_id);
+    let uses_extension = [lower_index, upper_index, active_index]
+        .into_iter()
+        .any(dlmm::needs_bitmap_extension);
+
+    let pool = DlmmSwapPool {
+        lb_pair: params.lb_pair,
+        reserve_x: lb_pair.reserve_x,
+        reserve_y: lb_pair.reserve_y,
+        token_x_mint: lb_pair.token_x_mint,
+        token_y_mint: lb_pair.token_y_mint,
+        token_x_program: token_program(lb_pair.token_mint_x_program_flag),
+        token_y_program: token_program(lb_pair.token_mint_y_program_flag),
+        oracle: lb_pair.oracle,
+        bin_array_bitmap_extension: uses_extension
+            .then(|| dlmm::bin_array_bitmap_extension(&params.lb_pair).0),
+    };
+    let user_token_x =
+        get_associated_token_address_with_program_id(&params.session_key, &pool.token_x_mint, &pool.token_x_program);
+    let user_token_y =
+        get_associated_token_address_with_program_id(&params.session_key, &pool.token_y_mint, &pool.token_y_program);
+
+    let mut ixs = Vec::new();
+    let (mut amount_x, mut amount_y) = (params.amount_x, params.amount_y);
+
+    // ── 1. Wrap ─────────────────────────────────────────────────────────────
+    if params.wrap_lamports > 0 {
+        let (wsol, amount) = if pool.token_x_mint == spl_token::native_mint::ID {
+            (user_token_x, &mut amount_x)
+        } else if pool.token_y_mint == spl_token::native_mint::ID {
+            (user_token_y, &mut amount_y)
+        } else {
+            return err!(ErrorCode::ConstraintTokenMint);
+        };
+        ixs.push(
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &params.session_key,
+                &params.session_key,
+                &spl_token::native_mint::ID,
+                &spl_token::ID,
+            ),
+        );
+        ixs.push(system_instruction::transfer(&params.session_key, &wsol, params.wrap_lamports));
+        ixs.push(spl_token::instruction::sync_native(&spl_token::ID, &wsol)?);
+        *amount = amount.checked_add(params.wrap_lamports).ok_or(AgentError::Overflow)?;
+    }
+
+    // ── 2. Bin arrays ───────────────────────────────────────────────────────
+    for index in lower_index..=upper_index {
+        let bin_array = dlmm::bin_array(&params.lb_pair, index).0;
+        if fetch(&bin_array).is_none() {
+            ixs.push(dlmm_ix(
+                dlmm_accounts::InitializeBinArray {
+                    lb_pair: params.lb_pair,
+                    bin_array,
+                    funder: params.session_key,
+                    system_program: system_program::ID,
+                },
+                dlmm_args::InitializeBinArray { index },
+            ));
+        }
+    }
+
+    // ── 3. Swap ─────────────────────────────────────────────────────────────
+    let swap = size_swap(
+        amount_x,
+        amount_y,
+        lower_bin_id,
+        upper_bin_id,
+        active_id,
+        lb_pair.bin_step,
+        params.slippage_bps,
+    );
+    if let Some(plan) = swap {
+        let (user_token_in, user_token_out) = if plan.swap_for_y {
+            (user_token_x, user_token_y)
+        } else {
+            (user_token_y, user_token_x)
+        };
+        ixs.push(instructions::execute_dlmm_swap(
+            params.session_key,
+            params.owner,
+            &pool,
+            user_token_in,
+            user_token_out,
+            plan.amount_in,
+            plan.min_amount_out,
+            params.fee_lamports,
+            Some((active_id - MAX_ACTIVE_BIN_DRIFT, active_id + MAX_ACTIVE_BIN_DRIFT)),
+            dlmm::swap_bin_array_metas(&params.lb_pair, active_id, plan.swap_for_y, SWAP_BIN_ARRAYS),
+        ));
+        // Deposit only what the swap is guaranteed to return
+        if plan.swap_for_y {
+            amount_x -= plan.amount_in;
+            amount_y = amount_y.checked_add(plan.min_amount_out).ok_or(AgentError::Overflow)?;
+        } else {
+            amount_y -= plan.amount_in;
+            amount_x = amount_x.checked_add(plan.min_amount_out).ok_or(AgentError::Overflow)?;
+        }
+    }
+
+    // ── 4. Create ───────────────────────────────────────────────────────────
+    let monitor = pda::lp_monitor(&session).0;
+    let register_monitor = fetch(&monitor).is_none();
+    let pair_pools = pda::pair_pools(&session, &pool.token_x_mint, &pool.token_y_mint).0;
+    ixs.push(instructions::execute_dlmm_create_position(
+        params.session_key,
+        params.owner,
+        params.position,
+        params.lb_pair,
+        lower_bin_id,
+        params.width,
+        params.fee_lamports,
+        register_monitor,
+        fetch(&pair_pools).is_some().then_some(pair_pools),
+    ));
+
+    // ── 5. Add liquidity ────────────────────────────────────────────────────
+    ixs.push(instructions::execute_dlmm_add_liquidity(
+        params.session_key,
+        params.owner,
+        &pool,
+        params.position,
+        user_token_x,
+        user_token_y,
+        LiquidityParameterByStrategy {
+            amount_x,
+            amount_y,
+            active_id,
+            max_active_bin_slippage: MAX_ACTIVE_BIN_DRIFT,
+            strategy_parameters: StrategyParameters {
+                min_bin_id: lower_bin_id,
+                max_bin_id: upper_bin_id,
+                strategy_type: StrategyType::SpotBalanced,
+                parameteres: [0; 64],
+            },
+        },
+        params.fee_lamports,
+    ));
+
+    // ── 6. Monitor checkpoint ───────────────────────────────────────────────
+    // Only when step 4 registered the monitor — an existing one tracks another
+    // position and would reject the checkpoint. Seeded with the deposit, which
+    // later closes are checked against until the device's next checkpoint
+    if register_monitor {
+        ixs.push(instructions::update_lp_status(
+            params.session_key,
+            params.owner,
+            params.lb_pair,
+            params.position,
+            0,
+            0,
+            amount_x,
+            amount_y,
+        ));
+    }
+
+    Ok(RebalanceBundle {
+        instructions: ixs,
+        swap,
+        deposit_x: amount_x,
+        deposit_y: amount_y,
+        active_id,
+    })
+}
@@ -0,0 +1,136 @@
+//! Instruction-data encoders for the instructions the device signs.
+//!
+//! Anchor discriminators are precomputed (`sha256("global:<name>")[..8]`) so
+//! the firmware does not hash at runtime; arguments are Borsh little-endian.
+
+use crate::tx::AccountMeta;
+use crate::{Pubkey, INSTRUCTIONS_SYSVAR_ID};
+
+pub const UPDATE_LP_STATUS: [u8; 8] = [118, 6, 101, 4, 26, 22, 173, 152];
+pub const DEVICE_HEARTBEAT: [u8; 8] = [97, 255, 245, 89, 70, 217, 249, 69];
+pub const EXECUTE_ACTION: [u8; 8] = [246, 137, 105, 113, 247, 6, 223, 174];
+pub const EXECUTE_DLMM_SWAP: [u8; 8] = [237, 238, 88, 142, 34, 93, 42, 199];
+pub const FULFILL_INTENT: [u8; 8] = [236, 191, 7, 151, 169, 132, 84, 160];
+
+fn encode<const N: usize>(discriminator: [u8; 8], args: &[&[u8]]) -> [u8; N] {
+    let mut out = [0u8; N];
+    out[..8].copy_from_slice(&discriminator);
+    let mut at = 8;
+    for arg in args {
+        out[at..at + arg.len()].copy_from_slice(arg);
+        at += arg.len();
+    }
+    debug_assert_eq!(at, N);
+    out
+}
+
+/// `update_lp_status(fee_x: u64, fee_y: u64, amount_x: u64, amount_y: u64)` —
+/// the program reads the active bin from the lb_pair account
+pub fn update_lp_status(fee_x: u64, fee_y: u64, amount_x: u64, amount_y: u64) -> [u8; 40] {
+    encode(
+        UPDATE_LP_STATUS,
+        &[
+            &fee_x.to_le_bytes(),
+            &fee_y.to_le_bytes(),
+            &amount_x.to_le_bytes(),
+            &amount_y.to_le_bytes(),
+        ],
+    )
+}
+
+/// `device_heartbeat()`
+pub fn device_heartbeat() -> [u8; 8] {
+    DEVICE_HEARTBEAT
+}
+
+/// `execute_action(action_type: u8, amount_lamports: u64, fee_lamports: u64, reason: u8)`
+pub fn execute_action(action_type: u8, amount_lamports: u64, fee_lamports: u64, reason: u8) -> [u8; 26] {
+    encode(
+        EXECUTE_ACTION,
+        &[&[action_type], &amount_lamports.to_le_bytes(), &fee_lamports.to_le_bytes(), &[reason]],
+    )
+}
+
+/// `execute_dlmm_swap(amount_in: u64, min_amount_out: u64, fee_lamports: u64,
+/// min_active_bin: Option<i32>, max_active_bin: Option<i32>)`, always sending
+/// both bounds — pass `i32::MIN` / `i32::MAX` to leave a side open
+pub fn execute_dlmm_swap(
+    amount_in: u64,
+    min_amount_out: u64,
+    fee_lamports: u64,
+    min_active_bin: i32,
+    max_active_bin: i32,
+) -> [u8; 42] {
+    encode(
+        EXECUTE_DLMM_SWAP,
+        &[
+            &amount_in.to_le_bytes(),
+            &min_amount_out.to_le_bytes(),
+            &fee_lamports.to_le_bytes(),
+            &[1],
+            &min_active_bin.to_le_bytes(),
+            &[1],
+            &max_active_bin.to_le_bytes(),
+        ],
+    )
+}
+
+/// `fulfill_intent(amount_in: u64, fee_lamports: u64)`
+pub fn fulfill_intent(amount_in: u64, fee_lamports: u64) -> [u8; 24] {
+    encode(FULFILL_INTENT, &[&amount_in.to_le_bytes(), &fee_lamports.to_le_bytes()])
+}
+
+// ── Account lists (same order as the program's Accounts structs) ────────────
+
+/// Accounts for `update_lp_status` — `position` / `lb_pair` must be the monitor's
+pub fn update_lp_status_accounts(
+    session_key: Pubkey,
+    session: Pubkey,
+    monitor: Pubkey,
+    position: Pubkey,
+    lb_pair: Pubkey,
+) -> [AccountMeta; 5] {
+    [
+        AccountMeta::readonly_signer(session_key),
+        AccountMeta::writable(session),
+        AccountMeta::writable(monitor),
+        AccountMeta::readonly(position),
+        AccountMeta::readonly(lb_pair),
+    ]
+}
+
+/// Accounts for `device_heartbeat`
+pub fn device_heartbeat_accounts(session_key: Pubkey, session: Pubkey) -> [AccountMeta; 2] {
+    [AccountMeta::readonly_signer(session_key), AccountMeta::writable(session)]
+}
+
+/// Accounts for `execute_action` without a co-signer — the optional slot is
+/// filled with the program id, as Anchor expects for `None`
+pub fn execute_action_accounts(session_key: Pubkey, session: Pubkey, config: Pubkey) -> [AccountMeta; 5] {
+    [
+        AccountMeta::readonly_signer(session_key),
+        AccountMeta::writable(session),
+        AccountMeta::readonly(config),
+        AccountMeta::readonly(INSTRUCTIONS_SYSVAR_ID),
+        AccountMeta::readonly(crate::PROGRAM_ID),
+    ]
+}
+
+/// Accounts for `execute_action` without a co-signer or daily stats,
+/// recording a receipt in the session's ActionReceiptLog (`pda::receipt_log`)
+pub fn execute_action_receipted_accounts(
+    session_key: Pubkey,
+    session: Pubkey,
+    config: Pubkey,
+    receipt_log: Pubkey,
+) -> [AccountMeta; 7] {
+    [
+        AccountMeta::readonly_signer(session_key),
+        AccountMeta::writable(session),
+        AccountMeta::readonly(config),
+        AccountMeta::readonly(INSTRUCTIONS_SYSVAR_ID),
+        AccountMeta::readonly(crate::PROGRAM_ID),
+        AccountMeta::readonly(crate::PROGRAM_ID),
+        AccountMeta::writable(receipt_log),
+    ]
+}
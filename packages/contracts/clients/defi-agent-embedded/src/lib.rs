@@ -0,0 +1,54 @@
+//! no_std transaction construction for the defi-agent device firmware.
+//!
+//! Lets the ESP32 build and sign its own transactions instead of asking an
+//! external service to do it. Everything works on caller-provided buffers —
+//! no allocator, fixed-capacity tables sized for device RAM:
+//!
+//! - [`ix`] — instruction-data encoders for the device-signed instructions
+//! - [`pda`] — program-derived address derivation
+//! - [`tx`] — v0 message compilation (with optional lookup table) and signing
+//!   through the [`tx::TxSigner`] hook
+
+#![no_std]
+
+pub mod ix;
+pub mod pda;
+pub mod tx;
+
+/// A Solana public key as raw bytes
+pub type Pubkey = [u8; 32];
+
+/// defi-agent program id (8reNvTG6PLT4sf4nGbT7VjZ1YqEGXzASkjcSQmQTkJPT)
+pub const PROGRAM_ID: Pubkey = [
+    116, 185, 136, 17, 206, 14, 107, 137, 238, 26, 227, 159, 33, 79, 38, 71,
+    163, 78, 200, 36, 168, 116, 77, 121, 57, 95, 208, 61, 219, 224, 169, 178,
+];
+
+/// Instructions sysvar (Sysvar1nstructions1111111111111111111111111)
+pub const INSTRUCTIONS_SYSVAR_ID: Pubkey = [
+    6, 167, 213, 23, 24, 123, 209, 102, 53, 218, 212, 4, 85, 253, 194, 192,
+    193, 36, 198, 143, 33, 86, 117, 165, 219, 186, 203, 95, 8, 0, 0, 0,
+];
+
+/// SPL Memo program v2 (MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr) — a memo
+/// instruction (no accounts, UTF-8 text as data) sent with an execute
+/// instruction annotates the action
+pub const MEMO_PROGRAM_ID: Pubkey = [
+    5, 74, 83, 90, 153, 41, 33, 6, 77, 36, 232, 113, 96, 218, 56, 124,
+    124, 53, 181, 221, 188, 146, 187, 129, 228, 31, 168, 64, 65, 5, 68, 141,
+];
+
+/// Errors raised while building a transaction on-device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The output buffer cannot hold the encoded message / transaction
+    BufferTooSmall,
+    /// More distinct accounts than `tx::MAX_ACCOUNTS`
+    TooManyAccounts,
+    /// More signatures required than `tx::MAX_SIGNERS`, or a required signer is missing
+    SignerMismatch,
+    /// Too many seeds or a seed longer than 32 bytes
+    InvalidSeeds,
+    /// No bump produced an off-curve address
+    NoViableBump,
+}
@@ -0,0 +1,78 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+
+use crate::{Error, Pubkey, PROGRAM_ID};
+
+const MAX_SEEDS: usize = 16;
+const MAX_SEED_LEN: usize = 32;
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// `create_program_address` — `seeds` must already include the bump
+pub fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Result<Pubkey, Error> {
+    if seeds.len() > MAX_SEEDS || seeds.iter().any(|s| s.len() > MAX_SEED_LEN) {
+        return Err(Error::InvalidSeeds);
+    }
+    let mut hasher = Sha256::new();
+    for seed in seeds {
+        hasher.update(seed);
+    }
+    hasher.update(program_id);
+    hasher.update(PDA_MARKER);
+    let hash: Pubkey = hasher.finalize().into();
+
+    // A PDA must not be a valid ed25519 point (no private key can exist)
+    if CompressedEdwardsY(hash).decompress().is_some() {
+        return Err(Error::NoViableBump);
+    }
+    Ok(hash)
+}
+
+/// `find_program_address` — searches bumps from 255 down
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Result<(Pubkey, u8), Error> {
+    if seeds.len() >= MAX_SEEDS {
+        return Err(Error::InvalidSeeds);
+    }
+    let mut with_bump: [&[u8]; MAX_SEEDS] = [&[]; MAX_SEEDS];
+    with_bump[..seeds.len()].copy_from_slice(seeds);
+
+    for bump in (0..=u8::MAX).rev() {
+        let bump_seed = [bump];
+        with_bump[seeds.len()] = &bump_seed;
+        match create_program_address(&with_bump[..=seeds.len()], program_id) {
+            Ok(address) => return Ok((address, bump)),
+            Err(Error::NoViableBump) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(Error::NoViableBump)
+}
+
+/// AgentSession PDA — `[b"session", owner]`
+pub fn session(owner: &Pubkey) -> Result<(Pubkey, u8), Error> {
+    find_program_address(&[b"session", owner], &PROGRAM_ID)
+}
+
+/// Global Config PDA — `[b"config"]`
+pub fn config() -> Result<(Pubkey, u8), Error> {
+    find_program_address(&[b"config"], &PROGRAM_ID)
+}
+
+/// Protocol fee vault — `[b"fee_vault"]`
+pub fn fee_vault() -> Result<(Pubkey, u8), Error> {
+    find_program_address(&[b"fee_vault"], &PROGRAM_ID)
+}
+
+/// LpPositionMonitor PDA — `[b"lp_monitor", session]`
+pub fn lp_monitor(session: &Pubkey) -> Result<(Pubkey, u8), Error> {
+    find_program_address(&[b"lp_monitor", session], &PROGRAM_ID)
+}
+
+/// ActionReceiptLog PDA — `[b"receipts", session]`
+pub fn receipt_log(session: &Pubkey) -> Result<(Pubkey, u8), Error> {
+    find_program_address(&[b"receipts", session], &PROGRAM_ID)
+}
+
+/// Intent PDA — `[b"intent", session, intent_id_le]`
+pub fn intent(session: &Pubkey, intent_id: u64) -> Result<(Pubkey, u8), Error> {
+    find_program_address(&[b"intent", session, &intent_id.to_le_bytes()], &PROGRAM_ID)
+}
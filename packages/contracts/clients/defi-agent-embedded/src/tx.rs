@@ -0,0 +1,271 @@
+//! v0 message compilation and signing into caller-provided buffers.
+//!
+//! Wire format (versioned message, version 0):
+//!   0x80 | header[3] | keys | recent_blockhash | instructions | lookups
+//! Non-signer, non-program accounts found in the optional address lookup
+//! table are moved out of the static key list, which is what keeps DLMM
+//! transactions under the packet limit.
+
+use crate::{Error, Pubkey};
+
+/// Distinct accounts a single transaction may reference
+pub const MAX_ACCOUNTS: usize = 32;
+
+/// Signatures a transaction built on-device may carry (device key + co-signer)
+pub const MAX_SIGNERS: usize = 2;
+
+const VERSION_0_PREFIX: u8 = 0x80;
+const SIGNATURE_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    pub const fn writable(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: true }
+    }
+
+    pub const fn readonly(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: false }
+    }
+
+    pub const fn writable_signer(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: true, is_writable: true }
+    }
+
+    pub const fn readonly_signer(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: true, is_writable: false }
+    }
+}
+
+/// A borrowed instruction — nothing is copied until compilation
+pub struct Instruction<'a> {
+    pub program_id: &'a Pubkey,
+    pub accounts: &'a [AccountMeta],
+    pub data: &'a [u8],
+}
+
+/// An address lookup table the device already knows the contents of
+/// (e.g. the session's table from `create_session_lookup_table`)
+pub struct LookupTable<'a> {
+    pub key: &'a Pubkey,
+    pub addresses: &'a [Pubkey],
+}
+
+/// ed25519 signing hook — implemented by the firmware (software key, secure
+/// element, …) so this crate never holds key material
+pub trait TxSigner {
+    fn pubkey(&self) -> Pubkey;
+    fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_LEN];
+}
+
+// ── Account table ───────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+struct Entry {
+    meta: AccountMeta,
+    is_program: bool,
+    /// Index inside the lookup table, when loaded from it
+    lookup_index: Option<u8>,
+}
+
+struct AccountTable {
+    entries: [Option<Entry>; MAX_ACCOUNTS],
+    len: usize,
+}
+
+impl AccountTable {
+    fn new() -> Self {
+        Self { entries: [None; MAX_ACCOUNTS], len: 0 }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Entry> {
+        self.entries[..self.len].iter().flatten()
+    }
+
+    fn add(&mut self, meta: AccountMeta, is_program: bool) -> Result<(), Error> {
+        for entry in self.entries[..self.len].iter_mut().flatten() {
+            if entry.meta.pubkey == meta.pubkey {
+                entry.meta.is_signer |= meta.is_signer;
+                entry.meta.is_writable |= meta.is_writable;
+                entry.is_program |= is_program;
+                return Ok(());
+            }
+        }
+        if self.len == MAX_ACCOUNTS {
+            return Err(Error::TooManyAccounts);
+        }
+        self.entries[self.len] = Some(Entry { meta, is_program, lookup_index: None });
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Order in which accounts are indexed by the compiled instructions:
+    /// static keys by (writable signer, readonly signer, writable, readonly),
+    /// then lookup-loaded writable, then lookup-loaded readonly.
+    fn rank(entry: &Entry) -> u8 {
+        let m = &entry.meta;
+        match (entry.lookup_index.is_some(), m.is_signer, m.is_writable) {
+            (false, true, true) => 0,
+            (false, true, false) => 1,
+            (false, false, true) => 2,
+            (false, false, false) => 3,
+            (true, _, true) => 4,
+            (true, _, false) => 5,
+        }
+    }
+
+    fn ordered(&self) -> impl Iterator<Item = &Entry> {
+        (0..=5u8).flat_map(move |rank| self.iter().filter(move |e| Self::rank(e) == rank))
+    }
+
+    fn index_of(&self, pubkey: &Pubkey) -> u8 {
+        self.ordered().position(|e| &e.meta.pubkey == pubkey).unwrap_or_default() as u8
+    }
+
+    fn count(&self, rank: u8) -> usize {
+        self.iter().filter(|e| Self::rank(e) == rank).count()
+    }
+}
+
+// ── Encoding ────────────────────────────────────────────────────────────────
+
+struct Writer<'a> {
+    buf: &'a mut [u8],
+    at: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn put(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let end = self.at + bytes.len();
+        if end > self.buf.len() {
+            return Err(Error::BufferTooSmall);
+        }
+        self.buf[self.at..end].copy_from_slice(bytes);
+        self.at = end;
+        Ok(())
+    }
+
+    /// Solana "short vec" length prefix (compact-u16)
+    fn compact_len(&mut self, mut len: usize) -> Result<(), Error> {
+        loop {
+            let mut byte = (len & 0x7f) as u8;
+            len >>= 7;
+            if len != 0 {
+                byte |= 0x80;
+            }
+            self.put(&[byte])?;
+            if len == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Compile a v0 message into `out`, returning its length.
+///
+/// `payer` is the fee payer and first signer; every other signer must appear
+/// as a signer in some instruction's accounts.
+pub fn compile_v0(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    recent_blockhash: &[u8; 32],
+    lookup_table: Option<&LookupTable>,
+    out: &mut [u8],
+) -> Result<usize, Error> {
+    let mut table = AccountTable::new();
+    table.add(AccountMeta::writable_signer(*payer), false)?;
+    for ix in instructions {
+        for meta in ix.accounts {
+            table.add(*meta, false)?;
+        }
+        table.add(AccountMeta::readonly(*ix.program_id), true)?;
+    }
+
+    // Invoked programs and signers must stay static
+    if let Some(lookup) = lookup_table {
+        for entry in table.entries[..table.len].iter_mut().flatten() {
+            if entry.meta.is_signer || entry.is_program {
+                continue;
+            }
+            entry.lookup_index = lookup
+                .addresses
+                .iter()
+                .position(|a| a == &entry.meta.pubkey)
+                .map(|i| i as u8);
+        }
+    }
+
+    let num_signers = table.count(0) + table.count(1);
+    if num_signers > MAX_SIGNERS {
+        return Err(Error::SignerMismatch);
+    }
+
+    let mut w = Writer { buf: out, at: 0 };
+    w.put(&[VERSION_0_PREFIX, num_signers as u8, table.count(1) as u8, table.count(3) as u8])?;
+
+    let static_count = (0..=3).map(|r| table.count(r)).sum();
+    w.compact_len(static_count)?;
+    for entry in table.ordered().take(static_count) {
+        w.put(&entry.meta.pubkey)?;
+    }
+    w.put(recent_blockhash)?;
+
+    w.compact_len(instructions.len())?;
+    for ix in instructions {
+        w.put(&[table.index_of(ix.program_id)])?;
+        w.compact_len(ix.accounts.len())?;
+        for meta in ix.accounts {
+            w.put(&[table.index_of(&meta.pubkey)])?;
+        }
+        w.compact_len(ix.data.len())?;
+        w.put(ix.data)?;
+    }
+
+    match lookup_table {
+        Some(lookup) if table.count(4) + table.count(5) > 0 => {
+            w.compact_len(1)?;
+            w.put(lookup.key)?;
+            for rank in [4, 5] {
+                w.compact_len(table.count(rank))?;
+                for entry in table.ordered().filter(|e| AccountTable::rank(e) == rank) {
+                    w.put(&[entry.lookup_index.unwrap_or_default()])?;
+                }
+            }
+        }
+        _ => w.compact_len(0)?,
+    }
+
+    Ok(w.at)
+}
+
+/// Sign a compiled message and write the wire transaction into `out`,
+/// returning its length. `signers` must cover exactly the message's required
+/// signatures, in any order.
+pub fn sign_transaction(message: &[u8], signers: &[&dyn TxSigner], out: &mut [u8]) -> Result<usize, Error> {
+    // header[0] = num_required_signatures; the signer keys follow the header
+    let num_signers = *message.get(1).ok_or(Error::BufferTooSmall)? as usize;
+    if num_signers != signers.len() {
+        return Err(Error::SignerMismatch);
+    }
+    let keys_at = 4 + 1; // prefix + header + one-byte key count (≤ MAX_ACCOUNTS)
+
+    let mut w = Writer { buf: out, at: 0 };
+    w.compact_len(num_signers)?;
+    for i in 0..num_signers {
+        let key = message
+            .get(keys_at + i * 32..keys_at + (i + 1) * 32)
+            .ok_or(Error::BufferTooSmall)?;
+        let signer = signers
+            .iter()
+            .find(|s| s.pubkey() == key)
+            .ok_or(Error::SignerMismatch)?;
+        w.put(&signer.sign(message))?;
+    }
+    w.put(message)?;
+    Ok(w.at)
+}
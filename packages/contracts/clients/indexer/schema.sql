@@ -0,0 +1,174 @@
+-- defi-agent indexer schema. Applied at startup; every statement is idempotent.
+-- Pubkeys are base58 TEXT; u64 amounts are BIGINT (never exceed i64 in practice).
+-- Rows are keyed by (signature, event_index) so replays are no-ops.
+
+CREATE TABLE IF NOT EXISTS sessions (
+    session          TEXT PRIMARY KEY,
+    first_seen_slot  BIGINT NOT NULL,
+    last_seen_slot   BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS session_devices (
+    session           TEXT NOT NULL REFERENCES sessions (session),
+    device_key        TEXT NOT NULL,
+    slot_index        SMALLINT NOT NULL,
+    attestation_hash  BYTEA NOT NULL,
+    signature         TEXT NOT NULL,
+    slot              BIGINT NOT NULL,
+    PRIMARY KEY (session, device_key)
+);
+
+CREATE TABLE IF NOT EXISTS actions (
+    signature        TEXT NOT NULL,
+    event_index      INTEGER NOT NULL,
+    slot             BIGINT NOT NULL,
+    session          TEXT NOT NULL REFERENCES sessions (session),
+    action_type      SMALLINT NOT NULL,
+    fee_mode         SMALLINT NOT NULL,
+    notional         BIGINT NOT NULL,
+    fee_lamports     BIGINT NOT NULL,
+    total_fees_paid  BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS actions_session_slot ON actions (session, slot DESC);
+
+-- Every counted action, fee or not; action_type separates the DLMM operations
+CREATE TABLE IF NOT EXISTS executions (
+    signature      TEXT NOT NULL,
+    event_index    INTEGER NOT NULL,
+    slot           BIGINT NOT NULL,
+    session        TEXT NOT NULL REFERENCES sessions (session),
+    action_type    SMALLINT NOT NULL,
+    amount         BIGINT NOT NULL,
+    total_actions  BIGINT NOT NULL,
+    reason         SMALLINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS executions_session_type ON executions (session, action_type);
+-- Why a rebalance / close happened (REASON_* codes; 0 = unspecified)
+ALTER TABLE executions ADD COLUMN IF NOT EXISTS reason SMALLINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS intents (
+    session    TEXT NOT NULL REFERENCES sessions (session),
+    intent_id  BIGINT NOT NULL,
+    signature  TEXT NOT NULL,
+    slot       BIGINT NOT NULL,
+    PRIMARY KEY (session, intent_id)
+);
+
+-- Latest state of each compressed monitor leaf (the tree's off-chain mirror)
+CREATE TABLE IF NOT EXISTS monitors (
+    tree             TEXT NOT NULL,
+    leaf_index       INTEGER NOT NULL,
+    lb_pair          TEXT NOT NULL,
+    position         TEXT NOT NULL,
+    min_bin_id       INTEGER NOT NULL,
+    max_bin_id       INTEGER NOT NULL,
+    last_active_bin  INTEGER NOT NULL,
+    is_in_range      BOOLEAN NOT NULL,
+    fee_x_snapshot   BIGINT NOT NULL,
+    fee_y_snapshot   BIGINT NOT NULL,
+    last_checked_at  BIGINT NOT NULL,
+    signature        TEXT NOT NULL,
+    slot             BIGINT NOT NULL,
+    PRIMARY KEY (tree, leaf_index)
+);
+
+-- Observed token movements of each swap; amount_out - min_amount_out is the
+-- slippage headroom the device left unused, expected_out - amount_out the
+-- slippage realized against the pool's pre-trade quote net of pool_fee, the
+-- estimated DLMM swap fee in the pool's Y units
+CREATE TABLE IF NOT EXISTS swaps (
+    signature       TEXT NOT NULL,
+    event_index     INTEGER NOT NULL,
+    slot            BIGINT NOT NULL,
+    session         TEXT NOT NULL REFERENCES sessions (session),
+    lb_pair         TEXT NOT NULL,
+    amount_in       BIGINT NOT NULL,
+    amount_out      BIGINT NOT NULL,
+    min_amount_out  BIGINT NOT NULL,
+    protocol_fee    BIGINT NOT NULL,
+    expected_out    BIGINT NOT NULL,
+    pool_fee        BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS swaps_session_slot ON swaps (session, slot DESC);
+ALTER TABLE swaps ADD COLUMN IF NOT EXISTS pool_fee BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS deposits (
+    signature    TEXT NOT NULL,
+    event_index  INTEGER NOT NULL,
+    slot         BIGINT NOT NULL,
+    session      TEXT NOT NULL REFERENCES sessions (session),
+    lb_pair      TEXT NOT NULL,
+    amount_x     BIGINT NOT NULL,
+    amount_y     BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS deposits_session_slot ON deposits (session, slot DESC);
+
+-- Portfolio valuations at session start, after each close and at undelegation;
+-- the latest row's realized_pnl is the session's PnL so far
+CREATE TABLE IF NOT EXISTS valuations (
+    signature       TEXT NOT NULL,
+    event_index     INTEGER NOT NULL,
+    slot            BIGINT NOT NULL,
+    session         TEXT NOT NULL REFERENCES sessions (session),
+    checkpoint      SMALLINT NOT NULL,
+    valuation_mint  TEXT NOT NULL,
+    value           BIGINT NOT NULL,
+    realized_pnl    BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS valuations_session_slot ON valuations (session, slot DESC);
+
+-- One row per closed position: what went in (at cost, in the pool's Y token)
+-- against what came out; realized_gain = proceeds - cost_basis
+CREATE TABLE IF NOT EXISTS realized_positions (
+    signature      TEXT NOT NULL,
+    event_index    INTEGER NOT NULL,
+    slot           BIGINT NOT NULL,
+    session        TEXT NOT NULL REFERENCES sessions (session),
+    position       TEXT NOT NULL,
+    lb_pair        TEXT NOT NULL,
+    deposited_x    BIGINT NOT NULL,
+    deposited_y    BIGINT NOT NULL,
+    cost_basis     BIGINT NOT NULL,
+    withdrawn_x    BIGINT NOT NULL,
+    withdrawn_y    BIGINT NOT NULL,
+    proceeds       BIGINT NOT NULL,
+    realized_gain  BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS realized_positions_session_slot ON realized_positions (session, slot DESC);
+
+-- Archival cut-points from snapshot_session, one row per (session, seq);
+-- a gap in seq means a snapshot transaction was missed
+CREATE TABLE IF NOT EXISTS snapshots (
+    session              TEXT NOT NULL REFERENCES sessions (session),
+    seq                  BIGINT NOT NULL,
+    signature            TEXT NOT NULL,
+    slot                 BIGINT NOT NULL,
+    taken_at             BIGINT NOT NULL,
+    is_active            BOOLEAN NOT NULL,
+    expires_at           BIGINT NOT NULL,
+    max_lamports         BIGINT NOT NULL,
+    spent_lamports       BIGINT NOT NULL,
+    spent_by_strategy    BIGINT[] NOT NULL,
+    fee_budget_lamports  BIGINT NOT NULL,
+    fee_spent_lamports   BIGINT NOT NULL,
+    protocol_fees_paid   BIGINT NOT NULL,
+    total_actions        BIGINT NOT NULL,
+    realized_pnl         BIGINT NOT NULL,
+    total_fees_earned_x  BIGINT NOT NULL,
+    total_fees_earned_y  BIGINT NOT NULL,
+    positions            TEXT[] NOT NULL,
+    monitor_position     TEXT NOT NULL,
+    monitor_in_range     BOOLEAN NOT NULL,
+    monitor_fee_x        BIGINT NOT NULL,
+    monitor_fee_y        BIGINT NOT NULL,
+    today_actions        BIGINT NOT NULL,
+    today_volume         BIGINT NOT NULL,
+    today_fees_paid      BIGINT NOT NULL,
+    PRIMARY KEY (session, seq)
+);
@@ -0,0 +1,85 @@
+//! Extract and decode the program's `emit!` events from transaction logs.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use defi_agent::events::{
+    ActionExecuted, ActionFeeCharged, CompressedMonitorUpdated, DeviceEnrolled, IntentsCreated, LiquidityDeposited,
+    PortfolioValued, PositionRealized, SessionSnapshot, SwapSettled,
+};
+use defi_agent::ID as PROGRAM_ID;
+
+pub enum ProgramEvent {
+    ActionExecuted(ActionExecuted),
+    ActionFeeCharged(ActionFeeCharged),
+    DeviceEnrolled(DeviceEnrolled),
+    IntentsCreated(IntentsCreated),
+    CompressedMonitorUpdated(CompressedMonitorUpdated),
+    SwapSettled(SwapSettled),
+    LiquidityDeposited(LiquidityDeposited),
+    PortfolioValued(PortfolioValued),
+    PositionRealized(PositionRealized),
+    SessionSnapshot(SessionSnapshot),
+}
+
+fn decode<T: AnchorDeserialize>(mut body: &[u8]) -> Option<T> {
+    T::deserialize(&mut body).ok()
+}
+
+impl ProgramEvent {
+    /// Decode one `Program data:` payload; unknown discriminators yield `None`
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (disc, body) = data.split_at(8);
+        match disc {
+            d if d == ActionExecuted::DISCRIMINATOR => decode(body).map(Self::ActionExecuted),
+            d if d == ActionFeeCharged::DISCRIMINATOR => decode(body).map(Self::ActionFeeCharged),
+            d if d == DeviceEnrolled::DISCRIMINATOR => decode(body).map(Self::DeviceEnrolled),
+            d if d == IntentsCreated::DISCRIMINATOR => decode(body).map(Self::IntentsCreated),
+            d if d == CompressedMonitorUpdated::DISCRIMINATOR => {
+                decode(body).map(Self::CompressedMonitorUpdated)
+            }
+            d if d == SwapSettled::DISCRIMINATOR => decode(body).map(Self::SwapSettled),
+            d if d == LiquidityDeposited::DISCRIMINATOR => decode(body).map(Self::LiquidityDeposited),
+            d if d == PortfolioValued::DISCRIMINATOR => decode(body).map(Self::PortfolioValued),
+            d if d == PositionRealized::DISCRIMINATOR => decode(body).map(Self::PositionRealized),
+            d if d == SessionSnapshot::DISCRIMINATOR => decode(body).map(Self::SessionSnapshot),
+            _ => None,
+        }
+    }
+}
+
+/// Every event our program emitted in `logs`, in order.
+///
+/// Tracks the invoke stack so `Program data:` lines written by other programs
+/// in the same transaction (e.g. CPI targets) are ignored.
+pub fn parse_logs(logs: &[String]) -> Vec<ProgramEvent> {
+    let program_id = PROGRAM_ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            if let Some(data) = rest.strip_prefix("data: ") {
+                if stack.last() == Some(&program_id.as_str()) {
+                    if let Some(event) = STANDARD.decode(data).ok().and_then(|d| ProgramEvent::decode(&d)) {
+                        events.push(event);
+                    }
+                }
+                continue;
+            }
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => stack.push(id),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
@@ -0,0 +1,82 @@
+//! indexer — streams the program's events into Postgres.
+//!
+//! Subscribes to confirmed transaction logs mentioning the program over the
+//! RPC websocket, decodes every `emit!` event, and writes them into the
+//! `sessions`, `session_devices`, `actions`, `intents` and `monitors` tables
+//! (see `schema.sql`). The app backend queries those tables instead of
+//! scraping signature history over RPC.
+//!
+//! Usage:
+//!   DATABASE_URL=postgres://localhost/defi_agent cargo run -p indexer
+
+mod events;
+mod store;
+
+use std::env;
+use std::thread;
+use std::time::Duration;
+
+use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_pubsub_client::pubsub_client::PubsubClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use defi_agent::ID as PROGRAM_ID;
+use store::Store;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+fn main() {
+    let ws_url = env::var("SOLANA_WS_URL").unwrap_or_else(|_| "wss://api.devnet.solana.com".into());
+    let database_url = match env::var("DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!("[fatal] Missing required env var: DATABASE_URL");
+            std::process::exit(1);
+        }
+    };
+
+    let mut store = match Store::connect(&database_url) {
+        Ok(store) => store,
+        Err(err) => {
+            eprintln!("[fatal] postgres: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("[init] websocket : {ws_url}");
+    println!("[init] program   : {PROGRAM_ID}");
+
+    // The subscription drops on websocket errors — reconnect forever
+    loop {
+        if let Err(err) = stream(&ws_url, &mut store) {
+            eprintln!("[indexer] subscription ended: {err}");
+        }
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+fn stream(ws_url: &str, store: &mut Store) -> Result<(), Box<dyn std::error::Error>> {
+    let (_subscription, receiver) = PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![PROGRAM_ID.to_string()]),
+        RpcTransactionLogsConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+        },
+    )?;
+
+    for response in receiver {
+        let slot = response.context.slot;
+        let logs = response.value;
+        // Failed transactions roll back their events
+        if logs.err.is_some() {
+            continue;
+        }
+        let events = events::parse_logs(&logs.logs);
+        if events.is_empty() {
+            continue;
+        }
+        store.record(&logs.signature, slot, &events)?;
+        println!("[indexer] {} · {} event(s) @ slot {slot}", logs.signature, events.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,288 @@
+//! Postgres writer — normalizes events into the tables in `schema.sql`.
+
+use postgres::{Client, NoTls, Transaction};
+
+use crate::events::ProgramEvent;
+
+const SCHEMA: &str = include_str!("../schema.sql");
+
+pub struct Store {
+    client: Client,
+}
+
+impl Store {
+    pub fn connect(database_url: &str) -> Result<Self, postgres::Error> {
+        let mut client = Client::connect(database_url, NoTls)?;
+        client.batch_execute(SCHEMA)?;
+        Ok(Self { client })
+    }
+
+    /// Persist all events of one transaction atomically
+    pub fn record(&mut self, signature: &str, slot: u64, events: &[ProgramEvent]) -> Result<(), postgres::Error> {
+        let mut tx = self.client.transaction()?;
+        for (index, event) in events.iter().enumerate() {
+            record_event(&mut tx, signature, slot as i64, index as i32, event)?;
+        }
+        tx.commit()
+    }
+}
+
+fn touch_session(tx: &mut Transaction, session: &str, slot: i64) -> Result<(), postgres::Error> {
+    tx.execute(
+        "INSERT INTO sessions (session, first_seen_slot, last_seen_slot) VALUES ($1, $2, $2)
+         ON CONFLICT (session) DO UPDATE SET last_seen_slot = GREATEST(sessions.last_seen_slot, EXCLUDED.last_seen_slot)",
+        &[&session, &slot],
+    )?;
+    Ok(())
+}
+
+fn record_event(
+    tx: &mut Transaction,
+    signature: &str,
+    slot: i64,
+    index: i32,
+    event: &ProgramEvent,
+) -> Result<(), postgres::Error> {
+    match event {
+        ProgramEvent::ActionExecuted(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            tx.execute(
+                "INSERT INTO executions (signature, event_index, slot, session, action_type, amount, total_actions,
+                                         reason)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &index,
+                    &slot,
+                    &session,
+                    &(e.action_type as i16),
+                    &(e.amount as i64),
+                    &(e.total_actions as i64),
+                    &(e.reason as i16),
+                ],
+            )?;
+        }
+        ProgramEvent::ActionFeeCharged(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            tx.execute(
+                "INSERT INTO actions (signature, event_index, slot, session, action_type, fee_mode,
+                                      notional, fee_lamports, total_fees_paid)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &index,
+                    &slot,
+                    &session,
+                    &(e.action_type as i16),
+                    &(e.fee_mode as i16),
+                    &(e.notional as i64),
+                    &(e.fee_lamports as i64),
+                    &(e.total_fees_paid as i64),
+                ],
+            )?;
+        }
+        ProgramEvent::DeviceEnrolled(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            tx.execute(
+                "INSERT INTO session_devices (session, device_key, slot_index, attestation_hash, signature, slot)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (session, device_key) DO UPDATE SET
+                     slot_index = EXCLUDED.slot_index,
+                     attestation_hash = EXCLUDED.attestation_hash,
+                     signature = EXCLUDED.signature,
+                     slot = EXCLUDED.slot
+                 WHERE session_devices.slot <= EXCLUDED.slot",
+                &[
+                    &session,
+                    &e.device_key.to_string(),
+                    &(e.slot as i16),
+                    &e.attestation_hash.as_slice(),
+                    &signature,
+                    &slot,
+                ],
+            )?;
+        }
+        ProgramEvent::IntentsCreated(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            for id in &e.intent_ids {
+                tx.execute(
+                    "INSERT INTO intents (session, intent_id, signature, slot) VALUES ($1, $2, $3, $4)
+                     ON CONFLICT DO NOTHING",
+                    &[&session, &(*id as i64), &signature, &slot],
+                )?;
+            }
+        }
+        ProgramEvent::CompressedMonitorUpdated(e) => {
+            let leaf = &e.leaf;
+            tx.execute(
+                "INSERT INTO monitors (tree, leaf_index, lb_pair, position, min_bin_id, max_bin_id,
+                                       last_active_bin, is_in_range, fee_x_snapshot, fee_y_snapshot,
+                                       last_checked_at, signature, slot)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                 ON CONFLICT (tree, leaf_index) DO UPDATE SET
+                     last_active_bin = EXCLUDED.last_active_bin,
+                     is_in_range = EXCLUDED.is_in_range,
+                     fee_x_snapshot = EXCLUDED.fee_x_snapshot,
+                     fee_y_snapshot = EXCLUDED.fee_y_snapshot,
+                     last_checked_at = EXCLUDED.last_checked_at,
+                     signature = EXCLUDED.signature,
+                     slot = EXCLUDED.slot
+                 WHERE monitors.slot <= EXCLUDED.slot",
+                &[
+                    &e.tree.to_string(),
+                    &(e.leaf_index as i32),
+                    &leaf.lb_pair.to_string(),
+                    &leaf.position.to_string(),
+                    &leaf.min_bin_id,
+                    &leaf.max_bin_id,
+                    &leaf.last_active_bin,
+                    &leaf.is_in_range,
+                    &(leaf.fee_x_snapshot as i64),
+                    &(leaf.fee_y_snapshot as i64),
+                    &leaf.last_checked_at,
+                    &signature,
+                    &slot,
+                ],
+            )?;
+        }
+        ProgramEvent::SwapSettled(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            tx.execute(
+                "INSERT INTO swaps (signature, event_index, slot, session, lb_pair, amount_in,
+                                    amount_out, min_amount_out, protocol_fee, expected_out, pool_fee)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &index,
+                    &slot,
+                    &session,
+                    &e.lb_pair.to_string(),
+                    &(e.amount_in as i64),
+                    &(e.amount_out as i64),
+                    &(e.min_amount_out as i64),
+                    &(e.protocol_fee as i64),
+                    &(e.expected_out as i64),
+                    &(e.pool_fee as i64),
+                ],
+            )?;
+        }
+        ProgramEvent::LiquidityDeposited(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            tx.execute(
+                "INSERT INTO deposits (signature, event_index, slot, session, lb_pair, amount_x, amount_y)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &index,
+                    &slot,
+                    &session,
+                    &e.lb_pair.to_string(),
+                    &(e.amount_x as i64),
+                    &(e.amount_y as i64),
+                ],
+            )?;
+        }
+        ProgramEvent::PortfolioValued(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            tx.execute(
+                "INSERT INTO valuations (signature, event_index, slot, session, checkpoint,
+                                         valuation_mint, value, realized_pnl)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &index,
+                    &slot,
+                    &session,
+                    &(e.checkpoint as i16),
+                    &e.valuation_mint.to_string(),
+                    &(e.value as i64),
+                    &e.realized_pnl,
+                ],
+            )?;
+        }
+        ProgramEvent::PositionRealized(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            tx.execute(
+                "INSERT INTO realized_positions (signature, event_index, slot, session, position,
+                                                 lb_pair, deposited_x, deposited_y, cost_basis,
+                                                 withdrawn_x, withdrawn_y, proceeds, realized_gain)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &signature,
+                    &index,
+                    &slot,
+                    &session,
+                    &e.position.to_string(),
+                    &e.lb_pair.to_string(),
+                    &(e.deposited_x as i64),
+                    &(e.deposited_y as i64),
+                    &(e.cost_basis as i64),
+                    &(e.withdrawn_x as i64),
+                    &(e.withdrawn_y as i64),
+                    &(e.proceeds as i64),
+                    &e.realized_gain,
+                ],
+            )?;
+        }
+        ProgramEvent::SessionSnapshot(e) => {
+            let session = e.session.to_string();
+            touch_session(tx, &session, slot)?;
+            let positions: Vec<String> = e.positions.iter().map(|p| p.to_string()).collect();
+            let spent_by_strategy: Vec<i64> = e.spent_by_strategy.iter().map(|&x| x as i64).collect();
+            tx.execute(
+                "INSERT INTO snapshots (session, seq, signature, slot, taken_at, is_active, expires_at,
+                                        max_lamports, spent_lamports, spent_by_strategy,
+                                        fee_budget_lamports, fee_spent_lamports, protocol_fees_paid,
+                                        total_actions, realized_pnl, total_fees_earned_x,
+                                        total_fees_earned_y, positions, monitor_position,
+                                        monitor_in_range, monitor_fee_x, monitor_fee_y,
+                                        today_actions, today_volume, today_fees_paid)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                         $17, $18, $19, $20, $21, $22, $23, $24, $25)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &session,
+                    &(e.seq as i64),
+                    &signature,
+                    &slot,
+                    &e.taken_at,
+                    &e.is_active,
+                    &e.expires_at,
+                    &(e.max_lamports as i64),
+                    &(e.spent_lamports as i64),
+                    &spent_by_strategy,
+                    &(e.fee_budget_lamports as i64),
+                    &(e.fee_spent_lamports as i64),
+                    &(e.protocol_fees_paid as i64),
+                    &(e.total_actions as i64),
+                    &e.realized_pnl,
+                    &(e.total_fees_earned_x as i64),
+                    &(e.total_fees_earned_y as i64),
+                    &positions,
+                    &e.monitor_position.to_string(),
+                    &e.monitor_in_range,
+                    &(e.monitor_fee_x as i64),
+                    &(e.monitor_fee_y as i64),
+                    &(e.today_actions as i64),
+                    &(e.today_volume as i64),
+                    &(e.today_fees_paid as i64),
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! Keeper configuration, loaded from the environment (same variable names as
+//! the TypeScript agent where they overlap).
+
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use solana_sdk::signature::{read_keypair_file, Keypair};
+
+pub struct KeeperConfig {
+    /// Base-layer RPC endpoint
+    pub rpc_url: String,
+    /// Device (session) key — signs monitor updates and rebalance actions
+    pub session_keypair: Keypair,
+    /// Session owner — only needed for owner-signed expiry cranks
+    pub owner_keypair: Option<Keypair>,
+    /// Time between ticks
+    pub interval: Duration,
+    /// Consecutive out-of-range ticks before a rebalance is submitted (0 = never)
+    pub rebalance_after_ticks: u32,
+    /// Notional passed to `execute_action` for a rebalance
+    pub rebalance_amount_lamports: u64,
+    /// Skip a due rebalance while the active bin is fewer than this many bins
+    /// outside the position's range (0 = no minimum)
+    pub min_rebalance_bins_out: u32,
+    /// Skip a due rebalance while the position is worth less than this, in
+    /// raw token Y units at the pool price (0 = no minimum)
+    pub min_rebalance_value: u64,
+    /// Refill the device key from the session's fee sponsor when its balance
+    /// drops below this many lamports (0 = never)
+    pub refill_below_lamports: u64,
+}
+
+fn required(key: &str) -> Result<String, String> {
+    env::var(key).map_err(|_| format!("Missing required env var: {key}"))
+}
+
+fn parsed<T: std::str::FromStr>(key: &str, default: T) -> Result<T, String> {
+    match env::var(key) {
+        Ok(v) => v.parse().map_err(|_| format!("{key} is not a valid number: {v}")),
+        Err(_) => Ok(default),
+    }
+}
+
+fn load_keypair(path: &str) -> Result<Keypair, String> {
+    let expanded = match (path.strip_prefix("~/"), env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    };
+    read_keypair_file(&expanded).map_err(|e| format!("{}: {e}", expanded.display()))
+}
+
+impl KeeperConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let interval_ms: u64 = parsed("CHECK_INTERVAL_MS", 30_000)?;
+        if !(5_000..=3_600_000).contains(&interval_ms) {
+            return Err("CHECK_INTERVAL_MS must be a number between 5000 and 3600000".into());
+        }
+
+        Ok(Self {
+            rpc_url: env::var("SOLANA_RPC_URL")
+                .unwrap_or_else(|_| "https://api.devnet.solana.com".into()),
+            session_keypair: load_keypair(&required("SESSION_KEYPAIR_PATH")?)?,
+            owner_keypair: env::var("OWNER_KEYPAIR_PATH")
+                .ok()
+                .map(|p| load_keypair(&p))
+                .transpose()?,
+            interval: Duration::from_millis(interval_ms),
+            rebalance_after_ticks: parsed("REBALANCE_AFTER_TICKS", 0)?,
+            rebalance_amount_lamports: parsed("REBALANCE_AMOUNT_LAMPORTS", 100_000)?,
+            min_rebalance_bins_out: parsed("MIN_REBALANCE_BINS_OUT", 0)?,
+            min_rebalance_value: parsed("MIN_REBALANCE_VALUE", 0)?,
+            refill_below_lamports: parsed("REFILL_BELOW_LAMPORTS", 0)?,
+        })
+    }
+}
@@ -0,0 +1,247 @@
+//! One keeper tick: top the device key up from its fee sponsor, refresh every
+//! monitor this device key serves, rebalance positions that stay out of
+//! range, and crank expired intents.
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+use defi_agent::state::{
+    Intent, LpPositionMonitor, ACTION_LP_REBALANCE, REASON_PRICE_DOWN, REASON_PRICE_UP,
+};
+use defi_agent_client::{accounts, dlmm, instructions, pda, PROGRAM_ID};
+
+use crate::config::KeeperConfig;
+
+type KeeperResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+pub struct Keeper {
+    rpc: RpcClient,
+    config: KeeperConfig,
+    /// Consecutive out-of-range ticks per monitor
+    out_of_range: HashMap<Pubkey, u32>,
+}
+
+impl Keeper {
+    pub fn new(config: KeeperConfig) -> Self {
+        Self {
+            rpc: RpcClient::new(config.rpc_url.clone()),
+            config,
+            out_of_range: HashMap::new(),
+        }
+    }
+
+    pub fn tick(&mut self, tick: u64) -> KeeperResult<()> {
+        let now = unix_now();
+
+        // ── Fee refill ──────────────────────────────────────────────────────────
+        if self.config.refill_below_lamports > 0 {
+            if let Err(err) = self.refill_device() {
+                eprintln!("[tick {tick}] fee refill: {err}");
+            }
+        }
+
+        // ── Monitor updates ─────────────────────────────────────────────────────
+        for (monitor_key, monitor) in self.monitors()? {
+            if let Err(err) = self.refresh_monitor(&monitor_key, &monitor, now) {
+                eprintln!("[tick {tick}] monitor {monitor_key}: {err}");
+            }
+        }
+
+        // ── Expiry cranks (owner-signed) ────────────────────────────────────────
+        if self.config.owner_keypair.is_some() {
+            if let Err(err) = self.crank_expired_intents(now) {
+                eprintln!("[tick {tick}] intent crank: {err}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Top the device key up from its session's fee sponsor once its balance
+    /// falls below the configured floor. The session is found through the
+    /// key's SessionLookup entry.
+    fn refill_device(&self) -> KeeperResult<()> {
+        let device = self.config.session_keypair.pubkey();
+        let balance = self.rpc.get_balance(&device)?;
+        if balance >= self.config.refill_below_lamports {
+            return Ok(());
+        }
+
+        let lookup = accounts::decode_session_lookup(&self.rpc.get_account_data(&pda::session_lookup(&device).0)?)?;
+        let session = accounts::decode_session(&self.rpc.get_account_data(&lookup.session)?)?;
+        let sig = self.send(
+            instructions::refill_session_key(session.owner, device),
+            &[&self.config.session_keypair],
+        )?;
+        println!("[keeper] device balance {balance} below floor — refill tx={sig}");
+        Ok(())
+    }
+
+    /// Every registered LpPositionMonitor on the program
+    fn monitors(&self) -> KeeperResult<Vec<(Pubkey, LpPositionMonitor)>> {
+        let accounts = self.program_accounts(vec![
+            RpcFilterType::DataSize(LpPositionMonitor::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, LpPositionMonitor::DISCRIMINATOR.to_vec())),
+        ])?;
+        Ok(accounts
+            .into_iter()
+            .filter_map(|(key, data)| accounts::decode_lp_monitor(&data).ok().map(|m| (key, m)))
+            .collect())
+    }
+
+    fn refresh_monitor(&mut self, monitor_key: &Pubkey, monitor: &LpPositionMonitor, now: i64) -> KeeperResult<()> {
+        let device = self.config.session_keypair.pubkey();
+
+        // Only serve sessions that enrolled this device key and are still live
+        let session = accounts::decode_session(&self.rpc.get_account_data(&monitor.session)?)?;
+        if session.require_device(&device, now).is_err() {
+            return Ok(());
+        }
+
+        let lb_pair = accounts::decode_lb_pair(&self.rpc.get_account_data(&monitor.lb_pair)?)?;
+        let position = accounts::decode_dlmm_position(&self.rpc.get_account_data(&monitor.position)?)?;
+        let active_bin = lb_pair.active_id;
+        let (fee_x, fee_y) = dlmm::pending_fees(&position);
+        let bin_arrays: Vec<_> = self
+            .rpc
+            .get_multiple_accounts(&dlmm::bin_arrays_for_range(
+                &monitor.lb_pair,
+                position.lower_bin_id,
+                position.upper_bin_id,
+            ))?
+            .into_iter()
+            .flatten()
+            .filter_map(|account| accounts::decode_bin_array(&account.data).ok())
+            .collect();
+        let (amount_x, amount_y) = dlmm::position_amounts(&position, &bin_arrays);
+
+        let sig = self.send(
+            instructions::update_lp_status(
+                device,
+                session.owner,
+                monitor.lb_pair,
+                monitor.position,
+                fee_x,
+                fee_y,
+                amount_x,
+                amount_y,
+            ),
+            &[&self.config.session_keypair],
+        )?;
+        let in_range = monitor.check_in_range(active_bin);
+        println!(
+            "[keeper] {monitor_key} bin={active_bin} range=[{}, {}] in_range={in_range} fees=({fee_x}, {fee_y}) \
+             size=({amount_x}, {amount_y}) tx={sig}",
+            monitor.min_bin_id, monitor.max_bin_id,
+        );
+
+        // ── Conditional rebalance ───────────────────────────────────────────────
+        let streak = self.out_of_range.entry(*monitor_key).or_default();
+        *streak = if in_range { 0 } else { *streak + 1 };
+
+        let threshold = self.config.rebalance_after_ticks;
+        if threshold > 0 && *streak >= threshold {
+            // Not worth the fees and slippage yet — leave the streak running
+            // so the rebalance goes out once the position qualifies
+            let bins_out = bins_out_of_range(monitor, active_bin);
+            let price = dlmm::bin_price(active_bin, lb_pair.bin_step);
+            let value = ((amount_x as f64 * price) as u64).saturating_add(amount_y);
+            if bins_out < self.config.min_rebalance_bins_out || value < self.config.min_rebalance_value {
+                println!("[keeper] {monitor_key} rebalance skipped — {bins_out} bins out, value {value}");
+                return Ok(());
+            }
+            *streak = 0;
+            let reason = if active_bin > monitor.max_bin_id { REASON_PRICE_UP } else { REASON_PRICE_DOWN };
+            let sig = self.send(
+                instructions::execute_action(
+                    device,
+                    session.owner,
+                    ACTION_LP_REBALANCE,
+                    self.config.rebalance_amount_lamports,
+                    0,
+                    reason,
+                    false,
+                ),
+                &[&self.config.session_keypair],
+            )?;
+            println!("[keeper] {monitor_key} out of range for {threshold} ticks — rebalance tx={sig}");
+        }
+
+        Ok(())
+    }
+
+    /// Close the owner's intents whose deadline has passed, refunding their rent
+    fn crank_expired_intents(&self, now: i64) -> KeeperResult<()> {
+        let Some(owner) = self.config.owner_keypair.as_ref() else {
+            return Ok(());
+        };
+        let session = pda::session(&owner.pubkey()).0;
+
+        let intents = self.program_accounts(vec![
+            RpcFilterType::DataSize(Intent::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, Intent::DISCRIMINATOR.to_vec())),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(8, session.to_bytes().to_vec())),
+        ])?;
+
+        for (key, data) in intents {
+            let intent = accounts::decode_intent(&data)?;
+            if now <= intent.deadline {
+                continue;
+            }
+            let sig = self.send(instructions::cancel_intent(owner.pubkey(), intent.intent_id), &[owner])?;
+            println!("[keeper] intent {key} (id {}) expired — cancelled tx={sig}", intent.intent_id);
+        }
+
+        Ok(())
+    }
+
+    fn program_accounts(&self, filters: Vec<RpcFilterType>) -> KeeperResult<Vec<(Pubkey, Vec<u8>)>> {
+        let accounts = self.rpc.get_program_accounts_with_config(
+            &PROGRAM_ID,
+            RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )?;
+        Ok(accounts.into_iter().map(|(key, account)| (key, account.data)).collect())
+    }
+
+    /// Sign with `signers` (the first pays) and wait for confirmation
+    fn send(&self, ix: Instruction, signers: &[&Keypair]) -> KeeperResult<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&signers[0].pubkey()), signers, blockhash);
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// How many bins the active bin sits outside the monitor's range (0 in range)
+fn bins_out_of_range(monitor: &LpPositionMonitor, active_bin: i32) -> u32 {
+    if active_bin > monitor.max_bin_id {
+        active_bin.abs_diff(monitor.max_bin_id)
+    } else if active_bin < monitor.min_bin_id {
+        monitor.min_bin_id.abs_diff(active_bin)
+    } else {
+        0
+    }
+}
@@ -0,0 +1,63 @@
+//! keeper — reference implementation of the monitoring loop, off the ESP32.
+//!
+//! Each tick it:
+//! - tops the device key up from its session's fee sponsor
+//!   (`refill_session_key`) once its balance is below `REFILL_BELOW_LAMPORTS`
+//! - refreshes every LpPositionMonitor whose session enrolled this device key
+//!   (`update_lp_status` with the position's fees and token amounts; the
+//!   program reads the pool's active bin itself)
+//! - submits an LP rebalance (`execute_action`) once a position has been out of
+//!   range for `REBALANCE_AFTER_TICKS` consecutive ticks, tagged with the
+//!   direction the price left it (`REASON_PRICE_UP` / `REASON_PRICE_DOWN`).
+//!   A due rebalance is held back while the position is fewer than
+//!   `MIN_REBALANCE_BINS_OUT` bins out or worth less than `MIN_REBALANCE_VALUE`
+//! - cancels the owner's expired intents when `OWNER_KEYPAIR_PATH` is set
+//!
+//! Usage:
+//!   SESSION_KEYPAIR_PATH=~/.config/solana/device.json cargo run -p keeper
+
+mod config;
+mod keeper;
+
+use std::thread;
+
+use solana_sdk::signature::Signer;
+
+use config::KeeperConfig;
+use keeper::Keeper;
+
+fn main() {
+    let config = match KeeperConfig::from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("[fatal] {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("[init] rpc         : {}", config.rpc_url);
+    println!("[init] session key : {}", config.session_keypair.pubkey());
+    if let Some(owner) = &config.owner_keypair {
+        println!("[init] owner       : {}", owner.pubkey());
+    }
+    println!("[init] interval    : {}s", config.interval.as_secs());
+    match config.rebalance_after_ticks {
+        0 => println!("[init] rebalance   : disabled"),
+        n => println!(
+            "[init] rebalance   : after {n} out-of-range ticks, min {} bins out, min value {}",
+            config.min_rebalance_bins_out, config.min_rebalance_value,
+        ),
+    }
+
+    let interval = config.interval;
+    let mut keeper = Keeper::new(config);
+    let mut tick = 0u64;
+
+    loop {
+        tick += 1;
+        if let Err(err) = keeper.tick(tick) {
+            eprintln!("[tick {tick}] ERROR: {err}");
+        }
+        thread::sleep(interval);
+    }
+}
@@ -0,0 +1,87 @@
+//! Alert model shared by the event and watchdog sources, plus deduplication.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// `OutOfRangeAlert` event from `update_lp_status`
+    PositionOutOfRange,
+    /// `SlippageExceeded` event from a swap past the session's bound
+    SlippageExceeded,
+    /// `SettlementDiscrepancy` event from a position close
+    SettlementDiscrepancy,
+    /// `SuspiciousActivity` event: repeated scope violations froze the session
+    SuspiciousActivity,
+    /// `FeeThresholdReached` event: fees paid reached the owner's alert threshold
+    FeeThreshold,
+    /// `ExposureThresholdReached` event: exposure reached the owner's alert threshold
+    ExposureThreshold,
+    /// `OutOfRangeTooLong` event: the position stayed out of range past the owner's threshold
+    OutOfRangeTooLong,
+    /// Watchdog: no regular device has heartbeated within the threshold
+    DeviceSilent,
+    /// Watchdog: session expires within the warning window
+    SessionExpiring,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub severity: Severity,
+    /// Session PDA
+    pub session: String,
+    /// Session owner — FCM topic suffix; resolved by the watchdog or from the session account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub title: String,
+    pub body: String,
+    /// Transaction signature for event-sourced alerts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl Alert {
+    pub fn session_key(&self) -> Option<Pubkey> {
+        self.session.parse().ok()
+    }
+}
+
+/// Suppresses repeats of the same (kind, session) within `window`.
+///
+/// A position bouncing across its range edge, or a watchdog re-checking a
+/// still-silent device every poll, notifies once per window.
+pub struct Dedup {
+    window: Duration,
+    last_sent: HashMap<(AlertKind, String), Instant>,
+}
+
+impl Dedup {
+    pub fn new(window: Duration) -> Self {
+        Self { window, last_sent: HashMap::new() }
+    }
+
+    /// True if `alert` should be delivered now; records it as sent
+    pub fn admit(&mut self, alert: &Alert) -> bool {
+        let now = Instant::now();
+        self.last_sent.retain(|_, sent| now.duration_since(*sent) < self.window);
+        let key = (alert.kind, alert.session.clone());
+        if self.last_sent.contains_key(&key) {
+            return false;
+        }
+        self.last_sent.insert(key, now);
+        true
+    }
+}
@@ -0,0 +1,89 @@
+//! Notifier configuration, loaded from the environment.
+
+use std::env;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::alert::Severity;
+
+pub struct FcmConfig {
+    pub project_id: String,
+    /// OAuth2 access token for the FCM HTTP v1 API (e.g. `gcloud auth print-access-token`)
+    pub access_token: String,
+    /// Messages go to topic `<prefix><owner>`; the app subscribes to its owner's topic
+    pub topic_prefix: String,
+}
+
+pub struct NotifierConfig {
+    pub ws_url: String,
+    pub rpc_url: String,
+    pub webhook_url: Option<String>,
+    pub fcm: Option<FcmConfig>,
+    /// Alerts below this severity are dropped
+    pub min_severity: Severity,
+    /// Repeats of the same alert within this window are suppressed
+    pub dedup_window: Duration,
+    /// Owners whose sessions the watchdog polls
+    pub watch_owners: Vec<Pubkey>,
+    pub watchdog_interval: Duration,
+    /// Regular devices silent this long trip the watchdog
+    pub device_silent_secs: i64,
+    /// Warn this long before session expiry
+    pub expiry_warning_secs: i64,
+}
+
+fn parsed<T: std::str::FromStr>(key: &str, default: T) -> Result<T, String> {
+    match env::var(key) {
+        Ok(v) => v.parse().map_err(|_| format!("{key} is not valid: {v}")),
+        Err(_) => Ok(default),
+    }
+}
+
+fn severity(key: &str) -> Result<Severity, String> {
+    match env::var(key).as_deref() {
+        Err(_) | Ok("info") => Ok(Severity::Info),
+        Ok("warning") => Ok(Severity::Warning),
+        Ok("critical") => Ok(Severity::Critical),
+        Ok(other) => Err(format!("{key} must be info, warning or critical: {other}")),
+    }
+}
+
+impl NotifierConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let fcm = match (env::var("FCM_PROJECT_ID"), env::var("FCM_ACCESS_TOKEN")) {
+            (Ok(project_id), Ok(access_token)) => Some(FcmConfig {
+                project_id,
+                access_token,
+                topic_prefix: env::var("FCM_TOPIC_PREFIX").unwrap_or_else(|_| "session-".into()),
+            }),
+            (Err(_), Err(_)) => None,
+            _ => return Err("FCM_PROJECT_ID and FCM_ACCESS_TOKEN must be set together".into()),
+        };
+        let webhook_url = env::var("WEBHOOK_URL").ok();
+        if webhook_url.is_none() && fcm.is_none() {
+            return Err("Set WEBHOOK_URL and/or FCM_PROJECT_ID + FCM_ACCESS_TOKEN".into());
+        }
+
+        let watch_owners = env::var("WATCH_OWNERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().map_err(|_| format!("WATCH_OWNERS: invalid pubkey {s}")))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            ws_url: env::var("SOLANA_WS_URL").unwrap_or_else(|_| "wss://api.devnet.solana.com".into()),
+            rpc_url: env::var("SOLANA_RPC_URL").unwrap_or_else(|_| "https://api.devnet.solana.com".into()),
+            webhook_url,
+            fcm,
+            min_severity: severity("MIN_SEVERITY")?,
+            dedup_window: Duration::from_secs(parsed("DEDUP_WINDOW_SECS", 900)?),
+            watch_owners,
+            watchdog_interval: Duration::from_millis(parsed("WATCHDOG_INTERVAL_MS", 60_000)?),
+            device_silent_secs: parsed("DEVICE_SILENT_SECS", 300)?,
+            expiry_warning_secs: parsed("EXPIRY_WARNING_SECS", 3_600)?,
+        })
+    }
+}
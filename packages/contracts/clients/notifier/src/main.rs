@@ -0,0 +1,108 @@
+//! notifier — relays agent alerts to the owner's phone.
+//!
+//! Sources:
+//! - `OutOfRangeAlert` events emitted by `update_lp_status`, and
+//!   `SlippageExceeded` / `SettlementDiscrepancy` / `SuspiciousActivity` events
+//!   emitted by swaps, position closes and the violation freeze, and the
+//!   owner-configured threshold alerts (`FeeThresholdReached`,
+//!   `ExposureThresholdReached`, `OutOfRangeTooLong`), streamed over the logs
+//!   websocket (every session on the program)
+//! - a watchdog polling `WATCH_OWNERS`' sessions for silent devices and
+//!   upcoming expiry
+//!
+//! There are no health-factor alerts yet: the program has no lending
+//! integration to read one from. They would be another `AlertKind` source.
+//!
+//! Each alert carries a severity (`info` / `warning` / `critical`); alerts below
+//! `MIN_SEVERITY` are dropped and repeats of the same (kind, session) within
+//! `DEDUP_WINDOW_SECS` are suppressed. Survivors go to `WEBHOOK_URL` and/or the
+//! FCM topic `<FCM_TOPIC_PREFIX><owner>`.
+//!
+//! Usage:
+//!   WEBHOOK_URL=https://example.com/hooks/agent WATCH_OWNERS=<owner> cargo run -p notifier
+
+mod alert;
+mod config;
+mod sinks;
+mod sources;
+
+use std::sync::mpsc;
+use std::thread;
+
+use solana_client::rpc_client::RpcClient;
+
+use alert::{Alert, Dedup};
+use config::NotifierConfig;
+use defi_agent_client::accounts;
+use sources::Watchdog;
+
+fn main() {
+    let config = match NotifierConfig::from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("[fatal] {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("[init] websocket : {}", config.ws_url);
+    println!("[init] webhook   : {}", config.webhook_url.as_deref().unwrap_or("disabled"));
+    match &config.fcm {
+        Some(fcm) => println!("[init] fcm       : project {} · topic {}<owner>", fcm.project_id, fcm.topic_prefix),
+        None => println!("[init] fcm       : disabled"),
+    }
+    println!("[init] watchdog  : {} owner(s)", config.watch_owners.len());
+
+    let (tx, rx) = mpsc::channel::<Alert>();
+
+    let ws_url = config.ws_url.clone();
+    let events_tx = tx.clone();
+    thread::spawn(move || sources::events(ws_url, events_tx));
+
+    if !config.watch_owners.is_empty() {
+        let watchdog = Watchdog {
+            rpc: RpcClient::new(config.rpc_url.clone()),
+            owners: config.watch_owners.clone(),
+            interval: config.watchdog_interval,
+            device_silent_secs: config.device_silent_secs,
+            expiry_warning_secs: config.expiry_warning_secs,
+        };
+        thread::spawn(move || watchdog.run(tx));
+    } else {
+        drop(tx);
+    }
+
+    let rpc = RpcClient::new(config.rpc_url.clone());
+    let mut dedup = Dedup::new(config.dedup_window);
+
+    for mut alert in rx {
+        if alert.severity < config.min_severity || !dedup.admit(&alert) {
+            continue;
+        }
+        println!("[alert] {:?} {:?} · {} · {}", alert.severity, alert.kind, alert.session, alert.title);
+
+        if let Some(url) = &config.webhook_url {
+            if let Err(err) = sinks::webhook(url, &alert) {
+                eprintln!("[webhook] {err}");
+            }
+        }
+        if let Some(fcm) = &config.fcm {
+            // Event alerts only carry the session — look up its owner for the topic
+            if alert.owner.is_none() {
+                alert.owner = alert
+                    .session_key()
+                    .and_then(|key| rpc.get_account_data(&key).ok())
+                    .and_then(|data| accounts::decode_session(&data).ok())
+                    .map(|session| session.owner.to_string());
+            }
+            match &alert.owner {
+                Some(owner) => {
+                    if let Err(err) = sinks::fcm(fcm, owner, &alert) {
+                        eprintln!("[fcm] {err}");
+                    }
+                }
+                None => eprintln!("[fcm] {}: could not resolve session owner", alert.session),
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Delivery: generic JSON webhook and Firebase Cloud Messaging (HTTP v1).
+
+use serde_json::json;
+
+use crate::alert::{Alert, Severity};
+use crate::config::FcmConfig;
+
+type SinkResult = Result<(), Box<ureq::Error>>;
+
+/// POST the alert as JSON
+pub fn webhook(url: &str, alert: &Alert) -> SinkResult {
+    ureq::post(url).send_json(alert).map_err(Box::new)?;
+    Ok(())
+}
+
+/// Push to the owner's FCM topic. Critical alerts go out high-priority so
+/// they wake the device; everything else is delivered normally.
+pub fn fcm(config: &FcmConfig, owner: &str, alert: &Alert) -> SinkResult {
+    let high = alert.severity == Severity::Critical;
+    let message = json!({
+        "message": {
+            "topic": format!("{}{}", config.topic_prefix, owner),
+            "notification": { "title": alert.title, "body": alert.body },
+            "data": {
+                "kind": serde_json::to_value(alert.kind).unwrap_or_default(),
+                "severity": serde_json::to_value(alert.severity).unwrap_or_default(),
+                "session": alert.session,
+                "signature": alert.signature.clone().unwrap_or_default(),
+            },
+            "android": { "priority": if high { "high" } else { "normal" } },
+            "apns": { "headers": { "apns-priority": if high { "10" } else { "5" } } },
+        }
+    });
+    let url = format!(
+        "https://fcm.googleapis.com/v1/projects/{}/messages:send",
+        config.project_id
+    );
+    ureq::post(&url)
+        .set("Authorization", &format!("Bearer {}", config.access_token))
+        .send_json(message)
+        .map_err(Box::new)?;
+    Ok(())
+}
@@ -0,0 +1,327 @@
+//! Alert sources: program events over the logs websocket, and a watchdog that
+//! polls watched sessions for silent devices and upcoming expiry.
+
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_pubsub_client::pubsub_client::PubsubClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use defi_agent::events::{
+    ExposureThresholdReached, FeeThresholdReached, OutOfRangeAlert, OutOfRangeTooLong,
+    SettlementDiscrepancy, SlippageExceeded, SuspiciousActivity,
+};
+use defi_agent::ID as PROGRAM_ID;
+use defi_agent_client::{accounts, pda};
+
+use crate::alert::{Alert, AlertKind, Severity};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// ── Program events ──────────────────────────────────────────────────────────
+
+/// Alert-worthy events our program emitted in `logs` (CPI targets' `Program data:`
+/// lines are skipped by tracking the invoke stack, as in the indexer).
+fn event_alerts(logs: &[String], signature: &str) -> Vec<Alert> {
+    let program_id = PROGRAM_ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut alerts = Vec::new();
+
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let Ok(bytes) = STANDARD.decode(data) else {
+                continue;
+            };
+            if let Some(mut body) = bytes.strip_prefix(OutOfRangeAlert::DISCRIMINATOR) {
+                if let Ok(event) = OutOfRangeAlert::deserialize(&mut body) {
+                    alerts.push(out_of_range_alert(&event, signature));
+                }
+            } else if let Some(mut body) = bytes.strip_prefix(SlippageExceeded::DISCRIMINATOR) {
+                if let Ok(event) = SlippageExceeded::deserialize(&mut body) {
+                    alerts.push(slippage_alert(&event, signature));
+                }
+            } else if let Some(mut body) = bytes.strip_prefix(SettlementDiscrepancy::DISCRIMINATOR) {
+                if let Ok(event) = SettlementDiscrepancy::deserialize(&mut body) {
+                    alerts.push(settlement_alert(&event, signature));
+                }
+            } else if let Some(mut body) = bytes.strip_prefix(SuspiciousActivity::DISCRIMINATOR) {
+                if let Ok(event) = SuspiciousActivity::deserialize(&mut body) {
+                    alerts.push(suspicious_activity_alert(&event, signature));
+                }
+            } else if let Some(mut body) = bytes.strip_prefix(FeeThresholdReached::DISCRIMINATOR) {
+                if let Ok(event) = FeeThresholdReached::deserialize(&mut body) {
+                    alerts.push(fee_threshold_alert(&event, signature));
+                }
+            } else if let Some(mut body) = bytes.strip_prefix(ExposureThresholdReached::DISCRIMINATOR) {
+                if let Ok(event) = ExposureThresholdReached::deserialize(&mut body) {
+                    alerts.push(exposure_threshold_alert(&event, signature));
+                }
+            } else if let Some(mut body) = bytes.strip_prefix(OutOfRangeTooLong::DISCRIMINATOR) {
+                if let Ok(event) = OutOfRangeTooLong::deserialize(&mut body) {
+                    alerts.push(out_of_range_too_long_alert(&event, signature));
+                }
+            }
+            continue;
+        }
+        let mut words = rest.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some(id), Some("invoke")) => stack.push(id),
+            (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    alerts
+}
+
+fn out_of_range_alert(event: &OutOfRangeAlert, signature: &str) -> Alert {
+    let distance = if event.active_bin < event.min_bin_id {
+        event.min_bin_id - event.active_bin
+    } else {
+        event.active_bin - event.max_bin_id
+    };
+    Alert {
+        kind: AlertKind::PositionOutOfRange,
+        // Just past the edge still earns nothing but may drift back; far out needs a rebalance
+        severity: if distance > (event.max_bin_id - event.min_bin_id + 1) / 2 {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        },
+        session: event.session.to_string(),
+        owner: None,
+        title: "LP position out of range".into(),
+        body: format!(
+            "Active bin {} is outside [{}, {}] — the position has stopped earning fees",
+            event.active_bin, event.min_bin_id, event.max_bin_id
+        ),
+        signature: Some(signature.to_string()),
+    }
+}
+
+fn slippage_alert(event: &SlippageExceeded, signature: &str) -> Alert {
+    Alert {
+        kind: AlertKind::SlippageExceeded,
+        // Twice the owner's bound points at a thin or manipulated pool
+        severity: if event.slippage_bps > 2 * event.max_slippage_bps as u64 {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        },
+        session: event.session.to_string(),
+        owner: None,
+        title: "Swap slippage above bound".into(),
+        body: format!(
+            "Swap on {} returned {} against a quote of {} — {} bps slippage, bound is {} bps",
+            event.lb_pair, event.amount_out, event.expected_out, event.slippage_bps, event.max_slippage_bps
+        ),
+        signature: Some(signature.to_string()),
+    }
+}
+
+fn settlement_alert(event: &SettlementDiscrepancy, signature: &str) -> Alert {
+    Alert {
+        kind: AlertKind::SettlementDiscrepancy,
+        severity: Severity::Critical,
+        session: event.session.to_string(),
+        owner: None,
+        title: "Position close needs review".into(),
+        body: format!(
+            "Closing {} returned x={} y={} but the device last reported x={} y={} ({} bps apart)",
+            event.position, event.withdrawn_x, event.withdrawn_y, event.expected_x, event.expected_y, event.gap_bps
+        ),
+        signature: Some(signature.to_string()),
+    }
+}
+
+fn suspicious_activity_alert(event: &SuspiciousActivity, signature: &str) -> Alert {
+    Alert {
+        kind: AlertKind::SuspiciousActivity,
+        severity: Severity::Critical,
+        session: event.session.to_string(),
+        owner: None,
+        title: "Session suspended".into(),
+        body: format!(
+            "Device {} made {} out-of-scope attempts in a row — disable or rotate it, then lift the freeze",
+            event.device, event.consecutive_violations
+        ),
+        signature: Some(signature.to_string()),
+    }
+}
+
+fn fee_threshold_alert(event: &FeeThresholdReached, signature: &str) -> Alert {
+    Alert {
+        kind: AlertKind::FeeThreshold,
+        severity: Severity::Warning,
+        session: event.session.to_string(),
+        owner: None,
+        title: "Fee alert".into(),
+        body: format!(
+            "Devices have paid {} lamports in fees, past your {} lamport alert",
+            event.fees_paid_lamports, event.threshold_lamports
+        ),
+        signature: Some(signature.to_string()),
+    }
+}
+
+fn exposure_threshold_alert(event: &ExposureThresholdReached, signature: &str) -> Alert {
+    Alert {
+        kind: AlertKind::ExposureThreshold,
+        severity: Severity::Warning,
+        session: event.session.to_string(),
+        owner: None,
+        title: "Exposure alert".into(),
+        body: format!(
+            "Exposure at {}/{} lamports ({} bps), past your {} bps alert",
+            event.exposure, event.max_lamports, event.exposure_bps, event.threshold_bps
+        ),
+        signature: Some(signature.to_string()),
+    }
+}
+
+fn out_of_range_too_long_alert(event: &OutOfRangeTooLong, signature: &str) -> Alert {
+    Alert {
+        kind: AlertKind::OutOfRangeTooLong,
+        severity: Severity::Critical,
+        session: event.session.to_string(),
+        owner: None,
+        title: "LP position still out of range".into(),
+        body: format!(
+            "{} has been out of range for over {}m — rebalance or close it",
+            event.position,
+            event.out_of_range_secs / 60
+        ),
+        signature: Some(signature.to_string()),
+    }
+}
+
+/// Subscribe to the program's logs forever, reconnecting on drop
+pub fn events(ws_url: String, tx: Sender<Alert>) {
+    loop {
+        if let Err(err) = stream(&ws_url, &tx) {
+            eprintln!("[events] subscription ended: {err}");
+        }
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+fn stream(ws_url: &str, tx: &Sender<Alert>) -> Result<(), Box<dyn std::error::Error>> {
+    let (_subscription, receiver) = PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![PROGRAM_ID.to_string()]),
+        RpcTransactionLogsConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+        },
+    )?;
+
+    for response in receiver {
+        let logs = response.value;
+        // Failed transactions roll back their events
+        if logs.err.is_some() {
+            continue;
+        }
+        for alert in event_alerts(&logs.logs, &logs.signature) {
+            tx.send(alert)?;
+        }
+    }
+    Ok(())
+}
+
+// ── Watchdog ────────────────────────────────────────────────────────────────
+
+pub struct Watchdog {
+    pub rpc: RpcClient,
+    pub owners: Vec<Pubkey>,
+    pub interval: Duration,
+    pub device_silent_secs: i64,
+    pub expiry_warning_secs: i64,
+}
+
+impl Watchdog {
+    pub fn run(self, tx: Sender<Alert>) {
+        loop {
+            for owner in &self.owners {
+                match self.check(owner, unix_now()) {
+                    Ok(alerts) => {
+                        for alert in alerts {
+                            if tx.send(alert).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(err) => eprintln!("[watchdog] {owner}: {err}"),
+                }
+            }
+            thread::sleep(self.interval);
+        }
+    }
+
+    fn check(&self, owner: &Pubkey, now: i64) -> Result<Vec<Alert>, Box<dyn std::error::Error>> {
+        let address = pda::session(owner).0;
+        let session = accounts::decode_session(&self.rpc.get_account_data(&address)?)?;
+        if !session.is_active || session.is_expired(now) {
+            return Ok(vec![]);
+        }
+
+        let alert = |kind, severity, title: &str, body: String| Alert {
+            kind,
+            severity,
+            session: address.to_string(),
+            owner: Some(owner.to_string()),
+            title: title.into(),
+            body,
+            signature: None,
+        };
+        let mut alerts = Vec::new();
+
+        // Standby devices take over after the same kind of silence — this is
+        // the off-chain view of that condition
+        if session.primaries_silent_for(self.device_silent_secs, now) {
+            let last_seen = session
+                .devices
+                .iter()
+                .filter(|d| !d.is_empty() && !d.disabled && !d.is_standby())
+                .map(|d| d.last_seen_at)
+                .max()
+                .unwrap_or(0);
+            alerts.push(alert(
+                AlertKind::DeviceSilent,
+                Severity::Critical,
+                "Agent device offline",
+                format!("No heartbeat for {}m — positions are unmonitored", (now - last_seen) / 60),
+            ));
+        }
+
+        let remaining = session.expires_at - now;
+        if remaining <= self.expiry_warning_secs {
+            alerts.push(alert(
+                AlertKind::SessionExpiring,
+                Severity::Warning,
+                "Session expiring",
+                format!("Session expires in {}m — extend it to keep the agent running", remaining / 60),
+            ));
+        }
+        Ok(alerts)
+    }
+}
@@ -0,0 +1,164 @@
+//! Alert rules evaluated against each fresh snapshot. Alerts are
+//! edge-triggered: a condition fires once when it becomes true and again only
+//! after it has cleared.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::config::ServiceConfig;
+use crate::status::StatusSnapshot;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    SessionInactive,
+    SessionExpiring,
+    SessionExpired,
+    ExposureHigh,
+    FeeBudgetExhausted,
+    DeviceExpired,
+    DeviceDisabled,
+    DeviceLowBalance,
+    PositionOutOfRange,
+    SettlementReview,
+    SessionSuspended,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Alert {
+    pub kind: AlertKind,
+    /// Device key for device-scoped alerts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    pub message: String,
+}
+
+/// Conditions currently true, keyed by kind and device
+type Condition = (AlertKind, Option<String>);
+
+#[derive(Default)]
+pub struct AlertState {
+    active: HashSet<Condition>,
+}
+
+impl AlertState {
+    /// Alerts that became true since the previous snapshot
+    pub fn update(&mut self, snapshot: &StatusSnapshot, config: &ServiceConfig) -> Vec<Alert> {
+        let current = evaluate(snapshot, config);
+        let fired = current
+            .iter()
+            .filter(|a| !self.active.contains(&(a.kind, a.device.clone())))
+            .cloned()
+            .collect();
+        self.active = current.into_iter().map(|a| (a.kind, a.device)).collect();
+        fired
+    }
+}
+
+fn evaluate(snapshot: &StatusSnapshot, config: &ServiceConfig) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    let alert = |kind, device: Option<&str>, message: String| Alert {
+        kind,
+        device: device.map(str::to_string),
+        message,
+    };
+
+    // ── Session ─────────────────────────────────────────────────────────────
+    if let Some(s) = &snapshot.session {
+        let remaining = s.expires_at - snapshot.now;
+        if !s.is_active {
+            alerts.push(alert(AlertKind::SessionInactive, None, "Session is inactive".into()));
+        }
+        if s.expired {
+            alerts.push(alert(AlertKind::SessionExpired, None, "Session has expired".into()));
+        } else if remaining <= config.expiry_warning_secs {
+            alerts.push(alert(
+                AlertKind::SessionExpiring,
+                None,
+                format!("Session expires in {}m", remaining / 60),
+            ));
+        }
+        if s.max_lamports > 0
+            && (s.spent_lamports as u128) * 10_000
+                >= (s.max_lamports as u128) * (config.exposure_warning_bps as u128)
+        {
+            alerts.push(alert(
+                AlertKind::ExposureHigh,
+                None,
+                format!("Exposure at {}/{} lamports", s.spent_lamports, s.max_lamports),
+            ));
+        }
+        if s.fee_budget_lamports > 0 && s.fee_spent_lamports >= s.fee_budget_lamports {
+            alerts.push(alert(
+                AlertKind::FeeBudgetExhausted,
+                None,
+                format!("Fee budget of {} lamports used up", s.fee_budget_lamports),
+            ));
+        }
+        if s.tx_fee_budget_lamports > 0 && s.tx_fees_lamports >= s.tx_fee_budget_lamports {
+            alerts.push(alert(
+                AlertKind::FeeBudgetExhausted,
+                None,
+                format!("Transaction fee budget of {} lamports used up", s.tx_fee_budget_lamports),
+            ));
+        }
+        if s.needs_review {
+            alerts.push(alert(
+                AlertKind::SettlementReview,
+                None,
+                "A position close diverged from its monitor checkpoint — review and acknowledge".into(),
+            ));
+        }
+        if s.suspended {
+            alerts.push(alert(
+                AlertKind::SessionSuspended,
+                None,
+                format!(
+                    "Suspended after {} consecutive scope violations — check the devices",
+                    s.consecutive_violations
+                ),
+            ));
+        }
+
+        // ── Devices ─────────────────────────────────────────────────────────
+        for d in &s.devices {
+            if d.disabled {
+                alerts.push(alert(AlertKind::DeviceDisabled, Some(&d.key), format!("Device {} disabled", d.key)));
+                continue;
+            }
+            if d.expired {
+                alerts.push(alert(AlertKind::DeviceExpired, Some(&d.key), format!("Device {} expired", d.key)));
+            }
+            if d.balance_lamports < config.low_balance_lamports {
+                alerts.push(alert(
+                    AlertKind::DeviceLowBalance,
+                    Some(&d.key),
+                    format!("Device {} balance {} lamports — top up for fees", d.key, d.balance_lamports),
+                ));
+            }
+        }
+    }
+
+    // ── LP monitor ──────────────────────────────────────────────────────────
+    if let Some(m) = &snapshot.monitor {
+        if !m.is_in_range && m.last_checked_at > 0 {
+            alerts.push(alert(
+                AlertKind::PositionOutOfRange,
+                None,
+                format!(
+                    "Position out of range{}: active bin {} outside [{}, {}]",
+                    match m.out_of_range_since {
+                        0 => String::new(),
+                        since => format!(" for {}m", (snapshot.now - since).max(0) / 60),
+                    },
+                    m.last_active_bin,
+                    m.min_bin_id,
+                    m.max_bin_id
+                ),
+            ));
+        }
+    }
+
+    alerts
+}
@@ -0,0 +1,50 @@
+//! Service configuration, loaded from the environment.
+
+use std::env;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+pub struct ServiceConfig {
+    /// Base-layer RPC endpoint
+    pub rpc_url: String,
+    /// Address the HTTP / WebSocket server binds to
+    pub listen_addr: SocketAddr,
+    /// How often each WebSocket subscriber's state is re-read
+    pub poll_interval: Duration,
+    /// Alert once a session is this close to expiry
+    pub expiry_warning_secs: i64,
+    /// Alert once spent / max exposure reaches this many basis points
+    pub exposure_warning_bps: u64,
+    /// Alert when a device key's SOL balance drops below this
+    pub low_balance_lamports: u64,
+}
+
+fn parsed<T: std::str::FromStr>(key: &str, default: T) -> Result<T, String> {
+    match env::var(key) {
+        Ok(v) => v.parse().map_err(|_| format!("{key} is not valid: {v}")),
+        Err(_) => Ok(default),
+    }
+}
+
+impl ServiceConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let poll_ms: u64 = parsed("POLL_INTERVAL_MS", 5_000)?;
+        if !(1_000..=600_000).contains(&poll_ms) {
+            return Err("POLL_INTERVAL_MS must be a number between 1000 and 600000".into());
+        }
+        let exposure_warning_bps: u64 = parsed("EXPOSURE_WARNING_BPS", 9_000)?;
+        if exposure_warning_bps > 10_000 {
+            return Err("EXPOSURE_WARNING_BPS must be at most 10000".into());
+        }
+
+        Ok(Self {
+            rpc_url: env::var("SOLANA_RPC_URL")
+                .unwrap_or_else(|_| "https://api.devnet.solana.com".into()),
+            listen_addr: parsed("LISTEN_ADDR", SocketAddr::from(([0, 0, 0, 0], 8080)))?,
+            poll_interval: Duration::from_millis(poll_ms),
+            expiry_warning_secs: parsed("EXPIRY_WARNING_SECS", 3_600)?,
+            exposure_warning_bps,
+            low_balance_lamports: parsed("LOW_BALANCE_LAMPORTS", 10_000_000)?,
+        })
+    }
+}
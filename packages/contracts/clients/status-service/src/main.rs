@@ -0,0 +1,58 @@
+//! status-service — one typed API over session, vault and monitor state.
+//!
+//! The mobile app and the ESP32 both read from here instead of issuing raw
+//! RPC calls and decoding accounts themselves. Plain HTTP for one-off reads,
+//! a WebSocket per owner for live snapshots and alerts (session expiring,
+//! exposure near the cap, device revoked / low on SOL, position out of range).
+//! See `routes.rs` for the endpoints and frame format.
+//!
+//! JSON over HTTP / WebSocket rather than gRPC: the ESP32's esp-idf HTTP stack
+//! speaks WebSocket natively, while gRPC would need HTTP/2 and protobuf on-device.
+//!
+//! Usage:
+//!   SOLANA_RPC_URL=https://api.devnet.solana.com cargo run -p status-service
+
+mod alerts;
+mod config;
+mod routes;
+mod status;
+
+use std::sync::Arc;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use config::ServiceConfig;
+use routes::AppState;
+
+#[tokio::main]
+async fn main() {
+    let config = match ServiceConfig::from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("[fatal] {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("[init] rpc      : {}", config.rpc_url);
+    println!("[init] listen   : {}", config.listen_addr);
+    println!("[init] poll     : {}ms", config.poll_interval.as_millis());
+
+    let listener = match tokio::net::TcpListener::bind(config.listen_addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("[fatal] bind {}: {err}", config.listen_addr);
+            std::process::exit(1);
+        }
+    };
+
+    let state = AppState {
+        rpc: Arc::new(RpcClient::new(config.rpc_url.clone())),
+        config: Arc::new(config),
+    };
+
+    if let Err(err) = axum::serve(listener, routes::router(state)).await {
+        eprintln!("[fatal] server: {err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,173 @@
+//! HTTP and WebSocket endpoints.
+//!
+//!   GET /v1/vault                    protocol fee vault
+//!   GET /v1/sessions/:owner          session + device keys
+//!   GET /v1/sessions/:owner/monitor  LP position monitor
+//!   GET /v1/sessions/:owner/status   all of the above in one snapshot
+//!   GET /v1/sessions/:owner/ws       live snapshots + alerts
+//!
+//! WebSocket frames are JSON: `{"type":"status","data":<snapshot>}` whenever
+//! on-chain state changes (and once on connect), `{"type":"alert","data":<alert>}`
+//! when an alert condition becomes true.
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use crate::alerts::{Alert, AlertState};
+use crate::config::ServiceConfig;
+use crate::status::{self, StatusSnapshot};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub rpc: Arc<RpcClient>,
+    pub config: Arc<ServiceConfig>,
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/v1/vault", get(vault))
+        .route("/v1/sessions/:owner", get(session))
+        .route("/v1/sessions/:owner/monitor", get(monitor))
+        .route("/v1/sessions/:owner/status", get(snapshot))
+        .route("/v1/sessions/:owner/ws", get(subscribe))
+        .with_state(state)
+}
+
+// ── Errors ──────────────────────────────────────────────────────────────────
+
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(&'static str),
+    Rpc(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            ApiError::NotFound(what) => (StatusCode::NOT_FOUND, format!("{what} not found")),
+            ApiError::Rpc(msg) => (StatusCode::BAD_GATEWAY, msg),
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for ApiError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        ApiError::Rpc(err.to_string())
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+fn parse_owner(owner: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(owner).map_err(|_| ApiError::BadRequest(format!("invalid owner pubkey: {owner}")))
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// ── Queries ─────────────────────────────────────────────────────────────────
+
+async fn vault(State(state): State<AppState>) -> ApiResult<status::VaultView> {
+    Ok(Json(status::vault(&state.rpc).await?))
+}
+
+async fn session(State(state): State<AppState>, Path(owner): Path<String>) -> ApiResult<status::SessionView> {
+    let owner = parse_owner(&owner)?;
+    status::session(&state.rpc, &owner, unix_now())
+        .await?
+        .map(Json)
+        .ok_or(ApiError::NotFound("session"))
+}
+
+async fn monitor(State(state): State<AppState>, Path(owner): Path<String>) -> ApiResult<status::MonitorView> {
+    let owner = parse_owner(&owner)?;
+    status::monitor(&state.rpc, &owner)
+        .await?
+        .map(Json)
+        .ok_or(ApiError::NotFound("monitor"))
+}
+
+async fn snapshot(State(state): State<AppState>, Path(owner): Path<String>) -> ApiResult<StatusSnapshot> {
+    let owner = parse_owner(&owner)?;
+    Ok(Json(status::snapshot(&state.rpc, &owner, unix_now()).await?))
+}
+
+// ── Live updates ────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+enum Push<'a> {
+    Status(&'a StatusSnapshot),
+    Alert(&'a Alert),
+}
+
+async fn subscribe(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Path(owner): Path<String>,
+) -> Result<Response, ApiError> {
+    let owner = parse_owner(&owner)?;
+    Ok(ws.on_upgrade(move |socket| stream(socket, state, owner)))
+}
+
+async fn send(socket: &mut WebSocket, push: Push<'_>) -> bool {
+    let text = serde_json::to_string(&push).expect("serializable push");
+    socket.send(Message::Text(text)).await.is_ok()
+}
+
+/// Poll the owner's state every `poll_interval` until the client disconnects
+async fn stream(mut socket: WebSocket, state: AppState, owner: Pubkey) {
+    let mut interval = tokio::time::interval(state.config.poll_interval);
+    let mut alerts = AlertState::default();
+    let mut last: Option<StatusSnapshot> = None;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return,
+                Some(Ok(_)) => continue,
+            },
+        }
+
+        let snapshot = match status::snapshot(&state.rpc, &owner, unix_now()).await {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                eprintln!("[ws {owner}] {err}");
+                continue;
+            }
+        };
+
+        // `now` moves every tick — only push when the chain state moved
+        let changed = last.as_ref().map_or(true, |prev| {
+            (&prev.session, &prev.monitor, &prev.vault) != (&snapshot.session, &snapshot.monitor, &snapshot.vault)
+        });
+        if changed && !send(&mut socket, Push::Status(&snapshot)).await {
+            return;
+        }
+        for alert in alerts.update(&snapshot, &state.config) {
+            if !send(&mut socket, Push::Alert(&alert)).await {
+                return;
+            }
+        }
+        last = Some(snapshot);
+    }
+}
@@ -0,0 +1,273 @@
+//! Typed, JSON-serializable views of a session and everything hanging off it,
+//! read over RPC and decoded with the client SDK.
+
+use anchor_lang::prelude::Pubkey;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use defi_agent::state::{AgentSession, LpPositionMonitor, STRATEGY_COUNT};
+use defi_agent_client::{accounts, pda, DELEGATION_PROGRAM_ID};
+
+pub type StatusResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct DeviceView {
+    pub key: String,
+    pub slot: u8,
+    pub expires_at: i64,
+    pub max_lamports: u64,
+    pub spent_lamports: u64,
+    pub disabled: bool,
+    pub expired: bool,
+    pub standby: bool,
+    pub last_seen_at: i64,
+    /// SOL balance of the device key (it pays its own transaction fees)
+    pub balance_lamports: u64,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct SessionView {
+    pub address: String,
+    pub owner: String,
+    /// Owner-set display name and off-chain metadata URI; empty when unset
+    pub label: String,
+    pub metadata_uri: String,
+    /// Relayer paying the devices' transaction fees; `None` when unset
+    pub fee_payer: Option<String>,
+    pub is_active: bool,
+    /// True while the session account is delegated to the Ephemeral Rollup
+    pub delegated: bool,
+    pub expires_at: i64,
+    pub expired: bool,
+    pub max_lamports: u64,
+    pub spent_lamports: u64,
+    pub max_action_lamports: u64,
+    pub strategy_mask: u8,
+    pub total_actions: u64,
+    pub last_action_at: i64,
+    pub fee_budget_lamports: u64,
+    pub fee_spent_lamports: u64,
+    /// Signature fees counted on the session, and their cap (0 = uncapped)
+    pub tx_fees_lamports: u64,
+    pub tx_fee_budget_lamports: u64,
+    /// Realized swap slippage in Y units, and the per-swap alert bound (0 = off)
+    pub slippage_paid: u64,
+    pub max_slippage_bps: u16,
+    /// Estimated DLMM pool swap fees in Y units, and their cap (0 = uncapped)
+    pub swap_fees_paid: u64,
+    pub swap_fee_budget: u64,
+    /// Actions run on the ER since the last commit, and the cadence limits
+    /// that force a commit (0 = no limit)
+    pub uncommitted_actions: u32,
+    pub max_uncommitted_actions: u32,
+    pub max_uncommitted_secs: i64,
+    pub last_committed_at: i64,
+    /// `commit_session` checkpoints over the session's lifetime
+    pub checkpoint_count: u64,
+    /// ER slot of the last delegated action, and the idle window after which
+    /// the next one deactivates the session (0 = off)
+    pub er_last_action_slot: u64,
+    pub max_idle_slots: u64,
+    /// Stable mint caps and exposure are counted in; empty = raw amounts
+    pub budget_mint: String,
+    /// A close diverged from its monitor checkpoint; cleared by the owner
+    pub needs_review: bool,
+    /// Consecutive scope violations, the count that suspends (0 = freeze off),
+    /// and whether it was reached
+    pub consecutive_violations: u8,
+    pub violation_threshold: u8,
+    pub suspended: bool,
+    /// Execution is cooling down after hitting the exposure cap until this time
+    pub exposure_cooldown_until: i64,
+    pub protocol_fees_paid: u64,
+    /// Mint the PnL fields are denominated in; empty before the first valuation
+    pub valuation_mint: String,
+    pub entry_value: u64,
+    pub last_value: u64,
+    /// Realized PnL since the entry valuation, in `valuation_mint` base units
+    pub realized_pnl: i64,
+    pub last_valued_at: i64,
+    /// Lifetime LP fees earned, in the pools' X / Y token base units
+    pub total_fees_earned_x: u64,
+    pub total_fees_earned_y: u64,
+    /// `spent_lamports` split into LP (DLMM included) / yield / liquidation protection
+    pub spent_by_strategy: [u64; STRATEGY_COUNT],
+    /// Outstanding exposure at market value, and whether the cap uses it
+    pub current_exposure_value: u64,
+    pub exposure_valued_at: i64,
+    pub mark_to_market: bool,
+    pub devices: Vec<DeviceView>,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct MonitorView {
+    pub address: String,
+    pub lb_pair: String,
+    pub position: String,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    pub last_active_bin: i32,
+    pub is_in_range: bool,
+    pub fee_x_snapshot: u64,
+    pub fee_y_snapshot: u64,
+    pub amount_x_snapshot: u64,
+    pub amount_y_snapshot: u64,
+    pub last_checked_at: i64,
+    /// Start of the current out-of-range excursion; 0 while in range
+    pub out_of_range_since: i64,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct VaultView {
+    /// Protocol fee vault PDA
+    pub fee_vault: String,
+    pub fee_vault_lamports: u64,
+}
+
+/// Everything the app shows for one owner; pushed whole over the WebSocket
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct StatusSnapshot {
+    pub now: i64,
+    pub session: Option<SessionView>,
+    pub monitor: Option<MonitorView>,
+    pub vault: VaultView,
+}
+
+/// Account data and owner program, or `None` if the account does not exist
+async fn fetch(rpc: &RpcClient, key: &Pubkey) -> StatusResult<Option<(Vec<u8>, Pubkey)>> {
+    let account = rpc.get_account_with_commitment(key, rpc.commitment()).await?.value;
+    Ok(account.map(|a| (a.data, a.owner)))
+}
+
+pub async fn session(rpc: &RpcClient, owner: &Pubkey, now: i64) -> StatusResult<Option<SessionView>> {
+    let address = pda::session(owner).0;
+    let Some((data, program_owner)) = fetch(rpc, &address).await? else {
+        return Ok(None);
+    };
+    let session = accounts::decode_session(&data)?;
+    let devices = devices(rpc, &session, now).await?;
+
+    Ok(Some(SessionView {
+        address: address.to_string(),
+        owner: session.owner.to_string(),
+        label: session.label().to_string(),
+        metadata_uri: session.metadata_uri().to_string(),
+        fee_payer: (session.fee_payer != Pubkey::default()).then(|| session.fee_payer.to_string()),
+        is_active: session.is_active,
+        delegated: program_owner == DELEGATION_PROGRAM_ID,
+        expires_at: session.expires_at,
+        expired: session.is_expired(now),
+        max_lamports: session.max_lamports,
+        spent_lamports: session.spent_lamports,
+        max_action_lamports: session.max_action_lamports,
+        strategy_mask: session.strategy_mask,
+        total_actions: session.total_actions,
+        last_action_at: session.last_action_at,
+        fee_budget_lamports: session.fee_budget_lamports,
+        fee_spent_lamports: session.fee_spent_lamports,
+        tx_fees_lamports: session.tx_fees_lamports,
+        tx_fee_budget_lamports: session.tx_fee_budget_lamports,
+        slippage_paid: session.slippage_paid,
+        max_slippage_bps: session.max_slippage_bps,
+        swap_fees_paid: session.swap_fees_paid,
+        swap_fee_budget: session.swap_fee_budget,
+        uncommitted_actions: session.uncommitted_actions,
+        max_uncommitted_actions: session.max_uncommitted_actions,
+        max_uncommitted_secs: session.max_uncommitted_secs,
+        last_committed_at: session.last_committed_at,
+        checkpoint_count: session.checkpoint_count,
+        er_last_action_slot: session.er_last_action_slot,
+        max_idle_slots: session.max_idle_slots,
+        budget_mint: if session.budget_mint == Pubkey::default() {
+            String::new()
+        } else {
+            session.budget_mint.to_string()
+        },
+        needs_review: session.needs_review,
+        consecutive_violations: session.consecutive_violations,
+        violation_threshold: session.violation_threshold,
+        suspended: session.suspended,
+        exposure_cooldown_until: session.exposure_cooldown_until,
+        protocol_fees_paid: session.protocol_fees_paid,
+        valuation_mint: if session.valuation_mint == Pubkey::default() {
+            String::new()
+        } else {
+            session.valuation_mint.to_string()
+        },
+        entry_value: session.entry_value,
+        last_value: session.last_value,
+        realized_pnl: session.realized_pnl,
+        last_valued_at: session.last_valued_at,
+        total_fees_earned_x: session.total_fees_earned_x,
+        total_fees_earned_y: session.total_fees_earned_y,
+        spent_by_strategy: session.spent_by_strategy,
+        current_exposure_value: session.current_exposure_value,
+        exposure_valued_at: session.exposure_valued_at,
+        mark_to_market: session.mark_to_market,
+        devices,
+    }))
+}
+
+async fn devices(rpc: &RpcClient, session: &AgentSession, now: i64) -> StatusResult<Vec<DeviceView>> {
+    let mut views = Vec::new();
+    for (slot, device) in session.devices.iter().enumerate() {
+        if device.is_empty() {
+            continue;
+        }
+        views.push(DeviceView {
+            key: device.key.to_string(),
+            slot: slot as u8,
+            expires_at: device.expires_at,
+            max_lamports: device.max_lamports,
+            spent_lamports: device.spent_lamports,
+            disabled: device.disabled,
+            expired: device.is_expired(now),
+            standby: device.is_standby(),
+            last_seen_at: device.last_seen_at,
+            balance_lamports: rpc.get_balance(&device.key).await?,
+        });
+    }
+    Ok(views)
+}
+
+pub async fn monitor(rpc: &RpcClient, owner: &Pubkey) -> StatusResult<Option<MonitorView>> {
+    let session = pda::session(owner).0;
+    let address = pda::lp_monitor(&session).0;
+    let Some((data, _)) = fetch(rpc, &address).await? else {
+        return Ok(None);
+    };
+    let m: LpPositionMonitor = accounts::decode_lp_monitor(&data)?;
+
+    Ok(Some(MonitorView {
+        address: address.to_string(),
+        lb_pair: m.lb_pair.to_string(),
+        position: m.position.to_string(),
+        min_bin_id: m.min_bin_id,
+        max_bin_id: m.max_bin_id,
+        last_active_bin: m.last_active_bin,
+        is_in_range: m.is_in_range == 1,
+        fee_x_snapshot: m.fee_x_snapshot,
+        fee_y_snapshot: m.fee_y_snapshot,
+        amount_x_snapshot: m.amount_x_snapshot,
+        amount_y_snapshot: m.amount_y_snapshot,
+        last_checked_at: m.last_checked_at,
+        out_of_range_since: m.out_of_range_since,
+    }))
+}
+
+pub async fn vault(rpc: &RpcClient) -> StatusResult<VaultView> {
+    let fee_vault = pda::fee_vault().0;
+    Ok(VaultView {
+        fee_vault: fee_vault.to_string(),
+        fee_vault_lamports: rpc.get_balance(&fee_vault).await?,
+    })
+}
+
+pub async fn snapshot(rpc: &RpcClient, owner: &Pubkey, now: i64) -> StatusResult<StatusSnapshot> {
+    Ok(StatusSnapshot {
+        now,
+        session: session(rpc, owner, now).await?,
+        monitor: monitor(rpc, owner).await?,
+        vault: vault(rpc).await?,
+    })
+}
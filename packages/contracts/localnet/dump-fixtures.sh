@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+# Clone the Meteora DLMM program binary from mainnet into the test fixtures.
+# Run once (and again whenever Meteora upgrades the program):
+#   ./dump-fixtures.sh
+set -euo pipefail
+cd "$(dirname "$0")"
+
+DLMM_PROGRAM_ID="LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo"
+RPC_URL="${MAINNET_RPC_URL:-https://api.mainnet-beta.solana.com}"
+
+solana program dump -u "$RPC_URL" "$DLMM_PROGRAM_ID" tests/fixtures/lb_clmm.so
+echo "wrote tests/fixtures/lb_clmm.so"
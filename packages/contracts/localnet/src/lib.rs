@@ -0,0 +1,380 @@
+//! Localnet harness: defi-agent + the real Meteora DLMM program inside
+//! `solana-program-test`, so the CPI paths run end to end without devnet.
+//!
+//! Setup:
+//!   anchor build                 # target/deploy/defi_agent.so
+//!   ./dump-fixtures.sh           # tests/fixtures/lb_clmm.so (cloned from mainnet)
+//!   cargo test -p defi-agent-localnet
+//!
+//! Both programs are loaded as upgradeable programs; defi-agent's upgrade
+//! authority is `Harness::admin`, so `initialize_config` runs unmodified.
+
+pub mod pool;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, spl_associated_token_account};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{metadata_pointer, ExtensionType, StateWithExtensions};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::system_program;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+use defi_agent::dlmm::accounts::{LbPair, PositionV2};
+use defi_agent::errors::AgentError;
+use defi_agent::state::RISK_TIER_LOW;
+use defi_agent_client::{instructions, pda};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+const DEFI_AGENT_SO: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../target/deploy/defi_agent.so");
+const DLMM_SO: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lb_clmm.so");
+
+fn read_program(path: &str, hint: &str) -> Vec<u8> {
+    std::fs::read(path).unwrap_or_else(|e| panic!("{path}: {e} — run `{hint}` first"))
+}
+
+/// Install `elf` as an upgradeable program (Program + ProgramData accounts)
+fn add_upgradeable_program(pt: &mut ProgramTest, program_id: Pubkey, elf: &[u8], authority: Option<Pubkey>) {
+    let loader = bpf_loader_upgradeable::id();
+    let programdata_address = Pubkey::find_program_address(&[program_id.as_ref()], &loader).0;
+    let rent = Rent::default();
+
+    let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: authority,
+    })
+    .unwrap();
+    programdata.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    programdata.extend_from_slice(elf);
+
+    let program = bincode::serialize(&UpgradeableLoaderState::Program { programdata_address }).unwrap();
+
+    pt.add_account(
+        programdata_address,
+        Account {
+            lamports: rent.minimum_balance(programdata.len()),
+            data: programdata,
+            owner: loader,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    pt.add_account(
+        program_id,
+        Account {
+            lamports: rent.minimum_balance(program.len()),
+            data: program,
+            owner: loader,
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Assert a transaction failed with `expected`
+pub fn assert_agent_error(result: Result<(), BanksClientError>, expected: AgentError) {
+    let code = anchor_lang::error::ERROR_CODE_OFFSET + expected as u32;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected {expected:?} ({code}), got {actual}"),
+        other => panic!("expected {expected:?}, got {other:?}"),
+    }
+}
+
+/// Assert a transaction failed with Anchor's own `expected` (an account
+/// constraint, say) rather than an `AgentError`
+pub fn assert_anchor_error(result: Result<(), BanksClientError>, expected: anchor_lang::error::ErrorCode) {
+    let code = expected as u32;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected {expected:?} ({code}), got {actual}"),
+        other => panic!("expected {expected:?}, got {other:?}"),
+    }
+}
+
+pub struct Harness {
+    pub ctx: ProgramTestContext,
+    /// defi-agent upgrade authority — becomes the Config admin
+    pub admin: Keypair,
+}
+
+impl Harness {
+    /// Boot a bank with both programs loaded and a funded admin
+    pub async fn start() -> Self {
+        let admin = Keypair::new();
+        let mut pt = ProgramTest::default();
+        pt.prefer_bpf(true);
+
+        add_upgradeable_program(
+            &mut pt,
+            defi_agent::ID,
+            &read_program(DEFI_AGENT_SO, "anchor build"),
+            Some(admin.pubkey()),
+        );
+        add_upgradeable_program(
+            &mut pt,
+            defi_agent::dlmm::ID,
+            &read_program(DLMM_SO, "./dump-fixtures.sh"),
+            None,
+        );
+        pt.add_account(
+            admin.pubkey(),
+            Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+
+        Self { ctx: pt.start_with_context().await, admin }
+    }
+
+    // ── Transactions ────────────────────────────────────────────────────────
+
+    /// Send `ixs` paid by the bank payer; `signers` are any extra signers
+    pub async fn send(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let blockhash = self.ctx.get_new_latest_blockhash().await?;
+        let mut all: Vec<&Keypair> = vec![&self.ctx.payer];
+        all.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(ixs, Some(&self.ctx.payer.pubkey()), &all, blockhash);
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// Build an instruction from Anchor's generated accounts / args structs for any program
+    pub fn ix(program_id: Pubkey, accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts.to_account_metas(None),
+            data: args.data(),
+        }
+    }
+
+    pub async fn fund(&mut self, to: &Pubkey, lamports: u64) {
+        let ix = system_instruction::transfer(&self.ctx.payer.pubkey(), to, lamports);
+        self.send(&[ix], &[]).await.expect("fund");
+    }
+
+    /// The bank clock's unix timestamp
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp
+    }
+
+    /// Move the bank clock forward by `secs`
+    pub async fn advance_clock(&mut self, secs: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.expect("clock");
+        clock.unix_timestamp += secs;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    // ── Account reads ───────────────────────────────────────────────────────
+
+    pub async fn data(&mut self, key: &Pubkey) -> Option<Vec<u8>> {
+        self.ctx.banks_client.get_account(*key).await.expect("get_account").map(|a| a.data)
+    }
+
+    pub async fn lamports(&mut self, key: &Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(*key).await.expect("get_balance")
+    }
+
+    /// Deserialize a Borsh (`#[account]`) account
+    pub async fn account<T: AccountDeserialize>(&mut self, key: &Pubkey) -> T {
+        let data = self.data(key).await.unwrap_or_else(|| panic!("missing account {key}"));
+        T::try_deserialize(&mut data.as_slice()).expect("deserialize")
+    }
+
+    /// Decode a zero-copy account
+    pub async fn zero_copy<T: bytemuck::Pod + Discriminator>(&mut self, key: &Pubkey) -> T {
+        let data = self.data(key).await.unwrap_or_else(|| panic!("missing account {key}"));
+        defi_agent_client::accounts::decode_zero_copy(&data).expect("decode")
+    }
+
+    /// Write a bare DLMM `LbPair` at `lb_pair` sitting at `active_id` — for
+    /// monitor tests that need a pool's active bin but no liquidity
+    pub fn set_active_bin(&mut self, lb_pair: &Pubkey, active_id: i32) {
+        let mut pair: LbPair = bytemuck::Zeroable::zeroed();
+        pair.active_id = active_id;
+        let mut data = LbPair::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&pair));
+        let account = Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: defi_agent::dlmm::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.ctx.set_account(lb_pair, &account.into());
+    }
+
+    /// Write a bare DLMM `PositionV2` at `position` in `lb_pair`, owned by
+    /// `owner` — for tests that check who holds a position but never touch
+    /// its liquidity
+    pub fn set_position(&mut self, position: &Pubkey, lb_pair: &Pubkey, owner: &Pubkey) {
+        let mut state: PositionV2 = bytemuck::Zeroable::zeroed();
+        state.lb_pair = *lb_pair;
+        state.owner = *owner;
+        let mut data = PositionV2::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&state));
+        let account = Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: defi_agent::dlmm::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.ctx.set_account(position, &account.into());
+    }
+
+    pub async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        let data = self.data(token_account).await.expect("token account");
+        // Token-2022 accounts are the classic layout plus extensions
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data).expect("unpack").base.amount
+    }
+
+    /// Program owning `key` — for a mint or token account, its token program
+    pub async fn owner(&mut self, key: &Pubkey) -> Pubkey {
+        let account = self.ctx.banks_client.get_account(*key).await.expect("get_account");
+        account.unwrap_or_else(|| panic!("missing account {key}")).owner
+    }
+
+    // ── SPL fixtures ────────────────────────────────────────────────────────
+
+    /// New 6-decimal classic SPL mint with the bank payer as authority
+    pub async fn create_mint(&mut self) -> Pubkey {
+        self.create_mint_with_program(&spl_token::ID).await
+    }
+
+    /// New 6-decimal mint owned by `token_program`. Token-2022 mints carry a
+    /// metadata pointer to themselves — an extension DLMM pools accept without
+    /// a token badge — so fixtures exercise the extended account layout.
+    pub async fn create_mint_with_program(&mut self, token_program: &Pubkey) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.ctx.payer.pubkey();
+        let mut ixs = Vec::new();
+        let extensions: &[ExtensionType] =
+            if *token_program == spl_token_2022::ID { &[ExtensionType::MetadataPointer] } else { &[] };
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions).unwrap();
+        ixs.push(system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            Rent::default().minimum_balance(space),
+            space as u64,
+            token_program,
+        ));
+        if !extensions.is_empty() {
+            ixs.push(
+                metadata_pointer::instruction::initialize(
+                    token_program,
+                    &mint.pubkey(),
+                    Some(payer),
+                    Some(mint.pubkey()),
+                )
+                .unwrap(),
+            );
+        }
+        ixs.push(
+            spl_token_2022::instruction::initialize_mint2(token_program, &mint.pubkey(), &payer, None, 6).unwrap(),
+        );
+        self.send(&ixs, &[&mint]).await.expect("create_mint");
+        mint.pubkey()
+    }
+
+    /// Create `owner`'s ATA for `mint` under the mint's token program, unless
+    /// it exists, and mint `amount` into it
+    pub async fn create_ata(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let payer = self.ctx.payer.pubkey();
+        let token_program = self.owner(mint).await;
+        let ata = get_associated_token_address_with_program_id(owner, mint, &token_program);
+        let mut ixs = vec![spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &payer,
+            owner,
+            mint,
+            &token_program,
+        )];
+        if amount > 0 {
+            ixs.push(
+                spl_token_2022::instruction::mint_to(&token_program, mint, &ata, &payer, &[], amount).unwrap(),
+            );
+        }
+        self.send(&ixs, &[]).await.expect("create_ata");
+        ata
+    }
+
+    // ── Program fixtures ────────────────────────────────────────────────────
+
+    /// `initialize_config` signed by the upgrade authority
+    pub async fn initialize_config(&mut self) {
+        let programdata =
+            Pubkey::find_program_address(&[defi_agent::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
+        let ix = Self::ix(
+            defi_agent::ID,
+            defi_agent::accounts::InitializeConfig {
+                admin: self.admin.pubkey(),
+                config: pda::config().0,
+                program: defi_agent::ID,
+                program_data: programdata,
+                system_program: system_program::ID,
+            },
+            defi_agent::instruction::InitializeConfig {
+                protocol_fee_bps: 0,
+                default_max_lamports: LAMPORTS_PER_SOL,
+                default_duration_secs: 86_400,
+            },
+        );
+        let admin = self.admin.insecure_clone();
+        self.send(&[ix], &[&admin]).await.expect("initialize_config");
+    }
+
+    /// Create the global PoolRegistry holding `pools` (low risk tier)
+    pub async fn initialize_pool_registry(&mut self, pools: &[Pubkey]) {
+        let mut ixs = vec![Self::ix(
+            defi_agent::ID,
+            defi_agent::accounts::InitializePoolRegistry {
+                admin: self.admin.pubkey(),
+                config: pda::config().0,
+                pool_registry: pda::pool_registry().0,
+                system_program: system_program::ID,
+            },
+            defi_agent::instruction::InitializePoolRegistry {},
+        )];
+        ixs.extend(
+            pools.iter().map(|lb_pair| instructions::add_registry_pool(self.admin.pubkey(), *lb_pair, RISK_TIER_LOW)),
+        );
+        let admin = self.admin.insecure_clone();
+        self.send(&ixs, &[&admin]).await.expect("initialize_pool_registry");
+    }
+
+    /// Fund `owner` and `device`, then create `owner`'s session with `device`
+    /// as its primary key. Returns the session PDA.
+    pub async fn create_session(&mut self, owner: &Keypair, device: &Keypair, strategy_mask: u8, max_lamports: u64) -> Pubkey {
+        self.fund(&owner.pubkey(), LAMPORTS_PER_SOL).await;
+        self.fund(&device.pubkey(), LAMPORTS_PER_SOL).await;
+        let ix = instructions::initialize_session(
+            owner.pubkey(),
+            instructions::InitializeSessionArgs {
+                session_key: device.pubkey(),
+                duration_secs: 86_400,
+                max_lamports,
+                strategy_mask,
+                max_action_lamports: 0,
+                referrer: None,
+                fee_tier: 0,
+                attestation_hash: [0; 32],
+                valuation: None,
+                previous_session: None,
+            },
+        );
+        self.send(&[ix], &[owner]).await.expect("initialize_session");
+        pda::session(&owner.pubkey()).0
+    }
+}
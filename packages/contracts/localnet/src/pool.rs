@@ -0,0 +1,210 @@
+//! Meteora DLMM pool fixtures, created through the real DLMM program.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::pubkey;
+use anchor_spl::token::spl_token;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+
+use defi_agent::dlmm::client::{accounts, args};
+use defi_agent::dlmm::types::{
+    CustomizableParams, LiquidityParameterByStrategy, StrategyParameters, StrategyType,
+};
+use defi_agent::dlmm::ID as DLMM_PROGRAM_ID;
+use defi_agent_client::dlmm::{self as dlmm_pda, DLMM_EVENT_AUTHORITY};
+
+use crate::Harness;
+
+/// Base key of customizable permissionless pools (`ILM_BASE` in the DLMM SDK)
+const ILM_BASE: Pubkey = pubkey!("MFGQxwAmB91SwuYX36okv2Qmdc9aMuHTwWGUrp4AtB1");
+
+/// Addresses of a pool created by [`Pool::create`]
+pub struct Pool {
+    pub lb_pair: Pubkey,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub oracle: Pubkey,
+    pub token_x_program: Pubkey,
+    pub token_y_program: Pubkey,
+}
+
+impl Pool {
+    /// Two fresh 6-decimal mints and a customizable permissionless pool over
+    /// them at `active_id` (bin step 10, base factor 4000 — the devnet preset
+    /// the TS suite uses).
+    pub async fn create(h: &mut Harness, active_id: i32) -> Self {
+        Self::create_with_program(h, active_id, &spl_token::ID).await
+    }
+
+    /// [`Pool::create`] over two mints owned by `token_program` — pass
+    /// `spl_token_2022::ID` for a Token-2022 pool.
+    pub async fn create_with_program(h: &mut Harness, active_id: i32, token_program: &Pubkey) -> Self {
+        let a = h.create_mint_with_program(token_program).await;
+        let b = h.create_mint_with_program(token_program).await;
+        Self::create_over(h, active_id, a, b).await
+    }
+
+    /// [`Pool::create`] over two existing mints of the same token program —
+    /// for pools sharing a mint with another, as a two-hop route needs
+    pub async fn create_over(h: &mut Harness, active_id: i32, a: Pubkey, b: Pubkey) -> Self {
+        let token_program = h.owner(&a).await;
+        // DLMM orders mints by pubkey bytes — smaller is token X
+        let (mint_x, mint_y) = if a.to_bytes() < b.to_bytes() { (a, b) } else { (b, a) };
+
+        let payer = h.ctx.payer.pubkey();
+        let user_token_x = h.create_ata(&mint_x, &payer, 0).await;
+        let user_token_y = h.create_ata(&mint_y, &payer, 0).await;
+
+        let lb_pair = Pubkey::find_program_address(
+            &[ILM_BASE.as_ref(), mint_x.as_ref(), mint_y.as_ref()],
+            &DLMM_PROGRAM_ID,
+        )
+        .0;
+        let reserve_x = Pubkey::find_program_address(&[lb_pair.as_ref(), mint_x.as_ref()], &DLMM_PROGRAM_ID).0;
+        let reserve_y = Pubkey::find_program_address(&[lb_pair.as_ref(), mint_y.as_ref()], &DLMM_PROGRAM_ID).0;
+        let oracle = Pubkey::find_program_address(&[b"oracle", lb_pair.as_ref()], &DLMM_PROGRAM_ID).0;
+
+        let ix = Harness::ix(
+            DLMM_PROGRAM_ID,
+            accounts::InitializeCustomizablePermissionlessLbPair2 {
+                lb_pair,
+                bin_array_bitmap_extension: None,
+                token_mint_x: mint_x,
+                token_mint_y: mint_y,
+                reserve_x,
+                reserve_y,
+                oracle,
+                user_token_x,
+                funder: payer,
+                token_badge_x: None,
+                token_badge_y: None,
+                token_program_x: token_program,
+                token_program_y: token_program,
+                system_program: system_program::ID,
+                user_token_y,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                program: DLMM_PROGRAM_ID,
+            },
+            args::InitializeCustomizablePermissionlessLbPair2 {
+                params: CustomizableParams {
+                    active_id,
+                    bin_step: 10,
+                    base_factor: 4000,
+                    activation_type: 0, // slot
+                    has_alpha_vault: false,
+                    activation_point: None,
+                    creator_pool_on_off_control: false,
+                    base_fee_power_factor: 0,
+                    function_type: 0,
+                    padding: [0; 61],
+                },
+            },
+        );
+        h.send(&[ix], &[]).await.expect("initialize_customizable_permissionless_lb_pair2");
+
+        Self {
+            lb_pair,
+            mint_x,
+            mint_y,
+            reserve_x,
+            reserve_y,
+            oracle,
+            token_x_program: token_program,
+            token_y_program: token_program,
+        }
+    }
+
+    /// Initialize every bin array covering `[lower_bin_id, upper_bin_id]`
+    pub async fn init_bin_arrays(&self, h: &mut Harness, lower_bin_id: i32, upper_bin_id: i32) {
+        let lower = dlmm_pda::bin_id_to_bin_array_index(lower_bin_id);
+        let upper = dlmm_pda::bin_id_to_bin_array_index(upper_bin_id);
+        for index in lower..=upper {
+            let bin_array = dlmm_pda::bin_array(&self.lb_pair, index).0;
+            if h.data(&bin_array).await.is_some() {
+                continue;
+            }
+            let ix = Harness::ix(
+                DLMM_PROGRAM_ID,
+                accounts::InitializeBinArray {
+                    lb_pair: self.lb_pair,
+                    bin_array,
+                    funder: h.ctx.payer.pubkey(),
+                    system_program: system_program::ID,
+                },
+                args::InitializeBinArray { index },
+            );
+            h.send(&[ix], &[]).await.expect("initialize_bin_array");
+        }
+    }
+
+    /// Deposit `amount_x` / `amount_y` SpotBalanced over
+    /// `[lower_bin_id, upper_bin_id]` from a payer-owned position, so swaps
+    /// through the pool fill. The payer's tokens are minted for it.
+    pub async fn seed(&self, h: &mut Harness, lower_bin_id: i32, upper_bin_id: i32, amount_x: u64, amount_y: u64) {
+        self.init_bin_arrays(h, lower_bin_id, upper_bin_id).await;
+        let payer = h.ctx.payer.pubkey();
+        let user_token_x = h.create_ata(&self.mint_x, &payer, amount_x).await;
+        let user_token_y = h.create_ata(&self.mint_y, &payer, amount_y).await;
+        let position = Keypair::new();
+        let (bin_array_lower, bin_array_upper) = self.position_bin_arrays(lower_bin_id, upper_bin_id);
+        let open = Harness::ix(
+            DLMM_PROGRAM_ID,
+            accounts::InitializePosition2 {
+                payer,
+                position: position.pubkey(),
+                lb_pair: self.lb_pair,
+                owner: payer,
+                system_program: system_program::ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                program: DLMM_PROGRAM_ID,
+            },
+            args::InitializePosition2 { lower_bin_id, width: upper_bin_id - lower_bin_id + 1 },
+        );
+        let fund = Harness::ix(
+            DLMM_PROGRAM_ID,
+            accounts::AddLiquidityByStrategy {
+                position: position.pubkey(),
+                lb_pair: self.lb_pair,
+                bin_array_bitmap_extension: None,
+                user_token_x,
+                user_token_y,
+                reserve_x: self.reserve_x,
+                reserve_y: self.reserve_y,
+                token_x_mint: self.mint_x,
+                token_y_mint: self.mint_y,
+                bin_array_lower,
+                bin_array_upper,
+                sender: payer,
+                token_x_program: self.token_x_program,
+                token_y_program: self.token_y_program,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                program: DLMM_PROGRAM_ID,
+            },
+            args::AddLiquidityByStrategy {
+                liquidity_parameter: LiquidityParameterByStrategy {
+                    amount_x,
+                    amount_y,
+                    active_id: 0,
+                    max_active_bin_slippage: i32::MAX,
+                    strategy_parameters: StrategyParameters {
+                        min_bin_id: lower_bin_id,
+                        max_bin_id: upper_bin_id,
+                        strategy_type: StrategyType::SpotBalanced,
+                        parameteres: [0; 64],
+                    },
+                },
+            },
+        );
+        h.send(&[open, fund], &[&position]).await.expect("seed liquidity");
+    }
+
+    /// (lower, upper) bin arrays for a position over `[lower_bin_id, upper_bin_id]`
+    pub fn position_bin_arrays(&self, lower_bin_id: i32, upper_bin_id: i32) -> (Pubkey, Pubkey) {
+        (
+            dlmm_pda::bin_array(&self.lb_pair, dlmm_pda::bin_id_to_bin_array_index(lower_bin_id)).0,
+            dlmm_pda::bin_array(&self.lb_pair, dlmm_pda::bin_id_to_bin_array_index(upper_bin_id)).0,
+        )
+    }
+}
@@ -0,0 +1,1217 @@
+//! End-to-end DLMM CPI paths against the real Meteora program:
+//! create position → add liquidity → swap → close over classic and Token-2022
+//! pools, the split remove-then-close path, batched deposits, two-hop routes,
+//! the swap's active-bin band, widening a position in place, the owner's
+//! close-all panic button, adopting an owner-opened position, owner-approved
+//! swap intents, migration between pools, plus the owner's liquidity-shape and
+//! ranked-pool restrictions.
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+
+use defi_agent::dlmm::client::{accounts as dlmm_accounts, args as dlmm_args};
+use defi_agent::dlmm::types::{LiquidityParameterByStrategy, StrategyParameters, StrategyType};
+use defi_agent::dlmm::ID as DLMM_PROGRAM_ID;
+use defi_agent::errors::AgentError;
+use defi_agent::instructions::execute_dlmm_add_liquidity_batch::MAX_BATCH_DEPOSITS;
+use defi_agent::instructions::execute_dlmm_swap_route::ROUTE_LEG_ACCOUNTS;
+use defi_agent::state::{
+    AgentSession, DailyStats, Intent, IntentSpec, LpPositionMonitor, PositionRegistry,
+    LIQUIDITY_SHAPE_CURVE, LIQUIDITY_SHAPE_SPOT, REASON_MANUAL, RISK_TIER_LOW, STRATEGY_LP,
+};
+use defi_agent::{accounts, instruction};
+use defi_agent_client::dlmm::{swap_bin_array_metas, DLMM_EVENT_AUTHORITY};
+use defi_agent_client::instructions::{self, DlmmSwapPool};
+use defi_agent_client::pda;
+use defi_agent_localnet::pool::Pool;
+use defi_agent_localnet::{assert_agent_error, assert_anchor_error, Harness};
+
+const LOWER_BIN: i32 = -5;
+const WIDTH: i32 = 11;
+const UPPER_BIN: i32 = LOWER_BIN + WIDTH - 1;
+const SESSION_CAP: u64 = 1_000_000_000_000;
+
+/// A SpotBalanced deposit over the fixture's position range
+fn spot_liquidity(amount_x: u64, amount_y: u64) -> LiquidityParameterByStrategy {
+    LiquidityParameterByStrategy {
+        amount_x,
+        amount_y,
+        active_id: 0,
+        max_active_bin_slippage: 3,
+        strategy_parameters: StrategyParameters {
+            min_bin_id: LOWER_BIN,
+            max_bin_id: UPPER_BIN,
+            strategy_type: StrategyType::SpotBalanced,
+            parameteres: [0; 64],
+        },
+    }
+}
+
+struct Fixture {
+    h: Harness,
+    owner: Keypair,
+    device: Keypair,
+    session: Pubkey,
+    pool: Pool,
+    device_x: Pubkey,
+    device_y: Pubkey,
+}
+
+async fn setup() -> Fixture {
+    setup_with_program(&spl_token::ID).await
+}
+
+/// [`setup`] over a pool whose mints are owned by `token_program`
+async fn setup_with_program(token_program: &Pubkey) -> Fixture {
+    let mut h = Harness::start().await;
+    h.initialize_config().await;
+    let owner = Keypair::new();
+    let device = Keypair::new();
+    let session = h.create_session(&owner, &device, STRATEGY_LP, SESSION_CAP).await;
+
+    let pool = Pool::create_with_program(&mut h, 0, token_program).await;
+    pool.init_bin_arrays(&mut h, LOWER_BIN, UPPER_BIN).await;
+    let device_x = h.create_ata(&pool.mint_x, &device.pubkey(), 1_000_000_000).await;
+    let device_y = h.create_ata(&pool.mint_y, &device.pubkey(), 1_000_000_000).await;
+
+    Fixture { h, owner, device, session, pool, device_x, device_y }
+}
+
+impl Fixture {
+    /// Open a position and create the session's LpPositionMonitor for it
+    async fn create_position(&mut self) -> Keypair {
+        self.open_position(true).await
+    }
+
+    /// Open a position without touching the monitor — for sessions that
+    /// already monitor another one
+    async fn create_unmonitored_position(&mut self) -> Keypair {
+        self.open_position(false).await
+    }
+
+    async fn open_position(&mut self, monitored: bool) -> Keypair {
+        let position = Keypair::new();
+        let mut ix = self.create_position_ix(&position.pubkey(), None);
+        if !monitored {
+            // An omitted optional account is passed as the program id
+            let monitor = pda::lp_monitor(&self.session).0;
+            let meta = ix.accounts.iter_mut().find(|meta| meta.pubkey == monitor).expect("monitor");
+            *meta = AccountMeta::new_readonly(defi_agent::ID, false);
+        }
+        let device = self.device.insecure_clone();
+        self.h.send(&[ix], &[&device, &position]).await.expect("create position");
+        position
+    }
+
+    fn create_position_ix(&self, position: &Pubkey, pair_pools: Option<Pubkey>) -> solana_sdk::instruction::Instruction {
+        Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmCreatePosition {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                pool_registry: None,
+                position_registry: pda::position_registry(&self.session).0,
+                monitor: Some(pda::lp_monitor(&self.session).0),
+                position: *position,
+                lb_pair: self.pool.lb_pair,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                fee_vault: pda::fee_vault().0,
+                system_program: system_program::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                daily_stats: None,
+                pair_pools,
+            },
+            instruction::ExecuteDlmmCreatePosition {
+                lower_bin_id: LOWER_BIN,
+                width: WIDTH,
+                fee_lamports: 0,
+            },
+        )
+    }
+
+    fn add_liquidity_ix(&self, position: &Pubkey, amount_x: u64, amount_y: u64) -> solana_sdk::instruction::Instruction {
+        let (bin_array_lower, bin_array_upper) = self.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmAddLiquidity {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                pool_registry: None,
+                position: *position,
+                lb_pair: self.pool.lb_pair,
+                bin_array_bitmap_extension: None,
+                user_token_x: self.device_x,
+                user_token_y: self.device_y,
+                reserve_x: self.pool.reserve_x,
+                reserve_y: self.pool.reserve_y,
+                token_x_mint: self.pool.mint_x,
+                token_y_mint: self.pool.mint_y,
+                bin_array_lower,
+                bin_array_upper,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                token_x_program: self.pool.token_x_program,
+                token_y_program: self.pool.token_y_program,
+                fee_vault: pda::fee_vault().0,
+                system_program: system_program::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                cosigner: None,
+                action_request: None,
+                position_registry: Some(pda::position_registry(&self.session).0),
+                daily_stats: None,
+                budget_price_pool: None,
+            },
+            instruction::ExecuteDlmmAddLiquidity {
+                liquidity_parameter: spot_liquidity(amount_x, amount_y),
+                fee_lamports: 0,
+            },
+        )
+    }
+
+    fn close_ix(&self, position: &Pubkey) -> solana_sdk::instruction::Instruction {
+        let (bin_array_lower, bin_array_upper) = self.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmClosePosition {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                position: *position,
+                lb_pair: self.pool.lb_pair,
+                bin_array_bitmap_extension: None,
+                user_token_x: self.device_x,
+                user_token_y: self.device_y,
+                reserve_x: self.pool.reserve_x,
+                reserve_y: self.pool.reserve_y,
+                token_x_mint: self.pool.mint_x,
+                token_y_mint: self.pool.mint_y,
+                bin_array_lower,
+                bin_array_upper,
+                rent_receiver: self.device.pubkey(),
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                token_x_program: self.pool.token_x_program,
+                token_y_program: self.pool.token_y_program,
+                fee_vault: pda::fee_vault().0,
+                system_program: system_program::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                position_registry: Some(pda::position_registry(&self.session).0),
+                monitor: Some(pda::lp_monitor(&self.session).0),
+                daily_stats: None,
+            },
+            instruction::ExecuteDlmmClosePosition { fee_lamports: 0, reason: REASON_MANUAL },
+        )
+    }
+
+    /// Owner-signed `adopt_position` of `old_position` into `new_position`,
+    /// withdrawing into `session_token_x` / `session_token_y`
+    fn adopt_ix(
+        &self,
+        old_position: &Pubkey,
+        new_position: &Pubkey,
+        session_token_x: Pubkey,
+        session_token_y: Pubkey,
+    ) -> Instruction {
+        let (bin_array_lower, bin_array_upper) = self.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        Harness::ix(
+            defi_agent::ID,
+            accounts::AdoptPosition {
+                owner: self.owner.pubkey(),
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                pool_registry: None,
+                position_registry: pda::position_registry(&self.session).0,
+                old_position: *old_position,
+                new_position: *new_position,
+                lb_pair: self.pool.lb_pair,
+                bin_array_bitmap_extension: None,
+                session_token_x,
+                session_token_y,
+                reserve_x: self.pool.reserve_x,
+                reserve_y: self.pool.reserve_y,
+                token_x_mint: self.pool.mint_x,
+                token_y_mint: self.pool.mint_y,
+                bin_array_lower,
+                bin_array_upper,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                token_x_program: self.pool.token_x_program,
+                token_y_program: self.pool.token_y_program,
+                system_program: system_program::ID,
+            },
+            instruction::AdoptPosition { lower_bin_id: LOWER_BIN, width: WIDTH },
+        )
+    }
+
+    /// `close_all_positions` over `positions`, each given with the pool its
+    /// accounts are taken from; the session's monitor and DailyStats are passed
+    /// when `settle`
+    fn close_all_ix(&self, positions: &[(Pubkey, Pubkey)], settle: bool) -> Instruction {
+        let (bin_array_lower, bin_array_upper) = self.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        let mut ix = Harness::ix(
+            defi_agent::ID,
+            accounts::CloseAllPositions {
+                owner: self.owner.pubkey(),
+                session_key: self.device.pubkey(),
+                session: self.session,
+                position_registry: pda::position_registry(&self.session).0,
+                monitor: settle.then(|| pda::lp_monitor(&self.session).0),
+                daily_stats: settle.then(|| pda::daily_stats(&self.session).0),
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+            },
+            instruction::CloseAllPositions {},
+        );
+        for (position, lb_pair) in positions {
+            ix.accounts.extend([
+                AccountMeta::new(*position, false),
+                AccountMeta::new(*lb_pair, false),
+                AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+                AccountMeta::new(self.device_x, false),
+                AccountMeta::new(self.device_y, false),
+                AccountMeta::new(self.pool.reserve_x, false),
+                AccountMeta::new(self.pool.reserve_y, false),
+                AccountMeta::new_readonly(self.pool.mint_x, false),
+                AccountMeta::new_readonly(self.pool.mint_y, false),
+                AccountMeta::new(bin_array_lower, false),
+                AccountMeta::new(bin_array_upper, false),
+                AccountMeta::new_readonly(self.pool.token_x_program, false),
+                AccountMeta::new_readonly(self.pool.token_y_program, false),
+            ]);
+        }
+        ix
+    }
+
+    /// `execute_dlmm_migrate_position` of `position` into `new_position` on
+    /// `target`, over the fixture's range; the monitor and registry follow
+    fn migrate_ix(&self, position: &Pubkey, new_position: &Pubkey, target: &Pool, pair_pools: Option<Pubkey>) -> Instruction {
+        let (source_bin_array_lower, source_bin_array_upper) = self.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        let (target_bin_array_lower, target_bin_array_upper) = target.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmMigratePosition {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                pool_registry: None,
+                user_token_x: self.device_x,
+                user_token_y: self.device_y,
+                token_x_mint: self.pool.mint_x,
+                token_y_mint: self.pool.mint_y,
+                position: *position,
+                source_lb_pair: self.pool.lb_pair,
+                source_bin_array_bitmap_extension: None,
+                source_reserve_x: self.pool.reserve_x,
+                source_reserve_y: self.pool.reserve_y,
+                source_bin_array_lower,
+                source_bin_array_upper,
+                new_position: *new_position,
+                target_lb_pair: target.lb_pair,
+                target_bin_array_bitmap_extension: None,
+                target_reserve_x: target.reserve_x,
+                target_reserve_y: target.reserve_y,
+                target_bin_array_lower,
+                target_bin_array_upper,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                token_x_program: self.pool.token_x_program,
+                token_y_program: self.pool.token_y_program,
+                fee_vault: pda::fee_vault().0,
+                system_program: system_program::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                position_registry: Some(pda::position_registry(&self.session).0),
+                monitor: Some(pda::lp_monitor(&self.session).0),
+                daily_stats: None,
+                pair_pools,
+            },
+            instruction::ExecuteDlmmMigratePosition {
+                lower_bin_id: LOWER_BIN,
+                width: WIDTH,
+                liquidity_parameter: spot_liquidity(0, 0),
+                fee_lamports: 0,
+                reason: REASON_MANUAL,
+            },
+        )
+    }
+
+    /// First half of a two-transaction close: `execute_dlmm_remove_all_liquidity`
+    fn remove_all_ix(&self, position: &Pubkey) -> Instruction {
+        let (bin_array_lower, bin_array_upper) = self.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmRemoveAllLiquidity {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                position: *position,
+                lb_pair: self.pool.lb_pair,
+                bin_array_bitmap_extension: None,
+                user_token_x: self.device_x,
+                user_token_y: self.device_y,
+                reserve_x: self.pool.reserve_x,
+                reserve_y: self.pool.reserve_y,
+                token_x_mint: self.pool.mint_x,
+                token_y_mint: self.pool.mint_y,
+                bin_array_lower,
+                bin_array_upper,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                token_x_program: self.pool.token_x_program,
+                token_y_program: self.pool.token_y_program,
+                instructions_sysvar: sysvar::instructions::ID,
+                position_registry: Some(pda::position_registry(&self.session).0),
+            },
+            instruction::ExecuteDlmmRemoveAllLiquidity { fee_lamports: 0 },
+        )
+    }
+
+    /// Second half: `execute_dlmm_close_empty_position`, closing the monitor
+    /// too when `monitored`
+    fn close_empty_ix(&self, position: &Pubkey, rent_receiver: Pubkey, monitored: bool) -> Instruction {
+        Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmCloseEmptyPosition {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                position: *position,
+                lb_pair: self.pool.lb_pair,
+                rent_receiver,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                fee_vault: pda::fee_vault().0,
+                system_program: system_program::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                position_registry: Some(pda::position_registry(&self.session).0),
+                monitor: monitored.then(|| pda::lp_monitor(&self.session).0),
+                daily_stats: None,
+            },
+            instruction::ExecuteDlmmCloseEmptyPosition { fee_lamports: 0 },
+        )
+    }
+
+    /// `execute_dlmm_swap_route` selling X through the fixture's pool into
+    /// `user_token_mid` (its Y), then through `leg2` into `user_token_out`;
+    /// the PoolRegistry is passed when `registered`
+    #[allow(clippy::too_many_arguments)]
+    fn route_ix(
+        &self,
+        leg2: &Pool,
+        user_token_mid: Pubkey,
+        user_token_out: Pubkey,
+        amount_in: u64,
+        min_amount_out: u64,
+        registered: bool,
+    ) -> Instruction {
+        let leg1_arrays = swap_bin_array_metas(&self.pool.lb_pair, 0, true, 2);
+        let leg2_x_to_y = leg2.mint_x == self.pool.mint_y;
+        let mut ix = Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmSwapRoute {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                pool_registry: registered.then(|| pda::pool_registry().0),
+                lb_pair: self.pool.lb_pair,
+                bin_array_bitmap_extension: None,
+                reserve_x: self.pool.reserve_x,
+                reserve_y: self.pool.reserve_y,
+                user_token_in: self.device_x,
+                user_token_mid,
+                user_token_out,
+                token_x_mint: self.pool.mint_x,
+                token_y_mint: self.pool.mint_y,
+                oracle: self.pool.oracle,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                token_x_program: self.pool.token_x_program,
+                token_y_program: self.pool.token_y_program,
+                treasury: None,
+                treasury_ledger: None,
+                referrer_token: None,
+                fee_vault: pda::fee_vault().0,
+                system_program: system_program::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                cosigner: None,
+                daily_stats: None,
+                budget_price_pool: None,
+            },
+            instruction::ExecuteDlmmSwapRoute {
+                amount_in,
+                min_amount_out,
+                leg1_bin_arrays: leg1_arrays.len() as u8,
+                fee_lamports: 0,
+            },
+        );
+        ix.accounts.extend(leg1_arrays);
+        ix.accounts.extend([
+            AccountMeta::new(leg2.lb_pair, false),
+            AccountMeta::new_readonly(DLMM_PROGRAM_ID, false),
+            AccountMeta::new(leg2.reserve_x, false),
+            AccountMeta::new(leg2.reserve_y, false),
+            AccountMeta::new_readonly(leg2.mint_x, false),
+            AccountMeta::new_readonly(leg2.mint_y, false),
+            AccountMeta::new(leg2.oracle, false),
+            AccountMeta::new_readonly(leg2.token_x_program, false),
+            AccountMeta::new_readonly(leg2.token_y_program, false),
+        ]);
+        // A small swap stays within array 0, the only one seeded above bin 0
+        ix.accounts.extend(swap_bin_array_metas(&leg2.lb_pair, 0, leg2_x_to_y, 1));
+        ix
+    }
+
+    /// `execute_dlmm_add_liquidity_batch` of `(position, amount_x, amount_y)`
+    /// deposits over the fixture's range, with the PositionRegistry
+    fn batch_ix(&self, deposits: &[(Pubkey, u64, u64)]) -> Instruction {
+        let (bin_array_lower, bin_array_upper) = self.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+        let mut ix = Harness::ix(
+            defi_agent::ID,
+            accounts::ExecuteDlmmAddLiquidityBatch {
+                session_key: self.device.pubkey(),
+                session: self.session,
+                config: pda::config().0,
+                pool_registry: None,
+                lb_pair: self.pool.lb_pair,
+                bin_array_bitmap_extension: None,
+                user_token_x: self.device_x,
+                user_token_y: self.device_y,
+                reserve_x: self.pool.reserve_x,
+                reserve_y: self.pool.reserve_y,
+                token_x_mint: self.pool.mint_x,
+                token_y_mint: self.pool.mint_y,
+                dlmm_program: DLMM_PROGRAM_ID,
+                event_authority: DLMM_EVENT_AUTHORITY,
+                token_x_program: self.pool.token_x_program,
+                token_y_program: self.pool.token_y_program,
+                fee_vault: pda::fee_vault().0,
+                system_program: system_program::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                cosigner: None,
+                position_registry: Some(pda::position_registry(&self.session).0),
+                daily_stats: None,
+                budget_price_pool: None,
+            },
+            instruction::ExecuteDlmmAddLiquidityBatch {
+                liquidity_parameters: deposits.iter().map(|(_, x, y)| spot_liquidity(*x, *y)).collect(),
+                fee_lamports: 0,
+            },
+        );
+        for (position, _, _) in deposits {
+            ix.accounts.extend([
+                AccountMeta::new(*position, false),
+                AccountMeta::new(bin_array_lower, false),
+                AccountMeta::new(bin_array_upper, false),
+            ]);
+        }
+        ix
+    }
+
+    fn swap_pool(&self) -> DlmmSwapPool {
+        DlmmSwapPool {
+            lb_pair: self.pool.lb_pair,
+            reserve_x: self.pool.reserve_x,
+            reserve_y: self.pool.reserve_y,
+            token_x_mint: self.pool.mint_x,
+            token_y_mint: self.pool.mint_y,
+            token_x_program: self.pool.token_x_program,
+            token_y_program: self.pool.token_y_program,
+            oracle: self.pool.oracle,
+            bin_array_bitmap_extension: None,
+        }
+    }
+
+    /// Fill `amount_in` of intent `intent_id`, selling X when `swap_for_y`
+    fn fulfill_ix(&self, intent_id: u64, amount_in: u64, swap_for_y: bool) -> Instruction {
+        let (token_in, token_out) =
+            if swap_for_y { (self.device_x, self.device_y) } else { (self.device_y, self.device_x) };
+        instructions::fulfill_intent(
+            self.device.pubkey(),
+            self.owner.pubkey(),
+            intent_id,
+            &self.swap_pool(),
+            token_in,
+            token_out,
+            amount_in,
+            0,
+            swap_bin_array_metas(&self.pool.lb_pair, 0, swap_for_y, 2),
+        )
+    }
+
+    fn swap_ix(&self, amount_in: u64, min_amount_out: u64) -> solana_sdk::instruction::Instruction {
+        self.banded_swap_ix(amount_in, min_amount_out, None)
+    }
+
+    fn banded_swap_ix(
+        &self,
+        amount_in: u64,
+        min_amount_out: u64,
+        active_bin_band: Option<(i32, i32)>,
+    ) -> solana_sdk::instruction::Instruction {
+        instructions::execute_dlmm_swap(
+            self.device.pubkey(),
+            self.owner.pubkey(),
+            &self.swap_pool(),
+            self.device_x,
+            self.device_y,
+            amount_in,
+            min_amount_out,
+            0,
+            active_bin_band,
+            swap_bin_array_metas(&self.pool.lb_pair, 0, true, 2),
+        )
+    }
+}
+
+#[tokio::test]
+async fn position_lifecycle() {
+    position_lifecycle_in(setup().await).await;
+}
+
+#[tokio::test]
+async fn token_2022_position_lifecycle() {
+    let mut f = setup_with_program(&spl_token_2022::ID).await;
+    // The session key's token accounts are Token-2022 ATAs
+    assert_eq!(f.h.owner(&f.device_x).await, spl_token_2022::ID);
+    assert_eq!(f.h.owner(&f.device_y).await, spl_token_2022::ID);
+    position_lifecycle_in(f).await;
+}
+
+async fn position_lifecycle_in(mut f: Fixture) {
+    let device = f.device.insecure_clone();
+
+    // ── Create: position is registered and monitored ────────────────────────
+    let position = f.create_position().await;
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(registry.contains(&position.pubkey()));
+    assert!(f.h.data(&pda::lp_monitor(&f.session).0).await.is_some());
+
+    // ── Add liquidity on both sides of the active bin ───────────────────────
+    let ix = f.add_liquidity_ix(&position.pubkey(), 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+    assert_eq!(f.h.token_balance(&f.device_x).await, 900_000_000);
+    assert_eq!(f.h.token_balance(&f.device_y).await, 900_000_000);
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    let entry = registry.get(&position.pubkey()).expect("registered");
+    assert_eq!((entry.deposited_x, entry.deposited_y), (100_000_000, 100_000_000));
+    assert!(entry.cost_basis >= 100_000_000);
+
+    // ── Swap X → Y through the session ──────────────────────────────────────
+    let before = f.h.account::<AgentSession>(&f.session).await.spent_lamports;
+    let ix = f.swap_ix(1_000_000, 1);
+    f.h.send(&[ix], &[&device]).await.expect("swap");
+    assert_eq!(f.h.token_balance(&f.device_x).await, 899_000_000);
+    assert!(f.h.token_balance(&f.device_y).await > 900_000_000);
+    let after = f.h.account::<AgentSession>(&f.session).await.spent_lamports;
+    assert_eq!(after - before, 1_000_000);
+
+    // ── Close: liquidity returns, registry and monitor are cleaned up ───────
+    let ix = f.close_ix(&position.pubkey());
+    f.h.send(&[ix], &[&device]).await.expect("close");
+    assert!(f.h.data(&position.pubkey()).await.is_none());
+    assert!(f.h.data(&pda::lp_monitor(&f.session).0).await.is_none());
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(!registry.contains(&position.pubkey()));
+}
+
+/// An X → Y intent `intent_id` on `lb_pair` for up to `max_amount_in`, with
+/// no keeper
+fn sell_x_intent(intent_id: u64, lb_pair: Pubkey, max_amount_in: u64, min_amount_out: u64, deadline: i64) -> IntentSpec {
+    IntentSpec {
+        intent_id,
+        lb_pair,
+        swap_for_y: true,
+        max_amount_in,
+        min_amount_out,
+        deadline,
+        keeper_fillable: false,
+        keeper_bounty_lamports: 0,
+    }
+}
+
+/// A fixture with liquidity on both sides of the active bin, so swaps fill
+async fn funded() -> Fixture {
+    let mut f = setup().await;
+    let device = f.device.insecure_clone();
+    let position = f.create_position().await;
+    let ix = f.add_liquidity_ix(&position.pubkey(), 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+    f
+}
+
+#[tokio::test]
+async fn fulfill_intent_stays_within_the_owners_bounds() {
+    let mut f = funded().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let deadline = f.h.now().await + 3_600;
+    let other = Pool::create(&mut f.h, 0).await;
+    for spec in [
+        sell_x_intent(1, f.pool.lb_pair, 2_000_000, 1, deadline),
+        sell_x_intent(2, other.lb_pair, 2_000_000, 1, deadline),
+        // Far above what the pool pays at bin 0
+        sell_x_intent(3, f.pool.lb_pair, 2_000_000, 4_000_000, deadline),
+    ] {
+        f.h.send(&[instructions::create_intent(owner.pubkey(), spec)], &[&owner]).await.expect("create intent");
+    }
+
+    // Another pool, the other side, or more than approved
+    assert_agent_error(f.h.send(&[f.fulfill_ix(2, 1_000_000, true)], &[&device]).await, AgentError::IntentMismatch);
+    assert_agent_error(f.h.send(&[f.fulfill_ix(1, 1_000_000, false)], &[&device]).await, AgentError::IntentMismatch);
+    assert_agent_error(f.h.send(&[f.fulfill_ix(1, 2_000_001, true)], &[&device]).await, AgentError::IntentBoundsExceeded);
+    assert_agent_error(f.h.send(&[f.fulfill_ix(1, 0, true)], &[&device]).await, AgentError::IntentBoundsExceeded);
+
+    // The owner's price floor is handed to DLMM as the swap's minimum out
+    assert!(f.h.send(&[f.fulfill_ix(3, 2_000_000, true)], &[&device]).await.is_err());
+    assert_eq!(f.h.token_balance(&f.device_x).await, 900_000_000);
+
+    // Within bounds the fill goes through and closes the intent
+    let intent = pda::intent(&f.session, 1).0;
+    f.h.send(&[f.fulfill_ix(1, 2_000_000, true)], &[&device]).await.expect("fulfill");
+    assert_eq!(f.h.token_balance(&f.device_x).await, 898_000_000);
+    assert!(f.h.data(&intent).await.is_none());
+    assert!(f.h.send(&[f.fulfill_ix(1, 1, true)], &[&device]).await.is_err(), "filled intents are gone");
+}
+
+#[tokio::test]
+async fn intents_fill_in_tranches_until_they_expire_or_are_cancelled() {
+    let mut f = funded().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let deadline = f.h.now().await + 3_600;
+    for id in [1, 2] {
+        let spec = sell_x_intent(id, f.pool.lb_pair, 2_000_000, 1, deadline);
+        f.h.send(&[instructions::create_intent(owner.pubkey(), spec)], &[&owner]).await.expect("create intent");
+    }
+    let (first, second) = (pda::intent(&f.session, 1).0, pda::intent(&f.session, 2).0);
+
+    // Tranches draw down the same approval
+    f.h.send(&[f.fulfill_ix(1, 500_000, true)], &[&device]).await.expect("first tranche");
+    f.h.send(&[f.fulfill_ix(1, 700_000, true)], &[&device]).await.expect("second tranche");
+    let intent: Intent = f.h.account(&first).await;
+    assert_eq!((intent.filled_amount, intent.remaining()), (1_200_000, 800_000));
+    assert_agent_error(f.h.send(&[f.fulfill_ix(1, 800_001, true)], &[&device]).await, AgentError::IntentBoundsExceeded);
+
+    // The owner withdraws one mid-way; its rent comes back
+    let before = f.h.lamports(&owner.pubkey()).await;
+    let rent = f.h.lamports(&first).await;
+    f.h.send(&[instructions::cancel_intent(owner.pubkey(), 1)], &[&owner]).await.expect("cancel");
+    assert!(f.h.data(&first).await.is_none());
+    assert_eq!(f.h.lamports(&owner.pubkey()).await, before + rent);
+
+    // The other runs out of time
+    f.h.advance_clock(3_600).await;
+    assert_agent_error(f.h.send(&[f.fulfill_ix(2, 500_000, true)], &[&device]).await, AgentError::IntentExpired);
+    f.h.send(&[instructions::cancel_intent(owner.pubkey(), 2)], &[&owner]).await.expect("cancel expired");
+    assert!(f.h.data(&second).await.is_none());
+}
+
+#[tokio::test]
+async fn swap_route_takes_both_legs_in_scope_or_neither() {
+    let mut f = funded().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    // X → Y through the fixture's pool, then Y → Z through a pool over (Y, Z)
+    let mint_z = f.h.create_mint().await;
+    let leg2 = Pool::create_over(&mut f.h, 0, f.pool.mint_y, mint_z).await;
+    leg2.seed(&mut f.h, LOWER_BIN, UPPER_BIN, 100_000_000, 100_000_000).await;
+    let device_z = f.h.create_ata(&mint_z, &device.pubkey(), 0).await;
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
+
+    // No second pool, or an intermediate leg in the input mint
+    let mut ix = f.route_ix(&leg2, f.device_y, device_z, 1_000_000, 0, false);
+    ix.accounts.truncate(ix.accounts.len() - 1 - ROUTE_LEG_ACCOUNTS);
+    assert_agent_error(f.h.send(&[budget.clone(), ix], &[&device]).await, AgentError::InvalidSwapRoute);
+    let ix = f.route_ix(&leg2, f.device_x, device_z, 1_000_000, 0, false);
+    assert_agent_error(f.h.send(&[budget.clone(), ix], &[&device]).await, AgentError::InvalidSwapRoute);
+
+    // A second pool that doesn't take the first leg's output
+    let unchained = Pool::create(&mut f.h, 0).await;
+    unchained.seed(&mut f.h, LOWER_BIN, UPPER_BIN, 100_000_000, 100_000_000).await;
+    let ix = f.route_ix(&unchained, f.device_y, device_z, 1_000_000, 0, false);
+    assert!(f.h.send(&[budget.clone(), ix], &[&device]).await.is_err(), "unchained second leg");
+
+    // Registry-only: the second pool is checked as well as the first
+    f.h.initialize_pool_registry(&[f.pool.lb_pair]).await;
+    let ix = instructions::set_registry_only(owner.pubkey(), true);
+    f.h.send(&[ix], &[&owner]).await.expect("registry only");
+    let ix = f.route_ix(&leg2, f.device_y, device_z, 1_000_000, 0, true);
+    assert_agent_error(f.h.send(&[budget.clone(), ix], &[&device]).await, AgentError::PoolNotRegistered);
+    let admin = f.h.admin.insecure_clone();
+    let ix = instructions::add_registry_pool(admin.pubkey(), leg2.lb_pair, RISK_TIER_LOW);
+    f.h.send(&[ix], &[&admin]).await.expect("register the second pool");
+
+    // The final minimum is enforced across both legs
+    let ix = f.route_ix(&leg2, f.device_y, device_z, 1_000_000, 10_000_000, true);
+    assert!(f.h.send(&[budget.clone(), ix], &[&device]).await.is_err(), "output under the minimum");
+
+    // Both legs in one action: the intermediate is passed straight through
+    let (x_before, y_before) = (f.h.token_balance(&f.device_x).await, f.h.token_balance(&f.device_y).await);
+    let before: AgentSession = f.h.account(&f.session).await;
+    let ix = f.route_ix(&leg2, f.device_y, device_z, 1_000_000, 1, true);
+    f.h.send(&[budget, ix], &[&device]).await.expect("route");
+    assert_eq!(f.h.token_balance(&f.device_x).await, x_before - 1_000_000);
+    assert_eq!(f.h.token_balance(&f.device_y).await, y_before);
+    assert!(f.h.token_balance(&device_z).await > 0);
+    let session: AgentSession = f.h.account(&f.session).await;
+    assert_eq!(session.total_actions, before.total_actions + 1);
+}
+
+#[tokio::test]
+async fn swap_rejects_exposure_over_cap() {
+    let mut f = setup().await;
+    let device = f.device.insecure_clone();
+    let position = f.create_position().await;
+    let ix = f.add_liquidity_ix(&position.pubkey(), 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+
+    let remaining = {
+        let s: AgentSession = f.h.account(&f.session).await;
+        s.max_lamports - s.spent_lamports
+    };
+    let ix = f.swap_ix(remaining + 1, 1);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::ExposureLimitExceeded);
+}
+
+#[tokio::test]
+async fn swap_refuses_an_active_bin_outside_its_band() {
+    let mut f = setup().await;
+    let device = f.device.insecure_clone();
+    let position = f.create_position().await;
+    let ix = f.add_liquidity_ix(&position.pubkey(), 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+
+    // The pool sits at bin 0
+    let ix = f.banded_swap_ix(1_000_000, 1, Some((1, 5)));
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::ActiveBinOutOfBand);
+    let ix = f.banded_swap_ix(1_000_000, 1, Some((-1, 1)));
+    f.h.send(&[ix], &[&device]).await.expect("swap within band");
+}
+
+#[tokio::test]
+async fn deposit_batches_fund_each_position_or_none() {
+    let mut f = setup().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let a = f.create_position().await.pubkey();
+    let b = f.create_unmonitored_position().await.pubkey();
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
+
+    // No deposit, more than the maximum, or a group short of its parameters
+    let ix = f.batch_ix(&[]);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::InvalidDepositBatch);
+    let ix = f.batch_ix(&[(a, 1_000_000, 1_000_000); MAX_BATCH_DEPOSITS + 1]);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::InvalidDepositBatch);
+    let mut ix = f.batch_ix(&[(a, 1_000_000, 1_000_000), (b, 1_000_000, 1_000_000)]);
+    ix.accounts.pop();
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::InvalidDepositBatch);
+
+    // A position of another pool fails in DLMM and takes the batch with it
+    let other = Pool::create(&mut f.h, 0).await;
+    let stray = Keypair::new();
+    let open = Harness::ix(
+        DLMM_PROGRAM_ID,
+        dlmm_accounts::InitializePosition2 {
+            payer: device.pubkey(),
+            position: stray.pubkey(),
+            lb_pair: other.lb_pair,
+            owner: device.pubkey(),
+            system_program: system_program::ID,
+            event_authority: DLMM_EVENT_AUTHORITY,
+            program: DLMM_PROGRAM_ID,
+        },
+        dlmm_args::InitializePosition2 { lower_bin_id: LOWER_BIN, width: WIDTH },
+    );
+    f.h.send(&[open], &[&device, &stray]).await.expect("position on another pool");
+    let x_before = f.h.token_balance(&f.device_x).await;
+    let ix = f.batch_ix(&[(a, 1_000_000, 1_000_000), (stray.pubkey(), 1_000_000, 1_000_000)]);
+    assert!(f.h.send(&[budget.clone(), ix], &[&device]).await.is_err(), "position of another pool");
+    assert_eq!(f.h.token_balance(&f.device_x).await, x_before);
+
+    // Every position must be bound once the owner binds any
+    let ix = instructions::set_bound_positions(owner.pubkey(), vec![a]);
+    f.h.send(&[ix], &[&owner]).await.expect("bind a");
+    let ix = f.batch_ix(&[(a, 1_000_000, 1_000_000), (b, 1_000_000, 1_000_000)]);
+    assert_agent_error(f.h.send(&[budget.clone(), ix], &[&device]).await, AgentError::PositionNotBound);
+    let ix = instructions::set_bound_positions(owner.pubkey(), vec![a, b]);
+    f.h.send(&[ix], &[&owner]).await.expect("bind both");
+
+    // One action; each position's own deposit goes to its cost basis
+    let y_before = f.h.token_balance(&f.device_y).await;
+    let before: AgentSession = f.h.account(&f.session).await;
+    let ix = f.batch_ix(&[(a, 100_000_000, 100_000_000), (b, 50_000_000, 50_000_000)]);
+    f.h.send(&[budget, ix], &[&device]).await.expect("batch deposit");
+    let session: AgentSession = f.h.account(&f.session).await;
+    assert_eq!(session.total_actions, before.total_actions + 1);
+
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    let (a, b) = (registry.get(&a).expect("a"), registry.get(&b).expect("b"));
+    assert!(a.deposited_x > b.deposited_x && b.deposited_x > 0);
+    assert_eq!(a.deposited_x + b.deposited_x, x_before - f.h.token_balance(&f.device_x).await);
+    assert_eq!(a.deposited_y + b.deposited_y, y_before - f.h.token_balance(&f.device_y).await);
+}
+
+#[tokio::test]
+async fn deposits_must_follow_the_owners_liquidity_shape() {
+    let mut f = setup().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let position = f.create_position().await;
+
+    // The fixture deposits SpotBalanced
+    let curve_only = instructions::set_liquidity_shapes(owner.pubkey(), LIQUIDITY_SHAPE_CURVE);
+    f.h.send(&[curve_only], &[&owner]).await.unwrap();
+    let ix = f.add_liquidity_ix(&position.pubkey(), 100_000_000, 100_000_000);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::LiquidityShapeNotAllowed);
+
+    let ix = instructions::set_liquidity_shapes(owner.pubkey(), LIQUIDITY_SHAPE_CURVE | LIQUIDITY_SHAPE_SPOT);
+    f.h.send(&[ix], &[&owner]).await.unwrap();
+    let ix = f.add_liquidity_ix(&position.pubkey(), 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+
+    let unknown = instructions::set_liquidity_shapes(owner.pubkey(), 1 << 9);
+    assert_agent_error(f.h.send(&[unknown], &[&owner]).await, AgentError::InvalidLiquidityShapes);
+}
+
+#[tokio::test]
+async fn ranked_pools_gate_new_positions() {
+    let mut f = setup().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let (mint_x, mint_y) = (f.pool.mint_x, f.pool.mint_y);
+
+    // A pool of another pair can't be ranked for this one
+    let other = Pool::create(&mut f.h, 0).await;
+    let ix = instructions::set_pair_pools(owner.pubkey(), mint_x, mint_y, vec![other.lb_pair]);
+    assert_agent_error(f.h.send(&[ix], &[&owner]).await, AgentError::InvalidPairPools);
+
+    let ix = instructions::set_pair_pools(owner.pubkey(), mint_x, mint_y, vec![f.pool.lb_pair]);
+    f.h.send(&[ix], &[&owner]).await.expect("set pair pools");
+    let list = pda::pair_pools(&f.session, &mint_x, &mint_y).0;
+
+    // Once ranked, the list must come with the instruction
+    let position = Keypair::new();
+    let ix = f.create_position_ix(&position.pubkey(), None);
+    assert_agent_error(f.h.send(&[ix], &[&device, &position]).await, AgentError::PoolNotRanked);
+    let ix = f.create_position_ix(&position.pubkey(), Some(list));
+    f.h.send(&[ix], &[&device, &position]).await.expect("create on a ranked pool");
+
+    let ix = instructions::close_pair_pools(owner.pubkey(), mint_x, mint_y);
+    f.h.send(&[ix], &[&owner]).await.expect("close pair pools");
+    let session: AgentSession = f.h.account(&f.session).await;
+    assert_eq!(session.pair_pool_lists, 0);
+}
+
+#[tokio::test]
+async fn migrate_only_moves_into_pools_in_the_sessions_scope() {
+    let mut f = setup().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let (mint_x, mint_y) = (f.pool.mint_x, f.pool.mint_y);
+    let position = f.create_position().await.pubkey();
+    let ix = f.add_liquidity_ix(&position, 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+    let new_position = Keypair::new();
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
+
+    // Registry-only sessions can't migrate into a pool off the registry
+    let ix = instructions::set_registry_only(owner.pubkey(), true);
+    f.h.send(&[ix], &[&owner]).await.expect("registry only");
+    let ix = f.migrate_ix(&position, &new_position.pubkey(), &f.pool, None);
+    assert_agent_error(
+        f.h.send(&[budget.clone(), ix], &[&device, &new_position]).await,
+        AgentError::PoolNotRegistered,
+    );
+    let ix = instructions::set_registry_only(owner.pubkey(), false);
+    f.h.send(&[ix], &[&owner]).await.expect("any pool");
+
+    // Once another pair is ranked, an unranked target is refused — with no
+    // list, or with a list that doesn't hold it
+    let other = Pool::create(&mut f.h, 0).await;
+    other.init_bin_arrays(&mut f.h, LOWER_BIN, UPPER_BIN).await;
+    let ix = instructions::set_pair_pools(owner.pubkey(), other.mint_x, other.mint_y, vec![other.lb_pair]);
+    f.h.send(&[ix], &[&owner]).await.expect("rank the other pair");
+    let other_list = pda::pair_pools(&f.session, &other.mint_x, &other.mint_y).0;
+    let ix = f.migrate_ix(&position, &new_position.pubkey(), &f.pool, None);
+    assert_agent_error(
+        f.h.send(&[budget.clone(), ix], &[&device, &new_position]).await,
+        AgentError::PoolNotRanked,
+    );
+    let ix = f.migrate_ix(&position, &new_position.pubkey(), &f.pool, Some(other_list));
+    assert_agent_error(
+        f.h.send(&[budget.clone(), ix], &[&device, &new_position]).await,
+        AgentError::PoolNotRanked,
+    );
+
+    // A ranked pool of another pair: DLMM refuses the re-deposit and the
+    // close is rolled back with it
+    let ix = f.migrate_ix(&position, &new_position.pubkey(), &other, Some(other_list));
+    assert!(f.h.send(&[budget.clone(), ix], &[&device, &new_position]).await.is_err(), "other pair");
+    assert!(f.h.data(&position).await.is_some(), "source position kept");
+
+    // Ranked for its own pair, the position moves and its records follow
+    let ix = instructions::set_pair_pools(owner.pubkey(), mint_x, mint_y, vec![f.pool.lb_pair]);
+    f.h.send(&[ix], &[&owner]).await.expect("rank the pair");
+    let list = pda::pair_pools(&f.session, &mint_x, &mint_y).0;
+    let ix = f.migrate_ix(&position, &new_position.pubkey(), &f.pool, Some(list));
+    f.h.send(&[budget, ix], &[&device, &new_position]).await.expect("migrate");
+
+    assert!(f.h.data(&position).await.is_none());
+    let monitor: LpPositionMonitor = f.h.zero_copy(&pda::lp_monitor(&f.session).0).await;
+    assert_eq!(monitor.position, new_position.pubkey());
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(!registry.contains(&position));
+    assert!(registry.contains(&new_position.pubkey()));
+}
+
+#[tokio::test]
+async fn widen_range_reopens_wider_around_the_active_bin() {
+    let mut f = setup().await;
+    let device = f.device.insecure_clone();
+    let position = f.create_position().await;
+    let ix = f.add_liquidity_ix(&position.pubkey(), 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    let deposited = registry.get(&position.pubkey()).expect("registered");
+
+    // Pool is at bin 0: [-5, 5] widened by 2 each side is [-7, 7]
+    let new_position = Keypair::new();
+    let ix = instructions::execute_dlmm_widen_range(
+        device.pubkey(),
+        f.owner.pubkey(),
+        &f.swap_pool(),
+        position.pubkey(),
+        new_position.pubkey(),
+        f.device_x,
+        f.device_y,
+        (LOWER_BIN, UPPER_BIN),
+        0,
+        2,
+        LiquidityParameterByStrategy {
+            amount_x: 0,
+            amount_y: 0,
+            active_id: 0,
+            max_active_bin_slippage: 3,
+            strategy_parameters: StrategyParameters {
+                min_bin_id: 0,
+                max_bin_id: 0,
+                strategy_type: StrategyType::SpotBalanced,
+                parameteres: [0; 64],
+            },
+        },
+        0,
+        REASON_MANUAL,
+        true,
+    );
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(1_000_000);
+    f.h.send(&[budget, ix], &[&device, &new_position]).await.expect("widen");
+
+    assert!(f.h.data(&position.pubkey()).await.is_none());
+    let monitor: LpPositionMonitor = f.h.zero_copy(&pda::lp_monitor(&f.session).0).await;
+    assert_eq!(monitor.position, new_position.pubkey());
+    assert_eq!((monitor.min_bin_id, monitor.max_bin_id), (LOWER_BIN - 2, UPPER_BIN + 2));
+
+    // Same tokens re-deposited: the cost basis carries over
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(!registry.contains(&position.pubkey()));
+    let entry = registry.get(&new_position.pubkey()).expect("registered");
+    assert_eq!(entry.cost_basis, deposited.cost_basis);
+}
+
+#[tokio::test]
+async fn split_close_only_closes_the_position_it_emptied() {
+    let mut f = setup().await;
+    let device = f.device.insecure_clone();
+    let position = f.create_position().await.pubkey();
+    let ix = f.add_liquidity_ix(&position, 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+    let other = f.create_unmonitored_position().await.pubkey();
+
+    // Nothing emptied yet
+    let ix = f.close_empty_ix(&position, device.pubkey(), true);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::PositionNotEmptied);
+
+    let x_before = f.h.token_balance(&f.device_x).await;
+    let ix = f.remove_all_ix(&position);
+    f.h.send(&[ix], &[&device]).await.expect("remove all liquidity");
+
+    // In between: tokens are back, the position and its records are not
+    assert!(f.h.token_balance(&f.device_x).await > x_before);
+    let session: AgentSession = f.h.account(&f.session).await;
+    assert_eq!(session.emptied_position, position);
+    assert!(f.h.data(&position).await.is_some());
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(registry.contains(&position));
+
+    // Only the emptied position, and only to an allowed rent receiver
+    let ix = f.close_empty_ix(&other, device.pubkey(), false);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::PositionNotEmptied);
+    let ix = f.close_empty_ix(&position, Keypair::new().pubkey(), true);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::InvalidRentReceiver);
+
+    let ix = f.close_empty_ix(&position, device.pubkey(), true);
+    f.h.send(&[ix], &[&device]).await.expect("close empty position");
+    assert!(f.h.data(&position).await.is_none());
+    assert!(f.h.data(&pda::lp_monitor(&f.session).0).await.is_none());
+    let session: AgentSession = f.h.account(&f.session).await;
+    assert_eq!(session.emptied_position, Pubkey::default());
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(!registry.contains(&position));
+    assert!(registry.contains(&other));
+
+    // Cleared once closed: a second close has nothing to close
+    let ix = f.close_empty_ix(&position, device.pubkey(), false);
+    assert_agent_error(f.h.send(&[ix], &[&device]).await, AgentError::PositionNotEmptied);
+}
+
+#[tokio::test]
+async fn close_all_only_takes_registered_position_groups() {
+    let mut f = setup().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let position = f.create_position().await.pubkey();
+    let ix = f.add_liquidity_ix(&position, 100_000_000, 100_000_000);
+    f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+
+    // No group at all, or a trailing partial group
+    let ix = f.close_all_ix(&[], false);
+    assert_agent_error(f.h.send(&[ix], &[&owner, &device]).await, AgentError::InvalidPositionBatch);
+    let mut ix = f.close_all_ix(&[(position, f.pool.lb_pair)], false);
+    ix.accounts.push(AccountMeta::new_readonly(position, false));
+    assert_agent_error(f.h.send(&[ix], &[&owner, &device]).await, AgentError::InvalidPositionBatch);
+
+    // A registered position under another pool, and an unregistered position
+    let other = Pool::create(&mut f.h, 0).await;
+    let ix = f.close_all_ix(&[(position, other.lb_pair)], false);
+    assert_agent_error(f.h.send(&[ix], &[&owner, &device]).await, AgentError::InvalidPositionBatch);
+    let ix = f.close_all_ix(&[(Pubkey::new_unique(), f.pool.lb_pair)], false);
+    assert_agent_error(f.h.send(&[ix], &[&owner, &device]).await, AgentError::InvalidPositionBatch);
+
+    // A bad group fails the whole batch: nothing was closed
+    let ix = f.close_all_ix(&[(position, f.pool.lb_pair), (Pubkey::new_unique(), f.pool.lb_pair)], false);
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
+    assert_agent_error(f.h.send(&[budget, ix], &[&owner, &device]).await, AgentError::InvalidPositionBatch);
+    assert!(f.h.data(&position).await.is_some());
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(registry.contains(&position));
+}
+
+#[tokio::test]
+async fn close_all_settles_the_monitor_of_a_closed_position() {
+    let mut f = setup().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let monitored = f.create_position().await.pubkey();
+    let unmonitored = f.create_unmonitored_position().await.pubkey();
+    for position in [monitored, unmonitored] {
+        let ix = f.add_liquidity_ix(&position, 100_000_000, 100_000_000);
+        f.h.send(&[ix], &[&device]).await.expect("add liquidity");
+    }
+    f.h.send(&[instructions::initialize_daily_stats(owner.pubkey())], &[&owner])
+        .await
+        .expect("daily stats");
+    let ix = instructions::update_lp_status(
+        device.pubkey(),
+        owner.pubkey(),
+        f.pool.lb_pair,
+        monitored,
+        30,
+        40,
+        100_000_000,
+        100_000_000,
+    );
+    f.h.send(&[ix], &[&device]).await.expect("checkpoint");
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
+
+    // Partial batch: the monitor tracks a position outside it and stays
+    let ix = f.close_all_ix(&[(unmonitored, f.pool.lb_pair)], true);
+    f.h.send(&[budget.clone(), ix], &[&owner, &device]).await.expect("close unmonitored");
+    assert!(f.h.data(&unmonitored).await.is_none());
+    let monitor: LpPositionMonitor = f.h.zero_copy(&pda::lp_monitor(&f.session).0).await;
+    assert_eq!(monitor.position, monitored);
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert_eq!(registry.count, 1);
+
+    // Closing the monitored position settles its fees and closes the monitor
+    let ix = f.close_all_ix(&[(monitored, f.pool.lb_pair)], true);
+    f.h.send(&[budget, ix], &[&owner, &device]).await.expect("close monitored");
+    assert!(f.h.data(&pda::lp_monitor(&f.session).0).await.is_none());
+    let session: AgentSession = f.h.account(&f.session).await;
+    assert_eq!((session.total_fees_earned_x, session.total_fees_earned_y), (30, 40));
+    let stats: DailyStats = f.h.zero_copy(&pda::daily_stats(&f.session).0).await;
+    let earned = stats.days.iter().fold((0, 0), |(x, y), d| (x + d.fees_earned_x, y + d.fees_earned_y));
+    assert_eq!(earned, (30, 40));
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert_eq!(registry.count, 0);
+}
+
+#[tokio::test]
+async fn adopt_withdraws_only_into_the_session_keys_accounts() {
+    let mut f = setup().await;
+    let (owner, device) = (f.owner.insecure_clone(), f.device.insecure_clone());
+    let owner_x = f.h.create_ata(&f.pool.mint_x, &owner.pubkey(), 1_000_000_000).await;
+    let owner_y = f.h.create_ata(&f.pool.mint_y, &owner.pubkey(), 1_000_000_000).await;
+
+    // The owner opens and funds a position straight through DLMM
+    let old = Keypair::new();
+    let (bin_array_lower, bin_array_upper) = f.pool.position_bin_arrays(LOWER_BIN, UPPER_BIN);
+    let open = Harness::ix(
+        DLMM_PROGRAM_ID,
+        dlmm_accounts::InitializePosition2 {
+            payer: owner.pubkey(),
+            position: old.pubkey(),
+            lb_pair: f.pool.lb_pair,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+            event_authority: DLMM_EVENT_AUTHORITY,
+            program: DLMM_PROGRAM_ID,
+        },
+        dlmm_args::InitializePosition2 { lower_bin_id: LOWER_BIN, width: WIDTH },
+    );
+    let fund = Harness::ix(
+        DLMM_PROGRAM_ID,
+        dlmm_accounts::AddLiquidityByStrategy {
+            position: old.pubkey(),
+            lb_pair: f.pool.lb_pair,
+            bin_array_bitmap_extension: None,
+            user_token_x: owner_x,
+            user_token_y: owner_y,
+            reserve_x: f.pool.reserve_x,
+            reserve_y: f.pool.reserve_y,
+            token_x_mint: f.pool.mint_x,
+            token_y_mint: f.pool.mint_y,
+            bin_array_lower,
+            bin_array_upper,
+            sender: owner.pubkey(),
+            token_x_program: f.pool.token_x_program,
+            token_y_program: f.pool.token_y_program,
+            event_authority: DLMM_EVENT_AUTHORITY,
+            program: DLMM_PROGRAM_ID,
+        },
+        dlmm_args::AddLiquidityByStrategy { liquidity_parameter: spot_liquidity(100_000_000, 100_000_000) },
+    );
+    f.h.send(&[open, fund], &[&owner, &old]).await.expect("owner position");
+
+    // The withdrawal can't be pointed at the owner's accounts, nor cross mints
+    let new = Keypair::new();
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
+    let signers = [&owner, &device, &new];
+    let ix = f.adopt_ix(&old.pubkey(), &new.pubkey(), owner_x, f.device_y);
+    assert_anchor_error(f.h.send(&[budget.clone(), ix], &signers).await, ErrorCode::ConstraintTokenOwner);
+    let ix = f.adopt_ix(&old.pubkey(), &new.pubkey(), f.device_y, f.device_x);
+    assert_anchor_error(f.h.send(&[budget.clone(), ix], &signers).await, ErrorCode::ConstraintTokenMint);
+
+    let ix = f.adopt_ix(&old.pubkey(), &new.pubkey(), f.device_x, f.device_y);
+    f.h.send(&[budget, ix], &signers).await.expect("adopt");
+    assert!(f.h.data(&old.pubkey()).await.is_none());
+    assert_eq!(f.h.token_balance(&owner_x).await, 900_000_000);
+    assert!(f.h.token_balance(&f.device_x).await > 1_000_000_000);
+    assert!(f.h.token_balance(&f.device_y).await > 1_000_000_000);
+
+    let data = f.h.data(&new.pubkey()).await.expect("new position");
+    let position = defi_agent_client::accounts::decode_dlmm_position(&data).expect("decode");
+    assert_eq!(position.owner, device.pubkey());
+    let registry: PositionRegistry = f.h.zero_copy(&pda::position_registry(&f.session).0).await;
+    assert!(registry.contains(&new.pubkey()));
+}
@@ -0,0 +1,202 @@
+//! LP monitor registration and device checkpoints.
+
+use anchor_lang::prelude::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::{AlertConfig, LpPositionMonitor, STRATEGY_LP};
+use defi_agent_client::{instructions, pda};
+use defi_agent_localnet::{assert_agent_error, Harness, LAMPORTS_PER_SOL};
+
+#[tokio::test]
+async fn register_and_track_range() {
+    let mut h = Harness::start().await;
+    h.initialize_config().await;
+    let owner = Keypair::new();
+    let device = Keypair::new();
+    let session = h.create_session(&owner, &device, STRATEGY_LP, LAMPORTS_PER_SOL).await;
+    let monitor = pda::lp_monitor(&session).0;
+
+    let (lb_pair, position) = (Pubkey::new_unique(), Pubkey::new_unique());
+    h.send(
+        &[instructions::register_lp_monitor(owner.pubkey(), lb_pair, position, -5, 5)],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    let m: LpPositionMonitor = h.zero_copy(&monitor).await;
+    assert_eq!(m.session, session);
+    assert_eq!((m.min_bin_id, m.max_bin_id), (-5, 5));
+
+    // In range
+    h.set_active_bin(&lb_pair, 2);
+    h.send(
+        &[instructions::update_lp_status(device.pubkey(), owner.pubkey(), lb_pair, position, 10, 20, 500, 700)],
+        &[&device],
+    )
+    .await
+    .unwrap();
+    let m: LpPositionMonitor = h.zero_copy(&monitor).await;
+    assert_eq!(m.is_in_range, 1);
+    assert_eq!((m.fee_x_snapshot, m.fee_y_snapshot), (10, 20));
+    assert_eq!((m.amount_x_snapshot, m.amount_y_snapshot), (500, 700));
+
+    // Out of range
+    h.set_active_bin(&lb_pair, 9);
+    h.send(
+        &[instructions::update_lp_status(device.pubkey(), owner.pubkey(), lb_pair, position, 11, 21, 0, 1_200)],
+        &[&device],
+    )
+    .await
+    .unwrap();
+    let m: LpPositionMonitor = h.zero_copy(&monitor).await;
+    assert_eq!(m.is_in_range, 0);
+    assert_eq!(m.last_active_bin, 9);
+}
+
+#[tokio::test]
+async fn register_rejects_inverted_range() {
+    let mut h = Harness::start().await;
+    h.initialize_config().await;
+    let owner = Keypair::new();
+    let device = Keypair::new();
+    h.create_session(&owner, &device, STRATEGY_LP, LAMPORTS_PER_SOL).await;
+
+    let result = h
+        .send(
+            &[instructions::register_lp_monitor(owner.pubkey(), Pubkey::new_unique(), Pubkey::new_unique(), 5, -5)],
+            &[&owner],
+        )
+        .await;
+    assert_agent_error(result, AgentError::InvalidBinRange);
+}
+
+#[tokio::test]
+async fn reassign_moves_the_monitor_with_its_history() {
+    let mut h = Harness::start().await;
+    h.initialize_config().await;
+    let (owner, device) = (Keypair::new(), Keypair::new());
+    let session = h.create_session(&owner, &device, STRATEGY_LP, LAMPORTS_PER_SOL).await;
+    let (lb_pair, position) = (Pubkey::new_unique(), Pubkey::new_unique());
+    h.send(
+        &[instructions::register_lp_monitor(owner.pubkey(), lb_pair, position, -5, 5)],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    h.set_active_bin(&lb_pair, 2);
+    h.send(
+        &[instructions::update_lp_status(device.pubkey(), owner.pubkey(), lb_pair, position, 10, 20, 500, 700)],
+        &[&device],
+    )
+    .await
+    .unwrap();
+
+    // The same owner on both sides is rejected
+    let result = h
+        .send(&[instructions::reassign_monitor(owner.pubkey(), owner.pubkey(), false, &[position])], &[&owner])
+        .await;
+    assert_agent_error(result, AgentError::ReassignAccountMismatch);
+
+    let (new_owner, new_device) = (Keypair::new(), Keypair::new());
+    let new_session = h.create_session(&new_owner, &new_device, STRATEGY_LP, LAMPORTS_PER_SOL).await;
+    let reassign = instructions::reassign_monitor(owner.pubkey(), new_owner.pubkey(), false, &[position]);
+
+    // The position is still held by the old session's device
+    h.set_position(&position, &lb_pair, &device.pubkey());
+    let result = h.send(&[reassign.clone()], &[&owner, &new_owner]).await;
+    assert_agent_error(result, AgentError::ReassignPositionNotOwned);
+
+    // Omitting the position, or passing another one, is rejected too
+    let result = h
+        .send(&[instructions::reassign_monitor(owner.pubkey(), new_owner.pubkey(), false, &[])], &[&owner, &new_owner])
+        .await;
+    assert_agent_error(result, AgentError::ReassignPositionNotOwned);
+    let other = Pubkey::new_unique();
+    h.set_position(&other, &lb_pair, &new_device.pubkey());
+    let result = h
+        .send(&[instructions::reassign_monitor(owner.pubkey(), new_owner.pubkey(), false, &[other])], &[&owner, &new_owner])
+        .await;
+    assert_agent_error(result, AgentError::ReassignPositionNotOwned);
+
+    // Once a device of the receiving session holds it, the monitor moves
+    h.set_position(&position, &lb_pair, &new_device.pubkey());
+    h.send(&[reassign], &[&owner, &new_owner]).await.unwrap();
+
+    assert!(h.data(&pda::lp_monitor(&session).0).await.is_none());
+    let m: LpPositionMonitor = h.zero_copy(&pda::lp_monitor(&new_session).0).await;
+    assert_eq!(m.session, new_session);
+    assert_eq!((m.lb_pair, m.position), (lb_pair, position));
+    assert_eq!((m.min_bin_id, m.max_bin_id, m.last_active_bin), (-5, 5, 2));
+    assert_eq!((m.fee_x_snapshot, m.fee_y_snapshot), (10, 20));
+    assert_eq!((m.amount_x_snapshot, m.amount_y_snapshot), (500, 700));
+
+    // The new session's device checkpoints it from here on
+    h.set_active_bin(&lb_pair, 9);
+    h.send(
+        &[instructions::update_lp_status(
+            new_device.pubkey(),
+            new_owner.pubkey(),
+            lb_pair,
+            position,
+            11,
+            21,
+            0,
+            1_200,
+        )],
+        &[&new_device],
+    )
+    .await
+    .unwrap();
+    let m: LpPositionMonitor = h.zero_copy(&pda::lp_monitor(&new_session).0).await;
+    assert_eq!(m.is_in_range, 0);
+}
+
+#[tokio::test]
+async fn out_of_range_excursions_are_timed() {
+    let mut h = Harness::start().await;
+    h.initialize_config().await;
+    let (owner, device) = (Keypair::new(), Keypair::new());
+    let session = h.create_session(&owner, &device, STRATEGY_LP, LAMPORTS_PER_SOL).await;
+    let monitor = pda::lp_monitor(&session).0;
+    let (lb_pair, position) = (Pubkey::new_unique(), Pubkey::new_unique());
+    h.send(
+        &[
+            instructions::register_lp_monitor(owner.pubkey(), lb_pair, position, -5, 5),
+            instructions::set_alert_config(
+                owner.pubkey(),
+                AlertConfig {
+                    fees_lamports: 0,
+                    out_of_range_secs: 600,
+                    exposure_bps: 0,
+                },
+            ),
+        ],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    let checkpoint = |fee| {
+        instructions::update_lp_status(device.pubkey(), owner.pubkey(), lb_pair, position, fee, 0, 500, 700)
+    };
+
+    h.set_active_bin(&lb_pair, 9);
+    h.send(&[checkpoint(1)], &[&device]).await.unwrap();
+    let m: LpPositionMonitor = h.zero_copy(&monitor).await;
+    let since = m.out_of_range_since;
+    assert_eq!(since, m.last_checked_at);
+
+    // Later checkpoints keep the excursion's start
+    h.advance_clock(900).await;
+    h.set_active_bin(&lb_pair, 10);
+    h.send(&[checkpoint(2)], &[&device]).await.unwrap();
+    let m: LpPositionMonitor = h.zero_copy(&monitor).await;
+    assert_eq!(m.out_of_range_since, since);
+
+    // Back in range ends it
+    h.set_active_bin(&lb_pair, 0);
+    h.send(&[checkpoint(3)], &[&device]).await.unwrap();
+    let m: LpPositionMonitor = h.zero_copy(&monitor).await;
+    assert_eq!(m.out_of_range_since, 0);
+}
@@ -0,0 +1,587 @@
+//! Session lifecycle: config, magic program overrides, init, metadata, heartbeat, device limits,
+//! revocation, renewal, strategy revocation, the violation freeze, the
+//! exposure cooldown, batched actions, action receipts, signed intents, batched intent creation,
+//! the delegated fee payer and the fee sponsor.
+
+use anchor_lang::prelude::Pubkey;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+
+use defi_agent::errors::AgentError;
+use defi_agent::introspection::MAX_ACTION_MEMO_LEN;
+use defi_agent::state::{
+    ActionReceipt, ActionReceiptLog, ActionSpec, AgentSession, Config, FeeSponsor, Intent, IntentSpec, SessionLookup, ACTION_LP_REBALANCE,
+    ACTION_YIELD_SWITCH, REASON_MANUAL, STRATEGY_LP, STRATEGY_YIELD,
+};
+use defi_agent_client::instructions::MagicAccounts;
+use defi_agent_client::{instructions, pda};
+use defi_agent_localnet::{assert_agent_error, Harness, LAMPORTS_PER_SOL};
+
+async fn setup() -> (Harness, Keypair, Keypair, Pubkey) {
+    let mut h = Harness::start().await;
+    h.initialize_config().await;
+    let owner = Keypair::new();
+    let device = Keypair::new();
+    let session = h.create_session(&owner, &device, STRATEGY_LP, LAMPORTS_PER_SOL).await;
+    (h, owner, device, session)
+}
+
+#[tokio::test]
+async fn initialize_session_enrolls_device() {
+    let (mut h, owner, device, session) = setup().await;
+
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.owner, owner.pubkey());
+    assert_eq!(s.devices[0].key, device.pubkey());
+    assert!(s.is_active);
+    assert_eq!(s.strategy_mask, STRATEGY_LP);
+    assert_eq!(s.max_lamports, LAMPORTS_PER_SOL);
+
+    let lookup: SessionLookup = h.account(&pda::session_lookup(&device.pubkey()).0).await;
+    assert_eq!(lookup.device_key, device.pubkey());
+    assert_eq!(lookup.session, session);
+}
+
+#[tokio::test]
+async fn admin_overrides_magic_programs() {
+    let (mut h, owner, _device, _session) = setup().await;
+    let admin = h.admin.insecure_clone();
+    let (program, context) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let config: Config = h.account(&pda::config().0).await;
+    assert_eq!(config.magic_program_id(), MagicAccounts::SDK.program);
+    assert_eq!(config.magic_context_id(), MagicAccounts::SDK.context);
+
+    // Only the admin, and only both together and distinct
+    let result = h.send(&[instructions::set_magic_programs(owner.pubkey(), program, context)], &[&owner]).await;
+    assert_agent_error(result, AgentError::UnauthorizedAdmin);
+    for (p, c) in [(program, Pubkey::default()), (program, program), (defi_agent::ID, context)] {
+        let result = h.send(&[instructions::set_magic_programs(admin.pubkey(), p, c)], &[&admin]).await;
+        assert_agent_error(result, AgentError::InvalidMagicOverride);
+    }
+
+    h.send(&[instructions::set_magic_programs(admin.pubkey(), program, context)], &[&admin])
+        .await
+        .unwrap();
+    let magic = MagicAccounts::from_config(&h.account(&pda::config().0).await);
+    assert_eq!((magic.program, magic.context), (program, context));
+    assert_eq!(magic.config, Some(pda::config().0));
+
+    // Clearing both restores the SDK's
+    h.send(&[instructions::set_magic_programs(admin.pubkey(), Pubkey::default(), Pubkey::default())], &[&admin])
+        .await
+        .unwrap();
+    let config: Config = h.account(&pda::config().0).await;
+    assert_eq!(config.magic_program_id(), MagicAccounts::SDK.program);
+}
+
+#[tokio::test]
+async fn owner_names_the_session() {
+    let (mut h, owner, _device, session) = setup().await;
+
+    let label = "Living-room ESP32 — SOL/USDC";
+    h.send(
+        &[instructions::set_session_metadata(owner.pubkey(), label, Some("https://example.com/agent.json"))],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.label(), label);
+    assert_eq!(s.metadata_uri(), "https://example.com/agent.json");
+
+    h.send(&[instructions::set_session_metadata(owner.pubkey(), label, None)], &[&owner])
+        .await
+        .unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.metadata_uri(), "");
+
+    let too_long = "x".repeat(129);
+    let result = h
+        .send(
+            &[instructions::set_session_metadata(owner.pubkey(), label, Some(&too_long))],
+            &[&owner],
+        )
+        .await;
+    assert_agent_error(result, AgentError::InvalidSessionMetadata);
+}
+
+#[tokio::test]
+async fn heartbeat_records_last_seen() {
+    let (mut h, owner, device, session) = setup().await;
+
+    h.send(&[instructions::device_heartbeat(device.pubkey(), owner.pubkey())], &[&device])
+        .await
+        .unwrap();
+
+    let s: AgentSession = h.account(&session).await;
+    assert!(s.devices[0].last_seen_at > 0);
+}
+
+#[tokio::test]
+async fn execute_action_rejects_unknown_key() {
+    let (mut h, owner, _device, _session) = setup().await;
+    let stranger = Keypair::new();
+
+    let result = h
+        .send(
+            &[instructions::execute_action(stranger.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, 1_000, 0, REASON_MANUAL, false)],
+            &[&stranger],
+        )
+        .await;
+    assert_agent_error(result, AgentError::UnauthorizedSessionKey);
+}
+
+#[tokio::test]
+async fn execute_action_spends_against_cap() {
+    let (mut h, owner, device, session) = setup().await;
+
+    h.send(
+        &[instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, 100_000, 0, REASON_MANUAL, false)],
+        &[&device],
+    )
+    .await
+    .unwrap();
+
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.spent_lamports, 100_000);
+    assert_eq!(s.total_actions, 1);
+
+    let result = h
+        .send(
+            &[instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, LAMPORTS_PER_SOL, 0, REASON_MANUAL, false)],
+            &[&device],
+        )
+        .await;
+    assert_agent_error(result, AgentError::ExposureLimitExceeded);
+}
+
+#[tokio::test]
+async fn execute_actions_batch_applies_all_or_nothing() {
+    let (mut h, owner, device, session) = setup().await;
+    h.send(&[instructions::initialize_receipt_log(owner.pubkey())], &[&owner]).await.unwrap();
+    let spec = |action_type, amount_lamports| ActionSpec { action_type, amount_lamports, reason: REASON_MANUAL };
+    let batch = |actions| instructions::execute_actions_batch(device.pubkey(), owner.pubkey(), actions, 0, true);
+
+    h.send(&[batch(vec![spec(ACTION_LP_REBALANCE, 1_000); 3])], &[&device]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!((s.spent_lamports, s.total_actions), (3_000, 3));
+    let log: ActionReceiptLog = h.zero_copy(&pda::receipt_log(&session).0).await;
+    assert_eq!(log.count, 3);
+
+    // One action outside the strategy mask, or a combined notional over the
+    // exposure cap, rejects the whole batch
+    let mixed = vec![spec(ACTION_LP_REBALANCE, 2_000), spec(ACTION_YIELD_SWITCH, 2_000)];
+    let result = h.send(&[batch(mixed)], &[&device]).await;
+    assert_agent_error(result, AgentError::StrategyNotEnabled);
+    let half = LAMPORTS_PER_SOL / 2;
+    let result = h.send(&[batch(vec![spec(ACTION_LP_REBALANCE, half); 2])], &[&device]).await;
+    assert_agent_error(result, AgentError::ExposureLimitExceeded);
+    let result = h.send(&[batch(vec![])], &[&device]).await;
+    assert_agent_error(result, AgentError::InvalidActionBatch);
+
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!((s.spent_lamports, s.total_actions), (3_000, 3));
+}
+
+#[tokio::test]
+async fn execute_action_records_receipts() {
+    let (mut h, owner, device, session) = setup().await;
+    h.send(&[instructions::initialize_receipt_log(owner.pubkey())], &[&owner]).await.unwrap();
+
+    for amount in [1_000, 2_000] {
+        h.send(
+            &[instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, amount, 0, REASON_MANUAL, true)],
+            &[&device],
+        )
+        .await
+        .unwrap();
+    }
+
+    let log: ActionReceiptLog = h.zero_copy(&pda::receipt_log(&session).0).await;
+    assert_eq!(log.session, session);
+    assert_eq!(log.count, 2);
+    for (nonce, amount) in [(1, 1_000), (2, 2_000)] {
+        let receipt = log.get(nonce).expect("receipt");
+        let expected = ActionReceipt::digest(
+            &session,
+            &device.pubkey(),
+            ACTION_LP_REBALANCE,
+            amount,
+            REASON_MANUAL,
+            nonce,
+            receipt.slot,
+        );
+        assert_eq!(receipt.hash, expected);
+    }
+    assert!(log.get(3).is_none());
+
+    // Once the session has a log, actions can't skip it
+    let result = h
+        .send(
+            &[instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, 1_000, 0, REASON_MANUAL, false)],
+            &[&device],
+        )
+        .await;
+    assert_agent_error(result, AgentError::ReceiptLogRequired);
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.receipt_log, pda::receipt_log(&session).0);
+    let log: ActionReceiptLog = h.zero_copy(&pda::receipt_log(&session).0).await;
+    assert_eq!(log.count, 2);
+}
+
+#[tokio::test]
+async fn execute_action_accepts_a_memo() {
+    let (mut h, owner, device, session) = setup().await;
+    let execute = instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, 1_000, 0, REASON_MANUAL, false);
+
+    h.send(&[execute.clone(), instructions::action_memo("rebalance: price left range")], &[&device])
+        .await
+        .unwrap();
+    assert_eq!(h.account::<AgentSession>(&session).await.total_actions, 1);
+
+    let long = "x".repeat(MAX_ACTION_MEMO_LEN + 1);
+    let result = h.send(&[execute, instructions::action_memo(&long)], &[&device]).await;
+    assert_agent_error(result, AgentError::InvalidActionMemo);
+}
+
+#[tokio::test]
+async fn execute_action_rejects_unknown_reason() {
+    let (mut h, owner, device, session) = setup().await;
+
+    let result = h
+        .send(
+            &[instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, 1_000, 0, REASON_MANUAL + 1, false)],
+            &[&device],
+        )
+        .await;
+    assert_agent_error(result, AgentError::InvalidActionReason);
+    assert_eq!(h.account::<AgentSession>(&session).await.total_actions, 0);
+}
+
+#[tokio::test]
+async fn device_limits_extend_and_revoke() {
+    let (mut h, owner, device, session) = setup().await;
+
+    // Expire the device, then extend it again
+    let s: AgentSession = h.account(&session).await;
+    let now = s.expires_at - 86_400;
+    h.send(
+        &[instructions::set_device_limits(owner.pubkey(), device.pubkey(), now + 60, 0)],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    h.advance_clock(120).await;
+
+    let heartbeat = instructions::device_heartbeat(device.pubkey(), owner.pubkey());
+    assert_agent_error(h.send(&[heartbeat.clone()], &[&device]).await, AgentError::DeviceExpired);
+
+    h.send(
+        &[instructions::set_device_limits(owner.pubkey(), device.pubkey(), now + 3_600, 0)],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    h.send(&[heartbeat.clone()], &[&device]).await.unwrap();
+
+    // Revocation is final for the key
+    h.send(&[instructions::disable_device_key(owner.pubkey(), device.pubkey())], &[&owner])
+        .await
+        .unwrap();
+    assert_agent_error(h.send(&[heartbeat], &[&device]).await, AgentError::DeviceDisabled);
+}
+
+#[tokio::test]
+async fn renew_session_resets_the_term_in_place() {
+    let (mut h, owner, device, session) = setup().await;
+    let action = |amount| instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, amount, 0, REASON_MANUAL, false);
+    let renew = instructions::renew_session(owner.pubkey(), 3_600, 2 * LAMPORTS_PER_SOL, 0);
+
+    h.send(&[action(100_000)], &[&device]).await.unwrap();
+    assert_agent_error(h.send(&[renew.clone()], &[&owner]).await, AgentError::SessionStillActive);
+
+    h.advance_clock(86_400).await;
+    assert_agent_error(h.send(&[action(1)], &[&device]).await, AgentError::SessionExpired);
+
+    h.send(&[renew], &[&owner]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert!(s.is_active);
+    assert_eq!(s.max_lamports, 2 * LAMPORTS_PER_SOL);
+    assert_eq!((s.spent_lamports, s.devices[0].spent_lamports), (0, 0));
+    assert_eq!(s.total_actions, 1);
+    assert_eq!(s.devices[0].key, device.pubkey());
+
+    h.send(&[action(200_000)], &[&device]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!((s.spent_lamports, s.total_actions), (200_000, 2));
+}
+
+#[tokio::test]
+async fn repeated_scope_violations_suspend_the_session() {
+    let (mut h, owner, device, session) = setup().await;
+    let action = |amount| instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, amount, 0, REASON_MANUAL, false);
+    h.send(&[instructions::set_violation_freeze(owner.pubkey(), 3)], &[&owner])
+        .await
+        .unwrap();
+
+    // Unenrolled signers are rejected outright, never counted
+    let stranger = Keypair::new();
+    let result = h
+        .send(
+            &[instructions::execute_action(stranger.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, 1_000, 0, REASON_MANUAL, false)],
+            &[&stranger],
+        )
+        .await;
+    assert_agent_error(result, AgentError::UnauthorizedSessionKey);
+
+    // Over-cap attempts are recorded and skipped; an executed action ends the run
+    h.send(&[action(LAMPORTS_PER_SOL + 1)], &[&device]).await.unwrap();
+    h.send(&[action(LAMPORTS_PER_SOL + 2)], &[&device]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!((s.consecutive_violations, s.spent_lamports, s.suspended), (2, 0, false));
+    h.send(&[action(1_000)], &[&device]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.consecutive_violations, 0);
+
+    for i in 1..=3 {
+        h.send(&[action(LAMPORTS_PER_SOL + i)], &[&device]).await.unwrap();
+    }
+    let s: AgentSession = h.account(&session).await;
+    assert!(s.suspended);
+    assert_agent_error(h.send(&[action(2_000)], &[&device]).await, AgentError::SessionSuspended);
+
+    // The owner lifts the suspension
+    h.send(&[instructions::set_violation_freeze(owner.pubkey(), 3)], &[&owner])
+        .await
+        .unwrap();
+    h.send(&[action(2_000)], &[&device]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!((s.suspended, s.spent_lamports), (false, 3_000));
+}
+
+#[tokio::test]
+async fn hitting_the_exposure_cap_starts_a_cooldown() {
+    let (mut h, owner, device, session) = setup().await;
+    let action = |amount| instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, amount, 0, REASON_MANUAL, false);
+    h.send(&[instructions::set_exposure_cooldown(owner.pubkey(), 600)], &[&owner])
+        .await
+        .unwrap();
+    h.send(&[action(100_000)], &[&device]).await.unwrap();
+
+    // The over-cap action is skipped and the cooldown recorded
+    h.send(&[action(LAMPORTS_PER_SOL)], &[&device]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.spent_lamports, 100_000);
+    assert!(s.exposure_cooldown_until > 0);
+
+    assert_agent_error(h.send(&[action(1_000)], &[&device]).await, AgentError::ExposureCooldown);
+    // Housekeeping is unaffected
+    h.send(&[instructions::device_heartbeat(device.pubkey(), owner.pubkey())], &[&device])
+        .await
+        .unwrap();
+
+    h.advance_clock(600).await;
+    h.send(&[action(1_000)], &[&device]).await.unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.spent_lamports, 101_000);
+}
+
+#[tokio::test]
+async fn strategies_can_be_revoked_but_not_restored() {
+    let mut h = Harness::start().await;
+    h.initialize_config().await;
+    let (owner, device) = (Keypair::new(), Keypair::new());
+    let session = h.create_session(&owner, &device, STRATEGY_LP | STRATEGY_YIELD, LAMPORTS_PER_SOL).await;
+    let action = |action_type| instructions::execute_action(device.pubkey(), owner.pubkey(), action_type, 1_000, 0, REASON_MANUAL, false);
+
+    h.send(&[instructions::set_strategy_mask(owner.pubkey(), STRATEGY_LP)], &[&owner])
+        .await
+        .unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.strategy_mask, STRATEGY_LP);
+
+    assert_agent_error(h.send(&[action(ACTION_YIELD_SWITCH)], &[&device]).await, AgentError::StrategyNotEnabled);
+    h.send(&[action(ACTION_LP_REBALANCE)], &[&device]).await.unwrap();
+
+    let widen = instructions::set_strategy_mask(owner.pubkey(), STRATEGY_LP | STRATEGY_YIELD);
+    assert_agent_error(h.send(&[widen], &[&owner]).await, AgentError::StrategyMaskWidened);
+}
+
+#[tokio::test]
+async fn fee_payer_cannot_sign_as_a_device() {
+    let (mut h, owner, device, session) = setup().await;
+    let relayer = Keypair::new();
+
+    for invalid in [device.pubkey(), owner.pubkey()] {
+        let result = h.send(&[instructions::set_fee_payer(owner.pubkey(), Some(invalid))], &[&owner]).await;
+        assert_agent_error(result, AgentError::InvalidFeePayer);
+    }
+
+    h.send(&[instructions::set_fee_payer(owner.pubkey(), Some(relayer.pubkey()))], &[&owner])
+        .await
+        .unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.fee_payer, relayer.pubkey());
+
+    let result = h
+        .send(&[instructions::device_heartbeat(relayer.pubkey(), owner.pubkey())], &[&relayer])
+        .await;
+    assert_agent_error(result, AgentError::FeePayerCannotSign);
+    h.send(&[instructions::device_heartbeat(device.pubkey(), owner.pubkey())], &[&device])
+        .await
+        .unwrap();
+
+    h.send(&[instructions::set_fee_payer(owner.pubkey(), None)], &[&owner])
+        .await
+        .unwrap();
+    let s: AgentSession = h.account(&session).await;
+    assert_eq!(s.fee_payer, Pubkey::default());
+}
+
+#[tokio::test]
+async fn fee_sponsor_refills_devices_within_the_daily_cap() {
+    let (mut h, owner, device, session) = setup().await;
+    h.fund(&owner.pubkey(), 2 * LAMPORTS_PER_SOL).await;
+    let sponsor = pda::fee_sponsor(&session).0;
+    let refill = instructions::refill_session_key(owner.pubkey(), device.pubkey());
+
+    let target = 2 * LAMPORTS_PER_SOL;
+    let cap = LAMPORTS_PER_SOL / 2;
+    h.send(&[instructions::configure_fee_sponsor(owner.pubkey(), target, cap)], &[&owner])
+        .await
+        .unwrap();
+    h.send(&[instructions::fund_fee_sponsor(owner.pubkey(), LAMPORTS_PER_SOL)], &[&owner])
+        .await
+        .unwrap();
+
+    let before = h.lamports(&device.pubkey()).await;
+    h.send(&[refill.clone()], &[]).await.unwrap();
+    assert_eq!(h.lamports(&device.pubkey()).await, before + cap);
+    assert_agent_error(h.send(&[refill.clone()], &[]).await, AgentError::SponsorDailyCapReached);
+
+    // A new day resets the cap; the device is brought up to the target
+    h.advance_clock(86_400).await;
+    h.send(&[refill.clone()], &[]).await.unwrap();
+    assert_eq!(h.lamports(&device.pubkey()).await, target);
+    assert_agent_error(h.send(&[refill.clone()], &[]).await, AgentError::RefillNotNeeded);
+
+    let s: FeeSponsor = h.account(&sponsor).await;
+    assert_eq!(s.total_refilled_lamports, target - before);
+
+    let stranger = instructions::refill_session_key(owner.pubkey(), Keypair::new().pubkey());
+    assert_agent_error(h.send(&[stranger], &[]).await, AgentError::UnauthorizedSessionKey);
+
+    h.send(&[instructions::close_fee_sponsor(owner.pubkey())], &[&owner])
+        .await
+        .unwrap();
+    assert!(h.data(&sponsor).await.is_none());
+}
+
+#[tokio::test]
+async fn signed_intents_gate_the_devices_actions() {
+    let (mut h, owner, device, session) = setup().await;
+    let certificate = Keypair::new();
+    let ix = instructions::set_intent_signer(owner.pubkey(), device.pubkey(), certificate.pubkey());
+    h.send(&[ix], &[&owner]).await.unwrap();
+
+    let now = h.now().await;
+    let none = Pubkey::default();
+    let execute = instructions::execute_action(device.pubkey(), owner.pubkey(), ACTION_LP_REBALANCE, 1_000, 0, REASON_MANUAL, false);
+    let signed = |signer: &Keypair, pool: &Pubkey, amount: u64, nonce: u64, expires_at: i64| {
+        let message = instructions::signed_intent_message(&session, pool, amount, nonce, expires_at);
+        let signature: [u8; 64] = signer.sign_message(&message).as_ref().try_into().unwrap();
+        instructions::ed25519_verify(&signer.pubkey(), &signature, &message)
+    };
+
+    // Unsigned, signed by the hot key, or for another pool or amount
+    let result = h.send(&[execute.clone()], &[&device]).await;
+    assert_agent_error(result, AgentError::SignedIntentMissing);
+    for intent in [
+        signed(&device, &none, 1_000, 1, now + 60),
+        signed(&certificate, &Pubkey::new_unique(), 1_000, 1, now + 60),
+        signed(&certificate, &none, 999, 1, now + 60),
+    ] {
+        let result = h.send(&[intent, execute.clone()], &[&device]).await;
+        assert_agent_error(result, AgentError::SignedIntentMissing);
+    }
+
+    // Past its expiry
+    let result = h.send(&[signed(&certificate, &none, 1_000, 1, now - 1), execute.clone()], &[&device]).await;
+    assert_agent_error(result, AgentError::SignedIntentExpired);
+
+    h.send(&[signed(&certificate, &none, 1_000, 1, now + 60), execute.clone()], &[&device])
+        .await
+        .unwrap();
+    assert_eq!(h.account::<AgentSession>(&session).await.devices[0].intent_nonce, 1);
+
+    // A nonce is spent once; later intents need a higher one
+    for nonce in [1, 0] {
+        let result = h.send(&[signed(&certificate, &none, 1_000, nonce, now + 60), execute.clone()], &[&device]).await;
+        assert_agent_error(result, AgentError::SignedIntentReplayed);
+    }
+    h.send(&[signed(&certificate, &none, 1_000, 2, now + 60), execute.clone()], &[&device])
+        .await
+        .unwrap();
+
+    // Lifting the requirement
+    let ix = instructions::set_intent_signer(owner.pubkey(), device.pubkey(), Pubkey::default());
+    h.send(&[ix], &[&owner]).await.unwrap();
+    h.send(&[execute], &[&device]).await.unwrap();
+    assert_eq!(h.account::<AgentSession>(&session).await.total_actions, 3);
+}
+
+#[tokio::test]
+async fn intent_batches_are_created_whole_or_not_at_all() {
+    let (mut h, owner, _device, session) = setup().await;
+    let lb_pair = Pubkey::new_unique();
+    let deadline = h.now().await + 3_600;
+    let spec = |intent_id: u64, deadline: i64| IntentSpec {
+        intent_id,
+        lb_pair,
+        swap_for_y: intent_id % 2 == 0,
+        max_amount_in: 1_000 * intent_id,
+        min_amount_out: 900 * intent_id,
+        deadline,
+        keeper_fillable: intent_id == 3,
+        keeper_bounty_lamports: if intent_id == 3 { 50_000 } else { 0 },
+    };
+
+    // Empty, or intent accounts that don't match the specs
+    let result = h.send(&[instructions::create_intents_batch(owner.pubkey(), vec![])], &[&owner]).await;
+    assert_agent_error(result, AgentError::InvalidIntentBatch);
+    let mut ix = instructions::create_intents_batch(owner.pubkey(), vec![spec(1, deadline), spec(2, deadline)]);
+    ix.accounts.pop();
+    assert_agent_error(h.send(&[ix], &[&owner]).await, AgentError::InvalidIntentBatch);
+    let mut ix = instructions::create_intents_batch(owner.pubkey(), vec![spec(1, deadline), spec(2, deadline)]);
+    let len = ix.accounts.len();
+    ix.accounts.swap(len - 1, len - 2);
+    assert_agent_error(h.send(&[ix], &[&owner]).await, AgentError::InvalidIntentBatch);
+
+    // One invalid spec fails the batch, and nothing is created
+    let ix = instructions::create_intents_batch(owner.pubkey(), vec![spec(1, deadline), spec(2, deadline - 3_600)]);
+    assert_agent_error(h.send(&[ix], &[&owner]).await, AgentError::InvalidIntent);
+    assert!(h.data(&pda::intent(&session, 1).0).await.is_none());
+
+    let specs = vec![spec(1, deadline), spec(2, deadline), spec(3, deadline)];
+    h.send(&[instructions::create_intents_batch(owner.pubkey(), specs)], &[&owner]).await.unwrap();
+    for id in 1..=3 {
+        let intent: Intent = h.account(&pda::intent(&session, id).0).await;
+        assert_eq!((intent.session, intent.intent_id, intent.lb_pair), (session, id, lb_pair));
+        assert_eq!((intent.max_amount_in, intent.min_amount_out), (1_000 * id, 900 * id));
+        assert_eq!((intent.filled_amount, intent.deadline), (0, deadline));
+    }
+    // The keeper bounty is escrowed on top of rent
+    let rent = Rent::default().minimum_balance(Intent::LEN);
+    assert_eq!(h.lamports(&pda::intent(&session, 3).0).await, rent + 50_000);
+
+    // An id can't be created twice
+    let ix = instructions::create_intents_batch(owner.pubkey(), vec![spec(4, deadline), spec(2, deadline)]);
+    assert!(h.send(&[ix], &[&owner]).await.is_err());
+    assert!(h.data(&pda::intent(&session, 4).0).await.is_none());
+    let readonly = AccountMeta::new_readonly(pda::intent(&session, 5).0, false);
+    let mut ix = instructions::create_intents_batch(owner.pubkey(), vec![spec(5, deadline)]);
+    *ix.accounts.last_mut().unwrap() = readonly;
+    assert!(h.send(&[ix], &[&owner]).await.is_err(), "intent accounts must be writable");
+}
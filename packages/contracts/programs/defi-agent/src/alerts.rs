@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::events::{ExposureThresholdReached, FeeThresholdReached, OutOfRangeTooLong};
+use crate::state::{AgentSession, LpPositionMonitor, ALERT_EXPOSURE, ALERT_FEES};
+
+/// Evaluate the session's fee and exposure thresholds after an instruction's
+/// accounting, emitting each alert the first time its threshold is reached.
+/// Fired alerts are latched in `alerts_raised` until the owner resets them
+/// with `set_alert_config` (or the session is renewed).
+pub fn check_thresholds(session: &mut Account<AgentSession>) {
+    let config = session.alert_config;
+
+    let fees_paid = session.fees_paid_lamports();
+    if config.fees_lamports > 0
+        && fees_paid >= config.fees_lamports
+        && session.alerts_raised & ALERT_FEES == 0
+    {
+        session.alerts_raised |= ALERT_FEES;
+        emit!(FeeThresholdReached {
+            session: session.key(),
+            fees_paid_lamports: fees_paid,
+            threshold_lamports: config.fees_lamports,
+        });
+    }
+
+    let exposure_bps = session.exposure_bps();
+    if config.exposure_bps > 0
+        && exposure_bps >= config.exposure_bps as u64
+        && session.alerts_raised & ALERT_EXPOSURE == 0
+    {
+        session.alerts_raised |= ALERT_EXPOSURE;
+        emit!(ExposureThresholdReached {
+            session: session.key(),
+            exposure: session.exposure(),
+            max_lamports: session.max_lamports,
+            exposure_bps,
+            threshold_bps: config.exposure_bps,
+        });
+    }
+}
+
+/// Emit `OutOfRangeTooLong` when the checkpoint at `monitor.last_checked_at`
+/// is the first of the current out-of-range excursion to pass the owner's
+/// `out_of_range_secs`, given the previous checkpoint time. Fires once per
+/// excursion without a latch: earlier checkpoints fell short, later ones
+/// were already past.
+pub fn check_out_of_range(
+    session: &Account<AgentSession>,
+    monitor: &LpPositionMonitor,
+    previous_checked_at: i64,
+) {
+    let threshold = session.alert_config.out_of_range_secs;
+    let since = monitor.out_of_range_since;
+    if threshold == 0 || since == 0 {
+        return;
+    }
+    let was_past = previous_checked_at.saturating_sub(since) >= threshold;
+    let is_past = monitor.last_checked_at.saturating_sub(since) >= threshold;
+    if is_past && !was_past {
+        emit!(OutOfRangeTooLong {
+            session: session.key(),
+            position: monitor.position,
+            lb_pair: monitor.lb_pair,
+            out_of_range_since: since,
+            out_of_range_secs: threshold,
+        });
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+/// Current `amount` of an SPL Token or Token-2022 account.
+///
+/// Execute handlers snapshot the session key's token accounts around each
+/// DLMM CPI and charge exposure, fees and events on the observed deltas
+/// rather than on the instruction arguments.
+pub fn token_amount(account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount)
+}
+
+/// Tokens that left `account` since its balance was `before`
+pub fn outflow(account: &AccountInfo, before: u64) -> Result<u64> {
+    Ok(before.saturating_sub(token_amount(account)?))
+}
+
+/// Tokens that arrived in `account` since its balance was `before`
+pub fn inflow(account: &AccountInfo, before: u64) -> Result<u64> {
+    Ok(token_amount(account)?.saturating_sub(before))
+}
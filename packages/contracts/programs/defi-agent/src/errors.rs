@@ -22,4 +22,379 @@ pub enum AgentError {
 
     #[msg("min_bin_id must be <= max_bin_id")]
     InvalidBinRange,
+
+    #[msg("Operational fee spend exceeds the session's fee budget")]
+    FeeBudgetExceeded,
+
+    #[msg("Declared fee is lower than the priority fee / tips found in this transaction")]
+    FeeUnderDeclared,
+
+    #[msg("Instructions sysvar data is malformed")]
+    InvalidInstructionsSysvar,
+
+    #[msg("Unauthorized: signer is not the config admin")]
+    UnauthorizedAdmin,
+
+    #[msg("Protocol fee exceeds the maximum allowed basis points")]
+    InvalidFeeBps,
+
+    #[msg("Session duration must be positive")]
+    InvalidDuration,
+
+    #[msg("Protocol is paused by the admin")]
+    ProtocolPaused,
+
+    #[msg("Treasury accounts are required when a protocol fee is owed")]
+    TreasuryRequired,
+
+    #[msg("Treasury account does not match the ledger for this mint")]
+    InvalidTreasury,
+
+    #[msg("Withdrawal exceeds the treasury balance")]
+    InsufficientTreasuryBalance,
+
+    #[msg("Pool registry is full")]
+    PoolRegistryFull,
+
+    #[msg("Pool is not in the approved registry")]
+    PoolNotRegistered,
+
+    #[msg("Invalid risk tier")]
+    InvalidRiskTier,
+
+    #[msg("Action amount exceeds the per-action cap for this session")]
+    ActionLimitExceeded,
+
+    #[msg("Requested session limits exceed the protocol ceilings")]
+    SessionLimitExceeded,
+
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+
+    #[msg("Admin transfer timelock has not elapsed")]
+    AdminTimelockActive,
+
+    #[msg("Timelock must not be negative")]
+    InvalidTimelock,
+
+    #[msg("Referrer token account is required when a referral share is owed")]
+    ReferrerAccountRequired,
+
+    #[msg("Referrer token account does not belong to the session referrer for this mint")]
+    InvalidReferrerAccount,
+
+    #[msg("Fee tier index or mode is invalid")]
+    InvalidFeeTier,
+
+    #[msg("DLMM instructions are frozen by the admin")]
+    DlmmFrozen,
+
+    #[msg("Account is not a valid Config")]
+    InvalidConfigAccount,
+
+    #[msg("Config is already at or beyond the current version")]
+    ConfigUpToDate,
+
+    #[msg("Device key list is full")]
+    DeviceListFull,
+
+    #[msg("Device key is already enrolled")]
+    DeviceAlreadyEnrolled,
+
+    #[msg("Device key is not enrolled in this session")]
+    DeviceNotEnrolled,
+
+    #[msg("Device key must not be the default pubkey")]
+    InvalidDeviceKey,
+
+    #[msg("Device key has expired")]
+    DeviceExpired,
+
+    #[msg("Action amount exceeds the signing device's spend cap")]
+    DeviceLimitExceeded,
+
+    #[msg("Device requires an ed25519-signed intent matching this action")]
+    SignedIntentMissing,
+
+    #[msg("Signed intent has expired")]
+    SignedIntentExpired,
+
+    #[msg("Signed intent nonce has already been used")]
+    SignedIntentReplayed,
+
+    #[msg("Rotation grace period must not be negative")]
+    InvalidGracePeriod,
+
+    #[msg("Device key has been disabled by the owner")]
+    DeviceDisabled,
+
+    #[msg("Standby device is not active while a primary device is still heartbeating")]
+    StandbyNotActive,
+
+    #[msg("Standby delay must not be negative")]
+    InvalidStandbyDelay,
+
+    #[msg("Action notional exceeds the co-sign threshold; the session owner must also sign")]
+    OwnerCosignRequired,
+
+    #[msg("Unknown action request kind")]
+    InvalidRequestKind,
+
+    #[msg("Action request does not match this action or is not approved")]
+    RequestMismatch,
+
+    #[msg("Withdrawal above the large-withdrawal threshold must be queued first")]
+    WithdrawalNotQueued,
+
+    #[msg("Queued withdrawal is still inside its time lock")]
+    WithdrawalTimelockActive,
+
+    #[msg("Queued withdrawal does not match this amount or destination")]
+    WithdrawalMismatch,
+
+    #[msg("Withdrawal policy can only be tightened")]
+    WithdrawalPolicyLoosened,
+
+    #[msg("Intent must have a positive max amount and a future deadline")]
+    InvalidIntent,
+
+    #[msg("Intent deadline has passed")]
+    IntentExpired,
+
+    #[msg("Swap does not match the intent's pool or side")]
+    IntentMismatch,
+
+    #[msg("Swap amount exceeds the intent's approved size")]
+    IntentBoundsExceeded,
+
+    #[msg("Intent batch is empty, too large, or its accounts do not match the specs")]
+    InvalidIntentBatch,
+
+    #[msg("Intent is not open to keepers")]
+    IntentNotKeeperFillable,
+
+    #[msg("Input token account has not delegated to the intent PDA")]
+    IntentNotDelegated,
+
+    #[msg("Position is not in the session's bound position set")]
+    PositionNotBound,
+
+    #[msg("Too many bound positions")]
+    TooManyBoundPositions,
+
+    #[msg("Position registry is full")]
+    PositionRegistryFull,
+
+    #[msg("LP monitor does not track this position")]
+    MonitorPositionMismatch,
+
+    #[msg("Position accounts do not match the session's position registry")]
+    InvalidPositionBatch,
+
+    #[msg("Session already has a lookup table")]
+    LookupTableExists,
+
+    #[msg("Lookup table does not match the session")]
+    LookupTableMismatch,
+
+    #[msg("Lookup table extension must add between 1 and MAX_LOOKUP_TABLE_EXTEND addresses")]
+    InvalidLookupTableExtension,
+
+    #[msg("Position was not emptied by execute_dlmm_remove_all_liquidity")]
+    PositionNotEmptied,
+
+    #[msg("Swap route accounts are malformed")]
+    InvalidSwapRoute,
+
+    #[msg("Deposit batch must hold 1..=MAX_BATCH_DEPOSITS entries with matching accounts")]
+    InvalidDepositBatch,
+
+    #[msg("Token program does not own the mint")]
+    TokenProgramMismatch,
+
+    #[msg("Transaction compute-unit limit is below the configured minimum for this instruction")]
+    InsufficientComputeBudget,
+
+    #[msg("Merkle proof does not match the monitor tree root")]
+    InvalidMerkleProof,
+
+    #[msg("Monitor tree is full")]
+    MonitorTreeFull,
+
+    #[msg("Rent receiver must be the session key, session owner or fee vault")]
+    InvalidRentReceiver,
+
+    #[msg("Session duration exceeds the one-year maximum")]
+    DurationTooLong,
+
+    #[msg("Strategy mask sets bits that map to no known strategy")]
+    UnknownStrategyBits,
+
+    #[msg("Session exposure cap must be non-zero")]
+    ZeroExposureCap,
+
+    #[msg("Session key must differ from the session owner")]
+    SessionKeyIsOwner,
+
+    #[msg("Session is delegated to the Ephemeral Rollup — undelegate before using base-layer instructions")]
+    SessionDelegated,
+
+    #[msg("Session is not delegated to the Ephemeral Rollup")]
+    SessionNotDelegated,
+
+    #[msg("Action amount is below the session's minimum trade amount for this mint")]
+    BelowMinTradeAmount,
+
+    #[msg("Minimum trade amounts are already set for the maximum number of mints")]
+    MinTradeListFull,
+
+    #[msg("Declared decimals do not match the mint")]
+    MintDecimalsMismatch,
+
+    #[msg("Tokens moved by the CPI exceed the amount the instruction declared")]
+    BalanceDeltaExceeded,
+
+    #[msg("Tokens received are below the minimum output")]
+    OutputBelowMinimum,
+
+    #[msg("execute_action only accepts the strategy action types; DLMM operations have their own instructions")]
+    InvalidActionType,
+
+    #[msg("Valuation accounts must be the pool's token accounts of one enrolled device, passed all together")]
+    ValuationAccountMismatch,
+
+    #[msg("Every pool in the position registry must be passed to revalue_exposure")]
+    RevaluationPoolMissing,
+
+    #[msg("Transaction signature fees would exceed the session's transaction fee budget")]
+    TxFeeBudgetExceeded,
+
+    #[msg("Slippage bound exceeds 10,000 basis points")]
+    InvalidSlippageBps,
+
+    #[msg("Session has no settlement discrepancy awaiting review")]
+    NoReviewPending,
+
+    #[msg("Session is still active and unexpired; only an ended session can be renewed")]
+    SessionStillActive,
+
+    #[msg("Reassignment accounts mismatch: distinct sessions required, registries moved in pairs")]
+    ReassignAccountMismatch,
+
+    #[msg("Session is suspended after repeated scope violations")]
+    SessionSuspended,
+
+    #[msg("Execution is cooling down after hitting the exposure cap")]
+    ExposureCooldown,
+
+    #[msg("Exposure cooldown must be between 0 and 86400 seconds")]
+    InvalidCooldown,
+
+    #[msg("A strategy mask update may only clear bits, never set new ones")]
+    StrategyMaskWidened,
+
+    #[msg("Alert config out of bounds: durations must be non-negative, exposure at most 10000 bps")]
+    InvalidAlertConfig,
+
+    #[msg("Session label must be zero-padded UTF-8; metadata URI at most 128 bytes")]
+    InvalidSessionMetadata,
+
+    #[msg("Fee payer must not be the owner or an enrolled device key")]
+    InvalidFeePayer,
+
+    #[msg("The session's fee payer cannot sign as a session key")]
+    FeePayerCannotSign,
+
+    #[msg("Fee sponsor target must be rent-exempt for a wallet; cap and deposits non-zero")]
+    InvalidSponsorAmount,
+
+    #[msg("Device key already holds the sponsor's target balance")]
+    RefillNotNeeded,
+
+    #[msg("Fee sponsor has refilled its daily cap for today")]
+    SponsorDailyCapReached,
+
+    #[msg("Fee sponsor has no lamports above its rent-exempt minimum")]
+    InsufficientSponsorBalance,
+
+    #[msg("Liquidity shapes may only set the LIQUIDITY_SHAPES_ALL bits")]
+    InvalidLiquidityShapes,
+
+    #[msg("Deposit distribution is not one of the session's allowed liquidity shapes")]
+    LiquidityShapeNotAllowed,
+
+    #[msg("Action reason must be one of the REASON_* codes")]
+    InvalidActionReason,
+
+    #[msg("Pair pools must be 1..=MAX_PAIR_POOLS distinct LbPairs of the given mints, passed in order")]
+    InvalidPairPools,
+
+    #[msg("Pool is not on the owner's ranked list for its pair")]
+    PoolNotRanked,
+
+    #[msg("Pool's active bin is outside the swap's price band")]
+    ActiveBinOutOfBand,
+
+    #[msg("Swap would push the session's pool swap fees past its swap fee budget")]
+    SwapFeeBudgetExceeded,
+
+    #[msg("Commit cadence exceeded — run commit_session before executing further actions")]
+    CommitRequired,
+
+    #[msg("Commit cadence seconds must not be negative")]
+    InvalidCommitCadence,
+
+    #[msg("Account is not a writable, distinct account of this session")]
+    InvalidSessionAccount,
+
+    #[msg("Magic program and context overrides must be set or cleared together, and be distinct")]
+    InvalidMagicOverride,
+
+    #[msg("Action batch must hold 1..=MAX_ACTION_BATCH actions")]
+    InvalidActionBatch,
+
+    #[msg("Action memo must be UTF-8 of at most MAX_ACTION_MEMO_LEN bytes")]
+    InvalidActionMemo,
+
+    #[msg("Budget mint can only change before the session has spent anything")]
+    BudgetMintLocked,
+
+    #[msg("Budget mode: pass the DLMM pool pinned for the input mint to value it")]
+    BudgetPriceRequired,
+
+    #[msg("Budget price pool must pair the valued mint with the session's budget mint")]
+    InvalidBudgetPricePool,
+
+    #[msg("Account already has the zero-copy layout of this build")]
+    LpAccountUpToDate,
+
+    #[msg("Account is not a legacy LP monitor or position registry of this session")]
+    InvalidLpAccount,
+
+    #[msg("Device key is still enrolled in the session its lookup names")]
+    DeviceEnrolledElsewhere,
+
+    #[msg("The session has an ActionReceiptLog — it must be passed")]
+    ReceiptLogRequired,
+
+    #[msg("Budget price pool is not the one the owner pinned for this mint")]
+    BudgetPricePoolNotPinned,
+
+    #[msg("Budget price pools can be pinned for at most MAX_BUDGET_PRICE_POOLS mints")]
+    BudgetPricePoolListFull,
+
+    #[msg("Budget caps: the per-action cap must be non-zero and empty device slots uncapped")]
+    InvalidBudgetCaps,
+
+    #[msg("Valuation pool must be in the PoolRegistry or pinned as a budget price pool")]
+    ValuationPoolNotTrusted,
+
+    #[msg("Unauthorized: signer is neither the session owner nor an enrolled device")]
+    UnauthorizedTeardown,
+
+    #[msg("Reassigned position is missing or not owned by a device of the receiving session")]
+    ReassignPositionNotOwned,
+
+    #[msg("Intent escrow cannot pay the keeper bounty and stay rent-exempt")]
+    IntentBountyUnfunded,
 }
@@ -0,0 +1,360 @@
+use anchor_lang::prelude::*;
+use crate::state::STRATEGY_COUNT;
+
+/// Emitted whenever an execute instruction bills the session's per-action fee.
+#[event]
+pub struct ActionFeeCharged {
+    pub session: Pubkey,
+    pub action_type: u8,
+    pub fee_mode: u8,
+    pub notional: u64,
+    pub fee_lamports: u64,
+    pub total_fees_paid: u64,
+}
+
+/// Emitted when a device key is enrolled, binding it to the physical device it
+/// claims to be. Off-chain apps compare `attestation_hash` against the hash of
+/// the certificate the device presents.
+#[event]
+pub struct DeviceEnrolled {
+    pub session: Pubkey,
+    pub device_key: Pubkey,
+    pub slot: u8,
+    pub attestation_hash: [u8; 32],
+}
+
+/// Emitted once per `create_intents_batch` call, listing every intent created.
+#[event]
+pub struct IntentsCreated {
+    pub session: Pubkey,
+    pub intent_ids: Vec<u64>,
+}
+
+/// Emitted whenever a compressed monitor leaf is appended or checkpointed.
+/// Carries the full leaf so indexers can rebuild the tree and serve proofs.
+#[event]
+pub struct CompressedMonitorUpdated {
+    pub tree: Pubkey,
+    pub leaf_index: u32,
+    pub leaf: crate::state::CompressedMonitorLeaf,
+}
+
+/// Emitted by `update_lp_status` when a monitored position moves out of range.
+/// Off-chain relays turn it into a notification for the owner.
+#[event]
+pub struct OutOfRangeAlert {
+    pub session: Pubkey,
+    pub position: Pubkey,
+    pub lb_pair: Pubkey,
+    pub active_bin: i32,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+}
+
+/// Emitted after every DLMM swap with the session key's observed balance
+/// deltas. `amount_in` includes the protocol fee skimmed from the input;
+/// `expected_out` is the pre-trade active-bin quote for what was swapped,
+/// net of the pool fee. `pool_fee` is that estimated DLMM fee, in the Y units
+/// added to the session's `swap_fees_paid`.
+#[event]
+pub struct SwapSettled {
+    pub session: Pubkey,
+    pub lb_pair: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub min_amount_out: u64,
+    pub protocol_fee: u64,
+    pub expected_out: u64,
+    pub pool_fee: u64,
+}
+
+/// Emitted after every DLMM deposit with the session key's observed balance
+/// deltas, summed over all positions of a batch
+#[event]
+pub struct LiquidityDeposited {
+    pub session: Pubkey,
+    pub lb_pair: Pubkey,
+    pub amount_x: u64,
+    pub amount_y: u64,
+}
+
+/// Emitted whenever a portfolio valuation is recorded on the session.
+/// `checkpoint` is one of the `VALUATION_*` codes; `value` and
+/// `realized_pnl` are in `valuation_mint` units.
+#[event]
+pub struct PortfolioValued {
+    pub session: Pubkey,
+    pub checkpoint: u8,
+    pub valuation_mint: Pubkey,
+    pub value: u64,
+    pub realized_pnl: i64,
+}
+
+/// Emitted when a registered position's liquidity is withdrawn. `cost_basis`,
+/// `proceeds` and `realized_gain` are in the pool's token Y units; proceeds
+/// include the fees claimed with the liquidity.
+#[event]
+pub struct PositionRealized {
+    pub session: Pubkey,
+    pub position: Pubkey,
+    pub lb_pair: Pubkey,
+    pub deposited_x: u64,
+    pub deposited_y: u64,
+    pub cost_basis: u64,
+    pub withdrawn_x: u64,
+    pub withdrawn_y: u64,
+    pub proceeds: u64,
+    pub realized_gain: i64,
+}
+
+/// Emitted by every execute instruction once the action is counted, so
+/// off-chain stats can break `total_actions` down by operation. `amount` is
+/// the exposure charged (0 for closes and position opens). `reason` is the
+/// device's `REASON_*` code for rebalances and closes, REASON_UNSPECIFIED
+/// for everything else.
+#[event]
+pub struct ActionExecuted {
+    pub session: Pubkey,
+    pub action_type: u8,
+    pub amount: u64,
+    pub total_actions: u64,
+    pub reason: u8,
+}
+
+/// Emitted by `snapshot_session`: a compact, self-consistent summary of the
+/// session for off-chain archival. `seq` increases by one per snapshot, so a
+/// gap means a missed snapshot. `positions`, the monitor fields and the
+/// `today_*` totals are empty / zero when the corresponding account was not
+/// passed (`monitor_position` is Pubkey::default() then).
+#[event]
+pub struct SessionSnapshot {
+    pub session: Pubkey,
+    pub seq: u64,
+    pub taken_at: i64,
+    pub is_active: bool,
+    pub expires_at: i64,
+    pub max_lamports: u64,
+    pub spent_lamports: u64,
+    pub spent_by_strategy: [u64; STRATEGY_COUNT],
+    pub fee_budget_lamports: u64,
+    pub fee_spent_lamports: u64,
+    pub protocol_fees_paid: u64,
+    pub total_actions: u64,
+    pub realized_pnl: i64,
+    pub total_fees_earned_x: u64,
+    pub total_fees_earned_y: u64,
+    pub positions: Vec<Pubkey>,
+    pub monitor_position: Pubkey,
+    pub monitor_in_range: bool,
+    pub monitor_fee_x: u64,
+    pub monitor_fee_y: u64,
+    pub today_actions: u64,
+    pub today_volume: u64,
+    pub today_fees_paid: u64,
+}
+
+/// Emitted by `revalue_exposure`. `value` is the registry's positions at the
+/// pools' current prices, in token Y units; the session's
+/// `current_exposure_value` moved from `previous_exposure` to `exposure` by
+/// the change in `value - cost_basis` since the last revaluation.
+#[event]
+pub struct ExposureRevalued {
+    pub session: Pubkey,
+    pub positions: u8,
+    pub value: u64,
+    pub cost_basis: u64,
+    pub previous_exposure: u64,
+    pub exposure: u64,
+}
+
+/// Emitted when a swap's realized slippage tops the session's
+/// `max_slippage_bps`. `lb_pair` is the pool that delivered the output.
+#[event]
+pub struct SlippageExceeded {
+    pub session: Pubkey,
+    pub lb_pair: Pubkey,
+    pub expected_out: u64,
+    pub amount_out: u64,
+    pub slippage_bps: u64,
+    pub max_slippage_bps: u16,
+}
+
+/// Emitted when a closed position's withdrawal diverges from the monitor's
+/// last checkpoint (size plus unclaimed fees) by more than
+/// `SETTLEMENT_TOLERANCE_BPS` of value, flagging the session for owner review.
+/// A device reporting stale or wrong snapshots is the usual cause.
+#[event]
+pub struct SettlementDiscrepancy {
+    pub session: Pubkey,
+    pub position: Pubkey,
+    pub lb_pair: Pubkey,
+    pub expected_x: u64,
+    pub expected_y: u64,
+    pub withdrawn_x: u64,
+    pub withdrawn_y: u64,
+    pub gap_bps: u64,
+    pub checked_at: i64,
+}
+
+/// Emitted by `renew_session` when an ended session starts a new term in
+/// place. `previous_spent_lamports` is the ended term's spend, now zeroed;
+/// `total_actions` carries over.
+#[event]
+pub struct SessionRenewed {
+    pub session: Pubkey,
+    pub expires_at: i64,
+    pub max_lamports: u64,
+    pub max_action_lamports: u64,
+    pub previous_spent_lamports: u64,
+    pub total_actions: u64,
+}
+
+/// Emitted by `reassign_monitor`. `registry_positions` is the number of
+/// registry entries moved along; 0 when the registry stayed behind.
+#[event]
+pub struct MonitorReassigned {
+    pub from_session: Pubkey,
+    pub to_session: Pubkey,
+    pub position: Pubkey,
+    pub lb_pair: Pubkey,
+    pub registry_positions: u8,
+}
+
+/// Emitted when `execute_action` rejects an enrolled device's action as out of
+/// scope while the violation freeze is on. The rejection is recorded instead
+/// of failing the transaction; `error_code` is the check that failed.
+#[event]
+pub struct ScopeViolation {
+    pub session: Pubkey,
+    pub device: Pubkey,
+    pub error_code: u32,
+    pub consecutive_violations: u8,
+}
+
+/// Emitted when consecutive scope violations reach the session's
+/// `violation_threshold` and the session is suspended.
+#[event]
+pub struct SuspiciousActivity {
+    pub session: Pubkey,
+    pub device: Pubkey,
+    pub consecutive_violations: u8,
+    pub suspended_at: i64,
+}
+
+/// Emitted when `execute_action` rejects an action with
+/// `ExposureLimitExceeded` while a cooldown is configured; execute calls fail
+/// until `until`.
+#[event]
+pub struct ExposureCooldownStarted {
+    pub session: Pubkey,
+    pub device: Pubkey,
+    pub spent_lamports: u64,
+    pub max_lamports: u64,
+    pub until: i64,
+}
+
+/// Emitted by `set_strategy_mask` when the owner narrows a session's enabled
+/// strategies mid-term.
+#[event]
+pub struct StrategiesRevoked {
+    pub session: Pubkey,
+    pub previous_mask: u8,
+    pub strategy_mask: u8,
+}
+
+/// Emitted once the fees a session's devices have paid (priority plus
+/// signature fees) reach the owner's `alert_config.fees_lamports`.
+#[event]
+pub struct FeeThresholdReached {
+    pub session: Pubkey,
+    pub fees_paid_lamports: u64,
+    pub threshold_lamports: u64,
+}
+
+/// Emitted once the session's exposure reaches the owner's
+/// `alert_config.exposure_bps` share of `max_lamports`.
+#[event]
+pub struct ExposureThresholdReached {
+    pub session: Pubkey,
+    pub exposure: u64,
+    pub max_lamports: u64,
+    pub exposure_bps: u64,
+    pub threshold_bps: u16,
+}
+
+/// Emitted by `update_lp_status` at the checkpoint where the monitored
+/// position's current out-of-range excursion passes the owner's
+/// `alert_config.out_of_range_secs`.
+#[event]
+pub struct OutOfRangeTooLong {
+    pub session: Pubkey,
+    pub position: Pubkey,
+    pub lb_pair: Pubkey,
+    pub out_of_range_since: i64,
+    pub out_of_range_secs: i64,
+}
+
+/// Emitted by `set_session_metadata`, so indexers can name sessions without
+/// re-reading the account.
+#[event]
+pub struct SessionMetadataUpdated {
+    pub session: Pubkey,
+    pub label: String,
+    pub metadata_uri: String,
+}
+
+/// Emitted by `set_fee_payer`. `fee_payer` is Pubkey::default() when cleared.
+#[event]
+pub struct FeePayerSet {
+    pub session: Pubkey,
+    pub previous: Pubkey,
+    pub fee_payer: Pubkey,
+}
+
+/// Emitted by `refill_session_key` for every top-up a fee sponsor pays.
+#[event]
+pub struct SessionKeyRefilled {
+    pub session: Pubkey,
+    pub device: Pubkey,
+    pub lamports: u64,
+    /// The device key's balance after the refill
+    pub device_balance: u64,
+    /// Lamports refilled so far this UTC day, this refill included
+    pub refilled_today_lamports: u64,
+    /// Sponsor lamports left above its rent-exempt minimum
+    pub sponsor_balance: u64,
+}
+
+/// Emitted when an ER action finds its delegated session idle past
+/// `max_idle_slots` and deactivates it instead of executing.
+#[event]
+pub struct SessionIdled {
+    pub session: Pubkey,
+    pub device: Pubkey,
+    pub last_action_slot: u64,
+    pub slot: u64,
+}
+
+/// Emitted by `commit_session` for every checkpoint of ER state.
+#[event]
+pub struct SessionCommitted {
+    pub session: Pubkey,
+    /// Checkpoints over the session's lifetime, this one included
+    pub checkpoint_count: u64,
+    /// Session time of the commit — the new `last_committed_at`
+    pub committed_at: i64,
+    /// Actions executed on the ER since the previous commit
+    pub actions: u32,
+}
+
+/// Emitted after an execute instruction's `ActionExecuted` when the
+/// transaction carries an SPL Memo instruction — the memo annotates the
+/// action `total_actions` counts (the batch's last, for
+/// `execute_actions_batch`).
+#[event]
+pub struct ActionMemo {
+    pub session: Pubkey,
+    pub device: Pubkey,
+    pub total_actions: u64,
+    pub memo: String,
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TransferChecked};
+use crate::errors::AgentError;
+use crate::events::ActionFeeCharged;
+use crate::state::{AgentSession, TreasuryLedger, BPS_DENOMINATOR};
+
+/// Accounts involved in skimming a protocol fee out of a session-key token
+/// account. Everything but the source side is optional on the execute
+/// instructions so sessions keep working while the protocol fee is 0.
+pub struct FeeSkimAccounts<'a, 'info> {
+    pub token_program: AccountInfo<'info>,
+    pub from: AccountInfo<'info>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub authority: AccountInfo<'info>,
+    pub treasury: Option<AccountInfo<'info>>,
+    pub ledger: Option<&'a mut Account<'info, TreasuryLedger>>,
+    pub referrer_token: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+}
+
+fn transfer_fee<'info>(
+    accounts: &FeeSkimAccounts<'_, 'info>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            TransferChecked {
+                from: accounts.from.clone(),
+                mint: accounts.mint.to_account_info(),
+                to,
+                authority: accounts.authority.clone(),
+            },
+        ),
+        amount,
+        accounts.mint.decimals,
+    )
+}
+
+/// Transfer `fee` of `mint` from the session key's token account into the
+/// protocol treasury vault and record it on the per-mint ledger.
+///
+/// When the session has a `referrer` and the Config sets a referral share,
+/// that share of the fee is routed to the referrer's token account for the
+/// same mint instead; the treasury receives the remainder. Once a non-zero
+/// amount is owed to a destination its accounts must be supplied and match.
+pub fn skim_protocol_fee(
+    fee: u64,
+    referral_share_bps: u16,
+    referrer: Pubkey,
+    accounts: FeeSkimAccounts<'_, '_>,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    let referral_cut = if referrer == Pubkey::default() {
+        0
+    } else {
+        // referral_share_bps <= BPS_DENOMINATOR, so the cut never exceeds `fee`
+        ((fee as u128) * (referral_share_bps as u128) / (BPS_DENOMINATOR as u128)) as u64
+    };
+    let treasury_cut = fee - referral_cut;
+
+    if referral_cut > 0 {
+        let referrer_token = accounts
+            .referrer_token
+            .ok_or(AgentError::ReferrerAccountRequired)?;
+        require_keys_eq!(referrer_token.owner, referrer, AgentError::InvalidReferrerAccount);
+        require_keys_eq!(
+            referrer_token.mint,
+            accounts.mint.key(),
+            AgentError::InvalidReferrerAccount
+        );
+        transfer_fee(&accounts, referrer_token.to_account_info(), referral_cut)?;
+    }
+
+    if treasury_cut > 0 {
+        let treasury = accounts
+            .treasury
+            .clone()
+            .ok_or(AgentError::TreasuryRequired)?;
+        {
+            let ledger = accounts.ledger.as_deref().ok_or(AgentError::TreasuryRequired)?;
+            require_keys_eq!(ledger.mint, accounts.mint.key(), AgentError::InvalidTreasury);
+            require_keys_eq!(ledger.vault, treasury.key(), AgentError::InvalidTreasury);
+        }
+        transfer_fee(&accounts, treasury, treasury_cut)?;
+    }
+
+    if let Some(ledger) = accounts.ledger {
+        ledger.record_accrual(treasury_cut)?;
+        ledger.record_referral(referral_cut)?;
+    }
+
+    Ok(())
+}
+
+/// Bill the session's per-action fee (flat or bps of `notional`, per the tier
+/// chosen at init): transfer lamports from the session key to the fee vault
+/// PDA, tally them on the session, and emit `ActionFeeCharged`.
+pub fn charge_action_fee<'info>(
+    session: &mut Account<'info, AgentSession>,
+    action_type: u8,
+    notional: u64,
+    payer: AccountInfo<'info>,
+    fee_vault: AccountInfo<'info>,
+    system_program_info: AccountInfo<'info>,
+) -> Result<u64> {
+    let fee = session.per_action_fee(notional);
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program_info,
+            system_program::Transfer {
+                from: payer,
+                to: fee_vault,
+            },
+        ),
+        fee,
+    )?;
+
+    session.protocol_fees_paid = session
+        .protocol_fees_paid
+        .checked_add(fee)
+        .ok_or(AgentError::Overflow)?;
+
+    emit!(ActionFeeCharged {
+        session: session.key(),
+        action_type,
+        fee_mode: session.fee_mode,
+        notional,
+        fee_lamports: fee,
+        total_fees_paid: session.protocol_fees_paid,
+    });
+
+    Ok(fee)
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::{ExposureCooldownStarted, ScopeViolation, SessionIdled, SuspiciousActivity};
+use crate::state::AgentSession;
+
+/// Scope checks whose failure counts toward the violation freeze: a device
+/// acting with a disabled or expired key, outside its strategies, or above
+/// its caps. Session-level states (inactive, expired, suspended) and input
+/// errors are not the device overstepping its scope.
+const SCOPE_VIOLATIONS: [AgentError; 8] = [
+    AgentError::DeviceDisabled,
+    AgentError::DeviceExpired,
+    AgentError::StandbyNotActive,
+    AgentError::StrategyNotEnabled,
+    AgentError::ActionLimitExceeded,
+    AgentError::ExposureLimitExceeded,
+    AgentError::DeviceLimitExceeded,
+    AgentError::OwnerCosignRequired,
+];
+
+/// Program error code of `err`, if it is one of this program's errors
+fn error_code(err: &Error) -> Option<u32> {
+    match err {
+        Error::AnchorError(e) => Some(e.error_code_number),
+        Error::ProgramError(_) => None,
+    }
+}
+
+/// Route a failed scope check. A transaction that fails leaves no trace on
+/// the session, so a rejection the session reacts to is recorded instead: the
+/// action is skipped and `Ok` returned so the reaction persists.
+///
+/// - With an exposure cooldown set, `ExposureLimitExceeded` starts it
+///   (`ExposureCooldownStarted`); execute calls then fail in
+///   `validate_session` until it ends.
+/// - With the freeze on (`violation_threshold` > 0), a scope violation is
+///   counted (`ScopeViolation`); reaching the threshold suspends the session
+///   and emits `SuspiciousActivity`.
+///
+/// Anything else — both off, an unenrolled signer (reacting to those would
+/// let anyone stall a session), other errors — is returned as is.
+pub fn screen(
+    session: &mut Account<AgentSession>,
+    device: &Pubkey,
+    err: Error,
+    now: i64,
+) -> Result<()> {
+    let key = session.key();
+    screen_session(&key, session, device, err, now)
+}
+
+/// `screen` on the deserialized session stored at `key` — the core the
+/// handlers and the simulation share
+pub fn screen_session(
+    key: &Pubkey,
+    session: &mut AgentSession,
+    device: &Pubkey,
+    err: Error,
+    now: i64,
+) -> Result<()> {
+    let Some(code) = error_code(&err) else {
+        return Err(err);
+    };
+    let cooldown = session.exposure_cooldown_secs > 0
+        && code == u32::from(AgentError::ExposureLimitExceeded);
+    let violation = session.violation_threshold > 0
+        && SCOPE_VIOLATIONS.iter().any(|v| u32::from(*v) == code);
+    if !(cooldown || violation) || session.device_index(device).is_none() {
+        return Err(err);
+    }
+
+    if cooldown {
+        session.exposure_cooldown_until = now.saturating_add(session.exposure_cooldown_secs);
+        emit!(ExposureCooldownStarted {
+            session: *key,
+            device: *device,
+            spent_lamports: session.spent_lamports,
+            max_lamports: session.max_lamports,
+            until: session.exposure_cooldown_until,
+        });
+        msg!("Exposure cap hit: cooling down until {}", session.exposure_cooldown_until);
+    }
+    if !violation {
+        return Ok(());
+    }
+
+    let suspended = session.record_violation();
+    emit!(ScopeViolation {
+        session: *key,
+        device: *device,
+        error_code: code,
+        consecutive_violations: session.consecutive_violations,
+    });
+    if suspended {
+        emit!(SuspiciousActivity {
+            session: *key,
+            device: *device,
+            consecutive_violations: session.consecutive_violations,
+            suspended_at: now,
+        });
+    }
+
+    msg!(
+        "Scope violation recorded: code={}, consecutive={}/{}, suspended={}",
+        code,
+        session.consecutive_violations,
+        session.violation_threshold,
+        suspended,
+    );
+    Ok(())
+}
+
+/// Deactivate a delegated session an enrolled device touched after it sat
+/// idle past `max_idle_slots` (`AgentSession::is_idle`), returning whether it
+/// did. As with a screened violation, the caller then skips the action and
+/// returns `Ok` so the deactivation persists; the owner undelegates and
+/// starts a new term with `renew_session`. Calls from unenrolled signers are
+/// left to fail validation as usual.
+pub fn retire_if_idle(
+    session: &mut Account<AgentSession>,
+    device: &Pubkey,
+    slot: u64,
+) -> Result<bool> {
+    let key = session.key();
+    Ok(retire_session_if_idle(&key, session, device, slot))
+}
+
+/// `retire_if_idle` on the deserialized session stored at `key`
+pub fn retire_session_if_idle(
+    key: &Pubkey,
+    session: &mut AgentSession,
+    device: &Pubkey,
+    slot: u64,
+) -> bool {
+    if !session.is_active || !session.is_idle(slot) || session.device_index(device).is_none() {
+        return false;
+    }
+    session.is_active = false;
+    emit!(SessionIdled {
+        session: *key,
+        device: *device,
+        last_action_slot: session.er_last_action_slot,
+        slot,
+    });
+    msg!(
+        "Session idle since slot {} (max {} slots): deactivated at slot {}",
+        session.er_last_action_slot,
+        session.max_idle_slots,
+        slot,
+    );
+    true
+}
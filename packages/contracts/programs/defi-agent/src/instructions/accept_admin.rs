@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::Config;
+use crate::errors::AgentError;
+
+/// [Base Layer] Step 2 of an admin transfer: the nominee takes over.
+///
+/// Signed by `pending_admin`, and only after `admin_transfer_available_at`.
+/// Clears the pending slot so the proposal cannot be replayed.
+pub fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.config;
+
+    require!(
+        clock.unix_timestamp >= config.admin_transfer_available_at,
+        AgentError::AdminTimelockActive
+    );
+
+    let previous = config.admin;
+    config.admin = config.pending_admin;
+    config.pending_admin = Pubkey::default();
+    config.admin_transfer_available_at = 0;
+
+    msg!("Admin transferred: {} -> {}", previous, config.admin);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The proposed admin — must sign
+    pub pending_admin: Signer<'info>,
+
+    /// Global Config PDA — must have `pending_admin` nominated
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_admin != Pubkey::default() @ AgentError::NoPendingAdmin,
+        constraint = config.pending_admin == pending_admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
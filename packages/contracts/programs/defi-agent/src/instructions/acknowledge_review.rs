@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Clear the session's `needs_review` flag.
+///
+/// Signed by the session owner once the `SettlementDiscrepancy` that raised it
+/// has been looked into — typically by comparing the device's reported
+/// snapshots with the close transaction. The flag is informational: the
+/// session keeps executing while it is set.
+pub fn handler(ctx: Context<AcknowledgeReview>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    require!(session.needs_review, AgentError::NoReviewPending);
+    session.needs_review = false;
+
+    msg!("Session review acknowledged: session={}", session.key());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcknowledgeReview<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
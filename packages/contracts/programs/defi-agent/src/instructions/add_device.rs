@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::DeviceEnrolled;
+use crate::state::{AgentSession, SessionLookup};
+
+/// [Base Layer] Enroll an additional device key on the session.
+///
+/// Signed by the session owner. Lets a backup ESP32 or a phone-side signer
+/// act for the same session; every enrolled key passes the same scope checks
+/// and draws from the same exposure cap.
+///
+/// `attestation_hash` is the hash of the device's hardware ID / secure-element
+/// certificate (all-zero if the device is not attested). The key's
+/// `SessionLookup` entry is pointed at this session; an entry naming another
+/// session needs it as `previous_session`, no longer listing the key.
+pub fn handler(
+    ctx: Context<AddDevice>,
+    device_key: Pubkey,
+    attestation_hash: [u8; 32],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let session = &mut ctx.accounts.session;
+    let slot = session.enroll_device(device_key, attestation_hash, clock.unix_timestamp)?;
+    ctx.accounts.session_lookup.set(
+        device_key,
+        session.key(),
+        ctx.bumps.session_lookup,
+        ctx.accounts.previous_session.as_deref(),
+    )?;
+
+    emit!(DeviceEnrolled {
+        session: session.key(),
+        device_key,
+        slot: slot as u8,
+        attestation_hash,
+    });
+
+    msg!("Device enrolled: key={}, slot={}", device_key, slot);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(device_key: Pubkey)]
+pub struct AddDevice<'info> {
+    /// The wallet owner of the session — pays for the key's lookup entry
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Reverse lookup from `device_key` to this session
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = SessionLookup::LEN,
+        seeds = [b"session_lookup", device_key.as_ref()],
+        bump,
+    )]
+    pub session_lookup: Account<'info, SessionLookup>,
+
+    /// CHECK: The session `session_lookup` currently names, when it names
+    /// another one — checked in `SessionLookup::set`
+    pub previous_session: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
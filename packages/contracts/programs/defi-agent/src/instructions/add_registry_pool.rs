@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, PoolRegistry, RISK_TIER_HIGH};
+use crate::errors::AgentError;
+
+/// [Base Layer] Add a vetted DLMM pool to the global registry.
+///
+/// Admin-only. Re-adding an existing pool updates its risk tier in place.
+pub fn handler(ctx: Context<AddRegistryPool>, lb_pair: Pubkey, risk_tier: u8) -> Result<()> {
+    require!(risk_tier <= RISK_TIER_HIGH, AgentError::InvalidRiskTier);
+
+    let registry = &mut ctx.accounts.pool_registry;
+    registry.upsert(lb_pair, risk_tier)?;
+
+    msg!(
+        "Registry pool added: lb_pair={}, risk_tier={}, total={}",
+        lb_pair,
+        risk_tier,
+        registry.pools.len(),
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddRegistryPool<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Account<'info, PoolRegistry>,
+}
@@ -0,0 +1,238 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::dlmm;
+use crate::state::{
+    ActionKind, AgentSession, Config, PoolRegistry, PositionRegistry, TemporalSource,
+    ACTION_DLMM_OPEN_POSITION,
+};
+use crate::errors::AgentError;
+
+/// [Base Layer] Move a position opened by the owner's wallet under session-key
+/// ownership so the agent can manage it.
+///
+/// DLMM has no ownership transfer, so the position is recreated in one
+/// transaction signed by the owner, the session key and the new position
+/// keypair:
+///   1. `remove_all_liquidity` — the owner withdraws everything (including
+///      pending fees) straight into the session key's token accounts for the
+///      pool's mints — checked here, since DLMM only requires the owner's
+///      signature and would pay out to any account.
+///   2. `close_position2` — the old position's rent returns to the owner.
+///   3. `initialize_position2` — a new position over `lower_bin_id` / `width`
+///      is opened with the session key as owner (rent paid by the owner).
+///
+/// The new position is appended to the session's PositionRegistry. The device
+/// re-deposits the withdrawn tokens afterwards with `execute_dlmm_add_liquidity`.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, AdoptPosition<'info>>,
+    lower_bin_id: i32,
+    width: i32,
+) -> Result<()> {
+    require!(width > 0, AgentError::InvalidBinRange);
+
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    // ── Session validation ──────────────────────────────────────────────────
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_OPEN_POSITION),
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    session.validate_pool(
+        ctx.accounts.pool_registry.as_deref(),
+        &ctx.accounts.lb_pair.key(),
+    )?;
+
+    let dlmm_prog = ctx.accounts.dlmm_program.to_account_info();
+
+    // ── Step 1: Withdraw the owner's liquidity into the session key's ATAs ──
+    let remove_accounts = dlmm::cpi::accounts::RemoveAllLiquidity {
+        position: ctx.accounts.old_position.to_account_info(),
+        lb_pair: ctx.accounts.lb_pair.to_account_info(),
+        bin_array_bitmap_extension: ctx
+            .accounts
+            .bin_array_bitmap_extension
+            .as_ref()
+            .map(|a| a.to_account_info()),
+        user_token_x: ctx.accounts.session_token_x.to_account_info(),
+        user_token_y: ctx.accounts.session_token_y.to_account_info(),
+        reserve_x: ctx.accounts.reserve_x.to_account_info(),
+        reserve_y: ctx.accounts.reserve_y.to_account_info(),
+        token_x_mint: ctx.accounts.token_x_mint.to_account_info(),
+        token_y_mint: ctx.accounts.token_y_mint.to_account_info(),
+        bin_array_lower: ctx.accounts.bin_array_lower.to_account_info(),
+        bin_array_upper: ctx.accounts.bin_array_upper.to_account_info(),
+        sender: ctx.accounts.owner.to_account_info(),
+        token_x_program: ctx.accounts.token_x_program.to_account_info(),
+        token_y_program: ctx.accounts.token_y_program.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: dlmm_prog.clone(),
+    };
+    dlmm::cpi::remove_all_liquidity(CpiContext::new(dlmm_prog.clone(), remove_accounts))?;
+
+    // ── Step 2: Close the owner's now-empty position ───────────────────────
+    let close_accounts = dlmm::cpi::accounts::ClosePosition2 {
+        position: ctx.accounts.old_position.to_account_info(),
+        sender: ctx.accounts.owner.to_account_info(),
+        rent_receiver: ctx.accounts.owner.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: dlmm_prog.clone(),
+    };
+    dlmm::cpi::close_position2(CpiContext::new(dlmm_prog.clone(), close_accounts))?;
+
+    // ── Step 3: Recreate the position under the session key ────────────────
+    let init_accounts = dlmm::cpi::accounts::InitializePosition2 {
+        payer: ctx.accounts.owner.to_account_info(),
+        position: ctx.accounts.new_position.to_account_info(),
+        lb_pair: ctx.accounts.lb_pair.to_account_info(),
+        owner: ctx.accounts.session_key.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: dlmm_prog.clone(),
+    };
+    dlmm::cpi::initialize_position2(
+        CpiContext::new(dlmm_prog, init_accounts),
+        lower_bin_id,
+        width,
+    )?;
+
+    // ── Register the adopted position ───────────────────────────────────────
+    PositionRegistry::load_or_init(
+        &ctx.accounts.position_registry,
+        session.key(),
+        ctx.bumps.position_registry,
+    )?
+    .add(
+        ctx.accounts.new_position.key(),
+        ctx.accounts.lb_pair.key(),
+        clock.unix_timestamp,
+    )?;
+
+    msg!(
+        "Position adopted: old={}, new={}, lower_bin_id={}, width={}",
+        ctx.accounts.old_position.key(),
+        ctx.accounts.new_position.key(),
+        lower_bin_id,
+        width,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdoptPosition<'info> {
+    /// The wallet owner of the session — owns the old position, pays new rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The ESP32 session key — must sign as the new position's DLMM `owner`
+    pub session_key: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — adoption is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    /// The session's PositionRegistry — the new position is appended here;
+    /// created on first use at the owner's expense
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = PositionRegistry::LEN,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump,
+    )]
+    pub position_registry: AccountLoader<'info, PositionRegistry>,
+
+    // ── Meteora DLMM accounts ────────────────────────────────────────────────
+
+    #[account(mut)]
+    /// CHECK: Owner's existing position — must be owned by `owner`; closed here
+    pub old_position: UncheckedAccount<'info>,
+
+    /// New position account — fresh keypair, must sign
+    #[account(mut)]
+    pub new_position: Signer<'info>,
+
+    #[account(mut)]
+    /// CHECK: Meteora DLMM LB pair pool
+    pub lb_pair: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Optional bin array bitmap extension (null for pools near bin 0)
+    pub bin_array_bitmap_extension: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, token::authority = session_key, token::mint = token_x_mint)]
+    /// Session key's token X ATA (receives withdrawn X tokens)
+    pub session_token_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::authority = session_key, token::mint = token_y_mint)]
+    /// Session key's token Y ATA (receives withdrawn Y tokens)
+    pub session_token_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    /// CHECK: Pool token X reserve
+    pub reserve_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Pool token Y reserve
+    pub reserve_y: UncheckedAccount<'info>,
+
+    /// Token X mint
+    pub token_x_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Y mint
+    pub token_y_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    /// CHECK: Lower bin array covering the old position's range
+    pub bin_array_lower: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Upper bin array covering the old position's range
+    pub bin_array_upper: UncheckedAccount<'info>,
+
+    // ── Programs ──────────────────────────────────────────────────────────
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{ActionRequest, AgentSession};
+
+/// [Base Layer] Approve a device's pending action request.
+///
+/// Signed by the session owner. Permits exactly one execution of the action
+/// recorded in the request, bypassing the per-action cap, registry-only mode
+/// and the co-sign threshold for that action only. Cumulative exposure and
+/// device spend caps still apply.
+pub fn handler(ctx: Context<ApproveRequest>) -> Result<()> {
+    let request = &mut ctx.accounts.action_request;
+    request.approved = true;
+
+    msg!(
+        "Action request approved: id={}, kind={}, lb_pair={}, amount={}",
+        request.request_id,
+        request.kind,
+        request.lb_pair,
+        request.amount,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveRequest<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        mut,
+        seeds = [b"action_request", session.key().as_ref(), &action_request.request_id.to_le_bytes()],
+        bump = action_request.bump,
+    )]
+    pub action_request: Account<'info, ActionRequest>,
+}
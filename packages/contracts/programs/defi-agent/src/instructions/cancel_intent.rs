@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, Intent};
+
+/// [Base Layer] Withdraw an intent before it is fully filled.
+///
+/// Signed by the session owner. Works on live and expired intents alike;
+/// any tranches already filled stay executed. Refunds the rent to the owner.
+pub fn handler(ctx: Context<CancelIntent>) -> Result<()> {
+    let intent = &ctx.accounts.intent;
+
+    msg!(
+        "Intent cancelled: id={}, filled={}/{}",
+        intent.intent_id,
+        intent.filled_amount,
+        intent.max_amount_in,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelIntent<'info> {
+    /// The wallet owner of the session — receives the refunded rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"intent", session.key().as_ref(), &intent.intent_id.to_le_bytes()],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, Intent>,
+}
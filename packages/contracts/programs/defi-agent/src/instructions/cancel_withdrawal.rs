@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, PendingWithdrawal};
+use crate::errors::AgentError;
+
+/// [Base Layer] Discard a queued withdrawal before it executes.
+///
+/// Admin-only. Closes the pending entry and refunds its rent to the admin.
+pub fn handler(ctx: Context<CancelWithdrawal>) -> Result<()> {
+    let pending = &ctx.accounts.pending_withdrawal;
+
+    msg!(
+        "Withdrawal cancelled: asset={}, amount={}, destination={}",
+        pending.asset,
+        pending.amount,
+        pending.destination,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    /// Config admin — must sign, receives the refunded rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_withdrawal", pending_withdrawal.asset.as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+}
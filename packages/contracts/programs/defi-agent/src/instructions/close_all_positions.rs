@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use crate::balances;
+use crate::dlmm;
+use crate::state::{AgentSession, DailyStats, LpPositionMonitor, PositionRegistry};
+use crate::errors::AgentError;
+use crate::valuation::{self, VALUATION_CLOSE};
+
+/// Accounts passed in `remaining_accounts` per position, in order:
+/// position, lb_pair, bin_array_bitmap_extension (DLMM program id for none),
+/// user_token_x, user_token_y, reserve_x, reserve_y, token_x_mint,
+/// token_y_mint, bin_array_lower, bin_array_upper, token_x_program,
+/// token_y_program.
+pub const CLOSE_ALL_ACCOUNTS_PER_POSITION: usize = 13;
+
+/// [Base Layer] Panic button — remove all liquidity from and close every
+/// registered position passed in.
+///
+/// Signed by the session owner. DLMM requires the position owner to sign, so
+/// the session key co-signs; tokens return to its ATAs and position rent to
+/// the session key. Each position's accounts are passed as a
+/// `CLOSE_ALL_ACCOUNTS_PER_POSITION` group in `remaining_accounts`; the
+/// position and its pool must match an entry in the session's
+/// PositionRegistry, which is removed once closed. Transaction size limits
+/// the number of positions per call — repeat until the registry is empty.
+/// Each close reports `PositionRealized` against the entry's cost basis and
+/// records a portfolio valuation of the returned tokens.
+///
+/// Pass the session's LpPositionMonitor (and DailyStats) as
+/// `execute_dlmm_close_position` takes them: when the monitor tracks one of
+/// the closed positions, the withdrawal is checked against its last
+/// checkpoint, its fee checkpoint is added to the session's lifetime earnings
+/// and today's DailyStats bucket, and it is closed to the session key. A
+/// monitor tracking a position outside the batch is left in place.
+///
+/// Not gated on `paused` / `dlmm_frozen` so users can always exit.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseAllPositions<'info>>,
+) -> Result<()> {
+    let groups = ctx.remaining_accounts.len() / CLOSE_ALL_ACCOUNTS_PER_POSITION;
+    require!(
+        groups > 0 && ctx.remaining_accounts.len() % CLOSE_ALL_ACCOUNTS_PER_POSITION == 0,
+        AgentError::InvalidPositionBatch
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let dlmm_prog = ctx.accounts.dlmm_program.to_account_info();
+    let mut registry = ctx.accounts.position_registry.load_mut()?;
+    let mut monitor_settled = false;
+
+    for accounts in ctx.remaining_accounts.chunks(CLOSE_ALL_ACCOUNTS_PER_POSITION) {
+        let position = &accounts[0];
+        let lb_pair = &accounts[1];
+        require!(
+            registry
+                .active()
+                .iter()
+                .any(|p| p.position == position.key() && p.lb_pair == lb_pair.key()),
+            AgentError::InvalidPositionBatch
+        );
+        let bitmap_ext = (accounts[2].key() != dlmm::ID).then(|| accounts[2].clone());
+
+        let x_before = balances::token_amount(&accounts[3])?;
+        let y_before = balances::token_amount(&accounts[4])?;
+
+        // ── Remove all liquidity → tokens return to session key's ATAs ─────
+        let remove_accounts = dlmm::cpi::accounts::RemoveAllLiquidity {
+            position: position.clone(),
+            lb_pair: lb_pair.clone(),
+            bin_array_bitmap_extension: bitmap_ext,
+            user_token_x: accounts[3].clone(),
+            user_token_y: accounts[4].clone(),
+            reserve_x: accounts[5].clone(),
+            reserve_y: accounts[6].clone(),
+            token_x_mint: accounts[7].clone(),
+            token_y_mint: accounts[8].clone(),
+            bin_array_lower: accounts[9].clone(),
+            bin_array_upper: accounts[10].clone(),
+            sender: ctx.accounts.session_key.to_account_info(),
+            token_x_program: accounts[11].clone(),
+            token_y_program: accounts[12].clone(),
+            event_authority: ctx.accounts.event_authority.to_account_info(),
+            program: dlmm_prog.clone(),
+        };
+        dlmm::cpi::remove_all_liquidity(CpiContext::new(dlmm_prog.clone(), remove_accounts))?;
+        let withdrawn_x = balances::inflow(&accounts[3], x_before)?;
+        let withdrawn_y = balances::inflow(&accounts[4], y_before)?;
+
+        // ── Close the now-empty position → rent to the session key ─────────
+        let close_accounts = dlmm::cpi::accounts::ClosePosition2 {
+            position: position.clone(),
+            sender: ctx.accounts.session_key.to_account_info(),
+            rent_receiver: ctx.accounts.session_key.to_account_info(),
+            event_authority: ctx.accounts.event_authority.to_account_info(),
+            program: dlmm_prog.clone(),
+        };
+        dlmm::cpi::close_position2(CpiContext::new(dlmm_prog.clone(), close_accounts))?;
+
+        if let Some(entry) = registry.remove(&position.key()) {
+            valuation::report_realized(
+                ctx.accounts.session.key(),
+                &entry,
+                lb_pair,
+                withdrawn_x,
+                withdrawn_y,
+            )?;
+        }
+
+        if let Some(monitor) = ctx.accounts.monitor.as_ref() {
+            let tracked = *monitor.load()?;
+            if tracked.position == position.key() {
+                let session = &mut ctx.accounts.session;
+                valuation::verify_settlement(
+                    session,
+                    &tracked,
+                    lb_pair,
+                    withdrawn_x,
+                    withdrawn_y,
+                )?;
+                session.record_fees_earned(&tracked);
+                if let Some(stats) = &ctx.accounts.daily_stats {
+                    stats.load_mut()?.record_fees_earned(now, &tracked);
+                }
+                monitor_settled = true;
+            }
+        }
+
+        valuation::record(
+            &mut ctx.accounts.session,
+            lb_pair,
+            &accounts[3],
+            &accounts[4],
+            VALUATION_CLOSE,
+            now,
+        )?;
+    }
+
+    if monitor_settled {
+        if let Some(monitor) = ctx.accounts.monitor.as_ref() {
+            monitor.close(ctx.accounts.session_key.to_account_info())?;
+        }
+    }
+    ctx.accounts.session.last_action_at = now;
+
+    msg!(
+        "Closed {} positions, {} still registered",
+        groups,
+        registry.count,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseAllPositions<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The ESP32 session key — must co-sign as the DLMM position owner
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = session.owner == owner.key(),
+        constraint = session.device_index(&session_key.key()).is_some()
+            @ AgentError::UnauthorizedSessionKey,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The session's PositionRegistry — closed positions are removed from it
+    #[account(
+        mut,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: AccountLoader<'info, PositionRegistry>,
+
+    /// The session's LpPositionMonitor — settled and closed to the session key
+    /// when it tracks one of the closed positions
+    #[account(
+        mut,
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump = monitor.load()?.bump,
+    )]
+    pub monitor: Option<AccountLoader<'info, LpPositionMonitor>>,
+
+    /// The session's DailyStats — when passed, settled monitor fees are
+    /// tallied into today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, FeeSponsor};
+
+/// [Base Layer] Close the session's fee sponsor, returning its rent and any
+/// unspent balance to the owner.
+pub fn handler(ctx: Context<CloseFeeSponsor>) -> Result<()> {
+    msg!(
+        "Fee sponsor closed: refunded={}, total_refilled={}",
+        ctx.accounts.fee_sponsor.to_account_info().lamports(),
+        ctx.accounts.fee_sponsor.total_refilled_lamports
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseFeeSponsor<'info> {
+    /// The wallet owner of the session — receives the sponsor's lamports
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"fee_sponsor", session.key().as_ref()],
+        bump = fee_sponsor.bump,
+    )]
+    pub fee_sponsor: Account<'info, FeeSponsor>,
+}
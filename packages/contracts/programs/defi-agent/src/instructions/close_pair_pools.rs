@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, PairPools};
+
+/// [Base Layer] Drop the owner's ranked pool list for a pair and reclaim its
+/// rent. Signed by the session owner. Once the last list is closed, new
+/// positions and migrations may target any pool again (subject to the
+/// session's other pool scope).
+pub fn handler(ctx: Context<ClosePairPools>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.pair_pool_lists = session.pair_pool_lists.saturating_sub(1);
+    msg!(
+        "Pair pools closed: {}/{} ({} lists left)",
+        ctx.accounts.pair_pools.token_x_mint,
+        ctx.accounts.pair_pools.token_y_mint,
+        session.pair_pool_lists
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClosePairPools<'info> {
+    /// The wallet owner of the session — receives the rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"pair_pools",
+            session.key().as_ref(),
+            pair_pools.token_x_mint.as_ref(),
+            pair_pools.token_y_mint.as_ref(),
+        ],
+        bump = pair_pools.bump,
+    )]
+    pub pair_pools: Account<'info, PairPools>,
+}
@@ -1,29 +1,78 @@
 use anchor_lang::prelude::*;
-use ephemeral_rollups_sdk::anchor::commit;
 use ephemeral_rollups_sdk::ephem::commit_accounts;
-use crate::state::AgentSession;
+use crate::errors::AgentError;
+use crate::events::SessionCommitted;
+use crate::state::{magic_context_for, magic_program_for, ActionReceiptLog, AgentSession, Config};
 
 /// Commits the current session state from the ER back to Solana mainnet
 /// WITHOUT undelegating. The session stays active on the ER.
 ///
 /// Must be sent to the EPHEMERAL ROLLUP.
 /// Use this periodically to checkpoint state (e.g. after large actions).
+/// The session must be marked delegated; its status is committed unchanged.
+/// Passing the delegated ActionReceiptLog commits it too, landing the
+/// receipts of the actions applied so far on the base layer and freeing
+/// their slots for new receipts. Each commit
+/// opens a new commit-cadence window (`set_commit_cadence`). Passing the
+/// Config applies its magic program/context overrides (`set_magic_programs`).
+///
+/// Each commit counts into `checkpoint_count`, stamps `last_committed_at`
+/// and emits `SessionCommitted`, so the owner can see how often ER state is
+/// actually checkpointed.
 pub fn handler(ctx: Context<CommitSession>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let actions = session.uncommitted_actions;
+    session.mark_committed(Clock::get()?.unix_timestamp);
+    session.checkpoint_count = session
+        .checkpoint_count
+        .checked_add(1)
+        .ok_or(AgentError::Overflow)?;
+    emit!(SessionCommitted {
+        session: session.key(),
+        checkpoint_count: session.checkpoint_count,
+        committed_at: session.last_committed_at,
+        actions,
+    });
+    // Flush the new window and count so the committed state carries them
+    session.exit(&crate::ID)?;
+    if let Some(log) = &ctx.accounts.receipt_log {
+        log.load_mut()?.mark_committed();
+    }
+
+    let session = ctx.accounts.session.to_account_info();
+    let receipt_log = ctx.accounts.receipt_log.as_ref().map(|log| log.to_account_info());
+    let mut accounts = vec![&session];
+    accounts.extend(receipt_log.as_ref());
     commit_accounts(
         &ctx.accounts.payer,
-        vec![&ctx.accounts.session.to_account_info()],
+        accounts,
         &ctx.accounts.magic_context,
         &ctx.accounts.magic_program,
     )?;
     Ok(())
 }
 
-#[commit]
 #[derive(Accounts)]
 pub struct CommitSession<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut, constraint = session.is_delegated() @ AgentError::SessionNotDelegated)]
     pub session: Account<'info, AgentSession>,
+
+    /// The session's delegated ActionReceiptLog, committed alongside it
+    #[account(mut, seeds = [b"receipts", session.key().as_ref()], bump = receipt_log.load()?.bump)]
+    pub receipt_log: Option<AccountLoader<'info, ActionReceiptLog>>,
+
+    /// Global Config PDA — when passed, its magic program/context overrides apply
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Option<Account<'info, Config>>,
+
+    /// CHECK: MagicBlock magic context — the SDK's unless the Config overrides it
+    #[account(mut, address = magic_context_for(config.as_deref()))]
+    pub magic_context: UncheckedAccount<'info>,
+
+    /// CHECK: MagicBlock magic program — the SDK's unless the Config overrides it
+    #[account(address = magic_program_for(config.as_deref()))]
+    pub magic_program: UncheckedAccount<'info>,
 }
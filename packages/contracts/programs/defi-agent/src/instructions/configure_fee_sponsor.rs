@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, FeeSponsor};
+
+/// [Base Layer] Create or retune the session's fee sponsor.
+///
+/// Signed by the session owner, who pays the sponsor's rent on first use.
+/// `target_balance_lamports` is what `refill_session_key` tops a device key
+/// up to, at least a wallet's rent-exempt minimum so a refill can never leave
+/// a device below it; `daily_cap_lamports` bounds all refills per UTC day. Today's tally
+/// is kept across updates, so lowering the cap takes effect immediately.
+/// Fund the sponsor with `fund_fee_sponsor`.
+pub fn handler(
+    ctx: Context<ConfigureFeeSponsor>,
+    target_balance_lamports: u64,
+    daily_cap_lamports: u64,
+) -> Result<()> {
+    require!(
+        target_balance_lamports >= Rent::get()?.minimum_balance(0) && daily_cap_lamports > 0,
+        AgentError::InvalidSponsorAmount
+    );
+
+    let sponsor = &mut ctx.accounts.fee_sponsor;
+    sponsor.session = ctx.accounts.session.key();
+    sponsor.bump = ctx.bumps.fee_sponsor;
+    sponsor.target_balance_lamports = target_balance_lamports;
+    sponsor.daily_cap_lamports = daily_cap_lamports;
+
+    msg!(
+        "Fee sponsor configured: target_balance={}, daily_cap={}",
+        target_balance_lamports,
+        daily_cap_lamports
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfigureFeeSponsor<'info> {
+    /// The wallet owner of the session — pays the sponsor's rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = FeeSponsor::LEN,
+        seeds = [b"fee_sponsor", session.key().as_ref()],
+        bump,
+    )]
+    pub fee_sponsor: Account<'info, FeeSponsor>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use anchor_lang::system_program;
+use crate::state::{AgentSession, Intent, IntentSpec};
+
+/// [Base Layer] Pre-approve one DLMM swap for the device to fulfill.
+///
+/// Signed by the session owner, who also pays the intent's rent (refunded when
+/// it is fully filled or cancelled). The device can execute exactly this trade — pool, side,
+/// size and worst-case price — and nothing else through `fulfill_intent`.
+///
+/// With `keeper_fillable`, any keeper may also fill it via `keeper_fill_intent`;
+/// `keeper_bounty_lamports` is escrowed in the intent from the owner's wallet
+/// and paid out pro rata per fill. Unpaid bounty returns with the rent.
+pub fn handler(
+    ctx: Context<CreateIntent>,
+    intent_id: u64,
+    lb_pair: Pubkey,
+    swap_for_y: bool,
+    max_amount_in: u64,
+    min_amount_out: u64,
+    deadline: i64,
+    keeper_fillable: bool,
+    keeper_bounty_lamports: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let spec = IntentSpec {
+        intent_id,
+        lb_pair,
+        swap_for_y,
+        max_amount_in,
+        min_amount_out,
+        deadline,
+        keeper_fillable,
+        keeper_bounty_lamports,
+    };
+    ctx.accounts.intent.set_inner(Intent::from_spec(
+        ctx.accounts.session.key(),
+        &spec,
+        ctx.bumps.intent,
+        clock.unix_timestamp,
+    )?);
+
+    if keeper_bounty_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.intent.to_account_info(),
+                },
+            ),
+            keeper_bounty_lamports,
+        )?;
+    }
+
+    msg!(
+        "Intent created: id={}, lb_pair={}, swap_for_y={}, max_in={}, min_out={}, deadline={}, keeper_bounty={}",
+        intent_id,
+        lb_pair,
+        swap_for_y,
+        max_amount_in,
+        min_amount_out,
+        deadline,
+        if keeper_fillable { keeper_bounty_lamports } else { 0 },
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(intent_id: u64)]
+pub struct CreateIntent<'info> {
+    /// The wallet owner of the session — pays rent for the intent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Intent::LEN,
+        seeds = [b"intent", session.key().as_ref(), &intent_id.to_le_bytes()],
+        bump,
+    )]
+    pub intent: Account<'info, Intent>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentError;
+use crate::events::IntentsCreated;
+use crate::state::{AgentSession, Intent, IntentSpec, MAX_INTENT_BATCH};
+
+/// [Base Layer] Create up to `MAX_INTENT_BATCH` intents in one instruction.
+///
+/// Signed by the session owner, who pays every intent's rent and escrowed
+/// keeper bounty. Each spec is
+/// validated exactly like `create_intent`. The intent PDAs —
+/// `[b"intent", session, intent_id]`, in spec order — are passed as writable
+/// `remaining_accounts` and created here. A single `IntentsCreated` event
+/// lists all ids.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CreateIntentsBatch<'info>>,
+    specs: Vec<IntentSpec>,
+) -> Result<()> {
+    require!(
+        !specs.is_empty()
+            && specs.len() <= MAX_INTENT_BATCH
+            && specs.len() == ctx.remaining_accounts.len(),
+        AgentError::InvalidIntentBatch
+    );
+
+    let clock = Clock::get()?;
+    let session_key = ctx.accounts.session.key();
+    let rent = Rent::get()?.minimum_balance(Intent::LEN);
+    let mut intent_ids = Vec::with_capacity(specs.len());
+
+    for (spec, info) in specs.iter().zip(ctx.remaining_accounts.iter()) {
+        let id_bytes = spec.intent_id.to_le_bytes();
+        let (expected, bump) = Pubkey::find_program_address(
+            &[b"intent", session_key.as_ref(), &id_bytes],
+            ctx.program_id,
+        );
+        require_keys_eq!(info.key(), expected, AgentError::InvalidIntentBatch);
+
+        let intent = Intent::from_spec(session_key, spec, bump, clock.unix_timestamp)?;
+        let lamports = rent
+            .checked_add(spec.keeper_bounty_lamports)
+            .ok_or(AgentError::Overflow)?;
+
+        let bump_bytes = [bump];
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"intent", session_key.as_ref(), &id_bytes, &bump_bytes]];
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: info.clone(),
+                },
+                signer_seeds,
+            ),
+            lamports,
+            Intent::LEN as u64,
+            ctx.program_id,
+        )?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        intent.try_serialize(&mut &mut data[..])?;
+        intent_ids.push(spec.intent_id);
+    }
+
+    msg!("Intents created: session={}, count={}", session_key, intent_ids.len());
+
+    emit!(IntentsCreated {
+        session: session_key,
+        intent_ids,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateIntentsBatch<'info> {
+    /// The wallet owner of the session — pays rent for every intent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    pub system_program: Program<'info, System>,
+    // Intent PDAs to create → ctx.remaining_accounts (writable, in spec order)
+}
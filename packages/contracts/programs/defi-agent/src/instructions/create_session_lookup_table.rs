@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use crate::state::{ActionKind, AgentSession, TemporalSource};
+use crate::errors::AgentError;
+use crate::lookup_table::{
+    create_lookup_table_ix, derive_lookup_table_address, ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+};
+
+/// [Base Layer] Create an Address Lookup Table owned by the session PDA.
+///
+/// Signed by an enrolled device key, which pays the table rent. The session
+/// PDA is the table authority, so only this program (via
+/// `extend_session_lookup_table`) can add addresses to it. `recent_slot` must
+/// be a recent slot, as required by the lookup table program. The table
+/// address is recorded on the session so clients can build v0 transactions
+/// for the DLMM instructions without hitting the transaction size limit.
+pub fn handler(ctx: Context<CreateSessionLookupTable>, recent_slot: u64) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Housekeeping,
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    require!(
+        session.lookup_table == Pubkey::default(),
+        AgentError::LookupTableExists
+    );
+
+    let (expected, bump) = derive_lookup_table_address(&session.key(), recent_slot);
+    require_keys_eq!(
+        ctx.accounts.lookup_table.key(),
+        expected,
+        AgentError::LookupTableMismatch
+    );
+
+    invoke(
+        &create_lookup_table_ix(
+            expected,
+            session.key(),
+            ctx.accounts.session_key.key(),
+            recent_slot,
+            bump,
+        ),
+        &[
+            ctx.accounts.lookup_table.to_account_info(),
+            session.to_account_info(),
+            ctx.accounts.session_key.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    session.lookup_table = expected;
+
+    msg!("Session lookup table created: {}", expected);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateSessionLookupTable<'info> {
+    /// Enrolled device key — must sign and pays the table rent
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — becomes the table authority
+    #[account(
+        mut,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(mut)]
+    /// CHECK: New lookup table — derived from (session, recent_slot) and checked in handler
+    pub lookup_table: UncheckedAccount<'info>,
+
+    #[account(address = ADDRESS_LOOKUP_TABLE_PROGRAM_ID)]
+    /// CHECK: Address Lookup Table program
+    pub address_lookup_table_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::anchor::delegate;
+use ephemeral_rollups_sdk::cpi::DelegateConfig;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// Delegates the session's ActionReceiptLog PDA to the MagicBlock Ephemeral
+/// Rollup, so `execute_action` can record receipts there.
+/// Must be sent to the BASE LAYER, signed by the session owner.
+///
+/// Send it before `delegate_session` — the session must still be on the base
+/// layer. The log comes back with the session: pass it to
+/// `undelegate_session`, which commits and undelegates both.
+pub fn handler(ctx: Context<DelegateReceiptLog>) -> Result<()> {
+    let session = ctx.accounts.session.key();
+    // Method name is auto-generated as `delegate_<field_name>` by #[delegate] macro
+    ctx.accounts.delegate_receipt_log(
+        &ctx.accounts.owner,
+        &[b"receipts", session.as_ref()],
+        DelegateConfig::default(),
+    )?;
+    Ok(())
+}
+
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateReceiptLog<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// CHECK: PDA to delegate — must use AccountInfo with `del` constraint
+    #[account(mut, del, seeds = [b"receipts", session.key().as_ref()], bump)]
+    pub receipt_log: AccountInfo<'info>,
+}
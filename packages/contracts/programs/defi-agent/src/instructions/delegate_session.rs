@@ -1,13 +1,37 @@
 use anchor_lang::prelude::*;
 use ephemeral_rollups_sdk::anchor::delegate;
 use ephemeral_rollups_sdk::cpi::DelegateConfig;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, DELEGATION_DELEGATED};
 
 /// Delegates the AgentSession PDA to the MagicBlock Ephemeral Rollup.
 /// Must be sent to the BASE LAYER.
 ///
 /// After this, the ESP32 can execute actions at sub-100ms latency on the ER
 /// without requiring user approval on every transaction.
+///
+/// Stamps the base-layer clock into `clock_high_water` first, so on the ER the
+/// session never judges expiry against a time earlier than delegation, and
+/// marks the session `DELEGATION_DELEGATED`. Delegation also opens the first
+/// commit-cadence window and restarts the idle-slot clock
+/// (`set_max_idle_slots`). An inactive, expired or already delegated session
+/// cannot be delegated.
 pub fn handler(ctx: Context<DelegateSession>, owner: Pubkey) -> Result<()> {
+    // ── Stamp the base-layer clock and delegation status ─────────────────────
+    let clock = Clock::get()?;
+    {
+        let info = &ctx.accounts.agent_session;
+        let mut session = AgentSession::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(session.is_active, AgentError::SessionInactive);
+        require!(!session.is_expired(clock.unix_timestamp), AgentError::SessionExpired);
+        require!(!session.is_delegated(), AgentError::SessionDelegated);
+        session.observe_clock(clock.unix_timestamp);
+        session.delegation_status = DELEGATION_DELEGATED;
+        session.mark_committed(clock.unix_timestamp);
+        session.er_last_action_slot = 0; // ER slots don't follow base-layer ones
+        session.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+
     // Method name is auto-generated as `delegate_<field_name>` by #[delegate] macro
     ctx.accounts.delegate_agent_session(
         &ctx.accounts.payer,
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{ActionKind, AgentSession, TemporalSource};
+use crate::introspection::signature_fee_lamports;
+use crate::log_info;
+
+/// Liveness ping from a device key. Runs on whichever layer currently holds
+/// the session (base layer, or the ER while delegated).
+///
+/// Records the signer's `last_seen_at` so standby keys stay dormant while
+/// the primary is healthy. Devices that also run the LP monitor get the same
+/// effect from `update_lp_status`. Passing the instructions sysvar also counts
+/// the ping's signature fees against the session's transaction fee budget.
+pub fn handler(ctx: Context<DeviceHeartbeat>) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Housekeeping,
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    if let Some(ix_sysvar) = &ctx.accounts.instructions_sysvar {
+        session.record_tx_fee(signature_fee_lamports(ix_sysvar)?)?;
+    }
+
+    log_info!(session, "Heartbeat: device={}", ctx.accounts.session_key.key());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DeviceHeartbeat<'info> {
+    /// The device key — must sign
+    pub session_key: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Optional instructions sysvar — when passed, the transaction's
+    /// signature fees are counted into `tx_fees_lamports`
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
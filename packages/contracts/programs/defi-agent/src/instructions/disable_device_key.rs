@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Immediately invalidate one enrolled device key.
+///
+/// Signed by the session owner. Unlike `remove_device`, the slot keeps the
+/// key's record (attestation, spend history) for audit, but the key fails
+/// every subsequent `require_device` check. The session stays active and the
+/// other devices, positions and LP monitors are untouched. Free the slot later
+/// with `remove_device`.
+pub fn handler(ctx: Context<DisableDeviceKey>, device_key: Pubkey) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let slot = session
+        .device_index(&device_key)
+        .ok_or(AgentError::DeviceNotEnrolled)?;
+    session.devices[slot].disabled = true;
+
+    msg!("Device disabled: key={}, slot={}", device_key, slot);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DisableDeviceKey<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(mut, constraint = session.owner == owner.key())]
+    pub session: Account<'info, AgentSession>,
+}
@@ -1,49 +1,134 @@
 use anchor_lang::prelude::*;
-use crate::state::AgentSession;
+use crate::alerts;
+use crate::state::{
+    ActionKind, ActionReceiptLog, AgentSession, Config, DailyStats, TemporalSource,
+    ACTION_LIQUIDATION_PROTECT, REASON_MANUAL,
+};
 use crate::errors::AgentError;
+use crate::events::ActionExecuted;
+use crate::freeze;
+use crate::log_info;
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
 
 /// Called by the ESP32 on the EPHEMERAL ROLLUP using the session key.
 ///
 /// Validates:
 /// - session is active and not expired
-/// - signer is the registered session key
+/// - signer is one of the session's enrolled device keys
 /// - requested strategy is enabled in the session's strategy_mask
-/// - cumulative spend stays within max_lamports cap
+/// - the amount meets the owner's minimum trade amount for the notional's
+///   mint, if set: the budget mint in budget mode, wrapped SOL otherwise
+/// - the action fits the per-action cap and cumulative spend stays within max_lamports
+/// - actions above `cosign_above_lamports` are also signed by the owner
+/// - if the device has an `intent_signer`, a matching ed25519-signed intent
+///   (pool = default pubkey, amount = `amount_lamports`) is present
+///
+/// With the session's violation freeze on, a failed scope check by an
+/// enrolled device is recorded rather than failing the transaction — the
+/// action is skipped — and enough of them in a row suspend the session. With
+/// an exposure cooldown set, hitting the exposure cap likewise starts the
+/// cooldown (see `freeze::screen`).
+///
+/// While delegated, the owner's commit cadence must not be exceeded
+/// (`CommitRequired` until `commit_session` runs), and a session idle past
+/// its `max_idle_slots` is deactivated instead of acting (see
+/// `freeze::retire_if_idle`).
+///
+/// Once the session has an ActionReceiptLog it must be passed, and the
+/// executed action is appended to it as a receipt (hash, slot, nonce); a
+/// skipped action leaves none. While delegated, a full log of uncommitted
+/// receipts fails with `CommitRequired`.
 ///
 /// `action_type`: 0 = LP rebalance, 1 = yield switch, 2 = liquidation protect
+/// (the DLMM operation codes are reserved for the DLMM instructions)
 /// `amount_lamports`: notional lamport exposure of this specific action
+/// `fee_lamports`: priority fee + tips the device attached to this transaction
+/// `reason`: why the device acted (`REASON_*`), recorded in `ActionExecuted`
 pub fn handler(
     ctx: Context<ExecuteAction>,
     action_type: u8,
     amount_lamports: u64,
+    fee_lamports: u64,
+    reason: u8,
 ) -> Result<()> {
     let session = &mut ctx.accounts.session;
     let clock = Clock::get()?;
 
-    require!(session.is_active, AgentError::SessionInactive);
-    require!(!session.is_expired(clock.unix_timestamp), AgentError::SessionExpired);
-
-    require_keys_eq!(
-        ctx.accounts.session_key.key(),
-        session.session_key,
-        AgentError::UnauthorizedSessionKey,
+    require!(reason <= REASON_MANUAL, AgentError::InvalidActionReason);
+    require!(
+        ctx.accounts.receipt_log.is_some() || !session.has_receipt_log(),
+        AgentError::ReceiptLogRequired
     );
+    let device = ctx.accounts.session_key.key();
+    let cosigner = ctx.accounts.cosigner.as_ref().map(|s| s.key());
+    let key = session.key();
+    let admitted = admit(
+        &key,
+        session,
+        &device,
+        action_type,
+        amount_lamports,
+        cosigner,
+        &clock,
+    )?;
+    let Some(device_slot) = admitted else {
+        return Ok(());
+    };
 
-    require!(session.has_strategy(action_type), AgentError::StrategyNotEnabled);
-
-    let new_spent = session
-        .spent_lamports
-        .checked_add(amount_lamports)
-        .ok_or(AgentError::Overflow)?;
-    require!(new_spent <= session.max_lamports, AgentError::ExposureLimitExceeded);
+    verify_declared_fee(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.session_key.key(),
+        fee_lamports,
+    )?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &Pubkey::default(),
+        amount_lamports,
+        clock.unix_timestamp,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    apply_action(
+        session,
+        device_slot,
+        action_type,
+        amount_lamports,
+        fee_lamports,
+        tx_fee,
+        &clock,
+    )?;
+    alerts::check_thresholds(session);
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, amount_lamports, 0);
+    }
+    if let Some(log) = &ctx.accounts.receipt_log {
+        let delegated = session.is_delegated();
+        log.load_mut()?
+            .record(&device, action_type, amount_lamports, reason, clock.slot, delegated)?;
+    }
 
-    session.spent_lamports = new_spent;
-    session.bump_actions()?;
-    session.last_action_at = clock.unix_timestamp;
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: action_type,
+        amount: amount_lamports,
+        total_actions: session.total_actions,
+        reason,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
 
-    msg!(
-        "Action executed: type={}, amount={}, total_spent={}/{}",
+    log_info!(
+        session,
+        "Action executed: type={}, reason={}, amount={}, total_spent={}/{}",
         action_type,
+        reason,
         amount_lamports,
         session.spent_lamports,
         session.max_lamports,
@@ -52,11 +137,118 @@ pub fn handler(
     Ok(())
 }
 
+/// The session-side gate of `execute_action`, shared with the simulation:
+/// retire an idle session, enforce the commit cadence, then check scope,
+/// screening a failure (see `freeze::screen`). Returns the device slot to
+/// charge, or `None` when the action is skipped. `key` is the session's
+/// address, for the events a skip emits.
+pub fn admit(
+    key: &Pubkey,
+    session: &mut AgentSession,
+    device: &Pubkey,
+    action_type: u8,
+    amount_lamports: u64,
+    cosigner: Option<Pubkey>,
+    clock: &Clock,
+) -> Result<Option<usize>> {
+    let now = clock.unix_timestamp;
+    require!(action_type <= ACTION_LIQUIDATION_PROTECT, AgentError::InvalidActionType);
+    if freeze::retire_session_if_idle(key, session, device, clock.slot) {
+        return Ok(None);
+    }
+    session.check_commit_cadence(now)?;
+    match check_scope(session, device, action_type, amount_lamports, cosigner, now) {
+        Ok(device_slot) => Ok(Some(device_slot)),
+        Err(err) => freeze::screen_session(key, session, device, err, now).map(|()| None),
+    }
+}
+
+/// Charge an admitted action to the session: declared and signature fees,
+/// spend against the device slot and strategy, and the action counters.
+pub fn apply_action(
+    session: &mut AgentSession,
+    device_slot: usize,
+    action_type: u8,
+    amount_lamports: u64,
+    fee_lamports: u64,
+    tx_fee: u64,
+    clock: &Clock,
+) -> Result<()> {
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+    session.apply_spend(device_slot, action_type, amount_lamports)?;
+    session.bump_actions()?;
+    session.mark_er_action(clock.slot);
+    session.last_action_at = clock.unix_timestamp;
+    Ok(())
+}
+
+/// Session, device and scope checks for an action of `amount_lamports`
+pub fn check_scope(
+    session: &mut AgentSession,
+    device: &Pubkey,
+    action_type: u8,
+    amount_lamports: u64,
+    cosigner: Option<Pubkey>,
+    now: i64,
+) -> Result<usize> {
+    let device_slot = session.validate_session(
+        device,
+        ActionKind::Action(action_type),
+        TemporalSource::Device(now),
+    )?;
+    session.check_min_trade(&session.notional_mint(), amount_lamports)?;
+    session.check_action_cap(amount_lamports)?;
+    session.check_exposure(device_slot, amount_lamports)?;
+    session.check_cosign(amount_lamports, cosigner)?;
+    Ok(device_slot)
+}
+
 #[derive(Accounts)]
 pub struct ExecuteAction<'info> {
     /// The ESP32 session key — must sign this transaction
     pub session_key: Signer<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
     pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while the protocol is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Session owner — must co-sign when the action's notional exceeds
+    /// `session.cosign_above_lamports`; pass `None` for routine actions
+    pub cosigner: Option<Signer<'info>>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+
+    /// The session's ActionReceiptLog — required once the session has one; a
+    /// receipt of the action is recorded (delegate it with the session to
+    /// record on the ER)
+    #[account(
+        mut,
+        seeds = [b"receipts", session.key().as_ref()],
+        bump = receipt_log.load()?.bump,
+    )]
+    pub receipt_log: Option<AccountLoader<'info, ActionReceiptLog>>,
 }
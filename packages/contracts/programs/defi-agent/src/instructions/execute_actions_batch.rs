@@ -0,0 +1,212 @@
+use anchor_lang::prelude::*;
+use crate::alerts;
+use crate::state::{
+    ActionKind, ActionReceiptLog, ActionSpec, AgentSession, Config, DailyStats, TemporalSource,
+    ACTION_LIQUIDATION_PROTECT, MAX_ACTION_BATCH, REASON_MANUAL,
+};
+use crate::errors::AgentError;
+use crate::events::ActionExecuted;
+use crate::freeze;
+use crate::log_info;
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
+
+/// [Ephemeral Rollup] Apply up to `MAX_ACTION_BATCH` actions in one
+/// instruction, signed by the session key.
+///
+/// The session, device, idle-slot rule and commit cadence are validated once
+/// for the whole batch; each action is then checked and applied as `execute_action` would
+/// (strategy, minimum trade amount, per-action cap). Checks that bound the
+/// device's total spend apply to the batch's combined notional: the session
+/// and device exposure caps, the owner co-sign threshold and a signed intent
+/// (pool = default pubkey, amount = the combined notional).
+///
+/// All actions pass their checks before any is applied, so the batch lands
+/// whole or not at all — with the violation freeze or exposure cooldown on,
+/// a failed scope check skips the entire batch (see `freeze::screen`).
+/// Each applied action counts toward `total_actions` and the commit cadence,
+/// emits its own `ActionExecuted`, is tallied into the DailyStats when passed
+/// and is recorded in the ActionReceiptLog, which must be passed once the
+/// session has one.
+///
+/// `fee_lamports`: priority fee + tips the device attached to this transaction
+pub fn handler(
+    ctx: Context<ExecuteActionsBatch>,
+    actions: Vec<ActionSpec>,
+    fee_lamports: u64,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    require!(
+        !actions.is_empty() && actions.len() <= MAX_ACTION_BATCH,
+        AgentError::InvalidActionBatch
+    );
+    for action in &actions {
+        require!(action.action_type <= ACTION_LIQUIDATION_PROTECT, AgentError::InvalidActionType);
+        require!(action.reason <= REASON_MANUAL, AgentError::InvalidActionReason);
+    }
+    require!(
+        ctx.accounts.receipt_log.is_some() || !session.has_receipt_log(),
+        AgentError::ReceiptLogRequired
+    );
+    let device = ctx.accounts.session_key.key();
+    if freeze::retire_if_idle(session, &device, clock.slot)? {
+        return Ok(());
+    }
+    session.check_commit_cadence_for(now, actions.len() as u32)?;
+    let cosigner = ctx.accounts.cosigner.as_ref().map(|s| s.key());
+    let (device_slot, total) = match check_batch_scope(session, &device, &actions, cosigner, now) {
+        Ok(scope) => scope,
+        Err(err) => return freeze::screen(session, &device, err, now),
+    };
+
+    verify_declared_fee(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.session_key.key(),
+        fee_lamports,
+    )?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &Pubkey::default(),
+        total,
+        now,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    let session_key = session.key();
+    let mut stats = match &ctx.accounts.daily_stats {
+        Some(stats) => Some(stats.load_mut()?),
+        None => None,
+    };
+    let delegated = session.is_delegated();
+    let mut log = match &ctx.accounts.receipt_log {
+        Some(log) => Some(log.load_mut()?),
+        None => None,
+    };
+    for action in &actions {
+        session.apply_spend(device_slot, action.action_type, action.amount_lamports)?;
+        session.bump_actions()?;
+        if let Some(stats) = stats.as_mut() {
+            stats.record_action(now, action.amount_lamports, 0);
+        }
+        if let Some(log) = log.as_mut() {
+            log.record(
+                &device,
+                action.action_type,
+                action.amount_lamports,
+                action.reason,
+                clock.slot,
+                delegated,
+            )?;
+        }
+        emit!(ActionExecuted {
+            session: session_key,
+            action_type: action.action_type,
+            amount: action.amount_lamports,
+            total_actions: session.total_actions,
+            reason: action.reason,
+        });
+    }
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session_key,
+        device,
+        session.total_actions,
+    )?;
+    session.mark_er_action(clock.slot);
+    alerts::check_thresholds(session);
+    session.last_action_at = now;
+
+    log_info!(
+        session,
+        "Actions executed: count={}, amount={}, total_spent={}/{}",
+        actions.len(),
+        total,
+        session.spent_lamports,
+        session.max_lamports,
+    );
+
+    Ok(())
+}
+
+/// Session and device checks once, then per-action scope checks and the
+/// combined-notional caps. Returns the device slot and combined notional.
+fn check_batch_scope(
+    session: &mut AgentSession,
+    device: &Pubkey,
+    actions: &[ActionSpec],
+    cosigner: Option<Pubkey>,
+    now: i64,
+) -> Result<(usize, u64)> {
+    let device_slot = session.validate_session(
+        device,
+        ActionKind::Action(actions[0].action_type),
+        TemporalSource::Device(now),
+    )?;
+    let notional_mint = session.notional_mint();
+    let mut total: u64 = 0;
+    for action in actions {
+        require!(session.has_strategy(action.action_type), AgentError::StrategyNotEnabled);
+        session.check_min_trade(&notional_mint, action.amount_lamports)?;
+        session.check_action_cap(action.amount_lamports)?;
+        total = total.checked_add(action.amount_lamports).ok_or(AgentError::Overflow)?;
+    }
+    session.check_exposure(device_slot, total)?;
+    session.check_cosign(total, cosigner)?;
+    Ok((device_slot, total))
+}
+
+#[derive(Accounts)]
+pub struct ExecuteActionsBatch<'info> {
+    /// The ESP32 session key — must sign this transaction
+    pub session_key: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while the protocol is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Session owner — must co-sign when the batch's combined notional exceeds
+    /// `session.cosign_above_lamports`; pass `None` for routine batches
+    pub cosigner: Option<Signer<'info>>,
+
+    /// The session's DailyStats — when passed, each action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+
+    /// The session's ActionReceiptLog — required once the session has one; a
+    /// receipt of each action is recorded
+    #[account(
+        mut,
+        seeds = [b"receipts", session.key().as_ref()],
+        bump = receipt_log.load()?.bump,
+    )]
+    pub receipt_log: Option<AccountLoader<'info, ActionReceiptLog>>,
+}
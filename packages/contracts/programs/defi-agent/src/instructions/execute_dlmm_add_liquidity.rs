@@ -1,39 +1,105 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+use crate::alerts;
 use crate::dlmm;
-use crate::state::AgentSession;
+use crate::state::{
+    ActionKind, ActionRequest, AgentSession, Config, DailyStats, PoolRegistry, PositionRegistry,
+    TemporalSource, ACTION_DLMM_ADD_LIQUIDITY, REASON_UNSPECIFIED, REQUEST_DLMM_ADD_LIQUIDITY,
+};
+use crate::balances;
 use crate::errors::AgentError;
+use crate::events::{ActionExecuted, LiquidityDeposited};
+use crate::freeze;
+use crate::log_info;
+use crate::valuation;
+use crate::fees::charge_action_fee;
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
 
 /// Called by the ESP32 on the BASE LAYER using the session key.
 ///
 /// Validates the session scope (active, not expired, session key matches,
 /// LP strategy enabled, combined exposure within cap) then CPIs into the
 /// Meteora DLMM program to add liquidity to an existing position.
-/// Updates session accounting after.
+/// Updates session accounting after, charging what actually left the session
+/// key's token accounts (never more than `amount_x + amount_y`).
 ///
 /// The position must already exist and be owned by the session key.
 /// `bin_array_lower` and `bin_array_upper` must cover the position's
 /// full bin range — derive their PDAs via `deriveBinArray` + `binIdToBinArrayIndex`
 /// from the `@meteora-ag/dlmm` SDK before building the transaction.
+///
+/// `fee_lamports` declares the priority fee + tips attached to this transaction;
+/// it is checked against the instructions sysvar and charged to the fee budget.
+///
+/// Devices with an `intent_signer` must also include an ed25519-verify
+/// instruction over the intent (session, lb_pair, amount_x + amount_y, nonce, expiry).
+///
+/// Passing an owner-approved `action_request` for this exact action waives the
+/// per-action cap, registry-only mode and co-sign threshold, once.
+///
+/// A failed scope check by an enrolled device is screened as in
+/// `execute_action` (see `freeze::screen`); a recorded one skips the deposit.
+/// With an `action_request` supplied the check fails outright, so the request
+/// is only consumed by a deposit that actually runs.
+///
+/// The deposit's distribution must be one of the session's `liquidity_shapes`
+/// (any when none are set); an approved request does not waive it.
+///
+/// Passing the session's PositionRegistry adds the deposit, valued at the
+/// pool price, to the position's cost basis.
+///
+/// For a session with a `budget_mint`, caps, co-sign threshold and exposure
+/// count the deposit in that mint's units: valued through the pool itself
+/// when it trades against the budget mint, otherwise in Y and then through
+/// `budget_price_pool`, the DLMM pool the owner pinned for the Y token.
 pub fn handler<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmAddLiquidity<'info>>,
     liquidity_parameter: dlmm::types::LiquidityParameterByStrategy,
+    fee_lamports: u64,
 ) -> Result<()> {
-    let session = &mut ctx.accounts.session;
     let clock = Clock::get()?;
-
-    // ── Session validation ──────────────────────────────────────────────────
-    session.validate_lp_session(ctx.accounts.session_key.key(), clock.unix_timestamp)?;
+    let device = ctx.accounts.session_key.key();
+    let now = clock.unix_timestamp;
 
     // Track total exposure as amount_x + amount_y
     let total_in = liquidity_parameter
         .amount_x
         .checked_add(liquidity_parameter.amount_y)
         .ok_or(AgentError::Overflow)?;
-    let new_spent = session
-        .spent_lamports
-        .checked_add(total_in)
-        .ok_or(AgentError::Overflow)?;
-    require!(new_spent <= session.max_lamports, AgentError::ExposureLimitExceeded);
+    let notional = valuation::deposit_budget_value(
+        &ctx.accounts.session,
+        &ctx.accounts.lb_pair,
+        liquidity_parameter.amount_x,
+        liquidity_parameter.amount_y,
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+
+    // ── Session validation ──────────────────────────────────────────────────
+    let scope = check_scope(ctx.accounts, &liquidity_parameter, total_in, notional, now);
+    let device_slot = match scope {
+        Ok(slot) => slot,
+        Err(err) if ctx.accounts.action_request.is_some() => return Err(err),
+        Err(err) => return freeze::screen(&mut ctx.accounts.session, &device, err, now),
+    };
+    let session = &mut ctx.accounts.session;
+    verify_declared_fee(&ctx.accounts.instructions_sysvar, &device, fee_lamports)?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.lb_pair.key(),
+        total_in,
+        now,
+    )?;
+
+    // ── Balance snapshot ─────────────────────────────────────────────────────
+    let x_before = balances::token_amount(&ctx.accounts.user_token_x.to_account_info())?;
+    let y_before = balances::token_amount(&ctx.accounts.user_token_y.to_account_info())?;
 
     // ── CPI to Meteora DLMM add_liquidity_by_strategy ──────────────────────
     let cpi_accounts = dlmm::cpi::accounts::AddLiquidityByStrategy {
@@ -65,14 +131,77 @@ pub fn handler<'a, 'b, 'c, 'info>(
 
     dlmm::cpi::add_liquidity_by_strategy(cpi_ctx, liquidity_parameter)?;
 
+    // ── Settle on observed balance deltas ───────────────────────────────────
+    let deposited_x = balances::outflow(&ctx.accounts.user_token_x.to_account_info(), x_before)?;
+    let deposited_y = balances::outflow(&ctx.accounts.user_token_y.to_account_info(), y_before)?;
+    let deposited = deposited_x
+        .checked_add(deposited_y)
+        .ok_or(AgentError::Overflow)?;
+    require!(deposited <= total_in, AgentError::BalanceDeltaExceeded);
+
+    // ── Cost basis ──────────────────────────────────────────────────────────
+    if let Some(registry) = ctx.accounts.position_registry.as_ref() {
+        let price = valuation::pool_price(&ctx.accounts.lb_pair)?;
+        registry.load_mut()?.record_deposit(
+            &ctx.accounts.position.key(),
+            deposited_x,
+            deposited_y,
+            valuation::value_in_y(price, deposited_x, deposited_y),
+        );
+    }
+
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_ADD_LIQUIDITY,
+        deposited,
+        ctx.accounts.session_key.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
     // ── Update session accounting ──────────────────────────────────────────
-    session.spent_lamports = new_spent;
+    let charged = valuation::deposit_budget_value(
+        session,
+        &ctx.accounts.lb_pair,
+        deposited_x,
+        deposited_y,
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+    session.apply_spend(device_slot, ACTION_DLMM_ADD_LIQUIDITY, charged)?;
     session.bump_actions()?;
+    alerts::check_thresholds(session);
     session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, charged, fee_paid);
+    }
 
-    msg!(
-        "DLMM add liquidity: total_in={}, total_spent={}/{}",
-        total_in,
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_ADD_LIQUIDITY,
+        amount: deposited,
+        total_actions: session.total_actions,
+        reason: REASON_UNSPECIFIED,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
+
+    emit!(LiquidityDeposited {
+        session: session.key(),
+        lb_pair: ctx.accounts.lb_pair.key(),
+        amount_x: deposited_x,
+        amount_y: deposited_y,
+    });
+
+    log_info!(
+        session,
+        "DLMM add liquidity: amount_x={}, amount_y={}, total_spent={}/{}",
+        deposited_x,
+        deposited_y,
         session.spent_lamports,
         session.max_lamports,
     );
@@ -80,15 +209,84 @@ pub fn handler<'a, 'b, 'c, 'info>(
     Ok(())
 }
 
+/// Session, device and scope checks for a deposit of `total_in`, `notional`
+/// in budget units
+fn check_scope(
+    accounts: &mut ExecuteDlmmAddLiquidity,
+    liquidity_parameter: &dlmm::types::LiquidityParameterByStrategy,
+    total_in: u64,
+    notional: u64,
+    now: i64,
+) -> Result<usize> {
+    let session = &mut accounts.session;
+    let device_slot = session.validate_session(
+        &accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_ADD_LIQUIDITY),
+        TemporalSource::Device(now),
+    )?;
+    session.validate_position(&accounts.position.key())?;
+    session.check_liquidity_shape(&liquidity_parameter.strategy_parameters.strategy_type)?;
+
+    // Scope checks — waived for an owner-approved ActionRequest matching this deposit
+    let approved = match &accounts.action_request {
+        Some(request) => {
+            require!(
+                request.authorizes(
+                    REQUEST_DLMM_ADD_LIQUIDITY,
+                    &accounts.session_key.key(),
+                    &accounts.lb_pair.key(),
+                    total_in,
+                ),
+                AgentError::RequestMismatch
+            );
+            true
+        }
+        None => false,
+    };
+    if !approved {
+        session.validate_pool(accounts.pool_registry.as_deref(), &accounts.lb_pair.key())?;
+        session.check_action_cap(notional)?;
+        session.check_cosign(notional, accounts.cosigner.as_ref().map(|s| s.key()))?;
+    }
+    session.check_exposure(device_slot, notional)?;
+    session.check_min_deposit(
+        &accounts.token_x_mint.key(),
+        liquidity_parameter.amount_x,
+        &accounts.token_y_mint.key(),
+        liquidity_parameter.amount_y,
+    )?;
+    Ok(device_slot)
+}
+
 #[derive(Accounts)]
 pub struct ExecuteDlmmAddLiquidity<'info> {
     /// The ESP32 session key — must sign this transaction (also the DLMM `sender`)
+    #[account(mut)]
     pub session_key: Signer<'info>,
 
     /// Scoped session PDA — validated and updated here
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
     pub session: Account<'info, AgentSession>,
 
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
     // ── Meteora DLMM accounts ──────────────────────────────────────────────
 
     #[account(mut)]
@@ -137,12 +335,67 @@ pub struct ExecuteDlmmAddLiquidity<'info> {
     /// CHECK: Meteora DLMM program
     pub dlmm_program: UncheckedAccount<'info>,
 
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
     /// CHECK: DLMM CPI event authority (PDA of DLMM program)
     pub event_authority: UncheckedAccount<'info>,
 
-    /// CHECK: Token program for token X (SPL Token or Token-2022)
-    pub token_x_program: UncheckedAccount<'info>,
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Session owner — must co-sign when the action's notional exceeds
+    /// `session.cosign_above_lamports`; pass `None` for routine actions
+    pub cosigner: Option<Signer<'info>>,
+
+    /// Owner-approved ActionRequest for exactly this deposit; closed (rent back
+    /// to the device) once consumed. Pass `None` for in-scope actions.
+    #[account(
+        mut,
+        close = session_key,
+        seeds = [b"action_request", session.key().as_ref(), &action_request.request_id.to_le_bytes()],
+        bump = action_request.bump,
+    )]
+    pub action_request: Option<Account<'info, ActionRequest>>,
+
+    /// The session's PositionRegistry — deposits are added to the position's
+    /// cost basis when passed
+    #[account(
+        mut,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
 
-    /// CHECK: Token program for token Y (SPL Token or Token-2022)
-    pub token_y_program: UncheckedAccount<'info>,
+    /// CHECK: the owner's pinned budget price pool for the pool's Y token —
+    /// values the deposit when neither pool token is the budget mint
+    pub budget_price_pool: Option<UncheckedAccount<'info>>,
 }
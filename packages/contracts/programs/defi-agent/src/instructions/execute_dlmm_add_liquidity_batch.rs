@@ -0,0 +1,377 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+use crate::alerts;
+use crate::dlmm;
+use crate::state::{
+    ActionKind, AgentSession, Config, DailyStats, PoolRegistry, PositionRegistry, TemporalSource,
+    ACTION_DLMM_ADD_LIQUIDITY, REASON_UNSPECIFIED,
+};
+use crate::balances;
+use crate::errors::AgentError;
+use crate::events::{ActionExecuted, LiquidityDeposited};
+use crate::freeze;
+use crate::log_info;
+use crate::valuation;
+use crate::fees::charge_action_fee;
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
+
+/// Maximum number of positions funded by one `execute_dlmm_add_liquidity_batch`.
+pub const MAX_BATCH_DEPOSITS: usize = 4;
+
+/// Accounts passed in `remaining_accounts` per deposit, in order:
+/// position, bin_array_lower, bin_array_upper.
+pub const BATCH_DEPOSIT_ACCOUNTS: usize = 3;
+
+/// Called by the ESP32 on the BASE LAYER using the session key.
+///
+/// Deposits into up to `MAX_BATCH_DEPOSITS` positions of the same pool (e.g.
+/// a ladder of adjacent ranges) with a single session validation. Entry `i`
+/// of `liquidity_parameters` is deposited into the `i`-th
+/// `BATCH_DEPOSIT_ACCOUNTS` group of `remaining_accounts`; each position must
+/// be owned by the session key and its bin arrays must cover its range.
+///
+/// The per-action cap and co-sign threshold are applied once to the combined
+/// `amount_x + amount_y` of all deposits; exposure and the per-action fee are
+/// charged once on the combined tokens that actually left the session key.
+/// Devices with an `intent_signer` sign one intent over (lb_pair, combined
+/// amount). Passing the session's PositionRegistry adds each position's own
+/// deposit to its cost basis. Every deposit's distribution must be one of the
+/// session's `liquidity_shapes`. A session `budget_mint` values the combined
+/// deposit as `execute_dlmm_add_liquidity` does, and a failed scope check is
+/// screened the same way (see `freeze::screen`).
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmAddLiquidityBatch<'info>>,
+    liquidity_parameters: Vec<dlmm::types::LiquidityParameterByStrategy>,
+    fee_lamports: u64,
+) -> Result<()> {
+    require!(
+        !liquidity_parameters.is_empty()
+            && liquidity_parameters.len() <= MAX_BATCH_DEPOSITS
+            && ctx.remaining_accounts.len()
+                == liquidity_parameters.len() * BATCH_DEPOSIT_ACCOUNTS,
+        AgentError::InvalidDepositBatch
+    );
+
+    let clock = Clock::get()?;
+    let device = ctx.accounts.session_key.key();
+    let now = clock.unix_timestamp;
+
+    // Track total exposure as the sum of amount_x + amount_y over all deposits
+    let (mut total_x, mut total_y) = (0u64, 0u64);
+    for params in &liquidity_parameters {
+        total_x = total_x.checked_add(params.amount_x).ok_or(AgentError::Overflow)?;
+        total_y = total_y.checked_add(params.amount_y).ok_or(AgentError::Overflow)?;
+    }
+    let total_in = total_x.checked_add(total_y).ok_or(AgentError::Overflow)?;
+    let notional = valuation::deposit_budget_value(
+        &ctx.accounts.session,
+        &ctx.accounts.lb_pair,
+        total_x,
+        total_y,
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+
+    // ── Session validation ──────────────────────────────────────────────────
+    let scope = check_scope(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        &liquidity_parameters,
+        notional,
+        now,
+    );
+    let device_slot = match scope {
+        Ok(slot) => slot,
+        Err(err) => return freeze::screen(&mut ctx.accounts.session, &device, err, now),
+    };
+    let session = &mut ctx.accounts.session;
+    verify_declared_fee(&ctx.accounts.instructions_sysvar, &device, fee_lamports)?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.lb_pair.key(),
+        total_in,
+        now,
+    )?;
+
+    // ── Balance snapshot ─────────────────────────────────────────────────────
+    let x_before = balances::token_amount(&ctx.accounts.user_token_x.to_account_info())?;
+    let y_before = balances::token_amount(&ctx.accounts.user_token_y.to_account_info())?;
+
+    // ── CPI to Meteora DLMM add_liquidity_by_strategy, once per position ────
+    let dlmm_prog = ctx.accounts.dlmm_program.to_account_info();
+    let mut registry = match ctx.accounts.position_registry.as_ref() {
+        Some(registry) => Some(registry.load_mut()?),
+        None => None,
+    };
+    let (mut x_prev, mut y_prev) = (x_before, y_before);
+    for (params, accounts) in liquidity_parameters
+        .into_iter()
+        .zip(ctx.remaining_accounts.chunks(BATCH_DEPOSIT_ACCOUNTS))
+    {
+        let cpi_accounts = dlmm::cpi::accounts::AddLiquidityByStrategy {
+            position: accounts[0].clone(),
+            lb_pair: ctx.accounts.lb_pair.to_account_info(),
+            bin_array_bitmap_extension: ctx
+                .accounts
+                .bin_array_bitmap_extension
+                .as_ref()
+                .map(|a| a.to_account_info()),
+            user_token_x: ctx.accounts.user_token_x.to_account_info(),
+            user_token_y: ctx.accounts.user_token_y.to_account_info(),
+            reserve_x: ctx.accounts.reserve_x.to_account_info(),
+            reserve_y: ctx.accounts.reserve_y.to_account_info(),
+            token_x_mint: ctx.accounts.token_x_mint.to_account_info(),
+            token_y_mint: ctx.accounts.token_y_mint.to_account_info(),
+            bin_array_lower: accounts[1].clone(),
+            bin_array_upper: accounts[2].clone(),
+            sender: ctx.accounts.session_key.to_account_info(),
+            token_x_program: ctx.accounts.token_x_program.to_account_info(),
+            token_y_program: ctx.accounts.token_y_program.to_account_info(),
+            event_authority: ctx.accounts.event_authority.to_account_info(),
+            program: dlmm_prog.clone(),
+        };
+        dlmm::cpi::add_liquidity_by_strategy(
+            CpiContext::new(dlmm_prog.clone(), cpi_accounts),
+            params,
+        )?;
+
+        // Per-position deltas feed each position's cost basis
+        let x_now = balances::token_amount(&ctx.accounts.user_token_x.to_account_info())?;
+        let y_now = balances::token_amount(&ctx.accounts.user_token_y.to_account_info())?;
+        if let Some(registry) = registry.as_mut() {
+            let (dx, dy) = (x_prev.saturating_sub(x_now), y_prev.saturating_sub(y_now));
+            let price = valuation::pool_price(&ctx.accounts.lb_pair)?;
+            let value = valuation::value_in_y(price, dx, dy);
+            registry.record_deposit(&accounts[0].key(), dx, dy, value);
+        }
+        (x_prev, y_prev) = (x_now, y_now);
+    }
+
+    // ── Settle on observed balance deltas ───────────────────────────────────
+    let deposited_x = balances::outflow(&ctx.accounts.user_token_x.to_account_info(), x_before)?;
+    let deposited_y = balances::outflow(&ctx.accounts.user_token_y.to_account_info(), y_before)?;
+    let deposited = deposited_x
+        .checked_add(deposited_y)
+        .ok_or(AgentError::Overflow)?;
+    require!(deposited <= total_in, AgentError::BalanceDeltaExceeded);
+
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_ADD_LIQUIDITY,
+        deposited,
+        ctx.accounts.session_key.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // ── Update session accounting ──────────────────────────────────────────
+    let charged = valuation::deposit_budget_value(
+        session,
+        &ctx.accounts.lb_pair,
+        deposited_x,
+        deposited_y,
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+    session.apply_spend(device_slot, ACTION_DLMM_ADD_LIQUIDITY, charged)?;
+    session.bump_actions()?;
+    alerts::check_thresholds(session);
+    session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, charged, fee_paid);
+    }
+
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_ADD_LIQUIDITY,
+        amount: deposited,
+        total_actions: session.total_actions,
+        reason: REASON_UNSPECIFIED,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
+
+    emit!(LiquidityDeposited {
+        session: session.key(),
+        lb_pair: ctx.accounts.lb_pair.key(),
+        amount_x: deposited_x,
+        amount_y: deposited_y,
+    });
+
+    log_info!(
+        session,
+        "DLMM batch add liquidity: positions={}, deposited={}, total_spent={}/{}",
+        ctx.remaining_accounts.len() / BATCH_DEPOSIT_ACCOUNTS,
+        deposited,
+        session.spent_lamports,
+        session.max_lamports,
+    );
+
+    Ok(())
+}
+
+/// Session, device and scope checks for the batch, `notional` in budget units
+fn check_scope(
+    accounts: &mut ExecuteDlmmAddLiquidityBatch,
+    deposits: &[AccountInfo],
+    liquidity_parameters: &[dlmm::types::LiquidityParameterByStrategy],
+    notional: u64,
+    now: i64,
+) -> Result<usize> {
+    let session = &mut accounts.session;
+    let device_slot = session.validate_session(
+        &accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_ADD_LIQUIDITY),
+        TemporalSource::Device(now),
+    )?;
+    for deposit in deposits.chunks(BATCH_DEPOSIT_ACCOUNTS) {
+        session.validate_position(&deposit[0].key())?;
+    }
+    for params in liquidity_parameters {
+        session.check_liquidity_shape(&params.strategy_parameters.strategy_type)?;
+    }
+    session.validate_pool(accounts.pool_registry.as_deref(), &accounts.lb_pair.key())?;
+    session.check_action_cap(notional)?;
+    session.check_cosign(notional, accounts.cosigner.as_ref().map(|s| s.key()))?;
+    session.check_exposure(device_slot, notional)?;
+    for params in liquidity_parameters {
+        session.check_min_deposit(
+            &accounts.token_x_mint.key(),
+            params.amount_x,
+            &accounts.token_y_mint.key(),
+            params.amount_y,
+        )?;
+    }
+    Ok(device_slot)
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDlmmAddLiquidityBatch<'info> {
+    /// The ESP32 session key — must sign this transaction (also the DLMM `sender`)
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — validated and updated here
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    // ── Meteora DLMM accounts shared by every deposit ──────────────────────
+
+    #[account(mut)]
+    /// CHECK: Meteora DLMM LB pair pool
+    pub lb_pair: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Optional bin array bitmap extension (null for pools near bin 0)
+    pub bin_array_bitmap_extension: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    /// CHECK: Session key's token X ATA (source of X tokens)
+    pub user_token_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Session key's token Y ATA (source of Y tokens)
+    pub user_token_y: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Pool token X reserve
+    pub reserve_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Pool token Y reserve
+    pub reserve_y: UncheckedAccount<'info>,
+
+    /// CHECK: Token X mint
+    pub token_x_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token Y mint
+    pub token_y_mint: UncheckedAccount<'info>,
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Session owner — must co-sign when the combined notional exceeds
+    /// `session.cosign_above_lamports`; pass `None` for routine batches
+    pub cosigner: Option<Signer<'info>>,
+
+    /// The session's PositionRegistry — deposits are added to the position's
+    /// cost basis when passed
+    #[account(
+        mut,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+
+    /// CHECK: the owner's pinned budget price pool for the pool's Y token —
+    /// values the deposit when neither pool token is the budget mint
+    pub budget_price_pool: Option<UncheckedAccount<'info>>,
+    // Per-deposit position + bin arrays → ctx.remaining_accounts
+}
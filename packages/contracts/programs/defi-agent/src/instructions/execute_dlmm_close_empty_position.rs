@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use crate::alerts;
+use crate::dlmm;
+use crate::state::{
+    ActionKind, AgentSession, Config, DailyStats, LpPositionMonitor, PositionRegistry,
+    TemporalSource, ACTION_DLMM_REMOVE_LIQUIDITY, REASON_UNSPECIFIED,
+};
+use crate::errors::AgentError;
+use crate::events::ActionExecuted;
+use crate::log_info;
+use crate::fees::charge_action_fee;
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
+
+/// Called by the ESP32 on the BASE LAYER using the session key.
+///
+/// Second half of `execute_dlmm_close_position`: CPIs DLMM `close_position2`
+/// on the position emptied by `execute_dlmm_remove_all_liquidity`, returning
+/// its rent to `rent_receiver`, and clears `session.emptied_position`. Only
+/// the position recorded there can be closed through this path.
+///
+/// `fee_lamports` (priority fee + tips) is charged to the fee budget and
+/// `total_actions` is incremented once for the whole close. The PositionRegistry
+/// entry and LpPositionMonitor, when passed, are removed / closed as in
+/// `execute_dlmm_close_position`.
+pub fn handler(ctx: Context<ExecuteDlmmCloseEmptyPosition>, fee_lamports: u64) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    // ── Session validation ──────────────────────────────────────────────────
+    let device_slot = session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_REMOVE_LIQUIDITY),
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    require_keys_eq!(
+        ctx.accounts.position.key(),
+        session.emptied_position,
+        AgentError::PositionNotEmptied
+    );
+    verify_declared_fee(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.session_key.key(),
+        fee_lamports,
+    )?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.lb_pair.key(),
+        0,
+        clock.unix_timestamp,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    // ── Close the empty position → rent reclaimed ──────────────────────────
+    let dlmm_prog = ctx.accounts.dlmm_program.to_account_info();
+    let close_accounts = dlmm::cpi::accounts::ClosePosition2 {
+        position: ctx.accounts.position.to_account_info(),
+        sender: ctx.accounts.session_key.to_account_info(),
+        rent_receiver: ctx.accounts.rent_receiver.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: dlmm_prog.clone(),
+    };
+    dlmm::cpi::close_position2(CpiContext::new(dlmm_prog, close_accounts))?;
+
+    session.emptied_position = Pubkey::default();
+    if let Some(registry) = ctx.accounts.position_registry.as_ref() {
+        registry.load_mut()?.remove(&ctx.accounts.position.key());
+    }
+    if let Some(monitor) = ctx.accounts.monitor.as_ref() {
+        let monitor = monitor.load()?;
+        session.record_fees_earned(&monitor);
+        if let Some(stats) = &ctx.accounts.daily_stats {
+            stats.load_mut()?.record_fees_earned(clock.unix_timestamp, &monitor);
+        }
+    }
+
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_REMOVE_LIQUIDITY,
+        0,
+        ctx.accounts.session_key.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // ── Update session accounting ──────────────────────────────────────────
+    session.bump_actions()?;
+    alerts::check_thresholds(session);
+    session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, 0, fee_paid);
+    }
+
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_REMOVE_LIQUIDITY,
+        amount: 0,
+        total_actions: session.total_actions,
+        reason: REASON_UNSPECIFIED,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
+
+    log_info!(
+        session,
+        "DLMM empty position closed: total_actions={}",
+        session.total_actions,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDlmmCloseEmptyPosition<'info> {
+    /// The ESP32 session key — must sign this transaction (also the DLMM `sender`)
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — validated and updated here
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    // ── Meteora DLMM accounts ─────────────────────────────────────────────
+
+    #[account(mut)]
+    /// CHECK: Emptied LP position — must equal `session.emptied_position`; closed here
+    pub position: UncheckedAccount<'info>,
+
+    /// CHECK: Meteora DLMM LB pair pool the position belongs to (intent scope)
+    pub lb_pair: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == session_key.key()
+            || rent_receiver.key() == session.owner
+            || rent_receiver.key() == fee_vault.key()
+            @ AgentError::InvalidRentReceiver,
+    )]
+    /// CHECK: Receives the position account's rent lamports — the session key,
+    /// the session owner, or the fee vault
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    // ── Programs ──────────────────────────────────────────────────────────
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The session's PositionRegistry — the closed position is removed from it
+    #[account(
+        mut,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    /// LpPositionMonitor PDA tracking this position — closed here when passed
+    #[account(
+        mut,
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump = monitor.load()?.bump,
+        constraint = monitor.load()?.position == position.key() @ AgentError::MonitorPositionMismatch,
+        close = session_key,
+    )]
+    pub monitor: Option<AccountLoader<'info, LpPositionMonitor>>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+}
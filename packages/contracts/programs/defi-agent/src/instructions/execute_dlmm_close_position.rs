@@ -1,14 +1,34 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+use crate::alerts;
+use crate::balances;
 use crate::dlmm;
-use crate::state::AgentSession;
+use crate::state::{
+    AgentSession, Config, DailyStats, LpPositionMonitor, PositionRegistry,
+    ACTION_DLMM_REMOVE_LIQUIDITY, REASON_MANUAL,
+};
+use crate::errors::AgentError;
+use crate::events::ActionExecuted;
+use crate::freeze;
+use crate::instructions::execute_dlmm_remove_all_liquidity::check_withdraw_scope;
+use crate::log_info;
+use crate::valuation::{self, VALUATION_CLOSE};
+use crate::fees::charge_action_fee;
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, require_compute_budget, signature_fee_lamports,
+    verify_declared_fee,
+};
 
 /// Called by the ESP32 on the BASE LAYER using the session key.
 ///
+/// Fails with `InsufficientComputeBudget` before any CPI when the transaction
+/// requests fewer compute units than `config.min_dual_cpi_compute_units`.
+///
 /// Combines two DLMM CPIs in sequence:
 ///   1. `remove_all_liquidity` — withdraws all tokens from the position back
 ///      to the session key's ATAs (also claims any pending fees).
 ///   2. `close_position2` — closes the now-empty position account and returns
-///      the rent lamports to `rent_receiver` (typically the session key).
+///      the rent lamports to `rent_receiver` (session key, owner or fee vault).
 ///
 /// The position must be owned by the session key. Bin arrays must cover the
 /// position's full range; derive their PDAs via `deriveBinArray` +
@@ -16,14 +36,58 @@ use crate::state::AgentSession;
 ///
 /// `spent_lamports` is NOT updated here since tokens are returned, not spent.
 /// `total_actions` is still incremented so the session log is accurate.
+/// `fee_lamports` (priority fee + tips) is still charged to the fee budget.
+/// `reason` (`REASON_*`) records why the device closed, in `ActionExecuted`.
+/// The session key's token X/Y holdings are valued at the pool price once the
+/// liquidity is back, updating the session's realized PnL.
+/// Devices with an `intent_signer` must sign an intent for (lb_pair, amount 0).
+/// When the session's PositionRegistry is passed, the position is removed from
+/// it and `PositionRealized` reports the withdrawal against its cost basis.
+/// When the session's LpPositionMonitor for this position is passed, the
+/// withdrawal is checked against its last checkpoint (see
+/// `valuation::verify_settlement`), its fee checkpoint is added to the
+/// session's lifetime earnings, then it is closed and its rent returned to
+/// the session key. A failed scope check is screened as in `execute_action`
+/// (see `freeze::screen`).
 pub fn handler<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmClosePosition<'info>>,
+    fee_lamports: u64,
+    reason: u8,
 ) -> Result<()> {
     let session = &mut ctx.accounts.session;
     let clock = Clock::get()?;
 
+    require!(reason <= REASON_MANUAL, AgentError::InvalidActionReason);
+
+    require_compute_budget(
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.config.min_dual_cpi_compute_units,
+    )?;
+
     // ── Session validation ──────────────────────────────────────────────────
-    session.validate_lp_session(ctx.accounts.session_key.key(), clock.unix_timestamp)?;
+    let device = ctx.accounts.session_key.key();
+    let now = clock.unix_timestamp;
+    let position = ctx.accounts.position.key();
+    let device_slot = match check_withdraw_scope(session, &device, &position, now) {
+        Ok(slot) => slot,
+        Err(err) => return freeze::screen(session, &device, err, now),
+    };
+    verify_declared_fee(&ctx.accounts.instructions_sysvar, &device, fee_lamports)?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.lb_pair.key(),
+        0,
+        clock.unix_timestamp,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    // ── Balance snapshot ─────────────────────────────────────────────────────
+    let x_before = balances::token_amount(&ctx.accounts.user_token_x.to_account_info())?;
+    let y_before = balances::token_amount(&ctx.accounts.user_token_y.to_account_info())?;
 
     let dlmm_prog = ctx.accounts.dlmm_program.to_account_info();
 
@@ -51,6 +115,8 @@ pub fn handler<'a, 'b, 'c, 'info>(
         program: dlmm_prog.clone(),
     };
     dlmm::cpi::remove_all_liquidity(CpiContext::new(dlmm_prog.clone(), remove_accounts))?;
+    let withdrawn_x = balances::inflow(&ctx.accounts.user_token_x.to_account_info(), x_before)?;
+    let withdrawn_y = balances::inflow(&ctx.accounts.user_token_y.to_account_info(), y_before)?;
 
     // ── Step 2: Close the now-empty position → rent reclaimed ──────────────
     let close_accounts = dlmm::cpi::accounts::ClosePosition2 {
@@ -62,12 +128,77 @@ pub fn handler<'a, 'b, 'c, 'info>(
     };
     dlmm::cpi::close_position2(CpiContext::new(dlmm_prog, close_accounts))?;
 
+    if let Some(registry) = ctx.accounts.position_registry.as_ref() {
+        if let Some(entry) = registry.load_mut()?.remove(&ctx.accounts.position.key()) {
+            valuation::report_realized(
+                session.key(),
+                &entry,
+                &ctx.accounts.lb_pair,
+                withdrawn_x,
+                withdrawn_y,
+            )?;
+        }
+    }
+    if let Some(monitor) = ctx.accounts.monitor.as_ref() {
+        let monitor = monitor.load()?;
+        valuation::verify_settlement(
+            session,
+            &monitor,
+            &ctx.accounts.lb_pair,
+            withdrawn_x,
+            withdrawn_y,
+        )?;
+        session.record_fees_earned(&monitor);
+        if let Some(stats) = &ctx.accounts.daily_stats {
+            stats.load_mut()?.record_fees_earned(clock.unix_timestamp, &monitor);
+        }
+    }
+
+    // ── Record the realized portfolio value ─────────────────────────────────
+    valuation::record(
+        session,
+        &ctx.accounts.lb_pair,
+        &ctx.accounts.user_token_x,
+        &ctx.accounts.user_token_y,
+        VALUATION_CLOSE,
+        clock.unix_timestamp,
+    )?;
+
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_REMOVE_LIQUIDITY,
+        0,
+        ctx.accounts.session_key.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
     // ── Update session accounting ──────────────────────────────────────────
     // No spent_lamports update — tokens are returned, not consumed.
     session.bump_actions()?;
+    alerts::check_thresholds(session);
     session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, 0, fee_paid);
+    }
+
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_REMOVE_LIQUIDITY,
+        amount: 0,
+        total_actions: session.total_actions,
+        reason,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
 
-    msg!(
+    log_info!(
+        session,
         "DLMM position closed: total_actions={}",
         session.total_actions,
     );
@@ -78,12 +209,28 @@ pub fn handler<'a, 'b, 'c, 'info>(
 #[derive(Accounts)]
 pub struct ExecuteDlmmClosePosition<'info> {
     /// The ESP32 session key — must sign this transaction (also the DLMM `sender`)
+    #[account(mut)]
     pub session_key: Signer<'info>,
 
     /// Scoped session PDA — validated and updated here
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
     pub session: Account<'info, AgentSession>,
 
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
     // ── Shared by remove_all_liquidity + close_position2 ──────────────────
 
     #[account(mut)]
@@ -132,8 +279,15 @@ pub struct ExecuteDlmmClosePosition<'info> {
 
     // ── close_position2-only accounts ─────────────────────────────────────
 
-    #[account(mut)]
-    /// CHECK: Receives the position account's rent lamports (typically session_key)
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == session_key.key()
+            || rent_receiver.key() == session.owner
+            || rent_receiver.key() == fee_vault.key()
+            @ AgentError::InvalidRentReceiver,
+    )]
+    /// CHECK: Receives the position account's rent lamports — the session key,
+    /// the session owner, or the fee vault
     pub rent_receiver: UncheckedAccount<'info>,
 
     // ── Programs ──────────────────────────────────────────────────────────
@@ -142,12 +296,58 @@ pub struct ExecuteDlmmClosePosition<'info> {
     /// CHECK: Meteora DLMM program
     pub dlmm_program: UncheckedAccount<'info>,
 
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
     /// CHECK: DLMM CPI event authority (PDA of DLMM program)
     pub event_authority: UncheckedAccount<'info>,
 
-    /// CHECK: Token program for token X (SPL Token or Token-2022)
-    pub token_x_program: UncheckedAccount<'info>,
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The session's PositionRegistry — the closed position is removed from it
+    #[account(
+        mut,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    /// LpPositionMonitor PDA tracking this position — closed here when passed
+    #[account(
+        mut,
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump = monitor.load()?.bump,
+        constraint = monitor.load()?.position == position.key() @ AgentError::MonitorPositionMismatch,
+        close = session_key,
+    )]
+    pub monitor: Option<AccountLoader<'info, LpPositionMonitor>>,
 
-    /// CHECK: Token program for token Y (SPL Token or Token-2022)
-    pub token_y_program: UncheckedAccount<'info>,
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
 }
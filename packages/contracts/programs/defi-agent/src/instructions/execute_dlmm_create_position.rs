@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+use crate::alerts;
+use crate::dlmm;
+use crate::state::{
+    ActionKind, AgentSession, Config, DailyStats, LpPositionMonitor, PairPools, PoolRegistry,
+    PositionRegistry, TemporalSource, ACTION_DLMM_OPEN_POSITION, REASON_UNSPECIFIED,
+};
+use crate::errors::AgentError;
+use crate::events::ActionExecuted;
+use crate::log_info;
+use crate::fees::charge_action_fee;
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
+
+/// Called by the ESP32 on the BASE LAYER using the session key.
+///
+/// Validates the session scope (active, not expired, session key matches,
+/// LP strategy enabled, pool allowed and, once the owner ranks pools, on the
+/// pair's `pair_pools` list) then CPIs into DLMM `initialize_position2` to
+/// open an empty position owned by the session key, covering `width` bins
+/// from `lower_bin_id`. The new position is appended to the session's
+/// PositionRegistry. Fund it afterwards with `execute_dlmm_add_liquidity`.
+///
+/// When `monitor` is passed, the session's LpPositionMonitor is created here
+/// for the new position's bin range, so no separate owner transaction
+/// (`register_lp_monitor`) is needed.
+///
+/// `position` is a fresh keypair generated by the device and must co-sign.
+/// The session key pays the position and monitor rent (both returned on close).
+/// `spent_lamports` is NOT updated — no tokens move.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmCreatePosition<'info>>,
+    lower_bin_id: i32,
+    width: i32,
+    fee_lamports: u64,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    require!(width > 0, AgentError::InvalidBinRange);
+
+    // ── Session validation ──────────────────────────────────────────────────
+    let device_slot = session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_OPEN_POSITION),
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    session.validate_pool(
+        ctx.accounts.pool_registry.as_deref(),
+        &ctx.accounts.lb_pair.key(),
+    )?;
+    session.validate_ranked_pool(ctx.accounts.pair_pools.as_deref(), &ctx.accounts.lb_pair.key())?;
+    verify_declared_fee(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.session_key.key(),
+        fee_lamports,
+    )?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.lb_pair.key(),
+        0,
+        clock.unix_timestamp,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    // ── CPI to Meteora DLMM initialize_position2 ────────────────────────────
+    let cpi_accounts = dlmm::cpi::accounts::InitializePosition2 {
+        payer: ctx.accounts.session_key.to_account_info(),
+        position: ctx.accounts.position.to_account_info(),
+        lb_pair: ctx.accounts.lb_pair.to_account_info(),
+        owner: ctx.accounts.session_key.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: ctx.accounts.dlmm_program.to_account_info(),
+    };
+    dlmm::cpi::initialize_position2(
+        CpiContext::new(ctx.accounts.dlmm_program.to_account_info(), cpi_accounts),
+        lower_bin_id,
+        width,
+    )?;
+
+    // ── Register the position ───────────────────────────────────────────────
+    PositionRegistry::load_or_init(
+        &ctx.accounts.position_registry,
+        session.key(),
+        ctx.bumps.position_registry,
+    )?
+    .add(
+        ctx.accounts.position.key(),
+        ctx.accounts.lb_pair.key(),
+        clock.unix_timestamp,
+    )?;
+
+    // ── Auto-register the LP monitor ────────────────────────────────────────
+    if let (Some(monitor), Some(bump)) = (ctx.accounts.monitor.as_ref(), ctx.bumps.monitor) {
+        let max_bin_id = lower_bin_id
+            .checked_add(width - 1)
+            .ok_or(AgentError::InvalidBinRange)?;
+        monitor.load_init()?.track(
+            session.key(),
+            ctx.accounts.lb_pair.key(),
+            ctx.accounts.position.key(),
+            lower_bin_id,
+            max_bin_id,
+            bump,
+        );
+    }
+
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_OPEN_POSITION,
+        0,
+        ctx.accounts.session_key.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // ── Update session accounting ──────────────────────────────────────────
+    session.bump_actions()?;
+    alerts::check_thresholds(session);
+    session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, 0, fee_paid);
+    }
+
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_OPEN_POSITION,
+        amount: 0,
+        total_actions: session.total_actions,
+        reason: REASON_UNSPECIFIED,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
+
+    log_info!(
+        session,
+        "DLMM position created: position={}, lower_bin_id={}, width={}",
+        ctx.accounts.position.key(),
+        lower_bin_id,
+        width,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDlmmCreatePosition<'info> {
+    /// The ESP32 session key — must sign (DLMM `payer` and position `owner`)
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — validated and updated here
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    /// The session's PositionRegistry — the new position is appended here;
+    /// created on first use at the session key's expense
+    #[account(
+        init_if_needed,
+        payer = session_key,
+        space = PositionRegistry::LEN,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump,
+    )]
+    pub position_registry: AccountLoader<'info, PositionRegistry>,
+
+    /// LpPositionMonitor PDA — created here for the new position when passed
+    #[account(
+        init,
+        payer = session_key,
+        space = LpPositionMonitor::LEN,
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump,
+    )]
+    pub monitor: Option<AccountLoader<'info, LpPositionMonitor>>,
+
+    // ── Meteora DLMM accounts ────────────────────────────────────────────────
+
+    /// New position account — fresh keypair, must sign
+    #[account(mut)]
+    pub position: Signer<'info>,
+
+    /// CHECK: Meteora DLMM LB pair pool
+    pub lb_pair: UncheckedAccount<'info>,
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+    /// The owner's PairPools list for this pool's pair — required once the
+    /// session has any ranked pool list
+    #[account(
+        seeds = [
+            b"pair_pools",
+            session.key().as_ref(),
+            pair_pools.token_x_mint.as_ref(),
+            pair_pools.token_y_mint.as_ref(),
+        ],
+        bump = pair_pools.bump,
+    )]
+    pub pair_pools: Option<Account<'info, PairPools>>,
+}
@@ -0,0 +1,423 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::alerts;
+use crate::dlmm;
+use crate::state::{
+    ActionKind, AgentSession, Config, DailyStats, LpPositionMonitor, PairPools, PoolRegistry,
+    PositionRegistry, TemporalSource, ACTION_DLMM_ADD_LIQUIDITY, ACTION_DLMM_OPEN_POSITION,
+    ACTION_DLMM_REMOVE_LIQUIDITY, REASON_MANUAL,
+};
+use crate::errors::AgentError;
+use crate::events::ActionExecuted;
+use crate::log_info;
+use crate::fees::charge_action_fee;
+use crate::reposition::{self, MovedPosition, PoolRange, Repositioner};
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, require_compute_budget, signature_fee_lamports,
+    verify_declared_fee,
+};
+
+/// Called by the ESP32 on the BASE LAYER using the session key.
+///
+/// Fails with `InsufficientComputeBudget` before any CPI when the transaction
+/// requests fewer compute units than `config.min_dual_cpi_compute_units`.
+///
+/// Moves a position from one DLMM pool to another pool of the same token pair
+/// (typically a different bin step) in a single instruction:
+///   1. `remove_all_liquidity` + `close_position2` on the source pool — tokens
+///      return to the session key's ATAs, rent to the session key.
+///   2. `initialize_position2` on `target_lb_pair` over `lower_bin_id` / `width`.
+///   3. `add_liquidity_by_strategy` on the target pool, depositing exactly the
+///      amounts withdrawn in step 1. The `amount_x` / `amount_y` fields of
+///      `liquidity_parameter` are ignored and replaced by those amounts.
+///
+/// Both pools share `token_x_mint` / `token_y_mint`, so DLMM rejects a target
+/// that is not the same pair. The target pool must pass the session's pool
+/// scope (including the owner's `pair_pools` list, once any exists) and the
+/// re-deposit must use one of the session's `liquidity_shapes`. Tokens are
+/// re-deposited rather than newly spent, so `spent_lamports` is NOT updated.
+/// Sessions with bound positions must bind the new position with
+/// `set_bound_positions` before managing it further.
+///
+/// The PositionRegistry and LpPositionMonitor, when passed, are moved to the
+/// new position (see `reposition::move_records`). Billed as
+/// `ACTION_DLMM_OPEN_POSITION`, but the strategy mask must also permit
+/// removing and adding liquidity. `reason` (`REASON_*`) is recorded in
+/// `ActionExecuted`.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmMigratePosition<'info>>,
+    lower_bin_id: i32,
+    width: i32,
+    mut liquidity_parameter: dlmm::types::LiquidityParameterByStrategy,
+    fee_lamports: u64,
+    reason: u8,
+) -> Result<()> {
+    require!(width > 0, AgentError::InvalidBinRange);
+    require!(reason <= REASON_MANUAL, AgentError::InvalidActionReason);
+
+    let repositioner = ctx.accounts.repositioner();
+    let (source, target) = ctx.accounts.pools();
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    require_compute_budget(
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.config.min_dual_cpi_compute_units,
+    )?;
+
+    // ── Session validation ──────────────────────────────────────────────────
+    let device_slot = session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_OPEN_POSITION),
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    // Migration also withdraws from the old position and re-deposits
+    require!(
+        session.has_strategy(ACTION_DLMM_REMOVE_LIQUIDITY)
+            && session.has_strategy(ACTION_DLMM_ADD_LIQUIDITY),
+        AgentError::StrategyNotEnabled
+    );
+    session.validate_position(&ctx.accounts.position.key())?;
+    session.check_liquidity_shape(&liquidity_parameter.strategy_parameters.strategy_type)?;
+    session.validate_pool(
+        ctx.accounts.pool_registry.as_deref(),
+        &ctx.accounts.target_lb_pair.key(),
+    )?;
+    session.validate_ranked_pool(
+        ctx.accounts.pair_pools.as_deref(),
+        &ctx.accounts.target_lb_pair.key(),
+    )?;
+    verify_declared_fee(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.session_key.key(),
+        fee_lamports,
+    )?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.target_lb_pair.key(),
+        0,
+        clock.unix_timestamp,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    let balance_x_before = ctx.accounts.user_token_x.amount;
+    let balance_y_before = ctx.accounts.user_token_y.amount;
+
+    // ── Step 1: Empty and close the source position ────────────────────────
+    repositioner.close(&ctx.accounts.position, &source)?;
+
+    ctx.accounts.user_token_x.reload()?;
+    ctx.accounts.user_token_y.reload()?;
+    let amount_x = ctx
+        .accounts
+        .user_token_x
+        .amount
+        .checked_sub(balance_x_before)
+        .ok_or(AgentError::Overflow)?;
+    let amount_y = ctx
+        .accounts
+        .user_token_y
+        .amount
+        .checked_sub(balance_y_before)
+        .ok_or(AgentError::Overflow)?;
+
+    // ── Steps 2–3: Open the target position and re-deposit ─────────────────
+    liquidity_parameter.amount_x = amount_x;
+    liquidity_parameter.amount_y = amount_y;
+    repositioner.open(
+        &ctx.accounts.new_position,
+        &target,
+        lower_bin_id,
+        width,
+        liquidity_parameter,
+    )?;
+
+    // ── Move registry / monitor entries to the new position ────────────────
+    reposition::move_records(
+        session,
+        ctx.accounts.position_registry.as_ref(),
+        ctx.accounts.monitor.as_ref(),
+        ctx.accounts.daily_stats.as_ref(),
+        &ctx.accounts.source_lb_pair,
+        &MovedPosition {
+            position: ctx.accounts.position.key(),
+            new_position: ctx.accounts.new_position.key(),
+            lb_pair: ctx.accounts.target_lb_pair.key(),
+            min_bin_id: lower_bin_id,
+            max_bin_id: reposition::max_bin_id(lower_bin_id, width)?,
+            withdrawn_x: amount_x,
+            withdrawn_y: amount_y,
+        },
+        clock.unix_timestamp,
+    )?;
+
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_OPEN_POSITION,
+        0,
+        ctx.accounts.session_key.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // ── Update session accounting ──────────────────────────────────────────
+    session.bump_actions()?;
+    alerts::check_thresholds(session);
+    session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, 0, fee_paid);
+    }
+
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_OPEN_POSITION,
+        amount: 0,
+        total_actions: session.total_actions,
+        reason,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
+
+    log_info!(
+        session,
+        "DLMM position migrated: {} -> {} (pool {}), amount_x={}, amount_y={}",
+        ctx.accounts.position.key(),
+        ctx.accounts.new_position.key(),
+        ctx.accounts.target_lb_pair.key(),
+        amount_x,
+        amount_y,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDlmmMigratePosition<'info> {
+    /// The ESP32 session key — must sign (DLMM `sender`, payer and new owner)
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — validated and updated here
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    // ── Shared accounts ───────────────────────────────────────────────────
+
+    #[account(mut, token::authority = session_key, token::mint = token_x_mint)]
+    /// Session key's token X ATA (receives, then re-deposits X tokens)
+    pub user_token_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::authority = session_key, token::mint = token_y_mint)]
+    /// Session key's token Y ATA (receives, then re-deposits Y tokens)
+    pub user_token_y: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token X mint — shared by both pools
+    pub token_x_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Y mint — shared by both pools
+    pub token_y_mint: InterfaceAccount<'info, Mint>,
+
+    // ── Source pool ───────────────────────────────────────────────────────
+
+    #[account(mut)]
+    /// CHECK: Position being migrated — must be owned by session_key; closed here
+    pub position: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Source Meteora DLMM LB pair pool
+    pub source_lb_pair: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Optional source bin array bitmap extension
+    pub source_bin_array_bitmap_extension: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    /// CHECK: Source pool token X reserve
+    pub source_reserve_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Source pool token Y reserve
+    pub source_reserve_y: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Lower bin array covering the source position's range
+    pub source_bin_array_lower: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Upper bin array covering the source position's range
+    pub source_bin_array_upper: UncheckedAccount<'info>,
+
+    // ── Target pool ───────────────────────────────────────────────────────
+
+    /// New position account — fresh keypair, must sign
+    #[account(mut)]
+    pub new_position: Signer<'info>,
+
+    #[account(mut)]
+    /// CHECK: Target Meteora DLMM LB pair pool (same pair, different bin step)
+    pub target_lb_pair: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Optional target bin array bitmap extension
+    pub target_bin_array_bitmap_extension: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    /// CHECK: Target pool token X reserve
+    pub target_reserve_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Target pool token Y reserve
+    pub target_reserve_y: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Lower bin array covering the new position's range
+    pub target_bin_array_lower: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Upper bin array covering the new position's range
+    pub target_bin_array_upper: UncheckedAccount<'info>,
+
+    // ── Programs ──────────────────────────────────────────────────────────
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The session's PositionRegistry — updated to the new position when passed
+    #[account(
+        mut,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    /// LpPositionMonitor PDA tracking the source position — retargeted when passed
+    #[account(
+        mut,
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump = monitor.load()?.bump,
+        constraint = monitor.load()?.position == position.key() @ AgentError::MonitorPositionMismatch,
+    )]
+    pub monitor: Option<AccountLoader<'info, LpPositionMonitor>>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+    /// The owner's PairPools list for this pool's pair — required once the
+    /// session has any ranked pool list
+    #[account(
+        seeds = [
+            b"pair_pools",
+            session.key().as_ref(),
+            pair_pools.token_x_mint.as_ref(),
+            pair_pools.token_y_mint.as_ref(),
+        ],
+        bump = pair_pools.bump,
+    )]
+    pub pair_pools: Option<Account<'info, PairPools>>,
+}
+
+impl<'info> ExecuteDlmmMigratePosition<'info> {
+    fn repositioner(&self) -> Repositioner<'info> {
+        Repositioner {
+            session_key: self.session_key.to_account_info(),
+            user_token_x: self.user_token_x.to_account_info(),
+            user_token_y: self.user_token_y.to_account_info(),
+            token_x_mint: self.token_x_mint.to_account_info(),
+            token_y_mint: self.token_y_mint.to_account_info(),
+            token_x_program: self.token_x_program.to_account_info(),
+            token_y_program: self.token_y_program.to_account_info(),
+            dlmm_program: self.dlmm_program.to_account_info(),
+            event_authority: self.event_authority.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }
+    }
+
+    /// (source, target)
+    fn pools(&self) -> (PoolRange<'info>, PoolRange<'info>) {
+        let source = PoolRange {
+            lb_pair: self.source_lb_pair.to_account_info(),
+            bin_array_bitmap_extension: self
+                .source_bin_array_bitmap_extension
+                .as_ref()
+                .map(|a| a.to_account_info()),
+            reserve_x: self.source_reserve_x.to_account_info(),
+            reserve_y: self.source_reserve_y.to_account_info(),
+            bin_array_lower: self.source_bin_array_lower.to_account_info(),
+            bin_array_upper: self.source_bin_array_upper.to_account_info(),
+        };
+        let target = PoolRange {
+            lb_pair: self.target_lb_pair.to_account_info(),
+            bin_array_bitmap_extension: self
+                .target_bin_array_bitmap_extension
+                .as_ref()
+                .map(|a| a.to_account_info()),
+            reserve_x: self.target_reserve_x.to_account_info(),
+            reserve_y: self.target_reserve_y.to_account_info(),
+            bin_array_lower: self.target_bin_array_lower.to_account_info(),
+            bin_array_upper: self.target_bin_array_upper.to_account_info(),
+        };
+        (source, target)
+    }
+}
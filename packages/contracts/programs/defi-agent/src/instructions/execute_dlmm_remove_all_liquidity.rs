@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+use crate::alerts;
+use crate::balances;
+use crate::dlmm;
+use crate::state::{
+    ActionKind, AgentSession, Config, PositionRegistry, TemporalSource,
+    ACTION_DLMM_REMOVE_LIQUIDITY,
+};
+use crate::errors::AgentError;
+use crate::freeze;
+use crate::log_info;
+use crate::valuation::{self, VALUATION_CLOSE};
+use crate::introspection::{enforce_signed_intent, signature_fee_lamports, verify_declared_fee};
+
+/// Called by the ESP32 on the BASE LAYER using the session key.
+///
+/// First half of `execute_dlmm_close_position`, for positions wide enough that
+/// both CPIs don't fit one transaction's compute budget: CPIs DLMM
+/// `remove_all_liquidity` (tokens and pending fees return to the session key's
+/// ATAs), values the returned holdings for the session's PnL and records the
+/// position in `session.emptied_position`. Finish with
+/// `execute_dlmm_close_empty_position` in a later transaction.
+///
+/// `fee_lamports` (priority fee + tips) is charged to the fee budget.
+/// Devices with an `intent_signer` must sign an intent for (lb_pair, amount 0).
+/// When the session's PositionRegistry is passed, `PositionRealized` reports
+/// the withdrawal against the position's cost basis. A failed scope check is
+/// screened as in `execute_action` (see `freeze::screen`).
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmRemoveAllLiquidity<'info>>,
+    fee_lamports: u64,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    // ── Session validation ──────────────────────────────────────────────────
+    let device = ctx.accounts.session_key.key();
+    let now = clock.unix_timestamp;
+    let position = ctx.accounts.position.key();
+    let device_slot = match check_withdraw_scope(session, &device, &position, now) {
+        Ok(slot) => slot,
+        Err(err) => return freeze::screen(session, &device, err, now),
+    };
+    verify_declared_fee(&ctx.accounts.instructions_sysvar, &device, fee_lamports)?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.lb_pair.key(),
+        0,
+        clock.unix_timestamp,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    // ── Balance snapshot ─────────────────────────────────────────────────────
+    let x_before = balances::token_amount(&ctx.accounts.user_token_x.to_account_info())?;
+    let y_before = balances::token_amount(&ctx.accounts.user_token_y.to_account_info())?;
+
+    let dlmm_prog = ctx.accounts.dlmm_program.to_account_info();
+
+    // ── Remove all liquidity → tokens return to session key's ATAs ───────
+    let remove_accounts = dlmm::cpi::accounts::RemoveAllLiquidity {
+        position: ctx.accounts.position.to_account_info(),
+        lb_pair: ctx.accounts.lb_pair.to_account_info(),
+        bin_array_bitmap_extension: ctx
+            .accounts
+            .bin_array_bitmap_extension
+            .as_ref()
+            .map(|a| a.to_account_info()),
+        user_token_x: ctx.accounts.user_token_x.to_account_info(),
+        user_token_y: ctx.accounts.user_token_y.to_account_info(),
+        reserve_x: ctx.accounts.reserve_x.to_account_info(),
+        reserve_y: ctx.accounts.reserve_y.to_account_info(),
+        token_x_mint: ctx.accounts.token_x_mint.to_account_info(),
+        token_y_mint: ctx.accounts.token_y_mint.to_account_info(),
+        bin_array_lower: ctx.accounts.bin_array_lower.to_account_info(),
+        bin_array_upper: ctx.accounts.bin_array_upper.to_account_info(),
+        sender: ctx.accounts.session_key.to_account_info(),
+        token_x_program: ctx.accounts.token_x_program.to_account_info(),
+        token_y_program: ctx.accounts.token_y_program.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: dlmm_prog.clone(),
+    };
+    dlmm::cpi::remove_all_liquidity(CpiContext::new(dlmm_prog, remove_accounts))?;
+    let withdrawn_x = balances::inflow(&ctx.accounts.user_token_x.to_account_info(), x_before)?;
+    let withdrawn_y = balances::inflow(&ctx.accounts.user_token_y.to_account_info(), y_before)?;
+
+    // The entry itself is removed by execute_dlmm_close_empty_position
+    if let Some(registry) = ctx.accounts.position_registry.as_ref() {
+        if let Some(entry) = registry.load()?.get(&ctx.accounts.position.key()) {
+            valuation::report_realized(
+                session.key(),
+                &entry,
+                &ctx.accounts.lb_pair,
+                withdrawn_x,
+                withdrawn_y,
+            )?;
+        }
+    }
+
+    // ── Record the realized portfolio value ─────────────────────────────────
+    valuation::record(
+        session,
+        &ctx.accounts.lb_pair,
+        &ctx.accounts.user_token_x,
+        &ctx.accounts.user_token_y,
+        VALUATION_CLOSE,
+        clock.unix_timestamp,
+    )?;
+
+    session.emptied_position = ctx.accounts.position.key();
+    alerts::check_thresholds(session);
+
+    log_info!(
+        session,
+        "DLMM liquidity removed: position={} ready to close",
+        ctx.accounts.position.key(),
+    );
+
+    Ok(())
+}
+
+/// Session, device and position checks for a withdrawal, shared with
+/// `execute_dlmm_close_position`
+pub(crate) fn check_withdraw_scope(
+    session: &mut AgentSession,
+    device: &Pubkey,
+    position: &Pubkey,
+    now: i64,
+) -> Result<usize> {
+    let device_slot = session.validate_session(
+        device,
+        ActionKind::Action(ACTION_DLMM_REMOVE_LIQUIDITY),
+        TemporalSource::Device(now),
+    )?;
+    session.validate_position(position)?;
+    Ok(device_slot)
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDlmmRemoveAllLiquidity<'info> {
+    /// The ESP32 session key — must sign this transaction (also the DLMM `sender`)
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — validated and updated here
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    // ── Meteora DLMM accounts ─────────────────────────────────────────────
+
+    #[account(mut)]
+    /// CHECK: LP position account — must be owned by session_key
+    pub position: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Meteora DLMM LB pair pool
+    pub lb_pair: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Optional bin array bitmap extension (null for pools near bin 0)
+    pub bin_array_bitmap_extension: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    /// CHECK: Session key's token X ATA (receives withdrawn X tokens)
+    pub user_token_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Session key's token Y ATA (receives withdrawn Y tokens)
+    pub user_token_y: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Pool token X reserve
+    pub reserve_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Pool token Y reserve
+    pub reserve_y: UncheckedAccount<'info>,
+
+    /// CHECK: Token X mint
+    pub token_x_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token Y mint
+    pub token_y_mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Lower bin array covering the position's range
+    pub bin_array_lower: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Upper bin array covering the position's range
+    pub bin_array_upper: UncheckedAccount<'info>,
+
+    // ── Programs ──────────────────────────────────────────────────────────
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// The session's PositionRegistry — read for the position's cost basis
+    #[account(
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+}
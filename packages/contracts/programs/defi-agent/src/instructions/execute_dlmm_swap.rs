@@ -1,9 +1,26 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::alerts;
 use crate::dlmm;
-use crate::state::AgentSession;
+use crate::state::{
+    ActionKind, ActionRequest, AgentSession, Config, DailyStats, PoolRegistry, TemporalSource,
+    TreasuryLedger, ACTION_DLMM_SWAP, REASON_UNSPECIFIED, REQUEST_DLMM_SWAP,
+};
+use crate::balances;
 use crate::errors::AgentError;
+use crate::events::{ActionExecuted, SwapSettled};
+use crate::freeze;
+use crate::log_info;
+use crate::fees::{charge_action_fee, skim_protocol_fee, FeeSkimAccounts};
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
+use crate::valuation::{
+    check_active_bin_band, pool_fee_rate, pool_price, pool_price_q64, quote_out, record_pool_fee,
+    record_slippage, swap_budget_value, swap_fee,
+};
 
-/// Called by the ESP32 on the EPHEMERAL ROLLUP using the session key.
+/// Called by the ESP32 on the BASE LAYER using the session key.
 ///
 /// Validates the session scope (active, not expired, session key matches,
 /// LP strategy enabled, exposure within cap) then CPIs into the Meteora DLMM
@@ -12,77 +29,323 @@ use crate::errors::AgentError;
 /// Bin arrays for the pool must be passed in `remaining_accounts` (1–2 accounts
 /// depending on the pool's active bin range). The TypeScript client fetches
 /// these via the `@meteora-ag/dlmm` SDK before building the transaction.
+///
+/// `fee_lamports` declares the priority fee + tips attached to this transaction;
+/// it is checked against the instructions sysvar and charged to the fee budget.
+///
+/// Devices with an `intent_signer` must also include an ed25519-verify
+/// instruction over the intent (session, lb_pair, amount_in, nonce, expiry).
+///
+/// When the Config carries a non-zero `protocol_fee_bps`, that share of
+/// `amount_in` is transferred to the input mint's treasury before the swap and
+/// only the remainder is routed through DLMM — `min_amount_out` should be
+/// quoted against the post-fee amount. If the session has a referrer, the
+/// Config's `referral_share_bps` of that fee goes to `referrer_token` instead.
+///
+/// Scope checks run against the declared `amount_in`; exposure, the per-action
+/// fee and the `SwapSettled` event are then charged on what actually left
+/// `user_token_in` (fee skim included) and arrived in `user_token_out`. A CPI
+/// that moves more than declared, or delivers less than `min_amount_out`, fails.
+///
+/// The pool's active-bin price and fee rate are read before the swap. The
+/// estimated pool fee is added to the session's `swap_fees_paid` (failing the
+/// swap past the owner's `swap_fee_budget`); the output the price quotes for
+/// the swapped amount after that fee is reported as `expected_out`, and the
+/// shortfall of the actual output is added to the session's `slippage_paid`.
+///
+/// Passing an owner-approved `action_request` for this exact action waives the
+/// per-action cap, registry-only mode and co-sign threshold, once. The owner's
+/// minimum trade amount for the input mint always applies.
+///
+/// As in `execute_action`, a failed scope check by an enrolled device is
+/// screened first (see `freeze::screen`): with the violation freeze or an
+/// exposure cooldown on, it is recorded and the swap skipped instead of failing.
+/// With an `action_request` supplied the check fails outright, so the request
+/// is only consumed by a swap that actually runs.
+///
+/// `min_active_bin` / `max_active_bin`, when set, bound the pool's active bin
+/// as read from `lb_pair`: outside the band the swap fails before any CPI,
+/// guarding against executing into a dislocated pool.
+///
+/// For a session with a `budget_mint`, caps, co-sign threshold and exposure
+/// are counted in that mint's units: a swap out of it at the input spent, a
+/// swap into it at the output received (quoted at the pool price for the
+/// scope checks), and any other swap at its input valued through
+/// `budget_price_pool`, the DLMM pool the owner pinned for the input mint.
 pub fn handler<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmSwap<'info>>,
     amount_in: u64,
     min_amount_out: u64,
+    fee_lamports: u64,
+    min_active_bin: Option<i32>,
+    max_active_bin: Option<i32>,
 ) -> Result<()> {
-    let session = &mut ctx.accounts.session;
+    check_active_bin_band(&ctx.accounts.lb_pair.to_account_info(), min_active_bin, max_active_bin)?;
+    process_swap(ctx.accounts, ctx.remaining_accounts, amount_in, min_amount_out, fee_lamports)?;
+    Ok(())
+}
+
+/// Validation, fee handling, DLMM CPI and accounting shared by
+/// `execute_dlmm_swap` and `fulfill_intent`. Returns the input tokens that
+/// actually left the session key — none when a screened scope check skipped
+/// the swap.
+pub(crate) fn process_swap<'info>(
+    accounts: &mut ExecuteDlmmSwap<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    min_amount_out: u64,
+    fee_lamports: u64,
+) -> Result<u64> {
     let clock = Clock::get()?;
+    let device = accounts.session_key.key();
+    let now = clock.unix_timestamp;
+
+    // ── Pre-trade quote ──────────────────────────────────────────────────────
+    let x_to_y = accounts.user_token_in.mint == accounts.token_x_mint.key();
+    let price = pool_price(&accounts.lb_pair.to_account_info())?;
+    let fee_price = pool_price_q64(&accounts.lb_pair.to_account_info())?;
+    let fee_rate = pool_fee_rate(&accounts.lb_pair.to_account_info())?;
+    let output_mint = if x_to_y {
+        accounts.token_y_mint.key()
+    } else {
+        accounts.token_x_mint.key()
+    };
+    let notional = swap_budget_value(
+        &accounts.session,
+        &accounts.user_token_in.mint,
+        amount_in,
+        &output_mint,
+        quote_out(price, x_to_y, amount_in),
+        accounts.budget_price_pool.as_deref(),
+    )?;
 
     // ── Session validation ────────────────────────────────────────────────────
-    session.validate_lp_session(ctx.accounts.session_key.key(), clock.unix_timestamp)?;
-    let new_spent = session
-        .spent_lamports
-        .checked_add(amount_in)
-        .ok_or(AgentError::Overflow)?;
-    require!(new_spent <= session.max_lamports, AgentError::ExposureLimitExceeded);
+    let device_slot = match check_scope(accounts, amount_in, notional, now) {
+        Ok(slot) => slot,
+        Err(err) if accounts.action_request.is_some() => return Err(err),
+        Err(err) => return freeze::screen(&mut accounts.session, &device, err, now).map(|()| 0),
+    };
+    let session = &mut accounts.session;
+    verify_declared_fee(&accounts.instructions_sysvar, &device, fee_lamports)?;
+    enforce_signed_intent(
+        &accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &accounts.lb_pair.key(),
+        amount_in,
+        now,
+    )?;
+    let tx_fee = signature_fee_lamports(&accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    // ── Balance snapshot ─────────────────────────────────────────────────────
+    let in_before = accounts.user_token_in.amount;
+    let out_before = balances::token_amount(&accounts.user_token_out.to_account_info())?;
+
+    // ── Protocol fee skim ────────────────────────────────────────────────────
+    let protocol_fee = accounts.config.protocol_fee_on(amount_in);
+    let swap_amount = amount_in - protocol_fee;
+    let (input_mint, input_token_program) =
+        if accounts.user_token_in.mint == accounts.token_x_mint.key() {
+            (&accounts.token_x_mint, &accounts.token_x_program)
+        } else {
+            (&accounts.token_y_mint, &accounts.token_y_program)
+        };
+    skim_protocol_fee(
+        protocol_fee,
+        accounts.config.referral_share_bps,
+        session.referrer,
+        FeeSkimAccounts {
+            token_program: input_token_program.to_account_info(),
+            from: accounts.user_token_in.to_account_info(),
+            mint: input_mint,
+            authority: accounts.session_key.to_account_info(),
+            treasury: accounts.treasury.as_ref().map(|a| a.to_account_info()),
+            ledger: accounts.treasury_ledger.as_mut(),
+            referrer_token: accounts.referrer_token.as_ref(),
+        },
+    )?;
 
     // ── CPI to Meteora DLMM swap ─────────────────────────────────────────────
     let cpi_accounts = dlmm::cpi::accounts::Swap {
-        lb_pair: ctx.accounts.lb_pair.to_account_info(),
-        bin_array_bitmap_extension: ctx
-            .accounts
+        lb_pair: accounts.lb_pair.to_account_info(),
+        bin_array_bitmap_extension: accounts
             .bin_array_bitmap_extension
             .as_ref()
             .map(|a| a.to_account_info()),
-        reserve_x: ctx.accounts.reserve_x.to_account_info(),
-        reserve_y: ctx.accounts.reserve_y.to_account_info(),
-        user_token_in: ctx.accounts.user_token_in.to_account_info(),
-        user_token_out: ctx.accounts.user_token_out.to_account_info(),
-        token_x_mint: ctx.accounts.token_x_mint.to_account_info(),
-        token_y_mint: ctx.accounts.token_y_mint.to_account_info(),
-        oracle: ctx.accounts.oracle.to_account_info(),
+        reserve_x: accounts.reserve_x.to_account_info(),
+        reserve_y: accounts.reserve_y.to_account_info(),
+        user_token_in: accounts.user_token_in.to_account_info(),
+        user_token_out: accounts.user_token_out.to_account_info(),
+        token_x_mint: accounts.token_x_mint.to_account_info(),
+        token_y_mint: accounts.token_y_mint.to_account_info(),
+        oracle: accounts.oracle.to_account_info(),
         host_fee_in: None,
-        user: ctx.accounts.session_key.to_account_info(),
-        token_x_program: ctx.accounts.token_x_program.to_account_info(),
-        token_y_program: ctx.accounts.token_y_program.to_account_info(),
-        event_authority: ctx.accounts.event_authority.to_account_info(),
-        program: ctx.accounts.dlmm_program.to_account_info(),
+        user: accounts.session_key.to_account_info(),
+        token_x_program: accounts.token_x_program.to_account_info(),
+        token_y_program: accounts.token_y_program.to_account_info(),
+        event_authority: accounts.event_authority.to_account_info(),
+        program: accounts.dlmm_program.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(
-        ctx.accounts.dlmm_program.to_account_info(),
+        accounts.dlmm_program.to_account_info(),
         cpi_accounts,
     )
-    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    .with_remaining_accounts(remaining_accounts.to_vec());
+
+    dlmm::cpi::swap(cpi_ctx, swap_amount, min_amount_out)?;
+
+    // ── Settle on observed balance deltas ───────────────────────────────────
+    let spent = balances::outflow(&accounts.user_token_in.to_account_info(), in_before)?;
+    let received = balances::inflow(&accounts.user_token_out.to_account_info(), out_before)?;
+    require!(spent <= amount_in, AgentError::BalanceDeltaExceeded);
+    require!(received >= min_amount_out, AgentError::OutputBelowMinimum);
+    let swapped = spent.saturating_sub(protocol_fee);
+    let pool_fee = swap_fee(swapped, fee_rate);
+    let expected_out = quote_out(price, x_to_y, swapped - pool_fee);
 
-    dlmm::cpi::swap(cpi_ctx, amount_in, min_amount_out)?;
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_SWAP,
+        spent,
+        accounts.session_key.to_account_info(),
+        accounts.fee_vault.to_account_info(),
+        accounts.system_program.to_account_info(),
+    )?;
 
     // ── Update session accounting ────────────────────────────────────────────
-    session.spent_lamports = new_spent;
+    let charged = swap_budget_value(
+        session,
+        &accounts.user_token_in.mint,
+        spent,
+        &output_mint,
+        received,
+        accounts.budget_price_pool.as_deref(),
+    )?;
+    session.apply_spend(device_slot, ACTION_DLMM_SWAP, charged)?;
     session.bump_actions()?;
+    alerts::check_thresholds(session);
     session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, charged, fee_paid);
+    }
+    let pool_fee = record_pool_fee(session, fee_price, x_to_y, pool_fee)?;
+    record_slippage(session, accounts.lb_pair.key(), price, !x_to_y, expected_out, received);
 
-    msg!(
-        "DLMM swap executed: amount_in={}, min_out={}, total_spent={}/{}",
-        amount_in,
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_SWAP,
+        amount: spent,
+        total_actions: session.total_actions,
+        reason: REASON_UNSPECIFIED,
+    });
+    emit_action_memo(
+        &accounts.instructions_sysvar,
+        session.key(),
+        accounts.session_key.key(),
+        session.total_actions,
+    )?;
+
+    emit!(SwapSettled {
+        session: session.key(),
+        lb_pair: accounts.lb_pair.key(),
+        amount_in: spent,
+        amount_out: received,
+        min_amount_out,
+        protocol_fee,
+        expected_out,
+        pool_fee,
+    });
+
+    log_info!(
+        session,
+        "DLMM swap executed: amount_in={}, amount_out={}, protocol_fee={}, min_out={}, total_spent={}/{}",
+        spent,
+        received,
+        protocol_fee,
         min_amount_out,
         session.spent_lamports,
         session.max_lamports,
     );
 
-    Ok(())
+    Ok(spent)
+}
+
+/// Session, device and scope checks for a swap of `amount_in`, `notional` in
+/// budget units
+fn check_scope(
+    accounts: &mut ExecuteDlmmSwap,
+    amount_in: u64,
+    notional: u64,
+    now: i64,
+) -> Result<usize> {
+    let session = &mut accounts.session;
+    let device_slot = session.validate_session(
+        &accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_SWAP),
+        TemporalSource::Device(now),
+    )?;
+
+    // Scope checks — waived for an owner-approved ActionRequest matching this swap
+    let approved = match &accounts.action_request {
+        Some(request) => {
+            require!(
+                request.authorizes(
+                    REQUEST_DLMM_SWAP,
+                    &accounts.session_key.key(),
+                    &accounts.lb_pair.key(),
+                    amount_in,
+                ),
+                AgentError::RequestMismatch
+            );
+            true
+        }
+        None => false,
+    };
+    if !approved {
+        session.validate_pool(
+            accounts.pool_registry.as_deref(),
+            &accounts.lb_pair.key(),
+        )?;
+        session.check_action_cap(notional)?;
+        session.check_cosign(notional, accounts.cosigner.as_ref().map(|s| s.key()))?;
+    }
+    session.check_exposure(device_slot, notional)?;
+    session.check_min_trade(&accounts.user_token_in.mint, amount_in)?;
+    Ok(device_slot)
 }
 
 #[derive(Accounts)]
 pub struct ExecuteDlmmSwap<'info> {
     /// The ESP32 session key — must sign this transaction (also the DLMM `user`)
+    #[account(mut)]
     pub session_key: Signer<'info>,
 
     /// Scoped session PDA — validated and updated here
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
     pub session: Account<'info, AgentSession>,
 
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
     // ── Meteora DLMM accounts ────────────────────────────────────────────────
 
     #[account(mut)]
@@ -100,19 +363,23 @@ pub struct ExecuteDlmmSwap<'info> {
     /// CHECK: Token Y reserve account of the pool
     pub reserve_y: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    /// CHECK: User's (session key) input token ATA
-    pub user_token_in: UncheckedAccount<'info>,
+    /// User's (session key) input token ATA
+    #[account(
+        mut,
+        constraint = user_token_in.mint == token_x_mint.key()
+            || user_token_in.mint == token_y_mint.key(),
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut)]
     /// CHECK: User's (session key) output token ATA
     pub user_token_out: UncheckedAccount<'info>,
 
-    /// CHECK: Token X mint
-    pub token_x_mint: UncheckedAccount<'info>,
+    /// Token X mint
+    pub token_x_mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK: Token Y mint
-    pub token_y_mint: UncheckedAccount<'info>,
+    /// Token Y mint
+    pub token_y_mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
     /// CHECK: Oracle account for the pool
@@ -122,13 +389,77 @@ pub struct ExecuteDlmmSwap<'info> {
     /// CHECK: Meteora DLMM program
     pub dlmm_program: UncheckedAccount<'info>,
 
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
     /// CHECK: DLMM CPI event authority (PDA of DLMM program)
     pub event_authority: UncheckedAccount<'info>,
 
-    /// CHECK: Token program for token X (SPL Token or Token-2022)
-    pub token_x_program: UncheckedAccount<'info>,
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    // ── Protocol treasury (required only when protocol_fee_bps > 0) ─────────
+
+    #[account(mut)]
+    /// CHECK: Treasury token account for the input mint — checked against `treasury_ledger.vault`
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    /// Per-mint fee ledger for the input mint
+    #[account(
+        mut,
+        seeds = [b"treasury_ledger", user_token_in.mint.as_ref()],
+        bump = treasury_ledger.bump,
+    )]
+    pub treasury_ledger: Option<Account<'info, TreasuryLedger>>,
+
+    /// Session referrer's token account for the input mint (required when a referral share is owed)
+    #[account(mut)]
+    pub referrer_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Session owner — must co-sign when the action's notional exceeds
+    /// `session.cosign_above_lamports`; pass `None` for routine actions
+    pub cosigner: Option<Signer<'info>>,
+
+    /// Owner-approved ActionRequest for exactly this swap; closed (rent back
+    /// to the device) once consumed. Pass `None` for in-scope actions.
+    #[account(
+        mut,
+        close = session_key,
+        seeds = [b"action_request", session.key().as_ref(), &action_request.request_id.to_le_bytes()],
+        bump = action_request.bump,
+    )]
+    pub action_request: Option<Account<'info, ActionRequest>>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
 
-    /// CHECK: Token program for token Y (SPL Token or Token-2022)
-    pub token_y_program: UncheckedAccount<'info>,
+    /// CHECK: the owner's pinned budget price pool for the input mint —
+    /// values the input when neither swap leg is the budget mint
+    pub budget_price_pool: Option<UncheckedAccount<'info>>,
     // Bin arrays → ctx.remaining_accounts (1–2 accounts, fetched via SDK)
 }
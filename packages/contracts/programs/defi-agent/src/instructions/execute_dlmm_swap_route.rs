@@ -0,0 +1,475 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::alerts;
+use crate::dlmm;
+use crate::state::{
+    ActionKind, AgentSession, Config, DailyStats, PoolRegistry, TemporalSource, TreasuryLedger,
+    ACTION_DLMM_SWAP, REASON_UNSPECIFIED,
+};
+use crate::balances;
+use crate::errors::AgentError;
+use crate::events::{ActionExecuted, SwapSettled};
+use crate::freeze;
+use crate::log_info;
+use crate::fees::{charge_action_fee, skim_protocol_fee, FeeSkimAccounts};
+use crate::introspection::{
+    emit_action_memo, enforce_signed_intent, signature_fee_lamports, verify_declared_fee,
+};
+use crate::valuation::{
+    pool_fee_rate, pool_price, pool_price_q64, quote_out, record_pool_fee, record_slippage,
+    swap_budget_value, swap_fee,
+};
+
+/// Accounts of the second leg's pool, passed in `remaining_accounts` after the
+/// first leg's bin arrays, in order: lb_pair, bin_array_bitmap_extension (DLMM
+/// program id for none), reserve_x, reserve_y, token_x_mint, token_y_mint,
+/// oracle, token_x_program, token_y_program. Its bin arrays follow.
+pub const ROUTE_LEG_ACCOUNTS: usize = 9;
+
+/// Called by the ESP32 on the BASE LAYER using the session key.
+///
+/// Two-hop swap (e.g. X → SOL → Y) for pairs without a direct pool, executed
+/// atomically with a single exposure debit. The first leg swaps `amount_in`
+/// (less the protocol fee) from `user_token_in` into `user_token_mid` through
+/// the pool in the named accounts; the second leg swaps exactly what the first
+/// leg produced from `user_token_mid` into `user_token_out` through the pool
+/// in `remaining_accounts`, and enforces `min_amount_out` on the final output.
+///
+/// `remaining_accounts` layout: the first leg's `leg1_bin_arrays` bin arrays,
+/// then `ROUTE_LEG_ACCOUNTS` accounts for the second pool, then its bin arrays.
+///
+/// Both pools must pass the session's pool scope. Validation (a failed scope
+/// check screened by `freeze::screen`), protocol fee, per-action fee and
+/// intent (signed over the first pool and `amount_in`) follow
+/// `execute_dlmm_swap`, as does settling exposure and the `SwapSettled`
+/// event on observed balance deltas; owner-approved action requests are not
+/// accepted for routes. The expected output chains both pools' pre-trade
+/// active-bin quotes net of each pool's estimated fee; each fee counts into
+/// `swap_fees_paid` in its own pool's Y units, and slippage is valued in the
+/// second pool's Y units.
+///
+/// With a session `budget_mint`, the route counts against caps and exposure
+/// like a direct swap (see `execute_dlmm_swap`), except that an intermediate
+/// leg in the budget mint values it at the amount the first leg produced.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmSwapRoute<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+    leg1_bin_arrays: u8,
+    fee_lamports: u64,
+) -> Result<()> {
+    let leg1_bin_arrays = leg1_bin_arrays as usize;
+    require!(
+        ctx.remaining_accounts.len() > leg1_bin_arrays + ROUTE_LEG_ACCOUNTS,
+        AgentError::InvalidSwapRoute
+    );
+    let (leg1_arrays, rest) = ctx.remaining_accounts.split_at(leg1_bin_arrays);
+    let (leg2, leg2_arrays) = rest.split_at(ROUTE_LEG_ACCOUNTS);
+
+    let clock = Clock::get()?;
+    let device = ctx.accounts.session_key.key();
+    let now = clock.unix_timestamp;
+
+    // ── Pre-trade quotes ─────────────────────────────────────────────────────
+    let leg1_x_to_y = ctx.accounts.user_token_in.mint == ctx.accounts.token_x_mint.key();
+    let leg2_x_to_y = ctx.accounts.user_token_mid.mint == leg2[4].key();
+    let leg1_price = pool_price(&ctx.accounts.lb_pair.to_account_info())?;
+    let leg2_price = pool_price(&leg2[0])?;
+    let leg1_fee_price = pool_price_q64(&ctx.accounts.lb_pair.to_account_info())?;
+    let leg2_fee_price = pool_price_q64(&leg2[0])?;
+    let leg1_fee_rate = pool_fee_rate(&ctx.accounts.lb_pair.to_account_info())?;
+    let leg2_fee_rate = pool_fee_rate(&leg2[0])?;
+    let mid_mint = ctx.accounts.user_token_mid.mint;
+    let output_mint = if leg2_x_to_y { leg2[5].key() } else { leg2[4].key() };
+    let quoted_mid = quote_out(leg1_price, leg1_x_to_y, amount_in);
+    let (leg_mint, leg_amount) = budget_leg(
+        &ctx.accounts.session,
+        (mid_mint, quoted_mid),
+        (output_mint, quote_out(leg2_price, leg2_x_to_y, quoted_mid)),
+    );
+    let notional = swap_budget_value(
+        &ctx.accounts.session,
+        &ctx.accounts.user_token_in.mint,
+        amount_in,
+        &leg_mint,
+        leg_amount,
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+
+    // ── Session validation ────────────────────────────────────────────────────
+    let device_slot = match check_scope(ctx.accounts, &leg2[0].key(), amount_in, notional, now) {
+        Ok(slot) => slot,
+        Err(err) => return freeze::screen(&mut ctx.accounts.session, &device, err, now),
+    };
+    let session = &mut ctx.accounts.session;
+    verify_declared_fee(&ctx.accounts.instructions_sysvar, &device, fee_lamports)?;
+    enforce_signed_intent(
+        &ctx.accounts.instructions_sysvar,
+        session,
+        device_slot,
+        &ctx.accounts.lb_pair.key(),
+        amount_in,
+        now,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    // ── Balance snapshot ─────────────────────────────────────────────────────
+    let in_before = ctx.accounts.user_token_in.amount;
+    let out_before = balances::token_amount(&ctx.accounts.user_token_out.to_account_info())?;
+
+    // ── Protocol fee skim ────────────────────────────────────────────────────
+    let protocol_fee = ctx.accounts.config.protocol_fee_on(amount_in);
+    let swap_amount = amount_in - protocol_fee;
+    let (input_mint, input_token_program) =
+        if ctx.accounts.user_token_in.mint == ctx.accounts.token_x_mint.key() {
+            (&ctx.accounts.token_x_mint, &ctx.accounts.token_x_program)
+        } else {
+            (&ctx.accounts.token_y_mint, &ctx.accounts.token_y_program)
+        };
+    skim_protocol_fee(
+        protocol_fee,
+        ctx.accounts.config.referral_share_bps,
+        session.referrer,
+        FeeSkimAccounts {
+            token_program: input_token_program.to_account_info(),
+            from: ctx.accounts.user_token_in.to_account_info(),
+            mint: input_mint,
+            authority: ctx.accounts.session_key.to_account_info(),
+            treasury: ctx.accounts.treasury.as_ref().map(|a| a.to_account_info()),
+            ledger: ctx.accounts.treasury_ledger.as_mut(),
+            referrer_token: ctx.accounts.referrer_token.as_ref(),
+        },
+    )?;
+
+    let dlmm_prog = ctx.accounts.dlmm_program.to_account_info();
+    let mid_before = ctx.accounts.user_token_mid.amount;
+
+    // ── Leg 1: input → intermediate ──────────────────────────────────────────
+    let leg1_accounts = dlmm::cpi::accounts::Swap {
+        lb_pair: ctx.accounts.lb_pair.to_account_info(),
+        bin_array_bitmap_extension: ctx
+            .accounts
+            .bin_array_bitmap_extension
+            .as_ref()
+            .map(|a| a.to_account_info()),
+        reserve_x: ctx.accounts.reserve_x.to_account_info(),
+        reserve_y: ctx.accounts.reserve_y.to_account_info(),
+        user_token_in: ctx.accounts.user_token_in.to_account_info(),
+        user_token_out: ctx.accounts.user_token_mid.to_account_info(),
+        token_x_mint: ctx.accounts.token_x_mint.to_account_info(),
+        token_y_mint: ctx.accounts.token_y_mint.to_account_info(),
+        oracle: ctx.accounts.oracle.to_account_info(),
+        host_fee_in: None,
+        user: ctx.accounts.session_key.to_account_info(),
+        token_x_program: ctx.accounts.token_x_program.to_account_info(),
+        token_y_program: ctx.accounts.token_y_program.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: dlmm_prog.clone(),
+    };
+    dlmm::cpi::swap(
+        CpiContext::new(dlmm_prog.clone(), leg1_accounts)
+            .with_remaining_accounts(leg1_arrays.to_vec()),
+        swap_amount,
+        0, // slippage is enforced on the final leg
+    )?;
+
+    ctx.accounts.user_token_mid.reload()?;
+    let mid_amount = ctx
+        .accounts
+        .user_token_mid
+        .amount
+        .checked_sub(mid_before)
+        .ok_or(AgentError::Overflow)?;
+
+    // ── Leg 2: intermediate → output ─────────────────────────────────────────
+    let leg2_accounts = dlmm::cpi::accounts::Swap {
+        lb_pair: leg2[0].clone(),
+        bin_array_bitmap_extension: (leg2[1].key() != dlmm::ID).then(|| leg2[1].clone()),
+        reserve_x: leg2[2].clone(),
+        reserve_y: leg2[3].clone(),
+        user_token_in: ctx.accounts.user_token_mid.to_account_info(),
+        user_token_out: ctx.accounts.user_token_out.to_account_info(),
+        token_x_mint: leg2[4].clone(),
+        token_y_mint: leg2[5].clone(),
+        oracle: leg2[6].clone(),
+        host_fee_in: None,
+        user: ctx.accounts.session_key.to_account_info(),
+        token_x_program: leg2[7].clone(),
+        token_y_program: leg2[8].clone(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: dlmm_prog.clone(),
+    };
+    dlmm::cpi::swap(
+        CpiContext::new(dlmm_prog, leg2_accounts).with_remaining_accounts(leg2_arrays.to_vec()),
+        mid_amount,
+        min_amount_out,
+    )?;
+
+    // ── Settle on observed balance deltas ───────────────────────────────────
+    let spent = balances::outflow(&ctx.accounts.user_token_in.to_account_info(), in_before)?;
+    let received = balances::inflow(&ctx.accounts.user_token_out.to_account_info(), out_before)?;
+    require!(spent <= amount_in, AgentError::BalanceDeltaExceeded);
+    require!(received >= min_amount_out, AgentError::OutputBelowMinimum);
+    let swapped = spent.saturating_sub(protocol_fee);
+    let leg1_fee = swap_fee(swapped, leg1_fee_rate);
+    let leg2_fee = swap_fee(mid_amount, leg2_fee_rate);
+    let expected_mid = quote_out(leg1_price, leg1_x_to_y, swapped - leg1_fee);
+    let expected_out = quote_out(
+        leg2_price,
+        leg2_x_to_y,
+        expected_mid.saturating_sub(swap_fee(expected_mid, leg2_fee_rate)),
+    );
+
+    // ── Per-action protocol fee ─────────────────────────────────────────────
+    let fee_paid = charge_action_fee(
+        session,
+        ACTION_DLMM_SWAP,
+        spent,
+        ctx.accounts.session_key.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // ── Update session accounting ────────────────────────────────────────────
+    let (leg_mint, leg_amount) =
+        budget_leg(session, (mid_mint, mid_amount), (output_mint, received));
+    let charged = swap_budget_value(
+        session,
+        &ctx.accounts.user_token_in.mint,
+        spent,
+        &leg_mint,
+        leg_amount,
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+    session.apply_spend(device_slot, ACTION_DLMM_SWAP, charged)?;
+    session.bump_actions()?;
+    alerts::check_thresholds(session);
+    session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, charged, fee_paid);
+    }
+    let pool_fee = record_pool_fee(session, leg1_fee_price, leg1_x_to_y, leg1_fee)?
+        .saturating_add(record_pool_fee(session, leg2_fee_price, leg2_x_to_y, leg2_fee)?);
+    record_slippage(session, leg2[0].key(), leg2_price, !leg2_x_to_y, expected_out, received);
+
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_SWAP,
+        amount: spent,
+        total_actions: session.total_actions,
+        reason: REASON_UNSPECIFIED,
+    });
+    emit_action_memo(
+        &ctx.accounts.instructions_sysvar,
+        session.key(),
+        ctx.accounts.session_key.key(),
+        session.total_actions,
+    )?;
+
+    emit!(SwapSettled {
+        session: session.key(),
+        lb_pair: ctx.accounts.lb_pair.key(),
+        amount_in: spent,
+        amount_out: received,
+        min_amount_out,
+        protocol_fee,
+        expected_out,
+        pool_fee,
+    });
+
+    log_info!(
+        session,
+        "DLMM route swap executed: amount_in={}, mid={}, amount_out={}, min_out={}, total_spent={}/{}",
+        spent,
+        mid_amount,
+        received,
+        min_amount_out,
+        session.spent_lamports,
+        session.max_lamports,
+    );
+
+    Ok(())
+}
+
+/// Leg a route is valued against in budget mode: the intermediate when it is
+/// the budget mint, otherwise the final output.
+fn budget_leg(session: &AgentSession, mid: (Pubkey, u64), out: (Pubkey, u64)) -> (Pubkey, u64) {
+    if session.has_budget_mint() && mid.0 == session.budget_mint {
+        mid
+    } else {
+        out
+    }
+}
+
+/// Session, device and scope checks for a route of `amount_in` through both
+/// pools, `notional` in budget units
+fn check_scope(
+    accounts: &mut ExecuteDlmmSwapRoute,
+    leg2_pool: &Pubkey,
+    amount_in: u64,
+    notional: u64,
+    now: i64,
+) -> Result<usize> {
+    let session = &mut accounts.session;
+    let device_slot = session.validate_session(
+        &accounts.session_key.key(),
+        ActionKind::Action(ACTION_DLMM_SWAP),
+        TemporalSource::Device(now),
+    )?;
+    session.validate_pool(accounts.pool_registry.as_deref(), &accounts.lb_pair.key())?;
+    session.validate_pool(accounts.pool_registry.as_deref(), leg2_pool)?;
+    session.check_action_cap(notional)?;
+    session.check_cosign(notional, accounts.cosigner.as_ref().map(|s| s.key()))?;
+    session.check_exposure(device_slot, notional)?;
+    session.check_min_trade(&accounts.user_token_in.mint, amount_in)?;
+    Ok(device_slot)
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDlmmSwapRoute<'info> {
+    /// The ESP32 session key — must sign this transaction (also the DLMM `user`)
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — validated and updated here
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    // ── First leg's Meteora DLMM accounts ────────────────────────────────────
+
+    #[account(mut)]
+    /// CHECK: First leg's Meteora DLMM LB pair pool
+    pub lb_pair: UncheckedAccount<'info>,
+
+    /// CHECK: Optional bin array bitmap extension of the first pool
+    pub bin_array_bitmap_extension: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    /// CHECK: Token X reserve account of the first pool
+    pub reserve_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Token Y reserve account of the first pool
+    pub reserve_y: UncheckedAccount<'info>,
+
+    /// User's (session key) input token ATA
+    #[account(
+        mut,
+        constraint = user_token_in.mint == token_x_mint.key()
+            || user_token_in.mint == token_y_mint.key(),
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's (session key) intermediate token ATA — first leg output, second leg input
+    #[account(
+        mut,
+        token::authority = session_key,
+        constraint = user_token_mid.mint == token_x_mint.key()
+            || user_token_mid.mint == token_y_mint.key(),
+        constraint = user_token_mid.mint != user_token_in.mint @ AgentError::InvalidSwapRoute,
+    )]
+    pub user_token_mid: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    /// CHECK: User's (session key) final output token ATA
+    pub user_token_out: UncheckedAccount<'info>,
+
+    /// Token X mint of the first pool
+    pub token_x_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Y mint of the first pool
+    pub token_y_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    /// CHECK: Oracle account of the first pool
+    pub oracle: UncheckedAccount<'info>,
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Token program for the first pool's token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for the first pool's token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    // ── Protocol treasury (required only when protocol_fee_bps > 0) ─────────
+
+    #[account(mut)]
+    /// CHECK: Treasury token account for the input mint — checked against `treasury_ledger.vault`
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    /// Per-mint fee ledger for the input mint
+    #[account(
+        mut,
+        seeds = [b"treasury_ledger", user_token_in.mint.as_ref()],
+        bump = treasury_ledger.bump,
+    )]
+    pub treasury_ledger: Option<Account<'info, TreasuryLedger>>,
+
+    /// Session referrer's token account for the input mint (required when a referral share is owed)
+    #[account(mut)]
+    pub referrer_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Protocol fee vault (system-owned PDA) — receives per-action fees
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Session owner — must co-sign when the action's notional exceeds
+    /// `session.cosign_above_lamports`; pass `None` for routine actions
+    pub cosigner: Option<Signer<'info>>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+
+    /// CHECK: the owner's pinned budget price pool for the input mint —
+    /// values the input when no leg of the route is the budget mint
+    pub budget_price_pool: Option<UncheckedAccount<'info>>,
+    // First leg bin arrays, second leg pool accounts + bin arrays → ctx.remaining_accounts
+}
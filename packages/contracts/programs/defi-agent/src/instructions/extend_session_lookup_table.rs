@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::state::{ActionKind, AgentSession, TemporalSource};
+use crate::errors::AgentError;
+use crate::lookup_table::{
+    extend_lookup_table_ix, ADDRESS_LOOKUP_TABLE_PROGRAM_ID, MAX_LOOKUP_TABLE_EXTEND,
+};
+
+/// [Base Layer] Append addresses to the session's Address Lookup Table.
+///
+/// Signed by an enrolled device key, which pays for the extra table space.
+/// The session PDA signs as the table authority. Intended for a pool's static
+/// accounts (lb_pair, reserves, mints, bin arrays, oracle, event authority,
+/// programs); at most `MAX_LOOKUP_TABLE_EXTEND` per call. Addresses become
+/// usable one slot after the extension lands.
+pub fn handler(ctx: Context<ExtendSessionLookupTable>, new_addresses: Vec<Pubkey>) -> Result<()> {
+    require!(
+        !new_addresses.is_empty() && new_addresses.len() <= MAX_LOOKUP_TABLE_EXTEND,
+        AgentError::InvalidLookupTableExtension
+    );
+
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Housekeeping,
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+
+    let owner = session.owner;
+    let bump_bytes = [session.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"session", owner.as_ref(), &bump_bytes]];
+    invoke_signed(
+        &extend_lookup_table_ix(
+            session.lookup_table,
+            session.key(),
+            ctx.accounts.session_key.key(),
+            &new_addresses,
+        ),
+        &[
+            ctx.accounts.lookup_table.to_account_info(),
+            session.to_account_info(),
+            ctx.accounts.session_key.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    msg!(
+        "Session lookup table extended: table={}, added={}",
+        session.lookup_table,
+        new_addresses.len(),
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExtendSessionLookupTable<'info> {
+    /// Enrolled device key — must sign and pays for the added space
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    /// Scoped session PDA — signs as the table authority
+    #[account(
+        mut,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        mut,
+        constraint = lookup_table.key() == session.lookup_table @ AgentError::LookupTableMismatch,
+    )]
+    /// CHECK: The session's lookup table — must match `session.lookup_table`
+    pub lookup_table: UncheckedAccount<'info>,
+
+    #[account(address = ADDRESS_LOOKUP_TABLE_PROGRAM_ID)]
+    /// CHECK: Address Lookup Table program
+    pub address_lookup_table_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{
+    ActionKind, ActionRequest, AgentSession, TemporalSource, REQUEST_DLMM_ADD_LIQUIDITY,
+};
+
+/// [Base Layer] File a request to run one out-of-scope action.
+///
+/// Signed (and paid for) by an enrolled device key. The request records the
+/// exact action — kind, pool, notional — and waits for the owner's
+/// `approve_request`. Filing does not grant anything by itself.
+pub fn handler(
+    ctx: Context<FileActionRequest>,
+    request_id: u64,
+    kind: u8,
+    lb_pair: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let session = &mut ctx.accounts.session;
+
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Housekeeping,
+        TemporalSource::Passive(clock.unix_timestamp),
+    )?;
+    require!(kind <= REQUEST_DLMM_ADD_LIQUIDITY, AgentError::InvalidRequestKind);
+
+    let request = &mut ctx.accounts.action_request;
+    request.session = session.key();
+    request.device_key = ctx.accounts.session_key.key();
+    request.request_id = request_id;
+    request.kind = kind;
+    request.lb_pair = lb_pair;
+    request.amount = amount;
+    request.approved = false;
+    request.created_at = clock.unix_timestamp;
+    request.bump = ctx.bumps.action_request;
+
+    msg!(
+        "Action request filed: id={}, kind={}, lb_pair={}, amount={}",
+        request_id,
+        kind,
+        lb_pair,
+        amount,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct FileActionRequest<'info> {
+    /// The device key filing the request — pays rent for the request PDA
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    #[account(
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        init,
+        payer = session_key,
+        space = ActionRequest::LEN,
+        seeds = [b"action_request", session.key().as_ref(), &request_id.to_le_bytes()],
+        bump,
+    )]
+    pub action_request: Account<'info, ActionRequest>,
+
+    pub system_program: Program<'info, System>,
+}
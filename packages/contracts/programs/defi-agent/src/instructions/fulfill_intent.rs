@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::instructions::execute_dlmm_swap::{process_swap, ExecuteDlmmSwap};
+use crate::state::Intent;
+
+/// [Base Layer] Execute all or part of an owner-preapproved swap.
+///
+/// Signed by the ESP32 session key. Checks the swap against the intent —
+/// same pool, input token on the approved side, `amount_in` within the
+/// unfilled remainder, deadline not passed — then runs the regular
+/// `execute_dlmm_swap` path with `min_amount_out` derived from the intent, so
+/// the device cannot accept a worse price than the owner approved. Session
+/// scope checks still apply. The fill is recorded at the input amount that
+/// actually moved. A large intent can be filled in tranches as
+/// liquidity allows; once fully filled it is closed and its rent returns to
+/// the owner.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, FulfillIntent<'info>>,
+    amount_in: u64,
+    fee_lamports: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let intent = &ctx.accounts.intent;
+    let swap = &ctx.accounts.swap;
+
+    // ── Intent bounds ───────────────────────────────────────────────────────
+    require!(clock.unix_timestamp < intent.deadline, AgentError::IntentExpired);
+    require!(
+        amount_in > 0 && amount_in <= intent.remaining(),
+        AgentError::IntentBoundsExceeded
+    );
+    require_keys_eq!(swap.lb_pair.key(), intent.lb_pair, AgentError::IntentMismatch);
+    let input_mint = if intent.swap_for_y {
+        swap.token_x_mint.key()
+    } else {
+        swap.token_y_mint.key()
+    };
+    require_keys_eq!(swap.user_token_in.mint, input_mint, AgentError::IntentMismatch);
+    let min_amount_out = intent.min_out_for(amount_in)?;
+
+    let spent = process_swap(
+        &mut ctx.accounts.swap,
+        ctx.remaining_accounts,
+        amount_in,
+        min_amount_out,
+        fee_lamports,
+    )?;
+
+    // ── Partial-fill accounting ─────────────────────────────────────────────
+    let intent = &mut ctx.accounts.intent;
+    intent.filled_amount = intent
+        .filled_amount
+        .checked_add(spent)
+        .ok_or(AgentError::Overflow)?;
+
+    msg!(
+        "Intent fill: id={}, amount_in={}, min_out={}, filled={}/{}",
+        intent.intent_id,
+        spent,
+        min_amount_out,
+        intent.filled_amount,
+        intent.max_amount_in,
+    );
+
+    if intent.is_filled() {
+        intent.close(ctx.accounts.owner.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FulfillIntent<'info> {
+    /// Full `execute_dlmm_swap` account set
+    pub swap: ExecuteDlmmSwap<'info>,
+
+    /// The owner-approved intent — closed to `owner` once fully filled
+    #[account(
+        mut,
+        seeds = [b"intent", swap.session.key().as_ref(), &intent.intent_id.to_le_bytes()],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, Intent>,
+
+    /// Session owner — receives the intent's rent when it closes
+    #[account(mut, address = swap.session.owner)]
+    pub owner: SystemAccount<'info>,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, FeeSponsor};
+
+/// [Base Layer] Deposit SOL into the session's fee sponsor.
+///
+/// Signed by the session owner. The lamports stay in the sponsor PDA until
+/// `refill_session_key` moves them to a device key or `close_fee_sponsor`
+/// returns them.
+pub fn handler(ctx: Context<FundFeeSponsor>, lamports: u64) -> Result<()> {
+    require!(lamports > 0, AgentError::InvalidSponsorAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.fee_sponsor.to_account_info(),
+            },
+        ),
+        lamports,
+    )?;
+
+    msg!(
+        "Fee sponsor funded: amount={}, balance={}",
+        lamports,
+        ctx.accounts.fee_sponsor.to_account_info().lamports()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundFeeSponsor<'info> {
+    /// The wallet owner of the session — source of the deposit
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_sponsor", session.key().as_ref()],
+        bump = fee_sponsor.bump,
+    )]
+    pub fee_sponsor: Account<'info, FeeSponsor>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::program::DefiAgent;
+use crate::state::{Config, FeeTier, CURRENT_CONFIG_VERSION, DEFAULT_ADMIN_TIMELOCK_SECS, DEFAULT_WITHDRAWAL_DELAY_SECS, MAX_FEE_TIERS, MAX_PROTOCOL_FEE_BPS, STRATEGY_ALL, WithdrawalWindow};
+use crate::errors::AgentError;
+
+/// [Base Layer] Create the program-wide Config PDA.
+///
+/// Can only be called once (the PDA has a fixed seed) and only by the program's
+/// upgrade authority, so a third party cannot front-run deployment and seize
+/// admin rights. The signer becomes the config `admin`.
+///
+/// Session ceilings start fully permissive; tighten them with
+/// `set_session_limits`.
+pub fn handler(
+    ctx: Context<InitializeConfig>,
+    protocol_fee_bps: u16,
+    default_max_lamports: u64,
+    default_duration_secs: i64,
+) -> Result<()> {
+    require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, AgentError::InvalidFeeBps);
+    require!(default_duration_secs > 0, AgentError::InvalidDuration);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.paused = false;
+    config.default_max_lamports = default_max_lamports;
+    config.default_duration_secs = default_duration_secs;
+    config.bump = ctx.bumps.config;
+    config.max_action_lamports = u64::MAX;
+    config.max_session_duration_secs = i64::MAX;
+    config.max_strategy_mask = STRATEGY_ALL;
+    config.pending_admin = Pubkey::default();
+    config.admin_transfer_available_at = 0;
+    config.admin_timelock_secs = DEFAULT_ADMIN_TIMELOCK_SECS;
+    config.referral_share_bps = 0;
+    config.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS]; // every tier free until set_fee_tier
+    config.dlmm_frozen = false;
+    config.version = CURRENT_CONFIG_VERSION;
+    config.withdrawal_delay_secs = DEFAULT_WITHDRAWAL_DELAY_SECS;
+    config.large_withdrawal_lamports = 0; // admin opts in via set_withdrawal_policy
+    config.min_dual_cpi_compute_units = 0; // admin opts in via set_compute_guard
+    config.magic_program = Pubkey::default(); // SDK addresses until set_magic_programs
+    config.magic_context = Pubkey::default();
+    config.fee_vault_withdrawal_window = WithdrawalWindow::default();
+
+    msg!(
+        "Config initialized: admin={}, protocol_fee_bps={}",
+        config.admin,
+        config.protocol_fee_bps,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// Program upgrade authority — becomes the config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Config PDA — created here
+    #[account(
+        init,
+        payer = admin,
+        space = Config::LEN,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// This program — used to locate its ProgramData account
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, DefiAgent>,
+
+    /// ProgramData of this program — proves `admin` is the upgrade authority
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ AgentError::UnauthorizedAdmin)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
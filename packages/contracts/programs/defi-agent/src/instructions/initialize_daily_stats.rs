@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, DailyStats};
+
+/// [Base Layer] Create the session's DailyStats PDA.
+///
+/// Signed by the session owner, who pays rent. Optional — execute handlers
+/// only tally per-day totals for sessions that have one.
+pub fn handler(ctx: Context<InitializeDailyStats>) -> Result<()> {
+    let mut stats = ctx.accounts.daily_stats.load_init()?;
+    stats.session = ctx.accounts.session.key();
+    stats.bump = ctx.bumps.daily_stats;
+
+    msg!("Daily stats initialized: session={}", stats.session);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeDailyStats<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = DailyStats::LEN,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump,
+    )]
+    pub daily_stats: AccountLoader<'info, DailyStats>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::spl_account_compression;
+use crate::state::{AgentSession, CompressedMonitorTree, MAX_MONITOR_TREE_DEPTH};
+use crate::errors::AgentError;
+
+/// [Base Layer] Create the session's compressed-monitor Merkle tree.
+///
+/// Signed by the session owner, who pays rent for the tree PDA and has
+/// already allocated `merkle_tree` — an empty account owned by the account
+/// compression program, `CompressedMonitorTree::merkle_tree_space` bytes for
+/// the chosen depth, buffer size and canopy. The tree PDA is set as the
+/// Merkle tree's authority, so only this program can append or replace
+/// leaves. `depth` and `max_buffer_size` must be a pair the account
+/// compression program supports.
+pub fn handler(
+    ctx: Context<InitializeMonitorTree>,
+    depth: u8,
+    max_buffer_size: u32,
+) -> Result<()> {
+    require!(
+        depth > 0 && depth <= MAX_MONITOR_TREE_DEPTH,
+        AgentError::InvalidMerkleProof
+    );
+
+    let session_key = ctx.accounts.session.key();
+    let tree = &mut ctx.accounts.tree;
+    tree.session = session_key;
+    tree.merkle_tree = ctx.accounts.merkle_tree.key();
+    tree.depth = depth;
+    tree.leaf_count = 0;
+    tree.bump = ctx.bumps.tree;
+
+    let seeds: &[&[u8]] = &[b"monitor_tree", session_key.as_ref(), &[tree.bump]];
+    spl_account_compression::cpi::init_empty_merkle_tree(
+        CpiContext::new_with_signer(
+            ctx.accounts.compression_program.to_account_info(),
+            spl_account_compression::cpi::accounts::InitEmptyMerkleTree {
+                merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+                authority: tree.to_account_info(),
+                noop: ctx.accounts.noop_program.to_account_info(),
+            },
+            &[seeds],
+        ),
+        depth as u32,
+        max_buffer_size,
+    )?;
+
+    msg!(
+        "Monitor tree initialized: depth={}, buffer={}, capacity={}",
+        depth,
+        max_buffer_size,
+        1u64 << depth
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeMonitorTree<'info> {
+    /// The wallet owner of the session — must sign and pay for the PDA rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// CompressedMonitorTree PDA — created here; authority of `merkle_tree`
+    #[account(
+        init,
+        payer = owner,
+        space = CompressedMonitorTree::LEN,
+        seeds = [b"monitor_tree", session.key().as_ref()],
+        bump,
+    )]
+    pub tree: Account<'info, CompressedMonitorTree>,
+
+    #[account(mut, owner = spl_account_compression::ID)]
+    /// CHECK: Pre-allocated ConcurrentMerkleTree account — initialized by the CPI
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(address = spl_account_compression::ID)]
+    /// CHECK: SPL account compression program
+    pub compression_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::SPL_NOOP_ID)]
+    /// CHECK: SPL noop program — the compression program logs changelogs through it
+    pub noop_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, PoolRegistry};
+use crate::errors::AgentError;
+
+/// [Base Layer] Create the empty global PoolRegistry PDA.
+///
+/// Admin-only, called once per deployment. Pools are then curated with
+/// `add_registry_pool` / `remove_registry_pool`.
+pub fn handler(ctx: Context<InitializePoolRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.pool_registry;
+    registry.pools = Vec::new();
+    registry.bump = ctx.bumps.pool_registry;
+
+    msg!("Pool registry initialized");
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePoolRegistry<'info> {
+    /// Config admin — must sign and pays for the PDA rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// PoolRegistry PDA — created here
+    #[account(
+        init,
+        payer = admin,
+        space = PoolRegistry::LEN,
+        seeds = [b"pool_registry"],
+        bump,
+    )]
+    pub pool_registry: Account<'info, PoolRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
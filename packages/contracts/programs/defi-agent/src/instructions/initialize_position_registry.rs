@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, PositionRegistry};
+
+/// [Base Layer] Create the session's PositionRegistry PDA.
+///
+/// Signed by the session owner, who pays rent. Optional — otherwise the first
+/// `execute_dlmm_create_position` creates it at the session key's expense.
+pub fn handler(ctx: Context<InitializePositionRegistry>) -> Result<()> {
+    let mut registry = ctx.accounts.position_registry.load_init()?;
+    registry.session = ctx.accounts.session.key();
+    registry.bump = ctx.bumps.position_registry;
+
+    msg!("Position registry initialized: session={}", registry.session);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePositionRegistry<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = PositionRegistry::LEN,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump,
+    )]
+    pub position_registry: AccountLoader<'info, PositionRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
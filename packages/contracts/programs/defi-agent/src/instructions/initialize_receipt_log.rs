@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{ActionReceiptLog, AgentSession};
+
+/// [Base Layer] Create the session's ActionReceiptLog PDA.
+///
+/// Signed by the session owner, who pays rent. Optional, but once created
+/// it is recorded on the session and `execute_action` and
+/// `execute_actions_batch` fail without it, so a device can't skip its
+/// receipts. Delegate it with `delegate_receipt_log` before `delegate_session`
+/// so the ER can write to it.
+pub fn handler(ctx: Context<InitializeReceiptLog>) -> Result<()> {
+    let mut log = ctx.accounts.receipt_log.load_init()?;
+    log.session = ctx.accounts.session.key();
+    log.bump = ctx.bumps.receipt_log;
+    ctx.accounts.session.receipt_log = ctx.accounts.receipt_log.key();
+
+    msg!("Receipt log initialized: session={}", log.session);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeReceiptLog<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ActionReceiptLog::LEN,
+        seeds = [b"receipts", session.key().as_ref()],
+        bump,
+    )]
+    pub receipt_log: AccountLoader<'info, ActionReceiptLog>,
+
+    pub system_program: Program<'info, System>,
+}
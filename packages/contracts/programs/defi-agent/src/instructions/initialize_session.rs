@@ -1,38 +1,117 @@
 use anchor_lang::prelude::*;
-use crate::state::AgentSession;
+use crate::state::{
+    AgentSession, AlertConfig, Config, FeeTier, PoolRegistry, SessionLookup,
+    DELEGATION_UNDELEGATED, MAX_FEE_TIERS, MAX_METADATA_URI_LEN, MAX_SESSION_DURATION_SECS,
+    STRATEGY_ALL, STRATEGY_COUNT, STRATEGY_DLMM_OPS,
+};
+use crate::errors::AgentError;
+use crate::events::DeviceEnrolled;
+use crate::valuation::{self, VALUATION_SESSION_START};
 
 /// Creates a new AgentSession PDA on the BASE LAYER.
 ///
 /// The owner specifies:
-/// - which ESP32 session key is authorized to sign actions
+/// - which ESP32 session key is authorized to sign actions (device slot 0;
+///   more devices can be enrolled later with `add_device`)
 /// - how long the session lasts (duration_secs)
 /// - maximum cumulative lamport exposure
-/// - which DeFi strategies are enabled (strategy_mask bitmask)
+/// - which DeFi strategies are enabled (strategy_mask bitmask), optionally
+///   narrowing STRATEGY_LP to specific DLMM operations (STRATEGY_DLMM_*)
+///
+/// - the largest single action the agent may take (max_action_lamports)
+/// - an optional integrator `referrer` that earns a share of protocol fees
+/// - which admin-approved per-action fee tier the session is billed under
+/// - the attestation hash of the session-key device (all-zero if unattested)
+///
+/// Passing 0 for `duration_secs` or `max_lamports` falls back to the
+/// deployment defaults stored in the global Config; passing 0 for
+/// `max_action_lamports` adopts the protocol per-action ceiling. The owner may
+/// always request tighter limits than the Config ceilings, never looser ones.
+///
+/// Independently of Config, the duration must be positive and at most
+/// `MAX_SESSION_DURATION_SECS`, the strategy mask may only set known bits,
+/// the exposure cap must be non-zero and the session key must not be the
+/// owner's own key.
+///
+/// The session key's `SessionLookup` entry is pointed at the new session so
+/// the device can find it from its key alone. An entry naming another session
+/// is only repointed once `previous_session` no longer lists the key.
+///
+/// Passing the `valuation_*` accounts (a DLMM pool and the session key's
+/// token accounts for its two mints) records the session's entry portfolio
+/// value, the baseline its realized PnL is measured from. Without them the
+/// first close valuation becomes the baseline.
 pub fn handler(
     ctx: Context<InitializeSession>,
     session_key: Pubkey,
     duration_secs: i64,
     max_lamports: u64,
     strategy_mask: u8,
+    max_action_lamports: u64,
+    referrer: Option<Pubkey>,
+    fee_tier: u8,
+    attestation_hash: [u8; 32],
 ) -> Result<()> {
     let clock = Clock::get()?;
+    let config = &ctx.accounts.config;
+
+    let (duration_secs, max_lamports, max_action_lamports) =
+        resolve_limits(config, duration_secs, max_lamports, max_action_lamports)?;
+
+    // ── Parameter sanity ────────────────────────────────────────────────────
+    require!((fee_tier as usize) < MAX_FEE_TIERS, AgentError::InvalidFeeTier);
+    let tier = config.fee_tiers[fee_tier as usize];
+
+    // ── Protocol ceilings ───────────────────────────────────────────────────
+    require!(
+        // DLMM operation bits only narrow STRATEGY_LP, so the ceiling ignores them
+        strategy_mask & STRATEGY_ALL & !config.max_strategy_mask == 0,
+        AgentError::SessionLimitExceeded
+    );
+
+    let terms = SessionTerms {
+        owner: ctx.accounts.owner.key(),
+        session_key,
+        attestation_hash,
+        duration_secs,
+        max_lamports,
+        max_action_lamports,
+        strategy_mask,
+        referrer: referrer.unwrap_or_default(),
+        fee_tier: tier,
+        bump: ctx.bumps.session,
+    };
     let session = &mut ctx.accounts.session;
+    open_session(session, &terms, clock.unix_timestamp)?;
 
-    session.owner = ctx.accounts.owner.key();
-    session.session_key = session_key;
-    session.expires_at = clock.unix_timestamp + duration_secs;
-    session.max_lamports = max_lamports;
-    session.spent_lamports = 0;
-    session.is_active = true;
-    session.bump = ctx.bumps.session;
-    session.strategy_mask = strategy_mask;
-    session.total_actions = 0;
-    session.last_action_at = clock.unix_timestamp;
+    ctx.accounts.session_lookup.set(
+        session_key,
+        session.key(),
+        ctx.bumps.session_lookup,
+        ctx.accounts.previous_session.as_deref(),
+    )?;
+
+    valuation::record_optional(
+        session,
+        ctx.accounts.pool_registry.as_deref(),
+        ctx.accounts.valuation_lb_pair.as_ref(),
+        ctx.accounts.valuation_token_x.as_ref(),
+        ctx.accounts.valuation_token_y.as_ref(),
+        VALUATION_SESSION_START,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(DeviceEnrolled {
+        session: session.key(),
+        device_key: session_key,
+        slot: 0,
+        attestation_hash,
+    });
 
     msg!(
         "Session initialized: owner={}, session_key={}, expires_at={}, max_lamports={}",
         session.owner,
-        session.session_key,
+        session_key,
         session.expires_at,
         session.max_lamports,
     );
@@ -40,7 +119,157 @@ pub fn handler(
     Ok(())
 }
 
+/// Term of a new session: the resolved limits (see `resolve_limits`), the
+/// first device and the fee tier it is billed under
+pub struct SessionTerms {
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub attestation_hash: [u8; 32],
+    pub duration_secs: i64,
+    pub max_lamports: u64,
+    pub max_action_lamports: u64,
+    pub strategy_mask: u8,
+    pub referrer: Pubkey,
+    pub fee_tier: FeeTier,
+    pub bump: u8,
+}
+
+/// Write a fresh session from `terms` at `now`, after the Config-independent
+/// checks: known strategy bits and a session key that isn't the owner's.
+/// Shared with the simulation, which starts every scenario here.
+pub fn open_session(session: &mut AgentSession, terms: &SessionTerms, now: i64) -> Result<()> {
+    require!(
+        terms.strategy_mask & !(STRATEGY_ALL | STRATEGY_DLMM_OPS) == 0,
+        AgentError::UnknownStrategyBits
+    );
+    // An owner-held session key would defeat the point of a scoped device key
+    require!(terms.session_key != terms.owner, AgentError::SessionKeyIsOwner);
+
+    session.owner = terms.owner;
+    session.devices = Default::default();
+    session.enroll_device(terms.session_key, terms.attestation_hash, now)?;
+    session.expires_at = now
+        .checked_add(terms.duration_secs)
+        .ok_or(AgentError::Overflow)?;
+    session.max_lamports = terms.max_lamports;
+    session.spent_lamports = 0;
+    session.is_active = true;
+    session.bump = terms.bump;
+    session.strategy_mask = terms.strategy_mask;
+    session.total_actions = 0;
+    session.last_action_at = now;
+    session.fee_budget_lamports = 0; // uncapped until set_fee_budget
+    session.fee_spent_lamports = 0;
+    session.registry_only = false;
+    session.max_action_lamports = terms.max_action_lamports;
+    session.referrer = terms.referrer;
+    session.fee_mode = terms.fee_tier.fee_mode;
+    session.fee_amount = terms.fee_tier.amount;
+    session.protocol_fees_paid = 0;
+    session.cosign_above_lamports = 0; // owner opts in via set_cosign_threshold
+    session.bound_positions = Default::default(); // unbound until set_bound_positions
+    session.quiet_logs = false;
+    session.lookup_table = Pubkey::default(); // created via create_session_lookup_table
+    session.emptied_position = Pubkey::default();
+    session.clock_high_water = now;
+    session.delegation_status = DELEGATION_UNDELEGATED;
+    session.valuation_mint = Pubkey::default(); // fixed by the first valuation
+    session.entry_value = 0;
+    session.last_value = 0;
+    session.realized_pnl = 0;
+    session.last_valued_at = 0;
+    session.total_fees_earned_x = 0;
+    session.total_fees_earned_y = 0;
+    session.spent_by_strategy = [0; STRATEGY_COUNT];
+    session.snapshot_seq = 0;
+    session.current_exposure_value = 0;
+    session.exposure_valued_at = 0;
+    session.mark_to_market = false;
+    session.tx_fees_lamports = 0;
+    session.tx_fee_budget_lamports = 0; // uncapped until set_fee_budget
+    session.slippage_paid = 0;
+    session.max_slippage_bps = 0; // no alerts until set_slippage_alert
+    session.needs_review = false;
+    session.violation_threshold = 0; // freeze off until set_violation_freeze
+    session.consecutive_violations = 0;
+    session.suspended = false;
+    session.exposure_cooldown_secs = 0; // no cooldown until set_exposure_cooldown
+    session.exposure_cooldown_until = 0;
+    session.alert_config = AlertConfig::default(); // alerts off until set_alert_config
+    session.alerts_raised = 0;
+    session.label = [0; 32]; // unnamed until set_session_metadata
+    session.metadata_uri = [0; MAX_METADATA_URI_LEN];
+    session.fee_payer = Pubkey::default(); // devices pay their own fees until set_fee_payer
+    session.liquidity_shapes = 0; // any shape until set_liquidity_shapes
+    session.pair_pool_lists = 0; // any pool until set_pair_pools
+    session.swap_fees_paid = 0;
+    session.swap_fee_budget = 0; // uncapped until set_swap_fee_budget
+    session.max_uncommitted_actions = 0; // no commit cadence until set_commit_cadence
+    session.max_uncommitted_secs = 0;
+    session.uncommitted_actions = 0;
+    session.last_committed_at = 0;
+    session.max_idle_slots = 0; // no idle rule until set_max_idle_slots
+    session.er_last_action_slot = 0;
+    session.checkpoint_count = 0;
+    session.budget_mint = Pubkey::default(); // raw amounts until set_budget_mint
+    session.exposure_mark = 0;
+    session.receipt_log = Pubkey::default(); // set by initialize_receipt_log
+    session.budget_price_pools = Default::default(); // none until set_budget_price_pool
+    Ok(())
+}
+
+/// Apply the Config defaults to zero-valued term limits, then check them
+/// against the hard bounds and the Config ceilings. Returns the resolved
+/// (duration_secs, max_lamports, max_action_lamports). Shared with
+/// `renew_session`.
+pub(crate) fn resolve_limits(
+    config: &Config,
+    duration_secs: i64,
+    max_lamports: u64,
+    max_action_lamports: u64,
+) -> Result<(i64, u64, u64)> {
+    let duration_secs = if duration_secs == 0 {
+        config.default_duration_secs
+    } else {
+        duration_secs
+    };
+    let max_lamports = if max_lamports == 0 {
+        config.default_max_lamports
+    } else {
+        max_lamports
+    };
+    let max_action_lamports = if max_action_lamports == 0 {
+        config.max_action_lamports
+    } else {
+        max_action_lamports
+    };
+
+    check_limits(duration_secs, max_lamports)?;
+    require!(
+        duration_secs <= config.max_session_duration_secs,
+        AgentError::SessionLimitExceeded
+    );
+    require!(
+        max_action_lamports <= config.max_action_lamports,
+        AgentError::SessionLimitExceeded
+    );
+    Ok((duration_secs, max_lamports, max_action_lamports))
+}
+
+/// The hard bounds on a term, whatever the Config says: a positive duration
+/// of at most `MAX_SESSION_DURATION_SECS` and a non-zero exposure cap
+pub fn check_limits(duration_secs: i64, max_lamports: u64) -> Result<()> {
+    require!(duration_secs > 0, AgentError::InvalidDuration);
+    require!(
+        duration_secs <= MAX_SESSION_DURATION_SECS,
+        AgentError::DurationTooLong
+    );
+    require!(max_lamports > 0, AgentError::ZeroExposureCap);
+    Ok(())
+}
+
 #[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
 pub struct InitializeSession<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
@@ -54,5 +283,36 @@ pub struct InitializeSession<'info> {
     )]
     pub session: Account<'info, AgentSession>,
 
+    /// Reverse lookup from `session_key` to this session
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = SessionLookup::LEN,
+        seeds = [b"session_lookup", session_key.as_ref()],
+        bump,
+    )]
+    pub session_lookup: Account<'info, SessionLookup>,
+
+    /// CHECK: The session `session_lookup` currently names, when it names
+    /// another one — checked in `SessionLookup::set`
+    pub previous_session: Option<UncheckedAccount<'info>>,
+
+    /// Global Config PDA — supplies default limits and protocol ceilings
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     pub system_program: Program<'info, System>,
+
+    /// CHECK: Optional DLMM pool pricing the entry valuation (loaded as LbPair)
+    pub valuation_lb_pair: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional session-key token account for the pool's X mint
+    pub valuation_token_x: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional session-key token account for the pool's Y mint
+    pub valuation_token_y: Option<UncheckedAccount<'info>>,
+
+    /// Global PoolRegistry — vouches for a registered `valuation_lb_pair`
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
 }
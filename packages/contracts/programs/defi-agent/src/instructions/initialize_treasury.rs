@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Config, TreasuryLedger, WithdrawalWindow};
+use crate::errors::AgentError;
+
+/// [Base Layer] Create the protocol treasury for a token mint.
+///
+/// Admin-only. Creates the treasury token account `[b"treasury", mint]`
+/// (authority = Config PDA) and its `TreasuryLedger`. Execute instructions
+/// skim `protocol_fee_bps` into this account for any input of `mint`.
+pub fn handler(ctx: Context<InitializeTreasury>) -> Result<()> {
+    let ledger = &mut ctx.accounts.treasury_ledger;
+    ledger.mint = ctx.accounts.mint.key();
+    ledger.vault = ctx.accounts.treasury.key();
+    ledger.total_accrued = 0;
+    ledger.total_withdrawn = 0;
+    ledger.bump = ctx.bumps.treasury_ledger;
+    ledger.vault_bump = ctx.bumps.treasury;
+    ledger.total_referral_paid = 0;
+    ledger.large_withdrawal_amount = 0; // admin opts in via set_treasury_withdrawal_threshold
+    ledger.withdrawal_window = WithdrawalWindow::default();
+
+    msg!(
+        "Treasury initialized: mint={}, vault={}",
+        ledger.mint,
+        ledger.vault,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    /// Config admin — must sign and pays for both accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — becomes the treasury token account authority
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Mint the treasury collects fees in
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Per-mint fee ledger — created here
+    #[account(
+        init,
+        payer = admin,
+        space = TreasuryLedger::LEN,
+        seeds = [b"treasury_ledger", mint.key().as_ref()],
+        bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+
+    /// Treasury token account — created here, owned by the Config PDA
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = config,
+        token::token_program = token_program,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
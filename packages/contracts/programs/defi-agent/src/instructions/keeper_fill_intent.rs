@@ -0,0 +1,350 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::alerts;
+use crate::balances;
+use crate::dlmm;
+use crate::errors::AgentError;
+use crate::events::{ActionExecuted, SwapSettled};
+use crate::introspection::{signature_fee_lamports, verify_declared_fee};
+use crate::log_info;
+use crate::state::{
+    ActionKind, AgentSession, Config, DailyStats, Intent, PoolRegistry, TemporalSource,
+    ACTION_DLMM_SWAP, REASON_UNSPECIFIED,
+};
+use crate::valuation::{
+    pool_fee_rate, pool_price, pool_price_q64, quote_out, record_pool_fee, record_slippage,
+    swap_budget_value, swap_fee,
+};
+
+/// [Base Layer] Fill a keeper-fillable intent on the device's behalf.
+///
+/// Signed by any keeper. The device must have approved the intent PDA as SPL
+/// delegate on its input token account (for at least the unfilled amount)
+/// while it was online; the program then signs the DLMM swap as that delegate.
+/// Output must land in a token account owned by the same device, so the
+/// keeper can trigger the trade but never redirect funds.
+///
+/// The same intent bounds as `fulfill_intent` apply, plus the session scope
+/// (active, not expired, swaps permitted by the strategy mask, registry,
+/// per-action and exposure caps, charged to the device whose tokens move —
+/// which must still be enabled and unexpired). Exposure and the fill are
+/// recorded at the observed balance deltas, and slippage against the pool's
+/// pre-trade quote is tallied, as in `execute_dlmm_swap`. The
+/// keeper is paid the pro-rata share of the escrowed bounty; the intent closes
+/// to the owner once filled.
+/// Keeper fills are not billed per-action or protocol fees — the bounty is
+/// the owner's cost of the fallback. Operational fees are accounted as in
+/// `execute_dlmm_swap`: `fee_lamports` must cover the priority fee and tips
+/// the transaction commits the keeper to, and it and the signature fee count
+/// against the session's fee and transaction-fee budgets. The bounty share is
+/// only paid while the intent stays rent-exempt after it. A session
+/// `budget_mint` values the fill as `execute_dlmm_swap` does, through the
+/// owner's pinned `budget_price_pool` when needed.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, KeeperFillIntent<'info>>,
+    amount_in: u64,
+    fee_lamports: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let intent = &ctx.accounts.intent;
+
+    // ── Intent bounds ───────────────────────────────────────────────────────
+    require!(intent.keeper_fillable, AgentError::IntentNotKeeperFillable);
+    require!(clock.unix_timestamp < intent.deadline, AgentError::IntentExpired);
+    require!(
+        amount_in > 0 && amount_in <= intent.remaining(),
+        AgentError::IntentBoundsExceeded
+    );
+    require_keys_eq!(ctx.accounts.lb_pair.key(), intent.lb_pair, AgentError::IntentMismatch);
+    let input_mint = if intent.swap_for_y {
+        ctx.accounts.token_x_mint.key()
+    } else {
+        ctx.accounts.token_y_mint.key()
+    };
+    require_keys_eq!(ctx.accounts.user_token_in.mint, input_mint, AgentError::IntentMismatch);
+    let min_amount_out = intent.min_out_for(amount_in)?;
+    let bounty = intent.bounty_for(amount_in);
+
+    // ── Session scope, charged to the device that owns the input tokens ─────
+    let session = &mut ctx.accounts.session;
+    let device_slot = session.validate_session(
+        &ctx.accounts.user_token_in.owner,
+        ActionKind::Action(ACTION_DLMM_SWAP),
+        TemporalSource::Passive(clock.unix_timestamp),
+    )?;
+    session.validate_pool(
+        ctx.accounts.pool_registry.as_deref(),
+        &ctx.accounts.lb_pair.key(),
+    )?;
+    let price = pool_price(&ctx.accounts.lb_pair.to_account_info())?;
+    let fee_price = pool_price_q64(&ctx.accounts.lb_pair.to_account_info())?;
+    let fee_rate = pool_fee_rate(&ctx.accounts.lb_pair.to_account_info())?;
+    let output_mint = ctx.accounts.user_token_out.mint;
+    let notional = swap_budget_value(
+        session,
+        &input_mint,
+        amount_in,
+        &output_mint,
+        quote_out(price, intent.swap_for_y, amount_in),
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+    session.check_action_cap(notional)?;
+    session.check_exposure(device_slot, notional)?;
+    session.check_min_trade(&input_mint, amount_in)?;
+    verify_declared_fee(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.keeper.key(),
+        fee_lamports,
+    )?;
+    let tx_fee = signature_fee_lamports(&ctx.accounts.instructions_sysvar)?;
+    session.record_fee_spend(fee_lamports)?;
+    session.record_tx_fee(tx_fee)?;
+
+    let in_before = ctx.accounts.user_token_in.amount;
+    let out_before = ctx.accounts.user_token_out.amount;
+
+    // ── CPI to Meteora DLMM swap, signed by the intent PDA as delegate ──────
+    let session_key = session.key();
+    let id_bytes = ctx.accounts.intent.intent_id.to_le_bytes();
+    let bump_bytes = [ctx.accounts.intent.bump];
+    let signer_seeds: &[&[&[u8]]] =
+        &[&[b"intent", session_key.as_ref(), &id_bytes, &bump_bytes]];
+
+    let cpi_accounts = dlmm::cpi::accounts::Swap {
+        lb_pair: ctx.accounts.lb_pair.to_account_info(),
+        bin_array_bitmap_extension: ctx
+            .accounts
+            .bin_array_bitmap_extension
+            .as_ref()
+            .map(|a| a.to_account_info()),
+        reserve_x: ctx.accounts.reserve_x.to_account_info(),
+        reserve_y: ctx.accounts.reserve_y.to_account_info(),
+        user_token_in: ctx.accounts.user_token_in.to_account_info(),
+        user_token_out: ctx.accounts.user_token_out.to_account_info(),
+        token_x_mint: ctx.accounts.token_x_mint.to_account_info(),
+        token_y_mint: ctx.accounts.token_y_mint.to_account_info(),
+        oracle: ctx.accounts.oracle.to_account_info(),
+        host_fee_in: None,
+        user: ctx.accounts.intent.to_account_info(),
+        token_x_program: ctx.accounts.token_x_program.to_account_info(),
+        token_y_program: ctx.accounts.token_y_program.to_account_info(),
+        event_authority: ctx.accounts.event_authority.to_account_info(),
+        program: ctx.accounts.dlmm_program.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.dlmm_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    )
+    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    dlmm::cpi::swap(cpi_ctx, amount_in, min_amount_out)?;
+
+    // ── Settle on observed balance deltas ───────────────────────────────────
+    let spent = balances::outflow(&ctx.accounts.user_token_in.to_account_info(), in_before)?;
+    let received = balances::inflow(&ctx.accounts.user_token_out.to_account_info(), out_before)?;
+    require!(spent <= amount_in, AgentError::BalanceDeltaExceeded);
+    require!(received >= min_amount_out, AgentError::OutputBelowMinimum);
+    let pool_fee = swap_fee(spent, fee_rate);
+    let expected_out = quote_out(price, intent.swap_for_y, spent - pool_fee);
+
+    // ── Update session accounting ───────────────────────────────────────────
+    let session = &mut ctx.accounts.session;
+    let charged = swap_budget_value(
+        session,
+        &input_mint,
+        spent,
+        &output_mint,
+        received,
+        ctx.accounts.budget_price_pool.as_deref(),
+    )?;
+    session.apply_spend(device_slot, ACTION_DLMM_SWAP, charged)?;
+    session.bump_actions()?;
+    alerts::check_thresholds(session);
+    session.last_action_at = clock.unix_timestamp;
+    if let Some(stats) = &ctx.accounts.daily_stats {
+        stats.load_mut()?.record_action(clock.unix_timestamp, charged, 0);
+    }
+    let lb_pair = ctx.accounts.lb_pair.key();
+    let pool_fee = record_pool_fee(session, fee_price, intent.swap_for_y, pool_fee)?;
+    record_slippage(session, lb_pair, price, !intent.swap_for_y, expected_out, received);
+
+    emit!(ActionExecuted {
+        session: session.key(),
+        action_type: ACTION_DLMM_SWAP,
+        amount: spent,
+        total_actions: session.total_actions,
+        reason: REASON_UNSPECIFIED,
+    });
+
+    // ── Pay the keeper and record the fill ──────────────────────────────────
+    let intent_info = ctx.accounts.intent.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(intent_info.data_len());
+    let escrow_left = intent_info
+        .lamports()
+        .checked_sub(bounty)
+        .filter(|left| *left >= rent_floor)
+        .ok_or(AgentError::IntentBountyUnfunded)?;
+    let keeper_info = ctx.accounts.keeper.to_account_info();
+    let keeper_lamports = keeper_info.lamports().checked_add(bounty).ok_or(AgentError::Overflow)?;
+    **intent_info.try_borrow_mut_lamports()? = escrow_left;
+    **keeper_info.try_borrow_mut_lamports()? = keeper_lamports;
+
+    let intent = &mut ctx.accounts.intent;
+    intent.filled_amount = intent
+        .filled_amount
+        .checked_add(spent)
+        .ok_or(AgentError::Overflow)?;
+
+    emit!(SwapSettled {
+        session: session.key(),
+        lb_pair: ctx.accounts.lb_pair.key(),
+        amount_in: spent,
+        amount_out: received,
+        min_amount_out,
+        protocol_fee: 0,
+        expected_out,
+        pool_fee,
+    });
+
+    log_info!(
+        session,
+        "Intent keeper fill: id={}, keeper={}, amount_in={}, bounty={}, filled={}/{}",
+        intent.intent_id,
+        ctx.accounts.keeper.key(),
+        spent,
+        bounty,
+        intent.filled_amount,
+        intent.max_amount_in,
+    );
+
+    if intent.is_filled() {
+        intent.close(ctx.accounts.owner.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct KeeperFillIntent<'info> {
+    /// Any keeper — signs and receives the bounty share
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// Scoped session PDA — validated and updated here
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The keeper-fillable intent — closed to `owner` once fully filled
+    #[account(
+        mut,
+        seeds = [b"intent", session.key().as_ref(), &intent.intent_id.to_le_bytes()],
+        bump = intent.bump,
+    )]
+    pub intent: Account<'info, Intent>,
+
+    /// Session owner — receives the intent's rent and unpaid bounty when it closes
+    #[account(mut, address = session.owner)]
+    pub owner: SystemAccount<'info>,
+
+    /// Global Config PDA — execution is rejected while paused or DLMM-frozen
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ AgentError::ProtocolPaused,
+        constraint = !config.dlmm_frozen @ AgentError::DlmmFrozen,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Global PoolRegistry — required when the session is in registry-only mode
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    // ── Meteora DLMM accounts ────────────────────────────────────────────────
+
+    #[account(mut)]
+    /// CHECK: Meteora DLMM LbPair account — validated against the intent
+    pub lb_pair: UncheckedAccount<'info>,
+
+    /// CHECK: Optional bin array bitmap extension (pass if pool uses extended bitmap)
+    pub bin_array_bitmap_extension: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    /// CHECK: Token X reserve account of the pool
+    pub reserve_x: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Token Y reserve account of the pool
+    pub reserve_y: UncheckedAccount<'info>,
+
+    /// Device's input token account — intent PDA must be its approved delegate
+    #[account(
+        mut,
+        constraint = user_token_in.delegate == COption::Some(intent.key())
+            @ AgentError::IntentNotDelegated,
+    )]
+    pub user_token_in: InterfaceAccount<'info, TokenAccount>,
+
+    /// Device's output token account — must belong to the same device
+    #[account(
+        mut,
+        constraint = user_token_out.owner == user_token_in.owner @ AgentError::IntentMismatch,
+    )]
+    pub user_token_out: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token X mint
+    pub token_x_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Y mint
+    pub token_y_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    /// CHECK: Oracle account for the pool
+    pub oracle: UncheckedAccount<'info>,
+
+    #[account(address = dlmm::ID)]
+    /// CHECK: Meteora DLMM program
+    pub dlmm_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::DLMM_EVENT_AUTHORITY)]
+    /// CHECK: DLMM CPI event authority (PDA of DLMM program)
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// Token program for token X (SPL Token or Token-2022) — must own `token_x_mint`
+    #[account(
+        constraint = token_x_program.key() == *token_x_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_x_program: Interface<'info, TokenInterface>,
+
+    /// Token program for token Y (SPL Token or Token-2022) — must own `token_y_mint`
+    #[account(
+        constraint = token_y_program.key() == *token_y_mint.to_account_info().owner
+            @ AgentError::TokenProgramMismatch,
+    )]
+    pub token_y_program: Interface<'info, TokenInterface>,
+
+    /// The session's DailyStats — when passed, the action is tallied into
+    /// today's bucket
+    #[account(
+        mut,
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+
+    /// CHECK: the owner's pinned budget price pool for the input mint —
+    /// values the input when neither swap leg is the budget mint
+    pub budget_price_pool: Option<UncheckedAccount<'info>>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Instructions sysvar — read to verify the declared priority fee / tips
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    // Bin arrays → ctx.remaining_accounts (1–2 accounts, fetched via SDK)
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{Config, CURRENT_CONFIG_VERSION, DEFAULT_WITHDRAWAL_DELAY_SECS};
+use crate::errors::AgentError;
+
+/// [Base Layer] Upgrade the Config account to the layout of this build.
+///
+/// Admin-only. Older Config accounts are shorter than `Config::LEN` and can't
+/// be deserialized as the current struct, so the account is taken unchecked:
+/// the discriminator and admin are verified from raw bytes, the account is
+/// grown to `Config::LEN` (admin tops up rent), and the zero-filled tail is
+/// then deserialized as the new layout. Per-version defaults for new fields
+/// are applied below before `version` is stamped.
+pub fn handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let info = ctx.accounts.config.to_account_info();
+
+    // ── Verify account identity + admin from raw bytes ────────────────────
+    {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 + 32 && &data[..8] == Config::DISCRIMINATOR,
+            AgentError::InvalidConfigAccount
+        );
+        let admin = Pubkey::try_from(&data[8..40]).map_err(|_| AgentError::InvalidConfigAccount)?;
+        require_keys_eq!(admin, ctx.accounts.admin.key(), AgentError::UnauthorizedAdmin);
+    }
+
+    // ── Grow to the current layout, topping up rent from the admin ────────
+    let old_len = info.data_len();
+    if old_len < Config::LEN {
+        let required = Rent::get()?.minimum_balance(Config::LEN);
+        let shortfall = required.saturating_sub(info.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        info.resize(Config::LEN)?;
+    }
+
+    // ── Apply per-version defaults and stamp the version ──────────────────
+    let mut data = info.try_borrow_mut_data()?;
+    let mut config = Config::try_deserialize(&mut &data[..])?;
+    let from_version = config.version;
+    require!(from_version < CURRENT_CONFIG_VERSION, AgentError::ConfigUpToDate);
+
+    // v0 → v1: `version` itself was introduced; no other fields to backfill.
+    // v1 → v2: withdrawal time lock; threshold stays 0 (disabled) until the admin opts in.
+    if from_version < 2 {
+        config.withdrawal_delay_secs = DEFAULT_WITHDRAWAL_DELAY_SECS;
+    }
+    // v2 → v3: compute-budget guard; stays 0 (disabled) until the admin opts in.
+    // v3 → v4: magic program/context overrides; zero-filled = the SDK's addresses.
+    // v4 → v5: fee-vault withdrawal window; zero-filled = no window open yet.
+    config.version = CURRENT_CONFIG_VERSION;
+    config.try_serialize(&mut &mut data[..])?;
+
+    msg!(
+        "Config migrated: version {} -> {}, len {} -> {}",
+        from_version,
+        config.version,
+        old_len,
+        Config::LEN,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Config admin — must sign and funds any extra rent
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump, owner = crate::ID)]
+    /// CHECK: Deserialized manually — older layouts don't fit the current `Config`
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{
+    AgentSession, LpPositionMonitor, ManagedPosition, PositionRegistry, MAX_MANAGED_POSITIONS,
+};
+use crate::errors::AgentError;
+
+/// `LpPositionMonitor` as borsh-serialized before it became zero-copy
+#[derive(AnchorDeserialize)]
+struct LegacyLpPositionMonitor {
+    session: Pubkey,
+    lb_pair: Pubkey,
+    position: Pubkey,
+    min_bin_id: i32,
+    max_bin_id: i32,
+    last_active_bin: i32,
+    is_in_range: bool,
+    fee_x_snapshot: u64,
+    fee_y_snapshot: u64,
+    last_checked_at: i64,
+    bump: u8,
+}
+
+/// `ManagedPosition` as borsh-serialized before the registry became zero-copy
+#[derive(AnchorDeserialize)]
+struct LegacyManagedPosition {
+    position: Pubkey,
+    lb_pair: Pubkey,
+    created_at: i64,
+}
+
+/// `PositionRegistry` as borsh-serialized before it became zero-copy
+#[derive(AnchorDeserialize)]
+struct LegacyPositionRegistry {
+    session: Pubkey,
+    positions: Vec<LegacyManagedPosition>,
+    bump: u8,
+}
+
+/// [Base Layer] Rewrite a session's LpPositionMonitor and/or PositionRegistry
+/// from their borsh layout to the zero-copy layout of this build.
+///
+/// Signed by the session owner, who funds any extra rent. Accounts created
+/// before the zero-copy switch can't be loaded by `AccountLoader` (different
+/// field order, `is_in_range` was a bool, the registry held a Vec), so each
+/// passed account is decoded from its legacy bytes, grown to the current
+/// `LEN` and written back field by field. Fields the legacy layout lacked
+/// start at zero: the monitor's token snapshots and out-of-range clock fill
+/// in at the next `update_lp_status`; registry entries carry no deposits or
+/// cost basis, so PnL for migrated positions counts from later deposits only.
+/// Passing an account that already has the current layout fails.
+///
+/// Owners who would rather not migrate can close the monitor (or the
+/// positions it tracks) and register it again with `register_lp_monitor`.
+pub fn handler(ctx: Context<MigrateLpAccounts>) -> Result<()> {
+    let session = ctx.accounts.session.key();
+    let payer = ctx.accounts.owner.to_account_info();
+    let system = ctx.accounts.system_program.to_account_info();
+
+    if let Some(monitor) = &ctx.accounts.lp_monitor {
+        let info = monitor.to_account_info();
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(data.len() < LpPositionMonitor::LEN, AgentError::LpAccountUpToDate);
+            require!(
+                &data[..8] == LpPositionMonitor::DISCRIMINATOR,
+                AgentError::InvalidLpAccount
+            );
+            LegacyLpPositionMonitor::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(legacy.session, session, AgentError::InvalidLpAccount);
+        grow(&info, LpPositionMonitor::LEN, &payer, &system)?;
+
+        let loader = AccountLoader::<LpPositionMonitor>::try_from(&info)?;
+        let mut m = loader.load_mut()?;
+        m.session = legacy.session;
+        m.lb_pair = legacy.lb_pair;
+        m.position = legacy.position;
+        m.min_bin_id = legacy.min_bin_id;
+        m.max_bin_id = legacy.max_bin_id;
+        m.last_active_bin = legacy.last_active_bin;
+        m.is_in_range = legacy.is_in_range as u8;
+        m.bump = legacy.bump;
+        m._padding = [0; 2];
+        m.fee_x_snapshot = legacy.fee_x_snapshot;
+        m.fee_y_snapshot = legacy.fee_y_snapshot;
+        m.last_checked_at = legacy.last_checked_at;
+        m.amount_x_snapshot = 0;
+        m.amount_y_snapshot = 0;
+        m.out_of_range_since = 0;
+        msg!("LP monitor migrated: position={}", m.position);
+    }
+
+    if let Some(registry) = &ctx.accounts.position_registry {
+        let info = registry.to_account_info();
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(data.len() < PositionRegistry::LEN, AgentError::LpAccountUpToDate);
+            require!(
+                &data[..8] == PositionRegistry::DISCRIMINATOR,
+                AgentError::InvalidLpAccount
+            );
+            LegacyPositionRegistry::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(legacy.session, session, AgentError::InvalidLpAccount);
+        require!(
+            legacy.positions.len() <= MAX_MANAGED_POSITIONS,
+            AgentError::InvalidLpAccount
+        );
+        grow(&info, PositionRegistry::LEN, &payer, &system)?;
+
+        let loader = AccountLoader::<PositionRegistry>::try_from(&info)?;
+        let mut r = loader.load_mut()?;
+        r.session = legacy.session;
+        for (slot, entry) in r.positions.iter_mut().enumerate() {
+            *entry = match legacy.positions.get(slot) {
+                Some(p) => ManagedPosition {
+                    position: p.position,
+                    lb_pair: p.lb_pair,
+                    created_at: p.created_at,
+                    deposited_x: 0,
+                    deposited_y: 0,
+                    cost_basis: 0,
+                },
+                None => ManagedPosition::EMPTY,
+            };
+        }
+        r.count = legacy.positions.len() as u8;
+        r.bump = legacy.bump;
+        r._padding = [0; 6];
+        msg!("Position registry migrated: positions={}", r.count);
+    }
+
+    Ok(())
+}
+
+/// Grow `info` to `len`, topping up rent from `payer`.
+fn grow<'info>(
+    info: &AccountInfo<'info>,
+    len: usize,
+    payer: &AccountInfo<'info>,
+    system: &AccountInfo<'info>,
+) -> Result<()> {
+    let shortfall = Rent::get()?.minimum_balance(len).saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.resize(len)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateLpAccounts<'info> {
+    /// The wallet owner of the session — funds any extra rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(constraint = session.owner == owner.key())]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Decoded manually — the legacy layout doesn't load as `LpPositionMonitor`
+    pub lp_monitor: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Decoded manually — the legacy layout doesn't load as `PositionRegistry`
+    pub position_registry: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -8,6 +8,96 @@ pub mod execute_dlmm_add_liquidity;
 pub mod execute_dlmm_close_position;
 pub mod register_lp_monitor;
 pub mod update_lp_status;
+pub mod set_fee_budget;
+pub mod initialize_config;
+pub mod update_config;
+pub mod set_paused;
+pub mod initialize_treasury;
+pub mod withdraw_treasury;
+pub mod initialize_pool_registry;
+pub mod add_registry_pool;
+pub mod remove_registry_pool;
+pub mod set_registry_only;
+pub mod set_session_limits;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod set_admin_timelock;
+pub mod set_referral_share;
+pub mod set_fee_tier;
+pub mod withdraw_fee_vault;
+pub mod set_dlmm_frozen;
+pub mod migrate_config;
+pub mod add_device;
+pub mod remove_device;
+pub mod set_device_limits;
+pub mod set_intent_signer;
+pub mod rotate_device;
+pub mod disable_device_key;
+pub mod set_standby_device;
+pub mod device_heartbeat;
+pub mod set_cosign_threshold;
+pub mod file_action_request;
+pub mod approve_request;
+pub mod queue_withdrawal;
+pub mod cancel_withdrawal;
+pub mod set_withdrawal_policy;
+pub mod set_treasury_withdrawal_threshold;
+pub mod create_intent;
+pub mod fulfill_intent;
+pub mod cancel_intent;
+pub mod create_intents_batch;
+pub mod keeper_fill_intent;
+pub mod set_bound_positions;
+pub mod initialize_position_registry;
+pub mod execute_dlmm_create_position;
+pub mod adopt_position;
+pub mod execute_dlmm_migrate_position;
+pub mod close_all_positions;
+pub mod set_quiet_logs;
+pub mod create_session_lookup_table;
+pub mod extend_session_lookup_table;
+pub mod execute_dlmm_remove_all_liquidity;
+pub mod execute_dlmm_close_empty_position;
+pub mod execute_dlmm_swap_route;
+pub mod execute_dlmm_add_liquidity_batch;
+pub mod set_compute_guard;
+pub mod initialize_monitor_tree;
+pub mod register_compressed_monitor;
+pub mod update_compressed_lp_status;
+pub mod set_min_trade_amount;
+pub mod initialize_daily_stats;
+pub mod snapshot_session;
+pub mod revalue_exposure;
+pub mod set_mark_to_market;
+pub mod set_slippage_alert;
+pub mod acknowledge_review;
+pub mod renew_session;
+pub mod reassign_monitor;
+pub mod set_violation_freeze;
+pub mod set_exposure_cooldown;
+pub mod set_strategy_mask;
+pub mod set_alert_config;
+pub mod set_session_metadata;
+pub mod set_fee_payer;
+pub mod configure_fee_sponsor;
+pub mod fund_fee_sponsor;
+pub mod refill_session_key;
+pub mod close_fee_sponsor;
+pub mod set_liquidity_shapes;
+pub mod execute_dlmm_widen_range;
+pub mod set_pair_pools;
+pub mod close_pair_pools;
+pub mod set_swap_fee_budget;
+pub mod initialize_receipt_log;
+pub mod delegate_receipt_log;
+pub mod set_commit_cadence;
+pub mod undelegate_all;
+pub mod set_magic_programs;
+pub mod execute_actions_batch;
+pub mod set_max_idle_slots;
+pub mod set_budget_mint;
+pub mod migrate_lp_accounts;
+pub mod set_budget_price_pool;
 
 // Anchor's #[program] macro needs `__client_accounts_*` types from each module
 // to be in the crate root scope. The `handler` name appears in all modules
@@ -33,3 +123,183 @@ pub use execute_dlmm_close_position::*;
 pub use register_lp_monitor::*;
 #[allow(ambiguous_glob_reexports)]
 pub use update_lp_status::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_fee_budget::*;
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_config::*;
+#[allow(ambiguous_glob_reexports)]
+pub use update_config::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_paused::*;
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_treasury::*;
+#[allow(ambiguous_glob_reexports)]
+pub use withdraw_treasury::*;
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_pool_registry::*;
+#[allow(ambiguous_glob_reexports)]
+pub use add_registry_pool::*;
+#[allow(ambiguous_glob_reexports)]
+pub use remove_registry_pool::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_registry_only::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_session_limits::*;
+#[allow(ambiguous_glob_reexports)]
+pub use propose_admin::*;
+#[allow(ambiguous_glob_reexports)]
+pub use accept_admin::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_admin_timelock::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_referral_share::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_fee_tier::*;
+#[allow(ambiguous_glob_reexports)]
+pub use withdraw_fee_vault::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_dlmm_frozen::*;
+#[allow(ambiguous_glob_reexports)]
+pub use migrate_config::*;
+#[allow(ambiguous_glob_reexports)]
+pub use add_device::*;
+#[allow(ambiguous_glob_reexports)]
+pub use remove_device::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_device_limits::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_intent_signer::*;
+#[allow(ambiguous_glob_reexports)]
+pub use rotate_device::*;
+#[allow(ambiguous_glob_reexports)]
+pub use disable_device_key::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_standby_device::*;
+#[allow(ambiguous_glob_reexports)]
+pub use device_heartbeat::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_cosign_threshold::*;
+#[allow(ambiguous_glob_reexports)]
+pub use file_action_request::*;
+#[allow(ambiguous_glob_reexports)]
+pub use approve_request::*;
+#[allow(ambiguous_glob_reexports)]
+pub use queue_withdrawal::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cancel_withdrawal::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_withdrawal_policy::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_treasury_withdrawal_threshold::*;
+#[allow(ambiguous_glob_reexports)]
+pub use create_intent::*;
+#[allow(ambiguous_glob_reexports)]
+pub use fulfill_intent::*;
+#[allow(ambiguous_glob_reexports)]
+pub use cancel_intent::*;
+#[allow(ambiguous_glob_reexports)]
+pub use create_intents_batch::*;
+#[allow(ambiguous_glob_reexports)]
+pub use keeper_fill_intent::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_bound_positions::*;
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_position_registry::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_dlmm_create_position::*;
+#[allow(ambiguous_glob_reexports)]
+pub use adopt_position::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_dlmm_migrate_position::*;
+#[allow(ambiguous_glob_reexports)]
+pub use close_all_positions::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_quiet_logs::*;
+#[allow(ambiguous_glob_reexports)]
+pub use create_session_lookup_table::*;
+#[allow(ambiguous_glob_reexports)]
+pub use extend_session_lookup_table::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_dlmm_remove_all_liquidity::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_dlmm_close_empty_position::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_dlmm_swap_route::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_dlmm_add_liquidity_batch::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_compute_guard::*;
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_monitor_tree::*;
+#[allow(ambiguous_glob_reexports)]
+pub use register_compressed_monitor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use update_compressed_lp_status::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_min_trade_amount::*;
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_daily_stats::*;
+#[allow(ambiguous_glob_reexports)]
+pub use snapshot_session::*;
+#[allow(ambiguous_glob_reexports)]
+pub use revalue_exposure::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_mark_to_market::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_slippage_alert::*;
+#[allow(ambiguous_glob_reexports)]
+pub use acknowledge_review::*;
+#[allow(ambiguous_glob_reexports)]
+pub use renew_session::*;
+#[allow(ambiguous_glob_reexports)]
+pub use reassign_monitor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_violation_freeze::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_exposure_cooldown::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_strategy_mask::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_alert_config::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_session_metadata::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_fee_payer::*;
+#[allow(ambiguous_glob_reexports)]
+pub use configure_fee_sponsor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use fund_fee_sponsor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use refill_session_key::*;
+#[allow(ambiguous_glob_reexports)]
+pub use close_fee_sponsor::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_liquidity_shapes::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_dlmm_widen_range::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_pair_pools::*;
+#[allow(ambiguous_glob_reexports)]
+pub use close_pair_pools::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_swap_fee_budget::*;
+#[allow(ambiguous_glob_reexports)]
+pub use initialize_receipt_log::*;
+#[allow(ambiguous_glob_reexports)]
+pub use delegate_receipt_log::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_commit_cadence::*;
+#[allow(ambiguous_glob_reexports)]
+pub use undelegate_all::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_magic_programs::*;
+#[allow(ambiguous_glob_reexports)]
+pub use execute_actions_batch::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_max_idle_slots::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_budget_mint::*;
+#[allow(ambiguous_glob_reexports)]
+pub use migrate_lp_accounts::*;
+#[allow(ambiguous_glob_reexports)]
+pub use set_budget_price_pool::*;
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::Config;
+use crate::errors::AgentError;
+
+/// [Base Layer] Step 1 of an admin transfer: nominate `new_admin`.
+///
+/// Admin-only. The nominee can call `accept_admin` once `admin_timelock_secs`
+/// have elapsed. Proposing again replaces the nominee and restarts the
+/// timelock; proposing `Pubkey::default()` cancels a pending transfer.
+pub fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.config;
+
+    config.pending_admin = new_admin;
+    config.admin_transfer_available_at = if new_admin == Pubkey::default() {
+        0
+    } else {
+        clock
+            .unix_timestamp
+            .checked_add(config.admin_timelock_secs)
+            .ok_or(AgentError::Overflow)?
+    };
+
+    msg!(
+        "Admin proposed: pending_admin={}, available_at={}",
+        config.pending_admin,
+        config.admin_transfer_available_at,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    /// Current config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
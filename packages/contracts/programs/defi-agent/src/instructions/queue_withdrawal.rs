@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, PendingWithdrawal};
+use crate::errors::AgentError;
+
+/// [Base Layer] Announce a large withdrawal from the treasury or fee vault.
+///
+/// Admin-only. `asset` is the treasury mint, or `NATIVE_ASSET` for the fee
+/// vault. The withdrawal becomes executable after `Config.withdrawal_delay_secs`
+/// and must then be sent for exactly `amount` to `destination`. One pending
+/// withdrawal per asset; cancel it with `cancel_withdrawal` to queue another.
+pub fn handler(
+    ctx: Context<QueueWithdrawal>,
+    asset: Pubkey,
+    amount: u64,
+    destination: Pubkey,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let available_at = clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.config.withdrawal_delay_secs)
+        .ok_or(AgentError::Overflow)?;
+
+    let pending = &mut ctx.accounts.pending_withdrawal;
+    pending.asset = asset;
+    pending.amount = amount;
+    pending.destination = destination;
+    pending.queued_at = clock.unix_timestamp;
+    pending.available_at = available_at;
+    pending.bump = ctx.bumps.pending_withdrawal;
+
+    msg!(
+        "Withdrawal queued: asset={}, amount={}, destination={}, available_at={}",
+        asset,
+        amount,
+        destination,
+        available_at,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(asset: Pubkey)]
+pub struct QueueWithdrawal<'info> {
+    /// Config admin — must sign and pays rent for the pending entry
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = PendingWithdrawal::LEN,
+        seeds = [b"pending_withdrawal", asset.as_ref()],
+        bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    pub system_program: Program<'info, System>,
+}
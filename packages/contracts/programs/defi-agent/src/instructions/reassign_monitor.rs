@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use crate::dlmm;
+use crate::errors::AgentError;
+use crate::events::MonitorReassigned;
+use crate::state::{AgentSession, LpPositionMonitor, PositionRegistry};
+
+/// [Base Layer] Move a session's LpPositionMonitor — and, when passed, its
+/// PositionRegistry — under another session.
+///
+/// For owners who start over with a fresh session (a new owner wallet, hence
+/// a new session PDA): instead of re-registering the monitor and re-adopting
+/// positions, both accounts are re-created at `new_session`'s PDAs with their
+/// contents intact — range, last checkpoint and fee snapshots, and every
+/// registry entry with its deposits and cost basis — then the old accounts are
+/// closed to the old owner. Only the `session` link and bump change.
+///
+/// Signed by both owners (the same key may sign twice); the new owner pays
+/// rent for the new accounts. Neither session may be delegated. The target
+/// must not already have a monitor (or registry, when moving one).
+///
+/// Session PDAs are per owner, so this always hands the accounts to another
+/// owner — but the DLMM positions themselves do not move: they stay owned by
+/// the device key that opened them. The receiving session must therefore
+/// already have that device enrolled. Every position being moved — the
+/// monitor's, then each registry entry in order — is passed in
+/// `remaining_accounts` and its DLMM `owner` must be a device of
+/// `new_session`; otherwise the call fails with `ReassignPositionNotOwned`
+/// and nothing moves. Ending the old session (or removing the device from
+/// it) is left to the old owner.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ReassignMonitor<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.position_registry.is_some() == ctx.accounts.new_position_registry.is_some(),
+        AgentError::ReassignAccountMismatch
+    );
+    let new_session = ctx.accounts.new_session.key();
+    let mut positions = ctx.remaining_accounts.iter();
+    let mut check_owned = |expected: &Pubkey| -> Result<()> {
+        let info = positions.next().ok_or(AgentError::ReassignPositionNotOwned)?;
+        require_keys_eq!(info.key(), *expected, AgentError::ReassignPositionNotOwned);
+        let position = AccountLoader::<dlmm::accounts::PositionV2>::try_from(info)?;
+        let owner = position.load()?.owner;
+        require!(
+            ctx.accounts.new_session.device_index(&owner).is_some(),
+            AgentError::ReassignPositionNotOwned
+        );
+        Ok(())
+    };
+
+    let (position, lb_pair) = {
+        let mut monitor = *ctx.accounts.monitor.load()?;
+        monitor.session = new_session;
+        monitor.bump = ctx.bumps.new_monitor;
+        check_owned(&monitor.position)?;
+        *ctx.accounts.new_monitor.load_init()? = monitor;
+        (monitor.position, monitor.lb_pair)
+    };
+
+    let mut registry_positions = 0;
+    if let (Some(old), Some(new), Some(bump)) = (
+        ctx.accounts.position_registry.as_ref(),
+        ctx.accounts.new_position_registry.as_ref(),
+        ctx.bumps.new_position_registry,
+    ) {
+        let mut registry = *old.load()?;
+        registry.session = new_session;
+        registry.bump = bump;
+        registry_positions = registry.count;
+        for entry in registry.active() {
+            check_owned(&entry.position)?;
+        }
+        *new.load_init()? = registry;
+    }
+
+    emit!(MonitorReassigned {
+        from_session: ctx.accounts.session.key(),
+        to_session: new_session,
+        position,
+        lb_pair,
+        registry_positions,
+    });
+
+    msg!(
+        "Monitor reassigned: position={}, to_session={}, registry_positions={}",
+        position,
+        new_session,
+        registry_positions,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReassignMonitor<'info> {
+    /// Owner of the session the monitor is moved out of — receives the old
+    /// accounts' rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Owner of the receiving session — pays rent for the new accounts
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+
+    /// The session giving up its monitor
+    #[account(
+        seeds = [b"session", owner.key().as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The session receiving it
+    #[account(
+        seeds = [b"session", new_owner.key().as_ref()],
+        bump = new_session.bump,
+        constraint = new_session.key() != session.key() @ AgentError::ReassignAccountMismatch,
+        constraint = !new_session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub new_session: Account<'info, AgentSession>,
+
+    /// The session's LpPositionMonitor — closed once copied
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump = monitor.load()?.bump,
+    )]
+    pub monitor: AccountLoader<'info, LpPositionMonitor>,
+
+    /// The receiving session's LpPositionMonitor — created here
+    #[account(
+        init,
+        payer = new_owner,
+        space = LpPositionMonitor::LEN,
+        seeds = [b"lp_monitor", new_session.key().as_ref()],
+        bump,
+    )]
+    pub new_monitor: AccountLoader<'info, LpPositionMonitor>,
+
+    /// The session's PositionRegistry — moved along when passed
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    /// The receiving session's PositionRegistry — created here when the
+    /// registry is moved
+    #[account(
+        init,
+        payer = new_owner,
+        space = PositionRegistry::LEN,
+        seeds = [b"position_registry", new_session.key().as_ref()],
+        bump,
+    )]
+    pub new_position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    pub system_program: Program<'info, System>,
+    // DLMM positions being moved (monitor's, then registry entries) →
+    // ctx.remaining_accounts
+}
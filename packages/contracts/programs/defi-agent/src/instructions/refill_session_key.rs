@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::SessionKeyRefilled;
+use crate::state::{ActionKind, AgentSession, FeeSponsor, TemporalSource};
+
+/// [Base Layer] Top a device key up from the session's fee sponsor.
+///
+/// Permissionless — the device itself, a keeper or the owner can crank it —
+/// because the lamports only ever go to a usable enrolled device of a live
+/// session. The device is brought up to the sponsor's
+/// `target_balance_lamports`, limited by what is left of today's
+/// `daily_cap_lamports` and the sponsor's balance above rent. Fails with
+/// `RefillNotNeeded` when the device already holds the target.
+///
+/// Base layer only: the device's wallet lives there, and transactions on the
+/// Ephemeral Rollup cost it nothing.
+pub fn handler(ctx: Context<RefillSessionKey>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let device_key = ctx.accounts.device.key();
+    ctx.accounts.session.validate_session(
+        &device_key,
+        ActionKind::Housekeeping,
+        TemporalSource::Passive(now),
+    )?;
+
+    let sponsor_info = ctx.accounts.fee_sponsor.to_account_info();
+    let available = sponsor_info
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(sponsor_info.data_len()));
+    let amount = ctx
+        .accounts
+        .fee_sponsor
+        .refill_amount(ctx.accounts.device.lamports(), available, now)?;
+
+    ctx.accounts.fee_sponsor.record_refill(amount, now)?;
+    ctx.accounts.fee_sponsor.sub_lamports(amount)?;
+    ctx.accounts.device.add_lamports(amount)?;
+
+    let sponsor = &ctx.accounts.fee_sponsor;
+    emit!(SessionKeyRefilled {
+        session: sponsor.session,
+        device: device_key,
+        lamports: amount,
+        device_balance: ctx.accounts.device.lamports(),
+        refilled_today_lamports: sponsor.refilled_today_lamports,
+        sponsor_balance: available - amount,
+    });
+    msg!(
+        "Session key refilled: device={}, amount={}, refilled_today={}/{}",
+        device_key,
+        amount,
+        sponsor.refilled_today_lamports,
+        sponsor.daily_cap_lamports
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefillSessionKey<'info> {
+    /// The session the device belongs to — validated live and undelegated
+    #[account(
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The session's fee sponsor — source of the refill
+    #[account(
+        mut,
+        seeds = [b"fee_sponsor", session.key().as_ref()],
+        bump = fee_sponsor.bump,
+    )]
+    pub fee_sponsor: Account<'info, FeeSponsor>,
+
+    /// The device key to top up — must be enrolled and usable
+    #[account(mut)]
+    pub device: SystemAccount<'info>,
+}
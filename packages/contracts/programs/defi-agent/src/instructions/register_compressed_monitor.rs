@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::spl_account_compression;
+use crate::state::{AgentSession, CompressedMonitorLeaf, CompressedMonitorTree};
+use crate::errors::AgentError;
+use crate::events::CompressedMonitorUpdated;
+
+/// [Base Layer] Register a DLMM position as a compressed monitor.
+///
+/// Rent-free counterpart of `register_lp_monitor`: the checkpoint's hash is
+/// appended to the session's Merkle tree through the account compression
+/// program, which needs no proof for appends.
+pub fn handler(
+    ctx: Context<RegisterCompressedMonitor>,
+    lb_pair: Pubkey,
+    position: Pubkey,
+    min_bin_id: i32,
+    max_bin_id: i32,
+) -> Result<()> {
+    require!(min_bin_id <= max_bin_id, AgentError::InvalidBinRange);
+    require!(ctx.accounts.session.is_active, AgentError::SessionInactive);
+    require!(ctx.accounts.tree.has_room(), AgentError::MonitorTreeFull);
+
+    let leaf = CompressedMonitorLeaf {
+        lb_pair,
+        position,
+        min_bin_id,
+        max_bin_id,
+        last_active_bin: 0,
+        is_in_range: true, // optimistic default — first update will correct
+        fee_x_snapshot: 0,
+        fee_y_snapshot: 0,
+        last_checked_at: 0,
+    };
+
+    let session_key = ctx.accounts.session.key();
+    let tree = &mut ctx.accounts.tree;
+    let seeds: &[&[u8]] = &[b"monitor_tree", session_key.as_ref(), &[tree.bump]];
+    spl_account_compression::cpi::append(
+        CpiContext::new_with_signer(
+            ctx.accounts.compression_program.to_account_info(),
+            spl_account_compression::cpi::accounts::Append {
+                merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+                authority: tree.to_account_info(),
+                noop: ctx.accounts.noop_program.to_account_info(),
+            },
+            &[seeds],
+        ),
+        leaf.hash(),
+    )?;
+    let leaf_index = tree.leaf_count;
+    tree.leaf_count += 1;
+
+    emit!(CompressedMonitorUpdated {
+        tree: tree.key(),
+        leaf_index,
+        leaf,
+    });
+
+    msg!(
+        "Compressed LP monitor registered: index={}, position={}, range=[{}, {}]",
+        leaf_index,
+        position,
+        min_bin_id,
+        max_bin_id,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterCompressedMonitor<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The session's CompressedMonitorTree — signs the append as tree authority
+    #[account(
+        mut,
+        seeds = [b"monitor_tree", session.key().as_ref()],
+        bump = tree.bump,
+    )]
+    pub tree: Account<'info, CompressedMonitorTree>,
+
+    #[account(mut, address = tree.merkle_tree)]
+    /// CHECK: The tree's ConcurrentMerkleTree account — the leaf is appended here
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(address = spl_account_compression::ID)]
+    /// CHECK: SPL account compression program
+    pub compression_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::SPL_NOOP_ID)]
+    /// CHECK: SPL noop program
+    pub noop_program: UncheckedAccount<'info>,
+}
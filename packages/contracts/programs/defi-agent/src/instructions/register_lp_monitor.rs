@@ -20,18 +20,15 @@ pub fn handler(
     let session = &ctx.accounts.session;
     require!(session.is_active, AgentError::SessionInactive);
 
-    let monitor = &mut ctx.accounts.monitor;
-    monitor.session = ctx.accounts.session.key();
-    monitor.lb_pair = lb_pair;
-    monitor.position = position;
-    monitor.min_bin_id = min_bin_id;
-    monitor.max_bin_id = max_bin_id;
-    monitor.last_active_bin = 0;
-    monitor.is_in_range = true; // optimistic default — first update will correct
-    monitor.fee_x_snapshot = 0;
-    monitor.fee_y_snapshot = 0;
-    monitor.last_checked_at = 0;
-    monitor.bump = ctx.bumps.monitor;
+    let session_key = ctx.accounts.session.key();
+    ctx.accounts.monitor.load_init()?.track(
+        session_key,
+        lb_pair,
+        position,
+        min_bin_id,
+        max_bin_id,
+        ctx.bumps.monitor,
+    );
 
     msg!(
         "LP monitor registered: position={}, range=[{}, {}]",
@@ -50,7 +47,10 @@ pub struct RegisterLpMonitor<'info> {
     pub owner: Signer<'info>,
 
     /// The owning AgentSession — validated to belong to `owner`
-    #[account(constraint = session.owner == owner.key())]
+    #[account(
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
     pub session: Account<'info, AgentSession>,
 
     /// LpPositionMonitor PDA — created here
@@ -61,7 +61,7 @@ pub struct RegisterLpMonitor<'info> {
         seeds = [b"lp_monitor", session.key().as_ref()],
         bump,
     )]
-    pub monitor: Account<'info, LpPositionMonitor>,
+    pub monitor: AccountLoader<'info, LpPositionMonitor>,
 
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Remove a device key from the session.
+///
+/// Signed by the session owner. The key can no longer sign any session
+/// instruction; other enrolled devices are unaffected.
+pub fn handler(ctx: Context<RemoveDevice>, device_key: Pubkey) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let slot = session.remove_device(&device_key)?;
+
+    msg!("Device removed: key={}, slot={}", device_key, slot);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveDevice<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
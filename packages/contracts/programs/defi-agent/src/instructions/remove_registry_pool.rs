@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, PoolRegistry};
+use crate::errors::AgentError;
+
+/// [Base Layer] Remove a DLMM pool from the global registry.
+///
+/// Admin-only. Registry-only sessions can no longer swap or add liquidity in
+/// the pool; `execute_dlmm_close_position` is not registry-gated, so existing
+/// positions there can still be exited.
+pub fn handler(ctx: Context<RemoveRegistryPool>, lb_pair: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.pool_registry;
+    registry.remove(&lb_pair)?;
+
+    msg!(
+        "Registry pool removed: lb_pair={}, total={}",
+        lb_pair,
+        registry.pools.len(),
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveRegistryPool<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Account<'info, PoolRegistry>,
+}
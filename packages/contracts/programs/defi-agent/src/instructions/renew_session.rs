@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::SessionRenewed;
+use crate::instructions::initialize_session::resolve_limits;
+use crate::state::{AgentSession, Config, STRATEGY_COUNT};
+
+/// [Base Layer] Start a new term on an ended session, in place.
+///
+/// Signed by the session owner, on a session that has expired or been
+/// undelegated (and so deactivated). Re-creating the session would mean a new
+/// PDA and orphaned monitors; renewing keeps the account and everything keyed
+/// off it — the PositionRegistry, LpPositionMonitor and DailyStats stay
+/// linked — along with the enrolled devices and the lifetime statistics
+/// (actions, valuation and realized PnL, fees earned, slippage, snapshot
+/// sequence).
+///
+/// The term is reset: a new expiry `duration_secs` from now, fresh
+/// `max_lamports` / `max_action_lamports` caps, and zeroed spend meters —
+/// session and per-device spend, the per-strategy split, and the fee and
+/// transaction-fee spend against their budgets. `current_exposure_value` is
+/// kept, since the positions it values are still open. Limits resolve against
+/// the Config exactly as in `initialize_session`. Devices past their own
+/// expiry stay expired until extended with `set_device_limits`.
+pub fn handler(
+    ctx: Context<RenewSession>,
+    duration_secs: i64,
+    max_lamports: u64,
+    max_action_lamports: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let session = &mut ctx.accounts.session;
+    require!(
+        !session.is_active || session.is_expired(clock.unix_timestamp),
+        AgentError::SessionStillActive
+    );
+
+    let (duration_secs, max_lamports, max_action_lamports) = resolve_limits(
+        &ctx.accounts.config,
+        duration_secs,
+        max_lamports,
+        max_action_lamports,
+    )?;
+    let previous_spent_lamports = session.spent_lamports;
+
+    let now = session.observe_clock(clock.unix_timestamp);
+    session.expires_at = now
+        .checked_add(duration_secs)
+        .ok_or(AgentError::Overflow)?;
+    session.max_lamports = max_lamports;
+    session.max_action_lamports = max_action_lamports;
+    session.spent_lamports = 0;
+    session.spent_by_strategy = [0; STRATEGY_COUNT];
+    for device in session.devices.iter_mut() {
+        device.spent_lamports = 0;
+    }
+    session.fee_spent_lamports = 0;
+    session.tx_fees_lamports = 0;
+    session.exposure_cooldown_until = 0;
+    session.alerts_raised = 0;
+    session.is_active = true;
+
+    emit!(SessionRenewed {
+        session: session.key(),
+        expires_at: session.expires_at,
+        max_lamports,
+        max_action_lamports,
+        previous_spent_lamports,
+        total_actions: session.total_actions,
+    });
+
+    msg!(
+        "Session renewed: expires_at={}, max_lamports={}, previous_spent={}",
+        session.expires_at,
+        session.max_lamports,
+        previous_spent_lamports,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RenewSession<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — must be back on the base layer
+    #[account(
+        mut,
+        seeds = [b"session", owner.key().as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — supplies default limits and protocol ceilings
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::ExposureRevalued;
+use crate::log_info;
+use crate::state::{ActionKind, AgentSession, PositionRegistry, TemporalSource};
+use crate::valuation;
+
+/// [Base Layer] Reprice the session's outstanding positions at current pool
+/// prices and carry the change into `current_exposure_value`.
+///
+/// Signed by an enrolled device key. Each position in the PositionRegistry is
+/// valued as its deposited X and Y at its pool's active-bin price (the pool
+/// is the oracle, as for PnL valuations), in Q64.64 fixed point since the
+/// result feeds the exposure cap; the pools' LbPair accounts are
+/// passed as remaining accounts, each once. Exposure moves only by the change
+/// in the positions' unrealized gain (value less cost basis) since the last
+/// revaluation; every spend stays counted at cost, so revaluing can't clear
+/// room under the cap. When the owner has enabled `mark_to_market`, the
+/// exposure cap is enforced against this figure instead of historical cost.
+/// Values of pools with different Y tokens are summed as-is, like the
+/// lamport-denominated cap itself.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RevalueExposure<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    ctx.accounts.session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Housekeeping,
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+
+    let registry = ctx.accounts.position_registry.load()?;
+    let mut value: u64 = 0;
+    let mut cost_basis: u64 = 0;
+    for entry in registry.active() {
+        let lb_pair = ctx
+            .remaining_accounts
+            .iter()
+            .find(|a| a.key() == entry.lb_pair)
+            .ok_or(AgentError::RevaluationPoolMissing)?;
+        let price = valuation::pool_price_q64(lb_pair)?;
+        let marked = valuation::value_in_y_q64(price, entry.deposited_x, entry.deposited_y);
+        value = value.saturating_add(marked);
+        cost_basis = cost_basis.saturating_add(entry.cost_basis);
+    }
+
+    let session = &mut ctx.accounts.session;
+    let previous_exposure = session.current_exposure_value;
+    let exposure = session.apply_revaluation(value, cost_basis, clock.unix_timestamp);
+
+    emit!(ExposureRevalued {
+        session: session.key(),
+        positions: registry.count,
+        value,
+        cost_basis,
+        previous_exposure,
+        exposure,
+    });
+
+    log_info!(
+        session,
+        "Exposure revalued: positions={}, value={}, cost_basis={}, exposure={} (was {})",
+        registry.count,
+        value,
+        cost_basis,
+        exposure,
+        previous_exposure,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevalueExposure<'info> {
+    /// The device key — must sign
+    pub session_key: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The session's PositionRegistry — the positions to reprice
+    #[account(
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: AccountLoader<'info, PositionRegistry>,
+    // Pools (LbPair) of the registered positions → ctx.remaining_accounts
+}
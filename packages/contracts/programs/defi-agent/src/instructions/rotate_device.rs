@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, SessionLookup};
+
+/// [Base Layer] Rotate a device key with an overlap grace period.
+///
+/// Signed by the session owner. Phase one (this instruction) enrolls
+/// `new_key` with the old device's limits and spend history, and caps the old
+/// key's expiry at `now + grace_secs`. During the window both keys are valid,
+/// so transactions the old device already queued can still land. Phase two is
+/// automatic: once the window passes the old key fails `require_device` with
+/// `DeviceExpired`. The owner can later free its slot with `remove_device`.
+///
+/// `grace_secs = 0` rotates immediately. The new key's `SessionLookup` entry
+/// is pointed at this session (see `add_device` for an entry naming another).
+pub fn handler(
+    ctx: Context<RotateDevice>,
+    old_key: Pubkey,
+    new_key: Pubkey,
+    grace_secs: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let session = &mut ctx.accounts.session;
+    let new_slot = session.rotate_device(&old_key, new_key, grace_secs, clock.unix_timestamp)?;
+    ctx.accounts.session_lookup.set(
+        new_key,
+        session.key(),
+        ctx.bumps.session_lookup,
+        ctx.accounts.previous_session.as_deref(),
+    )?;
+
+    msg!(
+        "Device rotation started: old={}, new={}, new_slot={}, old_valid_until={}",
+        old_key,
+        new_key,
+        new_slot,
+        clock.unix_timestamp.saturating_add(grace_secs),
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(old_key: Pubkey, new_key: Pubkey)]
+pub struct RotateDevice<'info> {
+    /// The wallet owner of the session — pays for the key's lookup entry
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Reverse lookup from `new_key` to this session
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = SessionLookup::LEN,
+        seeds = [b"session_lookup", new_key.as_ref()],
+        bump,
+    )]
+    pub session_lookup: Account<'info, SessionLookup>,
+
+    /// CHECK: The session `session_lookup` currently names, when it names
+    /// another one — checked in `SessionLookup::set`
+    pub previous_session: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::Config;
+use crate::errors::AgentError;
+
+/// [Base Layer] Change the delay applied to future admin proposals.
+///
+/// Admin-only. Does not affect a proposal that is already pending.
+pub fn handler(ctx: Context<SetAdminTimelock>, admin_timelock_secs: i64) -> Result<()> {
+    require!(admin_timelock_secs >= 0, AgentError::InvalidTimelock);
+
+    let config = &mut ctx.accounts.config;
+    config.admin_timelock_secs = admin_timelock_secs;
+
+    msg!("Admin timelock set: {}s", config.admin_timelock_secs);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAdminTimelock<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
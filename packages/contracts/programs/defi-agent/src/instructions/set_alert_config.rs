@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, AlertConfig};
+
+/// [Base Layer] Set the session's on-chain alert thresholds.
+///
+/// Signed by the session owner. Each threshold is evaluated where its value
+/// changes — fees and exposure after every execute (and fees on
+/// `update_lp_status`), out-of-range time on `update_lp_status` — and raises
+/// a typed event (`FeeThresholdReached`, `ExposureThresholdReached`,
+/// `OutOfRangeTooLong`) for the notifier to relay. A zero field is off.
+/// Setting the config re-arms the one-shot fee and exposure alerts.
+pub fn handler(ctx: Context<SetAlertConfig>, alert_config: AlertConfig) -> Result<()> {
+    require!(alert_config.out_of_range_secs >= 0, AgentError::InvalidAlertConfig);
+    require!(alert_config.exposure_bps <= 10_000, AgentError::InvalidAlertConfig);
+    let session = &mut ctx.accounts.session;
+    session.alert_config = alert_config;
+    session.alerts_raised = 0;
+
+    msg!(
+        "Session alerts: fees_lamports={}, out_of_range_secs={}, exposure_bps={}",
+        alert_config.fees_lamports,
+        alert_config.out_of_range_secs,
+        alert_config.exposure_bps,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAlertConfig<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
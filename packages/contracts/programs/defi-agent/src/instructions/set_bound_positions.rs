@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, MAX_BOUND_POSITIONS};
+
+/// [Base Layer] Restrict the session to an explicit set of DLMM positions.
+///
+/// Signed by the session owner. Once set, `execute_dlmm_add_liquidity` and
+/// `execute_dlmm_close_position` reject any `position` not in the list, so
+/// positions the wallet holds outside the agent's mandate stay untouched.
+/// Replaces the previous list; an empty list removes the binding.
+pub fn handler(ctx: Context<SetBoundPositions>, positions: Vec<Pubkey>) -> Result<()> {
+    require!(
+        positions.len() <= MAX_BOUND_POSITIONS,
+        AgentError::TooManyBoundPositions
+    );
+
+    let session = &mut ctx.accounts.session;
+    session.bound_positions = Default::default();
+    for (slot, position) in session.bound_positions.iter_mut().zip(positions.iter()) {
+        *slot = *position;
+    }
+
+    msg!("Bound positions set: count={}", positions.len());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBoundPositions<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, BudgetCaps, Config};
+
+/// [Base Layer] Denominate the session's budget in a stable mint (e.g. USDC).
+///
+/// Signed by the session owner. With a budget mint set, `max_lamports`, the
+/// device and per-action caps, the co-sign threshold and exposure are all
+/// counted in that mint's base units: DLMM swaps into or out of it are
+/// charged at the amount of the budget mint that moved, and other swaps at
+/// their input valued through the DLMM pool the owner pinned for the input
+/// mint (`set_budget_price_pool`). `execute_action` notionals are taken as
+/// budget-mint units. Pubkey::default() returns to raw amounts.
+///
+/// A cap sized in one unit is meaningless in another, so `caps` restates
+/// every one of them, and the per-action cap must stay within the Config
+/// ceiling. The unit can only change while nothing has been spent, so
+/// exposure is never summed across units; earlier price pool pins are
+/// cleared.
+pub fn handler(ctx: Context<SetBudgetMint>, budget_mint: Pubkey, caps: BudgetCaps) -> Result<()> {
+    require!(
+        caps.max_action_lamports <= ctx.accounts.config.max_action_lamports,
+        AgentError::SessionLimitExceeded
+    );
+    let session = &mut ctx.accounts.session;
+    session.set_budget_mint(budget_mint, &caps)?;
+
+    msg!(
+        "Budget mint set: {}, max_lamports={}, max_action_lamports={}, cosign_above_lamports={}",
+        session.budget_mint,
+        session.max_lamports,
+        session.max_action_lamports,
+        session.cosign_above_lamports,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBudgetMint<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Global Config PDA — its per-action ceiling bounds the restated cap
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::dlmm;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Pin the DLMM pool that prices `mint` in the session's budget mint.
+///
+/// Signed by the session owner. Swaps and deposits that need `mint` valued in
+/// the budget unit only accept this pool as their `budget_price_pool`, so a
+/// device can't value its actions through a thin pool of its own choosing.
+/// `price_pool` must pair `mint` with the budget mint, in either order; it
+/// replaces any earlier pin for the mint, and omitting it unpins the mint.
+/// `set_budget_mint` clears every pin.
+pub fn handler(ctx: Context<SetBudgetPricePool>, mint: Pubkey) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let lb_pair = match &ctx.accounts.price_pool {
+        Some(pool) => {
+            let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(pool.as_ref())?;
+            let pair = loader.load()?;
+            require!(
+                (pair.token_x_mint == mint && pair.token_y_mint == session.budget_mint)
+                    || (pair.token_y_mint == mint && pair.token_x_mint == session.budget_mint),
+                AgentError::InvalidBudgetPricePool
+            );
+            pool.key()
+        }
+        None => Pubkey::default(),
+    };
+    session.pin_budget_price_pool(mint, lb_pair)?;
+
+    msg!("Budget price pool set: mint={}, lb_pair={}", mint, lb_pair);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBudgetPricePool<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// CHECK: DLMM LbPair pairing `mint` with `session.budget_mint` —
+    /// deserialized and checked in the handler; omit to unpin the mint
+    pub price_pool: Option<UncheckedAccount<'info>>,
+}
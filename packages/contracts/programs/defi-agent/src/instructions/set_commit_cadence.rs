@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Bound how much ER state can go uncommitted.
+///
+/// Signed by the session owner. While the session is delegated,
+/// `execute_action` and `execute_actions_batch` fail with `CommitRequired`
+/// once `max_uncommitted_actions` actions have run, or `max_uncommitted_secs`
+/// have passed, since the last commit (or delegation); `commit_session`
+/// opens a new window. This bounds what a misbehaving ER could lose. 0
+/// turns either limit off.
+pub fn handler(
+    ctx: Context<SetCommitCadence>,
+    max_uncommitted_actions: u32,
+    max_uncommitted_secs: i64,
+) -> Result<()> {
+    require!(max_uncommitted_secs >= 0, AgentError::InvalidCommitCadence);
+    let session = &mut ctx.accounts.session;
+    session.max_uncommitted_actions = max_uncommitted_actions;
+    session.max_uncommitted_secs = max_uncommitted_secs;
+
+    msg!(
+        "Commit cadence set: max_uncommitted_actions={}, max_uncommitted_secs={}",
+        session.max_uncommitted_actions,
+        session.max_uncommitted_secs,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCommitCadence<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
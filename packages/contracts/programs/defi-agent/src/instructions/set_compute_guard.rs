@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::Config;
+use crate::errors::AgentError;
+
+/// [Base Layer] Set the minimum compute-unit limit required by the double-CPI
+/// paths (`execute_dlmm_close_position`, `execute_dlmm_migrate_position`).
+///
+/// Admin-only. When non-zero, those instructions read the transaction's
+/// SetComputeUnitLimit from the instructions sysvar and fail up front with
+/// `InsufficientComputeBudget` instead of running out of compute mid-CPI.
+/// 0 disables the guard.
+pub fn handler(ctx: Context<SetComputeGuard>, min_dual_cpi_compute_units: u32) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.min_dual_cpi_compute_units = min_dual_cpi_compute_units;
+
+    msg!(
+        "Compute guard set: min_dual_cpi_compute_units={}",
+        config.min_dual_cpi_compute_units,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetComputeGuard<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Set the notional above which actions need the owner as co-signer.
+///
+/// Signed by the session owner. Actions at or below the threshold run on the
+/// device key alone; larger ones must carry the owner's signature in the same
+/// transaction (`cosigner` account). 0 disables the requirement.
+pub fn handler(ctx: Context<SetCosignThreshold>, cosign_above_lamports: u64) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.cosign_above_lamports = cosign_above_lamports;
+
+    msg!("Co-sign threshold set: cosign_above_lamports={}", cosign_above_lamports);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCosignThreshold<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
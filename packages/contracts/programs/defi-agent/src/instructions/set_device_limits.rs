@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Set a device key's own expiry and cumulative spend cap.
+///
+/// Signed by the session owner. Device limits apply on top of the session
+/// limits — e.g. a backup device can be given a shorter lifetime and a tighter
+/// budget than the primary. `expires_at = 0` inherits the session expiry and
+/// `max_lamports = 0` leaves the device bounded only by the session cap.
+pub fn handler(
+    ctx: Context<SetDeviceLimits>,
+    device_key: Pubkey,
+    expires_at: i64,
+    max_lamports: u64,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let slot = session
+        .device_index(&device_key)
+        .ok_or(AgentError::DeviceNotEnrolled)?;
+
+    let device = &mut session.devices[slot];
+    device.expires_at = expires_at;
+    device.max_lamports = max_lamports;
+
+    msg!(
+        "Device limits set: key={}, expires_at={}, max_lamports={}, spent={}",
+        device_key,
+        expires_at,
+        max_lamports,
+        device.spent_lamports,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetDeviceLimits<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
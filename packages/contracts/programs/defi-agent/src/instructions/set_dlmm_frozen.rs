@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::Config;
+use crate::errors::AgentError;
+
+/// [Base Layer] Freeze or unfreeze the Meteora-facing instructions.
+///
+/// Admin-only. Narrower than `set_paused`: while frozen, only the
+/// instructions that CPI into DLMM (`execute_dlmm_swap`,
+/// `execute_dlmm_add_liquidity`, `execute_dlmm_close_position`) fail with
+/// `DlmmFrozen`. `update_lp_status`, `execute_action`, commit and undelegate
+/// keep working — intended for a suspected upstream Meteora incident.
+pub fn handler(ctx: Context<SetDlmmFrozen>, frozen: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.dlmm_frozen = frozen;
+
+    msg!("DLMM frozen={}", config.dlmm_frozen);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetDlmmFrozen<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
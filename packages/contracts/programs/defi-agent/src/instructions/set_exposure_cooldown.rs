@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// Longest exposure cooldown an owner can set
+pub const MAX_EXPOSURE_COOLDOWN_SECS: i64 = 86_400;
+
+/// [Base Layer] Set the cooldown that follows an `ExposureLimitExceeded`
+/// rejection.
+///
+/// Signed by the session owner. While a cooldown runs, every execute call
+/// fails in session validation with `ExposureCooldown`, so a device retrying
+/// against a cap it cannot pass stops paying for the full instruction each
+/// time. 0 turns the cooldown off. Any running cooldown is ended — raise the
+/// cap first, then call this to resume at once.
+pub fn handler(ctx: Context<SetExposureCooldown>, cooldown_secs: i64) -> Result<()> {
+    require!(
+        (0..=MAX_EXPOSURE_COOLDOWN_SECS).contains(&cooldown_secs),
+        AgentError::InvalidCooldown
+    );
+    let session = &mut ctx.accounts.session;
+    session.exposure_cooldown_secs = cooldown_secs;
+    session.exposure_cooldown_until = 0;
+
+    msg!("Session exposure_cooldown_secs={}", session.exposure_cooldown_secs);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetExposureCooldown<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
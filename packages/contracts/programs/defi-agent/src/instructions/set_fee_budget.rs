@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Set the operational fee budget for the session.
+///
+/// Caps the cumulative priority fees and Jito tips the ESP32 may declare
+/// across all execute instructions. A budget of 0 (the default after
+/// `initialize_session`) leaves them uncapped but still tallied, like the
+/// transaction and swap fee budgets. Lowering the budget below
+/// `fee_spent_lamports` simply blocks further fee-bearing actions.
+///
+/// `tx_fee_budget_lamports` separately caps the signature fees counted into
+/// `tx_fees_lamports`; 0 (the default) leaves them uncapped as well.
+pub fn handler(
+    ctx: Context<SetFeeBudget>,
+    fee_budget_lamports: u64,
+    tx_fee_budget_lamports: u64,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.fee_budget_lamports = fee_budget_lamports;
+    session.tx_fee_budget_lamports = tx_fee_budget_lamports;
+
+    msg!(
+        "Fee budget set: budget={}, spent={}, tx_budget={}, tx_spent={}",
+        session.fee_budget_lamports,
+        session.fee_spent_lamports,
+        session.tx_fee_budget_lamports,
+        session.tx_fees_lamports,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeBudget<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
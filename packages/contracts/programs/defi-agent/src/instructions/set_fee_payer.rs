@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::FeePayerSet;
+use crate::state::AgentSession;
+
+/// [Base Layer] Designate a relayer that pays transaction fees for
+/// device-signed instructions.
+///
+/// Signed by the session owner. The fee payer only ever signs as the
+/// transaction fee payer: it is never a device key, so it carries no spending
+/// authority, and every session-key instruction rejects it as `session_key`
+/// in its account constraints. It must not be the owner or an enrolled
+/// device, and `add_device`/`rotate_device` refuse to enroll it later.
+/// `Pubkey::default()` clears it, leaving devices to pay their own fees.
+pub fn handler(ctx: Context<SetFeePayer>, fee_payer: Pubkey) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    require!(
+        fee_payer == Pubkey::default()
+            || (fee_payer != session.owner && session.device_index(&fee_payer).is_none()),
+        AgentError::InvalidFeePayer
+    );
+
+    let previous = session.fee_payer;
+    session.fee_payer = fee_payer;
+
+    emit!(FeePayerSet {
+        session: session.key(),
+        previous,
+        fee_payer,
+    });
+    msg!("Fee payer: {} (was {})", fee_payer, previous);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeePayer<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, FeeTier, BPS_DENOMINATOR, FEE_MODE_BPS, MAX_FEE_TIERS};
+use crate::errors::AgentError;
+
+/// [Base Layer] Define one of the admin-approved per-action fee tiers.
+///
+/// Admin-only. Sessions snapshot their tier at `initialize_session`, so
+/// editing a tier only affects sessions created afterwards.
+pub fn handler(ctx: Context<SetFeeTier>, index: u8, fee_mode: u8, amount: u64) -> Result<()> {
+    require!((index as usize) < MAX_FEE_TIERS, AgentError::InvalidFeeTier);
+    require!(fee_mode <= FEE_MODE_BPS, AgentError::InvalidFeeTier);
+    if fee_mode == FEE_MODE_BPS {
+        require!(amount <= BPS_DENOMINATOR, AgentError::InvalidFeeBps);
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.fee_tiers[index as usize] = FeeTier { fee_mode, amount };
+
+    msg!(
+        "Fee tier set: index={}, fee_mode={}, amount={}",
+        index,
+        fee_mode,
+        amount,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTier<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Require ed25519-signed intents for a device's actions.
+///
+/// Signed by the session owner. `intent_signer` is the device's secure-element
+/// certificate key — kept distinct from the fee-paying session key so that
+/// exfiltrating the hot key alone is not enough to move funds. Every execute
+/// instruction signed by `device_key` must then be accompanied by an
+/// ed25519-verify instruction over the canonical intent payload. Passing the
+/// default pubkey lifts the requirement. The nonce counter is preserved so
+/// old intents cannot be replayed after re-enabling.
+pub fn handler(
+    ctx: Context<SetIntentSigner>,
+    device_key: Pubkey,
+    intent_signer: Pubkey,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let slot = session
+        .device_index(&device_key)
+        .ok_or(AgentError::DeviceNotEnrolled)?;
+    session.devices[slot].intent_signer = intent_signer;
+
+    msg!(
+        "Intent signer set: device={}, intent_signer={}",
+        device_key,
+        intent_signer,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetIntentSigner<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, LIQUIDITY_SHAPES_ALL};
+
+/// [Base Layer] Restrict the distributions the agent may deposit with.
+///
+/// Signed by the session owner. `liquidity_shapes` combines the
+/// `LIQUIDITY_SHAPE_*` presets (or single `liquidity_shape_bit`s); deposits,
+/// batch deposits and migrations whose `StrategyType` is not among them fail
+/// with `LiquidityShapeNotAllowed`. 0 allows every shape.
+pub fn handler(ctx: Context<SetLiquidityShapes>, liquidity_shapes: u16) -> Result<()> {
+    require!(
+        liquidity_shapes & !LIQUIDITY_SHAPES_ALL == 0,
+        AgentError::InvalidLiquidityShapes
+    );
+    ctx.accounts.session.liquidity_shapes = liquidity_shapes;
+    msg!("Liquidity shapes: {:#05x}", liquidity_shapes);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetLiquidityShapes<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
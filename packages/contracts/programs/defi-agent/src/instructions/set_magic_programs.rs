@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::Config;
+use crate::errors::AgentError;
+
+/// [Base Layer] Override the MagicBlock magic program and context that
+/// `commit_session`, `undelegate_session` and `undelegate_all` target.
+///
+/// Admin-only. Meant for devnet and local ER validators whose magic accounts
+/// differ from the SDK's constants. Both are set together, or both cleared
+/// with Pubkey::default() to fall back to the SDK's addresses. They must be
+/// distinct and can be neither this program nor the system program. The
+/// commit paths only honour the overrides when passed the Config; without
+/// it they use the SDK's addresses.
+pub fn handler(
+    ctx: Context<SetMagicPrograms>,
+    magic_program: Pubkey,
+    magic_context: Pubkey,
+) -> Result<()> {
+    let cleared = magic_program == Pubkey::default() && magic_context == Pubkey::default();
+    if !cleared {
+        require!(
+            magic_program != Pubkey::default() && magic_context != Pubkey::default(),
+            AgentError::InvalidMagicOverride
+        );
+        require_keys_neq!(magic_program, magic_context, AgentError::InvalidMagicOverride);
+        for reserved in [crate::ID, System::id()] {
+            require!(
+                magic_program != reserved && magic_context != reserved,
+                AgentError::InvalidMagicOverride
+            );
+        }
+    }
+
+    let config = &mut ctx.accounts.config;
+    config.magic_program = magic_program;
+    config.magic_context = magic_context;
+
+    msg!(
+        "Magic programs set: program={}, context={}",
+        config.magic_program_id(),
+        config.magic_context_id(),
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMagicPrograms<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Choose what the session's exposure cap is enforced against.
+///
+/// Signed by the session owner. When enabled, execute handlers check
+/// `max_lamports` against `current_exposure_value` — every spend at cost,
+/// adjusted by the registry positions' unrealized gain as last repriced by
+/// `revalue_exposure` — instead of the cumulative historical cost in
+/// `spent_lamports`. Until the first revaluation both figures grow together.
+pub fn handler(ctx: Context<SetMarkToMarket>, enabled: bool) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.mark_to_market = enabled;
+
+    msg!(
+        "Session mark_to_market={}, current_exposure_value={}",
+        session.mark_to_market,
+        session.current_exposure_value,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMarkToMarket<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Deactivate the session once it goes silent on the ER.
+///
+/// Signed by the session owner. While the session is delegated, an ER action
+/// (`execute_action`, `execute_actions_batch`)
+/// arriving more than `max_idle_slots` ER slots after the previous one
+/// deactivates the session instead of executing. Slots keep advancing on the
+/// ER, unlike the wall clock that session expiry relies on. The window opens
+/// at the first action after each delegation. 0 turns the rule off.
+pub fn handler(ctx: Context<SetMaxIdleSlots>, max_idle_slots: u64) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.max_idle_slots = max_idle_slots;
+
+    msg!("Max idle slots set: {}", session.max_idle_slots);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMaxIdleSlots<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
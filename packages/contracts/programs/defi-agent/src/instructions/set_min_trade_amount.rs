@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, MinTradeAmount};
+
+/// [Base Layer] Set the smallest per-action amount the agent may trade in a mint.
+///
+/// Signed by the session owner. `min_amount` is in the mint's base units and
+/// `decimals` must match the mint's, so a threshold computed for the wrong
+/// precision is refused rather than silently off by powers of ten. Swaps,
+/// deposits and keeper fills whose input in `mint` falls below the minimum are
+/// rejected before any fee or action is counted; the wrapped SOL minimum (the
+/// budget mint's, for a session with one) also applies to `execute_action`
+/// notionals. 0 removes the mint's threshold.
+pub fn handler(ctx: Context<SetMinTradeAmount>, min_amount: u64, decimals: u8) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+    require!(decimals == mint.decimals, AgentError::MintDecimalsMismatch);
+
+    let mint_key = mint.key();
+    let session = &mut ctx.accounts.session;
+    let existing = session
+        .min_trade_amounts
+        .iter()
+        .position(|m| m.mint == mint_key);
+
+    match (existing, min_amount) {
+        (Some(slot), 0) => session.min_trade_amounts[slot] = MinTradeAmount::default(),
+        (Some(slot), _) => session.min_trade_amounts[slot].amount = min_amount,
+        (None, 0) => {}
+        (None, _) => {
+            let slot = session
+                .min_trade_amounts
+                .iter()
+                .position(|m| m.mint == Pubkey::default())
+                .ok_or(AgentError::MinTradeListFull)?;
+            session.min_trade_amounts[slot] = MinTradeAmount {
+                mint: mint_key,
+                amount: min_amount,
+            };
+        }
+    }
+
+    msg!(
+        "Min trade amount set: mint={}, min_amount={}, decimals={}",
+        mint_key,
+        min_amount,
+        decimals,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMinTradeAmount<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// Mint the threshold applies to (SPL Token or Token-2022)
+    pub mint: InterfaceAccount<'info, Mint>,
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use crate::dlmm;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, PairPools, MAX_PAIR_POOLS};
+
+/// [Base Layer] Create or replace the owner's ranked pool list for a token pair.
+///
+/// Signed by the session owner, who pays the list's rent on first use.
+/// `pools` are the acceptable LbPairs for `token_x_mint` / `token_y_mint`,
+/// most preferred first; each must be passed, in the same order, as a
+/// remaining account so its mints can be checked against the pair. While the
+/// session has any list, `execute_dlmm_create_position` and
+/// `execute_dlmm_migrate_position` only accept pools on one.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetPairPools<'info>>,
+    token_x_mint: Pubkey,
+    token_y_mint: Pubkey,
+    pools: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        !pools.is_empty()
+            && pools.len() <= MAX_PAIR_POOLS
+            && pools.len() == ctx.remaining_accounts.len(),
+        AgentError::InvalidPairPools
+    );
+    for (i, (pool, info)) in pools.iter().zip(ctx.remaining_accounts).enumerate() {
+        require!(info.key() == *pool && !pools[..i].contains(pool), AgentError::InvalidPairPools);
+        let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(info)?;
+        let pair = loader.load()?;
+        require!(
+            pair.token_x_mint == token_x_mint && pair.token_y_mint == token_y_mint,
+            AgentError::InvalidPairPools
+        );
+    }
+
+    let list = &mut ctx.accounts.pair_pools;
+    if list.session == Pubkey::default() {
+        let session = &mut ctx.accounts.session;
+        session.pair_pool_lists =
+            session.pair_pool_lists.checked_add(1).ok_or(AgentError::Overflow)?;
+        list.session = session.key();
+        list.token_x_mint = token_x_mint;
+        list.token_y_mint = token_y_mint;
+        list.bump = ctx.bumps.pair_pools;
+    }
+    list.pools = pools;
+
+    msg!(
+        "Pair pools set: {}/{} -> {} pools ({} lists)",
+        token_x_mint,
+        token_y_mint,
+        list.pools.len(),
+        ctx.accounts.session.pair_pool_lists
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(token_x_mint: Pubkey, token_y_mint: Pubkey)]
+pub struct SetPairPools<'info> {
+    /// The wallet owner of the session — pays the list's rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = PairPools::LEN,
+        seeds = [
+            b"pair_pools",
+            session.key().as_ref(),
+            token_x_mint.as_ref(),
+            token_y_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub pair_pools: Account<'info, PairPools>,
+
+    pub system_program: Program<'info, System>,
+}
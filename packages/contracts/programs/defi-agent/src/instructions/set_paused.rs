@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::Config;
+use crate::errors::AgentError;
+
+/// [Base Layer] Pause or resume agent execution program-wide.
+///
+/// Admin-only incident-response switch. While paused, every execute-family
+/// instruction fails fast with `ProtocolPaused`. Owner-side instructions
+/// (fee budget, monitor registration) and the commit/undelegate flow are not
+/// gated, so users can always wind their sessions down.
+pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
+
+    msg!("Protocol paused={}", config.paused);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
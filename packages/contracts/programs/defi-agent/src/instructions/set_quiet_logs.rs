@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Turn routine execution logs off or on for a session.
+///
+/// Signed by the session owner. While `quiet_logs` is set, swap / liquidity /
+/// status handlers skip their informational `msg!` lines (see `log_info!`) so
+/// complex DLMM CPIs keep more of the compute budget. Events, alerts and
+/// errors are unaffected.
+pub fn handler(ctx: Context<SetQuietLogs>, quiet_logs: bool) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.quiet_logs = quiet_logs;
+
+    msg!("Quiet logs set: quiet_logs={}", quiet_logs);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetQuietLogs<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, BPS_DENOMINATOR};
+use crate::errors::AgentError;
+
+/// [Base Layer] Set the referral revenue share.
+///
+/// Admin-only. `referral_share_bps` of every protocol fee skimmed from a
+/// session with a recorded `referrer` is paid to that referrer's token
+/// account; the treasury keeps the rest.
+pub fn handler(ctx: Context<SetReferralShare>, referral_share_bps: u16) -> Result<()> {
+    require!(
+        referral_share_bps as u64 <= BPS_DENOMINATOR,
+        AgentError::InvalidFeeBps
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.referral_share_bps = referral_share_bps;
+
+    msg!("Referral share set: {} bps", config.referral_share_bps);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetReferralShare<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
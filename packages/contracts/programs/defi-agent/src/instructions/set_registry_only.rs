@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Opt the session into (or out of) registry-only mode.
+///
+/// Signed by the session owner. While enabled, `execute_dlmm_swap` and
+/// `execute_dlmm_add_liquidity` reject any `lb_pair` not listed in the
+/// admin-curated PoolRegistry. Closing positions is never gated.
+pub fn handler(ctx: Context<SetRegistryOnly>, registry_only: bool) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.registry_only = registry_only;
+
+    msg!("Session registry_only={}", session.registry_only);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRegistryOnly<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, STRATEGY_ALL};
+use crate::errors::AgentError;
+
+/// [Base Layer] Set the protocol ceilings applied to new sessions.
+///
+/// Admin-only. `initialize_session` rejects any request whose per-action cap,
+/// duration, or strategy mask exceeds these values (e.g. a 30-day maximum
+/// session). Sessions created earlier keep the limits they were granted.
+pub fn handler(
+    ctx: Context<SetSessionLimits>,
+    max_action_lamports: u64,
+    max_session_duration_secs: i64,
+    max_strategy_mask: u8,
+) -> Result<()> {
+    require!(max_action_lamports > 0, AgentError::SessionLimitExceeded);
+    require!(max_session_duration_secs > 0, AgentError::InvalidDuration);
+    require!(
+        max_strategy_mask & !STRATEGY_ALL == 0,
+        AgentError::SessionLimitExceeded
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.max_action_lamports = max_action_lamports;
+    config.max_session_duration_secs = max_session_duration_secs;
+    config.max_strategy_mask = max_strategy_mask;
+
+    msg!(
+        "Session limits set: max_action_lamports={}, max_session_duration_secs={}, max_strategy_mask={:#05b}",
+        config.max_action_lamports,
+        config.max_session_duration_secs,
+        config.max_strategy_mask,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSessionLimits<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
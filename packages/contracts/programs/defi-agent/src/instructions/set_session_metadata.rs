@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::SessionMetadataUpdated;
+use crate::state::{AgentSession, MAX_METADATA_URI_LEN};
+
+/// [Base Layer] Name the session for wallets and explorers.
+///
+/// Signed by the session owner. `label` is a zero-padded UTF-8 display name
+/// ("Living-room ESP32 — SOL/USDC LP"); `metadata_uri` optionally points at
+/// richer off-chain metadata, up to `MAX_METADATA_URI_LEN` bytes, and an
+/// empty string clears it. Purely descriptive — nothing on-chain reads either.
+pub fn handler(
+    ctx: Context<SetSessionMetadata>,
+    label: [u8; 32],
+    metadata_uri: String,
+) -> Result<()> {
+    require!(is_padded_utf8(&label), AgentError::InvalidSessionMetadata);
+    require!(
+        metadata_uri.len() <= MAX_METADATA_URI_LEN && !metadata_uri.contains('\0'),
+        AgentError::InvalidSessionMetadata
+    );
+
+    let session = &mut ctx.accounts.session;
+    session.label = label;
+    session.metadata_uri = [0; MAX_METADATA_URI_LEN];
+    session.metadata_uri[..metadata_uri.len()].copy_from_slice(metadata_uri.as_bytes());
+
+    emit!(SessionMetadataUpdated {
+        session: session.key(),
+        label: session.label().to_string(),
+        metadata_uri,
+    });
+
+    msg!("Session label: {}", session.label());
+
+    Ok(())
+}
+
+/// UTF-8 text followed only by zero padding
+fn is_padded_utf8(bytes: &[u8]) -> bool {
+    let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    bytes[len..].iter().all(|b| *b == 0) && std::str::from_utf8(&bytes[..len]).is_ok()
+}
+
+#[derive(Accounts)]
+pub struct SetSessionMetadata<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
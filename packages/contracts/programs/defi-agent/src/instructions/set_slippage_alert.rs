@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Set the per-swap slippage bound that raises `SlippageExceeded`.
+///
+/// Signed by the session owner. Swaps are never rejected over it — the
+/// hard bound stays the caller's `min_amount_out` — but any swap whose output
+/// falls more than `max_slippage_bps` short of the pool's pre-trade quote
+/// emits the event for monitoring. 0 turns the alert off.
+pub fn handler(ctx: Context<SetSlippageAlert>, max_slippage_bps: u16) -> Result<()> {
+    require!(max_slippage_bps <= 10_000, AgentError::InvalidSlippageBps);
+    let session = &mut ctx.accounts.session;
+    session.max_slippage_bps = max_slippage_bps;
+
+    msg!(
+        "Session max_slippage_bps={}, slippage_paid={}",
+        session.max_slippage_bps,
+        session.slippage_paid,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSlippageAlert<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Register an enrolled device as a standby (failover) key.
+///
+/// Signed by the session owner. A standby key is rejected by every session
+/// instruction for as long as any regular device has heartbeated within the
+/// last `standby_after_secs`; once all regular devices have gone silent that
+/// long, the standby key becomes valid automatically. Any device-signed
+/// instruction (`update_lp_status`, the execute paths, `device_heartbeat`)
+/// counts as a heartbeat. Passing 0 turns the device back into a regular key.
+pub fn handler(
+    ctx: Context<SetStandbyDevice>,
+    device_key: Pubkey,
+    standby_after_secs: i64,
+) -> Result<()> {
+    require!(standby_after_secs >= 0, AgentError::InvalidStandbyDelay);
+
+    let session = &mut ctx.accounts.session;
+    let slot = session
+        .device_index(&device_key)
+        .ok_or(AgentError::DeviceNotEnrolled)?;
+    session.devices[slot].standby_after_secs = standby_after_secs;
+
+    msg!(
+        "Standby set: device={}, standby_after_secs={}",
+        device_key,
+        standby_after_secs,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetStandbyDevice<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
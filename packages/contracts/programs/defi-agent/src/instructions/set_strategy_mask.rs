@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::StrategiesRevoked;
+use crate::state::AgentSession;
+
+/// [Base Layer / Ephemeral Rollup] Narrow an active session's enabled
+/// strategies to `strategy_mask`.
+///
+/// Signed by the session owner. The new mask may only clear bits — scope
+/// only ever widens through `initialize_session` and its Config ceilings —
+/// so, like `disable_device_key`, it is safe to run on whichever layer holds
+/// the session. Spend already charged to a revoked strategy stays in
+/// `spent_by_strategy`; positions it opened are untouched.
+pub fn handler(ctx: Context<SetStrategyMask>, strategy_mask: u8) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    require!(session.is_active, AgentError::SessionInactive);
+    require!(
+        strategy_mask & !session.strategy_mask == 0,
+        AgentError::StrategyMaskWidened
+    );
+    let previous_mask = session.strategy_mask;
+    session.strategy_mask = strategy_mask;
+
+    emit!(StrategiesRevoked {
+        session: session.key(),
+        previous_mask,
+        strategy_mask,
+    });
+
+    msg!(
+        "Strategy mask narrowed: {:#010b} -> {:#010b}",
+        previous_mask,
+        strategy_mask,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetStrategyMask<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(mut, constraint = session.owner == owner.key())]
+    pub session: Account<'info, AgentSession>,
+}
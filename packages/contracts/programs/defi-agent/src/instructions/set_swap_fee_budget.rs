@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Cap the DLMM pool swap fees the session may pay.
+///
+/// Signed by the session owner. Every swap adds its estimated pool fee, in
+/// the pool's Y units, to `swap_fees_paid`; once the total would pass
+/// `swap_fee_budget` the swap fails. 0 (the default) leaves the fees
+/// uncapped but still tallied. Lowering the budget below `swap_fees_paid`
+/// simply blocks further swaps.
+pub fn handler(ctx: Context<SetSwapFeeBudget>, swap_fee_budget: u64) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    session.swap_fee_budget = swap_fee_budget;
+
+    msg!(
+        "Swap fee budget set: budget={}, paid={}",
+        session.swap_fee_budget,
+        session.swap_fees_paid,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSwapFeeBudget<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
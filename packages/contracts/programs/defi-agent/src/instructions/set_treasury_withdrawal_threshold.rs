@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::{threshold_tightens, Config, TreasuryLedger};
+use crate::errors::AgentError;
+
+/// [Base Layer] Tighten the large-withdrawal threshold for one treasury mint.
+///
+/// Admin-only. Withdrawals of this mint above `large_withdrawal_amount` must
+/// be queued with `queue_withdrawal`. Like the global policy, the threshold
+/// can only be lowered or enabled, never raised or disabled.
+pub fn handler(
+    ctx: Context<SetTreasuryWithdrawalThreshold>,
+    large_withdrawal_amount: u64,
+) -> Result<()> {
+    let ledger = &mut ctx.accounts.treasury_ledger;
+    require!(
+        threshold_tightens(ledger.large_withdrawal_amount, large_withdrawal_amount),
+        AgentError::WithdrawalPolicyLoosened
+    );
+    ledger.large_withdrawal_amount = large_withdrawal_amount;
+
+    msg!(
+        "Treasury withdrawal threshold set: mint={}, large_withdrawal_amount={}",
+        ledger.mint,
+        large_withdrawal_amount,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTreasuryWithdrawalThreshold<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Per-mint fee ledger
+    #[account(
+        mut,
+        seeds = [b"treasury_ledger", treasury_ledger.mint.as_ref()],
+        bump = treasury_ledger.bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// [Base Layer] Arm, re-arm or turn off the session's violation freeze.
+///
+/// Signed by the session owner. With `violation_threshold` > 0, that many
+/// consecutive scope violations by enrolled devices in `execute_action`
+/// suspend the session (`SuspiciousActivity`); 0 turns the freeze off.
+/// Either way the violation count is reset and a suspension lifted — after a
+/// freeze, disable or rotate the offending device first.
+pub fn handler(ctx: Context<SetViolationFreeze>, violation_threshold: u8) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let was_suspended = session.suspended;
+    session.violation_threshold = violation_threshold;
+    session.consecutive_violations = 0;
+    session.suspended = false;
+
+    msg!(
+        "Session violation_threshold={}, lifted_suspension={}",
+        session.violation_threshold,
+        was_suspended,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetViolationFreeze<'info> {
+    /// The wallet owner of the session
+    pub owner: Signer<'info>,
+
+    /// The owning AgentSession — validated to belong to `owner`
+    #[account(
+        mut,
+        constraint = session.owner == owner.key(),
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+    )]
+    pub session: Account<'info, AgentSession>,
+}
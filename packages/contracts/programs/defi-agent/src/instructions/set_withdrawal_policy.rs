@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::state::{threshold_tightens, Config};
+use crate::errors::AgentError;
+
+/// [Base Layer] Tighten the large-withdrawal time lock.
+///
+/// Admin-only. Sets the queue delay shared by all vaults and the lamport
+/// threshold for the fee vault. The policy can only be tightened (longer
+/// delay, lower threshold, or enabling it) — otherwise a compromised admin
+/// key could simply switch the lock off before draining.
+pub fn handler(
+    ctx: Context<SetWithdrawalPolicy>,
+    withdrawal_delay_secs: i64,
+    large_withdrawal_lamports: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(
+        withdrawal_delay_secs >= config.withdrawal_delay_secs
+            && threshold_tightens(config.large_withdrawal_lamports, large_withdrawal_lamports),
+        AgentError::WithdrawalPolicyLoosened
+    );
+
+    config.withdrawal_delay_secs = withdrawal_delay_secs;
+    config.large_withdrawal_lamports = large_withdrawal_lamports;
+
+    msg!(
+        "Withdrawal policy set: delay={}s, large_withdrawal_lamports={}",
+        withdrawal_delay_secs,
+        large_withdrawal_lamports,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetWithdrawalPolicy<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
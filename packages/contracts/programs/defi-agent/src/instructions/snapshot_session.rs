@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::events::SessionSnapshot;
+use crate::log_info;
+use crate::state::{
+    ActionKind, AgentSession, DailyStats, LpPositionMonitor, PositionRegistry, TemporalSource,
+};
+
+/// Emit a `SessionSnapshot` of the session's budget, PnL, positions, monitor
+/// and today's stats under the next snapshot sequence number. Runs on
+/// whichever layer currently holds the session (base layer, or the ER while
+/// delegated).
+///
+/// Signed by an enrolled device key. Everything in the snapshot is read in
+/// one instruction, so it is a consistent cut-point for off-chain archival —
+/// send it in the same transaction as `commit_session` to align archives with
+/// ER commits. The registry, monitor and stats accounts are optional; their
+/// parts of the snapshot are left empty when omitted.
+pub fn handler(ctx: Context<SnapshotSession>) -> Result<()> {
+    let clock = Clock::get()?;
+    let session = &mut ctx.accounts.session;
+
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::Housekeeping,
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    session.snapshot_seq = session
+        .snapshot_seq
+        .checked_add(1)
+        .ok_or(AgentError::Overflow)?;
+
+    let positions = match &ctx.accounts.position_registry {
+        Some(registry) => registry.load()?.active().iter().map(|p| p.position).collect(),
+        None => Vec::new(),
+    };
+    let (monitor_position, monitor_in_range, monitor_fee_x, monitor_fee_y) =
+        match &ctx.accounts.monitor {
+            Some(monitor) => {
+                let m = monitor.load()?;
+                (m.position, m.in_range(), m.fee_x_snapshot, m.fee_y_snapshot)
+            }
+            None => (Pubkey::default(), false, 0, 0),
+        };
+    let (today_actions, today_volume, today_fees_paid) = match &ctx.accounts.daily_stats {
+        Some(stats) => stats
+            .load()?
+            .get(DailyStats::day_of(clock.unix_timestamp))
+            .map_or((0, 0, 0), |b| (b.actions, b.volume, b.fees_paid)),
+        None => (0, 0, 0),
+    };
+
+    emit!(SessionSnapshot {
+        session: session.key(),
+        seq: session.snapshot_seq,
+        taken_at: clock.unix_timestamp,
+        is_active: session.is_active,
+        expires_at: session.expires_at,
+        max_lamports: session.max_lamports,
+        spent_lamports: session.spent_lamports,
+        spent_by_strategy: session.spent_by_strategy,
+        fee_budget_lamports: session.fee_budget_lamports,
+        fee_spent_lamports: session.fee_spent_lamports,
+        protocol_fees_paid: session.protocol_fees_paid,
+        total_actions: session.total_actions,
+        realized_pnl: session.realized_pnl,
+        total_fees_earned_x: session.total_fees_earned_x,
+        total_fees_earned_y: session.total_fees_earned_y,
+        positions,
+        monitor_position,
+        monitor_in_range,
+        monitor_fee_x,
+        monitor_fee_y,
+        today_actions,
+        today_volume,
+        today_fees_paid,
+    });
+
+    log_info!(session, "Session snapshot: seq={}", session.snapshot_seq);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SnapshotSession<'info> {
+    /// The device key — must sign
+    pub session_key: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The session's PositionRegistry — its live positions are listed
+    #[account(
+        seeds = [b"position_registry", session.key().as_ref()],
+        bump = position_registry.load()?.bump,
+    )]
+    pub position_registry: Option<AccountLoader<'info, PositionRegistry>>,
+
+    /// The session's LpPositionMonitor — its last checkpoint is included
+    #[account(
+        seeds = [b"lp_monitor", session.key().as_ref()],
+        bump = monitor.load()?.bump,
+    )]
+    pub monitor: Option<AccountLoader<'info, LpPositionMonitor>>,
+
+    /// The session's DailyStats — today's totals are included
+    #[account(
+        seeds = [b"daily_stats", session.key().as_ref()],
+        bump = daily_stats.load()?.bump,
+    )]
+    pub daily_stats: Option<AccountLoader<'info, DailyStats>>,
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
+use crate::errors::AgentError;
+use crate::instructions::undelegate_session::close_out;
+use crate::state::{magic_context_for, magic_program_for, AgentSession, Config, PoolRegistry};
+
+/// Offset of the `session` field every session-scoped account stores first,
+/// right after the discriminator
+const SESSION_FIELD: usize = 8;
+
+/// Commits final state and returns the AgentSession together with every other
+/// delegated account of the session to Solana mainnet, in one instruction.
+/// Must be sent to the EPHEMERAL ROLLUP.
+///
+/// Behaves as `undelegate_session` (final valuation, deactivation) and also
+/// commits and undelegates each account in `remaining_accounts` — the
+/// session's receipt log, and any monitor or ledger delegated with it — so
+/// teardown cannot leave delegated state behind. Each must be writable,
+/// listed once, owned by this program and belong to the session (see
+/// `check_session_accounts`). Since this deactivates the session and pulls
+/// its monitors off the rollup, `payer` must be the owner or an enrolled
+/// device.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, UndelegateAll<'info>>,
+) -> Result<()> {
+    let session_key = ctx.accounts.session.key();
+    check_session_accounts(&session_key, ctx.remaining_accounts)?;
+    close_out(
+        &mut ctx.accounts.session,
+        ctx.accounts.pool_registry.as_deref(),
+        ctx.accounts.valuation_lb_pair.as_ref(),
+        ctx.accounts.valuation_token_x.as_ref(),
+        ctx.accounts.valuation_token_y.as_ref(),
+    )?;
+
+    let session = ctx.accounts.session.to_account_info();
+    let mut accounts = vec![&session];
+    accounts.extend(ctx.remaining_accounts.iter());
+    commit_and_undelegate_accounts(
+        &ctx.accounts.payer,
+        accounts,
+        &ctx.accounts.magic_context,
+        &ctx.accounts.magic_program,
+    )?;
+
+    msg!(
+        "Session undelegated and closed with {} account(s)",
+        ctx.remaining_accounts.len(),
+    );
+    Ok(())
+}
+
+/// Require each of `accounts` to be a distinct, writable account owned by
+/// this program whose leading `session` field is `session` — the layout every
+/// session-scoped PDA shares
+pub fn check_session_accounts(session: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+    for (i, account) in accounts.iter().enumerate() {
+        require!(account.is_writable, AgentError::InvalidSessionAccount);
+        require_keys_eq!(*account.owner, crate::ID, AgentError::InvalidSessionAccount);
+        require!(
+            !accounts[..i].iter().any(|other| other.key == account.key),
+            AgentError::InvalidSessionAccount
+        );
+        let data = account.try_borrow_data()?;
+        let owner = data.get(SESSION_FIELD..SESSION_FIELD + 32);
+        require!(owner == Some(session.as_ref()), AgentError::InvalidSessionAccount);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UndelegateAll<'info> {
+    /// Session owner or an enrolled device key
+    #[account(
+        mut,
+        constraint = session.is_owner_or_device(&payer.key()) @ AgentError::UnauthorizedTeardown,
+    )]
+    pub payer: Signer<'info>,
+
+    #[account(mut, constraint = session.is_delegated() @ AgentError::SessionNotDelegated)]
+    pub session: Account<'info, AgentSession>,
+
+    /// CHECK: Optional DLMM pool pricing the final valuation (loaded as LbPair)
+    pub valuation_lb_pair: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional device token account for the pool's X mint
+    pub valuation_token_x: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional device token account for the pool's Y mint
+    pub valuation_token_y: Option<UncheckedAccount<'info>>,
+
+    /// Global PoolRegistry — vouches for a registered `valuation_lb_pair`
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    /// Global Config PDA — when passed, its magic program/context overrides apply
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Option<Account<'info, Config>>,
+
+    /// CHECK: MagicBlock magic context — the SDK's unless the Config overrides it
+    #[account(mut, address = magic_context_for(config.as_deref()))]
+    pub magic_context: UncheckedAccount<'info>,
+
+    /// CHECK: MagicBlock magic program — the SDK's unless the Config overrides it
+    #[account(address = magic_program_for(config.as_deref()))]
+    pub magic_program: UncheckedAccount<'info>,
+}
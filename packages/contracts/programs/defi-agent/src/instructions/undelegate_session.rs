@@ -1,21 +1,40 @@
 use anchor_lang::prelude::*;
-use ephemeral_rollups_sdk::anchor::commit;
 use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
-use crate::state::AgentSession;
+use crate::errors::AgentError;
+use crate::state::{
+    magic_context_for, magic_program_for, ActionReceiptLog, AgentSession, Config, PoolRegistry,
+    DELEGATION_UNDELEGATED,
+};
+use crate::valuation::{self, VALUATION_UNDELEGATE};
 
 /// Commits final state and returns the AgentSession account to Solana mainnet.
 /// Must be sent to the EPHEMERAL ROLLUP.
 ///
 /// After this, the session is no longer active and the account owner reverts
-/// to our program. The user must call initialize_session + delegate_session
-/// again to start a new session.
+/// to our program. The owner starts a new term with `renew_session` (then
+/// `delegate_session` again), keeping the session's stats and monitors.
+///
+/// Passing the `valuation_*` accounts records a final portfolio valuation, so
+/// the committed `realized_pnl` covers the whole session. The pool must be
+/// registered (pass `pool_registry`) or one of the owner's budget price pools. A delegated
+/// ActionReceiptLog must be passed so it is committed and returned with the
+/// session.
 pub fn handler(ctx: Context<UndelegateSession>) -> Result<()> {
-    // Deactivate before undelegating so the final committed state reflects this
-    ctx.accounts.session.is_active = false;
+    close_out(
+        &mut ctx.accounts.session,
+        ctx.accounts.pool_registry.as_deref(),
+        ctx.accounts.valuation_lb_pair.as_ref(),
+        ctx.accounts.valuation_token_x.as_ref(),
+        ctx.accounts.valuation_token_y.as_ref(),
+    )?;
 
+    let session = ctx.accounts.session.to_account_info();
+    let receipt_log = ctx.accounts.receipt_log.as_ref().map(|log| log.to_account_info());
+    let mut accounts = vec![&session];
+    accounts.extend(receipt_log.as_ref());
     commit_and_undelegate_accounts(
         &ctx.accounts.payer,
-        vec![&ctx.accounts.session.to_account_info()],
+        accounts,
         &ctx.accounts.magic_context,
         &ctx.accounts.magic_program,
     )?;
@@ -24,12 +43,63 @@ pub fn handler(ctx: Context<UndelegateSession>) -> Result<()> {
     Ok(())
 }
 
-#[commit]
+/// Record the optional final valuation and deactivate the session — before
+/// undelegating, so the final committed state reflects both
+pub fn close_out(
+    session: &mut Account<AgentSession>,
+    pool_registry: Option<&PoolRegistry>,
+    valuation_lb_pair: Option<&UncheckedAccount>,
+    valuation_token_x: Option<&UncheckedAccount>,
+    valuation_token_y: Option<&UncheckedAccount>,
+) -> Result<()> {
+    valuation::record_optional(
+        session,
+        pool_registry,
+        valuation_lb_pair,
+        valuation_token_x,
+        valuation_token_y,
+        VALUATION_UNDELEGATE,
+        Clock::get()?.unix_timestamp,
+    )?;
+    session.is_active = false;
+    session.delegation_status = DELEGATION_UNDELEGATED;
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct UndelegateSession<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut, constraint = session.is_delegated() @ AgentError::SessionNotDelegated)]
     pub session: Account<'info, AgentSession>,
+
+    /// CHECK: Optional DLMM pool pricing the final valuation (loaded as LbPair)
+    pub valuation_lb_pair: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional device token account for the pool's X mint
+    pub valuation_token_x: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional device token account for the pool's Y mint
+    pub valuation_token_y: Option<UncheckedAccount<'info>>,
+
+    /// Global PoolRegistry — vouches for a registered `valuation_lb_pair`
+    #[account(seeds = [b"pool_registry"], bump = pool_registry.bump)]
+    pub pool_registry: Option<Account<'info, PoolRegistry>>,
+
+    /// The session's delegated ActionReceiptLog, undelegated alongside it
+    #[account(mut, seeds = [b"receipts", session.key().as_ref()], bump = receipt_log.load()?.bump)]
+    pub receipt_log: Option<AccountLoader<'info, ActionReceiptLog>>,
+
+    /// Global Config PDA — when passed, its magic program/context overrides apply
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Option<Account<'info, Config>>,
+
+    /// CHECK: MagicBlock magic context — the SDK's unless the Config overrides it
+    #[account(mut, address = magic_context_for(config.as_deref()))]
+    pub magic_context: UncheckedAccount<'info>,
+
+    /// CHECK: MagicBlock magic program — the SDK's unless the Config overrides it
+    #[account(address = magic_program_for(config.as_deref()))]
+    pub magic_program: UncheckedAccount<'info>,
 }
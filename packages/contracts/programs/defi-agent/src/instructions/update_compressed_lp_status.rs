@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use crate::spl_account_compression;
+use crate::state::{
+    ActionKind, AgentSession, CompressedMonitorLeaf, CompressedMonitorTree, TemporalSource,
+};
+use crate::errors::AgentError;
+use crate::events::CompressedMonitorUpdated;
+use crate::log_info;
+use crate::valuation;
+
+/// [Base Layer] Checkpoint a compressed LP monitor.
+///
+/// Compressed counterpart of `update_lp_status`. The device passes the
+/// monitor's current leaf (from the latest `CompressedMonitorUpdated` event),
+/// the `root` its proof was built against and, as remaining accounts, the
+/// proof nodes below the tree's canopy. The program applies the fee snapshot
+/// and the active bin read from the leaf's `lb_pair`, and swaps the leaf
+/// through the account compression program's `replace_leaf`, which accepts
+/// any root still in the changelog buffer. Emits the updated leaf and records
+/// the signing device's heartbeat.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, UpdateCompressedLpStatus<'info>>,
+    leaf_index: u32,
+    leaf: CompressedMonitorLeaf,
+    root: [u8; 32],
+    fee_x: u64,
+    fee_y: u64,
+) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    // Session must still be valid for the session key to act
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::LpMonitor,
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+
+    require_keys_eq!(ctx.accounts.lb_pair.key(), leaf.lb_pair, AgentError::MonitorPositionMismatch);
+    let active_bin = valuation::active_bin(&ctx.accounts.lb_pair.to_account_info())?;
+    let was_in_range = leaf.is_in_range;
+    let now_in_range = leaf.check_in_range(active_bin);
+    let updated = CompressedMonitorLeaf {
+        last_active_bin: active_bin,
+        is_in_range: now_in_range,
+        fee_x_snapshot: fee_x,
+        fee_y_snapshot: fee_y,
+        last_checked_at: clock.unix_timestamp,
+        ..leaf
+    };
+
+    let tree = &ctx.accounts.tree;
+    let session_key = session.key();
+    let seeds: &[&[u8]] = &[b"monitor_tree", session_key.as_ref(), &[tree.bump]];
+    spl_account_compression::cpi::replace_leaf(
+        CpiContext::new_with_signer(
+            ctx.accounts.compression_program.to_account_info(),
+            spl_account_compression::cpi::accounts::ReplaceLeaf {
+                merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+                authority: tree.to_account_info(),
+                noop: ctx.accounts.noop_program.to_account_info(),
+            },
+            &[seeds],
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        root,
+        leaf.hash(),
+        updated.hash(),
+        leaf_index,
+    )?;
+
+    emit!(CompressedMonitorUpdated {
+        tree: tree.key(),
+        leaf_index,
+        leaf: updated,
+    });
+
+    if was_in_range && !now_in_range {
+        msg!(
+            "ALERT: LP position out of range! position={}, active_bin={}, range=[{}, {}]",
+            leaf.position,
+            active_bin,
+            leaf.min_bin_id,
+            leaf.max_bin_id,
+        );
+    }
+
+    log_info!(
+        session,
+        "Compressed LP status: index={}, active_bin={}, in_range={}",
+        leaf_index,
+        active_bin,
+        now_in_range,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateCompressedLpStatus<'info> {
+    /// The ESP32 session key — must sign this checkpoint transaction
+    pub session_key: Signer<'info>,
+
+    /// The owning AgentSession — used to validate session_key and liveness;
+    /// mutable so the check-in is recorded as the device's heartbeat
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
+    pub session: Account<'info, AgentSession>,
+
+    /// The session's CompressedMonitorTree — signs the replace as tree authority
+    #[account(
+        seeds = [b"monitor_tree", session.key().as_ref()],
+        bump = tree.bump,
+    )]
+    pub tree: Account<'info, CompressedMonitorTree>,
+
+    #[account(mut, address = tree.merkle_tree)]
+    /// CHECK: The tree's ConcurrentMerkleTree account — the leaf is replaced here
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(address = spl_account_compression::ID)]
+    /// CHECK: SPL account compression program
+    pub compression_program: UncheckedAccount<'info>,
+
+    #[account(address = crate::SPL_NOOP_ID)]
+    /// CHECK: SPL noop program
+    pub noop_program: UncheckedAccount<'info>,
+
+    /// CHECK: DLMM pool of the leaf's position — must be the leaf's lb_pair;
+    /// its active bin is read here
+    pub lb_pair: UncheckedAccount<'info>,
+}
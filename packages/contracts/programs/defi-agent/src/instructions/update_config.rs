@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, MAX_PROTOCOL_FEE_BPS};
+use crate::errors::AgentError;
+
+/// [Base Layer] Update the protocol fee and default session limits.
+///
+/// Admin-only. Changes apply to sessions created after this call; existing
+/// sessions keep the limits they were initialized with.
+pub fn handler(
+    ctx: Context<UpdateConfig>,
+    protocol_fee_bps: u16,
+    default_max_lamports: u64,
+    default_duration_secs: i64,
+) -> Result<()> {
+    require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, AgentError::InvalidFeeBps);
+    require!(default_duration_secs > 0, AgentError::InvalidDuration);
+
+    let config = &mut ctx.accounts.config;
+    config.protocol_fee_bps = protocol_fee_bps;
+    config.default_max_lamports = default_max_lamports;
+    config.default_duration_secs = default_duration_secs;
+
+    msg!(
+        "Config updated: protocol_fee_bps={}, default_max_lamports={}, default_duration_secs={}",
+        config.protocol_fee_bps,
+        config.default_max_lamports,
+        config.default_duration_secs,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// Config admin — must sign
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — validated to be administered by `admin`
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
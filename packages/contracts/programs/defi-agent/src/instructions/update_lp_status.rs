@@ -1,50 +1,84 @@
 use anchor_lang::prelude::*;
-use crate::state::{AgentSession, LpPositionMonitor};
+use crate::state::{ActionKind, AgentSession, LpPositionMonitor, TemporalSource};
 use crate::errors::AgentError;
+use crate::alerts;
+use crate::events::OutOfRangeAlert;
+use crate::introspection::signature_fee_lamports;
+use crate::log_info;
+use crate::valuation;
 
 /// [Base Layer] Checkpoint the current LP position status on-chain.
 ///
-/// Called by the ESP32 session key after reading the DLMM pool state off-chain.
-/// The caller passes the current `active_bin` (from `getActiveBin()`) and
-/// unclaimed fee amounts (from `getPositionsByUserAndLbPair()`).
+/// Called by the ESP32 session key after reading the position off-chain. The
+/// pool's active bin is read from the `lb_pair` account itself, so range
+/// tracking and the alerts built on it don't trust the device; the caller
+/// passes only the position's unclaimed fee and token amounts (from
+/// `getPositionsByUserAndLbPair()`). The position and lb_pair accounts must be
+/// the ones the monitor was registered for, so a checkpoint can't be written
+/// about a different position. The session must have the LP strategy enabled.
 ///
 /// Updates:
 ///   • `last_active_bin` — what the pool's active bin was
 ///   • `is_in_range`     — whether active_bin ∈ [min_bin_id, max_bin_id]
 ///   • `fee_x_snapshot` / `fee_y_snapshot` — current unclaimed fees
+///   • `amount_x_snapshot` / `amount_y_snapshot` — current position size
 ///   • `last_checked_at` — current slot timestamp
+///   • `out_of_range_since` — start of the current out-of-range excursion
+///   • the signing device's `last_seen_at` heartbeat
+///   • `tx_fees_lamports`, when the instructions sysvar is passed
 ///
-/// Logs a warning when the position transitions out of range, giving the
-/// agent an on-chain signal it can relay to the mobile app.
+/// Emits `OutOfRangeAlert` when the position transitions out of range, giving
+/// the agent an on-chain signal it can relay to the mobile app (see `notifier`),
+/// and the owner's configured `OutOfRangeTooLong` / `FeeThresholdReached`
+/// alerts when their thresholds are passed.
 pub fn handler(
     ctx: Context<UpdateLpStatus>,
-    active_bin: i32,
     fee_x: u64,
     fee_y: u64,
+    amount_x: u64,
+    amount_y: u64,
 ) -> Result<()> {
-    let session = &ctx.accounts.session;
+    let session = &mut ctx.accounts.session;
     let clock = Clock::get()?;
 
     // Session must still be valid for the session key to act
-    require!(session.is_active, AgentError::SessionInactive);
-    require!(!session.is_expired(clock.unix_timestamp), AgentError::SessionExpired);
-    require_keys_eq!(
-        ctx.accounts.session_key.key(),
-        session.session_key,
-        AgentError::UnauthorizedSessionKey,
-    );
+    session.validate_session(
+        &ctx.accounts.session_key.key(),
+        ActionKind::LpMonitor,
+        TemporalSource::Device(clock.unix_timestamp),
+    )?;
+    if let Some(ix_sysvar) = &ctx.accounts.instructions_sysvar {
+        session.record_tx_fee(signature_fee_lamports(ix_sysvar)?)?;
+    }
 
-    let monitor = &mut ctx.accounts.monitor;
-    let was_in_range = monitor.is_in_range;
+    let active_bin = valuation::active_bin(&ctx.accounts.lb_pair.to_account_info())?;
+    let mut monitor = ctx.accounts.monitor.load_mut()?;
+    let was_in_range = monitor.in_range();
     let now_in_range = monitor.check_in_range(active_bin);
 
     monitor.last_active_bin = active_bin;
-    monitor.is_in_range = now_in_range;
+    monitor.is_in_range = now_in_range as u8;
     monitor.fee_x_snapshot = fee_x;
     monitor.fee_y_snapshot = fee_y;
+    monitor.amount_x_snapshot = amount_x;
+    monitor.amount_y_snapshot = amount_y;
+    let previous_checked_at = monitor.last_checked_at;
     monitor.last_checked_at = clock.unix_timestamp;
+    monitor.out_of_range_since = match (now_in_range, monitor.out_of_range_since) {
+        (true, _) => 0,
+        (false, 0) => clock.unix_timestamp,
+        (false, since) => since,
+    };
 
     if was_in_range && !now_in_range {
+        emit!(OutOfRangeAlert {
+            session: session.key(),
+            position: monitor.position,
+            lb_pair: monitor.lb_pair,
+            active_bin,
+            min_bin_id: monitor.min_bin_id,
+            max_bin_id: monitor.max_bin_id,
+        });
         msg!(
             "ALERT: LP position out of range! active_bin={}, range=[{}, {}]",
             active_bin,
@@ -52,13 +86,18 @@ pub fn handler(
             monitor.max_bin_id,
         );
     }
+    alerts::check_out_of_range(session, &monitor, previous_checked_at);
+    alerts::check_thresholds(session);
 
-    msg!(
-        "LP status: active_bin={}, in_range={}, fee_x={}, fee_y={}",
+    log_info!(
+        session,
+        "LP status: active_bin={}, in_range={}, fee_x={}, fee_y={}, amount_x={}, amount_y={}",
         active_bin,
         now_in_range,
         fee_x,
         fee_y,
+        amount_x,
+        amount_y,
     );
 
     Ok(())
@@ -69,15 +108,37 @@ pub struct UpdateLpStatus<'info> {
     /// The ESP32 session key — must sign this checkpoint transaction
     pub session_key: Signer<'info>,
 
-    /// The owning AgentSession — used to validate session_key and liveness
+    /// The owning AgentSession — used to validate session_key and liveness;
+    /// mutable so the check-in is recorded as the device's heartbeat
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref()],
+        bump = session.bump,
+        constraint = !session.is_delegated() @ AgentError::SessionDelegated,
+        constraint = session.fee_payer != session_key.key() @ AgentError::FeePayerCannotSign,
+    )]
     pub session: Account<'info, AgentSession>,
 
     /// LpPositionMonitor PDA to update — must belong to `session`
     #[account(
         mut,
         seeds = [b"lp_monitor", session.key().as_ref()],
-        bump = monitor.bump,
-        constraint = monitor.session == session.key(),
+        bump = monitor.load()?.bump,
+        constraint = monitor.load()?.session == session.key(),
     )]
-    pub monitor: Account<'info, LpPositionMonitor>,
+    pub monitor: AccountLoader<'info, LpPositionMonitor>,
+
+    /// CHECK: DLMM position being reported on — must be the one the monitor tracks
+    #[account(address = monitor.load()?.position @ AgentError::MonitorPositionMismatch)]
+    pub position: UncheckedAccount<'info>,
+
+    /// CHECK: DLMM pool of that position — must be the monitor's lb_pair;
+    /// its active bin is read here
+    #[account(address = monitor.load()?.lb_pair @ AgentError::MonitorPositionMismatch)]
+    pub lb_pair: UncheckedAccount<'info>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: Optional instructions sysvar — when passed, the transaction's
+    /// signature fees are counted into `tx_fees_lamports`
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{enforce_withdrawal_timelock, Config, PendingWithdrawal, NATIVE_ASSET};
+use crate::errors::AgentError;
+
+/// [Base Layer] Withdraw accrued per-action fees from the fee vault PDA.
+///
+/// Admin-only. The vault is a system-owned PDA, so the program signs the
+/// transfer with its seeds.
+///
+/// Once unqueued withdrawals within one `withdrawal_delay_secs` window would
+/// exceed `Config.large_withdrawal_lamports`, the amount must have been queued
+/// with `queue_withdrawal` (asset = `NATIVE_ASSET`) and waited out the delay;
+/// the matching `pending_withdrawal` is consumed.
+pub fn handler(ctx: Context<WithdrawFeeVault>, amount: u64) -> Result<()> {
+    require!(
+        amount <= ctx.accounts.fee_vault.lamports(),
+        AgentError::InsufficientTreasuryBalance
+    );
+    let config = &mut ctx.accounts.config;
+    enforce_withdrawal_timelock(
+        config.large_withdrawal_lamports,
+        config.withdrawal_delay_secs,
+        &mut config.fee_vault_withdrawal_window,
+        amount,
+        &ctx.accounts.destination.key(),
+        ctx.accounts.pending_withdrawal.as_deref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let vault_bump = [ctx.bumps.fee_vault];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"fee_vault", &vault_bump]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    msg!("Fee vault withdrawal: amount={}", amount);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFeeVault<'info> {
+    /// Config admin — must sign, receives the rent of a consumed pending entry
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — administered by `admin`, tracks the withdrawal window
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Protocol fee vault (system-owned PDA) — source of the withdrawal
+    #[account(mut, seeds = [b"fee_vault"], bump)]
+    pub fee_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Any lamport recipient chosen by the admin
+    pub destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Queued fee-vault withdrawal — required once the window passes the threshold
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_withdrawal", NATIVE_ASSET.as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Option<Account<'info, PendingWithdrawal>>,
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{enforce_withdrawal_timelock, Config, PendingWithdrawal, TreasuryLedger};
+use crate::errors::AgentError;
+
+/// [Base Layer] Withdraw accrued protocol fees from the treasury.
+///
+/// Admin-only. Transfers `amount` of the ledger's mint from the treasury
+/// token account to `destination`, signed by the Config PDA, and records the
+/// withdrawal on the per-mint ledger.
+///
+/// Once unqueued withdrawals within one `withdrawal_delay_secs` window would
+/// exceed the ledger's `large_withdrawal_amount`, the amount must have been
+/// queued with `queue_withdrawal` and waited out the delay; the matching
+/// `pending_withdrawal` is consumed.
+pub fn handler(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require!(
+        amount <= ctx.accounts.treasury.amount,
+        AgentError::InsufficientTreasuryBalance
+    );
+    let ledger = &mut ctx.accounts.treasury_ledger;
+    enforce_withdrawal_timelock(
+        ledger.large_withdrawal_amount,
+        ctx.accounts.config.withdrawal_delay_secs,
+        &mut ledger.withdrawal_window,
+        amount,
+        &ctx.accounts.destination.key(),
+        ctx.accounts.pending_withdrawal.as_deref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let config_bump = [ctx.accounts.config.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[b"config", &config_bump]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.treasury.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.config.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let ledger = &mut ctx.accounts.treasury_ledger;
+    ledger.record_withdrawal(amount)?;
+
+    msg!(
+        "Treasury withdrawal: mint={}, amount={}, accrued={}, withdrawn={}",
+        ledger.mint,
+        amount,
+        ledger.total_accrued,
+        ledger.total_withdrawn,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    /// Config admin — must sign, receives the rent of a consumed pending entry
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Global Config PDA — treasury authority, signs the transfer
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ AgentError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Mint being withdrawn
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Per-mint fee ledger
+    #[account(
+        mut,
+        seeds = [b"treasury_ledger", mint.key().as_ref()],
+        bump = treasury_ledger.bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+
+    /// Treasury token account — source of the withdrawal
+    #[account(mut, address = treasury_ledger.vault @ AgentError::InvalidTreasury)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient token account chosen by the admin
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Queued withdrawal for this mint — required once the window passes the threshold
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending_withdrawal", mint.key().as_ref()],
+        bump = pending_withdrawal.bump,
+    )]
+    pub pending_withdrawal: Option<Account<'info, PendingWithdrawal>>,
+}
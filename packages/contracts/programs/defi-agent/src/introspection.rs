@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+use crate::errors::AgentError;
+use crate::events::ActionMemo;
+use crate::state::AgentSession;
+
+/// Compute Budget program — SetComputeUnitLimit / SetComputeUnitPrice live here.
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// Native Ed25519 signature-verification program.
+pub const ED25519_PROGRAM_ID: Pubkey =
+    pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+/// SPL Memo program (v2) — a memo instruction in an execute transaction
+/// annotates the action (`emit_action_memo`).
+pub const MEMO_PROGRAM_ID: Pubkey =
+    pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Longest memo carried into `ActionMemo`, in bytes
+pub const MAX_ACTION_MEMO_LEN: usize = 256;
+
+/// Ed25519 instruction layout: [num_signatures u8, padding u8] followed by
+/// one 14-byte offsets record (7 × u16) per signature.
+const ED25519_HEADER_LEN: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+const ED25519_PUBKEY_LEN: usize = 32;
+/// Instruction index meaning "data lives in this same ed25519 instruction".
+const ED25519_CURRENT_IX: u16 = u16::MAX;
+
+/// ComputeBudgetInstruction discriminants (Borsh enum index, 1 byte)
+const IX_SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const IX_SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// SystemInstruction::Transfer discriminant (bincode enum index, 4 bytes LE)
+const SYSTEM_IX_TRANSFER: u32 = 2;
+
+/// Base fee the runtime charges per transaction signature, including each
+/// signature an Ed25519 precompile instruction verifies
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Runtime defaults used when the transaction carries no SetComputeUnitLimit
+const DEFAULT_CU_PER_INSTRUCTION: u64 = 200_000;
+const MAX_CU_PER_TRANSACTION: u64 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Jito block-engine tip accounts. A system transfer from the session key to
+/// any of these is counted as operational (tip) spend.
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// Number of top-level instructions in the current transaction.
+fn instruction_count(ix_sysvar: &AccountInfo) -> Result<usize> {
+    let data = ix_sysvar.try_borrow_data()?;
+    require!(data.len() >= 2, AgentError::InvalidInstructionsSysvar);
+    Ok(u16::from_le_bytes([data[0], data[1]]) as usize)
+}
+
+fn read_u32(bytes: &[u8]) -> Option<u32> {
+    bytes.get(..4)?.try_into().ok().map(u32::from_le_bytes)
+}
+
+fn read_u64(bytes: &[u8]) -> Option<u64> {
+    bytes.get(..8)?.try_into().ok().map(u64::from_le_bytes)
+}
+
+fn read_u16(bytes: &[u8]) -> Option<u16> {
+    bytes.get(..2)?.try_into().ok().map(u16::from_le_bytes)
+}
+
+/// Minimum operational spend (priority fee + Jito tips) the current transaction
+/// commits `payer` to, derived from the instructions sysvar.
+///
+/// Priority fee = ceil(cu_price_micro_lamports × cu_limit / 1_000_000). When no
+/// SetComputeUnitLimit is present the runtime default (200k per non-budget
+/// instruction, capped at 1.4M) is used. The caller's declared amount must be
+/// at least this value — the device may declare more (e.g. tips routed through
+/// a separate bundle transaction) but never less.
+pub fn operational_fee_lamports(ix_sysvar: &AccountInfo, payer: &Pubkey) -> Result<u64> {
+    let count = instruction_count(ix_sysvar)?;
+
+    let mut cu_limit: Option<u64> = None;
+    let mut cu_price: u64 = 0;
+    let mut non_budget_ixs: u64 = 0;
+    let mut tips: u64 = 0;
+
+    for index in 0..count {
+        let ix = load_instruction_at_checked(index, ix_sysvar)?;
+
+        if ix.program_id == COMPUTE_BUDGET_PROGRAM_ID {
+            match ix.data.split_first() {
+                Some((&IX_SET_COMPUTE_UNIT_LIMIT, rest)) => {
+                    cu_limit = read_u32(rest).map(u64::from);
+                }
+                Some((&IX_SET_COMPUTE_UNIT_PRICE, rest)) => {
+                    cu_price = read_u64(rest).unwrap_or(0);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        non_budget_ixs += 1;
+
+        let is_tip = ix.program_id == anchor_lang::system_program::ID
+            && read_u32(&ix.data) == Some(SYSTEM_IX_TRANSFER)
+            && ix.accounts.len() >= 2
+            && ix.accounts[0].pubkey == *payer
+            && JITO_TIP_ACCOUNTS.contains(&ix.accounts[1].pubkey);
+        if is_tip {
+            let lamports = ix.data.get(4..).and_then(read_u64).unwrap_or(0);
+            tips = tips.checked_add(lamports).ok_or(AgentError::Overflow)?;
+        }
+    }
+
+    let limit = cu_limit.unwrap_or_else(|| {
+        non_budget_ixs
+            .saturating_mul(DEFAULT_CU_PER_INSTRUCTION)
+            .min(MAX_CU_PER_TRANSACTION)
+    });
+    let priority_fee = (cu_price as u128)
+        .checked_mul(limit as u128)
+        .ok_or(AgentError::Overflow)?
+        .div_ceil(MICRO_LAMPORTS_PER_LAMPORT);
+    let priority_fee = u64::try_from(priority_fee).map_err(|_| AgentError::Overflow)?;
+
+    let total = priority_fee.checked_add(tips).ok_or(AgentError::Overflow)?;
+    Ok(total)
+}
+
+/// Signature fees the current transaction pays, at `LAMPORTS_PER_SIGNATURE`.
+///
+/// Signatures are counted as the distinct signers across the top-level
+/// instructions (at least one — the fee payer) plus the signatures verified
+/// by Ed25519 precompile instructions. A fee payer that signs none of the
+/// instructions is only counted when nothing else signs, so the figure may
+/// understate by one signature, never overstate.
+pub fn signature_fee_lamports(ix_sysvar: &AccountInfo) -> Result<u64> {
+    let count = instruction_count(ix_sysvar)?;
+
+    let mut signers: Vec<Pubkey> = Vec::new();
+    let mut precompile_signatures: u64 = 0;
+    for index in 0..count {
+        let ix = load_instruction_at_checked(index, ix_sysvar)?;
+        if ix.program_id == ED25519_PROGRAM_ID {
+            precompile_signatures += ix.data.first().copied().map_or(0, u64::from);
+        }
+        for meta in ix.accounts.iter().filter(|m| m.is_signer) {
+            if !signers.contains(&meta.pubkey) {
+                signers.push(meta.pubkey);
+            }
+        }
+    }
+
+    let signatures = (signers.len() as u64).max(1) + precompile_signatures;
+    signatures
+        .checked_mul(LAMPORTS_PER_SIGNATURE)
+        .ok_or(AgentError::Overflow.into())
+}
+
+/// Compute-unit limit the current transaction requested via SetComputeUnitLimit,
+/// or the runtime default (200k per non-budget instruction, capped at 1.4M).
+pub fn requested_compute_units(ix_sysvar: &AccountInfo) -> Result<u64> {
+    let count = instruction_count(ix_sysvar)?;
+
+    let mut cu_limit: Option<u64> = None;
+    let mut non_budget_ixs: u64 = 0;
+    for index in 0..count {
+        let ix = load_instruction_at_checked(index, ix_sysvar)?;
+        if ix.program_id != COMPUTE_BUDGET_PROGRAM_ID {
+            non_budget_ixs += 1;
+            continue;
+        }
+        if let Some((&IX_SET_COMPUTE_UNIT_LIMIT, rest)) = ix.data.split_first() {
+            cu_limit = read_u32(rest).map(u64::from);
+        }
+    }
+
+    Ok(cu_limit.unwrap_or_else(|| {
+        non_budget_ixs
+            .saturating_mul(DEFAULT_CU_PER_INSTRUCTION)
+            .min(MAX_CU_PER_TRANSACTION)
+    }))
+}
+
+/// Fail fast with `InsufficientComputeBudget` when the transaction requested
+/// fewer than `min_units` compute units. `min_units == 0` disables the guard.
+pub fn require_compute_budget(ix_sysvar: &AccountInfo, min_units: u32) -> Result<()> {
+    if min_units == 0 {
+        return Ok(());
+    }
+    let requested = requested_compute_units(ix_sysvar)?;
+    require!(
+        requested >= u64::from(min_units),
+        AgentError::InsufficientComputeBudget
+    );
+    Ok(())
+}
+
+/// Reject the call when `declared_lamports` understates the priority fee / tips
+/// that the current transaction actually commits `payer` to.
+pub fn verify_declared_fee(
+    ix_sysvar: &AccountInfo,
+    payer: &Pubkey,
+    declared_lamports: u64,
+) -> Result<()> {
+    let observed = operational_fee_lamports(ix_sysvar, payer)?;
+    require!(declared_lamports >= observed, AgentError::FeeUnderDeclared);
+    Ok(())
+}
+
+/// Canonical intent payload signed by a device's secure-element certificate key.
+///
+/// Layout (88 bytes): session (32) | pool (32) | amount u64 LE (8) |
+/// nonce u64 LE (8) | expires_at i64 LE (8). `pool` is the DLMM `lb_pair`, or
+/// the default pubkey for actions that do not touch a pool.
+pub struct SignedIntent {
+    pub session: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub expires_at: i64,
+}
+
+impl SignedIntent {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8;
+
+    fn parse(message: &[u8]) -> Option<Self> {
+        if message.len() != Self::LEN {
+            return None;
+        }
+        Some(Self {
+            session: Pubkey::try_from(&message[0..32]).ok()?,
+            pool: Pubkey::try_from(&message[32..64]).ok()?,
+            amount: read_u64(&message[64..])?,
+            nonce: read_u64(&message[72..])?,
+            expires_at: read_u64(&message[80..])? as i64,
+        })
+    }
+}
+
+/// Find an ed25519-verify instruction in the current transaction that carries a
+/// signature by `signer` over the intent for (`session`, `pool`, `amount`).
+///
+/// The Ed25519 program has already rejected the transaction if any signature
+/// it was given is invalid, so a matching offsets record here proves `signer`
+/// signed the message. Only self-contained records (all offsets pointing into
+/// the ed25519 instruction itself) are accepted.
+pub fn find_signed_intent(
+    ix_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    session: &Pubkey,
+    pool: &Pubkey,
+    amount: u64,
+) -> Result<SignedIntent> {
+    let count = instruction_count(ix_sysvar)?;
+
+    for index in 0..count {
+        let ix = load_instruction_at_checked(index, ix_sysvar)?;
+        if ix.program_id != ED25519_PROGRAM_ID {
+            continue;
+        }
+        let data = &ix.data;
+        let num_signatures = data.first().copied().unwrap_or(0) as usize;
+
+        for sig in 0..num_signatures {
+            let start = ED25519_HEADER_LEN + sig * ED25519_OFFSETS_LEN;
+            let Some(record) = data.get(start..start + ED25519_OFFSETS_LEN) else {
+                break;
+            };
+            let field = |i: usize| read_u16(&record[i * 2..]).unwrap_or(0);
+            // signature_ix, pubkey_ix, message_ix must all be the ed25519 ix itself
+            if field(1) != ED25519_CURRENT_IX
+                || field(3) != ED25519_CURRENT_IX
+                || field(6) != ED25519_CURRENT_IX
+            {
+                continue;
+            }
+            let pubkey_offset = field(2) as usize;
+            let message_offset = field(4) as usize;
+            let message_len = field(5) as usize;
+
+            let Some(pubkey) = data.get(pubkey_offset..pubkey_offset + ED25519_PUBKEY_LEN) else {
+                continue;
+            };
+            if pubkey != signer.as_ref() {
+                continue;
+            }
+            let Some(intent) = data
+                .get(message_offset..message_offset + message_len)
+                .and_then(SignedIntent::parse)
+            else {
+                continue;
+            };
+            if intent.session == *session && intent.pool == *pool && intent.amount == amount {
+                return Ok(intent);
+            }
+        }
+    }
+
+    err!(AgentError::SignedIntentMissing)
+}
+
+/// Enforce the signed-intent requirement for the device in `device_slot`.
+///
+/// No-op when the device has no `intent_signer` configured. Otherwise the
+/// transaction must carry a matching ed25519-verified intent that has not
+/// expired and whose nonce is above the device's last consumed nonce.
+pub fn enforce_signed_intent(
+    ix_sysvar: &AccountInfo,
+    session: &mut Account<AgentSession>,
+    device_slot: usize,
+    pool: &Pubkey,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    let signer = session.devices[device_slot].intent_signer;
+    if signer == Pubkey::default() {
+        return Ok(());
+    }
+    let intent = find_signed_intent(ix_sysvar, &signer, &session.key(), pool, amount)?;
+    session.consume_intent(device_slot, intent.nonce, intent.expires_at, now)
+}
+
+/// Text of the first SPL Memo instruction in the current transaction, if
+/// any. Fails with `InvalidActionMemo` unless it is UTF-8 of at most
+/// `MAX_ACTION_MEMO_LEN` bytes.
+pub fn action_memo(ix_sysvar: &AccountInfo) -> Result<Option<String>> {
+    let count = instruction_count(ix_sysvar)?;
+    for index in 0..count {
+        let ix = load_instruction_at_checked(index, ix_sysvar)?;
+        if ix.program_id != MEMO_PROGRAM_ID {
+            continue;
+        }
+        require!(ix.data.len() <= MAX_ACTION_MEMO_LEN, AgentError::InvalidActionMemo);
+        let memo = String::from_utf8(ix.data).map_err(|_| AgentError::InvalidActionMemo)?;
+        return Ok(Some(memo));
+    }
+    Ok(None)
+}
+
+/// Emit `ActionMemo` binding the transaction's memo, when it carries one, to
+/// the action just executed — the one `total_actions` now counts.
+///
+/// Devices annotate an action by adding an SPL Memo instruction to the
+/// execute transaction; the Memo program logs it as usual, and the event
+/// ties it to the session and action for audit trails and the owner's
+/// accounting.
+pub fn emit_action_memo(
+    ix_sysvar: &AccountInfo,
+    session: Pubkey,
+    device: Pubkey,
+    total_actions: u64,
+) -> Result<()> {
+    if let Some(memo) = action_memo(ix_sysvar)? {
+        emit!(ActionMemo {
+            session,
+            device,
+            total_actions,
+            memo,
+        });
+    }
+    Ok(())
+}
@@ -1,13 +1,34 @@
 use anchor_lang::prelude::*;
 use ephemeral_rollups_sdk::anchor::ephemeral;
 
+pub mod alerts;
+pub mod balances;
 pub mod errors;
+pub mod events;
+pub mod fees;
+pub mod freeze;
 pub mod instructions;
+pub mod introspection;
+pub mod logging;
+pub mod lookup_table;
+pub mod reposition;
 pub mod state;
+pub mod valuation;
 
 use instructions::*;
 
 declare_program!(dlmm);
+declare_program!(spl_account_compression);
+
+/// SPL noop program — the account compression program logs tree changelogs
+/// through it so indexers can follow compressed monitors.
+pub const SPL_NOOP_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// DLMM's CPI event authority — the DLMM program's `[b"__event_authority"]` PDA.
+/// Constant so clients never pass it and handlers skip the derivation.
+pub const DLMM_EVENT_AUTHORITY: Pubkey =
+    anchor_lang::solana_program::pubkey!("D1ZN9Wj1fRSUQfCjhvnu1hqDMT7hzjzBBpi12nVniYD6");
 
 // Replace with actual program ID after `anchor build && anchor keys list`
 declare_id!("8reNvTG6PLT4sf4nGbT7VjZ1YqEGXzASkjcSQmQTkJPT");
@@ -18,13 +39,20 @@ pub mod defi_agent {
     use super::*;
 
     /// [Base Layer] Create an AgentSession PDA, registering the ESP32 session key
-    /// with its scope: duration, max lamport exposure, and enabled strategies.
+    /// with its scope: duration, max lamport exposure, enabled strategies, and
+    /// per-action cap, plus an optional fee-sharing referrer and the chosen
+    /// per-action fee tier and the session-key device's attestation hash.
+    /// Limits are bounded by the ceilings on the global Config.
     pub fn initialize_session(
         ctx: Context<InitializeSession>,
         session_key: Pubkey,
         duration_secs: i64,
         max_lamports: u64,
         strategy_mask: u8,
+        max_action_lamports: u64,
+        referrer: Option<Pubkey>,
+        fee_tier: u8,
+        attestation_hash: [u8; 32],
     ) -> Result<()> {
         instructions::initialize_session::handler(
             ctx,
@@ -32,6 +60,10 @@ pub mod defi_agent {
             duration_secs,
             max_lamports,
             strategy_mask,
+            max_action_lamports,
+            referrer,
+            fee_tier,
+            attestation_hash,
         )
     }
 
@@ -43,12 +75,22 @@ pub mod defi_agent {
 
     /// [Ephemeral Rollup] Execute a DeFi strategy action.
     /// Signed by the ESP32 session key. Validates scope before updating state.
+    /// `fee_lamports` is the declared priority fee + tips, charged to the fee budget;
+    /// `reason` is the `REASON_*` code recorded in `ActionExecuted`.
     pub fn execute_action(
         ctx: Context<ExecuteAction>,
         action_type: u8,
         amount_lamports: u64,
+        fee_lamports: u64,
+        reason: u8,
     ) -> Result<()> {
-        instructions::execute_action::handler(ctx, action_type, amount_lamports)
+        instructions::execute_action::handler(
+            ctx,
+            action_type,
+            amount_lamports,
+            fee_lamports,
+            reason,
+        )
     }
 
     /// [Ephemeral Rollup] Checkpoint session state to Solana mainnet without undelegating.
@@ -63,13 +105,25 @@ pub mod defi_agent {
 
     /// [Base Layer] Execute a real Meteora DLMM swap via CPI.
     /// Signed by the ESP32 session key. Validates LP strategy scope then CPIs into
-    /// the Meteora DLMM program to perform the swap on-chain.
+    /// the Meteora DLMM program to perform the swap on-chain. Optional
+    /// `min_active_bin` / `max_active_bin` refuse the swap when the pool's
+    /// active bin has left that band.
     pub fn execute_dlmm_swap<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmSwap<'info>>,
         amount_in: u64,
         min_amount_out: u64,
+        fee_lamports: u64,
+        min_active_bin: Option<i32>,
+        max_active_bin: Option<i32>,
     ) -> Result<()> {
-        instructions::execute_dlmm_swap::handler(ctx, amount_in, min_amount_out)
+        instructions::execute_dlmm_swap::handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            fee_lamports,
+            min_active_bin,
+            max_active_bin,
+        )
     }
 
     /// [Base Layer] Remove all liquidity from a Meteora DLMM position and close it via CPI.
@@ -77,8 +131,10 @@ pub mod defi_agent {
     /// in sequence — tokens return to the session key's ATAs, rent goes to `rent_receiver`.
     pub fn execute_dlmm_close_position<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmClosePosition<'info>>,
+        fee_lamports: u64,
+        reason: u8,
     ) -> Result<()> {
-        instructions::execute_dlmm_close_position::handler(ctx)
+        instructions::execute_dlmm_close_position::handler(ctx, fee_lamports, reason)
     }
 
     /// [Base Layer] Add liquidity to an existing Meteora DLMM position via CPI.
@@ -88,8 +144,9 @@ pub mod defi_agent {
     pub fn execute_dlmm_add_liquidity<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmAddLiquidity<'info>>,
         liquidity_parameter: dlmm::types::LiquidityParameterByStrategy,
+        fee_lamports: u64,
     ) -> Result<()> {
-        instructions::execute_dlmm_add_liquidity::handler(ctx, liquidity_parameter)
+        instructions::execute_dlmm_add_liquidity::handler(ctx, liquidity_parameter, fee_lamports)
     }
 
     /// [Base Layer] Register a Meteora DLMM position for on-chain status monitoring.
@@ -106,14 +163,852 @@ pub mod defi_agent {
     }
 
     /// [Base Layer] Checkpoint the current LP position status on-chain.
-    /// Signed by the ESP32 session key. Caller passes the current pool active bin
-    /// and unclaimed fee amounts read off-chain — updates is_in_range and fee snapshots.
+    /// Signed by the ESP32 session key. Reads the pool's active bin from the
+    /// lb_pair account; caller passes unclaimed fees and position amounts read
+    /// off-chain — updates is_in_range and the fee / size snapshots.
     pub fn update_lp_status(
         ctx: Context<UpdateLpStatus>,
-        active_bin: i32,
+        fee_x: u64,
+        fee_y: u64,
+        amount_x: u64,
+        amount_y: u64,
+    ) -> Result<()> {
+        instructions::update_lp_status::handler(ctx, fee_x, fee_y, amount_x, amount_y)
+    }
+
+    /// [Base Layer] Set the operational fee budget for priority fees and Jito tips,
+    /// and the cap on transaction signature fees (0 = uncapped for either).
+    /// Signed by the session owner. Execute instructions fail once the declared
+    /// cumulative fee spend would exceed this cap.
+    pub fn set_fee_budget(
+        ctx: Context<SetFeeBudget>,
+        fee_budget_lamports: u64,
+        tx_fee_budget_lamports: u64,
+    ) -> Result<()> {
+        instructions::set_fee_budget::handler(ctx, fee_budget_lamports, tx_fee_budget_lamports)
+    }
+
+    /// [Base Layer] Create the program-wide Config PDA (admin, protocol fee, default limits).
+    /// Signed by the program upgrade authority, which becomes the config admin.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        protocol_fee_bps: u16,
+        default_max_lamports: u64,
+        default_duration_secs: i64,
+    ) -> Result<()> {
+        instructions::initialize_config::handler(
+            ctx,
+            protocol_fee_bps,
+            default_max_lamports,
+            default_duration_secs,
+        )
+    }
+
+    /// [Base Layer] Update the protocol fee and default session limits on the Config.
+    /// Signed by the config admin.
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        protocol_fee_bps: u16,
+        default_max_lamports: u64,
+        default_duration_secs: i64,
+    ) -> Result<()> {
+        instructions::update_config::handler(
+            ctx,
+            protocol_fee_bps,
+            default_max_lamports,
+            default_duration_secs,
+        )
+    }
+
+    /// [Base Layer] Pause or resume all execute-family instructions program-wide.
+    /// Signed by the config admin. Owner-side and commit/undelegate flows keep working.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused::handler(ctx, paused)
+    }
+
+    /// [Base Layer] Create the treasury token account and fee ledger for a mint.
+    /// Signed by the config admin. The token account is owned by the Config PDA.
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        instructions::initialize_treasury::handler(ctx)
+    }
+
+    /// [Base Layer] Withdraw accrued protocol fees from a mint's treasury.
+    /// Signed by the config admin; the Config PDA signs the token transfer.
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury::handler(ctx, amount)
+    }
+
+    /// [Base Layer] Create the global PoolRegistry of vetted DLMM pools.
+    /// Signed by the config admin.
+    pub fn initialize_pool_registry(ctx: Context<InitializePoolRegistry>) -> Result<()> {
+        instructions::initialize_pool_registry::handler(ctx)
+    }
+
+    /// [Base Layer] Add (or re-tier) a vetted DLMM pool in the global registry.
+    /// Signed by the config admin.
+    pub fn add_registry_pool(
+        ctx: Context<AddRegistryPool>,
+        lb_pair: Pubkey,
+        risk_tier: u8,
+    ) -> Result<()> {
+        instructions::add_registry_pool::handler(ctx, lb_pair, risk_tier)
+    }
+
+    /// [Base Layer] Remove a DLMM pool from the global registry.
+    /// Signed by the config admin.
+    pub fn remove_registry_pool(ctx: Context<RemoveRegistryPool>, lb_pair: Pubkey) -> Result<()> {
+        instructions::remove_registry_pool::handler(ctx, lb_pair)
+    }
+
+    /// [Base Layer] Restrict the session's DLMM instructions to registry pools.
+    /// Signed by the session owner.
+    pub fn set_registry_only(ctx: Context<SetRegistryOnly>, registry_only: bool) -> Result<()> {
+        instructions::set_registry_only::handler(ctx, registry_only)
+    }
+
+    /// [Base Layer] Set the protocol ceilings enforced by `initialize_session`:
+    /// per-action cap, maximum session duration, and allowed strategy mask.
+    /// Signed by the config admin.
+    pub fn set_session_limits(
+        ctx: Context<SetSessionLimits>,
+        max_action_lamports: u64,
+        max_session_duration_secs: i64,
+        max_strategy_mask: u8,
+    ) -> Result<()> {
+        instructions::set_session_limits::handler(
+            ctx,
+            max_action_lamports,
+            max_session_duration_secs,
+            max_strategy_mask,
+        )
+    }
+
+    /// [Base Layer] Nominate a new config admin, starting the transfer timelock.
+    /// Signed by the current admin. Passing the default pubkey cancels.
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::handler(ctx, new_admin)
+    }
+
+    /// [Base Layer] Complete an admin transfer once the timelock has elapsed.
+    /// Signed by the nominated admin.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    /// [Base Layer] Set the delay applied to future admin proposals.
+    /// Signed by the config admin.
+    pub fn set_admin_timelock(ctx: Context<SetAdminTimelock>, admin_timelock_secs: i64) -> Result<()> {
+        instructions::set_admin_timelock::handler(ctx, admin_timelock_secs)
+    }
+
+    /// [Base Layer] Set the share of protocol fees routed to session referrers.
+    /// Signed by the config admin.
+    pub fn set_referral_share(ctx: Context<SetReferralShare>, referral_share_bps: u16) -> Result<()> {
+        instructions::set_referral_share::handler(ctx, referral_share_bps)
+    }
+
+    /// [Base Layer] Define an admin-approved per-action fee tier (flat or bps).
+    /// Signed by the config admin.
+    pub fn set_fee_tier(ctx: Context<SetFeeTier>, index: u8, fee_mode: u8, amount: u64) -> Result<()> {
+        instructions::set_fee_tier::handler(ctx, index, fee_mode, amount)
+    }
+
+    /// [Base Layer] Withdraw per-action fees accrued in the fee vault PDA.
+    /// Signed by the config admin.
+    pub fn withdraw_fee_vault(ctx: Context<WithdrawFeeVault>, amount: u64) -> Result<()> {
+        instructions::withdraw_fee_vault::handler(ctx, amount)
+    }
+
+    /// [Base Layer] Freeze or unfreeze only the DLMM CPI instructions.
+    /// Signed by the config admin. Monitoring, commit and undelegate keep working.
+    pub fn set_dlmm_frozen(ctx: Context<SetDlmmFrozen>, frozen: bool) -> Result<()> {
+        instructions::set_dlmm_frozen::handler(ctx, frozen)
+    }
+
+    /// [Base Layer] Realloc the Config to the current layout and bump its version.
+    /// Signed by the config admin, who funds any additional rent.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config::handler(ctx)
+    }
+
+    /// [Base Layer] Enroll an additional device key (backup ESP32, phone signer).
+    /// Signed by the session owner.
+    pub fn add_device(
+        ctx: Context<AddDevice>,
+        device_key: Pubkey,
+        attestation_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::add_device::handler(ctx, device_key, attestation_hash)
+    }
+
+    /// [Base Layer] Remove an enrolled device key from the session.
+    /// Signed by the session owner.
+    pub fn remove_device(ctx: Context<RemoveDevice>, device_key: Pubkey) -> Result<()> {
+        instructions::remove_device::handler(ctx, device_key)
+    }
+
+    /// [Base Layer] Set a device key's own expiry and cumulative spend cap.
+    /// Signed by the session owner.
+    pub fn set_device_limits(
+        ctx: Context<SetDeviceLimits>,
+        device_key: Pubkey,
+        expires_at: i64,
+        max_lamports: u64,
+    ) -> Result<()> {
+        instructions::set_device_limits::handler(ctx, device_key, expires_at, max_lamports)
+    }
+
+    /// [Base Layer] Require ed25519-signed intents from a device's secure element.
+    /// Signed by the session owner; the default pubkey lifts the requirement.
+    pub fn set_intent_signer(
+        ctx: Context<SetIntentSigner>,
+        device_key: Pubkey,
+        intent_signer: Pubkey,
+    ) -> Result<()> {
+        instructions::set_intent_signer::handler(ctx, device_key, intent_signer)
+    }
+
+    /// [Base Layer] Rotate a device key; the old key stays valid for `grace_secs`.
+    /// Signed by the session owner.
+    pub fn rotate_device(
+        ctx: Context<RotateDevice>,
+        old_key: Pubkey,
+        new_key: Pubkey,
+        grace_secs: i64,
+    ) -> Result<()> {
+        instructions::rotate_device::handler(ctx, old_key, new_key, grace_secs)
+    }
+
+    /// [Base Layer] Invalidate one device key without touching the rest of the session.
+    /// Signed by the session owner.
+    pub fn disable_device_key(ctx: Context<DisableDeviceKey>, device_key: Pubkey) -> Result<()> {
+        instructions::disable_device_key::handler(ctx, device_key)
+    }
+
+    /// [Base Layer] Mark a device as a standby key that activates only after
+    /// every regular device has stopped heartbeating for `standby_after_secs`.
+    /// Signed by the session owner.
+    pub fn set_standby_device(
+        ctx: Context<SetStandbyDevice>,
+        device_key: Pubkey,
+        standby_after_secs: i64,
+    ) -> Result<()> {
+        instructions::set_standby_device::handler(ctx, device_key, standby_after_secs)
+    }
+
+    /// [Base Layer / Ephemeral Rollup] Device liveness ping.
+    /// Signed by a device key; keeps standby keys dormant.
+    pub fn device_heartbeat(ctx: Context<DeviceHeartbeat>) -> Result<()> {
+        instructions::device_heartbeat::handler(ctx)
+    }
+
+    /// [Base Layer] Require the owner's co-signature for actions above a notional.
+    /// Signed by the session owner; 0 disables.
+    pub fn set_cosign_threshold(
+        ctx: Context<SetCosignThreshold>,
+        cosign_above_lamports: u64,
+    ) -> Result<()> {
+        instructions::set_cosign_threshold::handler(ctx, cosign_above_lamports)
+    }
+
+    /// [Base Layer] File a request for one out-of-scope action (new pool, amount above cap).
+    /// Signed by a device key.
+    pub fn file_action_request(
+        ctx: Context<FileActionRequest>,
+        request_id: u64,
+        kind: u8,
+        lb_pair: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::file_action_request::handler(ctx, request_id, kind, lb_pair, amount)
+    }
+
+    /// [Base Layer] Approve a pending action request for one-shot execution.
+    /// Signed by the session owner.
+    pub fn approve_request(ctx: Context<ApproveRequest>) -> Result<()> {
+        instructions::approve_request::handler(ctx)
+    }
+
+    /// [Base Layer] Announce a large treasury / fee-vault withdrawal; executable after
+    /// `Config.withdrawal_delay_secs`. Admin-only.
+    pub fn queue_withdrawal(
+        ctx: Context<QueueWithdrawal>,
+        asset: Pubkey,
+        amount: u64,
+        destination: Pubkey,
+    ) -> Result<()> {
+        instructions::queue_withdrawal::handler(ctx, asset, amount, destination)
+    }
+
+    /// [Base Layer] Cancel a queued withdrawal. Admin-only.
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> Result<()> {
+        instructions::cancel_withdrawal::handler(ctx)
+    }
+
+    /// [Base Layer] Tighten the withdrawal delay and fee-vault large-withdrawal threshold.
+    /// Admin-only; the policy can never be loosened.
+    pub fn set_withdrawal_policy(
+        ctx: Context<SetWithdrawalPolicy>,
+        withdrawal_delay_secs: i64,
+        large_withdrawal_lamports: u64,
+    ) -> Result<()> {
+        instructions::set_withdrawal_policy::handler(
+            ctx,
+            withdrawal_delay_secs,
+            large_withdrawal_lamports,
+        )
+    }
+
+    /// [Base Layer] Tighten the large-withdrawal threshold for one treasury mint.
+    /// Admin-only.
+    pub fn set_treasury_withdrawal_threshold(
+        ctx: Context<SetTreasuryWithdrawalThreshold>,
+        large_withdrawal_amount: u64,
+    ) -> Result<()> {
+        instructions::set_treasury_withdrawal_threshold::handler(ctx, large_withdrawal_amount)
+    }
+
+    /// [Base Layer] Pre-approve a (pool, side, max amount, min out, deadline) swap.
+    /// Signed by the session owner.
+    pub fn create_intent(
+        ctx: Context<CreateIntent>,
+        intent_id: u64,
+        lb_pair: Pubkey,
+        swap_for_y: bool,
+        max_amount_in: u64,
+        min_amount_out: u64,
+        deadline: i64,
+        keeper_fillable: bool,
+        keeper_bounty_lamports: u64,
+    ) -> Result<()> {
+        instructions::create_intent::handler(
+            ctx,
+            intent_id,
+            lb_pair,
+            swap_for_y,
+            max_amount_in,
+            min_amount_out,
+            deadline,
+            keeper_fillable,
+            keeper_bounty_lamports,
+        )
+    }
+
+    /// [Base Layer] Execute all or part of an owner-preapproved swap within the intent's
+    /// bounds. Signed by the ESP32 session key; closes the intent once fully filled.
+    pub fn fulfill_intent<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, FulfillIntent<'info>>,
+        amount_in: u64,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::fulfill_intent::handler(ctx, amount_in, fee_lamports)
+    }
+
+    /// [Base Layer] Cancel an unfilled or partially filled intent.
+    /// Signed by the session owner.
+    pub fn cancel_intent(ctx: Context<CancelIntent>) -> Result<()> {
+        instructions::cancel_intent::handler(ctx)
+    }
+
+    /// [Base Layer] Create up to MAX_INTENT_BATCH intents in one instruction.
+    /// Signed by the session owner; intent PDAs go in `remaining_accounts`.
+    pub fn create_intents_batch<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CreateIntentsBatch<'info>>,
+        specs: Vec<state::IntentSpec>,
+    ) -> Result<()> {
+        instructions::create_intents_batch::handler(ctx, specs)
+    }
+
+    /// [Base Layer] Fill a keeper-fillable intent while the device is offline.
+    /// Signed by any keeper, who earns a pro-rata share of the escrowed bounty.
+    pub fn keeper_fill_intent<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, KeeperFillIntent<'info>>,
+        amount_in: u64,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::keeper_fill_intent::handler(ctx, amount_in, fee_lamports)
+    }
+
+    /// [Base Layer] Bind the session to an explicit list of DLMM positions (empty = any).
+    /// Signed by the session owner.
+    pub fn set_bound_positions(ctx: Context<SetBoundPositions>, positions: Vec<Pubkey>) -> Result<()> {
+        instructions::set_bound_positions::handler(ctx, positions)
+    }
+
+    /// [Base Layer] Create the session's PositionRegistry PDA.
+    /// Signed by the session owner.
+    pub fn initialize_position_registry(ctx: Context<InitializePositionRegistry>) -> Result<()> {
+        instructions::initialize_position_registry::handler(ctx)
+    }
+
+    /// [Base Layer] Open a new Meteora DLMM position owned by the session key via CPI
+    /// and record it in the session's PositionRegistry. Signed by the ESP32 session key.
+    pub fn execute_dlmm_create_position<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmCreatePosition<'info>>,
+        lower_bin_id: i32,
+        width: i32,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::execute_dlmm_create_position::handler(ctx, lower_bin_id, width, fee_lamports)
+    }
+
+    /// [Base Layer] Recreate an owner-opened DLMM position under session-key ownership
+    /// and register it. Signed by the session owner, the ESP32 session key and the
+    /// new position keypair.
+    pub fn adopt_position<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, AdoptPosition<'info>>,
+        lower_bin_id: i32,
+        width: i32,
+    ) -> Result<()> {
+        instructions::adopt_position::handler(ctx, lower_bin_id, width)
+    }
+
+    /// [Base Layer] Close a session-key-owned DLMM position and reopen the withdrawn
+    /// liquidity in another pool of the same pair via CPI. Signed by the ESP32
+    /// session key and the new position keypair.
+    pub fn execute_dlmm_migrate_position<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmMigratePosition<'info>>,
+        lower_bin_id: i32,
+        width: i32,
+        liquidity_parameter: dlmm::types::LiquidityParameterByStrategy,
+        fee_lamports: u64,
+        reason: u8,
+    ) -> Result<()> {
+        instructions::execute_dlmm_migrate_position::handler(
+            ctx,
+            lower_bin_id,
+            width,
+            liquidity_parameter,
+            fee_lamports,
+            reason,
+        )
+    }
+
+    /// [Base Layer] Remove liquidity from and close every registered DLMM position
+    /// passed in remaining_accounts. Signed by the session owner, co-signed by the
+    /// session key. Works while paused or DLMM-frozen.
+    pub fn close_all_positions<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseAllPositions<'info>>,
+    ) -> Result<()> {
+        instructions::close_all_positions::handler(ctx)
+    }
+
+    /// [Base Layer] Suppress or restore routine informational logs in execution
+    /// handlers to reclaim compute units. Signed by the session owner.
+    pub fn set_quiet_logs(ctx: Context<SetQuietLogs>, quiet_logs: bool) -> Result<()> {
+        instructions::set_quiet_logs::handler(ctx, quiet_logs)
+    }
+
+    /// [Base Layer] Create an Address Lookup Table with the session PDA as authority
+    /// and record it on the session. Signed by an enrolled device key.
+    pub fn create_session_lookup_table(
+        ctx: Context<CreateSessionLookupTable>,
+        recent_slot: u64,
+    ) -> Result<()> {
+        instructions::create_session_lookup_table::handler(ctx, recent_slot)
+    }
+
+    /// [Base Layer] Append a pool's static accounts to the session's lookup table.
+    /// Signed by an enrolled device key; the session PDA signs as table authority.
+    pub fn extend_session_lookup_table(
+        ctx: Context<ExtendSessionLookupTable>,
+        new_addresses: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::extend_session_lookup_table::handler(ctx, new_addresses)
+    }
+
+    /// [Base Layer] First step of a split close: remove all liquidity from a
+    /// session-key-owned DLMM position via CPI. Signed by the ESP32 session key.
+    pub fn execute_dlmm_remove_all_liquidity<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmRemoveAllLiquidity<'info>>,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::execute_dlmm_remove_all_liquidity::handler(ctx, fee_lamports)
+    }
+
+    /// [Base Layer] Second step of a split close: close the position emptied by
+    /// `execute_dlmm_remove_all_liquidity` via CPI. Signed by the ESP32 session key.
+    pub fn execute_dlmm_close_empty_position(
+        ctx: Context<ExecuteDlmmCloseEmptyPosition>,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::execute_dlmm_close_empty_position::handler(ctx, fee_lamports)
+    }
+
+    /// [Base Layer] Two-hop DLMM swap through an intermediate token in one
+    /// instruction with a single exposure debit. Signed by the ESP32 session key.
+    pub fn execute_dlmm_swap_route<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmSwapRoute<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        leg1_bin_arrays: u8,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::execute_dlmm_swap_route::handler(
+            ctx,
+            amount_in,
+            min_amount_out,
+            leg1_bin_arrays,
+            fee_lamports,
+        )
+    }
+
+    /// [Base Layer] Add liquidity to several session-key-owned positions of one
+    /// DLMM pool via CPI with a single session validation. Signed by the ESP32
+    /// session key.
+    pub fn execute_dlmm_add_liquidity_batch<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmAddLiquidityBatch<'info>>,
+        liquidity_parameters: Vec<dlmm::types::LiquidityParameterByStrategy>,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::execute_dlmm_add_liquidity_batch::handler(
+            ctx,
+            liquidity_parameters,
+            fee_lamports,
+        )
+    }
+
+    /// [Base Layer] Set the minimum compute-unit limit the double-CPI close and
+    /// migrate paths require (0 = unchecked). Signed by the config admin.
+    pub fn set_compute_guard(
+        ctx: Context<SetComputeGuard>,
+        min_dual_cpi_compute_units: u32,
+    ) -> Result<()> {
+        instructions::set_compute_guard::handler(ctx, min_dual_cpi_compute_units)
+    }
+
+    /// [Base Layer] Create the session's compressed-monitor Merkle tree through the
+    /// account compression program. Signed by the session owner, who pre-allocates
+    /// `merkle_tree` with room for the changelog buffer and canopy.
+    pub fn initialize_monitor_tree(
+        ctx: Context<InitializeMonitorTree>,
+        depth: u8,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        instructions::initialize_monitor_tree::handler(ctx, depth, max_buffer_size)
+    }
+
+    /// [Base Layer] Append a DLMM position to the session's compressed-monitor tree.
+    /// Signed by the session owner.
+    pub fn register_compressed_monitor(
+        ctx: Context<RegisterCompressedMonitor>,
+        lb_pair: Pubkey,
+        position: Pubkey,
+        min_bin_id: i32,
+        max_bin_id: i32,
+    ) -> Result<()> {
+        instructions::register_compressed_monitor::handler(
+            ctx,
+            lb_pair,
+            position,
+            min_bin_id,
+            max_bin_id,
+        )
+    }
+
+    /// [Base Layer] Checkpoint a compressed LP monitor leaf with a Merkle proof.
+    /// Signed by the ESP32 session key; the active bin is read from the leaf's lb_pair.
+    /// Proof nodes below the canopy are passed as remaining accounts.
+    pub fn update_compressed_lp_status<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, UpdateCompressedLpStatus<'info>>,
+        leaf_index: u32,
+        leaf: state::CompressedMonitorLeaf,
+        root: [u8; 32],
         fee_x: u64,
         fee_y: u64,
     ) -> Result<()> {
-        instructions::update_lp_status::handler(ctx, active_bin, fee_x, fee_y)
+        instructions::update_compressed_lp_status::handler(
+            ctx, leaf_index, leaf, root, fee_x, fee_y,
+        )
+    }
+
+    /// [Base Layer] Set the minimum per-action amount for a mint (base units,
+    /// decimals-checked); smaller swaps, deposits and fills are rejected. 0 removes it.
+    pub fn set_min_trade_amount(
+        ctx: Context<SetMinTradeAmount>,
+        min_amount: u64,
+        decimals: u8,
+    ) -> Result<()> {
+        instructions::set_min_trade_amount::handler(ctx, min_amount, decimals)
+    }
+
+    /// [Base Layer] Create the session's DailyStats PDA (per-UTC-day action,
+    /// volume and fee totals). Signed by the session owner.
+    pub fn initialize_daily_stats(ctx: Context<InitializeDailyStats>) -> Result<()> {
+        instructions::initialize_daily_stats::handler(ctx)
+    }
+
+    /// [Base Layer / Ephemeral Rollup] Emit a numbered `SessionSnapshot` for
+    /// off-chain archival. Signed by a device key.
+    pub fn snapshot_session(ctx: Context<SnapshotSession>) -> Result<()> {
+        instructions::snapshot_session::handler(ctx)
+    }
+
+    /// [Base Layer] Reprice the session's registered positions at current pool
+    /// prices into `current_exposure_value`. Signed by a device key; the
+    /// positions' pools are passed as remaining accounts.
+    pub fn revalue_exposure<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RevalueExposure<'info>>,
+    ) -> Result<()> {
+        instructions::revalue_exposure::handler(ctx)
+    }
+
+    /// [Base Layer] Enforce the exposure cap against marked-to-market value
+    /// (`true`) or historical cost (`false`). Signed by the session owner.
+    pub fn set_mark_to_market(ctx: Context<SetMarkToMarket>, enabled: bool) -> Result<()> {
+        instructions::set_mark_to_market::handler(ctx, enabled)
+    }
+
+    /// [Base Layer] Set the per-swap slippage, in basis points, above which
+    /// swaps emit `SlippageExceeded` (0 = off). Signed by the session owner.
+    pub fn set_slippage_alert(ctx: Context<SetSlippageAlert>, max_slippage_bps: u16) -> Result<()> {
+        instructions::set_slippage_alert::handler(ctx, max_slippage_bps)
+    }
+
+    /// [Base Layer] Clear the review flag raised by a `SettlementDiscrepancy`.
+    /// Signed by the session owner.
+    pub fn acknowledge_review(ctx: Context<AcknowledgeReview>) -> Result<()> {
+        instructions::acknowledge_review::handler(ctx)
+    }
+
+    /// [Base Layer] Start a new term on an expired or undelegated session in
+    /// place, keeping its devices, statistics, registries and monitors.
+    /// Signed by the session owner; limits are bounded as in `initialize_session`.
+    pub fn renew_session(
+        ctx: Context<RenewSession>,
+        duration_secs: i64,
+        max_lamports: u64,
+        max_action_lamports: u64,
+    ) -> Result<()> {
+        instructions::renew_session::handler(ctx, duration_secs, max_lamports, max_action_lamports)
+    }
+
+    /// [Base Layer] Move the session's LpPositionMonitor (and optionally its
+    /// PositionRegistry) under another session, history intact. Signed by the
+    /// owners of both sessions.
+    pub fn reassign_monitor<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ReassignMonitor<'info>>,
+    ) -> Result<()> {
+        instructions::reassign_monitor::handler(ctx)
+    }
+
+    /// [Base Layer] Suspend the session after `violation_threshold`
+    /// consecutive scope violations by its devices (0 = off). Also lifts a
+    /// suspension and resets the count.
+    pub fn set_violation_freeze(
+        ctx: Context<SetViolationFreeze>,
+        violation_threshold: u8,
+    ) -> Result<()> {
+        instructions::set_violation_freeze::handler(ctx, violation_threshold)
+    }
+
+    /// [Base Layer] Fail execute calls for `cooldown_secs` after an action is
+    /// rejected with `ExposureLimitExceeded` (0 = off). Also ends a running
+    /// cooldown.
+    pub fn set_exposure_cooldown(
+        ctx: Context<SetExposureCooldown>,
+        cooldown_secs: i64,
+    ) -> Result<()> {
+        instructions::set_exposure_cooldown::handler(ctx, cooldown_secs)
+    }
+
+    /// [Base Layer / Ephemeral Rollup] Clear strategy bits on an active
+    /// session, e.g. to stop yield switching while LP management keeps
+    /// running. Signed by the session owner; bits can never be added.
+    pub fn set_strategy_mask(ctx: Context<SetStrategyMask>, strategy_mask: u8) -> Result<()> {
+        instructions::set_strategy_mask::handler(ctx, strategy_mask)
+    }
+
+    /// [Base Layer] Set the fee, out-of-range and exposure alert thresholds
+    /// the program evaluates and reports as typed events. Signed by the owner.
+    pub fn set_alert_config(
+        ctx: Context<SetAlertConfig>,
+        alert_config: state::AlertConfig,
+    ) -> Result<()> {
+        instructions::set_alert_config::handler(ctx, alert_config)
+    }
+
+    /// [Base Layer] Set the session's display label and optional off-chain
+    /// metadata URI. Signed by the session owner.
+    pub fn set_session_metadata(
+        ctx: Context<SetSessionMetadata>,
+        label: [u8; 32],
+        metadata_uri: String,
+    ) -> Result<()> {
+        instructions::set_session_metadata::handler(ctx, label, metadata_uri)
+    }
+
+    /// [Base Layer] Designate a relayer that may pay transaction fees for
+    /// session-key-signed instructions without spending authority. Signed by
+    /// the owner; Pubkey::default() clears it.
+    pub fn set_fee_payer(ctx: Context<SetFeePayer>, fee_payer: Pubkey) -> Result<()> {
+        instructions::set_fee_payer::handler(ctx, fee_payer)
+    }
+
+    /// [Base Layer] Create or retune the session's fee sponsor: the balance
+    /// refills top device keys up to and the per-day refill cap. Signed by
+    /// the owner.
+    pub fn configure_fee_sponsor(
+        ctx: Context<ConfigureFeeSponsor>,
+        target_balance_lamports: u64,
+        daily_cap_lamports: u64,
+    ) -> Result<()> {
+        instructions::configure_fee_sponsor::handler(
+            ctx,
+            target_balance_lamports,
+            daily_cap_lamports,
+        )
+    }
+
+    /// [Base Layer] Deposit SOL into the session's fee sponsor. Signed by the
+    /// owner.
+    pub fn fund_fee_sponsor(ctx: Context<FundFeeSponsor>, lamports: u64) -> Result<()> {
+        instructions::fund_fee_sponsor::handler(ctx, lamports)
+    }
+
+    /// [Base Layer] Top an enrolled device key up to the sponsor's target
+    /// balance, within its daily cap. Permissionless.
+    pub fn refill_session_key(ctx: Context<RefillSessionKey>) -> Result<()> {
+        instructions::refill_session_key::handler(ctx)
+    }
+
+    /// [Base Layer] Close the fee sponsor, returning its balance to the
+    /// owner. Signed by the owner.
+    pub fn close_fee_sponsor(ctx: Context<CloseFeeSponsor>) -> Result<()> {
+        instructions::close_fee_sponsor::handler(ctx)
+    }
+
+    /// [Base Layer] Restrict deposits to the owner's chosen liquidity shapes
+    /// (`LIQUIDITY_SHAPE_*` bits; 0 = any). Signed by the owner.
+    pub fn set_liquidity_shapes(
+        ctx: Context<SetLiquidityShapes>,
+        liquidity_shapes: u16,
+    ) -> Result<()> {
+        instructions::set_liquidity_shapes::handler(ctx, liquidity_shapes)
+    }
+
+    /// [Base Layer] Close a session-key-owned DLMM position and reopen the withdrawn
+    /// liquidity in the same pool, `extra_bins_each_side` bins wider on each side and
+    /// centered on the active bin. Signed by the ESP32 session key and the new
+    /// position keypair.
+    pub fn execute_dlmm_widen_range<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ExecuteDlmmWidenRange<'info>>,
+        extra_bins_each_side: u16,
+        liquidity_parameter: dlmm::types::LiquidityParameterByStrategy,
+        fee_lamports: u64,
+        reason: u8,
+    ) -> Result<()> {
+        instructions::execute_dlmm_widen_range::handler(
+            ctx,
+            extra_bins_each_side,
+            liquidity_parameter,
+            fee_lamports,
+            reason,
+        )
+    }
+
+    /// [Base Layer] Create or replace the owner's ranked list of acceptable pools for a
+    /// token pair; the pools are passed as remaining accounts. Signed by the owner.
+    pub fn set_pair_pools<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetPairPools<'info>>,
+        token_x_mint: Pubkey,
+        token_y_mint: Pubkey,
+        pools: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_pair_pools::handler(ctx, token_x_mint, token_y_mint, pools)
+    }
+
+    /// [Base Layer] Close a ranked pool list and return its rent. Signed by the owner.
+    pub fn close_pair_pools(ctx: Context<ClosePairPools>) -> Result<()> {
+        instructions::close_pair_pools::handler(ctx)
+    }
+
+    /// [Base Layer] Cap the estimated DLMM pool swap fees counted into
+    /// `swap_fees_paid` (0 = uncapped). Signed by the session owner.
+    pub fn set_swap_fee_budget(ctx: Context<SetSwapFeeBudget>, swap_fee_budget: u64) -> Result<()> {
+        instructions::set_swap_fee_budget::handler(ctx, swap_fee_budget)
+    }
+
+    /// [Base Layer] Create the session's ActionReceiptLog PDA (receipts of the
+    /// actions executed on the ER). Signed by the session owner; execute calls
+    /// must pass it from then on.
+    pub fn initialize_receipt_log(ctx: Context<InitializeReceiptLog>) -> Result<()> {
+        instructions::initialize_receipt_log::handler(ctx)
+    }
+
+    /// [Base Layer] Delegate the session's ActionReceiptLog to the Ephemeral
+    /// Rollup, ahead of `delegate_session`. Signed by the session owner.
+    pub fn delegate_receipt_log(ctx: Context<DelegateReceiptLog>) -> Result<()> {
+        instructions::delegate_receipt_log::handler(ctx)
+    }
+
+    /// [Base Layer] Set how many actions / seconds a delegated session may run
+    /// on the ER between commits (0 = no limit). Signed by the session owner.
+    pub fn set_commit_cadence(
+        ctx: Context<SetCommitCadence>,
+        max_uncommitted_actions: u32,
+        max_uncommitted_secs: i64,
+    ) -> Result<()> {
+        instructions::set_commit_cadence::handler(ctx, max_uncommitted_actions, max_uncommitted_secs)
+    }
+
+    /// [Ephemeral Rollup] Commit final state and return the session and every delegated
+    /// account of it, passed as remaining accounts, to Solana mainnet.
+    pub fn undelegate_all<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, UndelegateAll<'info>>,
+    ) -> Result<()> {
+        instructions::undelegate_all::handler(ctx)
+    }
+
+    /// [Base Layer] Override the magic program and context the commit and
+    /// undelegate paths target (Pubkey::default() for both = the SDK's).
+    /// Signed by the config admin.
+    pub fn set_magic_programs(
+        ctx: Context<SetMagicPrograms>,
+        magic_program: Pubkey,
+        magic_context: Pubkey,
+    ) -> Result<()> {
+        instructions::set_magic_programs::handler(ctx, magic_program, magic_context)
+    }
+
+    /// [Ephemeral Rollup] Apply up to MAX_ACTION_BATCH strategy actions at once,
+    /// validating the session once. Signed by the ESP32 session key; lands
+    /// whole or not at all.
+    pub fn execute_actions_batch(
+        ctx: Context<ExecuteActionsBatch>,
+        actions: Vec<state::ActionSpec>,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        instructions::execute_actions_batch::handler(ctx, actions, fee_lamports)
+    }
+
+    /// [Base Layer] Set how many ER slots a delegated session may go without an
+    /// action before its next one deactivates it (0 = off). Signed by the owner.
+    pub fn set_max_idle_slots(ctx: Context<SetMaxIdleSlots>, max_idle_slots: u64) -> Result<()> {
+        instructions::set_max_idle_slots::handler(ctx, max_idle_slots)
+    }
+
+    /// [Base Layer] Denominate the session's caps and exposure in a stable mint
+    /// (Pubkey::default() = raw amounts), restating every cap in it. Only
+    /// before anything is spent. Signed by the owner.
+    pub fn set_budget_mint(
+        ctx: Context<SetBudgetMint>,
+        budget_mint: Pubkey,
+        caps: state::BudgetCaps,
+    ) -> Result<()> {
+        instructions::set_budget_mint::handler(ctx, budget_mint, caps)
+    }
+
+    /// [Base Layer] Rewrite a session's LpPositionMonitor / PositionRegistry from
+    /// the borsh layout to the current zero-copy one. Signed by the owner, who
+    /// funds any additional rent.
+    pub fn migrate_lp_accounts(ctx: Context<MigrateLpAccounts>) -> Result<()> {
+        instructions::migrate_lp_accounts::handler(ctx)
+    }
+
+    /// [Base Layer] Pin the DLMM pool that values `mint` in the session's budget
+    /// mint (omit the pool to unpin). Signed by the owner.
+    pub fn set_budget_price_pool(ctx: Context<SetBudgetPricePool>, mint: Pubkey) -> Result<()> {
+        instructions::set_budget_price_pool::handler(ctx, mint)
     }
 }
@@ -0,0 +1,13 @@
+/// `msg!` for routine informational logs in execution handlers.
+///
+/// Formatting several integers into a log line costs thousands of CU, enough
+/// to push a DLMM CPI over budget. The line is skipped when the session has
+/// `quiet_logs` set; events, alerts and errors are always emitted.
+#[macro_export]
+macro_rules! log_info {
+    ($session:expr, $($arg:tt)*) => {
+        if !$session.quiet_logs {
+            anchor_lang::prelude::msg!($($arg)*);
+        }
+    };
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::pubkey;
+
+/// Native Address Lookup Table program.
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey =
+    pubkey!("AddressLookupTab1e1111111111111111111111111");
+
+/// Max addresses appended by a single `extend_session_lookup_table` call —
+/// keeps the instruction itself inside the transaction size limit.
+pub const MAX_LOOKUP_TABLE_EXTEND: usize = 20;
+
+// Bincode enum tags of the lookup table program's instructions.
+const CREATE_LOOKUP_TABLE: u32 = 0;
+const EXTEND_LOOKUP_TABLE: u32 = 2;
+
+/// Address of the table `authority` creates at `recent_slot`, and its bump.
+pub fn derive_lookup_table_address(authority: &Pubkey, recent_slot: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[authority.as_ref(), &recent_slot.to_le_bytes()],
+        &ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+    )
+}
+
+/// `CreateLookupTable { recent_slot, bump_seed }` — the authority need not sign.
+pub fn create_lookup_table_ix(
+    lookup_table: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    recent_slot: u64,
+    bump: u8,
+) -> Instruction {
+    let mut data = Vec::with_capacity(4 + 8 + 1);
+    data.extend_from_slice(&CREATE_LOOKUP_TABLE.to_le_bytes());
+    data.extend_from_slice(&recent_slot.to_le_bytes());
+    data.push(bump);
+
+    Instruction {
+        program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(lookup_table, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data,
+    }
+}
+
+/// `ExtendLookupTable { new_addresses }` — the authority must sign.
+pub fn extend_lookup_table_ix(
+    lookup_table: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    new_addresses: &[Pubkey],
+) -> Instruction {
+    let mut data = Vec::with_capacity(4 + 8 + 32 * new_addresses.len());
+    data.extend_from_slice(&EXTEND_LOOKUP_TABLE.to_le_bytes());
+    data.extend_from_slice(&(new_addresses.len() as u64).to_le_bytes());
+    for address in new_addresses {
+        data.extend_from_slice(address.as_ref());
+    }
+
+    Instruction {
+        program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(lookup_table, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data,
+    }
+}
@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use crate::dlmm;
+use crate::errors::AgentError;
+use crate::state::{AgentSession, DailyStats, LpPositionMonitor, PositionRegistry};
+use crate::valuation;
+
+/// Accounts shared by every DLMM step of moving liquidity from one position
+/// into a new one (`execute_dlmm_migrate_position`, `execute_dlmm_widen_range`).
+/// The session key is the DLMM `sender`, payer and owner throughout.
+pub struct Repositioner<'info> {
+    pub session_key: AccountInfo<'info>,
+    pub user_token_x: AccountInfo<'info>,
+    pub user_token_y: AccountInfo<'info>,
+    pub token_x_mint: AccountInfo<'info>,
+    pub token_y_mint: AccountInfo<'info>,
+    pub token_x_program: AccountInfo<'info>,
+    pub token_y_program: AccountInfo<'info>,
+    pub dlmm_program: AccountInfo<'info>,
+    pub event_authority: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// A pool and the bin arrays covering one position's range in it
+#[derive(Clone)]
+pub struct PoolRange<'info> {
+    pub lb_pair: AccountInfo<'info>,
+    pub bin_array_bitmap_extension: Option<AccountInfo<'info>>,
+    pub reserve_x: AccountInfo<'info>,
+    pub reserve_y: AccountInfo<'info>,
+    pub bin_array_lower: AccountInfo<'info>,
+    pub bin_array_upper: AccountInfo<'info>,
+}
+
+impl<'info> Repositioner<'info> {
+    /// `remove_all_liquidity` + `close_position2`: tokens return to the
+    /// session key's token accounts, rent to the session key.
+    pub fn close(&self, position: &AccountInfo<'info>, pool: &PoolRange<'info>) -> Result<()> {
+        let remove_accounts = dlmm::cpi::accounts::RemoveAllLiquidity {
+            position: position.clone(),
+            lb_pair: pool.lb_pair.clone(),
+            bin_array_bitmap_extension: pool.bin_array_bitmap_extension.clone(),
+            user_token_x: self.user_token_x.clone(),
+            user_token_y: self.user_token_y.clone(),
+            reserve_x: pool.reserve_x.clone(),
+            reserve_y: pool.reserve_y.clone(),
+            token_x_mint: self.token_x_mint.clone(),
+            token_y_mint: self.token_y_mint.clone(),
+            bin_array_lower: pool.bin_array_lower.clone(),
+            bin_array_upper: pool.bin_array_upper.clone(),
+            sender: self.session_key.clone(),
+            token_x_program: self.token_x_program.clone(),
+            token_y_program: self.token_y_program.clone(),
+            event_authority: self.event_authority.clone(),
+            program: self.dlmm_program.clone(),
+        };
+        dlmm::cpi::remove_all_liquidity(CpiContext::new(
+            self.dlmm_program.clone(),
+            remove_accounts,
+        ))?;
+
+        let close_accounts = dlmm::cpi::accounts::ClosePosition2 {
+            position: position.clone(),
+            sender: self.session_key.clone(),
+            rent_receiver: self.session_key.clone(),
+            event_authority: self.event_authority.clone(),
+            program: self.dlmm_program.clone(),
+        };
+        dlmm::cpi::close_position2(CpiContext::new(self.dlmm_program.clone(), close_accounts))
+    }
+
+    /// `initialize_position2` over `[lower_bin_id, lower_bin_id + width - 1]`
+    /// + `add_liquidity_by_strategy` of `liquidity_parameter` into it.
+    pub fn open(
+        &self,
+        new_position: &AccountInfo<'info>,
+        pool: &PoolRange<'info>,
+        lower_bin_id: i32,
+        width: i32,
+        liquidity_parameter: dlmm::types::LiquidityParameterByStrategy,
+    ) -> Result<()> {
+        let init_accounts = dlmm::cpi::accounts::InitializePosition2 {
+            payer: self.session_key.clone(),
+            position: new_position.clone(),
+            lb_pair: pool.lb_pair.clone(),
+            owner: self.session_key.clone(),
+            system_program: self.system_program.clone(),
+            event_authority: self.event_authority.clone(),
+            program: self.dlmm_program.clone(),
+        };
+        dlmm::cpi::initialize_position2(
+            CpiContext::new(self.dlmm_program.clone(), init_accounts),
+            lower_bin_id,
+            width,
+        )?;
+
+        let add_accounts = dlmm::cpi::accounts::AddLiquidityByStrategy {
+            position: new_position.clone(),
+            lb_pair: pool.lb_pair.clone(),
+            bin_array_bitmap_extension: pool.bin_array_bitmap_extension.clone(),
+            user_token_x: self.user_token_x.clone(),
+            user_token_y: self.user_token_y.clone(),
+            reserve_x: pool.reserve_x.clone(),
+            reserve_y: pool.reserve_y.clone(),
+            token_x_mint: self.token_x_mint.clone(),
+            token_y_mint: self.token_y_mint.clone(),
+            bin_array_lower: pool.bin_array_lower.clone(),
+            bin_array_upper: pool.bin_array_upper.clone(),
+            sender: self.session_key.clone(),
+            token_x_program: self.token_x_program.clone(),
+            token_y_program: self.token_y_program.clone(),
+            event_authority: self.event_authority.clone(),
+            program: self.dlmm_program.clone(),
+        };
+        dlmm::cpi::add_liquidity_by_strategy(
+            CpiContext::new(self.dlmm_program.clone(), add_accounts),
+            liquidity_parameter,
+        )
+    }
+}
+
+/// A position whose liquidity was withdrawn and re-deposited into a new one
+pub struct MovedPosition {
+    pub position: Pubkey,
+    pub new_position: Pubkey,
+    /// Pool of the new position
+    pub lb_pair: Pubkey,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    /// Tokens the withdrawal returned (and the new position received)
+    pub withdrawn_x: u64,
+    pub withdrawn_y: u64,
+}
+
+/// Carry the session's records over to the new position. The registry entry
+/// keeps its cost basis — the same tokens were re-deposited. The withdrawal
+/// is checked against the monitor's last checkpoint (priced in
+/// `source_lb_pair`), its fee checkpoint added to lifetime earnings, and the
+/// monitor retargeted to the new range.
+pub fn move_records(
+    session: &mut Account<AgentSession>,
+    position_registry: Option<&AccountLoader<PositionRegistry>>,
+    monitor: Option<&AccountLoader<LpPositionMonitor>>,
+    daily_stats: Option<&AccountLoader<DailyStats>>,
+    source_lb_pair: &AccountInfo,
+    moved: &MovedPosition,
+    now: i64,
+) -> Result<()> {
+    if let Some(registry) = position_registry {
+        let mut registry = registry.load_mut()?;
+        let previous = registry.remove(&moved.position);
+        registry.add(moved.new_position, moved.lb_pair, now)?;
+        if let Some(previous) = previous {
+            registry.record_deposit(
+                &moved.new_position,
+                previous.deposited_x,
+                previous.deposited_y,
+                previous.cost_basis,
+            );
+        }
+    }
+    if let Some(monitor) = monitor {
+        let mut monitor = monitor.load_mut()?;
+        valuation::verify_settlement(
+            session,
+            &monitor,
+            source_lb_pair,
+            moved.withdrawn_x,
+            moved.withdrawn_y,
+        )?;
+        session.record_fees_earned(&monitor);
+        if let Some(stats) = daily_stats {
+            stats.load_mut()?.record_fees_earned(now, &monitor);
+        }
+        let bump = monitor.bump;
+        monitor.track(
+            session.key(),
+            moved.lb_pair,
+            moved.new_position,
+            moved.min_bin_id,
+            moved.max_bin_id,
+            bump,
+        );
+    }
+    Ok(())
+}
+
+/// `(lower_bin_id, width)` of a range `extra` bins wider on each side than
+/// `[lower, upper]`, centered on `active_id`. `None` on overflow.
+pub fn widened_range(lower: i32, upper: i32, active_id: i32, extra: u16) -> Option<(i32, i32)> {
+    let width = upper.checked_sub(lower)?.checked_add(1 + 2 * extra as i32)?;
+    Some((active_id.checked_sub(width / 2)?, width))
+}
+
+/// Upper bin of a range starting at `lower_bin_id`, `width` bins wide
+pub fn max_bin_id(lower_bin_id: i32, width: i32) -> Result<i32> {
+    lower_bin_id
+        .checked_add(width - 1)
+        .ok_or(AgentError::InvalidBinRange.into())
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::errors::AgentError;
+
+/// Number of receipts an ActionReceiptLog holds. While delegated, a slot is
+/// only recycled once its receipt was committed, so at most this many
+/// actions run between commits (a `max_uncommitted_actions` at or below it
+/// never hits the limit).
+pub const ACTION_RECEIPTS: usize = 64;
+
+#[zero_copy]
+pub struct ActionReceipt {
+    /// Position of the action in the log, starting at 1 (8)
+    pub nonce: u64,
+
+    /// Slot the action executed in (8)
+    pub slot: u64,
+
+    /// `ActionReceipt::digest` of the executed action (32)
+    pub hash: [u8; 32],
+}
+
+impl ActionReceipt {
+    pub const LEN: usize = 8 + 8 + 32;
+
+    /// sha256 over the session, signing device, the action's arguments and
+    /// its place in the log — what an off-chain auditor recomputes from the
+    /// ER transaction to match it against a committed receipt
+    pub fn digest(
+        session: &Pubkey,
+        device: &Pubkey,
+        action_type: u8,
+        amount_lamports: u64,
+        reason: u8,
+        nonce: u64,
+        slot: u64,
+    ) -> [u8; 32] {
+        hashv(&[
+            session.as_ref(),
+            device.as_ref(),
+            &[action_type],
+            &amount_lamports.to_le_bytes(),
+            &[reason],
+            &nonce.to_le_bytes(),
+            &slot.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+}
+
+/// Receipts of the actions a session executed, recorded on the Ephemeral
+/// Rollup so each commit carries evidence of exactly which actions were
+/// applied.
+///
+/// Created by `initialize_receipt_log` (owner signs, base layer) and
+/// delegated alongside the session by `delegate_receipt_log`;
+/// `commit_session` and `undelegate_session` commit it when passed.
+/// `execute_action` and `execute_actions_batch` append a receipt per action
+/// and must pass it once it exists. A ring buffer of `ACTION_RECEIPTS`
+/// entries: receipt `nonce` lives in slot `(nonce - 1) % ACTION_RECEIPTS`,
+/// `count` is the latest nonce and `committed` the latest one landed on the
+/// base layer.
+///
+/// Zero-copy (`AccountLoader`) so per-action updates write in place.
+///
+/// Seeds: [b"receipts", session.key().as_ref()]
+#[account(zero_copy)]
+pub struct ActionReceiptLog {
+    /// The AgentSession these receipts belong to (32)
+    pub session: Pubkey,
+
+    /// Receipts recorded so far — the nonce of the latest one (8)
+    pub count: u64,
+
+    /// Nonce of the latest receipt committed to the base layer (8)
+    pub committed: u64,
+
+    /// Receipt slots (48 × ACTION_RECEIPTS)
+    pub receipts: [ActionReceipt; ACTION_RECEIPTS],
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+
+    /// Alignment padding (7)
+    pub _padding: [u8; 7],
+}
+
+impl ActionReceiptLog {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + 8   // count
+        + 8   // committed
+        + ActionReceipt::LEN * ACTION_RECEIPTS  // receipts
+        + 1   // bump
+        + 7;  // _padding
+
+    /// The receipt with `nonce`, if the ring still holds it
+    pub fn get(&self, nonce: u64) -> Option<&ActionReceipt> {
+        if nonce == 0 {
+            return None;
+        }
+        let receipt = &self.receipts[((nonce - 1) % ACTION_RECEIPTS as u64) as usize];
+        (receipt.nonce == nonce).then_some(receipt)
+    }
+
+    /// Mark every receipt recorded so far as committed
+    pub fn mark_committed(&mut self) {
+        self.committed = self.count;
+    }
+
+    /// Append a receipt for an action executed in `slot`; returns its nonce.
+    /// While `delegated`, fails with `CommitRequired` rather than recycle the
+    /// slot of a receipt that hasn't been committed; base-layer receipts land
+    /// as they are written.
+    pub fn record(
+        &mut self,
+        device: &Pubkey,
+        action_type: u8,
+        amount_lamports: u64,
+        reason: u8,
+        slot: u64,
+        delegated: bool,
+    ) -> Result<u64> {
+        if !delegated {
+            self.mark_committed();
+        }
+        let nonce = self.count.checked_add(1).ok_or(AgentError::Overflow)?;
+        require!(
+            nonce - self.committed <= ACTION_RECEIPTS as u64,
+            AgentError::CommitRequired
+        );
+        let hash = ActionReceipt::digest(
+            &self.session,
+            device,
+            action_type,
+            amount_lamports,
+            reason,
+            nonce,
+            slot,
+        );
+        self.receipts[((nonce - 1) % ACTION_RECEIPTS as u64) as usize] =
+            ActionReceipt { nonce, slot, hash };
+        self.count = nonce;
+        Ok(nonce)
+    }
+}
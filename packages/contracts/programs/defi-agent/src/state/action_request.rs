@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+/// Request kinds — which execute instruction an approval unlocks
+pub const REQUEST_DLMM_SWAP: u8 = 0;
+pub const REQUEST_DLMM_ADD_LIQUIDITY: u8 = 1;
+
+/// A device's request to run one action outside its normal scope.
+///
+/// Filed by a device key with `file_action_request` when an action would be
+/// rejected by the session scope (pool outside the registry, amount above
+/// the per-action cap, notional above the co-sign threshold). Once the owner
+/// signs `approve_request`, the device may run exactly that action once: the
+/// matching execute instruction consumes and closes this account.
+///
+/// Seeds: [b"action_request", session.key().as_ref(), request_id.to_le_bytes()]
+#[account]
+pub struct ActionRequest {
+    /// The AgentSession the request belongs to (32)
+    pub session: Pubkey,
+
+    /// Device key that filed the request and must execute it (32)
+    pub device_key: Pubkey,
+
+    /// Device-chosen identifier, unique per session (8)
+    pub request_id: u64,
+
+    /// Which execute instruction this unlocks: REQUEST_DLMM_* (1)
+    pub kind: u8,
+
+    /// Meteora DLMM pool (LbPair) the action targets (32)
+    pub lb_pair: Pubkey,
+
+    /// Exact notional of the action (amount_in, or amount_x + amount_y) (8)
+    pub amount: u64,
+
+    /// Set by the owner in `approve_request` (1)
+    pub approved: bool,
+
+    /// Unix timestamp the request was filed (8)
+    pub created_at: i64,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+}
+
+impl ActionRequest {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + 32  // device_key
+        + 8   // request_id
+        + 1   // kind
+        + 32  // lb_pair
+        + 8   // amount
+        + 1   // approved
+        + 8   // created_at
+        + 1;  // bump
+
+    /// True when this request authorizes `device_key` to run `kind` against
+    /// `lb_pair` for exactly `amount`.
+    pub fn authorizes(&self, kind: u8, device_key: &Pubkey, lb_pair: &Pubkey, amount: u64) -> bool {
+        self.approved
+            && self.kind == kind
+            && self.device_key == *device_key
+            && self.lb_pair == *lb_pair
+            && self.amount == amount
+    }
+}
@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::dlmm::types::StrategyType;
 use crate::errors::AgentError;
+use crate::state::{
+    LpPositionMonitor, PairPools, PoolRegistry, BPS_DENOMINATOR, FEE_MODE_BPS, FEE_MODE_FLAT,
+};
 
 /// Strategy bitmask flags — combine with bitwise OR to enable multiple
 pub const STRATEGY_LP: u8 = 1 << 0;             // Concentrated LP rebalancing
@@ -7,18 +12,267 @@ pub const STRATEGY_YIELD: u8 = 1 << 1;          // Lending yield switching
 pub const STRATEGY_LIQUIDATION: u8 = 1 << 2;    // Leveraged position protection
 pub const STRATEGY_ALL: u8 = STRATEGY_LP | STRATEGY_YIELD | STRATEGY_LIQUIDATION;
 
+/// Number of strategies spend is attributed to; `spent_by_strategy` is indexed
+/// by the strategy's bit position (0 = LP, 1 = yield, 2 = liquidation)
+pub const STRATEGY_COUNT: usize = 3;
+
 /// Action type indices (used as array index into strategy bitmask)
 pub const ACTION_LP_REBALANCE: u8 = 0;
 pub const ACTION_YIELD_SWITCH: u8 = 1;
 pub const ACTION_LIQUIDATION_PROTECT: u8 = 2;
 
+/// DLMM operation action types — each DLMM instruction validates, bills and
+/// emits under its own code. All of them require STRATEGY_LP.
+pub const ACTION_DLMM_SWAP: u8 = 3;             // swap, route swap, intent fills
+pub const ACTION_DLMM_ADD_LIQUIDITY: u8 = 4;    // deposits into existing positions
+pub const ACTION_DLMM_REMOVE_LIQUIDITY: u8 = 5; // withdrawals and position closes
+pub const ACTION_DLMM_OPEN_POSITION: u8 = 6;    // create, adopt and migrate
+
+/// Why the agent rebalanced or closed, passed by the device and carried in
+/// `ActionExecuted.reason`. Actions that take no reason emit REASON_UNSPECIFIED.
+pub const REASON_UNSPECIFIED: u8 = 0;
+pub const REASON_PRICE_UP: u8 = 1;      // active bin moved above the position's range
+pub const REASON_PRICE_DOWN: u8 = 2;    // active bin moved below the position's range
+pub const REASON_FEE_COMPOUND: u8 = 3;  // reinvesting earned fees
+pub const REASON_MANUAL: u8 = 4;        // requested by the owner or operator
+
+/// Maximum number of actions `execute_actions_batch` applies in one instruction
+pub const MAX_ACTION_BATCH: usize = 8;
+
+/// One action of an `execute_actions_batch` call — the per-action arguments
+/// of `execute_action`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ActionSpec {
+    pub action_type: u8,
+    pub amount_lamports: u64,
+    pub reason: u8,
+}
+
+/// Per-operation bits in `strategy_mask`. They narrow STRATEGY_LP: with none
+/// set every DLMM operation is allowed; once any is set, only those are.
+pub const STRATEGY_DLMM_SWAP: u8 = 1 << ACTION_DLMM_SWAP;
+pub const STRATEGY_DLMM_ADD_LIQUIDITY: u8 = 1 << ACTION_DLMM_ADD_LIQUIDITY;
+pub const STRATEGY_DLMM_REMOVE_LIQUIDITY: u8 = 1 << ACTION_DLMM_REMOVE_LIQUIDITY;
+pub const STRATEGY_DLMM_OPEN_POSITION: u8 = 1 << ACTION_DLMM_OPEN_POSITION;
+pub const STRATEGY_DLMM_OPS: u8 = STRATEGY_DLMM_SWAP
+    | STRATEGY_DLMM_ADD_LIQUIDITY
+    | STRATEGY_DLMM_REMOVE_LIQUIDITY
+    | STRATEGY_DLMM_OPEN_POSITION;
+
+/// Liquidity-shape bits in `liquidity_shapes`: bit `i` allows the DLMM
+/// `StrategyType` variant with index `i` (see `liquidity_shape_bit`). The
+/// presets cover a distribution in its one-sided, balanced and imbalanced
+/// forms; with no bit set every shape is allowed.
+pub const LIQUIDITY_SHAPE_SPOT: u16 = 1 << 0 | 1 << 3 | 1 << 6;
+pub const LIQUIDITY_SHAPE_CURVE: u16 = 1 << 1 | 1 << 4 | 1 << 7;
+pub const LIQUIDITY_SHAPE_BID_ASK: u16 = 1 << 2 | 1 << 5 | 1 << 8;
+pub const LIQUIDITY_SHAPES_ALL: u16 =
+    LIQUIDITY_SHAPE_SPOT | LIQUIDITY_SHAPE_CURVE | LIQUIDITY_SHAPE_BID_ASK;
+
+/// The `liquidity_shapes` bit for a DLMM deposit distribution
+pub fn liquidity_shape_bit(strategy_type: &StrategyType) -> u16 {
+    1 << match strategy_type {
+        StrategyType::SpotOneSide => 0,
+        StrategyType::CurveOneSide => 1,
+        StrategyType::BidAskOneSide => 2,
+        StrategyType::SpotBalanced => 3,
+        StrategyType::CurveBalanced => 4,
+        StrategyType::BidAskBalanced => 5,
+        StrategyType::SpotImBalanced => 6,
+        StrategyType::CurveImBalanced => 7,
+        StrategyType::BidAskImBalanced => 8,
+    }
+}
+
+/// Maximum number of device keys (ESP32s, phone-side signers) per session
+pub const MAX_DEVICES: usize = 4;
+
+/// Delegation status values — which layer currently owns the session
+pub const DELEGATION_UNDELEGATED: u8 = 0; // owned by the program on the base layer
+pub const DELEGATION_DELEGATED: u8 = 1;   // delegated to the Ephemeral Rollup
+
+/// Maximum number of mints a session can set a minimum trade amount for
+pub const MAX_MIN_TRADE_MINTS: usize = 4;
+
+/// Maximum number of mints a session can pin a budget price pool for
+pub const MAX_BUDGET_PRICE_POOLS: usize = 4;
+
+/// Wrapped SOL mint — its minimum also applies to lamport-denominated
+/// `execute_action` notionals (see `AgentSession::notional_mint`)
+pub const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+/// Absolute session lifetime bound, independent of the Config ceiling
+/// (which defaults to `i64::MAX` until the admin tightens it)
+pub const MAX_SESSION_DURATION_SECS: i64 = 60 * 60 * 24 * 365;
+
+/// Maximum number of DLMM positions a session can be explicitly bound to
+pub const MAX_BOUND_POSITIONS: usize = 8;
+
+/// A key enrolled to sign agent actions for a session.
+/// An all-zero `key` marks an empty slot.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeviceKey {
+    /// Device signing key (32)
+    pub key: Pubkey,
+
+    /// Device-specific expiry; 0 = valid for the session's lifetime (8)
+    pub expires_at: i64,
+
+    /// Device-specific cumulative spend cap; 0 = bounded only by the session cap (8)
+    pub max_lamports: u64,
+
+    /// Lamports spent by actions this device signed (8)
+    pub spent_lamports: u64,
+
+    /// Hash of the device's hardware ID / secure-element certificate, supplied
+    /// by the owner at enrollment; all-zero = not attested (32)
+    pub attestation_hash: [u8; 32],
+
+    /// Secure-element certificate key that must sign an ed25519 intent for
+    /// every action this device submits; default = no intent required (32)
+    pub intent_signer: Pubkey,
+
+    /// Highest intent nonce consumed for this device (8)
+    pub intent_nonce: u64,
+
+    /// Set by the owner to revoke this key while keeping its record (1)
+    pub disabled: bool,
+
+    /// Standby activation delay; 0 = regular device. A standby device is only
+    /// valid once every regular device has been silent this long (8)
+    pub standby_after_secs: i64,
+
+    /// Last time this key signed a session instruction (heartbeat) (8)
+    pub last_seen_at: i64,
+}
+
+impl DeviceKey {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 32 + 32 + 8 + 1 + 8 + 8;
+
+    pub fn is_empty(&self) -> bool {
+        self.key == Pubkey::default()
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+
+    pub fn is_standby(&self) -> bool {
+        self.standby_after_secs > 0
+    }
+}
+
+/// What a session-key instruction is about to do, for `validate_session`'s
+/// strategy check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionKind {
+    /// An agent action — its `ACTION_*` code must be permitted by the strategy mask
+    Action(u8),
+    /// LP monitoring (status checkpoints) — requires the LP strategy bit
+    LpMonitor,
+    /// Housekeeping with no strategy scope (heartbeats, lookup tables, requests)
+    Housekeeping,
+}
+
+/// Whose clock reading a validation stands for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TemporalSource {
+    /// The device signed: `now` is observed (advancing the clock high-water
+    /// mark) and recorded as the device's heartbeat
+    Device(i64),
+    /// The device did not sign, or the call must not count as activity
+    /// (keeper fills, action requests): `now` is only read
+    Passive(i64),
+}
+
+impl TemporalSource {
+    pub fn now(self) -> i64 {
+        match self {
+            TemporalSource::Device(now) | TemporalSource::Passive(now) => now,
+        }
+    }
+}
+
+/// Longest off-chain metadata URI a session stores, in bytes
+pub const MAX_METADATA_URI_LEN: usize = 128;
+
+/// Owner-set dust threshold for one mint, in the mint's base units.
+/// An all-zero `mint` marks an empty slot.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MinTradeAmount {
+    /// Token mint the threshold applies to (32)
+    pub mint: Pubkey,
+
+    /// Smallest accepted per-action amount of `mint` (8)
+    pub amount: u64,
+}
+
+impl MinTradeAmount {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// Owner-pinned DLMM pool pairing `mint` with the session's budget mint —
+/// the only pool `valuation::budget_price` accepts for `mint`.
+/// An all-zero `mint` marks an empty slot.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BudgetPricePool {
+    /// Token mint the pool prices (32)
+    pub mint: Pubkey,
+
+    /// LbPair pairing `mint` with the budget mint (32)
+    pub lb_pair: Pubkey,
+}
+
+impl BudgetPricePool {
+    pub const LEN: usize = 32 + 32;
+}
+
+/// Every cap counted in the session's budget unit, restated by
+/// `set_budget_mint` whenever the unit changes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BudgetCaps {
+    /// Cumulative spend cap, `max_lamports`
+    pub max_lamports: u64,
+
+    /// Per-action cap, `max_action_lamports`
+    pub max_action_lamports: u64,
+
+    /// Co-sign threshold, `cosign_above_lamports`; 0 = never
+    pub cosign_above_lamports: u64,
+
+    /// Per-device spend caps by device slot; 0 = bounded only by the session
+    /// cap. Empty slots must be 0.
+    pub device_max_lamports: [u64; MAX_DEVICES],
+}
+
+/// `alerts_raised` bits: thresholds whose alert has already fired
+pub const ALERT_FEES: u8 = 1 << 0;
+pub const ALERT_EXPOSURE: u8 = 1 << 1;
+
+/// Owner-set alert thresholds, evaluated on-chain. A zero field is off.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AlertConfig {
+    /// Alert once priority and signature fees paid reach this many lamports (8)
+    pub fees_lamports: u64,
+
+    /// Alert when the monitored position has been out of range this long (8)
+    pub out_of_range_secs: i64,
+
+    /// Alert once exposure reaches this share of `max_lamports`, in bps (2)
+    pub exposure_bps: u16,
+}
+
+impl AlertConfig {
+    pub const LEN: usize = 8 + 8 + 2;
+}
+
 #[account]
 pub struct AgentSession {
     /// The user wallet that owns and created this session (32)
     pub owner: Pubkey,
 
-    /// Pubkey of the ESP32 session key authorized to sign actions (32)
-    pub session_key: Pubkey,
+    /// Device keys authorized to sign actions; slot 0 is the key given at init (DeviceKey::LEN × MAX_DEVICES)
+    pub devices: [DeviceKey; MAX_DEVICES],
 
     /// Unix timestamp when this session expires (8)
     pub expires_at: i64,
@@ -43,12 +297,232 @@ pub struct AgentSession {
 
     /// Unix timestamp of the last executed action (8)
     pub last_action_at: i64,
+
+    /// Owner-set cap on cumulative priority fees + Jito tips paid by the device (8)
+    pub fee_budget_lamports: u64,
+
+    /// Running total of declared priority fees + tips across all actions (8)
+    pub fee_spent_lamports: u64,
+
+    /// When true, DLMM instructions may only target pools in the global PoolRegistry (1)
+    pub registry_only: bool,
+
+    /// Maximum notional exposure of a single action (8)
+    pub max_action_lamports: u64,
+
+    /// Integrator that receives a share of protocol fees; Pubkey::default() if none (32)
+    pub referrer: Pubkey,
+
+    /// Per-action billing mode snapshotted from the chosen Config fee tier (1)
+    pub fee_mode: u8,
+
+    /// Flat lamports per action, or bps of notional, depending on fee_mode (8)
+    pub fee_amount: u64,
+
+    /// Running total of per-action protocol fees paid by the session key (8)
+    pub protocol_fees_paid: u64,
+
+    /// Actions with a notional above this also need the owner's signature; 0 = never (8)
+    pub cosign_above_lamports: u64,
+
+    /// DLMM positions the session may manage; all-default = any position (32 × MAX_BOUND_POSITIONS)
+    pub bound_positions: [Pubkey; MAX_BOUND_POSITIONS],
+
+    /// Suppress routine `msg!` logs in execution handlers to save compute (1)
+    pub quiet_logs: bool,
+
+    /// Session-owned Address Lookup Table; Pubkey::default() when none (32)
+    pub lookup_table: Pubkey,
+
+    /// Position emptied by `execute_dlmm_remove_all_liquidity`, awaiting
+    /// `execute_dlmm_close_empty_position`; Pubkey::default() when none (32)
+    pub emptied_position: Pubkey,
+
+    /// Latest unix time any instruction has observed for this session. Expiry
+    /// is judged against `max(clock, clock_high_water)`, so a clock that lags
+    /// the base layer (the Ephemeral Rollup's, while delegated) can never
+    /// stretch the session past `expires_at` (8)
+    pub clock_high_water: i64,
+
+    /// `DELEGATION_UNDELEGATED` or `DELEGATION_DELEGATED`; set by
+    /// `delegate_session` and `undelegate_session` (1)
+    pub delegation_status: u8,
+
+    /// Per-mint minimum action amounts set by `set_min_trade_amount`; actions
+    /// below a mint's minimum are rejected (40 * MAX_MIN_TRADE_MINTS)
+    pub min_trade_amounts: [MinTradeAmount; MAX_MIN_TRADE_MINTS],
+
+    /// Mint every portfolio valuation is denominated in — the Y mint of the
+    /// pool that priced the first valuation; Pubkey::default() until then (32)
+    pub valuation_mint: Pubkey,
+
+    /// Portfolio value at the first valuation, in `valuation_mint` units (8)
+    pub entry_value: u64,
+
+    /// Portfolio value at the latest valuation (8)
+    pub last_value: u64,
+
+    /// Realized PnL accumulated over all valuations: `last_value - entry_value` (8)
+    pub realized_pnl: i64,
+
+    /// When the latest valuation was recorded; 0 = never (8)
+    pub last_valued_at: i64,
+
+    /// Lifetime LP fees earned in token X, rolled up from each monitor's last
+    /// fee checkpoint when its position is closed or migrated (8)
+    pub total_fees_earned_x: u64,
+
+    /// Lifetime LP fees earned in token Y, as `total_fees_earned_x` (8)
+    pub total_fees_earned_y: u64,
+
+    /// `spent_lamports` split by the strategy each action belongs to — LP
+    /// (every DLMM operation included), yield, liquidation protection. Always
+    /// sums to `spent_lamports` (8 × STRATEGY_COUNT)
+    pub spent_by_strategy: [u64; STRATEGY_COUNT],
+
+    /// Sequence number of the latest `snapshot_session`; 0 = none taken (8)
+    pub snapshot_seq: u64,
+
+    /// Outstanding exposure at market value: every spend at cost, moved by the
+    /// registry positions' unrealized gain as of the last `revalue_exposure` (8)
+    pub current_exposure_value: u64,
+
+    /// When `revalue_exposure` last ran; 0 = never (8)
+    pub exposure_valued_at: i64,
+
+    /// When true, the exposure cap is checked against `current_exposure_value`
+    /// instead of the historical `spent_lamports`; set by `set_mark_to_market` (1)
+    pub mark_to_market: bool,
+
+    /// Running total of transaction signature fees paid for the session's
+    /// instructions, counted from each transaction at the base-layer rate (8)
+    pub tx_fees_lamports: u64,
+
+    /// Owner-set cap on `tx_fees_lamports`; 0 = uncapped (8)
+    pub tx_fee_budget_lamports: u64,
+
+    /// Running total of realized swap slippage — output short of the
+    /// pre-trade active-bin quote — in each swap's output-pool Y units (8)
+    pub slippage_paid: u64,
+
+    /// Per-swap slippage above which `SlippageExceeded` is emitted, in basis
+    /// points; 0 = no alerts. Set by `set_slippage_alert` (2)
+    pub max_slippage_bps: u16,
+
+    /// Set when a close withdrew amounts that diverge from the monitor's last
+    /// checkpoint (`SettlementDiscrepancy`); cleared by `acknowledge_review` (1)
+    pub needs_review: bool,
+
+    /// Consecutive scope violations by enrolled devices that suspend the
+    /// session; 0 = freeze off. Set by `set_violation_freeze` (1)
+    pub violation_threshold: u8,
+
+    /// Scope violations recorded since the last executed action (1)
+    pub consecutive_violations: u8,
+
+    /// Set once `consecutive_violations` reaches `violation_threshold`; every
+    /// device call is rejected until `set_violation_freeze` clears it (1)
+    pub suspended: bool,
+
+    /// Length of the cooldown an `ExposureLimitExceeded` rejection starts, in
+    /// seconds; 0 = off. Set by `set_exposure_cooldown` (8)
+    pub exposure_cooldown_secs: i64,
+
+    /// Execute calls fail with `ExposureCooldown` until this time (8)
+    pub exposure_cooldown_until: i64,
+
+    /// Alert thresholds, set by `set_alert_config` (AlertConfig::LEN)
+    pub alert_config: AlertConfig,
+
+    /// `ALERT_*` bits for the one-shot alerts already emitted; cleared by
+    /// `set_alert_config` and `renew_session` (1)
+    pub alerts_raised: u8,
+
+    /// Owner-set display name, UTF-8, zero-padded; all-zero = unnamed (32)
+    pub label: [u8; 32],
+
+    /// Off-chain metadata URI, UTF-8, zero-padded; all-zero = none
+    /// (MAX_METADATA_URI_LEN)
+    pub metadata_uri: [u8; MAX_METADATA_URI_LEN],
+
+    /// Relayer allowed to pay transaction fees for device-signed
+    /// instructions; holds no spending authority and may never sign as a
+    /// device. Pubkey::default() = none. Set by `set_fee_payer` (32)
+    pub fee_payer: Pubkey,
+
+    /// `LIQUIDITY_SHAPE_*` bits: the distributions deposits may use; 0 = any.
+    /// Set by `set_liquidity_shapes` (2)
+    pub liquidity_shapes: u16,
+
+    /// Number of PairPools lists the owner keeps; while non-zero, new
+    /// positions and migration targets must be on one (1)
+    pub pair_pool_lists: u8,
+
+    /// Running total of estimated DLMM pool swap fees, in each swap's pool Y
+    /// units like `slippage_paid` (8)
+    pub swap_fees_paid: u64,
+
+    /// Owner-set cap on `swap_fees_paid`; 0 = uncapped. Set by
+    /// `set_swap_fee_budget` (8)
+    pub swap_fee_budget: u64,
+
+    /// Actions the session may execute on the ER between commits; 0 = no
+    /// limit. Set by `set_commit_cadence` (4)
+    pub max_uncommitted_actions: u32,
+
+    /// Seconds the session may run on the ER since its last commit before
+    /// actions stop; 0 = no limit. Set by `set_commit_cadence` (8)
+    pub max_uncommitted_secs: i64,
+
+    /// Actions executed on the ER since the last commit (4)
+    pub uncommitted_actions: u32,
+
+    /// Session time of the last commit — or of delegation, which starts the
+    /// first window (8)
+    pub last_committed_at: i64,
+
+    /// ER slots a delegated session may go without an action before its next
+    /// ER action deactivates it; 0 = off. Set by `set_max_idle_slots` (8)
+    pub max_idle_slots: u64,
+
+    /// ER slot of the last action executed while delegated; 0 until the
+    /// first one after delegation (8)
+    pub er_last_action_slot: u64,
+
+    /// `commit_session` checkpoints landed over the session's lifetime (8)
+    pub checkpoint_count: u64,
+
+    /// Stable mint the session's caps and exposure are denominated in;
+    /// default = raw amounts. Set by `set_budget_mint` (32)
+    pub budget_mint: Pubkey,
+
+    /// Registry positions' value less their cost basis at the last
+    /// `revalue_exposure`, already folded into `current_exposure_value` (8)
+    pub exposure_mark: i64,
+
+    /// The session's ActionReceiptLog; default = none. Once set by
+    /// `initialize_receipt_log`, execute calls must pass it (32)
+    pub receipt_log: Pubkey,
+
+    /// Owner-pinned budget price pools, one per mint, set by
+    /// `set_budget_price_pool` (64 * MAX_BUDGET_PRICE_POOLS)
+    pub budget_price_pools: [BudgetPricePool; MAX_BUDGET_PRICE_POOLS],
+}
+
+/// Index into `spent_by_strategy` for an action type: the strategy whose bit
+/// gates it. Every DLMM operation counts toward LP.
+pub fn strategy_index(action_type: u8) -> Option<usize> {
+    match action_type {
+        ACTION_LP_REBALANCE..=ACTION_LIQUIDATION_PROTECT => Some(action_type as usize),
+        ACTION_DLMM_SWAP..=ACTION_DLMM_OPEN_POSITION => Some(ACTION_LP_REBALANCE as usize),
+        _ => None,
+    }
 }
 
 impl AgentSession {
     pub const LEN: usize = 8   // discriminator
         + 32  // owner
-        + 32  // session_key
+        + DeviceKey::LEN * MAX_DEVICES  // devices
         + 8   // expires_at
         + 8   // max_lamports
         + 8   // spent_lamports
@@ -56,31 +530,687 @@ impl AgentSession {
         + 1   // bump
         + 1   // strategy_mask
         + 8   // total_actions
-        + 8;  // last_action_at
+        + 8   // last_action_at
+        + 8   // fee_budget_lamports
+        + 8   // fee_spent_lamports
+        + 1   // registry_only
+        + 8   // max_action_lamports
+        + 32  // referrer
+        + 1   // fee_mode
+        + 8   // fee_amount
+        + 8   // protocol_fees_paid
+        + 8   // cosign_above_lamports
+        + 32 * MAX_BOUND_POSITIONS  // bound_positions
+        + 1   // quiet_logs
+        + 32  // lookup_table
+        + 32  // emptied_position
+        + 8   // clock_high_water
+        + 1   // delegation_status
+        + MinTradeAmount::LEN * MAX_MIN_TRADE_MINTS  // min_trade_amounts
+        + 32  // valuation_mint
+        + 8   // entry_value
+        + 8   // last_value
+        + 8   // realized_pnl
+        + 8   // last_valued_at
+        + 8   // total_fees_earned_x
+        + 8   // total_fees_earned_y
+        + 8 * STRATEGY_COUNT  // spent_by_strategy
+        + 8   // snapshot_seq
+        + 8   // current_exposure_value
+        + 8   // exposure_valued_at
+        + 1   // mark_to_market
+        + 8   // tx_fees_lamports
+        + 8   // tx_fee_budget_lamports
+        + 8   // slippage_paid
+        + 2   // max_slippage_bps
+        + 1   // needs_review
+        + 1   // violation_threshold
+        + 1   // consecutive_violations
+        + 1   // suspended
+        + 8   // exposure_cooldown_secs
+        + 8   // exposure_cooldown_until
+        + AlertConfig::LEN  // alert_config
+        + 1   // alerts_raised
+        + 32  // label
+        + MAX_METADATA_URI_LEN  // metadata_uri
+        + 32  // fee_payer
+        + 2   // liquidity_shapes
+        + 1   // pair_pool_lists
+        + 8   // swap_fees_paid
+        + 8   // swap_fee_budget
+        + 4   // max_uncommitted_actions
+        + 8   // max_uncommitted_secs
+        + 4   // uncommitted_actions
+        + 8   // last_committed_at
+        + 8   // max_idle_slots
+        + 8   // er_last_action_slot
+        + 8   // checkpoint_count
+        + 32  // budget_mint
+        + 8   // exposure_mark
+        + 32  // receipt_log
+        + BudgetPricePool::LEN * MAX_BUDGET_PRICE_POOLS; // budget_price_pools
+
+    /// The session's view of the current time: the layer's clock, never
+    /// earlier than the latest time already observed.
+    pub fn session_now(&self, now: i64) -> i64 {
+        now.max(self.clock_high_water)
+    }
+
+    /// Record `now` as observed and return the session time.
+    pub fn observe_clock(&mut self, now: i64) -> i64 {
+        self.clock_high_water = self.session_now(now);
+        self.clock_high_water
+    }
+
+    /// Whether the session is currently delegated to the Ephemeral Rollup.
+    /// On the ER the account reads as program-owned, so base-layer-only
+    /// instructions check this to refuse running against the ER copy.
+    pub fn is_delegated(&self) -> bool {
+        self.delegation_status == DELEGATION_DELEGATED
+    }
 
     pub fn is_expired(&self, now: i64) -> bool {
-        now >= self.expires_at
+        self.session_now(now) >= self.expires_at
     }
 
     /// Returns true if the given action type's strategy bit is enabled
     pub fn has_strategy(&self, action_type: u8) -> bool {
-        let bit = 1u8 << action_type;
-        self.strategy_mask & bit != 0
+        match action_type {
+            ACTION_LP_REBALANCE..=ACTION_LIQUIDATION_PROTECT => {
+                self.strategy_mask & (1u8 << action_type) != 0
+            }
+            ACTION_DLMM_SWAP..=ACTION_DLMM_OPEN_POSITION => {
+                let ops = self.strategy_mask & STRATEGY_DLMM_OPS;
+                self.strategy_mask & STRATEGY_LP != 0
+                    && (ops == 0 || ops & (1u8 << action_type) != 0)
+            }
+            _ => false,
+        }
+    }
+
+    /// Slot index of `key` in the device list, if enrolled.
+    pub fn device_index(&self, key: &Pubkey) -> Option<usize> {
+        if *key == Pubkey::default() {
+            return None;
+        }
+        self.devices.iter().position(|d| d.key == *key)
+    }
+
+    /// True when `key` is the session owner or one of its enrolled device keys.
+    pub fn is_owner_or_device(&self, key: &Pubkey) -> bool {
+        *key == self.owner || self.device_index(key).is_some()
+    }
+
+    /// Require `key` to be one of the session's enrolled, enabled, unexpired device keys.
+    pub fn require_device(&self, key: &Pubkey, now: i64) -> Result<usize> {
+        let now = self.session_now(now);
+        let slot = self
+            .device_index(key)
+            .ok_or(AgentError::UnauthorizedSessionKey)?;
+        let device = &self.devices[slot];
+        require!(!device.disabled, AgentError::DeviceDisabled);
+        require!(!device.is_expired(now), AgentError::DeviceExpired);
+        if device.is_standby() {
+            require!(
+                self.primaries_silent_for(device.standby_after_secs, now),
+                AgentError::StandbyNotActive
+            );
+        }
+        Ok(slot)
     }
 
-    /// Validate session state for any LP DLMM instruction (active, not expired,
-    /// correct session key, LP strategy enabled). Consolidates the repeated
-    /// 4-line validation block across execute_dlmm_swap/add_liquidity/close_position.
-    pub fn validate_lp_session(&self, session_key: Pubkey, timestamp: i64) -> Result<()> {
+    /// True when no live regular (non-standby) device has heartbeated within
+    /// the last `secs` seconds.
+    pub fn primaries_silent_for(&self, secs: i64, now: i64) -> bool {
+        let now = self.session_now(now);
+        self.devices
+            .iter()
+            .filter(|d| !d.is_empty() && !d.disabled && !d.is_standby() && !d.is_expired(now))
+            .all(|d| d.last_seen_at.saturating_add(secs) <= now)
+    }
+
+    /// `require_device`, then record the call as the device's heartbeat.
+    pub fn authorize_device(&mut self, key: &Pubkey, now: i64) -> Result<usize> {
+        let now = self.observe_clock(now);
+        let slot = self.require_device(key, now)?;
+        self.devices[slot].last_seen_at = now;
+        Ok(slot)
+    }
+
+    /// Enroll `key` in the first free device slot.
+    pub fn enroll_device(
+        &mut self,
+        key: Pubkey,
+        attestation_hash: [u8; 32],
+        now: i64,
+    ) -> Result<usize> {
+        require!(key != Pubkey::default(), AgentError::InvalidDeviceKey);
+        require!(key != self.fee_payer, AgentError::InvalidFeePayer);
+        require!(self.device_index(&key).is_none(), AgentError::DeviceAlreadyEnrolled);
+        let slot = self
+            .devices
+            .iter()
+            .position(DeviceKey::is_empty)
+            .ok_or(AgentError::DeviceListFull)?;
+        self.devices[slot] = DeviceKey {
+            key,
+            attestation_hash,
+            last_seen_at: now,
+            ..DeviceKey::default()
+        };
+        Ok(slot)
+    }
+
+    /// Require the owner as co-signer when `notional` exceeds the session's
+    /// `cosign_above_lamports` threshold. Small actions stay autonomous.
+    pub fn check_cosign(&self, notional: u64, cosigner: Option<Pubkey>) -> Result<()> {
+        if self.cosign_above_lamports != 0 && notional > self.cosign_above_lamports {
+            require!(cosigner == Some(self.owner), AgentError::OwnerCosignRequired);
+        }
+        Ok(())
+    }
+
+    /// Mark a signed intent as used. Rejects expired intents and any nonce at or
+    /// below the last one consumed by this device, so each intent executes once.
+    pub fn consume_intent(
+        &mut self,
+        device_slot: usize,
+        nonce: u64,
+        expires_at: i64,
+        now: i64,
+    ) -> Result<()> {
+        require!(now < expires_at, AgentError::SignedIntentExpired);
+        let device = &mut self.devices[device_slot];
+        require!(nonce > device.intent_nonce, AgentError::SignedIntentReplayed);
+        device.intent_nonce = nonce;
+        Ok(())
+    }
+
+    /// Begin rotating `old_key` to `new_key`. The new key takes over the old
+    /// device's record (limits, spend counter, attestation, intent signer and
+    /// nonce) in a free slot; the old key stays valid for `grace_secs` so any
+    /// queued transactions can land, then is rejected by `require_device`.
+    pub fn rotate_device(
+        &mut self,
+        old_key: &Pubkey,
+        new_key: Pubkey,
+        grace_secs: i64,
+        now: i64,
+    ) -> Result<usize> {
+        require!(grace_secs >= 0, AgentError::InvalidGracePeriod);
+        let old_slot = self.require_device(old_key, now)?;
+        let new_slot = self.enroll_device(new_key, self.devices[old_slot].attestation_hash, now)?;
+
+        let old = self.devices[old_slot];
+        self.devices[new_slot] = DeviceKey {
+            key: new_key,
+            last_seen_at: now,
+            ..old
+        };
+
+        let grace_end = now.checked_add(grace_secs).ok_or(AgentError::Overflow)?;
+        let old = &mut self.devices[old_slot];
+        old.expires_at = if old.expires_at == 0 {
+            grace_end
+        } else {
+            old.expires_at.min(grace_end)
+        };
+        Ok(new_slot)
+    }
+
+    /// Clear the slot holding `key`.
+    pub fn remove_device(&mut self, key: &Pubkey) -> Result<usize> {
+        let slot = self.device_index(key).ok_or(AgentError::DeviceNotEnrolled)?;
+        self.devices[slot] = DeviceKey::default();
+        Ok(slot)
+    }
+
+    /// The single session check every session-key instruction runs: active,
+    /// not suspended or expired, `device` enrolled and usable, `kind`
+    /// permitted by the strategy mask, then for actions, no exposure cooldown
+    /// running. Checks run in that order so every handler fails with the
+    /// same error for the same session state. A `Device` time source also
+    /// records the call as the device's heartbeat. Returns the device's slot.
+    pub fn validate_session(
+        &mut self,
+        device: &Pubkey,
+        kind: ActionKind,
+        time: TemporalSource,
+    ) -> Result<usize> {
+        let now = time.now();
         require!(self.is_active, AgentError::SessionInactive);
-        require!(!self.is_expired(timestamp), AgentError::SessionExpired);
-        require_keys_eq!(session_key, self.session_key, AgentError::UnauthorizedSessionKey);
-        require!(self.has_strategy(ACTION_LP_REBALANCE), AgentError::StrategyNotEnabled);
+        require!(!self.suspended, AgentError::SessionSuspended);
+        require!(!self.is_expired(now), AgentError::SessionExpired);
+        let slot = match time {
+            TemporalSource::Device(now) => self.authorize_device(device, now)?,
+            TemporalSource::Passive(now) => self.require_device(device, now)?,
+        };
+        let permitted = match kind {
+            ActionKind::Action(action_type) => self.has_strategy(action_type),
+            ActionKind::LpMonitor => self.strategy_mask & STRATEGY_LP != 0,
+            ActionKind::Housekeeping => true,
+        };
+        require!(permitted, AgentError::StrategyNotEnabled);
+        if let ActionKind::Action(_) = kind {
+            require!(now >= self.exposure_cooldown_until, AgentError::ExposureCooldown);
+        }
+        Ok(slot)
+    }
+
+    /// Validate `amount` against the per-action cap.
+    pub fn check_action_cap(&self, amount: u64) -> Result<()> {
+        require!(amount <= self.max_action_lamports, AgentError::ActionLimitExceeded);
+        Ok(())
+    }
+
+    /// Exposure counted against `max_lamports`: market value when
+    /// `mark_to_market` is set, historical cost otherwise.
+    pub fn exposure(&self) -> u64 {
+        if self.mark_to_market {
+            self.current_exposure_value
+        } else {
+            self.spent_lamports
+        }
+    }
+
+    /// Exposure as a share of `max_lamports`, in basis points (saturating).
+    pub fn exposure_bps(&self) -> u64 {
+        if self.max_lamports == 0 {
+            return 0;
+        }
+        (self.exposure() as u128 * 10_000 / self.max_lamports as u128).min(u64::MAX as u128) as u64
+    }
+
+    /// Priority and signature fees the session's devices have paid, in lamports.
+    pub fn fees_paid_lamports(&self) -> u64 {
+        self.fee_spent_lamports.saturating_add(self.tx_fees_lamports)
+    }
+
+    /// Validate `amount` against the cumulative session exposure cap and the
+    /// signing device's own spend cap. The session cap counts historical cost
+    /// (`spent_lamports`), or market value when `mark_to_market` is set.
+    pub fn check_exposure(&self, device_slot: usize, amount: u64) -> Result<()> {
+        let new_spent = self
+            .exposure()
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        require!(new_spent <= self.max_lamports, AgentError::ExposureLimitExceeded);
+
+        let device = &self.devices[device_slot];
+        if device.max_lamports != 0 {
+            let device_spent = device
+                .spent_lamports
+                .checked_add(amount)
+                .ok_or(AgentError::Overflow)?;
+            require!(device_spent <= device.max_lamports, AgentError::DeviceLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Charge `amount` to the session, the signing device and the strategy
+    /// `action_type` belongs to.
+    /// Call after `check_exposure` once the action has succeeded.
+    pub fn apply_spend(&mut self, device_slot: usize, action_type: u8, amount: u64) -> Result<()> {
+        let strategy = strategy_index(action_type).ok_or(AgentError::InvalidActionType)?;
+        self.spent_lamports = self
+            .spent_lamports
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        self.spent_by_strategy[strategy] = self.spent_by_strategy[strategy]
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        self.current_exposure_value = self
+            .current_exposure_value
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        let device = &mut self.devices[device_slot];
+        device.spent_lamports = device
+            .spent_lamports
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        Ok(())
+    }
+
+    /// Mark the registry's positions at `value` against their `cost_basis`:
+    /// `current_exposure_value` moves only by the change in their unrealized
+    /// gain since the last revaluation, so spends applied in between — swaps,
+    /// `execute_action`, deposits — stay counted. Returns the new exposure.
+    pub fn apply_revaluation(&mut self, value: u64, cost_basis: u64, now: i64) -> u64 {
+        let mark = (value as i128 - cost_basis as i128).clamp(i64::MIN as i128, i64::MAX as i128);
+        let exposure = self.current_exposure_value as i128 + mark - self.exposure_mark as i128;
+        self.current_exposure_value = exposure.clamp(0, u64::MAX as i128) as u64;
+        self.exposure_mark = mark as i64;
+        self.exposure_valued_at = now;
+        self.current_exposure_value
+    }
+
+    /// Per-action protocol fee owed for an action of the given notional size.
+    pub fn per_action_fee(&self, notional: u64) -> u64 {
+        match self.fee_mode {
+            FEE_MODE_FLAT => self.fee_amount,
+            FEE_MODE_BPS => {
+                // fee_amount <= BPS_DENOMINATOR (validated in set_fee_tier), so this fits in u64
+                ((notional as u128) * (self.fee_amount as u128) / (BPS_DENOMINATOR as u128)) as u64
+            }
+            _ => 0,
+        }
+    }
+
+    /// Enforce registry-only mode: when enabled, `lb_pair` must be listed in
+    /// the global PoolRegistry (which must then be supplied).
+    pub fn validate_pool(&self, registry: Option<&PoolRegistry>, lb_pair: &Pubkey) -> Result<()> {
+        if !self.registry_only {
+            return Ok(());
+        }
+        let registry = registry.ok_or(AgentError::PoolNotRegistered)?;
+        require!(registry.contains(lb_pair), AgentError::PoolNotRegistered);
         Ok(())
     }
 
-    /// Increment total_actions with overflow protection.
+    /// Enforce the owner's ranked pools: once any PairPools list exists,
+    /// `lb_pair` must be on `list` (which must then be supplied).
+    pub fn validate_ranked_pool(&self, list: Option<&PairPools>, lb_pair: &Pubkey) -> Result<()> {
+        if self.pair_pool_lists == 0 {
+            return Ok(());
+        }
+        let list = list.ok_or(AgentError::PoolNotRanked)?;
+        require!(list.contains(lb_pair), AgentError::PoolNotRanked);
+        Ok(())
+    }
+
+    /// Enforce the owner's liquidity shapes: when any is set, a deposit's
+    /// distribution must be one of them.
+    pub fn check_liquidity_shape(&self, strategy_type: &StrategyType) -> Result<()> {
+        if self.liquidity_shapes != 0 {
+            require!(
+                self.liquidity_shapes & liquidity_shape_bit(strategy_type) != 0,
+                AgentError::LiquidityShapeNotAllowed
+            );
+        }
+        Ok(())
+    }
+
+    /// Enforce the owner's position binding: when any position is listed,
+    /// `position` must be one of them.
+    pub fn validate_position(&self, position: &Pubkey) -> Result<()> {
+        let mut bound = self
+            .bound_positions
+            .iter()
+            .filter(|p| **p != Pubkey::default())
+            .peekable();
+        if bound.peek().is_some() {
+            require!(bound.any(|p| p == position), AgentError::PositionNotBound);
+        }
+        Ok(())
+    }
+
+    /// Record a portfolio valuation of `value` in `quote_mint` units. The first
+    /// valuation fixes the session's valuation mint and entry value; later
+    /// ones accumulate their change into `realized_pnl`. Returns `None`, and
+    /// records nothing, for a valuation in a different mint.
+    pub fn record_valuation(&mut self, quote_mint: Pubkey, value: u64, now: i64) -> Option<i64> {
+        if self.valuation_mint == Pubkey::default() {
+            self.valuation_mint = quote_mint;
+            self.entry_value = value;
+            self.last_value = value;
+        } else if self.valuation_mint != quote_mint {
+            return None;
+        }
+        let change = (value as i128 - self.last_value as i128)
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+        self.realized_pnl = self.realized_pnl.saturating_add(change);
+        self.last_value = value;
+        self.last_valued_at = self.session_now(now);
+        Some(self.realized_pnl)
+    }
+
+    /// Roll a monitor's final fee checkpoint into the lifetime earnings.
+    pub fn record_fees_earned(&mut self, monitor: &LpPositionMonitor) {
+        self.total_fees_earned_x = self.total_fees_earned_x.saturating_add(monitor.fee_x_snapshot);
+        self.total_fees_earned_y = self.total_fees_earned_y.saturating_add(monitor.fee_y_snapshot);
+    }
+
+    /// The owner's minimum for `mint`, or 0 when none is set
+    pub fn min_trade_amount(&self, mint: &Pubkey) -> u64 {
+        self.min_trade_amounts
+            .iter()
+            .find(|m| m.mint == *mint && *mint != Pubkey::default())
+            .map_or(0, |m| m.amount)
+    }
+
+    /// Reject dust: `amount` of `mint` must meet the owner's minimum for it.
+    pub fn check_min_trade(&self, mint: &Pubkey, amount: u64) -> Result<()> {
+        require!(
+            amount >= self.min_trade_amount(mint),
+            AgentError::BelowMinTradeAmount
+        );
+        Ok(())
+    }
+
+    /// Dust check for a two-sided deposit. An empty side of a one-sided
+    /// deposit is skipped; an entirely empty deposit is checked on both sides.
+    pub fn check_min_deposit(
+        &self,
+        mint_x: &Pubkey,
+        amount_x: u64,
+        mint_y: &Pubkey,
+        amount_y: u64,
+    ) -> Result<()> {
+        if amount_x > 0 || amount_y == 0 {
+            self.check_min_trade(mint_x, amount_x)?;
+        }
+        if amount_y > 0 || amount_x == 0 {
+            self.check_min_trade(mint_y, amount_y)?;
+        }
+        Ok(())
+    }
+
+    /// Add a declared priority-fee/tip amount to the running total, enforcing
+    /// the owner-set operational fee budget (0 = uncapped).
+    pub fn record_fee_spend(&mut self, fee_lamports: u64) -> Result<()> {
+        let new_fee_spent = self
+            .fee_spent_lamports
+            .checked_add(fee_lamports)
+            .ok_or(AgentError::Overflow)?;
+        require!(
+            self.fee_budget_lamports == 0 || new_fee_spent <= self.fee_budget_lamports,
+            AgentError::FeeBudgetExceeded
+        );
+        self.fee_spent_lamports = new_fee_spent;
+        Ok(())
+    }
+
+    /// Add a transaction's signature fees to the running total, enforcing
+    /// the owner-set transaction fee budget (0 = uncapped).
+    pub fn record_tx_fee(&mut self, tx_fee_lamports: u64) -> Result<()> {
+        let new_tx_fees = self
+            .tx_fees_lamports
+            .checked_add(tx_fee_lamports)
+            .ok_or(AgentError::Overflow)?;
+        require!(
+            self.tx_fee_budget_lamports == 0 || new_tx_fees <= self.tx_fee_budget_lamports,
+            AgentError::TxFeeBudgetExceeded
+        );
+        self.tx_fees_lamports = new_tx_fees;
+        Ok(())
+    }
+
+    /// Add a swap's estimated pool fee, in Y units, to the running total,
+    /// enforcing the owner-set swap fee budget (0 = uncapped).
+    pub fn record_swap_fee(&mut self, fee_y: u64) -> Result<()> {
+        let new_total = self
+            .swap_fees_paid
+            .checked_add(fee_y)
+            .ok_or(AgentError::Overflow)?;
+        require!(
+            self.swap_fee_budget == 0 || new_total <= self.swap_fee_budget,
+            AgentError::SwapFeeBudgetExceeded
+        );
+        self.swap_fees_paid = new_total;
+        Ok(())
+    }
+
+    /// Add a swap's realized slippage, in Y units, to the running total.
+    pub fn record_slippage(&mut self, slippage_y: u64) {
+        self.slippage_paid = self.slippage_paid.saturating_add(slippage_y);
+    }
+
+    /// While delegated, refuse further actions once the owner's commit
+    /// cadence is exceeded — too many actions, or too long, since the last
+    /// commit — until `commit_session` runs. Base-layer actions settle
+    /// directly and are never held back.
+    pub fn check_commit_cadence(&self, now: i64) -> Result<()> {
+        self.check_commit_cadence_for(now, 1)
+    }
+
+    /// `check_commit_cadence` for `actions` actions applied at once: all of
+    /// them must fit in the current window.
+    pub fn check_commit_cadence_for(&self, now: i64, actions: u32) -> Result<()> {
+        if !self.is_delegated() {
+            return Ok(());
+        }
+        require!(
+            self.max_uncommitted_actions == 0
+                || self.uncommitted_actions.saturating_add(actions) <= self.max_uncommitted_actions,
+            AgentError::CommitRequired
+        );
+        require!(
+            self.max_uncommitted_secs == 0
+                || self.session_now(now).saturating_sub(self.last_committed_at)
+                    < self.max_uncommitted_secs,
+            AgentError::CommitRequired
+        );
+        Ok(())
+    }
+
+    /// While delegated, whether more than `max_idle_slots` ER slots have
+    /// passed since the last action — the slot clock keeps running on the ER
+    /// where wall-clock expiry can't be trusted. The window opens at the
+    /// first action after delegation.
+    pub fn is_idle(&self, slot: u64) -> bool {
+        self.is_delegated()
+            && self.max_idle_slots != 0
+            && self.er_last_action_slot != 0
+            && slot.saturating_sub(self.er_last_action_slot) > self.max_idle_slots
+    }
+
+    /// Record the ER slot of an executed action; base-layer actions leave it.
+    pub fn mark_er_action(&mut self, slot: u64) {
+        if self.is_delegated() {
+            self.er_last_action_slot = slot;
+        }
+    }
+
+    /// Whether executed actions must leave a receipt in an ActionReceiptLog.
+    pub fn has_receipt_log(&self) -> bool {
+        self.receipt_log != Pubkey::default()
+    }
+
+    /// Whether caps and exposure are denominated in a budget mint.
+    pub fn has_budget_mint(&self) -> bool {
+        self.budget_mint != Pubkey::default()
+    }
+
+    /// Denominate the session in `budget_mint`, restating every cap in the
+    /// new unit. Only while nothing has been spent, so exposure is never
+    /// summed across units; the pinned price pools, which pair the old unit,
+    /// are cleared.
+    pub fn set_budget_mint(&mut self, budget_mint: Pubkey, caps: &BudgetCaps) -> Result<()> {
+        require!(self.spent_lamports == 0, AgentError::BudgetMintLocked);
+        require!(caps.max_lamports > 0, AgentError::ZeroExposureCap);
+        require!(caps.max_action_lamports > 0, AgentError::InvalidBudgetCaps);
+        for (device, max_lamports) in self.devices.iter_mut().zip(caps.device_max_lamports) {
+            require!(
+                max_lamports == 0 || !device.is_empty(),
+                AgentError::InvalidBudgetCaps
+            );
+            device.max_lamports = max_lamports;
+        }
+        self.budget_mint = budget_mint;
+        self.max_lamports = caps.max_lamports;
+        self.max_action_lamports = caps.max_action_lamports;
+        self.cosign_above_lamports = caps.cosign_above_lamports;
+        self.budget_price_pools = [BudgetPricePool::default(); MAX_BUDGET_PRICE_POOLS];
+        Ok(())
+    }
+
+    /// The pool pinned to price `mint` in the budget unit, if any
+    pub fn budget_price_pool(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.budget_price_pools
+            .iter()
+            .find(|p| p.mint == *mint && *mint != Pubkey::default())
+            .map(|p| p.lb_pair)
+    }
+
+    /// Pin `lb_pair` as the price pool for `mint`, replacing any earlier pin;
+    /// Pubkey::default() unpins the mint. The caller checks the pool pairs
+    /// `mint` with the budget mint.
+    pub fn pin_budget_price_pool(&mut self, mint: Pubkey, lb_pair: Pubkey) -> Result<()> {
+        require!(
+            self.has_budget_mint() && mint != Pubkey::default() && mint != self.budget_mint,
+            AgentError::InvalidBudgetPricePool
+        );
+        let existing = self.budget_price_pools.iter().position(|p| p.mint == mint);
+        match (existing, lb_pair == Pubkey::default()) {
+            (Some(slot), true) => self.budget_price_pools[slot] = BudgetPricePool::default(),
+            (Some(slot), false) => self.budget_price_pools[slot].lb_pair = lb_pair,
+            (None, true) => {}
+            (None, false) => {
+                let slot = self
+                    .budget_price_pools
+                    .iter()
+                    .position(|p| p.mint == Pubkey::default())
+                    .ok_or(AgentError::BudgetPricePoolListFull)?;
+                self.budget_price_pools[slot] = BudgetPricePool { mint, lb_pair };
+            }
+        }
+        Ok(())
+    }
+
+    /// The mint `execute_action` notionals are denominated in: the budget
+    /// mint when set, wrapped SOL otherwise. Its minimum trade amount applies.
+    pub fn notional_mint(&self) -> Pubkey {
+        if self.has_budget_mint() {
+            self.budget_mint
+        } else {
+            NATIVE_MINT
+        }
+    }
+
+    /// Start a new commit window at `now`: on delegation and at each commit.
+    pub fn mark_committed(&mut self, now: i64) {
+        self.uncommitted_actions = 0;
+        self.last_committed_at = self.session_now(now);
+    }
+
+    /// Count a scope violation by an enrolled device. Returns true when it
+    /// reaches `violation_threshold` and suspends the session.
+    pub fn record_violation(&mut self) -> bool {
+        self.consecutive_violations = self.consecutive_violations.saturating_add(1);
+        if self.consecutive_violations >= self.violation_threshold {
+            self.suspended = true;
+        }
+        self.suspended
+    }
+
+    /// The session's label, without its zero padding.
+    pub fn label(&self) -> &str {
+        padded_str(&self.label)
+    }
+
+    /// The session's metadata URI, without its zero padding; empty when unset.
+    pub fn metadata_uri(&self) -> &str {
+        padded_str(&self.metadata_uri)
+    }
+
+    /// Increment total_actions with overflow protection. An executed action
+    /// also ends any run of scope violations and, while delegated, counts
+    /// toward the commit cadence.
     pub fn bump_actions(&mut self) -> Result<()> {
+        self.consecutive_violations = 0;
+        if self.is_delegated() {
+            self.uncommitted_actions = self.uncommitted_actions.saturating_add(1);
+        }
         self.total_actions = self
             .total_actions
             .checked_add(1)
@@ -88,3 +1218,10 @@ impl AgentSession {
         Ok(())
     }
 }
+
+/// The UTF-8 text in a zero-padded byte field. Writers validate it, so
+/// invalid bytes only come from a corrupt account and read as empty.
+fn padded_str(bytes: &[u8]) -> &str {
+    let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..len]).unwrap_or_default()
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+/// Deepest tree a session may allocate — 2^14 = 16_384 monitors.
+pub const MAX_MONITOR_TREE_DEPTH: u8 = 14;
+
+/// Checkpoint of one compressed LP monitor — the preimage of a tree leaf.
+///
+/// Mirrors `LpPositionMonitor` without the per-account rent: only its hash
+/// lives on-chain (inside the tree root). Full leaves are published in
+/// `CompressedMonitorUpdated` events so indexers can rebuild the tree and
+/// serve proofs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedMonitorLeaf {
+    pub lb_pair: Pubkey,
+    pub position: Pubkey,
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    pub last_active_bin: i32,
+    pub is_in_range: bool,
+    pub fee_x_snapshot: u64,
+    pub fee_y_snapshot: u64,
+    pub last_checked_at: i64,
+}
+
+impl CompressedMonitorLeaf {
+    pub fn hash(&self) -> [u8; 32] {
+        hashv(&[
+            b"monitor_leaf",
+            self.lb_pair.as_ref(),
+            self.position.as_ref(),
+            &self.min_bin_id.to_le_bytes(),
+            &self.max_bin_id.to_le_bytes(),
+            &self.last_active_bin.to_le_bytes(),
+            &[self.is_in_range as u8],
+            &self.fee_x_snapshot.to_le_bytes(),
+            &self.fee_y_snapshot.to_le_bytes(),
+            &self.last_checked_at.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /// Returns true when active_bin is within the monitored position's range.
+    pub fn check_in_range(&self, active_bin: i32) -> bool {
+        active_bin >= self.min_bin_id && active_bin <= self.max_bin_id
+    }
+}
+
+/// A session's compressed LP monitors: the config and CPI authority of an
+/// spl-account-compression concurrent Merkle tree holding their leaves.
+///
+/// Created by `initialize_monitor_tree` (owner signs, base layer) over a
+/// `merkle_tree` account the owner allocated for the account compression
+/// program (see `merkle_tree_space`). Monitors are appended by
+/// `register_compressed_monitor` and checkpointed by
+/// `update_compressed_lp_status`. The tree keeps a changelog buffer of its
+/// last `max_buffer_size` roots, so a proof built against any of them still
+/// applies and several checkpoints can land in the same slot; with a canopy,
+/// only the proof nodes below it are passed.
+///
+/// Seeds: [b"monitor_tree", session.key().as_ref()]
+#[account]
+pub struct CompressedMonitorTree {
+    /// The AgentSession that owns this tree (32)
+    pub session: Pubkey,
+
+    /// The account compression program's ConcurrentMerkleTree account (32)
+    pub merkle_tree: Pubkey,
+
+    /// Tree depth; capacity is 2^depth leaves (1)
+    pub depth: u8,
+
+    /// Number of leaves appended so far (4)
+    pub leaf_count: u32,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+}
+
+impl CompressedMonitorTree {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + 32  // merkle_tree
+        + 1   // depth
+        + 4   // leaf_count
+        + 1;  // bump
+
+    /// Bytes the owner allocates for the `merkle_tree` account: the account
+    /// compression header, the tree with its changelog buffer and rightmost
+    /// path, and a canopy of `canopy_depth` cached upper levels.
+    pub const fn merkle_tree_space(depth: u8, max_buffer_size: u32, canopy_depth: u8) -> usize {
+        let path = 32 * depth as usize + 32 + 8; // nodes + root/leaf + index/padding
+        let canopy = ((1usize << (canopy_depth as usize + 1)) - 2) * 32;
+        2 + 54 // account type + version, header
+            + 24 // sequence number, active index, buffer size
+            + max_buffer_size as usize * path // changelog buffer
+            + path // rightmost proof
+            + canopy
+    }
+
+    /// Whether another leaf fits.
+    pub fn has_room(&self) -> bool {
+        (self.leaf_count as u64) < (1u64 << self.depth)
+    }
+}
@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use crate::state::WithdrawalWindow;
+
+/// Layout version written by this build. Bump whenever `Config` gains fields
+/// and teach `migrate_config` how to initialize them.
+pub const CURRENT_CONFIG_VERSION: u8 = 5;
+
+/// Hard ceiling on `protocol_fee_bps` (10%) — guards against fat-finger updates.
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+/// Default delay between `propose_admin` and when `accept_admin` becomes valid (48h).
+pub const DEFAULT_ADMIN_TIMELOCK_SECS: i64 = 60 * 60 * 48;
+
+/// Default delay between `queue_withdrawal` and a large withdrawal executing (24 h)
+pub const DEFAULT_WITHDRAWAL_DELAY_SECS: i64 = 60 * 60 * 24;
+
+/// Number of admin-approved fee tiers a session can pick from at initialization.
+pub const MAX_FEE_TIERS: usize = 4;
+
+/// Per-action fee modes — how a session is billed for each executed action
+pub const FEE_MODE_NONE: u8 = 0;  // no per-action fee
+pub const FEE_MODE_FLAT: u8 = 1;  // fixed lamports per action
+pub const FEE_MODE_BPS: u8 = 2;   // basis points of the action's notional
+
+/// Basis-point denominator (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// An admin-approved billing option for sessions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    /// FEE_MODE_NONE / FEE_MODE_FLAT / FEE_MODE_BPS (1)
+    pub fee_mode: u8,
+
+    /// Lamports per action (flat) or basis points of notional (bps) (8)
+    pub amount: u64,
+}
+
+impl FeeTier {
+    pub const LEN: usize = 1 + 8;
+}
+
+/// Program-wide configuration, one per deployment.
+///
+/// Created once by the program upgrade authority via `initialize_config` and
+/// read by instruction handlers for policy knobs that previously were
+/// compile-time constants, so devnet and mainnet deployments can differ
+/// without a rebuild.
+///
+/// Seeds: [b"config"]
+#[account]
+pub struct Config {
+    /// Authority allowed to update this config (32)
+    pub admin: Pubkey,
+
+    /// Protocol fee charged on agent-executed volume, in basis points (2)
+    pub protocol_fee_bps: u16,
+
+    /// Global kill switch for agent execution (1)
+    pub paused: bool,
+
+    /// Exposure cap applied when `initialize_session` is called with max_lamports = 0 (8)
+    pub default_max_lamports: u64,
+
+    /// Session duration applied when `initialize_session` is called with duration_secs = 0 (8)
+    pub default_duration_secs: i64,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+
+    /// Ceiling on any single action's notional exposure; also the session default (8)
+    pub max_action_lamports: u64,
+
+    /// Ceiling on the duration a session may request at initialization (8)
+    pub max_session_duration_secs: i64,
+
+    /// Strategies a session is allowed to enable — requested masks must be a subset (1)
+    pub max_strategy_mask: u8,
+
+    /// Proposed next admin; Pubkey::default() when no transfer is pending (32)
+    pub pending_admin: Pubkey,
+
+    /// Unix timestamp after which `pending_admin` may accept (8)
+    pub admin_transfer_available_at: i64,
+
+    /// Delay applied to every admin proposal (8)
+    pub admin_timelock_secs: i64,
+
+    /// Share of each skimmed protocol fee routed to the session's referrer, in bps (2)
+    pub referral_share_bps: u16,
+
+    /// Per-action billing tiers a session may select at initialization (9 × MAX_FEE_TIERS)
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+
+    /// Narrow kill switch: blocks only instructions that CPI into Meteora DLMM (1)
+    pub dlmm_frozen: bool,
+
+    /// Layout version of this account — see CURRENT_CONFIG_VERSION (1)
+    pub version: u8,
+
+    /// Delay between `queue_withdrawal` and executing a large withdrawal (8)
+    pub withdrawal_delay_secs: i64,
+
+    /// Fee-vault withdrawals above this many lamports must be queued; 0 = no time lock (8)
+    pub large_withdrawal_lamports: u64,
+
+    /// Minimum requested compute units for the double-CPI close / migrate paths; 0 = unchecked (4)
+    pub min_dual_cpi_compute_units: u32,
+
+    /// Magic program the commit/undelegate paths target; Pubkey::default() = the SDK's (32)
+    pub magic_program: Pubkey,
+
+    /// Magic context the commit/undelegate paths target; Pubkey::default() = the SDK's (32)
+    pub magic_context: Pubkey,
+
+    /// Unqueued fee-vault withdrawals counted against `large_withdrawal_lamports` (16)
+    pub fee_vault_withdrawal_window: WithdrawalWindow,
+}
+
+impl Config {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // admin
+        + 2   // protocol_fee_bps
+        + 1   // paused
+        + 8   // default_max_lamports
+        + 8   // default_duration_secs
+        + 1   // bump
+        + 8   // max_action_lamports
+        + 8   // max_session_duration_secs
+        + 1   // max_strategy_mask
+        + 32  // pending_admin
+        + 8   // admin_transfer_available_at
+        + 8   // admin_timelock_secs
+        + 2   // referral_share_bps
+        + FeeTier::LEN * MAX_FEE_TIERS  // fee_tiers
+        + 1   // dlmm_frozen
+        + 1   // version
+        + 8   // withdrawal_delay_secs
+        + 8   // large_withdrawal_lamports
+        + 4   // min_dual_cpi_compute_units
+        + 32  // magic_program
+        + 32  // magic_context
+        + WithdrawalWindow::LEN; // fee_vault_withdrawal_window
+
+    /// Protocol fee owed on `amount`, rounded down in the user's favour.
+    pub fn protocol_fee_on(&self, amount: u64) -> u64 {
+        // protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, so the quotient always fits in u64
+        ((amount as u128) * (self.protocol_fee_bps as u128) / (BPS_DENOMINATOR as u128)) as u64
+    }
+
+    /// Magic program commits and undelegations CPI into — the admin override
+    /// when set, else the SDK's
+    pub fn magic_program_id(&self) -> Pubkey {
+        if self.magic_program == Pubkey::default() {
+            MAGIC_PROGRAM_ID
+        } else {
+            self.magic_program
+        }
+    }
+
+    /// Magic context commits and undelegations write to — the admin override
+    /// when set, else the SDK's
+    pub fn magic_context_id(&self) -> Pubkey {
+        if self.magic_context == Pubkey::default() {
+            MAGIC_CONTEXT_ID
+        } else {
+            self.magic_context
+        }
+    }
+}
+
+/// Magic program for an instruction that optionally takes the Config — the
+/// SDK's when the Config is not passed
+pub fn magic_program_for(config: Option<&Config>) -> Pubkey {
+    config.map_or(MAGIC_PROGRAM_ID, Config::magic_program_id)
+}
+
+/// Magic context for an instruction that optionally takes the Config — the
+/// SDK's when the Config is not passed
+pub fn magic_context_for(config: Option<&Config>) -> Pubkey {
+    config.map_or(MAGIC_CONTEXT_ID, Config::magic_context_id)
+}
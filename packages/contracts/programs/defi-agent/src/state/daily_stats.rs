@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use crate::state::LpPositionMonitor;
+
+/// Number of UTC days a session's DailyStats keeps before recycling buckets.
+pub const DAILY_STATS_DAYS: usize = 30;
+
+pub const SECS_PER_DAY: i64 = 86_400;
+
+#[zero_copy]
+pub struct DailyBucket {
+    /// UTC day this bucket covers, in days since the Unix epoch (8)
+    pub day: i64,
+
+    /// Actions executed on that day (8)
+    pub actions: u64,
+
+    /// Sum of the actions' amounts (8)
+    pub volume: u64,
+
+    /// Per-action protocol fees paid, in lamports (8)
+    pub fees_paid: u64,
+
+    /// LP fees in token X rolled up from closed monitors (8)
+    pub fees_earned_x: u64,
+
+    /// LP fees in token Y rolled up from closed monitors (8)
+    pub fees_earned_y: u64,
+}
+
+impl DailyBucket {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8;
+
+    const EMPTY: Self = Self {
+        day: 0,
+        actions: 0,
+        volume: 0,
+        fees_paid: 0,
+        fees_earned_x: 0,
+        fees_earned_y: 0,
+    };
+}
+
+/// Per-UTC-day activity totals for a session, for day-over-day charts
+/// without an indexer.
+///
+/// Created by `initialize_daily_stats` (owner signs, base layer). Execute
+/// handlers tally into it when it is passed; the account is a ring buffer of
+/// `DAILY_STATS_DAYS` buckets indexed by day, so a day's bucket is reset the
+/// first time it is written after the ring wraps. Days with no activity keep
+/// whatever older day last used their slot — readers match on `day`.
+///
+/// Zero-copy (`AccountLoader`) so per-action updates write in place.
+///
+/// Seeds: [b"daily_stats", session.key().as_ref()]
+#[account(zero_copy)]
+pub struct DailyStats {
+    /// The AgentSession these stats belong to (32)
+    pub session: Pubkey,
+
+    /// Day buckets, slot = day % DAILY_STATS_DAYS (48 × DAILY_STATS_DAYS)
+    pub days: [DailyBucket; DAILY_STATS_DAYS],
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+
+    /// Alignment padding (7)
+    pub _padding: [u8; 7],
+}
+
+impl DailyStats {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + DailyBucket::LEN * DAILY_STATS_DAYS  // days
+        + 1   // bump
+        + 7;  // _padding
+
+    /// UTC day of a unix timestamp, in days since the epoch
+    pub fn day_of(now: i64) -> i64 {
+        now.div_euclid(SECS_PER_DAY)
+    }
+
+    /// The bucket for `day`, if the ring still holds it
+    pub fn get(&self, day: i64) -> Option<&DailyBucket> {
+        let bucket = &self.days[day.rem_euclid(DAILY_STATS_DAYS as i64) as usize];
+        (bucket.day == day).then_some(bucket)
+    }
+
+    /// Today's bucket, recycling its slot if it still holds an older day
+    fn bucket(&mut self, now: i64) -> &mut DailyBucket {
+        let day = Self::day_of(now);
+        let bucket = &mut self.days[day.rem_euclid(DAILY_STATS_DAYS as i64) as usize];
+        if bucket.day != day {
+            *bucket = DailyBucket { day, ..DailyBucket::EMPTY };
+        }
+        bucket
+    }
+
+    /// Tally one executed action of `amount`, billed `fee_paid` lamports
+    pub fn record_action(&mut self, now: i64, amount: u64, fee_paid: u64) {
+        let bucket = self.bucket(now);
+        bucket.actions = bucket.actions.saturating_add(1);
+        bucket.volume = bucket.volume.saturating_add(amount);
+        bucket.fees_paid = bucket.fees_paid.saturating_add(fee_paid);
+    }
+
+    /// Roll a closing monitor's last fee checkpoint into today's earnings,
+    /// as `AgentSession::record_fees_earned` does for the lifetime totals
+    pub fn record_fees_earned(&mut self, now: i64, monitor: &LpPositionMonitor) {
+        let bucket = self.bucket(now);
+        bucket.fees_earned_x = bucket.fees_earned_x.saturating_add(monitor.fee_x_snapshot);
+        bucket.fees_earned_y = bucket.fees_earned_y.saturating_add(monitor.fee_y_snapshot);
+    }
+}
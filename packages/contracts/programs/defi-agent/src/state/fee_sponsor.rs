@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::DailyStats;
+
+/// Owner-funded SOL pool that tops up a session's device keys with
+/// transaction-fee lamports, so a device only ever holds a small balance.
+///
+/// The account's lamports above its rent-exempt minimum are the sponsor
+/// balance; `refill_session_key` moves them to a device, at most up to
+/// `target_balance_lamports` per device and `daily_cap_lamports` per UTC day
+/// across all devices. Closing the sponsor returns everything to the owner.
+///
+/// Seeds: [b"fee_sponsor", session.as_ref()]
+#[account]
+pub struct FeeSponsor {
+    /// The AgentSession whose devices this sponsor refills (32)
+    pub session: Pubkey,
+
+    /// Lamports a refill tops a device key up to (8)
+    pub target_balance_lamports: u64,
+
+    /// Lamports all refills may move per UTC day (8)
+    pub daily_cap_lamports: u64,
+
+    /// UTC day `refilled_today_lamports` covers, in days since the epoch (8)
+    pub day: i64,
+
+    /// Lamports refilled during `day` (8)
+    pub refilled_today_lamports: u64,
+
+    /// Lamports refilled over the sponsor's lifetime (8)
+    pub total_refilled_lamports: u64,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+}
+
+impl FeeSponsor {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + 8   // target_balance_lamports
+        + 8   // daily_cap_lamports
+        + 8   // day
+        + 8   // refilled_today_lamports
+        + 8   // total_refilled_lamports
+        + 1;  // bump
+
+    /// Lamports today's cap still allows
+    pub fn remaining_today(&self, now: i64) -> u64 {
+        if self.day == DailyStats::day_of(now) {
+            self.daily_cap_lamports.saturating_sub(self.refilled_today_lamports)
+        } else {
+            self.daily_cap_lamports
+        }
+    }
+
+    /// The refill a device holding `device_balance` gets from `available`
+    /// sponsor lamports: up to the target, within today's cap.
+    pub fn refill_amount(&self, device_balance: u64, available: u64, now: i64) -> Result<u64> {
+        let shortfall = self.target_balance_lamports.saturating_sub(device_balance);
+        require!(shortfall > 0, AgentError::RefillNotNeeded);
+        let remaining = self.remaining_today(now);
+        require!(remaining > 0, AgentError::SponsorDailyCapReached);
+        let amount = shortfall.min(remaining).min(available);
+        require!(amount > 0, AgentError::InsufficientSponsorBalance);
+        Ok(amount)
+    }
+
+    /// Count `amount` against today's cap and the lifetime total.
+    pub fn record_refill(&mut self, amount: u64, now: i64) -> Result<()> {
+        let today = DailyStats::day_of(now);
+        if self.day != today {
+            self.day = today;
+            self.refilled_today_lamports = 0;
+        }
+        self.refilled_today_lamports = self
+            .refilled_today_lamports
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        self.total_refilled_lamports = self
+            .total_refilled_lamports
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        Ok(())
+    }
+}
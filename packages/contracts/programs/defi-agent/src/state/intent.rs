@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+
+/// Maximum number of intents `create_intents_batch` creates in one instruction
+pub const MAX_INTENT_BATCH: usize = 10;
+
+/// Parameters of one intent, as passed to `create_intents_batch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct IntentSpec {
+    pub intent_id: u64,
+    pub lb_pair: Pubkey,
+    pub swap_for_y: bool,
+    pub max_amount_in: u64,
+    pub min_amount_out: u64,
+    pub deadline: i64,
+    pub keeper_fillable: bool,
+    pub keeper_bounty_lamports: u64,
+}
+
+/// An owner-preapproved DLMM swap the device may later fulfill.
+///
+/// Created by `create_intent` (owner signs, base layer). The device calls
+/// `fulfill_intent`, which runs the swap only inside these bounds — exact
+/// pool and direction, at most the unfilled part of `max_amount_in`, at
+/// least a pro-rata share of `min_amount_out`, before `deadline`. Large
+/// intents may be filled in tranches; the intent closes once fully filled.
+/// The owner can discard it at any time with `cancel_intent`.
+///
+/// Keeper-fillable intents may also be executed by anyone via
+/// `keeper_fill_intent`, which pays the keeper a pro-rata share of the
+/// lamport bounty escrowed in this account — so time-critical exits still
+/// fire while the device is offline.
+///
+/// Seeds: [b"intent", session.key().as_ref(), intent_id.to_le_bytes()]
+#[account]
+pub struct Intent {
+    /// The AgentSession the intent belongs to (32)
+    pub session: Pubkey,
+
+    /// Owner-chosen identifier, unique per session (8)
+    pub intent_id: u64,
+
+    /// Meteora DLMM pool (LbPair) the swap must target (32)
+    pub lb_pair: Pubkey,
+
+    /// Side: true = sell token X for Y, false = sell token Y for X (1)
+    pub swap_for_y: bool,
+
+    /// Maximum input amount the device may swap (8)
+    pub max_amount_in: u64,
+
+    /// Minimum output for the full `max_amount_in`; scaled down pro rata (8)
+    pub min_amount_out: u64,
+
+    /// Unix timestamp after which the intent can no longer be fulfilled (8)
+    pub deadline: i64,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+
+    /// Input amount swapped so far across partial fills (8)
+    pub filled_amount: u64,
+
+    /// When true, any keeper may fill via `keeper_fill_intent` (1)
+    pub keeper_fillable: bool,
+
+    /// Lamport bounty escrowed on top of rent, paid out pro rata to keepers (8)
+    pub keeper_bounty_lamports: u64,
+}
+
+impl Intent {
+    /// Validate `spec` and build the intent account for `session`.
+    pub fn from_spec(session: Pubkey, spec: &IntentSpec, bump: u8, now: i64) -> Result<Self> {
+        require!(spec.max_amount_in > 0, AgentError::InvalidIntent);
+        require!(spec.deadline > now, AgentError::InvalidIntent);
+        Ok(Self {
+            session,
+            intent_id: spec.intent_id,
+            lb_pair: spec.lb_pair,
+            swap_for_y: spec.swap_for_y,
+            max_amount_in: spec.max_amount_in,
+            min_amount_out: spec.min_amount_out,
+            deadline: spec.deadline,
+            bump,
+            filled_amount: 0,
+            keeper_fillable: spec.keeper_fillable,
+            keeper_bounty_lamports: spec.keeper_bounty_lamports,
+        })
+    }
+
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + 8   // intent_id
+        + 32  // lb_pair
+        + 1   // swap_for_y
+        + 8   // max_amount_in
+        + 8   // min_amount_out
+        + 8   // deadline
+        + 1   // bump
+        + 8   // filled_amount
+        + 1   // keeper_fillable
+        + 8;  // keeper_bounty_lamports
+
+    /// Input amount still available to fill.
+    pub fn remaining(&self) -> u64 {
+        self.max_amount_in.saturating_sub(self.filled_amount)
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.filled_amount >= self.max_amount_in
+    }
+
+    /// Keeper bounty earned by filling `amount_in`, rounded down.
+    pub fn bounty_for(&self, amount_in: u64) -> u64 {
+        ((self.keeper_bounty_lamports as u128) * (amount_in as u128)
+            / (self.max_amount_in as u128)) as u64
+    }
+
+    /// Minimum output required when swapping `amount_in`, rounded up so a
+    /// partial amount never accepts a worse price than the owner approved.
+    pub fn min_out_for(&self, amount_in: u64) -> Result<u64> {
+        let scaled = (self.min_amount_out as u128)
+            .checked_mul(amount_in as u128)
+            .ok_or(AgentError::Overflow)?
+            .div_ceil(self.max_amount_in as u128);
+        u64::try_from(scaled).map_err(|_| AgentError::Overflow.into())
+    }
+}
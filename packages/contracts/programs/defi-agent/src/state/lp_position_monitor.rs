@@ -2,14 +2,22 @@ use anchor_lang::prelude::*;
 
 /// On-chain record of a monitored Meteora DLMM LP position.
 ///
-/// Created by `register_lp_monitor` (owner signs, base layer).
+/// Created by `register_lp_monitor` (owner signs, base layer), or
+/// automatically by `execute_dlmm_create_position` when the monitor account is
+/// passed — in that case `execute_dlmm_close_position` closes it again.
 /// Updated by `update_lp_status` (session key signs, base layer) — the ESP32
-/// calls this periodically after reading pool state off-chain to checkpoint:
-///   • whether the active bin is still inside the position's bin range
+/// calls this periodically after reading the position off-chain to checkpoint:
+///   • whether the pool's active bin (read from the LbPair) is still inside
+///     the position's bin range
 ///   • the current unclaimed fee balances
+///   • the position's token amounts, which closes check their withdrawals against
+///
+/// Zero-copy (`AccountLoader`) so the frequent `update_lp_status` checkpoints
+/// write fields in place instead of paying borsh (de)serialization. Monitors
+/// created with the earlier borsh layout are rewritten by `migrate_lp_accounts`.
 ///
 /// Seeds: [b"lp_monitor", session.key().as_ref()]
-#[account]
+#[account(zero_copy)]
 pub struct LpPositionMonitor {
     /// The AgentSession that owns this monitor (32)
     pub session: Pubkey,
@@ -29,8 +37,14 @@ pub struct LpPositionMonitor {
     /// Pool active bin observed at the last update (4)
     pub last_active_bin: i32,
 
-    /// True when last_active_bin ∈ [min_bin_id, max_bin_id] (1)
-    pub is_in_range: bool,
+    /// 1 when last_active_bin ∈ [min_bin_id, max_bin_id], else 0 (1)
+    pub is_in_range: u8,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+
+    /// Alignment padding for the u64 fields below (2)
+    pub _padding: [u8; 2],
 
     /// Unclaimed fee X amount at last checkpoint (8)
     pub fee_x_snapshot: u64,
@@ -41,8 +55,15 @@ pub struct LpPositionMonitor {
     /// Unix timestamp of the last status update (8)
     pub last_checked_at: i64,
 
-    /// PDA bump seed (1)
-    pub bump: u8,
+    /// Token X held by the position at last checkpoint, fees excluded (8)
+    pub amount_x_snapshot: u64,
+
+    /// Token Y held by the position at last checkpoint, fees excluded (8)
+    pub amount_y_snapshot: u64,
+
+    /// Checkpoint time the position was first seen out of range in its
+    /// current excursion; 0 while in range (8)
+    pub out_of_range_since: i64,
 }
 
 impl LpPositionMonitor {
@@ -54,10 +75,45 @@ impl LpPositionMonitor {
         + 4   // max_bin_id
         + 4   // last_active_bin
         + 1   // is_in_range
+        + 1   // bump
+        + 2   // _padding
         + 8   // fee_x_snapshot
         + 8   // fee_y_snapshot
         + 8   // last_checked_at
-        + 1;  // bump
+        + 8   // amount_x_snapshot
+        + 8   // amount_y_snapshot
+        + 8;  // out_of_range_since
+
+    /// Start tracking `position` with a fresh, optimistic checkpoint.
+    pub fn track(
+        &mut self,
+        session: Pubkey,
+        lb_pair: Pubkey,
+        position: Pubkey,
+        min_bin_id: i32,
+        max_bin_id: i32,
+        bump: u8,
+    ) {
+        self.session = session;
+        self.lb_pair = lb_pair;
+        self.position = position;
+        self.min_bin_id = min_bin_id;
+        self.max_bin_id = max_bin_id;
+        self.last_active_bin = 0;
+        self.is_in_range = 1; // optimistic default — first update will correct
+        self.fee_x_snapshot = 0;
+        self.fee_y_snapshot = 0;
+        self.last_checked_at = 0;
+        self.amount_x_snapshot = 0;
+        self.amount_y_snapshot = 0;
+        self.out_of_range_since = 0;
+        self.bump = bump;
+    }
+
+    /// Whether the last checkpoint found the position in range.
+    pub fn in_range(&self) -> bool {
+        self.is_in_range != 0
+    }
 
     /// Returns true when active_bin is within the registered position's range.
     pub fn check_in_range(&self, active_bin: i32) -> bool {
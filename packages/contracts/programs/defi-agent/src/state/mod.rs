@@ -3,3 +3,42 @@ pub use agent_session::*;
 
 pub mod lp_position_monitor;
 pub use lp_position_monitor::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod treasury_ledger;
+pub use treasury_ledger::*;
+
+pub mod pool_registry;
+pub use pool_registry::*;
+
+pub mod action_request;
+pub use action_request::*;
+
+pub mod pending_withdrawal;
+pub use pending_withdrawal::*;
+
+pub mod intent;
+pub use intent::*;
+
+pub mod position_registry;
+pub use position_registry::*;
+
+pub mod compressed_monitor;
+pub use compressed_monitor::*;
+
+pub mod daily_stats;
+pub use daily_stats::*;
+
+pub mod session_lookup;
+pub use session_lookup::*;
+
+pub mod fee_sponsor;
+pub use fee_sponsor::*;
+
+pub mod pair_pools;
+pub use pair_pools::*;
+
+pub mod action_receipt_log;
+pub use action_receipt_log::*;
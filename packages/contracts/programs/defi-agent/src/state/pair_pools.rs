@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+/// Maximum number of pools an owner can rank for one token pair.
+pub const MAX_PAIR_POOLS: usize = 8;
+
+/// The owner's ranked list of acceptable DLMM pools for one token pair —
+/// typically the same pair at different bin steps / fee tiers, best first.
+///
+/// Once a session has any list (`AgentSession::pair_pool_lists` > 0), new
+/// positions and migration targets must be in a pool on the list passed with
+/// the instruction, so the agent chooses the venue only among those the
+/// owner ranked. The program checks membership only; the ranking is for the
+/// agent to weigh.
+///
+/// Written by `set_pair_pools`, closed by `close_pair_pools`.
+///
+/// Seeds: [b"pair_pools", session, token_x_mint, token_y_mint]
+#[account]
+pub struct PairPools {
+    /// Owning AgentSession (32)
+    pub session: Pubkey,
+
+    /// The pair every listed pool trades (32 + 32)
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+
+    /// Acceptable LbPairs, most preferred first (4 + 32 × MAX_PAIR_POOLS)
+    pub pools: Vec<Pubkey>,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+}
+
+impl PairPools {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + 32  // token_x_mint
+        + 32  // token_y_mint
+        + 4 + 32 * MAX_PAIR_POOLS  // pools
+        + 1;  // bump
+
+    pub fn contains(&self, lb_pair: &Pubkey) -> bool {
+        self.pools.contains(lb_pair)
+    }
+
+    /// 0-based rank of `lb_pair`, if listed
+    pub fn rank(&self, lb_pair: &Pubkey) -> Option<usize> {
+        self.pools.iter().position(|p| p == lb_pair)
+    }
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+
+/// `asset` value used for lamport withdrawals from the fee vault PDA.
+pub const NATIVE_ASSET: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// An announced large withdrawal waiting out its time lock.
+///
+/// Created by `queue_withdrawal` (admin signs, base layer). Withdrawals above
+/// the configured threshold — `Config.large_withdrawal_lamports` for the fee
+/// vault, `TreasuryLedger.large_withdrawal_amount` per treasury mint — only
+/// execute against a matching pending entry whose `available_at` has passed,
+/// and consume it. `cancel_withdrawal` discards it. The threshold caps the
+/// running total of unqueued withdrawals per `withdrawal_delay_secs` window
+/// (see `WithdrawalWindow`), so splitting a drain into sub-threshold calls
+/// still hits the lock: a compromised admin key can move at most one
+/// threshold per delay without queueing.
+///
+/// Seeds: [b"pending_withdrawal", asset.as_ref()]  (asset = mint, or NATIVE_ASSET)
+#[account]
+pub struct PendingWithdrawal {
+    /// Treasury mint, or NATIVE_ASSET for the lamport fee vault (32)
+    pub asset: Pubkey,
+
+    /// Exact amount to withdraw (8)
+    pub amount: u64,
+
+    /// Recipient the withdrawal must be sent to (32)
+    pub destination: Pubkey,
+
+    /// Unix timestamp the withdrawal was queued (8)
+    pub queued_at: i64,
+
+    /// Earliest Unix timestamp the withdrawal may execute (8)
+    pub available_at: i64,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+}
+
+impl PendingWithdrawal {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // asset
+        + 8   // amount
+        + 32  // destination
+        + 8   // queued_at
+        + 8   // available_at
+        + 1;  // bump
+
+    /// Ensure this entry releases exactly `amount` to `destination` at `now`.
+    pub fn check_release(&self, amount: u64, destination: &Pubkey, now: i64) -> Result<()> {
+        require!(
+            self.amount == amount && self.destination == *destination,
+            AgentError::WithdrawalMismatch
+        );
+        require!(now >= self.available_at, AgentError::WithdrawalTimelockActive);
+        Ok(())
+    }
+}
+
+/// Running total of unqueued withdrawals in the current time-lock window.
+///
+/// Embedded in `Config` (fee vault) and `TreasuryLedger` (per mint). A window
+/// opens at the first unqueued withdrawal and lasts `withdrawal_delay_secs`;
+/// queued releases have already waited out the delay and are not counted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawalWindow {
+    /// Unix timestamp the current window opened (8)
+    pub started_at: i64,
+
+    /// Unqueued amount withdrawn since `started_at` (8)
+    pub withdrawn: u64,
+}
+
+impl WithdrawalWindow {
+    pub const LEN: usize = 8 + 8;
+
+    /// Add `amount` to the window at `now`, opening a fresh window once
+    /// `window_secs` have passed, and return the new running total.
+    pub fn record(&mut self, amount: u64, window_secs: i64, now: i64) -> Result<u64> {
+        if self.withdrawn == 0 || now >= self.started_at.saturating_add(window_secs) {
+            self.started_at = now;
+            self.withdrawn = 0;
+        }
+        self.withdrawn = self.withdrawn.checked_add(amount).ok_or(AgentError::Overflow)?;
+        Ok(self.withdrawn)
+    }
+}
+
+/// True when moving a large-withdrawal threshold from `current` to `new` keeps
+/// or tightens the lock. 0 means "no time lock", the loosest value.
+pub fn threshold_tightens(current: u64, new: u64) -> bool {
+    new == current || (new != 0 && (current == 0 || new < current))
+}
+
+/// Require a released `pending` entry once unqueued withdrawals in `window`
+/// would exceed `threshold` (0 = no time lock). A supplied entry is always
+/// checked, since it is consumed by the withdrawal, and bypasses the window.
+pub fn enforce_withdrawal_timelock(
+    threshold: u64,
+    window_secs: i64,
+    window: &mut WithdrawalWindow,
+    amount: u64,
+    destination: &Pubkey,
+    pending: Option<&PendingWithdrawal>,
+    now: i64,
+) -> Result<()> {
+    match pending {
+        Some(pending) => pending.check_release(amount, destination, now),
+        None if threshold == 0 => Ok(()),
+        None => {
+            let mut next = *window;
+            let total = next.record(amount, window_secs, now)?;
+            require!(total <= threshold, AgentError::WithdrawalNotQueued);
+            *window = next;
+            Ok(())
+        }
+    }
+}
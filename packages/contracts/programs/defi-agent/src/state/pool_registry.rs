@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+
+/// Maximum number of pools the global registry can hold.
+pub const MAX_REGISTRY_POOLS: usize = 32;
+
+/// Risk tiers an admin can attach to a vetted pool (informational for clients)
+pub const RISK_TIER_LOW: u8 = 0;
+pub const RISK_TIER_MEDIUM: u8 = 1;
+pub const RISK_TIER_HIGH: u8 = 2;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RegisteredPool {
+    /// Meteora DLMM LbPair (32)
+    pub lb_pair: Pubkey,
+
+    /// Admin-assigned risk tier: 0=low, 1=medium, 2=high (1)
+    pub risk_tier: u8,
+}
+
+impl RegisteredPool {
+    pub const LEN: usize = 32 + 1;
+}
+
+/// Admin-curated list of vetted Meteora DLMM pools.
+///
+/// Sessions that opt into registry-only mode (`set_registry_only`) may only
+/// run DLMM instructions against pools listed here.
+///
+/// Seeds: [b"pool_registry"]
+#[account]
+pub struct PoolRegistry {
+    /// Vetted pools (4 + 33 × MAX_REGISTRY_POOLS)
+    pub pools: Vec<RegisteredPool>,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+}
+
+impl PoolRegistry {
+    pub const LEN: usize = 8   // discriminator
+        + 4 + RegisteredPool::LEN * MAX_REGISTRY_POOLS  // pools
+        + 1;  // bump
+
+    pub fn contains(&self, lb_pair: &Pubkey) -> bool {
+        self.pools.iter().any(|p| p.lb_pair == *lb_pair)
+    }
+
+    /// Insert a pool or update its risk tier if already present.
+    pub fn upsert(&mut self, lb_pair: Pubkey, risk_tier: u8) -> Result<()> {
+        if let Some(entry) = self.pools.iter_mut().find(|p| p.lb_pair == lb_pair) {
+            entry.risk_tier = risk_tier;
+            return Ok(());
+        }
+        require!(self.pools.len() < MAX_REGISTRY_POOLS, AgentError::PoolRegistryFull);
+        self.pools.push(RegisteredPool { lb_pair, risk_tier });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, lb_pair: &Pubkey) -> Result<()> {
+        let index = self
+            .pools
+            .iter()
+            .position(|p| p.lb_pair == *lb_pair)
+            .ok_or(AgentError::PoolNotRegistered)?;
+        self.pools.swap_remove(index);
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use std::cell::RefMut;
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+
+/// Maximum number of positions a session's registry can track.
+pub const MAX_MANAGED_POSITIONS: usize = 16;
+
+#[zero_copy]
+pub struct ManagedPosition {
+    /// DLMM position account (32)
+    pub position: Pubkey,
+
+    /// Meteora DLMM pool (LbPair) the position belongs to (32)
+    pub lb_pair: Pubkey,
+
+    /// Unix timestamp the position was registered (8)
+    pub created_at: i64,
+
+    /// Token X deposited into the position over its lifetime (8)
+    pub deposited_x: u64,
+
+    /// Token Y deposited into the position over its lifetime (8)
+    pub deposited_y: u64,
+
+    /// Cost basis: each deposit valued in token Y at the pool price when it
+    /// was made, summed (8)
+    pub cost_basis: u64,
+}
+
+impl ManagedPosition {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8;
+
+    pub(crate) const EMPTY: Self = Self {
+        position: Pubkey::new_from_array([0; 32]),
+        lb_pair: Pubkey::new_from_array([0; 32]),
+        created_at: 0,
+        deposited_x: 0,
+        deposited_y: 0,
+        cost_basis: 0,
+    };
+}
+
+/// Every DLMM position the agent currently manages for a session.
+///
+/// Created by `initialize_position_registry` (owner signs, base layer), or
+/// lazily via `init_if_needed` by the first `execute_dlmm_create_position` /
+/// `adopt_position`, so sessions that never open positions pay no rent for it.
+/// `execute_dlmm_create_position` appends to it and
+/// `execute_dlmm_close_position` removes from it, so the owner's app and
+/// settle / close-all flows can enumerate the agent's positions without
+/// scanning the DLMM program.
+///
+/// Zero-copy (`AccountLoader`): only the first `count` entries are live.
+/// Registries created with the earlier borsh layout are rewritten by
+/// `migrate_lp_accounts`.
+///
+/// Seeds: [b"position_registry", session.key().as_ref()]
+#[account(zero_copy)]
+pub struct PositionRegistry {
+    /// The AgentSession this registry belongs to (32)
+    pub session: Pubkey,
+
+    /// Managed positions; entries at index >= `count` are unused (96 × MAX_MANAGED_POSITIONS)
+    pub positions: [ManagedPosition; MAX_MANAGED_POSITIONS],
+
+    /// Number of live entries in `positions` (1)
+    pub count: u8,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+
+    /// Alignment padding (6)
+    pub _padding: [u8; 6],
+}
+
+impl PositionRegistry {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // session
+        + ManagedPosition::LEN * MAX_MANAGED_POSITIONS  // positions
+        + 1   // count
+        + 1   // bump
+        + 6;  // _padding
+
+    /// Borrow a registry that `init_if_needed` may have created in this very
+    /// instruction: a fresh account (zeroed discriminator) is initialized for
+    /// `session`, an existing one is loaded normally.
+    pub fn load_or_init<'a, 'info>(
+        loader: &'a AccountLoader<'info, Self>,
+        session: Pubkey,
+        bump: u8,
+    ) -> Result<RefMut<'a, Self>> {
+        let fresh = {
+            let info = loader.to_account_info();
+            let data = info.try_borrow_data()?;
+            data[..8].iter().all(|b| *b == 0)
+        };
+        if !fresh {
+            return loader.load_mut();
+        }
+        let mut registry = loader.load_init()?;
+        registry.session = session;
+        registry.bump = bump;
+        Ok(registry)
+    }
+
+    /// The live entries.
+    pub fn active(&self) -> &[ManagedPosition] {
+        &self.positions[..self.count as usize]
+    }
+
+    pub fn contains(&self, position: &Pubkey) -> bool {
+        self.active().iter().any(|p| p.position == *position)
+    }
+
+    /// A copy of `position`'s entry, if tracked.
+    pub fn get(&self, position: &Pubkey) -> Option<ManagedPosition> {
+        self.active().iter().find(|p| p.position == *position).copied()
+    }
+
+    /// Add a deposit of `amount_x` / `amount_y`, worth `value` in token Y, to
+    /// `position`'s cost basis; returns whether the position is tracked.
+    pub fn record_deposit(
+        &mut self,
+        position: &Pubkey,
+        amount_x: u64,
+        amount_y: u64,
+        value: u64,
+    ) -> bool {
+        let count = self.count as usize;
+        match self.positions[..count].iter_mut().find(|p| p.position == *position) {
+            Some(entry) => {
+                entry.deposited_x = entry.deposited_x.saturating_add(amount_x);
+                entry.deposited_y = entry.deposited_y.saturating_add(amount_y);
+                entry.cost_basis = entry.cost_basis.saturating_add(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn add(&mut self, position: Pubkey, lb_pair: Pubkey, now: i64) -> Result<()> {
+        if self.contains(&position) {
+            return Ok(());
+        }
+        let count = self.count as usize;
+        require!(count < MAX_MANAGED_POSITIONS, AgentError::PositionRegistryFull);
+        self.positions[count] = ManagedPosition {
+            position,
+            lb_pair,
+            created_at: now,
+            ..ManagedPosition::EMPTY
+        };
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Drop `position` if present; returns its entry if it was tracked.
+    pub fn remove(&mut self, position: &Pubkey) -> Option<ManagedPosition> {
+        let count = self.count as usize;
+        let i = self.active().iter().position(|p| p.position == *position)?;
+        let entry = self.positions[i];
+        self.positions.copy_within(i + 1..count, i);
+        self.positions[count - 1] = ManagedPosition::EMPTY;
+        self.count -= 1;
+        Some(entry)
+    }
+}
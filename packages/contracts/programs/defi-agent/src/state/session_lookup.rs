@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::AgentSession;
+
+/// Reverse lookup from a device key to the session it was enrolled in, so a
+/// client holding only the device key can find its session without scanning
+/// program accounts.
+///
+/// Written by `initialize_session`, `add_device` and `rotate_device` (owner
+/// pays, base layer) whenever a key is enrolled. An entry naming another
+/// session is only repointed once that session — passed as
+/// `previous_session` — no longer lists the key (removed, or the session
+/// closed), so no owner can redirect a key another session still uses.
+/// Entries are not cleared when a key is removed, so readers confirm the key
+/// is still in the session's `devices`.
+///
+/// Seeds: [b"session_lookup", device_key.as_ref()]
+#[account]
+pub struct SessionLookup {
+    /// The device key this entry is for (32)
+    pub device_key: Pubkey,
+
+    /// The AgentSession the key was last enrolled in (32)
+    pub session: Pubkey,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+}
+
+impl SessionLookup {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // device_key
+        + 32  // session
+        + 1;  // bump
+
+    /// Point the entry for `device_key` at `session`. An entry naming another
+    /// session needs that session's account as `previous`, and fails with
+    /// `DeviceEnrolledElsewhere` while it still lists the key.
+    pub fn set(
+        &mut self,
+        device_key: Pubkey,
+        session: Pubkey,
+        bump: u8,
+        previous: Option<&AccountInfo>,
+    ) -> Result<()> {
+        if self.session != Pubkey::default() && self.session != session {
+            let previous = previous.ok_or(AgentError::DeviceEnrolledElsewhere)?;
+            require_keys_eq!(previous.key(), self.session, AgentError::DeviceEnrolledElsewhere);
+            // A closed session no longer lists anything
+            if previous.owner == &crate::ID && !previous.data_is_empty() {
+                let other = AgentSession::try_deserialize(&mut &previous.try_borrow_data()?[..])?;
+                require!(
+                    other.device_index(&device_key).is_none(),
+                    AgentError::DeviceEnrolledElsewhere
+                );
+            }
+        }
+        self.device_key = device_key;
+        self.session = session;
+        self.bump = bump;
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentError;
+use crate::state::WithdrawalWindow;
+
+/// Per-mint accounting for protocol fees held by the program treasury.
+///
+/// Created by `initialize_treasury` (admin signs, base layer) alongside the
+/// treasury token account `[b"treasury", mint]`, whose authority is the global
+/// Config PDA. Execute handlers bump `total_accrued` whenever they skim a fee;
+/// `withdraw_treasury` bumps `total_withdrawn`. Referral shares paid out of
+/// skimmed fees never touch the vault but are tallied in `total_referral_paid`.
+///
+/// Seeds: [b"treasury_ledger", mint.as_ref()]
+#[account]
+pub struct TreasuryLedger {
+    /// Token mint this ledger accounts for (32)
+    pub mint: Pubkey,
+
+    /// Treasury token account holding the accrued fees (32)
+    pub vault: Pubkey,
+
+    /// Cumulative protocol fees skimmed into the vault (8)
+    pub total_accrued: u64,
+
+    /// Cumulative amount withdrawn by the admin (8)
+    pub total_withdrawn: u64,
+
+    /// PDA bump seed (1)
+    pub bump: u8,
+
+    /// Bump of the treasury vault token account PDA (1)
+    pub vault_bump: u8,
+
+    /// Cumulative fees routed to session referrers instead of the vault (8)
+    pub total_referral_paid: u64,
+
+    /// Withdrawals above this amount must be queued first; 0 = no time lock (8)
+    pub large_withdrawal_amount: u64,
+
+    /// Unqueued withdrawals counted against `large_withdrawal_amount` (16)
+    pub withdrawal_window: WithdrawalWindow,
+}
+
+impl TreasuryLedger {
+    pub const LEN: usize = 8   // discriminator
+        + 32  // mint
+        + 32  // vault
+        + 8   // total_accrued
+        + 8   // total_withdrawn
+        + 1   // bump
+        + 1   // vault_bump
+        + 8   // total_referral_paid
+        + 8   // large_withdrawal_amount
+        + WithdrawalWindow::LEN; // withdrawal_window
+
+    pub fn record_accrual(&mut self, amount: u64) -> Result<()> {
+        self.total_accrued = self
+            .total_accrued
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_referral(&mut self, amount: u64) -> Result<()> {
+        self.total_referral_paid = self
+            .total_referral_paid
+            .checked_add(amount)
+            .ok_or(AgentError::Overflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,517 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::dlmm;
+use crate::errors::AgentError;
+use crate::events::{
+    PortfolioValued, PositionRealized, SettlementDiscrepancy, SlippageExceeded,
+};
+use crate::state::{AgentSession, LpPositionMonitor, ManagedPosition, PoolRegistry};
+
+/// Valuation checkpoints, carried by `PortfolioValued`
+pub const VALUATION_SESSION_START: u8 = 0; // initialize_session
+pub const VALUATION_CLOSE: u8 = 1;         // liquidity removed / fees claimed
+pub const VALUATION_UNDELEGATE: u8 = 2;    // undelegate_session
+
+const BPS_PER_UNIT: f64 = 10_000.0;
+
+/// 1.0 in the Q64.64 fixed point budget prices are computed in
+pub const ONE_Q64: u128 = 1 << 64;
+
+/// DLMM fee rates are fractions of `FEE_PRECISION`
+const FEE_PRECISION: u128 = 1_000_000_000;
+
+/// DLMM caps a pool's total fee rate at 10%
+const MAX_FEE_RATE: u128 = 100_000_000;
+
+/// Value gap, in basis points, between a close's withdrawal and the monitor's
+/// last checkpoint beyond which the session is flagged for review. Leaves
+/// room for price moves and fees accrued since the checkpoint.
+pub const SETTLEMENT_TOLERANCE_BPS: u64 = 200;
+
+/// Price of one base unit of the pool's X token in base units of its Y token,
+/// at a pool's active bin: `(1 + bin_step / 10_000) ^ active_id`.
+/// The pool is the price oracle. This float approximation only feeds
+/// reporting (PnL, slippage, realized proceeds); every amount enforced against
+/// a session limit is priced with the exact integer `bin_price_q64` instead.
+pub fn active_bin_price(bin_step: u16, active_id: i32) -> f64 {
+    (1.0 + bin_step as f64 / BPS_PER_UNIT).powi(active_id)
+}
+
+/// `active_bin_price` in Q64.64 fixed point, by square-and-multiply on the
+/// bin's base (inverted for a negative `active_id`). Every step rounds down;
+/// a price past the format's range saturates at u128::MAX.
+pub fn bin_price_q64(bin_step: u16, active_id: i32) -> u128 {
+    let step = bin_step as u128;
+    let mut base = if active_id >= 0 {
+        ONE_Q64 + ONE_Q64 * step / 10_000
+    } else {
+        ONE_Q64 * 10_000 / (10_000 + step)
+    };
+    let mut exponent = active_id.unsigned_abs();
+    let mut price = ONE_Q64;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            price = mul_q64(price, base);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = mul_q64(base, base);
+        }
+    }
+    price
+}
+
+/// Q64.64 product, rounded down and saturating at u128::MAX
+fn mul_q64(a: u128, b: u128) -> u128 {
+    let (a_hi, a_lo) = (a >> 64, a & u64::MAX as u128);
+    let (b_hi, b_lo) = (b >> 64, b & u64::MAX as u128);
+    a_hi.checked_mul(b_hi)
+        .and_then(|hi| hi.checked_mul(ONE_Q64))
+        .and_then(|hi| hi.checked_add(a_hi * b_lo))
+        .and_then(|sum| sum.checked_add(a_lo * b_hi))
+        .and_then(|sum| sum.checked_add((a_lo * b_lo) >> 64))
+        .unwrap_or(u128::MAX)
+}
+
+/// `amount` at a Q64.64 `price`, rounded up and saturating at u64::MAX — a
+/// cap is never undercharged by rounding.
+pub fn value_at_q64(amount: u64, price: u128) -> u64 {
+    let amount = amount as u128;
+    let low = amount * (price & u64::MAX as u128);
+    amount
+        .checked_mul(price >> 64)
+        .and_then(|value| value.checked_add(low >> 64))
+        .and_then(|value| value.checked_add(u128::from(low as u64 != 0)))
+        .map_or(u64::MAX, |value| value.min(u64::MAX as u128) as u64)
+}
+
+/// The pool's current active-bin price (see `active_bin_price`)
+pub fn pool_price(lb_pair: &AccountInfo) -> Result<f64> {
+    let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(lb_pair)?;
+    let pair = loader.load()?;
+    Ok(active_bin_price(pair.bin_step, pair.active_id))
+}
+
+/// The pool's current active-bin price in Q64.64 (see `bin_price_q64`)
+pub fn pool_price_q64(lb_pair: &AccountInfo) -> Result<u128> {
+    let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(lb_pair)?;
+    let pair = loader.load()?;
+    Ok(bin_price_q64(pair.bin_step, pair.active_id))
+}
+
+/// The pool's current active bin, read from the LbPair account
+pub fn active_bin(lb_pair: &AccountInfo) -> Result<i32> {
+    let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(lb_pair)?;
+    let active_id = loader.load()?.active_id;
+    Ok(active_id)
+}
+
+/// Require the pool's active bin to lie within `[min_active_bin,
+/// max_active_bin]`; an unset bound is open.
+pub fn check_active_bin_band(
+    lb_pair: &AccountInfo,
+    min_active_bin: Option<i32>,
+    max_active_bin: Option<i32>,
+) -> Result<()> {
+    let active_id = active_bin(lb_pair)?;
+    if let Some(min) = min_active_bin {
+        require!(active_id >= min, AgentError::ActiveBinOutOfBand);
+    }
+    if let Some(max) = max_active_bin {
+        require!(active_id <= max, AgentError::ActiveBinOutOfBand);
+    }
+    Ok(())
+}
+
+/// A DLMM pool's base fee rate, in `FEE_PRECISION` units:
+/// `base_factor * bin_step * 10 * 10^base_fee_power_factor`.
+pub fn base_fee_rate(base_factor: u16, bin_step: u16, base_fee_power_factor: u8) -> u128 {
+    (base_factor as u128 * bin_step as u128 * 10)
+        .saturating_mul(10u128.saturating_pow(base_fee_power_factor as u32))
+}
+
+/// A DLMM pool's volatility fee rate, in `FEE_PRECISION` units:
+/// `variable_fee_control * (volatility_accumulator * bin_step)^2 / 1e11`,
+/// rounded up.
+pub fn variable_fee_rate(
+    volatility_accumulator: u32,
+    bin_step: u16,
+    variable_fee_control: u32,
+) -> u128 {
+    let square = (volatility_accumulator as u128 * bin_step as u128).pow(2);
+    (variable_fee_control as u128)
+        .saturating_mul(square)
+        .div_ceil(100_000_000_000)
+}
+
+/// The pool's current total fee rate, in `FEE_PRECISION` units, capped at
+/// DLMM's maximum. Read before a swap it estimates the fee: the pool updates
+/// its volatility as the swap crosses bins.
+pub fn pool_fee_rate(lb_pair: &AccountInfo) -> Result<u64> {
+    let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(lb_pair)?;
+    let pair = loader.load()?;
+    let params = &pair.parameters;
+    let rate = base_fee_rate(params.base_factor, pair.bin_step, params.base_fee_power_factor)
+        .saturating_add(variable_fee_rate(
+            pair.v_parameters.volatility_accumulator,
+            pair.bin_step,
+            params.variable_fee_control,
+        ));
+    Ok(rate.min(MAX_FEE_RATE) as u64)
+}
+
+/// Fee DLMM takes from a swap input of `amount_in` (fee included) at
+/// `fee_rate`, rounded up as the pool does.
+pub fn swap_fee(amount_in: u64, fee_rate: u64) -> u64 {
+    (amount_in as u128 * fee_rate as u128).div_ceil(FEE_PRECISION) as u64
+}
+
+/// Record a swap's estimated pool fee (`fee`, in input units) on the session,
+/// valued in Y units of the pool at its pre-trade Q64.64 `price` (rounded up,
+/// see `value_at_q64`) and counted against the owner's swap fee budget.
+/// Returns the Y-unit value.
+pub fn record_pool_fee(
+    session: &mut AgentSession,
+    price: u128,
+    input_is_x: bool,
+    fee: u64,
+) -> Result<u64> {
+    let fee_y = if input_is_x { value_at_q64(fee, price) } else { fee };
+    session.record_swap_fee(fee_y)?;
+    Ok(fee_y)
+}
+
+/// `amount_x` + `amount_y` in Y units at `price`. Float-to-int casts
+/// saturate, so an extreme price caps at u64::MAX.
+pub fn value_in_y(price: f64, amount_x: u64, amount_y: u64) -> u64 {
+    amount_y.saturating_add((amount_x as f64 * price) as u64)
+}
+
+/// `value_in_y` at a Q64.64 `price`, the X leg rounded up (see
+/// `value_at_q64`) — for values enforced against a session limit
+pub fn value_in_y_q64(price: u128, amount_x: u64, amount_y: u64) -> u64 {
+    amount_y.saturating_add(value_at_q64(amount_x, price))
+}
+
+/// Value of a device's holdings of the pool's two tokens, in Y units.
+/// Both token accounts must hold the pool's mints and belong to the same
+/// enrolled device.
+pub fn portfolio_value(
+    session: &AgentSession,
+    lb_pair: &AccountInfo,
+    token_x: &AccountInfo,
+    token_y: &AccountInfo,
+) -> Result<(Pubkey, u64)> {
+    let (x_mint, y_mint, price) = {
+        let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(lb_pair)?;
+        let pair = loader.load()?;
+        let price = active_bin_price(pair.bin_step, pair.active_id);
+        (pair.token_x_mint, pair.token_y_mint, price)
+    };
+    let x = TokenAccount::try_deserialize(&mut &token_x.try_borrow_data()?[..])?;
+    let y = TokenAccount::try_deserialize(&mut &token_y.try_borrow_data()?[..])?;
+    require!(
+        x.mint == x_mint
+            && y.mint == y_mint
+            && x.owner == y.owner
+            && session.device_index(&x.owner).is_some(),
+        AgentError::ValuationAccountMismatch
+    );
+
+    Ok((y_mint, value_in_y(price, x.amount, y.amount)))
+}
+
+/// Value the device's holdings and record the valuation on the session,
+/// emitting `PortfolioValued`. Valuations in a different mint than the
+/// session's first one are skipped, so a close never fails over reporting.
+pub fn record(
+    session: &mut Account<AgentSession>,
+    lb_pair: &AccountInfo,
+    token_x: &AccountInfo,
+    token_y: &AccountInfo,
+    checkpoint: u8,
+    now: i64,
+) -> Result<()> {
+    let (quote_mint, value) = portfolio_value(session, lb_pair, token_x, token_y)?;
+    if let Some(realized_pnl) = session.record_valuation(quote_mint, value, now) {
+        emit!(PortfolioValued {
+            session: session.key(),
+            checkpoint,
+            valuation_mint: quote_mint,
+            value,
+            realized_pnl,
+        });
+    }
+    Ok(())
+}
+
+/// Require a caller-chosen valuation pool to be vouched for: listed in the
+/// global PoolRegistry (passed as `registry`) or pinned by the owner as the
+/// budget price pool of either of its mints. Anyone can create a DLMM pool at
+/// an arbitrary active bin, so an unvetted one would let the signer write the
+/// session's `last_value` / `realized_pnl`.
+pub fn check_valuation_pool(
+    session: &AgentSession,
+    registry: Option<&PoolRegistry>,
+    lb_pair: &AccountInfo,
+) -> Result<()> {
+    if registry.is_some_and(|registry| registry.contains(lb_pair.key)) {
+        return Ok(());
+    }
+    let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(lb_pair)?;
+    let pair = loader.load()?;
+    require!(
+        session.budget_price_pool(&pair.token_x_mint) == Some(*lb_pair.key)
+            || session.budget_price_pool(&pair.token_y_mint) == Some(*lb_pair.key),
+        AgentError::ValuationPoolNotTrusted
+    );
+    Ok(())
+}
+
+/// `record` for instructions that take the valuation accounts as optional:
+/// all three or none must be passed. The pool is caller-chosen here, so it
+/// must pass `check_valuation_pool`.
+pub fn record_optional(
+    session: &mut Account<AgentSession>,
+    registry: Option<&PoolRegistry>,
+    lb_pair: Option<&UncheckedAccount>,
+    token_x: Option<&UncheckedAccount>,
+    token_y: Option<&UncheckedAccount>,
+    checkpoint: u8,
+    now: i64,
+) -> Result<()> {
+    match (lb_pair, token_x, token_y) {
+        (Some(lb_pair), Some(token_x), Some(token_y)) => {
+            check_valuation_pool(session, registry, lb_pair)?;
+            record(session, lb_pair, token_x, token_y, checkpoint, now)
+        }
+        (None, None, None) => Ok(()),
+        _ => err!(AgentError::ValuationAccountMismatch),
+    }
+}
+
+/// Emit `PositionRealized` for a position whose liquidity was just withdrawn:
+/// `withdrawn_x` / `withdrawn_y` (principal plus claimed fees) valued at the
+/// pool's current price, against the cost basis in its registry `entry`.
+pub fn report_realized(
+    session: Pubkey,
+    entry: &ManagedPosition,
+    lb_pair: &AccountInfo,
+    withdrawn_x: u64,
+    withdrawn_y: u64,
+) -> Result<()> {
+    let proceeds = value_in_y(pool_price(lb_pair)?, withdrawn_x, withdrawn_y);
+    let realized_gain = (proceeds as i128 - entry.cost_basis as i128)
+        .clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+    emit!(PositionRealized {
+        session,
+        position: entry.position,
+        lb_pair: entry.lb_pair,
+        deposited_x: entry.deposited_x,
+        deposited_y: entry.deposited_y,
+        cost_basis: entry.cost_basis,
+        withdrawn_x,
+        withdrawn_y,
+        proceeds,
+        realized_gain,
+    });
+    Ok(())
+}
+
+/// Output of swapping `amount_in` at `price` (Y per X), ignoring pool fees
+/// and price impact — the quote realized slippage is measured against.
+pub fn quote_out(price: f64, x_to_y: bool, amount_in: u64) -> u64 {
+    let out = if x_to_y {
+        amount_in as f64 * price
+    } else {
+        amount_in as f64 / price
+    };
+    out as u64
+}
+
+/// Q64.64 price of one base unit of `mint` in base units of the session's
+/// budget mint, read from `price_pool` — which must be the DLMM pool the
+/// owner pinned for `mint` (`set_budget_price_pool`), pairing the two in
+/// either order. As elsewhere in this module the pool is the oracle; here the
+/// price feeds the session's caps, so a device can't pick the pool, and the
+/// math is exact integer arithmetic.
+pub fn budget_price(
+    session: &AgentSession,
+    mint: &Pubkey,
+    price_pool: Option<&AccountInfo>,
+) -> Result<u128> {
+    if *mint == session.budget_mint {
+        return Ok(ONE_Q64);
+    }
+    let pool = price_pool.ok_or(AgentError::BudgetPriceRequired)?;
+    require!(
+        session.budget_price_pool(mint) == Some(pool.key()),
+        AgentError::BudgetPricePoolNotPinned
+    );
+    let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(pool)?;
+    let pair = loader.load()?;
+    if pair.token_x_mint == *mint && pair.token_y_mint == session.budget_mint {
+        Ok(bin_price_q64(pair.bin_step, pair.active_id))
+    } else if pair.token_y_mint == *mint && pair.token_x_mint == session.budget_mint {
+        Ok(bin_price_q64(pair.bin_step, pair.active_id.saturating_neg()))
+    } else {
+        err!(AgentError::InvalidBudgetPricePool)
+    }
+}
+
+/// `amount` of `mint` in the session's budget units, valued via `price_pool`
+/// (see `budget_price`) and rounded up. Without a budget mint the amount is
+/// returned as is.
+pub fn budget_value(
+    session: &AgentSession,
+    mint: &Pubkey,
+    amount: u64,
+    price_pool: Option<&AccountInfo>,
+) -> Result<u64> {
+    if !session.has_budget_mint() || *mint == session.budget_mint {
+        return Ok(amount);
+    }
+    let price = budget_price(session, mint, price_pool)?;
+    Ok(value_at_q64(amount, price))
+}
+
+/// A deposit of `amount_x` / `amount_y` into `lb_pair`, in the session's
+/// budget units: a pool whose X or Y token is the budget mint prices the
+/// other token itself; otherwise the deposit is valued in Y at the pool's
+/// price, then through the `price_pool` pinned for Y; rounded up. Without a
+/// budget mint this is `amount_x + amount_y`.
+pub fn deposit_budget_value(
+    session: &AgentSession,
+    lb_pair: &AccountInfo,
+    amount_x: u64,
+    amount_y: u64,
+    price_pool: Option<&AccountInfo>,
+) -> Result<u64> {
+    if !session.has_budget_mint() {
+        return Ok(amount_x.checked_add(amount_y).ok_or(AgentError::Overflow)?);
+    }
+    let (x_mint, y_mint, bin_step, active_id) = {
+        let loader = AccountLoader::<dlmm::accounts::LbPair>::try_from(lb_pair)?;
+        let pair = loader.load()?;
+        (pair.token_x_mint, pair.token_y_mint, pair.bin_step, pair.active_id)
+    };
+    if x_mint == session.budget_mint {
+        let price_y = bin_price_q64(bin_step, active_id.saturating_neg());
+        return Ok(amount_x.saturating_add(value_at_q64(amount_y, price_y)));
+    }
+    let value_x = value_at_q64(amount_x, bin_price_q64(bin_step, active_id));
+    let value_y = amount_y.saturating_add(value_x);
+    budget_value(session, &y_mint, value_y, price_pool)
+}
+
+/// A swap of `amount_in` of `input_mint` for `amount_out` of `output_mint`,
+/// in the session's budget units: a leg in the budget mint counts at its own
+/// amount (the input first) — so a settled swap converts at its executed
+/// price — and otherwise the input is valued via the `price_pool` pinned for
+/// its mint. Without a budget mint this is `amount_in`.
+pub fn swap_budget_value(
+    session: &AgentSession,
+    input_mint: &Pubkey,
+    amount_in: u64,
+    output_mint: &Pubkey,
+    amount_out: u64,
+    price_pool: Option<&AccountInfo>,
+) -> Result<u64> {
+    if session.has_budget_mint()
+        && *input_mint != session.budget_mint
+        && *output_mint == session.budget_mint
+    {
+        return Ok(amount_out);
+    }
+    budget_value(session, input_mint, amount_in, price_pool)
+}
+
+/// Shortfall of `amount_out` below `expected_out`, in basis points of
+/// `expected_out`. Output above the quote counts as zero.
+pub fn slippage_bps(expected_out: u64, amount_out: u64) -> u64 {
+    if expected_out == 0 {
+        return 0;
+    }
+    (expected_out.saturating_sub(amount_out) as u128 * BPS_PER_UNIT as u128
+        / expected_out as u128) as u64
+}
+
+/// Record a settled swap's realized slippage on the session, valued in Y
+/// units of the output pool `lb_pair` at its pre-trade `price`, and emit
+/// `SlippageExceeded` when it tops the session's `max_slippage_bps`.
+pub fn record_slippage(
+    session: &mut Account<AgentSession>,
+    lb_pair: Pubkey,
+    price: f64,
+    output_is_x: bool,
+    expected_out: u64,
+    amount_out: u64,
+) {
+    let shortfall = expected_out.saturating_sub(amount_out);
+    let shortfall_y = if output_is_x {
+        value_in_y(price, shortfall, 0)
+    } else {
+        shortfall
+    };
+    session.record_slippage(shortfall_y);
+
+    let slippage = slippage_bps(expected_out, amount_out);
+    if session.max_slippage_bps > 0 && slippage > session.max_slippage_bps as u64 {
+        emit!(SlippageExceeded {
+            session: session.key(),
+            lb_pair,
+            expected_out,
+            amount_out,
+            slippage_bps: slippage,
+            max_slippage_bps: session.max_slippage_bps,
+        });
+    }
+}
+
+/// Gap between `expected` and `actual`, in basis points of the larger, so the
+/// result stays within 0..=10_000 whichever side is short.
+pub fn settlement_gap_bps(expected: u64, actual: u64) -> u64 {
+    let larger = expected.max(actual);
+    if larger == 0 {
+        return 0;
+    }
+    (expected.abs_diff(actual) as u128 * BPS_PER_UNIT as u128 / larger as u128) as u64
+}
+
+/// Check a closed position's withdrawal against `monitor`'s last checkpoint
+/// of its size plus unclaimed fees, both valued at the pool's current price.
+/// A gap beyond `SETTLEMENT_TOLERANCE_BPS` sets `needs_review` and emits
+/// `SettlementDiscrepancy`. Skipped for a monitor never checkpointed.
+pub fn verify_settlement(
+    session: &mut Account<AgentSession>,
+    monitor: &LpPositionMonitor,
+    lb_pair: &AccountInfo,
+    withdrawn_x: u64,
+    withdrawn_y: u64,
+) -> Result<()> {
+    if monitor.last_checked_at == 0 {
+        return Ok(());
+    }
+    let expected_x = monitor.amount_x_snapshot.saturating_add(monitor.fee_x_snapshot);
+    let expected_y = monitor.amount_y_snapshot.saturating_add(monitor.fee_y_snapshot);
+    let price = pool_price(lb_pair)?;
+    let gap_bps = settlement_gap_bps(
+        value_in_y(price, expected_x, expected_y),
+        value_in_y(price, withdrawn_x, withdrawn_y),
+    );
+    if gap_bps > SETTLEMENT_TOLERANCE_BPS {
+        session.needs_review = true;
+        emit!(SettlementDiscrepancy {
+            session: session.key(),
+            position: monitor.position,
+            lb_pair: monitor.lb_pair,
+            expected_x,
+            expected_y,
+            withdrawn_x,
+            withdrawn_y,
+            gap_bps,
+            checked_at: monitor.last_checked_at,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,345 @@
+//! Deterministic, off-chain model of the session policy handlers.
+//!
+//! Each [`Op`] replays the exact `AgentSession` method sequence its on-chain
+//! handler runs (`initialize_session`, `add_device`, `set_device_limits`,
+//! `set_fee_budget`, `disable_device_key`, `execute_action`,
+//! `delegate_session`, `commit_session`, `undelegate_session` and the owner
+//! setters for co-signing, the violation freeze, commit cadence, idle slots,
+//! budget mint, budget price pools and mark-to-market, plus
+//! `revalue_exposure`), against a caller-controlled clock and ER slot.
+//! `initialize_session` and `execute_action` run the program's own cores
+//! (`open_session`, `admit` — scope checks and `freeze::screen` included —
+//! and `apply_action`); only account plumbing and sysvar checks are left out.
+//! A failed op rolls the session back, as a failed transaction would.
+//! [`Sim::lp`] is the session scenario tests start from.
+//!
+//! `tests/invariants.rs` drives random op sequences through [`Sim`] and checks
+//! the accounting invariants after every step; `tests/validation.rs` checks
+//! that `validate_session` fails identically for every handler shape. When a handler's validation
+//! order changes, update the matching arm of [`Sim::step`].
+
+use anchor_lang::prelude::*;
+use anchor_lang::AccountDeserialize;
+
+use defi_agent::dlmm::accounts::LbPair;
+use defi_agent::errors::AgentError;
+use defi_agent::instructions::execute_action::{admit, apply_action};
+use defi_agent::instructions::initialize_session::{check_limits, open_session, SessionTerms};
+use defi_agent::introspection::LAMPORTS_PER_SIGNATURE;
+use defi_agent::state::{
+    ActionKind, AgentSession, BudgetCaps, FeeTier, TemporalSource, DELEGATION_DELEGATED,
+    DELEGATION_UNDELEGATED, ACTION_LP_REBALANCE, MAX_DEVICES, STRATEGY_COUNT, STRATEGY_LP,
+};
+
+/// Clock value the simulation starts at
+pub const GENESIS: i64 = 1_700_000_000;
+
+/// Address the simulated session is reported under in events
+pub const SESSION: Pubkey = Pubkey::new_from_array([0x5E; 32]);
+
+/// Size of the key pool ops pick devices from — larger than `MAX_DEVICES`
+/// so enrollment also exercises `DeviceListFull` and unknown-key paths.
+pub const KEY_POOL: usize = MAX_DEVICES + 2;
+
+/// `initialize_session` arguments, after config defaults are applied
+#[derive(Clone, Copy, Debug)]
+pub struct InitParams {
+    pub duration_secs: i64,
+    pub max_lamports: u64,
+    pub max_action_lamports: u64,
+    pub strategy_mask: u8,
+}
+
+impl InitParams {
+    /// A day-long, LP-only session with 1 SOL exposure and per-action caps
+    pub const LP: Self = Self {
+        duration_secs: 86_400,
+        max_lamports: 1_000_000_000,
+        max_action_lamports: 1_000_000_000,
+        strategy_mask: STRATEGY_LP,
+    };
+}
+
+/// One owner or device instruction; `device` indexes the key pool
+#[derive(Clone, Copy, Debug)]
+pub enum Op {
+    /// `add_device`
+    Enroll { device: usize },
+    /// `set_device_limits` — `expires_in` is relative to the current clock, 0 = no expiry
+    Extend { device: usize, expires_in: i64, max_lamports: u64 },
+    /// `set_fee_budget`
+    SetFeeBudget { lamports: u64 },
+    /// `disable_device_key`
+    Disable { device: usize },
+    /// `set_cosign_threshold`
+    SetCosignThreshold { lamports: u64 },
+    /// `set_violation_freeze`
+    SetViolationFreeze { threshold: u8 },
+    /// `execute_action` signed by `device`, and by the owner when `cosigned`
+    Execute { device: usize, action_type: u8, amount: u64, fee: u64, cosigned: bool },
+    /// `undelegate_session` — deactivates the session
+    Close,
+    /// Move the clock forward
+    Advance { secs: i64 },
+    /// Move the ER slot forward
+    AdvanceSlots { slots: u64 },
+    /// `delegate_session` — opens the first commit window
+    Delegate,
+    /// `commit_session`
+    Commit,
+    /// `set_commit_cadence`
+    SetCommitCadence { max_actions: u32, max_secs: i64 },
+    /// `set_max_idle_slots`
+    SetMaxIdleSlots { slots: u64 },
+    /// `set_budget_mint`, restating every cap in the new unit
+    SetBudgetMint { mint: Pubkey, caps: BudgetCaps },
+    /// `set_budget_price_pool` — the pool's pairing is checked from its
+    /// account on-chain; Pubkey::default() unpins `mint`
+    PinBudgetPricePool { mint: Pubkey, lb_pair: Pubkey },
+    /// `set_mark_to_market`
+    SetMarkToMarket { enabled: bool },
+    /// `revalue_exposure` signed by `device`, with the registry's positions
+    /// worth `value` against a total `cost_basis`
+    Revalue { device: usize, value: u64, cost_basis: u64 },
+}
+
+impl Op {
+    /// A fee-free LP rebalance of `amount` by the primary device, not co-signed
+    pub fn rebalance(amount: u64) -> Self {
+        Op::Execute { device: 0, action_type: ACTION_LP_REBALANCE, amount, fee: 0, cosigned: false }
+    }
+}
+
+/// Running totals of successful `execute_action` calls, tracked
+/// independently of the session so the two can be cross-checked.
+#[derive(Clone, Debug, Default)]
+pub struct Ledger {
+    pub actions: u64,
+    pub spent: u128,
+    pub fees: u128,
+    pub tx_fees: u128,
+    pub device_spent: [u128; MAX_DEVICES],
+    pub strategy_spent: [u128; STRATEGY_COUNT],
+}
+
+pub struct Sim {
+    pub session: AgentSession,
+    pub now: i64,
+    /// ER slot actions execute in
+    pub slot: u64,
+    pub keys: [Pubkey; KEY_POOL],
+    pub ledger: Ledger,
+}
+
+/// An all-zero session, as `init` allocates it before the handler runs
+fn zeroed_session() -> AgentSession {
+    let data = vec![0u8; AgentSession::LEN];
+    AgentSession::try_deserialize_unchecked(&mut data.as_slice()).expect("zeroed AgentSession")
+}
+
+impl Sim {
+    /// `initialize_session` at [`GENESIS`] with key pool slot 0 as the primary device
+    pub fn new(params: InitParams) -> Result<Self> {
+        let keys = std::array::from_fn(|i| Pubkey::new_from_array([i as u8 + 1; 32]));
+        let mut session = zeroed_session();
+        let now = GENESIS;
+
+        check_limits(params.duration_secs, params.max_lamports)?;
+        let terms = SessionTerms {
+            owner: Pubkey::new_from_array([0xAA; 32]),
+            session_key: keys[0],
+            attestation_hash: [0; 32],
+            duration_secs: params.duration_secs,
+            max_lamports: params.max_lamports,
+            max_action_lamports: params.max_action_lamports,
+            strategy_mask: params.strategy_mask,
+            referrer: Pubkey::default(),
+            fee_tier: FeeTier::default(),
+            bump: 255,
+        };
+        open_session(&mut session, &terms, now)?;
+
+        Ok(Self { session, now, slot: 0, keys, ledger: Ledger::default() })
+    }
+
+    /// `initialize_session` with [`InitParams::LP`]
+    pub fn lp() -> Self {
+        Self::new(InitParams::LP).expect("init")
+    }
+
+    /// `Sim::lp` delegated to the ER
+    pub fn delegated() -> Self {
+        let mut sim = Self::lp();
+        sim.step(Op::Delegate).expect("delegate");
+        sim
+    }
+
+    /// Apply `op`; on error the session is left exactly as it was.
+    pub fn step(&mut self, op: Op) -> Result<()> {
+        let snapshot = self.session.clone();
+        let result = self.apply(op);
+        if result.is_err() {
+            self.session = snapshot;
+        }
+        result
+    }
+
+    fn apply(&mut self, op: Op) -> Result<()> {
+        let now = self.now;
+        let session = &mut self.session;
+        match op {
+            Op::Enroll { device } => {
+                session.enroll_device(self.keys[device], [0; 32], now)?;
+            }
+            Op::Extend { device, expires_in, max_lamports } => {
+                let slot = session
+                    .device_index(&self.keys[device])
+                    .ok_or(AgentError::DeviceNotEnrolled)?;
+                let expires_at = if expires_in == 0 { 0 } else { now.saturating_add(expires_in) };
+                session.devices[slot].expires_at = expires_at;
+                session.devices[slot].max_lamports = max_lamports;
+            }
+            Op::SetFeeBudget { lamports } => {
+                session.fee_budget_lamports = lamports;
+            }
+            Op::Disable { device } => {
+                let slot = session
+                    .device_index(&self.keys[device])
+                    .ok_or(AgentError::DeviceNotEnrolled)?;
+                session.devices[slot].disabled = true;
+            }
+            Op::SetCosignThreshold { lamports } => {
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.cosign_above_lamports = lamports;
+            }
+            Op::SetViolationFreeze { threshold } => {
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.violation_threshold = threshold;
+                session.consecutive_violations = 0;
+                session.suspended = false;
+            }
+            Op::Execute { device, action_type, amount, fee, cosigned } => {
+                let clock = Clock { slot: self.slot, unix_timestamp: now, ..Clock::default() };
+                let cosigner = cosigned.then_some(session.owner);
+                let device_key = self.keys[device];
+                let admitted =
+                    admit(&SESSION, session, &device_key, action_type, amount, cosigner, &clock)?;
+                // Retired while idle, or a screened scope violation: skipped
+                let Some(slot) = admitted else {
+                    return Ok(());
+                };
+                // One signature: the device key paying for its own transaction
+                let tx_fee = LAMPORTS_PER_SIGNATURE;
+                apply_action(session, slot, action_type, amount, fee, tx_fee, &clock)?;
+
+                self.ledger.actions += 1;
+                self.ledger.spent += amount as u128;
+                self.ledger.fees += fee as u128;
+                self.ledger.tx_fees += LAMPORTS_PER_SIGNATURE as u128;
+                self.ledger.device_spent[slot] += amount as u128;
+                self.ledger.strategy_spent[action_type as usize] += amount as u128;
+            }
+            Op::Close => {
+                session.is_active = false;
+                session.delegation_status = DELEGATION_UNDELEGATED;
+            }
+            Op::Advance { secs } => {
+                self.now = now.checked_add(secs).ok_or(AgentError::Overflow)?;
+            }
+            Op::AdvanceSlots { slots } => {
+                self.slot = self.slot.checked_add(slots).ok_or(AgentError::Overflow)?;
+            }
+            Op::Delegate => {
+                require!(session.is_active, AgentError::SessionInactive);
+                require!(!session.is_expired(now), AgentError::SessionExpired);
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.observe_clock(now);
+                session.delegation_status = DELEGATION_DELEGATED;
+                session.mark_committed(now);
+                session.er_last_action_slot = 0;
+            }
+            Op::Commit => {
+                require!(session.is_delegated(), AgentError::SessionNotDelegated);
+                session.mark_committed(now);
+                session.checkpoint_count =
+                    session.checkpoint_count.checked_add(1).ok_or(AgentError::Overflow)?;
+            }
+            Op::SetCommitCadence { max_actions, max_secs } => {
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.max_uncommitted_actions = max_actions;
+                session.max_uncommitted_secs = max_secs;
+            }
+            Op::SetMaxIdleSlots { slots } => {
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.max_idle_slots = slots;
+            }
+            Op::SetBudgetMint { mint, caps } => {
+                // No Config is modelled, so its per-action ceiling isn't either
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.set_budget_mint(mint, &caps)?;
+            }
+            Op::PinBudgetPricePool { mint, lb_pair } => {
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.pin_budget_price_pool(mint, lb_pair)?;
+            }
+            Op::SetMarkToMarket { enabled } => {
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.mark_to_market = enabled;
+            }
+            Op::Revalue { device, value, cost_basis } => {
+                require!(!session.is_delegated(), AgentError::SessionDelegated);
+                session.validate_session(
+                    &self.keys[device],
+                    ActionKind::Housekeeping,
+                    TemporalSource::Device(now),
+                )?;
+                session.apply_revaluation(value, cost_basis, now);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A DLMM LbPair account as `AccountLoader` reads it, standing in for the
+/// price and valuation pools handlers take
+pub struct Pool {
+    pub key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+impl Pool {
+    pub fn new(token_x_mint: Pubkey, token_y_mint: Pubkey, bin_step: u16, active_id: i32) -> Self {
+        let mut pool = Self {
+            key: Pubkey::new_unique(),
+            owner: defi_agent::dlmm::ID,
+            lamports: 1,
+            data: vec![0; 8 + std::mem::size_of::<LbPair>()],
+        };
+        pool.data[..8].copy_from_slice(LbPair::DISCRIMINATOR);
+        {
+            let info = pool.info();
+            let loader = AccountLoader::<LbPair>::try_from(&info).unwrap();
+            let mut pair = loader.load_mut().unwrap();
+            pair.token_x_mint = token_x_mint;
+            pair.token_y_mint = token_y_mint;
+            pair.bin_step = bin_step;
+            pair.active_id = active_id;
+        }
+        pool
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            false,
+            true,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
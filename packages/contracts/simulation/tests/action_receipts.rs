@@ -0,0 +1,88 @@
+//! `ActionReceiptLog` ring buffer: nonces in order, hashes binding each
+//! action to its place in the log, slots recycled once the ring wraps — on
+//! the ER only after their receipts were committed.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::{ActionReceipt, ActionReceiptLog, ACTION_RECEIPTS};
+
+fn empty() -> ActionReceiptLog {
+    let receipt = ActionReceipt { nonce: 0, slot: 0, hash: [0; 32] };
+    ActionReceiptLog {
+        session: Pubkey::new_unique(),
+        count: 0,
+        committed: 0,
+        receipts: [receipt; ACTION_RECEIPTS],
+        bump: 0,
+        _padding: [0; 7],
+    }
+}
+
+#[test]
+fn receipts_are_numbered_in_execution_order() {
+    let mut log = empty();
+    let device = Pubkey::new_unique();
+
+    assert_eq!(log.record(&device, 0, 1_000, 0, 10, false).unwrap(), 1);
+    assert_eq!(log.record(&device, 1, 2_000, 0, 11, false).unwrap(), 2);
+
+    assert_eq!(log.count, 2);
+    assert_eq!(log.get(1).unwrap().slot, 10);
+    assert_eq!(log.get(2).unwrap().slot, 11);
+    assert!(log.get(0).is_none());
+    assert!(log.get(3).is_none());
+}
+
+#[test]
+fn the_hash_covers_the_action_and_its_nonce() {
+    let mut log = empty();
+    let device = Pubkey::new_unique();
+    log.record(&device, 0, 1_000, 0, 10, false).unwrap();
+    log.record(&device, 0, 1_000, 0, 10, false).unwrap();
+
+    let first = log.get(1).unwrap().hash;
+    assert_eq!(first, ActionReceipt::digest(&log.session, &device, 0, 1_000, 0, 1, 10));
+    // The same action replayed lands under a different nonce and hash
+    assert_ne!(first, log.get(2).unwrap().hash);
+    assert_ne!(first, ActionReceipt::digest(&log.session, &device, 0, 1_001, 0, 1, 10));
+}
+
+#[test]
+fn wrapping_the_ring_drops_the_oldest_receipt() {
+    let mut log = empty();
+    let device = Pubkey::new_unique();
+    for slot in 0..=ACTION_RECEIPTS as u64 {
+        log.record(&device, 0, 1_000, 0, slot, false).unwrap();
+    }
+
+    assert_eq!(log.count, ACTION_RECEIPTS as u64 + 1);
+    assert!(log.get(1).is_none());
+    assert_eq!(log.get(2).unwrap().slot, 1);
+    assert_eq!(log.get(ACTION_RECEIPTS as u64 + 1).unwrap().slot, ACTION_RECEIPTS as u64);
+}
+
+#[test]
+fn delegated_receipts_are_not_recycled_before_a_commit() {
+    let mut log = empty();
+    let device = Pubkey::new_unique();
+    for slot in 1..=ACTION_RECEIPTS as u64 {
+        log.record(&device, 0, 1_000, 0, slot, true).unwrap();
+    }
+
+    match log.record(&device, 0, 1_000, 0, 0, true) {
+        Err(Error::AnchorError(e)) => assert_eq!(
+            e.error_code_number,
+            ERROR_CODE_OFFSET + AgentError::CommitRequired as u32
+        ),
+        other => panic!("expected CommitRequired, got {other:?}"),
+    }
+    assert_eq!(log.get(1).unwrap().slot, 1);
+
+    // commit_session frees every slot committed so far
+    log.mark_committed();
+    log.record(&device, 0, 1_000, 0, 100, true).unwrap();
+    assert!(log.get(1).is_none());
+    assert_eq!(log.committed, ACTION_RECEIPTS as u64);
+}
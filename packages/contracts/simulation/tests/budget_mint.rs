@@ -0,0 +1,261 @@
+//! Stable-denominated budgets: with a `budget_mint` set, swaps and deposits
+//! are charged in that mint's units — a budget leg at its own amount,
+//! anything else through the price pool the owner pinned for its mint, in
+//! Q64.64 fixed point. Switching the unit restates every cap.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::{BudgetCaps, MinTradeAmount, NATIVE_MINT};
+use defi_agent::valuation::{
+    active_bin_price, bin_price_q64, budget_price, budget_value, deposit_budget_value,
+    swap_budget_value, value_at_q64, ONE_Q64,
+};
+use defi_agent_simulation::{Op, Pool, Sim};
+
+fn caps() -> BudgetCaps {
+    BudgetCaps {
+        max_lamports: 1_000_000_000,
+        max_action_lamports: 1_000_000_000,
+        ..BudgetCaps::default()
+    }
+}
+
+fn budget_sim(mint: Pubkey) -> Sim {
+    let mut sim = Sim::lp();
+    sim.step(Op::SetBudgetMint { mint, caps: caps() }).unwrap();
+    sim
+}
+
+fn assert_error<T: std::fmt::Debug>(result: Result<T>, error: AgentError) {
+    match result {
+        Err(Error::AnchorError(e)) => {
+            assert_eq!(e.error_code_number, ERROR_CODE_OFFSET + error as u32)
+        }
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+#[test]
+fn bin_prices_are_exact_fixed_point() {
+    assert_eq!(bin_price_q64(25, 0), ONE_Q64);
+    // Rounded down, then valued up: a round trip through one bin is exact
+    assert_eq!(value_at_q64(100, bin_price_q64(100, 1)), 101);
+    assert_eq!(value_at_q64(101, bin_price_q64(100, -1)), 100);
+
+    let price = bin_price_q64(10, 100) as f64 / ONE_Q64 as f64;
+    assert!((price / active_bin_price(10, 100) - 1.0).abs() < 1e-12);
+
+    // Out of range: saturates high, vanishes low
+    assert_eq!(bin_price_q64(400, i32::MAX), u128::MAX);
+    assert_eq!(bin_price_q64(400, i32::MIN), 0);
+}
+
+#[test]
+fn values_round_up_and_saturate() {
+    assert_eq!(value_at_q64(1_000, ONE_Q64), 1_000);
+    assert_eq!(value_at_q64(3, ONE_Q64 / 2), 2);
+    assert_eq!(value_at_q64(0, u128::MAX), 0);
+    assert_eq!(value_at_q64(2, u128::MAX), u64::MAX);
+    assert_eq!(value_at_q64(u64::MAX, ONE_Q64 * 2), u64::MAX);
+}
+
+#[test]
+fn without_a_budget_mint_the_input_is_charged() {
+    let sim = Sim::lp();
+    let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+    assert!(!sim.session.has_budget_mint());
+    assert_eq!(swap_budget_value(&sim.session, &sol, 1_000, &usdc, 150_000, None).unwrap(), 1_000);
+
+    let mut pool = Pool::new(sol, usdc, 10, 100);
+    let value = deposit_budget_value(&sim.session, &pool.info(), 1_000, 2_000, None).unwrap();
+    assert_eq!(value, 3_000);
+}
+
+#[test]
+fn budget_legs_count_at_their_executed_amount() {
+    let usdc = Pubkey::new_unique();
+    let sol = Pubkey::new_unique();
+    let sim = budget_sim(usdc);
+    let session = &sim.session;
+
+    // Out of the budget mint: the stable amount spent
+    assert_eq!(swap_budget_value(session, &usdc, 150_000, &sol, 1_000, None).unwrap(), 150_000);
+    // Into it: the stable amount received
+    assert_eq!(swap_budget_value(session, &sol, 1_000, &usdc, 149_000, None).unwrap(), 149_000);
+    // A round trip through the budget mint is charged at its input
+    assert_eq!(swap_budget_value(session, &usdc, 150_000, &usdc, 149_000, None).unwrap(), 150_000);
+}
+
+#[test]
+fn other_legs_are_priced_through_the_pinned_pool() {
+    let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut sim = budget_sim(usdc);
+    let mut pinned = Pool::new(sol, usdc, 10, 100); // ~1.1051 USDC units per SOL unit
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: pinned.key }).unwrap();
+    let session = &sim.session;
+
+    assert_eq!(budget_price(session, &usdc, None).unwrap(), ONE_Q64);
+    let info = pinned.info();
+    assert_eq!(budget_price(session, &sol, Some(&info)).unwrap(), bin_price_q64(10, 100));
+    assert_eq!(budget_value(session, &sol, 1_000_000, Some(&info)).unwrap(), 1_105_116);
+    let charged = swap_budget_value(session, &sol, 1_000_000, &bonk, 5_000, Some(&info));
+    assert_eq!(charged.unwrap(), 1_105_116);
+}
+
+#[test]
+fn a_pinned_pool_prices_either_way_round() {
+    let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut sim = budget_sim(usdc);
+    let mut pinned = Pool::new(usdc, sol, 10, -100); // SOL is Y: the inverse price
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: pinned.key }).unwrap();
+
+    let price = budget_price(&sim.session, &sol, Some(&pinned.info())).unwrap();
+    assert_eq!(price, bin_price_q64(10, 100));
+}
+
+#[test]
+fn only_the_pinned_pool_is_accepted() {
+    let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut sim = budget_sim(usdc);
+    // A thin pool the device picked, pairing the right mints
+    let mut thin = Pool::new(sol, usdc, 100, 5_000);
+
+    let missing = swap_budget_value(&sim.session, &sol, 1_000, &bonk, 5_000, None);
+    assert_error(missing, AgentError::BudgetPriceRequired);
+    let unpinned = swap_budget_value(&sim.session, &sol, 1_000, &bonk, 5_000, Some(&thin.info()));
+    assert_error(unpinned, AgentError::BudgetPricePoolNotPinned);
+
+    let mut pinned = Pool::new(sol, usdc, 10, 100);
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: pinned.key }).unwrap();
+    let unpinned = budget_price(&sim.session, &sol, Some(&thin.info()));
+    assert_error(unpinned, AgentError::BudgetPricePoolNotPinned);
+    budget_price(&sim.session, &sol, Some(&pinned.info())).unwrap();
+
+    // Pinned for one mint, it doesn't price another
+    let other_mint = budget_price(&sim.session, &bonk, Some(&pinned.info()));
+    assert_error(other_mint, AgentError::BudgetPricePoolNotPinned);
+
+    // Unpinning refuses the pool again
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: Pubkey::default() }).unwrap();
+    let unpinned = budget_price(&sim.session, &sol, Some(&pinned.info()));
+    assert_error(unpinned, AgentError::BudgetPricePoolNotPinned);
+}
+
+#[test]
+fn a_pin_must_pair_its_mint_with_the_budget_mint() {
+    let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut sim = budget_sim(usdc);
+    // set_budget_price_pool checks the pairing; a pin that slipped past it
+    // still can't price the mint
+    let mut wrong = Pool::new(bonk, usdc, 10, 100);
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: wrong.key }).unwrap();
+    let price = budget_price(&sim.session, &sol, Some(&wrong.info()));
+    assert_error(price, AgentError::InvalidBudgetPricePool);
+
+    // The budget mint itself needs no pool, and raw amounts no pins
+    let budget = sim.step(Op::PinBudgetPricePool { mint: usdc, lb_pair: wrong.key });
+    assert_error(budget, AgentError::InvalidBudgetPricePool);
+    let raw = Sim::lp().step(Op::PinBudgetPricePool { mint: sol, lb_pair: wrong.key });
+    assert_error(raw, AgentError::InvalidBudgetPricePool);
+}
+
+#[test]
+fn pins_are_one_per_mint_and_bounded() {
+    let usdc = Pubkey::new_unique();
+    let mut sim = budget_sim(usdc);
+    let mints: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    for mint in &mints[..4] {
+        sim.step(Op::PinBudgetPricePool { mint: *mint, lb_pair: Pubkey::new_unique() }).unwrap();
+    }
+    let full = sim.step(Op::PinBudgetPricePool { mint: mints[4], lb_pair: Pubkey::new_unique() });
+    assert_error(full, AgentError::BudgetPricePoolListFull);
+
+    // Re-pinning a mint replaces its pool in place
+    let replacement = Pubkey::new_unique();
+    sim.step(Op::PinBudgetPricePool { mint: mints[0], lb_pair: replacement }).unwrap();
+    assert_eq!(sim.session.budget_price_pool(&mints[0]), Some(replacement));
+}
+
+#[test]
+fn deposits_are_valued_through_the_pool_then_the_pin() {
+    let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut sim = budget_sim(usdc);
+
+    // The pool trades against the budget mint: it prices the other token
+    let mut sol_usdc = Pool::new(sol, usdc, 10, 100);
+    let value = deposit_budget_value(&sim.session, &sol_usdc.info(), 1_000_000, 7, None).unwrap();
+    assert_eq!(value, 1_105_116 + 7);
+    let mut usdc_sol = Pool::new(usdc, sol, 10, -100);
+    let value = deposit_budget_value(&sim.session, &usdc_sol.info(), 7, 1_000_000, None).unwrap();
+    assert_eq!(value, 7 + 1_105_116);
+
+    // Otherwise in Y at the pool's price, then through the pin for Y
+    let mut bonk_sol = Pool::new(bonk, sol, 20, -50);
+    let price_pool = sol_usdc.info();
+    let unpinned =
+        deposit_budget_value(&sim.session, &bonk_sol.info(), 4_000, 1_000, Some(&price_pool));
+    assert_error(unpinned, AgentError::BudgetPricePoolNotPinned);
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: *price_pool.key }).unwrap();
+    let value_y = 1_000 + value_at_q64(4_000, bin_price_q64(20, -50));
+    let expected = value_at_q64(value_y, bin_price_q64(10, 100));
+    let value =
+        deposit_budget_value(&sim.session, &bonk_sol.info(), 4_000, 1_000, Some(&price_pool));
+    assert_eq!(value.unwrap(), expected);
+}
+
+#[test]
+fn switching_the_unit_restates_every_cap() {
+    let usdc = Pubkey::new_unique();
+    let mut sim = Sim::lp();
+    sim.step(Op::Enroll { device: 1 }).unwrap();
+    let mut caps = BudgetCaps {
+        max_lamports: 500_000_000,
+        max_action_lamports: 50_000_000,
+        cosign_above_lamports: 10_000_000,
+        device_max_lamports: [0, 100_000_000, 0, 0],
+    };
+    sim.step(Op::SetBudgetMint { mint: usdc, caps }).unwrap();
+    assert_eq!(sim.session.budget_mint, usdc);
+    assert_eq!(sim.session.max_lamports, 500_000_000);
+    assert_eq!(sim.session.max_action_lamports, 50_000_000);
+    assert_eq!(sim.session.cosign_above_lamports, 10_000_000);
+    assert_eq!(sim.session.devices[1].max_lamports, 100_000_000);
+
+    // Every cap must be stated: none zeroed, no cap on an empty device slot
+    caps.max_action_lamports = 0;
+    assert_error(sim.step(Op::SetBudgetMint { mint: usdc, caps }), AgentError::InvalidBudgetCaps);
+    caps.max_action_lamports = 50_000_000;
+    caps.device_max_lamports = [0, 100_000_000, 0, 1];
+    assert_error(sim.step(Op::SetBudgetMint { mint: usdc, caps }), AgentError::InvalidBudgetCaps);
+    caps.device_max_lamports = [0; 4];
+    caps.max_lamports = 0;
+    assert_error(sim.step(Op::SetBudgetMint { mint: usdc, caps }), AgentError::ZeroExposureCap);
+}
+
+#[test]
+fn switching_the_unit_clears_pins_and_locks_once_spent() {
+    let (usdc, usdt, sol) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut sim = budget_sim(usdc);
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: Pubkey::new_unique() }).unwrap();
+    sim.step(Op::SetBudgetMint { mint: usdt, caps: caps() }).unwrap();
+    assert_eq!(sim.session.budget_price_pool(&sol), None);
+
+    sim.step(Op::rebalance(1_000)).unwrap();
+    let locked = sim.step(Op::SetBudgetMint { mint: usdc, caps: caps() });
+    assert_error(locked, AgentError::BudgetMintLocked);
+    assert_eq!(sim.session.budget_mint, usdt);
+}
+
+#[test]
+fn action_notionals_meet_the_budget_mints_minimum() {
+    let usdc = Pubkey::new_unique();
+    let mut sim = budget_sim(usdc);
+    sim.session.min_trade_amounts[0] = MinTradeAmount { mint: NATIVE_MINT, amount: 1_000_000 };
+    sim.session.min_trade_amounts[1] = MinTradeAmount { mint: usdc, amount: 10_000 };
+
+    assert_error(sim.step(Op::rebalance(5_000)), AgentError::BelowMinTradeAmount);
+    // The wrapped SOL minimum no longer applies to budget-unit notionals
+    sim.step(Op::rebalance(20_000)).unwrap();
+}
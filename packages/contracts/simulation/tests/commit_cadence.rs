@@ -0,0 +1,89 @@
+//! Commit cadence: while delegated, actions stop once too many have run, or
+//! too long has passed, since the last commit; a commit reopens the window.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent_simulation::{Op, Sim};
+
+fn cadence_sim(max_actions: u32, max_secs: i64) -> Sim {
+    let mut sim = Sim::lp();
+    sim.step(Op::SetCommitCadence { max_actions, max_secs }).unwrap();
+    sim
+}
+
+fn delegated_sim(max_actions: u32, max_secs: i64) -> Sim {
+    let mut sim = cadence_sim(max_actions, max_secs);
+    sim.step(Op::Delegate).unwrap();
+    sim
+}
+
+fn execute(sim: &mut Sim) -> Result<()> {
+    sim.step(Op::rebalance(1_000))
+}
+
+fn assert_commit_required(result: Result<()>) {
+    match result {
+        Err(Error::AnchorError(e)) => {
+            assert_eq!(e.error_code_number, ERROR_CODE_OFFSET + AgentError::CommitRequired as u32)
+        }
+        other => panic!("expected CommitRequired, got {other:?}"),
+    }
+}
+
+#[test]
+fn actions_stop_after_the_uncommitted_limit_until_a_commit() {
+    let mut sim = delegated_sim(2, 0);
+    execute(&mut sim).unwrap();
+    execute(&mut sim).unwrap();
+    assert_eq!(sim.session.uncommitted_actions, 2);
+    assert_commit_required(execute(&mut sim));
+    assert_eq!(sim.session.total_actions, 2);
+
+    sim.step(Op::Commit).unwrap();
+    execute(&mut sim).unwrap();
+    assert_eq!(sim.session.uncommitted_actions, 1);
+}
+
+#[test]
+fn actions_stop_once_the_window_runs_out() {
+    let mut sim = delegated_sim(0, 60);
+    sim.step(Op::Advance { secs: 59 }).unwrap();
+    execute(&mut sim).unwrap();
+    sim.step(Op::Advance { secs: 1 }).unwrap();
+    assert_commit_required(execute(&mut sim));
+
+    sim.step(Op::Commit).unwrap();
+    execute(&mut sim).unwrap();
+}
+
+#[test]
+fn base_layer_sessions_are_not_held_back() {
+    let mut sim = cadence_sim(1, 60);
+    sim.step(Op::Advance { secs: 120 }).unwrap();
+    execute(&mut sim).unwrap();
+    execute(&mut sim).unwrap();
+    assert_eq!(sim.session.uncommitted_actions, 0);
+}
+
+#[test]
+fn the_cadence_is_set_before_delegating() {
+    let mut sim = delegated_sim(0, 0);
+    match sim.step(Op::SetCommitCadence { max_actions: 1, max_secs: 0 }) {
+        Err(Error::AnchorError(e)) => assert_eq!(
+            e.error_code_number,
+            ERROR_CODE_OFFSET + AgentError::SessionDelegated as u32
+        ),
+        other => panic!("expected SessionDelegated, got {other:?}"),
+    }
+    assert_eq!(sim.session.max_uncommitted_actions, 0);
+}
+
+#[test]
+fn a_batch_must_fit_in_the_window() {
+    let mut sim = delegated_sim(3, 0);
+    execute(&mut sim).unwrap();
+    assert_commit_required(sim.session.check_commit_cadence_for(sim.now, 3));
+    sim.session.check_commit_cadence_for(sim.now, 2).unwrap();
+}
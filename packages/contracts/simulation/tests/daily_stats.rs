@@ -0,0 +1,90 @@
+//! `DailyStats` ring buffer: per-UTC-day buckets, recycled once the ring wraps.
+
+use anchor_lang::prelude::*;
+
+use defi_agent::state::{DailyBucket, DailyStats, LpPositionMonitor, DAILY_STATS_DAYS, SECS_PER_DAY};
+use defi_agent_simulation::GENESIS;
+
+fn empty() -> DailyStats {
+    let bucket = DailyBucket {
+        day: 0,
+        actions: 0,
+        volume: 0,
+        fees_paid: 0,
+        fees_earned_x: 0,
+        fees_earned_y: 0,
+    };
+    DailyStats {
+        session: Pubkey::new_unique(),
+        days: [bucket; DAILY_STATS_DAYS],
+        bump: 0,
+        _padding: [0; 7],
+    }
+}
+
+#[test]
+fn actions_on_the_same_day_share_a_bucket() {
+    let mut stats = empty();
+    let day = DailyStats::day_of(GENESIS);
+    let start_of_day = day * SECS_PER_DAY;
+
+    stats.record_action(start_of_day, 100, 5);
+    stats.record_action(start_of_day + SECS_PER_DAY - 1, 50, 0);
+
+    let bucket = stats.get(day).expect("today");
+    assert_eq!((bucket.actions, bucket.volume, bucket.fees_paid), (2, 150, 5));
+    assert!(stats.get(day + 1).is_none());
+}
+
+#[test]
+fn a_new_day_starts_a_fresh_bucket() {
+    let mut stats = empty();
+    let day = DailyStats::day_of(GENESIS);
+    stats.record_action(GENESIS, 100, 5);
+    stats.record_action(GENESIS + SECS_PER_DAY, 7, 1);
+
+    assert_eq!(stats.get(day).unwrap().volume, 100);
+    assert_eq!(stats.get(day + 1).unwrap().volume, 7);
+}
+
+#[test]
+fn wrapping_the_ring_recycles_the_oldest_day() {
+    let mut stats = empty();
+    let day = DailyStats::day_of(GENESIS);
+    stats.record_action(GENESIS, 100, 5);
+
+    let later = GENESIS + DAILY_STATS_DAYS as i64 * SECS_PER_DAY;
+    stats.record_action(later, 1, 0);
+
+    assert!(stats.get(day).is_none());
+    let bucket = stats.get(day + DAILY_STATS_DAYS as i64).unwrap();
+    assert_eq!((bucket.actions, bucket.volume, bucket.fees_paid), (1, 1, 0));
+}
+
+#[test]
+fn fees_earned_land_in_todays_bucket() {
+    let mut stats = empty();
+    let monitor = LpPositionMonitor {
+        session: Pubkey::default(),
+        lb_pair: Pubkey::default(),
+        position: Pubkey::default(),
+        min_bin_id: 0,
+        max_bin_id: 0,
+        last_active_bin: 0,
+        is_in_range: 1,
+        bump: 0,
+        _padding: [0; 2],
+        fee_x_snapshot: 120,
+        fee_y_snapshot: 7,
+        last_checked_at: 0,
+        amount_x_snapshot: 0,
+        amount_y_snapshot: 0,
+        out_of_range_since: 0,
+    };
+    stats.record_fees_earned(GENESIS, &monitor);
+    stats.record_fees_earned(GENESIS, &monitor);
+
+    let bucket = stats.get(DailyStats::day_of(GENESIS)).unwrap();
+    assert_eq!((bucket.fees_earned_x, bucket.fees_earned_y), (240, 14));
+    assert_eq!(bucket.actions, 0);
+}
@@ -0,0 +1,66 @@
+//! Slot-based liveness: a delegated session counts ER slots since its last
+//! action, and is idle once more than `max_idle_slots` have passed; the next
+//! action then deactivates it instead of running.
+
+use defi_agent_simulation::{Op, Sim};
+
+fn idle_sim(max_idle_slots: u64) -> Sim {
+    let mut sim = Sim::lp();
+    sim.step(Op::SetMaxIdleSlots { slots: max_idle_slots }).unwrap();
+    sim
+}
+
+fn delegated_sim(max_idle_slots: u64) -> Sim {
+    let mut sim = idle_sim(max_idle_slots);
+    sim.step(Op::Delegate).unwrap();
+    sim
+}
+
+/// Advance `slots` ER slots, then act
+fn execute_after(sim: &mut Sim, slots: u64) {
+    sim.step(Op::AdvanceSlots { slots }).unwrap();
+    sim.step(Op::rebalance(1_000)).unwrap();
+}
+
+#[test]
+fn idle_after_the_window_since_the_last_action() {
+    let mut sim = delegated_sim(100);
+    execute_after(&mut sim, 1_000);
+    assert_eq!(sim.session.er_last_action_slot, 1_000);
+    assert!(!sim.session.is_idle(1_100));
+    assert!(sim.session.is_idle(1_101));
+
+    execute_after(&mut sim, 50);
+    assert!(!sim.session.is_idle(1_101));
+}
+
+#[test]
+fn the_window_opens_at_the_first_action() {
+    let mut sim = delegated_sim(100);
+    assert!(!sim.session.is_idle(u64::MAX));
+    execute_after(&mut sim, 1_000_000);
+    assert!(sim.session.is_active);
+    assert_eq!(sim.session.total_actions, 1);
+}
+
+#[test]
+fn an_idle_session_is_retired_instead_of_acting() {
+    let mut sim = delegated_sim(100);
+    execute_after(&mut sim, 1_000);
+    execute_after(&mut sim, 101);
+    assert!(!sim.session.is_active);
+    assert_eq!(sim.session.total_actions, 1);
+}
+
+#[test]
+fn off_or_on_the_base_layer_never_idles() {
+    let mut sim = delegated_sim(0);
+    execute_after(&mut sim, 1_000);
+    assert!(!sim.session.is_idle(1_000_000));
+
+    let mut sim = idle_sim(100);
+    execute_after(&mut sim, 1_000);
+    execute_after(&mut sim, 5_000);
+    assert_eq!(sim.session.er_last_action_slot, 0);
+    assert!(sim.session.is_active);
+}
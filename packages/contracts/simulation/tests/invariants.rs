@@ -0,0 +1,199 @@
+//! Random op sequences against the session accounting invariants:
+//! caps are never exceeded, counters never overflow, expiry is always enforced.
+//!
+//! Cases are deterministic per seed; reproduce a failure with the seed
+//! proptest prints (or the `proptest-regressions/` file it writes).
+
+use anchor_lang::prelude::*;
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use proptest::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::{AgentSession, MAX_DEVICES, STRATEGY_ALL, STRATEGY_COUNT};
+use defi_agent_simulation::{InitParams, Op, Sim, KEY_POOL};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+fn is_agent_error(result: &Result<()>, expected: AgentError) -> bool {
+    match result {
+        Err(Error::AnchorError(e)) => e.error_code_number == ERROR_CODE_OFFSET + expected as u32,
+        _ => false,
+    }
+}
+
+fn bytes(session: &AgentSession) -> Vec<u8> {
+    borsh::to_vec(session).expect("serialize")
+}
+
+// ── Strategies ──────────────────────────────────────────────────────────────
+
+/// Mostly realistic lamport amounts, with some right at the u64 edge
+fn lamports() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        8 => 0..10 * LAMPORTS_PER_SOL,
+        1 => Just(0u64),
+        1 => (u64::MAX - 10 * LAMPORTS_PER_SOL)..=u64::MAX,
+    ]
+}
+
+fn init_params() -> impl Strategy<Value = InitParams> {
+    // A zero exposure cap is rejected by `initialize_session`
+    let cap = lamports().prop_map(|l| l.max(1));
+    (1..30 * 86_400i64, cap, lamports(), 0..=STRATEGY_ALL).prop_map(
+        |(duration_secs, max_lamports, max_action_lamports, strategy_mask)| InitParams {
+            duration_secs,
+            max_lamports,
+            max_action_lamports,
+            strategy_mask,
+        },
+    )
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let device = 0..KEY_POOL;
+    prop_oneof![
+        1 => device.clone().prop_map(|device| Op::Enroll { device }),
+        1 => (device.clone(), prop_oneof![Just(0i64), 1..7 * 86_400i64], lamports())
+            .prop_map(|(device, expires_in, max_lamports)| Op::Extend { device, expires_in, max_lamports }),
+        1 => lamports().prop_map(|lamports| Op::SetFeeBudget { lamports }),
+        1 => device.clone().prop_map(|device| Op::Disable { device }),
+        8 => (device, 0..3u8, lamports(), prop_oneof![Just(0u64), 0..LAMPORTS_PER_SOL], any::<bool>())
+            .prop_map(|(device, action_type, amount, fee, cosigned)| {
+                Op::Execute { device, action_type, amount, fee, cosigned }
+            }),
+        1 => Just(Op::Close),
+        2 => (1..2 * 86_400i64).prop_map(|secs| Op::Advance { secs }),
+    ]
+}
+
+// ── Properties ──────────────────────────────────────────────────────────────
+
+proptest! {
+    #![proptest_config(ProptestConfig { cases: 512, ..ProptestConfig::default() })]
+
+    /// After every step the session's counters match an independent ledger of
+    /// successful actions, and every cap holds.
+    #[test]
+    fn accounting_invariants(params in init_params(), ops in prop::collection::vec(op(), 1..64)) {
+        let mut sim = Sim::new(params).unwrap();
+
+        for op in ops {
+            let before = sim.session.clone();
+            let now = sim.now;
+            let result = sim.step(op);
+            let s = &sim.session;
+
+            // A rejected instruction changes nothing
+            if result.is_err() {
+                prop_assert_eq!(bytes(&before), bytes(s), "{:?} failed but mutated the session", op);
+                continue;
+            }
+
+            // Counters agree with the ledger, so none wrapped
+            prop_assert_eq!(s.total_actions, sim.ledger.actions);
+            prop_assert_eq!(s.spent_lamports as u128, sim.ledger.spent);
+            prop_assert_eq!(s.fee_spent_lamports as u128, sim.ledger.fees);
+            prop_assert_eq!(s.tx_fees_lamports as u128, sim.ledger.tx_fees);
+            for slot in 0..MAX_DEVICES {
+                prop_assert_eq!(s.devices[slot].spent_lamports as u128, sim.ledger.device_spent[slot]);
+            }
+            for strategy in 0..STRATEGY_COUNT {
+                prop_assert_eq!(
+                    s.spent_by_strategy[strategy] as u128,
+                    sim.ledger.strategy_spent[strategy]
+                );
+            }
+            let attributed: u128 = s.spent_by_strategy.iter().map(|&x| x as u128).sum();
+            prop_assert_eq!(attributed, sim.ledger.spent);
+
+            // Session cap is unconditional
+            prop_assert!(s.spent_lamports <= s.max_lamports);
+
+            if let Op::Execute { device, amount, .. } = op {
+                // Only a live, enrolled, enabled, unexpired device on an active,
+                // unexpired session may act
+                prop_assert!(before.is_active);
+                prop_assert!(now < before.expires_at);
+                let slot = before.device_index(&sim.keys[device]).expect("enrolled");
+                let d = &s.devices[slot];
+                prop_assert!(!d.disabled);
+                prop_assert!(!d.is_expired(now));
+
+                prop_assert!(amount <= s.max_action_lamports);
+                prop_assert!(d.max_lamports == 0 || d.spent_lamports <= d.max_lamports);
+                prop_assert!(
+                    s.fee_budget_lamports == 0 || s.fee_spent_lamports <= s.fee_budget_lamports
+                );
+            }
+        }
+    }
+
+    /// Once the clock passes `expires_at`, no action executes — whatever the
+    /// owner does to device limits or budgets beforehand.
+    #[test]
+    fn expiry_always_enforced(
+        params in init_params(),
+        ops in prop::collection::vec(op(), 0..32),
+        late_by in 0..86_400i64,
+        attempts in prop::collection::vec(op(), 1..16),
+    ) {
+        let mut sim = Sim::new(params).unwrap();
+        for op in ops {
+            let _ = sim.step(op);
+        }
+
+        let expires_at = sim.session.expires_at;
+        sim.now = sim.now.max(expires_at) + late_by;
+
+        for op in attempts {
+            let executed = matches!(op, Op::Execute { .. });
+            let result = sim.step(op);
+            if executed {
+                prop_assert!(
+                    is_agent_error(&result, AgentError::SessionExpired)
+                        || is_agent_error(&result, AgentError::SessionInactive),
+                    "{:?} after expiry returned {:?}", op, result
+                );
+            }
+        }
+    }
+
+    /// Counters at the edge of u64 reject the action with `Overflow` (or a cap
+    /// error) instead of wrapping, and leave the session untouched.
+    #[test]
+    fn counters_never_wrap(
+        spent in (u64::MAX - LAMPORTS_PER_SOL)..=u64::MAX,
+        fee_spent in (u64::MAX - LAMPORTS_PER_SOL)..=u64::MAX,
+        total_actions in (u64::MAX - 4)..=u64::MAX,
+        amount in lamports(),
+        fee in 0..LAMPORTS_PER_SOL,
+    ) {
+        let mut sim = Sim::new(InitParams {
+            duration_secs: 86_400,
+            max_lamports: u64::MAX,
+            max_action_lamports: u64::MAX,
+            strategy_mask: STRATEGY_ALL,
+        })
+        .unwrap();
+        sim.session.spent_lamports = spent;
+        sim.session.devices[0].spent_lamports = spent;
+        sim.session.spent_by_strategy[0] = spent;
+        sim.session.fee_spent_lamports = fee_spent;
+        sim.session.fee_budget_lamports = u64::MAX;
+        sim.session.total_actions = total_actions;
+
+        let before = bytes(&sim.session);
+        let result = sim.step(Op::Execute { device: 0, action_type: 0, amount, fee, cosigned: false });
+
+        let fits = spent.checked_add(amount).is_some()
+            && fee_spent.checked_add(fee).is_some()
+            && total_actions.checked_add(1).is_some();
+        if fits {
+            prop_assert!(result.is_ok(), "{:?}", result);
+            prop_assert_eq!(sim.session.total_actions, total_actions + 1);
+        } else {
+            prop_assert!(result.is_err());
+            prop_assert_eq!(before, bytes(&sim.session));
+        }
+    }
+}
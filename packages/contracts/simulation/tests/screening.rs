@@ -0,0 +1,77 @@
+//! `execute_action`'s co-sign threshold and `freeze::screen`, run through the
+//! program's own `admit` core: with the violation freeze on, an enrolled
+//! device's scope violation is recorded and the action skipped, and enough
+//! of them in a row suspend the session.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::ACTION_LP_REBALANCE;
+use defi_agent_simulation::{InitParams, Op, Sim};
+
+/// `Sim::lp` with a 1_000 lamport per-action cap
+fn capped_sim() -> Sim {
+    Sim::new(InitParams { max_action_lamports: 1_000, ..InitParams::LP }).expect("init")
+}
+
+fn assert_error(result: Result<()>, error: AgentError) {
+    match result {
+        Err(Error::AnchorError(e)) => {
+            assert_eq!(e.error_code_number, ERROR_CODE_OFFSET + error as u32)
+        }
+        other => panic!("expected {error:?}, got {other:?}"),
+    }
+}
+
+fn cosigned(amount: u64) -> Op {
+    Op::Execute { device: 0, action_type: ACTION_LP_REBALANCE, amount, fee: 0, cosigned: true }
+}
+
+#[test]
+fn actions_above_the_threshold_need_the_owner() {
+    let mut sim = Sim::lp();
+    sim.step(Op::SetCosignThreshold { lamports: 500 }).unwrap();
+    sim.step(Op::rebalance(500)).unwrap();
+    assert_error(sim.step(Op::rebalance(501)), AgentError::OwnerCosignRequired);
+    sim.step(cosigned(501)).unwrap();
+    assert_eq!(sim.session.spent_lamports, 1_001);
+}
+
+#[test]
+fn without_the_freeze_violations_fail() {
+    let mut sim = capped_sim();
+    assert_error(sim.step(Op::rebalance(1_001)), AgentError::ActionLimitExceeded);
+    assert_eq!(sim.session.consecutive_violations, 0);
+}
+
+#[test]
+fn screened_violations_are_recorded_then_suspend() {
+    let mut sim = capped_sim();
+    sim.step(Op::SetViolationFreeze { threshold: 2 }).unwrap();
+
+    sim.step(Op::rebalance(1_001)).unwrap();
+    assert_eq!(sim.session.consecutive_violations, 1);
+    assert_eq!(sim.session.spent_lamports, 0, "a screened action is skipped");
+    assert!(!sim.session.suspended);
+
+    sim.step(Op::rebalance(1_001)).unwrap();
+    assert!(sim.session.suspended);
+    assert_error(sim.step(Op::rebalance(1)), AgentError::SessionSuspended);
+}
+
+#[test]
+fn unenrolled_signers_are_not_screened() {
+    let mut sim = capped_sim();
+    sim.step(Op::SetViolationFreeze { threshold: 1 }).unwrap();
+    let stranger = Op::Execute {
+        device: 3,
+        action_type: ACTION_LP_REBALANCE,
+        amount: 1_001,
+        fee: 0,
+        cosigned: false,
+    };
+    assert_error(sim.step(stranger), AgentError::UnauthorizedSessionKey);
+    assert_eq!(sim.session.consecutive_violations, 0);
+    assert!(!sim.session.suspended);
+}
@@ -0,0 +1,72 @@
+//! `SessionLookup::set`: an entry naming another session is only repointed
+//! once that session no longer lists the key.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::SessionLookup;
+use defi_agent_simulation::{Op, Sim};
+
+fn lookup(device_key: Pubkey, session: Pubkey) -> SessionLookup {
+    SessionLookup { device_key, session, bump: 255 }
+}
+
+/// `set` with the serialized `previous` session passed as its account
+fn repoint(entry: &mut SessionLookup, device: Pubkey, key: Pubkey, previous: &Sim) -> Result<()> {
+    let mut data = Vec::new();
+    previous.session.try_serialize(&mut data)?;
+    let mut lamports = 1;
+    let owner = defi_agent::ID;
+    let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+    entry.set(device, Pubkey::new_unique(), 254, Some(&info))
+}
+
+fn assert_enrolled_elsewhere(result: Result<()>) {
+    match result {
+        Err(Error::AnchorError(e)) => assert_eq!(
+            e.error_code_number,
+            ERROR_CODE_OFFSET + AgentError::DeviceEnrolledElsewhere as u32
+        ),
+        other => panic!("expected DeviceEnrolledElsewhere, got {other:?}"),
+    }
+}
+
+#[test]
+fn fresh_and_same_session_entries_are_written() {
+    let (device, session) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut entry = lookup(Pubkey::default(), Pubkey::default());
+    entry.set(device, session, 255, None).unwrap();
+    assert_eq!(entry.session, session);
+    entry.set(device, session, 255, None).unwrap();
+}
+
+#[test]
+fn a_key_another_session_lists_is_not_repointed() {
+    let mut sim = Sim::lp();
+    sim.step(Op::Enroll { device: 1 }).unwrap();
+    let (device, previous) = (sim.keys[1], Pubkey::new_unique());
+    let mut entry = lookup(device, previous);
+
+    // Without the named session, or with a different one
+    assert_enrolled_elsewhere(entry.set(device, Pubkey::new_unique(), 254, None));
+    assert_enrolled_elsewhere(repoint(&mut entry, device, Pubkey::new_unique(), &sim));
+    // With it, while it still lists the key
+    assert_enrolled_elsewhere(repoint(&mut entry, device, previous, &sim));
+    assert_eq!(entry.session, previous);
+
+    // Once removed there, the key can move
+    sim.session.remove_device(&device).unwrap();
+    repoint(&mut entry, device, previous, &sim).unwrap();
+    assert_ne!(entry.session, previous);
+}
+
+#[test]
+fn a_closed_session_releases_its_keys() {
+    let (device, previous) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut entry = lookup(device, previous);
+    let (mut lamports, mut data) = (0, Vec::new());
+    let system = Pubkey::default();
+    let info = AccountInfo::new(&previous, false, false, &mut lamports, &mut data, &system, false, 0);
+    entry.set(device, Pubkey::new_unique(), 254, Some(&info)).unwrap();
+}
@@ -0,0 +1,93 @@
+//! `undelegate_all`'s checks: only the owner or an enrolled device may tear a
+//! session down, and only distinct, writable, program-owned accounts of that
+//! session are accepted as remaining accounts.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::instructions::undelegate_all::check_session_accounts;
+use defi_agent_simulation::Sim;
+
+/// Discriminator placeholder, then the account's `session` field
+fn session_scoped(session: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; 8];
+    data.extend_from_slice(session.as_ref());
+    data.extend_from_slice(&[0; 16]);
+    data
+}
+
+fn check(session: &Pubkey, accounts: &mut [(Pubkey, Pubkey, bool, u64, Vec<u8>)]) -> Result<()> {
+    let infos: Vec<AccountInfo> = accounts
+        .iter_mut()
+        .map(|(key, owner, writable, lamports, data)| {
+            AccountInfo::new(
+                &*key,
+                false,
+                *writable,
+                lamports,
+                data.as_mut_slice(),
+                &*owner,
+                false,
+                0,
+            )
+        })
+        .collect();
+    check_session_accounts(session, &infos)
+}
+
+fn assert_rejected(result: Result<()>) {
+    match result {
+        Err(Error::AnchorError(e)) => assert_eq!(
+            e.error_code_number,
+            ERROR_CODE_OFFSET + AgentError::InvalidSessionAccount as u32
+        ),
+        other => panic!("expected InvalidSessionAccount, got {other:?}"),
+    }
+}
+
+#[test]
+fn accepts_the_sessions_own_accounts() {
+    let session = Pubkey::new_unique();
+    let mut accounts = [
+        (Pubkey::new_unique(), defi_agent::ID, true, 1, session_scoped(&session)),
+        (Pubkey::new_unique(), defi_agent::ID, true, 1, session_scoped(&session)),
+    ];
+    check(&session, &mut accounts).unwrap();
+    check(&session, &mut []).unwrap();
+}
+
+#[test]
+fn rejects_another_sessions_account() {
+    let session = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let mut accounts = [(Pubkey::new_unique(), defi_agent::ID, true, 1, session_scoped(&other))];
+    assert_rejected(check(&session, &mut accounts));
+}
+
+#[test]
+fn rejects_foreign_readonly_short_or_repeated_accounts() {
+    let session = Pubkey::new_unique();
+    let foreign = [(Pubkey::new_unique(), Pubkey::new_unique(), true, 1, session_scoped(&session))];
+    let readonly = [(Pubkey::new_unique(), defi_agent::ID, false, 1, session_scoped(&session))];
+    let short = [(Pubkey::new_unique(), defi_agent::ID, true, 1, vec![0u8; 8])];
+    for mut accounts in [foreign, readonly, short] {
+        assert_rejected(check(&session, &mut accounts));
+    }
+
+    let key = Pubkey::new_unique();
+    let mut repeated = [
+        (key, defi_agent::ID, true, 1, session_scoped(&session)),
+        (key, defi_agent::ID, true, 1, session_scoped(&session)),
+    ];
+    assert_rejected(check(&session, &mut repeated));
+}
+
+#[test]
+fn only_the_owner_or_a_device_may_tear_down() {
+    let sim = Sim::delegated();
+    assert!(sim.session.is_owner_or_device(&sim.session.owner));
+    assert!(sim.session.is_owner_or_device(&sim.keys[0]));
+    assert!(!sim.session.is_owner_or_device(&Pubkey::new_unique()));
+    assert!(!sim.session.is_owner_or_device(&Pubkey::default()));
+}
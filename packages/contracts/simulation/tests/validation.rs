@@ -0,0 +1,189 @@
+//! `validate_session` as every session-key handler calls it: for the same
+//! session state, each handler shape fails with the same error, in the same
+//! order, whatever its action kind or time source.
+
+use anchor_lang::prelude::*;
+use anchor_lang::error::ERROR_CODE_OFFSET;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::{
+    ActionKind, AgentSession, TemporalSource, ACTION_DLMM_ADD_LIQUIDITY, ACTION_DLMM_OPEN_POSITION,
+    ACTION_DLMM_REMOVE_LIQUIDITY, ACTION_DLMM_SWAP, ACTION_LP_REBALANCE, ACTION_YIELD_SWITCH,
+    STRATEGY_LP, STRATEGY_YIELD,
+};
+use defi_agent_simulation::{InitParams, Op, Sim, GENESIS};
+
+const DURATION: i64 = 86_400;
+
+/// (handler, action kind, time source) for each session-key instruction
+const HANDLERS: &[(&str, ActionKind, fn(i64) -> TemporalSource)] = &[
+    ("execute_action", ActionKind::Action(ACTION_LP_REBALANCE), TemporalSource::Device),
+    ("execute_dlmm_swap", ActionKind::Action(ACTION_DLMM_SWAP), TemporalSource::Device),
+    (
+        "execute_dlmm_add_liquidity",
+        ActionKind::Action(ACTION_DLMM_ADD_LIQUIDITY),
+        TemporalSource::Device,
+    ),
+    (
+        "execute_dlmm_close_position",
+        ActionKind::Action(ACTION_DLMM_REMOVE_LIQUIDITY),
+        TemporalSource::Device,
+    ),
+    (
+        "execute_dlmm_create_position",
+        ActionKind::Action(ACTION_DLMM_OPEN_POSITION),
+        TemporalSource::Device,
+    ),
+    ("update_lp_status", ActionKind::LpMonitor, TemporalSource::Device),
+    ("device_heartbeat", ActionKind::Housekeeping, TemporalSource::Device),
+    ("file_action_request", ActionKind::Housekeeping, TemporalSource::Passive),
+    ("keeper_fill_intent", ActionKind::Action(ACTION_DLMM_SWAP), TemporalSource::Passive),
+];
+
+fn new_sim(strategy_mask: u8) -> Sim {
+    Sim::new(InitParams {
+        duration_secs: DURATION,
+        max_lamports: 1_000_000_000,
+        max_action_lamports: 1_000_000_000,
+        strategy_mask,
+    })
+    .expect("init")
+}
+
+fn error_code(result: &Result<usize>) -> Option<u32> {
+    match result {
+        Ok(_) => None,
+        Err(Error::AnchorError(e)) => Some(e.error_code_number),
+        Err(e) => panic!("unexpected error: {e:?}"),
+    }
+}
+
+fn code(error: AgentError) -> Option<u32> {
+    Some(ERROR_CODE_OFFSET + error as u32)
+}
+
+/// Run every handler against a copy of `sim`'s session, signed by key `device`
+fn outcomes(sim: &Sim, device: usize) -> Vec<(&'static str, Option<u32>)> {
+    HANDLERS
+        .iter()
+        .map(|&(name, kind, time)| {
+            let mut session: AgentSession = sim.session.clone();
+            let result = session.validate_session(&sim.keys[device], kind, time(sim.now));
+            (name, error_code(&result))
+        })
+        .collect()
+}
+
+fn assert_all(sim: &Sim, device: usize, expected: Option<u32>) {
+    for (name, got) in outcomes(sim, device) {
+        assert_eq!(got, expected, "{name}");
+    }
+}
+
+#[test]
+fn healthy_session_passes_every_handler() {
+    assert_all(&new_sim(STRATEGY_LP), 0, None);
+}
+
+#[test]
+fn inactive_session_fails_every_handler_alike() {
+    let mut sim = new_sim(STRATEGY_LP);
+    sim.step(Op::Close).unwrap();
+    assert_all(&sim, 0, code(AgentError::SessionInactive));
+}
+
+#[test]
+fn expired_session_fails_every_handler_alike() {
+    let mut sim = new_sim(STRATEGY_LP);
+    sim.step(Op::Advance { secs: DURATION }).unwrap();
+    assert_all(&sim, 0, code(AgentError::SessionExpired));
+}
+
+#[test]
+fn unknown_disabled_and_expired_devices_fail_every_handler_alike() {
+    let mut sim = new_sim(STRATEGY_LP);
+    assert_all(&sim, 1, code(AgentError::UnauthorizedSessionKey));
+
+    sim.step(Op::Enroll { device: 1 }).unwrap();
+    sim.step(Op::Disable { device: 1 }).unwrap();
+    assert_all(&sim, 1, code(AgentError::DeviceDisabled));
+
+    sim.step(Op::Extend { device: 0, expires_in: 60, max_lamports: 0 }).unwrap();
+    sim.step(Op::Advance { secs: 60 }).unwrap();
+    assert_all(&sim, 0, code(AgentError::DeviceExpired));
+}
+
+#[test]
+fn checks_run_in_the_same_order_everywhere() {
+    // Inactive beats expired beats device beats strategy
+    let mut sim = new_sim(STRATEGY_YIELD);
+    sim.step(Op::Advance { secs: DURATION }).unwrap();
+    sim.step(Op::Close).unwrap();
+    assert_all(&sim, 1, code(AgentError::SessionInactive));
+
+    let mut sim = new_sim(STRATEGY_YIELD);
+    sim.step(Op::Advance { secs: DURATION }).unwrap();
+    assert_all(&sim, 1, code(AgentError::SessionExpired));
+
+    let sim = new_sim(STRATEGY_YIELD);
+    assert_all(&sim, 1, code(AgentError::UnauthorizedSessionKey));
+}
+
+#[test]
+fn lp_handlers_need_the_lp_strategy() {
+    let sim = new_sim(STRATEGY_YIELD);
+    for (name, got) in outcomes(&sim, 0) {
+        let expected = match name {
+            "device_heartbeat" | "file_action_request" => None,
+            _ => code(AgentError::StrategyNotEnabled),
+        };
+        assert_eq!(got, expected, "{name}");
+    }
+
+    let mut session = sim.session.clone();
+    let slot = session.validate_session(
+        &sim.keys[0],
+        ActionKind::Action(ACTION_YIELD_SWITCH),
+        TemporalSource::Device(sim.now),
+    );
+    assert_eq!(slot.unwrap(), 0);
+}
+
+#[test]
+fn only_device_time_records_a_heartbeat() {
+    let mut sim = new_sim(STRATEGY_LP);
+    sim.step(Op::Advance { secs: 600 }).unwrap();
+    let now = GENESIS + 600;
+
+    let mut passive = sim.session.clone();
+    passive
+        .validate_session(&sim.keys[0], ActionKind::Housekeeping, TemporalSource::Passive(now))
+        .unwrap();
+    assert_eq!(
+        borsh::to_vec(&passive).unwrap(),
+        borsh::to_vec(&sim.session).unwrap(),
+        "a passive validation must not touch the session"
+    );
+
+    let mut device = sim.session.clone();
+    device
+        .validate_session(&sim.keys[0], ActionKind::Housekeeping, TemporalSource::Device(now))
+        .unwrap();
+    assert_eq!(device.devices[0].last_seen_at, now);
+    assert_eq!(device.clock_high_water, now);
+}
+
+#[test]
+fn suspension_fails_every_handler_alike() {
+    let mut sim = new_sim(STRATEGY_LP);
+    sim.session.violation_threshold = 2;
+    assert!(!sim.session.record_violation());
+    assert_all(&sim, 0, None);
+    assert!(sim.session.record_violation());
+    assert_all(&sim, 0, code(AgentError::SessionSuspended));
+
+    // An executed action ends the run, but only the owner lifts a suspension
+    sim.session.bump_actions().unwrap();
+    assert_eq!(sim.session.consecutive_violations, 0);
+    assert_all(&sim, 0, code(AgentError::SessionSuspended));
+}
@@ -0,0 +1,295 @@
+//! Session PnL bookkeeping: `record_valuation`, the active-bin price, the
+//! lifetime fee counters, the marked-to-market exposure cap and the exposure
+//! share alerts read, realized swap slippage, estimated pool swap fees (priced
+//! in Q64.64 against the swap fee budget) and the settlement check on close,
+//! and which pools may price a caller-chosen valuation.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::{
+    BudgetCaps, LpPositionMonitor, PoolRegistry, RegisteredPool, ACTION_DLMM_SWAP,
+};
+use defi_agent::valuation::{
+    active_bin_price, base_fee_rate, bin_price_q64, check_valuation_pool, quote_out,
+    record_pool_fee, settlement_gap_bps, slippage_bps, swap_fee, value_in_y_q64,
+    variable_fee_rate, ONE_Q64, SETTLEMENT_TOLERANCE_BPS,
+};
+use defi_agent_simulation::{InitParams, Op, Pool, Sim, GENESIS};
+
+/// `Sim::lp` with a 1_000 lamport exposure cap
+fn capped_sim() -> Sim {
+    Sim::new(InitParams { max_lamports: 1_000, max_action_lamports: 1_000, ..InitParams::LP })
+        .expect("init")
+}
+
+fn revalue(sim: &mut Sim, value: u64, cost_basis: u64) -> u64 {
+    sim.step(Op::Revalue { device: 0, value, cost_basis }).unwrap();
+    sim.session.current_exposure_value
+}
+
+#[test]
+fn first_valuation_is_the_entry() {
+    let mut sim = Sim::lp();
+    let mint = Pubkey::new_unique();
+
+    assert_eq!(sim.session.record_valuation(mint, 5_000, GENESIS), Some(0));
+    assert_eq!(sim.session.valuation_mint, mint);
+    assert_eq!(sim.session.entry_value, 5_000);
+    assert_eq!(sim.session.last_value, 5_000);
+    assert_eq!(sim.session.last_valued_at, GENESIS);
+}
+
+#[test]
+fn realized_pnl_accumulates_across_valuations() {
+    let mut sim = Sim::lp();
+    let mint = Pubkey::new_unique();
+    sim.session.record_valuation(mint, 5_000, GENESIS);
+
+    assert_eq!(sim.session.record_valuation(mint, 5_400, GENESIS + 10), Some(400));
+    assert_eq!(sim.session.record_valuation(mint, 5_100, GENESIS + 20), Some(100));
+    assert_eq!(
+        sim.session.realized_pnl,
+        sim.session.last_value as i64 - sim.session.entry_value as i64
+    );
+}
+
+#[test]
+fn valuations_in_another_mint_are_skipped() {
+    let mut sim = Sim::lp();
+    let mint = Pubkey::new_unique();
+    sim.session.record_valuation(mint, 5_000, GENESIS);
+    let before = sim.session.clone();
+
+    assert_eq!(sim.session.record_valuation(Pubkey::new_unique(), 9_000, GENESIS + 10), None);
+    assert_eq!(sim.session.last_value, before.last_value);
+    assert_eq!(sim.session.realized_pnl, before.realized_pnl);
+    assert_eq!(sim.session.last_valued_at, before.last_valued_at);
+}
+
+#[test]
+fn extreme_values_saturate() {
+    let mut sim = Sim::lp();
+    let mint = Pubkey::new_unique();
+    sim.session.record_valuation(mint, 0, GENESIS);
+
+    assert_eq!(sim.session.record_valuation(mint, u64::MAX, GENESIS), Some(i64::MAX));
+    assert_eq!(sim.session.record_valuation(mint, u64::MAX, GENESIS), Some(i64::MAX));
+}
+
+#[test]
+fn active_bin_price_compounds_the_bin_step() {
+    assert_eq!(active_bin_price(25, 0), 1.0);
+    assert!((active_bin_price(100, 1) - 1.01).abs() < 1e-12);
+    assert!((active_bin_price(100, -1) - 1.0 / 1.01).abs() < 1e-12);
+    assert!((active_bin_price(10, 100) - 1.001f64.powi(100)).abs() < 1e-9);
+}
+
+fn monitor(fee_x_snapshot: u64, fee_y_snapshot: u64) -> LpPositionMonitor {
+    LpPositionMonitor {
+        session: Pubkey::default(),
+        lb_pair: Pubkey::default(),
+        position: Pubkey::default(),
+        min_bin_id: 0,
+        max_bin_id: 0,
+        last_active_bin: 0,
+        is_in_range: 1,
+        bump: 0,
+        _padding: [0; 2],
+        fee_x_snapshot,
+        fee_y_snapshot,
+        last_checked_at: 0,
+        amount_x_snapshot: 0,
+        amount_y_snapshot: 0,
+        out_of_range_since: 0,
+    }
+}
+
+#[test]
+fn closed_monitors_roll_up_into_lifetime_fees() {
+    let mut sim = Sim::lp();
+    sim.session.record_fees_earned(&monitor(120, 7));
+    sim.session.record_fees_earned(&monitor(30, 0));
+    assert_eq!(sim.session.total_fees_earned_x, 150);
+    assert_eq!(sim.session.total_fees_earned_y, 7);
+
+    sim.session.record_fees_earned(&monitor(u64::MAX, u64::MAX));
+    assert_eq!(sim.session.total_fees_earned_x, u64::MAX);
+    assert_eq!(sim.session.total_fees_earned_y, u64::MAX);
+}
+
+#[test]
+fn mark_to_market_caps_against_the_revalued_exposure() {
+    let mut sim = capped_sim();
+    sim.session.apply_spend(0, ACTION_DLMM_SWAP, 900).unwrap();
+    assert_eq!(sim.session.current_exposure_value, 900);
+    assert!(sim.session.check_exposure(0, 200).is_err());
+
+    // A revaluation marks the positions down; only the opt-in uses it
+    assert_eq!(revalue(&mut sim, 500, 900), 500);
+    assert!(sim.session.check_exposure(0, 200).is_err());
+    sim.step(Op::SetMarkToMarket { enabled: true }).unwrap();
+    sim.session.check_exposure(0, 200).unwrap();
+
+    sim.session.apply_spend(0, ACTION_DLMM_SWAP, 200).unwrap();
+    assert_eq!(sim.session.current_exposure_value, 700);
+    assert_eq!(sim.session.spent_lamports, 1_100);
+    assert!(sim.session.check_exposure(0, 301).is_err());
+}
+
+#[test]
+fn revaluation_moves_exposure_by_the_registry_gain_only() {
+    let mut sim = capped_sim();
+    sim.step(Op::SetMarkToMarket { enabled: true }).unwrap();
+
+    // Swaps and execute_action spends never enter the registry: an empty
+    // registry revalues to nothing and frees no room
+    sim.session.apply_spend(0, ACTION_DLMM_SWAP, 900).unwrap();
+    assert_eq!(revalue(&mut sim, 0, 0), 900);
+    assert!(sim.session.check_exposure(0, 200).is_err());
+
+    // A 400-cost position marked at 300: exposure drops by the 100 loss, once
+    sim.step(Op::Advance { secs: 1 }).unwrap();
+    assert_eq!(revalue(&mut sim, 300, 400), 800);
+    sim.step(Op::Advance { secs: 1 }).unwrap();
+    assert_eq!(revalue(&mut sim, 300, 400), 800);
+    assert_eq!(sim.session.exposure_valued_at, GENESIS + 2);
+
+    // Recovering to 450 adds back the loss and the 50 gain
+    assert_eq!(revalue(&mut sim, 450, 400), 950);
+    assert_eq!(sim.session.spent_lamports, 900);
+}
+
+#[test]
+fn slippage_is_measured_against_the_active_bin_quote() {
+    let price = active_bin_price(100, 70); // ~2.0068 Y per X
+    let expected = quote_out(price, true, 1_000);
+    assert_eq!(expected, 2_006);
+    assert_eq!(quote_out(price, false, expected), 999);
+
+    assert_eq!(slippage_bps(expected, expected), 0);
+    assert_eq!(slippage_bps(expected, expected + 50), 0);
+    assert_eq!(slippage_bps(2_000, 1_950), 250);
+    assert_eq!(slippage_bps(0, 10), 0);
+
+    let mut sim = Sim::lp();
+    sim.session.record_slippage(56);
+    sim.session.record_slippage(u64::MAX);
+    assert_eq!(sim.session.slippage_paid, u64::MAX);
+}
+
+#[test]
+fn pool_fees_follow_the_dlmm_rate_and_budget() {
+    // base_factor 10_000 at bin step 25 is the 0.25% tier
+    assert_eq!(base_fee_rate(10_000, 25, 0), 2_500_000);
+    assert_eq!(base_fee_rate(10_000, 25, 1), 25_000_000);
+    assert_eq!(variable_fee_rate(0, 25, 40_000), 0);
+    assert_eq!(variable_fee_rate(10_000, 25, 40_000), 25_000);
+    assert_eq!(variable_fee_rate(1, 1, 1), 1); // rounds up
+
+    assert_eq!(swap_fee(1_000_000, 2_500_000), 2_500);
+    assert_eq!(swap_fee(1, 2_500_000), 1);
+    assert_eq!(swap_fee(0, 2_500_000), 0);
+
+    let mut sim = Sim::lp();
+    sim.session.record_swap_fee(u64::MAX).expect("uncapped");
+    assert!(sim.session.record_swap_fee(1).is_err()); // overflow
+
+    let mut sim = Sim::lp();
+    sim.session.swap_fee_budget = 100;
+    sim.session.record_swap_fee(60).expect("within budget");
+    assert!(sim.session.record_swap_fee(41).is_err());
+    sim.session.record_swap_fee(40).expect("up to the budget");
+    assert_eq!(sim.session.swap_fees_paid, 100);
+}
+
+#[test]
+fn budgeted_pool_fees_are_priced_in_fixed_point() {
+    let mut sim = Sim::lp();
+    sim.session.swap_fee_budget = 202;
+    let price = bin_price_q64(100, 1); // 1.01 Y per X
+    assert_eq!(record_pool_fee(&mut sim.session, price, true, 100).unwrap(), 101);
+    assert_eq!(record_pool_fee(&mut sim.session, price, false, 100).unwrap(), 100);
+    // Rounded up, so the budget is never undercharged
+    assert_eq!(record_pool_fee(&mut sim.session, ONE_Q64 / 2, true, 1).unwrap(), 1);
+    assert!(record_pool_fee(&mut sim.session, price, true, 1).is_err());
+    assert_eq!(sim.session.swap_fees_paid, 202);
+}
+
+#[test]
+fn revalued_positions_are_marked_in_fixed_point() {
+    let price = bin_price_q64(100, 1); // 1.01 Y per X
+    assert_eq!(value_in_y_q64(price, 100, 50), 151);
+    assert_eq!(value_in_y_q64(ONE_Q64 / 3, 1, 0), 1, "rounded up");
+    assert_eq!(value_in_y_q64(u128::MAX, 2, 1), u64::MAX);
+}
+
+#[test]
+fn settlement_gap_is_symmetric_and_bounded() {
+    assert_eq!(settlement_gap_bps(0, 0), 0);
+    assert_eq!(settlement_gap_bps(10_000, 10_000), 0);
+    assert_eq!(settlement_gap_bps(10_000, 9_900), 100);
+    assert_eq!(settlement_gap_bps(9_900, 10_000), 100);
+    assert_eq!(settlement_gap_bps(0, 5), 10_000);
+    assert_eq!(settlement_gap_bps(u64::MAX, 0), 10_000);
+
+    // A checkpoint a few fees stale stays within tolerance; a lost bin does not
+    assert!(settlement_gap_bps(1_000_000, 1_004_000) <= SETTLEMENT_TOLERANCE_BPS);
+    assert!(settlement_gap_bps(1_000_000, 700_000) > SETTLEMENT_TOLERANCE_BPS);
+}
+
+#[test]
+fn exposure_share_follows_the_capped_measure() {
+    let mut sim = capped_sim();
+    assert_eq!(sim.session.exposure_bps(), 0);
+
+    sim.session.apply_spend(0, ACTION_DLMM_SWAP, 800).unwrap();
+    assert_eq!(sim.session.exposure_bps(), 8_000);
+
+    assert_eq!(revalue(&mut sim, 100, 400), 500);
+    assert_eq!(sim.session.exposure_bps(), 8_000);
+    sim.step(Op::SetMarkToMarket { enabled: true }).unwrap();
+    assert_eq!(sim.session.exposure(), 500);
+    assert_eq!(sim.session.exposure_bps(), 5_000);
+
+    sim.session.max_lamports = 0;
+    assert_eq!(sim.session.exposure_bps(), 0);
+}
+
+fn registry(pools: &[Pubkey]) -> PoolRegistry {
+    PoolRegistry {
+        pools: pools.iter().map(|&lb_pair| RegisteredPool { lb_pair, risk_tier: 0 }).collect(),
+        bump: 255,
+    }
+}
+
+#[test]
+fn arbitrary_valuation_pools_are_rejected() {
+    let sim = Sim::lp();
+    let mut pool = Pool::new(Pubkey::new_unique(), Pubkey::new_unique(), 10, 5_000);
+    let other = registry(&[Pubkey::new_unique()]);
+    for registry in [None, Some(&other)] {
+        match check_valuation_pool(&sim.session, registry, &pool.info()) {
+            Err(Error::AnchorError(e)) => assert_eq!(
+                e.error_code_number,
+                ERROR_CODE_OFFSET + AgentError::ValuationPoolNotTrusted as u32
+            ),
+            other => panic!("expected ValuationPoolNotTrusted, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn registered_or_pinned_pools_may_value() {
+    let (sol, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut sim = Sim::lp();
+    let mut pool = Pool::new(sol, usdc, 10, 100);
+    let listed = registry(&[pool.key]);
+    check_valuation_pool(&sim.session, Some(&listed), &pool.info()).unwrap();
+
+    let caps = BudgetCaps { max_lamports: 1_000, max_action_lamports: 1_000, ..Default::default() };
+    sim.step(Op::SetBudgetMint { mint: usdc, caps }).unwrap();
+    sim.step(Op::PinBudgetPricePool { mint: sol, lb_pair: pool.key }).unwrap();
+    check_valuation_pool(&sim.session, None, &pool.info()).unwrap();
+}
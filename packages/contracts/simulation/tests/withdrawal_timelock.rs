@@ -0,0 +1,89 @@
+//! `enforce_withdrawal_timelock`: the large-withdrawal threshold caps the
+//! running total of unqueued withdrawals per delay window, so a drain split
+//! into sub-threshold calls still has to be queued.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+
+use defi_agent::errors::AgentError;
+use defi_agent::state::{enforce_withdrawal_timelock, PendingWithdrawal, WithdrawalWindow};
+
+const THRESHOLD: u64 = 1_000;
+const DELAY: i64 = 86_400;
+
+fn withdraw(
+    window: &mut WithdrawalWindow,
+    amount: u64,
+    pending: Option<&PendingWithdrawal>,
+    now: i64,
+) -> Result<()> {
+    enforce_withdrawal_timelock(THRESHOLD, DELAY, window, amount, &destination(), pending, now)
+}
+
+fn destination() -> Pubkey {
+    Pubkey::new_from_array([7; 32])
+}
+
+fn pending(amount: u64, available_at: i64) -> PendingWithdrawal {
+    PendingWithdrawal {
+        asset: Pubkey::default(),
+        amount,
+        destination: destination(),
+        queued_at: available_at - DELAY,
+        available_at,
+        bump: 255,
+    }
+}
+
+fn assert_not_queued(result: Result<()>) {
+    match result {
+        Err(Error::AnchorError(e)) => assert_eq!(
+            e.error_code_number,
+            ERROR_CODE_OFFSET + AgentError::WithdrawalNotQueued as u32
+        ),
+        other => panic!("expected WithdrawalNotQueued, got {other:?}"),
+    }
+}
+
+#[test]
+fn split_withdrawals_hit_the_timelock() {
+    let mut window = WithdrawalWindow::default();
+    for i in 0..4 {
+        withdraw(&mut window, 250, None, 100 + i).unwrap();
+    }
+    assert_eq!(window.withdrawn, THRESHOLD);
+    assert_not_queued(withdraw(&mut window, 1, None, 200));
+    assert_eq!(window.withdrawn, THRESHOLD, "a rejected withdrawal is not counted");
+}
+
+#[test]
+fn single_withdrawal_above_threshold_needs_a_queue() {
+    let mut window = WithdrawalWindow::default();
+    assert_not_queued(withdraw(&mut window, THRESHOLD + 1, None, 100));
+}
+
+#[test]
+fn window_reopens_after_the_delay() {
+    let mut window = WithdrawalWindow::default();
+    withdraw(&mut window, THRESHOLD, None, 100).unwrap();
+    assert_not_queued(withdraw(&mut window, 1, None, 100 + DELAY - 1));
+    withdraw(&mut window, THRESHOLD, None, 100 + DELAY).unwrap();
+    assert_eq!(window.started_at, 100 + DELAY);
+}
+
+#[test]
+fn queued_release_bypasses_and_skips_the_window() {
+    let mut window = WithdrawalWindow::default();
+    withdraw(&mut window, THRESHOLD, None, 100).unwrap();
+    let entry = pending(5 * THRESHOLD, 150);
+    withdraw(&mut window, 5 * THRESHOLD, Some(&entry), 150).unwrap();
+    assert_eq!(window.withdrawn, THRESHOLD);
+}
+
+#[test]
+fn zero_threshold_disables_the_lock() {
+    let mut window = WithdrawalWindow::default();
+    enforce_withdrawal_timelock(0, DELAY, &mut window, u64::MAX, &destination(), None, 1)
+        .unwrap();
+    assert_eq!(window, WithdrawalWindow::default());
+}
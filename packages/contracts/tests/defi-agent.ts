@@ -8,12 +8,18 @@ import {
   LAMPORTS_PER_SOL,
   Transaction,
 } from "@solana/web3.js";
-import { DELEGATION_PROGRAM_ID } from "@magicblock-labs/ephemeral-rollups-sdk";
+import {
+  DELEGATION_PROGRAM_ID,
+  MAGIC_CONTEXT_ID,
+  MAGIC_PROGRAM_ID,
+} from "@magicblock-labs/ephemeral-rollups-sdk";
 import { assert } from "chai";
 import { DefiAgent } from "../target/types/defi_agent";
 import {
   BASE_RPC, ER_RPC, ER_WS,
   STRATEGY_LP, STRATEGY_YIELD,
+  REASON_MANUAL,
+  ensureConfig,
   sleep,
 } from "./helpers";
 
@@ -80,6 +86,7 @@ describe("defi-agent", () => {
     owner = ownerKeypair.publicKey;
     sessionKeypair = Keypair.generate();
     sessionKey = sessionKeypair.publicKey;
+    await ensureConfig(baseProgram);
 
     [sessionPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("session"), owner.toBuffer()],
@@ -116,6 +123,10 @@ describe("defi-agent", () => {
         new anchor.BN(SESSION_DURATION_SECS),
         new anchor.BN(MAX_LAMPORTS),
         STRATEGY_MASK,
+        new anchor.BN(0), // max_action_lamports=0: adopt the protocol per-action ceiling
+        null, // referrer: none
+        0, // fee_tier: tier 0
+        Array(32).fill(0), // attestation_hash: unattested
       )
       .accounts({ owner })
       .instruction();
@@ -133,7 +144,7 @@ describe("defi-agent", () => {
 
     const session = await baseProgram.account.agentSession.fetch(sessionPda);
     assert.ok(session.isActive, "session should be active");
-    assert.ok(session.sessionKey.equals(sessionKey), "session key mismatch");
+    assert.ok(session.devices[0].key.equals(sessionKey), "session key mismatch");
     assert.equal(session.strategyMask, STRATEGY_MASK);
     assert.equal(session.maxLamports.toNumber(), MAX_LAMPORTS);
     assert.equal(session.spentLamports.toNumber(), 0);
@@ -160,8 +171,8 @@ describe("defi-agent", () => {
     const actionAmount = 100_000;
 
     const tx = await erProgram.methods
-      .executeAction(ACTION_LP_REBALANCE, new anchor.BN(actionAmount))
-      .accounts({ sessionKey, session: sessionPda })
+      .executeAction(ACTION_LP_REBALANCE, new anchor.BN(actionAmount), new anchor.BN(0), REASON_MANUAL)
+      .accounts({ sessionKey, session: sessionPda, cosigner: null })
       .transaction();
 
     const sig = await sendErTx(tx, [sessionKeypair]);
@@ -176,8 +187,8 @@ describe("defi-agent", () => {
     const actionAmount = 50_000;
 
     const tx = await erProgram.methods
-      .executeAction(ACTION_YIELD_SWITCH, new anchor.BN(actionAmount))
-      .accounts({ sessionKey, session: sessionPda })
+      .executeAction(ACTION_YIELD_SWITCH, new anchor.BN(actionAmount), new anchor.BN(0), REASON_MANUAL)
+      .accounts({ sessionKey, session: sessionPda, cosigner: null })
       .transaction();
 
     const sig = await sendErTx(tx, [sessionKeypair]);
@@ -191,8 +202,8 @@ describe("defi-agent", () => {
     const rogue = Keypair.generate();
 
     let tx = await erProgram.methods
-      .executeAction(ACTION_LP_REBALANCE, new anchor.BN(1000))
-      .accounts({ sessionKey: rogue.publicKey, session: sessionPda })
+      .executeAction(ACTION_LP_REBALANCE, new anchor.BN(1000), new anchor.BN(0), REASON_MANUAL)
+      .accounts({ sessionKey: rogue.publicKey, session: sessionPda, cosigner: null })
       .transaction();
 
     tx.feePayer = erProvider.wallet.publicKey;
@@ -221,8 +232,8 @@ describe("defi-agent", () => {
 
   it("6. Reject disabled strategy (liquidation not enabled)", async () => {
     let tx = await erProgram.methods
-      .executeAction(ACTION_LIQUIDATION_PROTECT, new anchor.BN(1000))
-      .accounts({ sessionKey, session: sessionPda })
+      .executeAction(ACTION_LIQUIDATION_PROTECT, new anchor.BN(1000), new anchor.BN(0), REASON_MANUAL)
+      .accounts({ sessionKey, session: sessionPda, cosigner: null })
       .transaction();
 
     tx.feePayer = erProvider.wallet.publicKey;
@@ -251,7 +262,12 @@ describe("defi-agent", () => {
   it("7. Commit state to base layer (without undelegating)", async () => {
     const tx = await erProgram.methods
       .commitSession()
-      .accounts({ payer: wallet.publicKey, session: sessionPda })
+      .accounts({
+        payer: wallet.publicKey,
+        session: sessionPda,
+        magicProgram: MAGIC_PROGRAM_ID,
+        magicContext: MAGIC_CONTEXT_ID,
+      })
       .transaction();
 
     const sig = await sendErTx(tx);
@@ -276,7 +292,12 @@ describe("defi-agent", () => {
   it("8. Undelegate session back to base layer", async () => {
     const tx = await erProgram.methods
       .undelegateSession()
-      .accounts({ payer: wallet.publicKey, session: sessionPda })
+      .accounts({
+        payer: wallet.publicKey,
+        session: sessionPda,
+        magicProgram: MAGIC_PROGRAM_ID,
+        magicContext: MAGIC_CONTEXT_ID,
+      })
       .transaction();
 
     const sig = await sendErTx(tx);
@@ -5,11 +5,25 @@
  * so the two sources stay in sync.
  */
 
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+
 // ── Strategy bitmask constants ─────────────────────────────────────────────────
 export const STRATEGY_LP           = 1 << 0; // Concentrated LP rebalancing
 export const STRATEGY_YIELD        = 1 << 1; // Lending yield switching
 export const STRATEGY_LIQUIDATION  = 1 << 2; // Leveraged position protection
 
+// Action reason codes (REASON_* on-chain) passed to rebalance / close instructions
+export const REASON_MANUAL = 4;
+
+// ── Global Config defaults (used when the suite has to create the Config) ──────
+export const DEFAULT_MAX_LAMPORTS     = 1_000_000_000;
+export const DEFAULT_DURATION_SECS    = 60 * 60 * 24;
+
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111",
+);
+
 // ── RPC endpoints ─────────────────────────────────────────────────────────────
 export const BASE_RPC =
   process.env.SOLANA_DEVNET_RPC_URL ?? "https://api.devnet.solana.com";
@@ -20,3 +34,29 @@ export const ER_WS =
 
 // ── Utilities ─────────────────────────────────────────────────────────────────
 export const sleep = (ms: number) => new Promise<void>((r) => setTimeout(r, ms));
+
+/**
+ * Create the global Config PDA if this deployment doesn't have one yet.
+ * Must run as the program upgrade authority (the provider wallet on devnet).
+ */
+export async function ensureConfig(program: anchor.Program<any>): Promise<PublicKey> {
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId,
+  );
+  if (await program.provider.connection.getAccountInfo(configPda)) return configPda;
+
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID,
+  );
+  await program.methods
+    .initializeConfig(
+      0,
+      new anchor.BN(DEFAULT_MAX_LAMPORTS),
+      new anchor.BN(DEFAULT_DURATION_SECS),
+    )
+    .accounts({ admin: program.provider.publicKey, programData })
+    .rpc({ commitment: "confirmed" });
+  return configPda;
+}
@@ -7,11 +7,12 @@
  *   3. Register the position for monitoring via `register_lp_monitor`
  *   4. Read off-chain position status via `checkLpPosition`
  *   5. Submit the status on-chain via `update_lp_status` — assert PDA updated
- *   6. Simulate an out-of-range condition by passing an active_bin outside range
+ *   6. Check the program takes the active bin from the pool, not the caller
  *
  * Position ownership note: monitoring does not require the session key to own the
  * DLMM position. The `register_lp_monitor` instruction only stores the pubkey;
- * `update_lp_status` takes pre-read values as args. The position is created with
+ * `update_lp_status` reads the pool's active bin and takes pre-read fees and
+ * amounts as args. The position is created with
  * the wallet as user to avoid the DLMM SDK v1.9.3 simulation issue that occurs
  * when creating a session-key-owned position in an empty pool.
  *
@@ -42,7 +43,7 @@ import DLMM, {
 import BN from "bn.js";
 import { assert } from "chai";
 import { DefiAgent } from "../target/types/defi_agent";
-import { BASE_RPC, STRATEGY_LP, sleep } from "./helpers";
+import { BASE_RPC, STRATEGY_LP, ensureConfig, sleep } from "./helpers";
 import { checkLpPosition } from "@hyperbiscus/shared";
 
 const DLMM_PROGRAM_ID = new PublicKey(
@@ -124,6 +125,7 @@ describe("lp-monitor", () => {
     owner = ownerKeypair.publicKey;
     sessionKeypair = Keypair.generate();
     sessionKey = sessionKeypair.publicKey;
+    await ensureConfig(baseProgram);
 
     [sessionPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("session"), owner.toBuffer()],
@@ -248,6 +250,10 @@ describe("lp-monitor", () => {
         new anchor.BN(SESSION_DURATION_SECS),
         new anchor.BN(MAX_LAMPORTS),
         STRATEGY_LP,
+        new anchor.BN(0), // max_action_lamports=0: adopt the protocol per-action ceiling
+        null, // referrer: none
+        0, // fee_tier: tier 0
+        Array(32).fill(0), // attestation_hash: unattested
       )
       .accounts({ owner })
       .instruction();
@@ -285,7 +291,7 @@ describe("lp-monitor", () => {
     );
     assert.equal(monitor.minBinId, setupActiveBinId - BIN_RANGE, "minBinId mismatch");
     assert.equal(monitor.maxBinId, setupActiveBinId + BIN_RANGE, "maxBinId mismatch");
-    assert.equal(monitor.isInRange, true, "initial isInRange should be true");
+    assert.equal(monitor.isInRange, 1, "initial isInRange should be 1");
     console.log(`  Monitor: range=[${monitor.minBinId}, ${monitor.maxBinId}], in_range=${monitor.isInRange}`);
   });
 
@@ -320,15 +326,21 @@ describe("lp-monitor", () => {
     );
 
     const updateTx = await baseProgram.methods
-      .updateLpStatus(status.activeBin, status.feeX, status.feeY)
-      .accounts({ sessionKey, session: sessionPda, monitor: monitorPda })
+      .updateLpStatus(status.feeX, status.feeY, status.amountX, status.amountY)
+      .accounts({
+        sessionKey,
+        session: sessionPda,
+        monitor: monitorPda,
+        position: monitoredPositionKeypair.publicKey,
+        lbPair,
+      })
       .transaction();
 
     await sendAndVerifyTx("updateLpStatus", updateTx, [sessionKeypair]);
 
     const monitor = await baseProgram.account.lpPositionMonitor.fetch(monitorPda);
     assert.equal(monitor.lastActiveBin, status.activeBin, "lastActiveBin mismatch");
-    assert.equal(monitor.isInRange, true, "isInRange should be true");
+    assert.equal(monitor.isInRange, 1, "isInRange should be 1");
     assert.ok(monitor.lastCheckedAt.toNumber() > 0, "lastCheckedAt should be set");
     console.log(
       `  On-chain: lastActiveBin=${monitor.lastActiveBin}, isInRange=${monitor.isInRange}, ` +
@@ -336,23 +348,35 @@ describe("lp-monitor", () => {
     );
   });
 
-  it("4. Detect out-of-range condition when active bin moves outside position", async function () {
+  it("4. Checkpoint records the pool's own active bin", async function () {
     this.timeout(60_000);
 
-    const outOfRangeBin = setupActiveBinId + BIN_RANGE + 100;
+    await dlmmPool.refetchStates();
+    const poolActiveBin = dlmmPool.lbPair.activeId;
 
     const updateTx = await baseProgram.methods
-      .updateLpStatus(outOfRangeBin, new anchor.BN(0), new anchor.BN(0))
-      .accounts({ sessionKey, session: sessionPda, monitor: monitorPda })
+      .updateLpStatus(
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0),
+      )
+      .accounts({
+        sessionKey,
+        session: sessionPda,
+        monitor: monitorPda,
+        position: monitoredPositionKeypair.publicKey,
+        lbPair,
+      })
       .transaction();
 
-    await sendAndVerifyTx("updateLpStatus(out-of-range)", updateTx, [sessionKeypair]);
+    await sendAndVerifyTx("updateLpStatus(zero snapshot)", updateTx, [sessionKeypair]);
 
     const monitor = await baseProgram.account.lpPositionMonitor.fetch(monitorPda);
-    assert.equal(monitor.lastActiveBin, outOfRangeBin, "lastActiveBin should reflect simulated bin");
-    assert.equal(monitor.isInRange, false, "isInRange should be false");
+    assert.equal(monitor.lastActiveBin, poolActiveBin, "lastActiveBin should be read from the pool");
+    assert.equal(monitor.isInRange, 1, "isInRange should be 1");
     console.log(
-      `  Out-of-range detected: activeBin=${monitor.lastActiveBin}, ` +
+      `  Active bin read on-chain: activeBin=${monitor.lastActiveBin}, ` +
       `range=[${monitor.minBinId}, ${monitor.maxBinId}], isInRange=${monitor.isInRange}`,
     );
   });
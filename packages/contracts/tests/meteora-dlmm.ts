@@ -39,7 +39,6 @@ import {
 import DLMM, {
   ActivationType,
   StrategyType,
-  deriveEventAuthority,
   deriveCustomizablePermissionlessLbPair,
   deriveBinArray,
   binIdToBinArrayIndex,
@@ -47,7 +46,7 @@ import DLMM, {
 import BN from "bn.js";
 import { assert } from "chai";
 import { DefiAgent } from "../target/types/defi_agent";
-import { BASE_RPC, STRATEGY_LP, sleep } from "./helpers";
+import { BASE_RPC, REASON_MANUAL, STRATEGY_LP, ensureConfig, sleep } from "./helpers";
 
 // ── Meteora DLMM ───────────────────────────────────────────────────────────────
 const DLMM_PROGRAM_ID = new PublicKey(
@@ -99,7 +98,6 @@ describe("meteora-dlmm", () => {
   let setupActiveBinId: number; // pool's active bin ID at pool-creation time
 
   // Derived once in before() — reused across all tests
-  let eventAuthority: PublicKey;
   let bitmapExt: PublicKey | null;
   let binArrayLower: PublicKey;
   let binArrayUpper: PublicKey;
@@ -127,6 +125,7 @@ describe("meteora-dlmm", () => {
     owner = ownerKeypair.publicKey;
     sessionKeypair = Keypair.generate();
     sessionKey = sessionKeypair.publicKey;
+    await ensureConfig(baseProgram);
 
     [sessionPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("session"), owner.toBuffer()],
@@ -236,7 +235,6 @@ describe("meteora-dlmm", () => {
     setupActiveBinId = activeBin.binId;
 
     // Compute static PDAs once — reused across all tests
-    [eventAuthority] = deriveEventAuthority(DLMM_PROGRAM_ID);
     bitmapExt = dlmmPool.binArrayBitmapExtension?.publicKey ?? null;
     const lowerIdx = binIdToBinArrayIndex(new BN(setupActiveBinId - BIN_RANGE));
     const upperIdx = binIdToBinArrayIndex(new BN(setupActiveBinId + BIN_RANGE));
@@ -300,6 +298,10 @@ describe("meteora-dlmm", () => {
         new anchor.BN(SESSION_DURATION_SECS),
         new anchor.BN(MAX_LAMPORTS),
         STRATEGY_LP,
+        new anchor.BN(0), // max_action_lamports=0: adopt the protocol per-action ceiling
+        null, // referrer: none
+        0, // fee_tier: tier 0
+        Array(32).fill(0), // attestation_hash: unattested
       )
       .accounts({ owner })
       .instruction();
@@ -335,6 +337,9 @@ describe("meteora-dlmm", () => {
       .executeDlmmSwap(
         new anchor.BN(SWAP_AMOUNT_IN),
         new anchor.BN(0), // min_amount_out=0: accept any output (test only)
+        new anchor.BN(0), // fee_lamports=0: no priority fee / tips attached
+        null, // min_active_bin: no price band
+        null, // max_active_bin
       )
       .accounts({
         sessionKey,
@@ -348,10 +353,15 @@ describe("meteora-dlmm", () => {
         tokenXMint: dlmmPool.lbPair.tokenXMint,
         tokenYMint: dlmmPool.lbPair.tokenYMint,
         oracle: dlmmPool.lbPair.oracle,
-        // dlmmProgram is auto-resolved from the address constraint in the IDL
-        eventAuthority,
+        // dlmmProgram + eventAuthority are auto-resolved from address constraints in the IDL
         tokenXProgram: TOKEN_PROGRAM_ID,
         tokenYProgram: TOKEN_PROGRAM_ID,
+        poolRegistry: null, // session is not in registry-only mode
+        cosigner: null, // below the co-sign threshold
+        actionRequest: null, // in-scope action, no owner approval needed
+        treasury: null, // protocol_fee_bps = 0 on the test Config
+        treasuryLedger: null,
+        referrerToken: null,
       })
       .remainingAccounts(binArrayRemaining)
       .transaction();
@@ -404,7 +414,7 @@ describe("meteora-dlmm", () => {
     const overLimit = MAX_LAMPORTS + 1;
 
     const overTx = await baseProgram.methods
-      .executeDlmmSwap(new anchor.BN(overLimit), new anchor.BN(0))
+      .executeDlmmSwap(new anchor.BN(overLimit), new anchor.BN(0), new anchor.BN(0), null, null)
       .accounts({
         sessionKey,
         session: sessionPda,
@@ -417,9 +427,14 @@ describe("meteora-dlmm", () => {
         tokenXMint: dlmmPool.lbPair.tokenXMint,
         tokenYMint: dlmmPool.lbPair.tokenYMint,
         oracle: dlmmPool.lbPair.oracle,
-        eventAuthority,
         tokenXProgram: TOKEN_PROGRAM_ID,
         tokenYProgram: TOKEN_PROGRAM_ID,
+        poolRegistry: null, // session is not in registry-only mode
+        cosigner: null, // below the co-sign threshold
+        actionRequest: null, // in-scope action, no owner approval needed
+        treasury: null, // protocol_fee_bps = 0 on the test Config
+        treasuryLedger: null,
+        referrerToken: null,
       })
       .remainingAccounts(binArrayRemaining)
       .transaction();
@@ -484,7 +499,7 @@ describe("meteora-dlmm", () => {
     };
 
     const addLiqTx = await baseProgram.methods
-      .executeDlmmAddLiquidity(liquidityParam)
+      .executeDlmmAddLiquidity(liquidityParam, new anchor.BN(0))
       .accounts({
         sessionKey,
         session: sessionPda,
@@ -499,10 +514,12 @@ describe("meteora-dlmm", () => {
         tokenYMint: dlmmPool.lbPair.tokenYMint,
         binArrayLower,
         binArrayUpper,
-        // dlmmProgram auto-resolved from address constraint in IDL
-        eventAuthority,
+        // dlmmProgram + eventAuthority auto-resolved from address constraints in IDL
         tokenXProgram: TOKEN_PROGRAM_ID,
         tokenYProgram: TOKEN_PROGRAM_ID,
+        poolRegistry: null, // session is not in registry-only mode
+        cosigner: null, // below the co-sign threshold
+        actionRequest: null, // in-scope action, no owner approval needed
       })
       .transaction();
 
@@ -551,7 +568,7 @@ describe("meteora-dlmm", () => {
     };
 
     const overTx = await baseProgram.methods
-      .executeDlmmAddLiquidity(overLimitParam)
+      .executeDlmmAddLiquidity(overLimitParam, new anchor.BN(0))
       .accounts({
         sessionKey,
         session: sessionPda,
@@ -566,9 +583,11 @@ describe("meteora-dlmm", () => {
         tokenYMint: dlmmPool.lbPair.tokenYMint,
         binArrayLower,
         binArrayUpper,
-        eventAuthority,
         tokenXProgram: TOKEN_PROGRAM_ID,
         tokenYProgram: TOKEN_PROGRAM_ID,
+        poolRegistry: null, // session is not in registry-only mode
+        cosigner: null, // below the co-sign threshold
+        actionRequest: null, // in-scope action, no owner approval needed
       })
       .transaction();
 
@@ -613,7 +632,7 @@ describe("meteora-dlmm", () => {
     const preBalY = (await getAccount(baseConnection, sessionAtaY)).amount;
 
     const closeTx = await baseProgram.methods
-      .executeDlmmClosePosition()
+      .executeDlmmClosePosition(new anchor.BN(0), REASON_MANUAL)
       .accounts({
         sessionKey,
         session: sessionPda,
@@ -629,10 +648,11 @@ describe("meteora-dlmm", () => {
         binArrayLower,
         binArrayUpper,
         rentReceiver: sessionKey, // session key reclaims position rent
-        // dlmmProgram auto-resolved from address constraint in IDL
-        eventAuthority,
+        // dlmmProgram + eventAuthority auto-resolved from address constraints in IDL
         tokenXProgram: TOKEN_PROGRAM_ID,
         tokenYProgram: TOKEN_PROGRAM_ID,
+        positionRegistry: null,
+        monitor: null,
       })
       .transaction();
 
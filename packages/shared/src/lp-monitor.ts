@@ -32,6 +32,10 @@ export interface LpPositionStatus {
   feeX: BN;
   /** Unclaimed fee token Y (raw, pending; 0 if not available) */
   feeY: BN;
+  /** Token X held by the position, fees excluded (raw; 0 if not available) */
+  amountX: BN;
+  /** Token Y held by the position, fees excluded (raw; 0 if not available) */
+  amountY: BN;
 }
 
 /**
@@ -54,10 +58,11 @@ export async function checkLpPosition(
 ): Promise<LpPositionStatus> {
   const dlmmPool = await DLMM.create(connection, lbPair, { cluster });
 
-  // Fetch active bin + raw position account in parallel
-  const [activeBinInfo, positionAccountInfo] = await Promise.all([
+  // Fetch active bin, raw position account and position amounts in parallel
+  const [activeBinInfo, positionAccountInfo, lbPosition] = await Promise.all([
     dlmmPool.getActiveBin(),
     connection.getAccountInfo(positionPubkey),
+    dlmmPool.getPosition(positionPubkey).catch(() => null),
   ]);
 
   if (!positionAccountInfo) {
@@ -99,6 +104,10 @@ export async function checkLpPosition(
   const activeBin = activeBinInfo.binId;
   const isInRange = activeBin >= minBinId && activeBin <= maxBinId;
 
+  // totalXAmount / totalYAmount — the position's share of its bins' reserves
+  const amountX = new BN(lbPosition?.positionData.totalXAmount?.toString().split(".")[0] ?? "0");
+  const amountY = new BN(lbPosition?.positionData.totalYAmount?.toString().split(".")[0] ?? "0");
+
   return {
     activeBin,
     positionMinBin: minBinId,
@@ -106,5 +115,7 @@ export async function checkLpPosition(
     isInRange,
     feeX,
     feeY,
+    amountX,
+    amountY,
   };
 }